@@ -1,6 +1,8 @@
 use crate::cf_types::{CfRule, Cfvo};
+use crate::conditional_formatting::parse_sqref;
 use crate::constants::{LAST_COLUMN, LAST_ROW};
 use crate::cut_paste::cf_sqref_anchor;
+use crate::data_validation::{ranges_to_sqref, rule_formulas_mut};
 use crate::expressions::parser::stringify::{
     to_localized_string, to_string_displaced, DisplaceData,
 };
@@ -9,13 +11,14 @@ use crate::expressions::types::CellReferenceRC;
 use crate::expressions::utils;
 use crate::language::get_default_language;
 use crate::locale::get_default_locale;
+use crate::merged_cells::displace_interval;
 use crate::model::{CellStructure, Model};
 use crate::types::{ArrayKind, Cell, Link, Worksheet};
 
 /// Applies `map` to the (row, column) key of every link in the worksheet, so
 /// that links follow their cells when rows or columns are inserted, deleted or
 /// moved: `Some((row, column))` moves the link there, `None` removes it.
-fn displace_links<F>(worksheet: &mut Worksheet, map: F)
+pub(crate) fn displace_links<F>(worksheet: &mut Worksheet, map: F)
 where
     F: Fn(i32, i32) -> Option<(i32, i32)>,
 {
@@ -327,6 +330,8 @@ impl<'a> Model<'a> {
     ///
     /// * `displace_data` - A reference to `DisplaceData` describing the displacement's direction and magnitude.
     fn displace_cells(&mut self, displace_data: &DisplaceData) -> Result<(), String> {
+        // Cells change position: the dependency graph no longer points to the right cells
+        self.invalidate_dependency_graph();
         let cells = self.get_all_cells();
         for cell in cells {
             self.shift_cell_formula(cell.index, cell.row, cell.column, displace_data)?;
@@ -376,6 +381,98 @@ impl<'a> Model<'a> {
         self.parser.set_language(language);
     }
 
+    /// Updates the ranges and formulas of the data validation rules of `sheet` according to
+    /// `displace_data`. Rules whose cells are all deleted are removed.
+    fn displace_data_validations(
+        &mut self,
+        sheet: u32,
+        displace_data: &DisplaceData,
+    ) -> Result<(), String> {
+        let old_data_validations =
+            std::mem::take(&mut self.workbook.worksheet_mut(sheet)?.data_validations);
+        let mut data_validations = Vec::new();
+        for mut data_validation in old_data_validations {
+            let new_ranges: Vec<(i32, i32, i32, i32)> = parse_sqref(&data_validation.range)
+                .into_iter()
+                .filter_map(|(row1, column1, row2, column2)| match *displace_data {
+                    DisplaceData::Row { row, delta, .. } => {
+                        let (row1, row2) = displace_interval(row1, row2, row, delta)?;
+                        Some((row1, column1, row2, column2))
+                    }
+                    DisplaceData::Column { column, delta, .. } => {
+                        let (column1, column2) =
+                            displace_interval(column1, column2, column, delta)?;
+                        Some((row1, column1, row2, column2))
+                    }
+                    _ => {
+                        let (new_row1, new_row2) = (
+                            displace_cf_row(row1, displace_data, sheet)?,
+                            displace_cf_row(row2, displace_data, sheet)?,
+                        );
+                        let (new_column1, new_column2) = (
+                            displace_cf_col(column1, displace_data, sheet)?,
+                            displace_cf_col(column2, displace_data, sheet)?,
+                        );
+                        Some((
+                            new_row1.min(new_row2),
+                            new_column1.min(new_column2),
+                            new_row1.max(new_row2),
+                            new_column1.max(new_column2),
+                        ))
+                    }
+                })
+                .collect();
+            if new_ranges.is_empty() {
+                continue;
+            }
+            let mut context = self.data_validation_context(sheet, &data_validation.range)?;
+
+            // Formulas are relative to the top left cell of the first range. If the rule starts
+            // somewhere else after rows or columns are deleted, they are first rewritten relative
+            // to the cell that becomes the new top left cell.
+            let (anchor_row, anchor_column, _, _) = new_ranges[0];
+            let (row, column) = match *displace_data {
+                DisplaceData::Row { row, delta, .. } if anchor_row >= row => {
+                    (anchor_row - delta, anchor_column)
+                }
+                DisplaceData::Column { column, delta, .. } if anchor_column >= column => {
+                    (anchor_row, anchor_column - delta)
+                }
+                DisplaceData::Row { .. } | DisplaceData::Column { .. } => {
+                    (anchor_row, anchor_column)
+                }
+                _ => (context.row, context.column),
+            };
+            if (row, column) != (context.row, context.column) {
+                let new_context = CellReferenceRC {
+                    row,
+                    column,
+                    ..context.clone()
+                };
+                for formula in rule_formulas_mut(&mut data_validation.rule) {
+                    *formula = self.rebase_formula(formula, &context, &new_context);
+                }
+                context = new_context;
+            }
+
+            // Formulas are stored in English
+            let locale = self.locale;
+            let language = self.language;
+            self.parser.set_locale(get_default_locale());
+            self.parser.set_language(get_default_language());
+            for formula in rule_formulas_mut(&mut data_validation.rule) {
+                *formula =
+                    displace_cf_formula_str(&mut self.parser, formula, &context, displace_data);
+            }
+            self.parser.set_locale(locale);
+            self.parser.set_language(language);
+            data_validation.range = ranges_to_sqref(&new_ranges);
+            data_validations.push(data_validation);
+        }
+        self.workbook.worksheet_mut(sheet)?.data_validations = data_validations;
+        Ok(())
+    }
+
     /// Retrieves the column indices for a specific row in a given sheet, sorted in ascending or descending order.
     ///
     /// # Arguments
@@ -572,6 +669,12 @@ impl<'a> Model<'a> {
         };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
+        self.displace_tables(&disp)?;
+        self.displace_merged_cells(&disp)?;
+        self.displace_comments(&disp)?;
+        self.displace_auto_filter(&disp)?;
+        self.displace_page_setup(&disp)?;
 
         // In the list of columns:
         // * Keep all the columns to the left
@@ -599,6 +702,7 @@ impl<'a> Model<'a> {
         // TODO: If in a row the cell to the right and left have the same style we should copy it
 
         worksheet.cols = new_columns;
+        self.extend_column_outline(sheet, column, column_count)?;
 
         Ok(())
     }
@@ -674,6 +778,12 @@ impl<'a> Model<'a> {
         };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
+        self.displace_tables(&disp)?;
+        self.displace_merged_cells(&disp)?;
+        self.displace_comments(&disp)?;
+        self.displace_auto_filter(&disp)?;
+        self.displace_page_setup(&disp)?;
         let worksheet = &mut self.workbook.worksheet_mut(sheet)?;
 
         // deletes all the column styles
@@ -926,6 +1036,13 @@ impl<'a> Model<'a> {
         };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
+        self.displace_tables(&disp)?;
+        self.displace_merged_cells(&disp)?;
+        self.displace_comments(&disp)?;
+        self.displace_auto_filter(&disp)?;
+        self.displace_page_setup(&disp)?;
+        self.extend_row_outline(sheet, row, row_count)?;
 
         Ok(())
     }
@@ -1007,6 +1124,12 @@ impl<'a> Model<'a> {
         };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
+        self.displace_tables(&disp)?;
+        self.displace_merged_cells(&disp)?;
+        self.displace_comments(&disp)?;
+        self.displace_auto_filter(&disp)?;
+        self.displace_page_setup(&disp)?;
         Ok(())
     }
 
@@ -1163,6 +1286,7 @@ impl<'a> Model<'a> {
         };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
         Ok(())
     }
 
@@ -1309,6 +1433,7 @@ impl<'a> Model<'a> {
         let disp = DisplaceData::RowMove { sheet, row, delta };
         self.displace_cells(&disp)?;
         self.displace_cf_ranges(sheet, &disp);
+        self.displace_data_validations(sheet, &disp)?;
         Ok(())
     }
 
@@ -1505,6 +1630,8 @@ impl<'a> Model<'a> {
                 "Cannot move columns because that would split an array formula".to_string(),
             );
         }
+        self.move_merged_cells_columns(sheet, column, column_count, delta)?;
+        self.move_comments_columns(sheet, column, column_count, delta)?;
         self.reset_dynamic_array_spills(sheet)?;
 
         // Move columns in the correct order
@@ -1548,6 +1675,8 @@ impl<'a> Model<'a> {
         if !self.can_move_rows_action(sheet, row, row_count, delta)? {
             return Err("Cannot move rows because that would split an array formula".to_string());
         }
+        self.move_merged_cells_rows(sheet, row, row_count, delta)?;
+        self.move_comments_rows(sheet, row, row_count, delta)?;
         self.reset_dynamic_array_spills(sheet)?;
 
         // Move rows in the correct order
@@ -0,0 +1,523 @@
+//! Autofilters hide the rows of a range whose cells don't match the criteria of their column.
+//!
+//! A sheet has at most one autofilter, in [Worksheet::auto_filter](crate::types::Worksheet::auto_filter).
+//! Each table has its own autofilter, with the criteria stored in its columns.
+//! Like in Excel, filters are not dynamic: rows are hidden when the filters are applied and stay
+//! that way when the values change, until the filters are applied again.
+
+use crate::{
+    calc_result::CalcResult,
+    expressions::{
+        parser::{parse_range, stringify::DisplaceData},
+        types::{Area, CellReferenceIndex},
+    },
+    filter_types::{
+        AutoFilter, CustomFilter, DateGroupItem, DateTimeGrouping, DynamicFilter, Filter,
+        FilterColumn, FilterOperator,
+    },
+    formatter::dates::from_excel_date,
+    functions::util::{compare_values, from_wildcard_to_regex},
+    merged_cells::displace_interval,
+    model::Model,
+    tables::{area_to_reference, areas_overlap},
+    types::{Cell, Table},
+};
+
+use chrono::Datelike;
+
+// The value of each cell of a column, None for empty cells
+type ColumnValues = Vec<Option<CalcResult>>;
+
+fn check_filter(filter: &Filter) -> Result<(), String> {
+    match filter {
+        Filter::Top10 { percent, value, .. } => {
+            if *value < 1.0 || (*percent && *value > 100.0) {
+                return Err(format!("Invalid value for a top 10 filter: {value}"));
+            }
+        }
+        Filter::Custom { filters, .. } => {
+            if filters.is_empty() || filters.len() > 2 {
+                return Err("A custom filter needs one or two conditions".to_string());
+            }
+        }
+        Filter::Values { .. }
+        | Filter::Dynamic { .. }
+        | Filter::CellColor { .. }
+        | Filter::FontColor { .. } => {}
+    }
+    Ok(())
+}
+
+fn date_group_matches(item: &DateGroupItem, serial: f64) -> bool {
+    let Ok(date) = from_excel_date(serial.floor() as i64) else {
+        return false;
+    };
+    let seconds = ((serial - serial.floor()) * 86400.0).round() as u32;
+    let fields = [
+        (DateTimeGrouping::Year, date.year() == item.year),
+        (DateTimeGrouping::Month, date.month() == item.month),
+        (DateTimeGrouping::Day, date.day() == item.day),
+        (DateTimeGrouping::Hour, seconds / 3600 == item.hour),
+        (DateTimeGrouping::Minute, (seconds / 60) % 60 == item.minute),
+        (DateTimeGrouping::Second, seconds % 60 == item.second),
+    ];
+    for (grouping, matches) in fields {
+        if !matches {
+            return false;
+        }
+        if grouping == item.grouping {
+            break;
+        }
+    }
+    true
+}
+
+fn custom_filter_matches(filter: &CustomFilter, value: &Option<CalcResult>, text: &str) -> bool {
+    let criterion = match filter.value.parse::<f64>() {
+        Ok(number) => CalcResult::Number(number),
+        Err(_) => CalcResult::String(filter.value.clone()),
+    };
+    let value = match value {
+        Some(CalcResult::Number(number)) => CalcResult::Number(*number),
+        Some(_) | None => CalcResult::String(text.to_string()),
+    };
+    let same_type = matches!(
+        (&value, &criterion),
+        (CalcResult::Number(_), CalcResult::Number(_))
+            | (CalcResult::String(_), CalcResult::String(_))
+    );
+    match filter.operator {
+        FilterOperator::Equal | FilterOperator::NotEqual => {
+            let equal = match (&value, &criterion) {
+                (CalcResult::String(_), CalcResult::String(pattern)) => {
+                    from_wildcard_to_regex(&pattern.to_lowercase(), true)
+                        .map(|regex| regex.is_match(&text.to_lowercase()))
+                        .unwrap_or(false)
+                }
+                _ => same_type && compare_values(&value, &criterion) == 0,
+            };
+            equal == (filter.operator == FilterOperator::Equal)
+        }
+        _ if !same_type => false,
+        FilterOperator::GreaterThan => compare_values(&value, &criterion) > 0,
+        FilterOperator::GreaterThanOrEqual => compare_values(&value, &criterion) >= 0,
+        FilterOperator::LessThan => compare_values(&value, &criterion) < 0,
+        FilterOperator::LessThanOrEqual => compare_values(&value, &criterion) <= 0,
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Returns the autofilter of the sheet, if any
+    pub fn get_auto_filter(&self, sheet: u32) -> Result<Option<AutoFilter>, String> {
+        Ok(self.workbook.worksheet(sheet)?.auto_filter.clone())
+    }
+
+    /// Returns the area of the autofilter of the sheet, including the header row
+    pub(crate) fn get_auto_filter_area(&self, sheet: u32) -> Result<Option<Area>, String> {
+        let Some(auto_filter) = &self.workbook.worksheet(sheet)?.auto_filter else {
+            return Ok(None);
+        };
+        let (column, row, column_end, row_end) = parse_range(&auto_filter.range)?;
+        Ok(Some(Area {
+            sheet,
+            row,
+            column,
+            width: column_end - column + 1,
+            height: row_end - row + 1,
+        }))
+    }
+
+    /// Adds an autofilter to the sheet in `area`, the first row has the filter buttons.
+    /// It replaces the autofilter of the sheet, if any, showing the rows it filtered.
+    /// Returns the rows that were shown.
+    pub fn set_auto_filter(&mut self, area: &Area) -> Result<Vec<i32>, String> {
+        if area.width < 1 || area.height < 1 {
+            return Err("Invalid area for an autofilter".to_string());
+        }
+        for table in self.get_tables(area.sheet)? {
+            if areas_overlap(area, &self.get_table_area(&table)?) {
+                return Err("An autofilter cannot overlap a table".to_string());
+            }
+        }
+        let range = area_to_reference(area)?;
+        let shown = self.remove_auto_filter(area.sheet)?;
+        self.workbook.worksheet_mut(area.sheet)?.auto_filter = Some(AutoFilter {
+            range,
+            columns: Vec::new(),
+        });
+        Ok(shown)
+    }
+
+    /// Removes the autofilter of the sheet and shows the rows it filtered.
+    /// Returns the rows that were shown.
+    pub fn remove_auto_filter(&mut self, sheet: u32) -> Result<Vec<i32>, String> {
+        let Some(area) = self.get_auto_filter_area(sheet)? else {
+            return Ok(Vec::new());
+        };
+        let has_criteria = self
+            .get_auto_filter(sheet)?
+            .is_some_and(|auto_filter| !auto_filter.columns.is_empty());
+        self.workbook.worksheet_mut(sheet)?.auto_filter = None;
+        let mut shown = Vec::new();
+        if has_criteria {
+            for row in area.row + 1..area.row + area.height {
+                if self.is_row_hidden(sheet, row)? {
+                    self.set_row_hidden(sheet, row, false)?;
+                    shown.push(row);
+                }
+            }
+        }
+        Ok(shown)
+    }
+
+    // Returns the table or the autofilter of the sheet the cell is in
+    fn get_filter_target(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<(Option<Table>, Area), String> {
+        if let Some(name) = self.get_table_name_at(sheet, row, column)? {
+            let table = self.get_table(&name)?;
+            let area = self.get_table_area(&table)?;
+            return Ok((Some(table), area));
+        }
+        match self.get_auto_filter_area(sheet)? {
+            Some(area)
+                if (area.row..area.row + area.height).contains(&row)
+                    && (area.column..area.column + area.width).contains(&column) =>
+            {
+                Ok((None, area))
+            }
+            _ => Err("The cell is not in a table or an autofilter".to_string()),
+        }
+    }
+
+    /// Returns the criteria of the autofilter in the column of the cell, if any.
+    /// The cell can be in a table or in the autofilter of the sheet.
+    pub fn get_filter(&self, sheet: u32, row: i32, column: i32) -> Result<Option<Filter>, String> {
+        let (table, area) = self.get_filter_target(sheet, row, column)?;
+        let index = column - area.column;
+        if let Some(table) = table {
+            return Ok(table.columns[index as usize].filter.clone());
+        }
+        Ok(self.get_auto_filter(sheet)?.and_then(|auto_filter| {
+            auto_filter
+                .columns
+                .into_iter()
+                .find(|filter_column| filter_column.column == index)
+                .map(|filter_column| filter_column.filter)
+        }))
+    }
+
+    /// Sets (or removes if `None`) the criteria of the autofilter in the column of the cell and
+    /// applies the filters of the sheet.
+    /// Returns the rows that were hidden or shown, and whether they are hidden now.
+    pub fn set_filter(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        filter: Option<Filter>,
+    ) -> Result<Vec<(i32, bool)>, String> {
+        if let Some(filter) = &filter {
+            check_filter(filter)?;
+            match filter {
+                Filter::CellColor { color } => self.add_color_dxf(color, false),
+                Filter::FontColor { color } => self.add_color_dxf(color, true),
+                _ => {}
+            }
+        }
+        let (table, area) = self.get_filter_target(sheet, row, column)?;
+        let index = column - area.column;
+        if let Some(mut table) = table {
+            if table.header_row_count == 0 {
+                return Err("A table without a header row cannot be filtered".to_string());
+            }
+            table.columns[index as usize].filter = filter;
+            table.has_filters = table.columns.iter().any(|column| column.filter.is_some());
+            let name = table.name.clone();
+            self.set_table(Some(&name), Some(table))?;
+        } else if let Some(auto_filter) = &mut self.workbook.worksheet_mut(sheet)?.auto_filter {
+            auto_filter
+                .columns
+                .retain(|filter_column| filter_column.column != index);
+            if let Some(filter) = filter {
+                auto_filter.columns.push(FilterColumn {
+                    column: index,
+                    filter,
+                });
+                auto_filter
+                    .columns
+                    .sort_by_key(|filter_column| filter_column.column);
+            }
+        }
+        self.apply_filters(sheet)
+    }
+
+    /// Applies again the autofilters of the sheet and of its tables, hiding the rows that
+    /// don't match their criteria and showing those that do.
+    /// Returns the rows that were hidden or shown, and whether they are hidden now.
+    pub fn apply_filters(&mut self, sheet: u32) -> Result<Vec<(i32, bool)>, String> {
+        let changes = self.get_filter_changes(sheet)?;
+        for &(row, hidden) in &changes {
+            self.set_row_hidden(sheet, row, hidden)?;
+        }
+        Ok(changes)
+    }
+
+    /// Returns the rows that applying the filters of the sheet would hide or show
+    pub(crate) fn get_filter_changes(&self, sheet: u32) -> Result<Vec<(i32, bool)>, String> {
+        // The data rows and the criteria by column of each autofilter
+        let mut autofilters = Vec::new();
+        if let (Some(area), Some(auto_filter)) = (
+            self.get_auto_filter_area(sheet)?,
+            self.get_auto_filter(sheet)?,
+        ) {
+            let filters: Vec<(i32, Filter)> = auto_filter
+                .columns
+                .into_iter()
+                .map(|filter_column| (area.column + filter_column.column, filter_column.filter))
+                .collect();
+            autofilters.push((area.row + 1, area.row + area.height - 1, filters));
+        }
+        for table in self.get_tables(sheet)? {
+            let area = self.get_table_area(&table)?;
+            let filters: Vec<(i32, Filter)> = table
+                .columns
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, column)| {
+                    column
+                        .filter
+                        .map(|filter| (area.column + index as i32, filter))
+                })
+                .collect();
+            let row_start = area.row + table.header_row_count as i32;
+            let row_end = area.row + area.height - 1 - table.totals_row_count as i32;
+            autofilters.push((row_start, row_end, filters));
+        }
+
+        let mut changes = Vec::new();
+        for (row_start, row_end, filters) in autofilters {
+            if row_start > row_end {
+                continue;
+            }
+            let mut visible = vec![true; (row_end - row_start + 1) as usize];
+            for (column, filter) in &filters {
+                let matches =
+                    self.get_column_matches(sheet, *column, row_start, row_end, filter)?;
+                for (visible, matches) in visible.iter_mut().zip(matches) {
+                    *visible = *visible && matches;
+                }
+            }
+            for (row, visible) in (row_start..=row_end).zip(visible) {
+                if self.is_row_hidden(sheet, row)? == visible {
+                    changes.push((row, !visible));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    fn get_column_values(
+        &self,
+        sheet: u32,
+        column: i32,
+        row_start: i32,
+        row_end: i32,
+    ) -> Result<ColumnValues, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        Ok((row_start..=row_end)
+            .map(|row| match worksheet.cell(row, column) {
+                None | Some(Cell::EmptyCell { .. }) => None,
+                Some(cell) => {
+                    Some(self.get_cell_value(cell, CellReferenceIndex { sheet, row, column }))
+                }
+            })
+            .collect())
+    }
+
+    // Returns whether each cell of the column between row_start and row_end matches the filter
+    fn get_column_matches(
+        &self,
+        sheet: u32,
+        column: i32,
+        row_start: i32,
+        row_end: i32,
+        filter: &Filter,
+    ) -> Result<Vec<bool>, String> {
+        let values = self.get_column_values(sheet, column, row_start, row_end)?;
+        let numbers: Vec<f64> = values
+            .iter()
+            .filter_map(|value| match value {
+                Some(CalcResult::Number(number)) => Some(*number),
+                _ => None,
+            })
+            .collect();
+        let number_matches = |predicate: &dyn Fn(f64) -> bool| -> Vec<bool> {
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CalcResult::Number(number)) => predicate(*number),
+                    _ => false,
+                })
+                .collect()
+        };
+        let rows = row_start..=row_end;
+        match filter {
+            Filter::Values {
+                values: texts,
+                date_groups,
+                blank,
+            } => {
+                let texts: Vec<String> = texts.iter().map(|text| text.to_lowercase()).collect();
+                let mut matches = Vec::new();
+                for (row, value) in rows.zip(&values) {
+                    matches.push(match value {
+                        None => *blank,
+                        Some(value) => {
+                            let text = self
+                                .get_formatted_cell_value(sheet, row, column)?
+                                .to_lowercase();
+                            let in_date_group = match value {
+                                CalcResult::Number(serial) => {
+                                    let serial =
+                                        self.date_system().to_1900_serial(serial.floor() as i64)
+                                            as f64
+                                            + serial.fract();
+                                    date_groups
+                                        .iter()
+                                        .any(|item| date_group_matches(item, serial))
+                                }
+                                _ => false,
+                            };
+                            texts.contains(&text) || in_date_group
+                        }
+                    });
+                }
+                Ok(matches)
+            }
+            Filter::Top10 {
+                top,
+                percent,
+                value,
+            } => {
+                let mut sorted = numbers.clone();
+                sorted.sort_by(|a, b| if *top { b.total_cmp(a) } else { a.total_cmp(b) });
+                let count = if *percent {
+                    (sorted.len() as f64 * value / 100.0).ceil() as usize
+                } else {
+                    *value as usize
+                };
+                let Some(&threshold) = sorted.get(count.min(sorted.len()).saturating_sub(1)) else {
+                    return Ok(vec![false; values.len()]);
+                };
+                Ok(number_matches(&|number| {
+                    if *top {
+                        number >= threshold
+                    } else {
+                        number <= threshold
+                    }
+                }))
+            }
+            Filter::Dynamic { kind } => {
+                if numbers.is_empty() {
+                    return Ok(vec![false; values.len()]);
+                }
+                let average = numbers.iter().sum::<f64>() / numbers.len() as f64;
+                Ok(number_matches(&|number| match kind {
+                    DynamicFilter::AboveAverage => number > average,
+                    DynamicFilter::BelowAverage => number < average,
+                }))
+            }
+            Filter::Custom { and, filters } => {
+                let mut matches = Vec::new();
+                for (row, value) in rows.zip(&values) {
+                    let text = match value {
+                        None => String::new(),
+                        Some(_) => self.get_formatted_cell_value(sheet, row, column)?,
+                    };
+                    let mut results = filters
+                        .iter()
+                        .map(|filter| custom_filter_matches(filter, value, &text));
+                    matches.push(if *and {
+                        results.all(|result| result)
+                    } else {
+                        results.any(|result| result)
+                    });
+                }
+                Ok(matches)
+            }
+            Filter::CellColor { color } => rows
+                .map(|row| Ok(&self.get_style_for_cell(sheet, row, column)?.fill.color == color))
+                .collect(),
+            Filter::FontColor { color } => rows
+                .map(|row| Ok(&self.get_style_for_cell(sheet, row, column)?.font.color == color))
+                .collect(),
+        }
+    }
+
+    /// Moves the autofilter of the sheet when rows or columns are inserted or deleted.
+    /// The criteria of deleted columns are removed, and so is the autofilter if all its rows
+    /// are deleted.
+    pub(crate) fn displace_auto_filter(
+        &mut self,
+        displace_data: &DisplaceData,
+    ) -> Result<(), String> {
+        let sheet = match displace_data {
+            DisplaceData::Row { sheet, .. } | DisplaceData::Column { sheet, .. } => *sheet,
+            _ => return Ok(()),
+        };
+        let Some(area) = self.get_auto_filter_area(sheet)? else {
+            return Ok(());
+        };
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let Some(mut auto_filter) = worksheet.auto_filter.take() else {
+            return Ok(());
+        };
+        let new_area = match *displace_data {
+            DisplaceData::Row { row, delta, .. } => {
+                let Some((start, end)) =
+                    displace_interval(area.row, area.row + area.height - 1, row, delta)
+                else {
+                    return Ok(());
+                };
+                Area {
+                    row: start,
+                    height: end - start + 1,
+                    ..area
+                }
+            }
+            DisplaceData::Column { column, delta, .. } => {
+                let Some((start, end)) =
+                    displace_interval(area.column, area.column + area.width - 1, column, delta)
+                else {
+                    return Ok(());
+                };
+                auto_filter.columns = auto_filter
+                    .columns
+                    .into_iter()
+                    .filter_map(|filter_column| {
+                        let old_column = area.column + filter_column.column;
+                        let (new_column, _) =
+                            displace_interval(old_column, old_column, column, delta)?;
+                        Some(FilterColumn {
+                            column: new_column - start,
+                            ..filter_column
+                        })
+                    })
+                    .collect();
+                Area {
+                    column: start,
+                    width: end - start + 1,
+                    ..area
+                }
+            }
+            _ => area,
+        };
+        auto_filter.range = area_to_reference(&new_area)?;
+        worksheet.auto_filter = Some(auto_filter);
+        Ok(())
+    }
+}
@@ -24,7 +24,10 @@ const FILL_BORDER: StyleIncludes = includes(false, false, true, true);
 const NUMBER_FORMAT_ONLY: StyleIncludes = includes(true, false, false, false);
 
 fn solid_fill(color: Color) -> Fill {
-    Fill { color }
+    Fill {
+        color,
+        ..Default::default()
+    }
 }
 
 fn thin_box_border(color: Color) -> Border {
@@ -22,6 +22,8 @@ pub(crate) enum CalcResult {
         left: CellReferenceIndex,
         right: CellReferenceIndex,
     },
+    // A reference with more than one area, the result of the union operator: (A1:A3,C1:C3)
+    MultiRange(Vec<Range>),
     EmptyCell,
     EmptyArg,
     Array(Vec<Vec<ArrayNode>>),
@@ -43,6 +45,13 @@ impl CalcResult {
             message: "Wrong number of arguments".to_string(),
         }
     }
+    pub fn new_multiple_areas_error(origin: CellReferenceIndex) -> CalcResult {
+        CalcResult::Error {
+            error: Error::VALUE,
+            origin,
+            message: "Multiple areas are not supported".to_string(),
+        }
+    }
     pub fn is_error(&self) -> bool {
         matches!(self, CalcResult::Error { .. })
     }
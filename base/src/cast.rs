@@ -66,7 +66,8 @@ impl<'a> Model<'a> {
                     currencies.push(currency);
                 }
                 // Try to parse as a formatted number (e.g., dates, currencies, percentages)
-                if let Ok((v, _number_format)) = parse_formatted_number(s, &currencies, self.locale)
+                if let Ok((v, _number_format)) =
+                    parse_formatted_number(s, &currencies, self.locale, self.date_system())
                 {
                     return Some(v);
                 }
@@ -97,6 +98,7 @@ impl<'a> Model<'a> {
                 }
             }
             CalcResult::EmptyCell | CalcResult::EmptyArg => Ok(NumberOrArray::Number(0.0)),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { left, right } => {
                 let sheet = left.sheet;
                 if sheet != right.sheet {
@@ -117,7 +119,7 @@ impl<'a> Model<'a> {
                                 CalcResult::Number(f) => ArrayNode::Number(f),
                                 CalcResult::Boolean(b) => ArrayNode::Boolean(b),
                                 CalcResult::Error { error, .. } => ArrayNode::Error(error),
-                                CalcResult::Range { .. } => {
+                                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                                     // if we do things right this can never happen.
                                     // the evaluation of a cell should never return a range
                                     ArrayNode::Number(0.0)
@@ -265,6 +267,7 @@ impl<'a> Model<'a> {
             }
             CalcResult::EmptyCell | CalcResult::EmptyArg => Ok(0.0),
             error @ CalcResult::Error { .. } => Err(error),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { .. } => Err(CalcResult::Error {
                 error: Error::NIMPL,
                 origin: cell,
@@ -322,6 +325,7 @@ impl<'a> Model<'a> {
             }
             CalcResult::EmptyCell | CalcResult::EmptyArg => Ok("".to_string()),
             error @ CalcResult::Error { .. } => Err(error),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { .. } => Err(CalcResult::Error {
                 error: Error::NIMPL,
                 origin: cell,
@@ -371,6 +375,7 @@ impl<'a> Model<'a> {
             CalcResult::Boolean(b) => Ok(b),
             CalcResult::EmptyCell | CalcResult::EmptyArg => Ok(false),
             error @ CalcResult::Error { .. } => Err(error),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { .. } => Err(CalcResult::Error {
                 error: Error::NIMPL,
                 origin: cell,
@@ -0,0 +1,208 @@
+//! Comments attached to cells.
+//!
+//! The comments of a sheet are stored in [Worksheet::comments](crate::types::Worksheet::comments)
+//! with the reference of their cell, like "C4". Like the content of the cell they follow it when
+//! rows or columns are inserted, deleted or moved.
+
+use chrono::DateTime;
+
+use crate::{
+    expressions::{
+        parser::stringify::DisplaceData,
+        utils::{number_to_column, parse_reference_a1},
+    },
+    merged_cells::{displace_interval, move_interval},
+    model::get_milliseconds_since_epoch,
+    types::Comment,
+    Model,
+};
+
+// Returns (row, column) of the cell the comment is attached to
+fn get_comment_cell(comment: &Comment) -> Option<(i32, i32)> {
+    parse_reference_a1(&comment.cell_ref).map(|reference| (reference.row, reference.column))
+}
+
+fn get_cell_reference(row: i32, column: i32) -> Result<String, String> {
+    match number_to_column(column) {
+        Some(column_name) => Ok(format!("{column_name}{row}")),
+        None => Err(format!("Invalid column: '{column}'")),
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Returns the comments of the sheet
+    pub fn get_comments(&self, sheet: u32) -> Result<Vec<Comment>, String> {
+        Ok(self.workbook.worksheet(sheet)?.comments.clone())
+    }
+
+    /// Returns the comment attached to the cell, if any
+    pub fn get_comment(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Comment>, String> {
+        Ok(self
+            .workbook
+            .worksheet(sheet)?
+            .comments
+            .iter()
+            .find(|comment| get_comment_cell(comment) == Some((row, column)))
+            .cloned())
+    }
+
+    /// Attaches `comment` to the cell replacing the previous one, or removes the comment of the
+    /// cell if `comment` is None. The `cell_ref` of the comment is set to the cell.
+    pub fn set_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        comment: Option<Comment>,
+    ) -> Result<(), String> {
+        let cell_ref = get_cell_reference(row, column)?;
+        let comments = &mut self.workbook.worksheet_mut(sheet)?.comments;
+        let index = comments
+            .iter()
+            .position(|comment| get_comment_cell(comment) == Some((row, column)));
+        match (index, comment) {
+            (Some(index), Some(comment)) => {
+                comments[index] = Comment {
+                    cell_ref,
+                    ..comment
+                };
+            }
+            (None, Some(comment)) => comments.push(Comment {
+                cell_ref,
+                ..comment
+            }),
+            (Some(index), None) => {
+                comments.remove(index);
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Returns a new id for a threaded comment, a reply or a person, like
+    /// "{0184FD3A-1C22-4000-8000-000000000003}". It is different from the ids in the workbook
+    /// and from those in `taken`.
+    pub(crate) fn new_comment_id(&self, taken: &[&str]) -> String {
+        let milliseconds = get_milliseconds_since_epoch();
+        let mut used_ids: Vec<&str> = taken.to_vec();
+        for worksheet in &self.workbook.worksheets {
+            for comment in &worksheet.comments {
+                if let Some(author_id) = &comment.author_id {
+                    used_ids.push(author_id.as_str());
+                }
+                if let Some(thread) = &comment.thread {
+                    used_ids.push(&thread.id);
+                    for reply in &thread.replies {
+                        used_ids.push(&reply.id);
+                        if let Some(author_id) = &reply.author_id {
+                            used_ids.push(author_id.as_str());
+                        }
+                    }
+                }
+            }
+        }
+        let mut count = used_ids.len() as i64;
+        loop {
+            let id = format!(
+                "{{{:08X}-{:04X}-4000-8000-{:012X}}}",
+                (milliseconds >> 16) & 0xFFFF_FFFF,
+                milliseconds & 0xFFFF,
+                count
+            );
+            if !used_ids.contains(&id.as_str()) {
+                return id;
+            }
+            count += 1;
+        }
+    }
+
+    /// The current time as used in the dates of threaded comments, like "2024-03-05T10:20:30.000"
+    pub(crate) fn get_comment_date(&self) -> String {
+        match DateTime::from_timestamp_millis(get_milliseconds_since_epoch()) {
+            Some(date) => date.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
+            None => "".to_string(),
+        }
+    }
+
+    // Moves each comment of the sheet to the cell `map(row, column)`, removing it if it is None
+    pub(crate) fn map_comments<F>(&mut self, sheet: u32, map: F) -> Result<(), String>
+    where
+        F: Fn(i32, i32) -> Option<(i32, i32)>,
+    {
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let mut comments = Vec::new();
+        for comment in worksheet.comments.drain(..) {
+            let Some((row, column)) = get_comment_cell(&comment) else {
+                comments.push(comment);
+                continue;
+            };
+            if let Some((new_row, new_column)) = map(row, column) {
+                let cell_ref = get_cell_reference(new_row, new_column)?;
+                comments.push(Comment {
+                    cell_ref,
+                    ..comment
+                });
+            }
+        }
+        worksheet.comments = comments;
+        Ok(())
+    }
+
+    /// Moves the comments when rows or columns are inserted, and removes those of the deleted cells
+    pub(crate) fn displace_comments(&mut self, displace_data: &DisplaceData) -> Result<(), String> {
+        match *displace_data {
+            DisplaceData::Row { sheet, row, delta } => {
+                self.map_comments(sheet, |comment_row, column| {
+                    let (new_row, _) = displace_interval(comment_row, comment_row, row, delta)?;
+                    Some((new_row, column))
+                })
+            }
+            DisplaceData::Column {
+                sheet,
+                column,
+                delta,
+            } => self.map_comments(sheet, |row, comment_column| {
+                let (new_column, _) =
+                    displace_interval(comment_column, comment_column, column, delta)?;
+                Some((row, new_column))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves the comments when the rows [row, row + row_count - 1] are moved by `delta`
+    pub(crate) fn move_comments_rows(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        let group_end = row + row_count - 1;
+        self.map_comments(sheet, |comment_row, column| {
+            let (new_row, _) = move_interval(comment_row, comment_row, row, group_end, delta)?;
+            Some((new_row, column))
+        })
+    }
+
+    /// Moves the comments when the columns [column, column + column_count - 1] are moved by `delta`
+    pub(crate) fn move_comments_columns(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        column_count: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        let group_end = column + column_count - 1;
+        self.map_comments(sheet, |row, comment_column| {
+            let (new_column, _) =
+                move_interval(comment_column, comment_column, column, group_end, delta)?;
+            Some((row, new_column))
+        })
+    }
+}
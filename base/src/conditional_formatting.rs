@@ -11,7 +11,7 @@ use crate::{
         PeriodType, TextOperator, ValueOperator,
     },
     expressions::types::{CellReferenceIndex, CellReferenceRC},
-    types::{Color, Dxf},
+    types::{Color, DateSystem, Dxf, DxfFont, Fill},
     Model,
 };
 
@@ -22,7 +22,7 @@ use chrono::{Datelike, Duration, Months, NaiveDate};
 // ---------------------------------------------------------------------------
 
 /// Parses a space-separated sqref like "A1:C3 E5" into a list of (row1,col1,row2,col2) tuples.
-fn parse_sqref(sqref: &str) -> Vec<(i32, i32, i32, i32)> {
+pub(crate) fn parse_sqref(sqref: &str) -> Vec<(i32, i32, i32, i32)> {
     sqref
         .split_whitespace()
         .filter_map(parse_range_part)
@@ -109,13 +109,15 @@ fn compute_icon_index(v: f64, thresholds: &[(f64, bool)]) -> u32 {
 
 /// Parses a date bound of a Between/NotBetween time-period rule: either a
 /// date string in any format DATEVALUE accepts (e.g. "2025-04-25",
-/// "4/25/2025") or a plain Excel serial number.
-fn parse_cf_date_bound(value: &str) -> Option<f64> {
+/// "4/25/2025") or a plain Excel serial number in `date_system`.
+/// The result is always a serial number of the 1900 date system.
+fn parse_cf_date_bound(value: &str, date_system: DateSystem) -> Option<f64> {
     let trimmed = value.trim();
     if let Ok(serial) = crate::functions::date_and_time::parse_datevalue_text(trimmed) {
         return Some(serial as f64);
     }
-    trimmed.parse::<f64>().ok().map(f64::floor)
+    let serial = trimmed.parse::<f64>().ok()?.floor();
+    Some(date_system.to_1900_serial(serial as i64) as f64)
 }
 
 /// Stable string key for a CellValue, used for duplicate detection.
@@ -988,21 +990,26 @@ impl<'a> Model<'a> {
                 let (Some(d1), Some(d2)) = (date1, date2) else {
                     return;
                 };
-                let (Some(s1), Some(s2)) = (parse_cf_date_bound(d1), parse_cf_date_bound(d2))
-                else {
+                let date_system = self.date_system();
+                let (Some(s1), Some(s2)) = (
+                    parse_cf_date_bound(d1, date_system),
+                    parse_cf_date_bound(d2, date_system),
+                ) else {
                     return;
                 };
                 (s1.min(s2), s1.max(s2))
             }
         };
         let negate = matches!(period, PeriodType::NotBetween);
+        // The period is computed in the 1900 date system
+        let date_system = self.date_system();
 
         for &(r1, c1, r2, c2) in ranges {
             for row in r1..=r2 {
                 for col in c1..=c2 {
                     if let Ok(CellValue::Number(v)) = self.get_cell_value_by_index(sheet, row, col)
                     {
-                        let day = v.floor();
+                        let day = date_system.to_1900_serial(v.floor() as i64) as f64;
                         let inside = day >= range.0 && day <= range.1;
                         if inside != negate {
                             self.update_cf_cache(
@@ -1159,7 +1166,10 @@ impl<'a> Model<'a> {
                     }
                 }
                 CfCellResult::ColorScale(color) => {
-                    style.fill.color = color.clone();
+                    style.fill = Fill {
+                        color: color.clone(),
+                        ..Default::default()
+                    };
                 }
                 CfCellResult::DataBar {
                     positive_color,
@@ -1219,12 +1229,37 @@ impl<'a> Model<'a> {
     // -----------------------------------------------------------------------
 
     /// Appends `dxf` to the workbook's dxf table and returns its new index.
-    fn create_dxf(&mut self, dxf: Dxf) -> u32 {
+    pub(crate) fn create_dxf(&mut self, dxf: Dxf) -> u32 {
         let id = self.workbook.styles.dxfs.len() as u32;
         self.workbook.styles.dxfs.push(dxf);
         id
     }
 
+    /// Adds a dxf with just a fill color (or a font color), unless there is one already.
+    /// Sorts and filters by color reference them in xlsx files.
+    pub(crate) fn add_color_dxf(&mut self, color: &Color, font: bool) {
+        let dxf = if font {
+            Dxf {
+                font: Some(DxfFont {
+                    color: color.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        } else {
+            Dxf {
+                fill: Some(Fill {
+                    color: color.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        };
+        if !self.workbook.styles.dxfs.contains(&dxf) {
+            self.create_dxf(dxf);
+        }
+    }
+
     /// Converts a `CfRuleInput` into a stored `CfRule`, creating a dxf entry when a format is provided.
     fn cf_rule_from_input(&mut self, rule: CfRuleInput) -> CfRule {
         match rule {
@@ -0,0 +1,588 @@
+//! Data validation: rules that restrict the values that can be typed in a range of cells.
+//!
+//! The rules are stored in [Worksheet::data_validations](crate::types::Worksheet::data_validations),
+//! each one attached to a space separated list of ranges. A cell has at most one rule.
+//! Formulas are stored in English, relative to the top left cell of the first range of the rule.
+
+use crate::{
+    calc_result::CalcResult,
+    cast::array_node_to_string,
+    cf_types::ValueOperator,
+    conditional_formatting::parse_sqref,
+    cut_paste::cf_sqref_anchor,
+    dv_types::{DataValidation, DataValidationAlert, DataValidationListSource, DataValidationRule},
+    expressions::{
+        parser::stringify::to_english_string,
+        types::{Area, CellReferenceIndex, CellReferenceRC},
+        utils::number_to_column,
+    },
+    formatter::format::parse_formatted_number,
+    types::Cell,
+    Model,
+};
+
+const DEFAULT_ERROR_MESSAGE: &str =
+    "The value doesn't match the data validation restrictions defined for this cell.";
+
+/// The formulas of the rule, so they can be translated or displaced
+pub(crate) fn rule_formulas_mut(rule: &mut DataValidationRule) -> Vec<&mut String> {
+    match rule {
+        DataValidationRule::List {
+            source: DataValidationListSource::Formula(formula),
+            ..
+        }
+        | DataValidationRule::Custom { formula } => vec![formula],
+        DataValidationRule::List { .. } => vec![],
+        DataValidationRule::WholeNumber {
+            formula1, formula2, ..
+        }
+        | DataValidationRule::Decimal {
+            formula1, formula2, ..
+        }
+        | DataValidationRule::Date {
+            formula1, formula2, ..
+        }
+        | DataValidationRule::Time {
+            formula1, formula2, ..
+        }
+        | DataValidationRule::TextLength {
+            formula1, formula2, ..
+        } => {
+            let mut formulas = vec![formula1];
+            if let Some(formula2) = formula2 {
+                formulas.push(formula2);
+            }
+            formulas
+        }
+    }
+}
+
+/// Returns a sqref like "A1:B3 D4" for a list of (row1, column1, row2, column2) ranges
+pub(crate) fn ranges_to_sqref(ranges: &[(i32, i32, i32, i32)]) -> String {
+    ranges
+        .iter()
+        .map(|&(row1, column1, row2, column2)| {
+            let start = format!("{}{row1}", number_to_column(column1).unwrap_or_default());
+            if row1 == row2 && column1 == column2 {
+                start
+            } else {
+                let end = number_to_column(column2).unwrap_or_default();
+                format!("{start}:{end}{row2}")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn sqref_contains(sqref: &str, row: i32, column: i32) -> bool {
+    parse_sqref(sqref)
+        .iter()
+        .any(|&(row1, column1, row2, column2)| {
+            (row1..=row2).contains(&row) && (column1..=column2).contains(&column)
+        })
+}
+
+// The parts of `range` that are not in `removed`
+fn subtract_range(
+    range: (i32, i32, i32, i32),
+    removed: (i32, i32, i32, i32),
+) -> Vec<(i32, i32, i32, i32)> {
+    let (row1, column1, row2, column2) = range;
+    let (removed_row1, removed_column1, removed_row2, removed_column2) = removed;
+    if removed_row2 < row1
+        || row2 < removed_row1
+        || removed_column2 < column1
+        || column2 < removed_column1
+    {
+        return vec![range];
+    }
+    let mut parts = Vec::new();
+    if row1 < removed_row1 {
+        parts.push((row1, column1, removed_row1 - 1, column2));
+    }
+    let middle_row1 = row1.max(removed_row1);
+    let middle_row2 = row2.min(removed_row2);
+    if column1 < removed_column1 {
+        parts.push((middle_row1, column1, middle_row2, removed_column1 - 1));
+    }
+    if removed_column2 < column2 {
+        parts.push((middle_row1, removed_column2 + 1, middle_row2, column2));
+    }
+    if removed_row2 < row2 {
+        parts.push((removed_row2 + 1, column1, row2, column2));
+    }
+    parts
+}
+
+fn compare(operator: &ValueOperator, value: f64, bound1: f64, bound2: Option<f64>) -> bool {
+    match operator {
+        ValueOperator::Equal => value == bound1,
+        ValueOperator::NotEqual => value != bound1,
+        ValueOperator::GreaterThan => value > bound1,
+        ValueOperator::GreaterThanOrEqual => value >= bound1,
+        ValueOperator::LessThan => value < bound1,
+        ValueOperator::LessThanOrEqual => value <= bound1,
+        ValueOperator::Between => {
+            bound2.is_some_and(|bound2| bound1.min(bound2) <= value && value <= bound1.max(bound2))
+        }
+        ValueOperator::NotBetween => {
+            bound2.is_some_and(|bound2| value < bound1.min(bound2) || bound1.max(bound2) < value)
+        }
+    }
+}
+
+impl<'a> Model<'a> {
+    // The cell the formulas of a rule with that range are relative to
+    pub(crate) fn data_validation_context(
+        &self,
+        sheet: u32,
+        range: &str,
+    ) -> Result<CellReferenceRC, String> {
+        let (row, column) = cf_sqref_anchor(range)
+            .ok_or_else(|| format!("Invalid data validation range: '{range}'"))?;
+        Ok(CellReferenceRC {
+            sheet: self.workbook.worksheet(sheet)?.get_name(),
+            row,
+            column,
+        })
+    }
+
+    // Rewrites a formula written in the cell `from` so that it means the same written in `to`
+    pub(crate) fn rebase_formula(
+        &mut self,
+        formula: &str,
+        from: &CellReferenceRC,
+        to: &CellReferenceRC,
+    ) -> String {
+        let node = self.parse_internal_formula(formula, from);
+        to_english_string(&node, to)
+    }
+
+    /// Returns the data validation rules of the sheet, with the formulas in the active language
+    pub fn get_data_validations(&self, sheet: u32) -> Result<Vec<DataValidation>, String> {
+        let mut data_validations = self.workbook.worksheet(sheet)?.data_validations.clone();
+        for data_validation in &mut data_validations {
+            let context = self.data_validation_context(sheet, &data_validation.range)?;
+            for formula in rule_formulas_mut(&mut data_validation.rule) {
+                *formula = self.internal_formula_to_display(formula, &context);
+            }
+        }
+        Ok(data_validations)
+    }
+
+    /// Returns the data validation rule of the cell, if any
+    pub fn get_data_validation(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<DataValidation>, String> {
+        Ok(self
+            .get_data_validations(sheet)?
+            .into_iter()
+            .find(|data_validation| sqref_contains(&data_validation.range, row, column)))
+    }
+
+    // The stored rule of the cell, if any
+    fn get_cell_data_validation(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<DataValidation>, String> {
+        Ok(self
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .iter()
+            .find(|data_validation| sqref_contains(&data_validation.range, row, column))
+            .cloned())
+    }
+
+    /// Adds a data validation rule. The formulas are in the active language and relative to
+    /// the top left cell of the first range. The cells in the range lose their previous rule.
+    pub fn add_data_validation(
+        &mut self,
+        sheet: u32,
+        data_validation: DataValidation,
+    ) -> Result<(), String> {
+        let ranges = parse_sqref(&data_validation.range);
+        if ranges.is_empty() {
+            return Err(format!(
+                "Invalid data validation range: '{}'",
+                data_validation.range
+            ));
+        }
+        let mut data_validation = DataValidation {
+            range: ranges_to_sqref(&ranges),
+            ..data_validation
+        };
+        let context = self.data_validation_context(sheet, &data_validation.range)?;
+        for formula in rule_formulas_mut(&mut data_validation.rule) {
+            let internal = self.user_formula_to_internal(formula, &context)?;
+            *formula = match internal.strip_prefix('=') {
+                Some(body) => body.to_string(),
+                None => internal,
+            };
+        }
+        for range in ranges {
+            self.remove_data_validation_range(sheet, range)?;
+        }
+        self.workbook
+            .worksheet_mut(sheet)?
+            .data_validations
+            .push(data_validation);
+        Ok(())
+    }
+
+    /// Removes the data validation rules from the cells in `area`
+    pub fn remove_data_validations(&mut self, area: &Area) -> Result<(), String> {
+        if area.width < 1 || area.height < 1 {
+            return Err("Invalid area".to_string());
+        }
+        self.remove_data_validation_range(
+            area.sheet,
+            (
+                area.row,
+                area.column,
+                area.row + area.height - 1,
+                area.column + area.width - 1,
+            ),
+        )
+    }
+
+    fn remove_data_validation_range(
+        &mut self,
+        sheet: u32,
+        removed: (i32, i32, i32, i32),
+    ) -> Result<(), String> {
+        let old_data_validations =
+            std::mem::take(&mut self.workbook.worksheet_mut(sheet)?.data_validations);
+        let mut data_validations = Vec::new();
+        for mut data_validation in old_data_validations {
+            let ranges = parse_sqref(&data_validation.range);
+            let new_ranges: Vec<(i32, i32, i32, i32)> = ranges
+                .iter()
+                .flat_map(|&range| subtract_range(range, removed))
+                .collect();
+            if new_ranges.is_empty() {
+                continue;
+            }
+            if new_ranges != ranges {
+                let new_range = ranges_to_sqref(&new_ranges);
+                let from = self.data_validation_context(sheet, &data_validation.range)?;
+                let to = self.data_validation_context(sheet, &new_range)?;
+                if (from.row, from.column) != (to.row, to.column) {
+                    for formula in rule_formulas_mut(&mut data_validation.rule) {
+                        *formula = self.rebase_formula(formula, &from, &to);
+                    }
+                }
+                data_validation.range = new_range;
+            }
+            data_validations.push(data_validation);
+        }
+        self.workbook.worksheet_mut(sheet)?.data_validations = data_validations;
+        Ok(())
+    }
+
+    // Evaluates a bound of a rule in the cell
+    fn evaluate_data_validation_bound(
+        &mut self,
+        formula: &str,
+        context: &CellReferenceRC,
+        cell: CellReferenceIndex,
+    ) -> Option<f64> {
+        let node = self.parse_internal_formula(formula, context);
+        let result = match self.evaluate_node_in_context(&node, cell) {
+            CalcResult::Range { left, right } if left == right => self.evaluate_cell(left),
+            result => result,
+        };
+        self.cast_to_number(result, cell).ok()
+    }
+
+    fn compare_with_bounds(
+        &mut self,
+        value: f64,
+        operator: &ValueOperator,
+        (formula1, formula2): (&str, &Option<String>),
+        context: &CellReferenceRC,
+        cell: CellReferenceIndex,
+    ) -> bool {
+        let Some(bound1) = self.evaluate_data_validation_bound(formula1, context, cell) else {
+            return false;
+        };
+        let bound2 = match formula2 {
+            Some(formula2) => self.evaluate_data_validation_bound(formula2, context, cell),
+            None => None,
+        };
+        compare(operator, value, bound1, bound2)
+    }
+
+    // The allowed values of a list rule, evaluated in the cell
+    fn get_list_values(
+        &mut self,
+        source: &DataValidationListSource,
+        context: &CellReferenceRC,
+        cell: CellReferenceIndex,
+    ) -> Vec<String> {
+        let formula = match source {
+            DataValidationListSource::Values(values) => return values.clone(),
+            DataValidationListSource::Formula(formula) => formula,
+        };
+        let node = self.parse_internal_formula(formula, context);
+        match self.evaluate_node_in_context(&node, cell) {
+            CalcResult::Range { left, right } => {
+                let mut values = Vec::new();
+                for row in left.row..=right.row {
+                    for column in left.column..=right.column {
+                        let value = self.evaluate_cell(CellReferenceIndex {
+                            sheet: left.sheet,
+                            row,
+                            column,
+                        });
+                        if let Ok(value) = self.cast_to_string(value, cell) {
+                            if !value.is_empty() {
+                                values.push(value);
+                            }
+                        }
+                    }
+                }
+                values
+            }
+            CalcResult::Array(array) => array
+                .iter()
+                .flatten()
+                .filter_map(|node| array_node_to_string(node).ok())
+                .collect(),
+            result => self.cast_to_string(result, cell).into_iter().collect(),
+        }
+    }
+
+    // Whether the value of the cell satisfies the rule
+    fn is_valid_cell(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        data_validation: &DataValidation,
+    ) -> Result<bool, String> {
+        let context = self.data_validation_context(sheet, &data_validation.range)?;
+        let cell = CellReferenceIndex { sheet, row, column };
+        let value = self.evaluate_cell(cell);
+        let is_blank = match &value {
+            CalcResult::EmptyCell => true,
+            CalcResult::String(s) => s.is_empty(),
+            _ => false,
+        };
+        if is_blank {
+            return Ok(data_validation.allow_blank);
+        }
+        let is_valid = match &data_validation.rule {
+            DataValidationRule::List { source, .. } => match self.cast_to_string(value, cell) {
+                Ok(text) => {
+                    let text = text.to_lowercase();
+                    self.get_list_values(source, &context, cell)
+                        .iter()
+                        .any(|item| item.to_lowercase() == text)
+                }
+                Err(_) => false,
+            },
+            DataValidationRule::WholeNumber {
+                operator,
+                formula1,
+                formula2,
+            } => match value {
+                CalcResult::Number(n) if n.fract() == 0.0 => {
+                    self.compare_with_bounds(n, operator, (formula1, formula2), &context, cell)
+                }
+                _ => false,
+            },
+            DataValidationRule::Decimal {
+                operator,
+                formula1,
+                formula2,
+            }
+            | DataValidationRule::Date {
+                operator,
+                formula1,
+                formula2,
+            }
+            | DataValidationRule::Time {
+                operator,
+                formula1,
+                formula2,
+            } => match value {
+                CalcResult::Number(n) => {
+                    self.compare_with_bounds(n, operator, (formula1, formula2), &context, cell)
+                }
+                _ => false,
+            },
+            DataValidationRule::TextLength {
+                operator,
+                formula1,
+                formula2,
+            } => match self.cast_to_string(value, cell) {
+                Ok(text) => {
+                    let length = text.chars().count() as f64;
+                    self.compare_with_bounds(length, operator, (formula1, formula2), &context, cell)
+                }
+                Err(_) => false,
+            },
+            DataValidationRule::Custom { formula } => {
+                let node = self.parse_internal_formula(formula, &context);
+                match self.evaluate_node_in_context(&node, cell) {
+                    CalcResult::Boolean(b) => b,
+                    CalcResult::Number(n) => n != 0.0,
+                    _ => false,
+                }
+            }
+        };
+        Ok(is_valid)
+    }
+
+    // The cell that `set_user_input` would write, ignoring the style
+    fn user_input_to_cell(&mut self, input: &str) -> Cell {
+        if let Some(text) = input.strip_prefix('\'') {
+            return self.temporary_string_cell(text);
+        }
+        let mut currencies = vec!["$", "€"];
+        let currency = &self.locale.currency.symbol;
+        if !currencies.iter().any(|e| e == currency) {
+            currencies.push(currency);
+        }
+        if let Ok((v, _)) =
+            parse_formatted_number(input, &currencies, self.locale, self.date_system())
+        {
+            return Cell::NumberCell { v, s: 0 };
+        }
+        if let Ok(v) = input.to_lowercase().parse::<bool>() {
+            return Cell::BooleanCell { v, s: 0 };
+        }
+        self.temporary_string_cell(input)
+    }
+
+    // A string cell. New strings are added at the end of the shared strings.
+    fn temporary_string_cell(&mut self, text: &str) -> Cell {
+        let si = match self.shared_strings.get(text) {
+            Some(index) => *index,
+            None => {
+                let index = self.workbook.shared_strings.len();
+                self.workbook.shared_strings.push(text.to_string());
+                self.shared_strings.insert(text.to_string(), index);
+                index
+            }
+        };
+        Cell::SharedString {
+            si: si as i32,
+            s: 0,
+        }
+    }
+
+    /// Checks the value the user wants to type in a cell against its data validation rule.
+    /// Returns the alert that should be shown to the user if the value is not valid.
+    ///
+    /// Empty values and formulas are always accepted.
+    /// Nothing is reported if the rule does not show error alerts, as in Excel.
+    pub fn validate_user_input(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        input: &str,
+    ) -> Result<Option<DataValidationAlert>, String> {
+        let Some(data_validation) = self.get_cell_data_validation(sheet, row, column)? else {
+            return Ok(None);
+        };
+        if !data_validation.show_error_message
+            || input.is_empty()
+            || self.formula_without_prefix(input).is_some()
+        {
+            return Ok(None);
+        }
+
+        // The value is written in the cell while the rule is checked, so custom formulas see it
+        let old_cell = self.workbook.worksheet(sheet)?.cell(row, column).cloned();
+        let shared_strings_count = self.workbook.shared_strings.len();
+        let new_cell = self.user_input_to_cell(input);
+        self.workbook
+            .worksheet_mut(sheet)?
+            .update_cell(row, column, new_cell)?;
+        let is_valid = self
+            .evaluate_detached(|model| model.is_valid_cell(sheet, row, column, &data_validation));
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        match old_cell {
+            Some(cell) => worksheet.update_cell(row, column, cell)?,
+            None => worksheet.remove_cell(row, column)?,
+        }
+        if self.workbook.shared_strings.len() > shared_strings_count {
+            if let Some(text) = self.workbook.shared_strings.pop() {
+                self.shared_strings.remove(&text);
+            }
+        }
+
+        if is_valid? {
+            return Ok(None);
+        }
+        Ok(Some(DataValidationAlert {
+            style: data_validation.error_style,
+            title: data_validation.error_title,
+            message: data_validation
+                .error
+                .unwrap_or_else(|| DEFAULT_ERROR_MESSAGE.to_string()),
+        }))
+    }
+
+    /// Returns the allowed values of the cell if it has a list rule, for the dropdown
+    pub fn get_data_validation_list(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Vec<String>, String> {
+        let Some(data_validation) = self.get_cell_data_validation(sheet, row, column)? else {
+            return Ok(vec![]);
+        };
+        let DataValidationRule::List { source, .. } = &data_validation.rule else {
+            return Ok(vec![]);
+        };
+        let context = self.data_validation_context(sheet, &data_validation.range)?;
+        let cell = CellReferenceIndex { sheet, row, column };
+        Ok(self.evaluate_detached(|model| model.get_list_values(source, &context, cell)))
+    }
+
+    /// Returns the non empty cells of the sheet that break their data validation rule,
+    /// sorted by row and column ("circle invalid data")
+    pub fn get_invalid_cells(&mut self, sheet: u32) -> Result<Vec<(i32, i32)>, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        let data_validations = worksheet.data_validations.clone();
+        let mut cells = Vec::new();
+        for data_validation in &data_validations {
+            for (row1, column1, row2, column2) in parse_sqref(&data_validation.range) {
+                let worksheet = self.workbook.worksheet(sheet)?;
+                for (row, row_data) in &worksheet.sheet_data {
+                    if !(row1..=row2).contains(row) {
+                        continue;
+                    }
+                    for (column, cell) in row_data {
+                        if (column1..=column2).contains(column)
+                            && !matches!(cell, Cell::EmptyCell { .. })
+                        {
+                            cells.push((*row, *column, data_validation));
+                        }
+                    }
+                }
+            }
+        }
+        let mut invalid_cells = self.evaluate_detached(|model| {
+            let mut invalid_cells = Vec::new();
+            for (row, column, data_validation) in cells {
+                if !model.is_valid_cell(sheet, row, column, data_validation)? {
+                    invalid_cells.push((row, column));
+                }
+            }
+            Ok::<_, String>(invalid_cells)
+        })?;
+        invalid_cells.sort_unstable();
+        Ok(invalid_cells)
+    }
+}
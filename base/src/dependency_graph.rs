@@ -0,0 +1,191 @@
+//! A persistent graph of the dependencies between cells.
+//!
+//! Every formula cell records the cells and ranges it reads while it is being
+//! evaluated (its _precedents_). The graph keeps the reverse index as well (the
+//! _dependents_ of a cell) so that after an edit we can find the formulas that
+//! need to be recomputed without walking the whole workbook.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{expressions::types::CellReferenceIndex, model::CellOrRange};
+
+/// (row1, column1, row2, column2), always with row1 <= row2 and column1 <= column2
+type Bounds = (i32, i32, i32, i32);
+
+#[derive(Default)]
+pub(crate) struct DependencyGraph {
+    /// The cells and ranges each formula cell reads
+    precedents: HashMap<CellReferenceIndex, Vec<CellOrRange>>,
+    /// For every cell, the formula cells that reference it directly
+    cell_dependents: HashMap<CellReferenceIndex, HashSet<CellReferenceIndex>>,
+    /// For every sheet and range in that sheet, the formula cells that reference it
+    range_dependents: HashMap<u32, HashMap<Bounds, HashSet<CellReferenceIndex>>>,
+    /// Formula cells that call a volatile function (NOW, RAND, OFFSET, INDIRECT, ...)
+    volatile: HashSet<CellReferenceIndex>,
+    /// Formula cells that read other workbooks
+    external: HashSet<CellReferenceIndex>,
+    /// While paused nothing is recorded, see [DependencyGraph::pause]
+    paused: bool,
+}
+
+fn contains(bounds: &Bounds, row: i32, column: i32) -> bool {
+    let (r1, c1, r2, c2) = *bounds;
+    row >= r1 && row <= r2 && column >= c1 && column <= c2
+}
+
+fn intersects(a: &Bounds, b: &Bounds) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+impl DependencyGraph {
+    /// Removes all the information in the graph
+    pub(crate) fn clear(&mut self) {
+        self.precedents.clear();
+        self.cell_dependents.clear();
+        self.range_dependents.clear();
+        self.volatile.clear();
+        self.external.clear();
+    }
+
+    /// Stops recording precedents, volatile and external cells. Used to evaluate formulas that do
+    /// not belong to any cell (data validation rules, for instance) in the context of a cell.
+    pub(crate) fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes recording after [DependencyGraph::pause]
+    pub(crate) fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Records that `cell` reads `precedent`
+    pub(crate) fn add_precedent(&mut self, cell: CellReferenceIndex, precedent: CellOrRange) {
+        if self.paused {
+            return;
+        }
+        let is_new = match precedent {
+            CellOrRange::Cell((sheet, row, column)) => self
+                .cell_dependents
+                .entry(CellReferenceIndex { sheet, row, column })
+                .or_default()
+                .insert(cell),
+            CellOrRange::Range((sheet, row1, column1, row2, column2)) => self
+                .range_dependents
+                .entry(sheet)
+                .or_default()
+                .entry((row1, column1, row2, column2))
+                .or_default()
+                .insert(cell),
+        };
+        // The reverse index doubles as a way of not storing the same precedent twice
+        if is_new {
+            self.precedents.entry(cell).or_default().push(precedent);
+        }
+    }
+
+    /// The cells and ranges read by `cell` the last time it was evaluated
+    pub(crate) fn precedents(&self, cell: &CellReferenceIndex) -> &[CellOrRange] {
+        match self.precedents.get(cell) {
+            Some(precedents) => precedents,
+            None => &[],
+        }
+    }
+
+    /// Forgets everything we know about `cell`, typically before re-evaluating it
+    pub(crate) fn remove_cell(&mut self, cell: &CellReferenceIndex) {
+        self.volatile.remove(cell);
+        self.external.remove(cell);
+        let precedents = match self.precedents.remove(cell) {
+            Some(p) => p,
+            None => return,
+        };
+        for precedent in precedents {
+            match precedent {
+                CellOrRange::Cell((sheet, row, column)) => {
+                    let key = CellReferenceIndex { sheet, row, column };
+                    if let Some(dependents) = self.cell_dependents.get_mut(&key) {
+                        dependents.remove(cell);
+                        if dependents.is_empty() {
+                            self.cell_dependents.remove(&key);
+                        }
+                    }
+                }
+                CellOrRange::Range((sheet, row1, column1, row2, column2)) => {
+                    if let Some(ranges) = self.range_dependents.get_mut(&sheet) {
+                        let key = (row1, column1, row2, column2);
+                        if let Some(dependents) = ranges.get_mut(&key) {
+                            dependents.remove(cell);
+                            if dependents.is_empty() {
+                                ranges.remove(&key);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flags `cell` as volatile: it will be recomputed on every recalculation
+    pub(crate) fn set_volatile(&mut self, cell: CellReferenceIndex) {
+        if self.paused {
+            return;
+        }
+        self.volatile.insert(cell);
+    }
+
+    /// All the cells flagged as volatile
+    pub(crate) fn volatile_cells(&self) -> Vec<CellReferenceIndex> {
+        self.volatile.iter().copied().collect()
+    }
+
+    /// Flags `cell` as reading other workbooks, see [crate::Model::refresh_external_links]
+    pub(crate) fn set_external(&mut self, cell: CellReferenceIndex) {
+        if self.paused {
+            return;
+        }
+        self.external.insert(cell);
+    }
+
+    /// All the cells that read other workbooks
+    pub(crate) fn external_cells(&self) -> Vec<CellReferenceIndex> {
+        self.external.iter().copied().collect()
+    }
+
+    /// Returns the formula cells that read any position of `changed`
+    pub(crate) fn dependents(&self, changed: &CellOrRange) -> Vec<CellReferenceIndex> {
+        let mut result = Vec::new();
+        match *changed {
+            CellOrRange::Cell((sheet, row, column)) => {
+                if let Some(dependents) =
+                    self.cell_dependents
+                        .get(&CellReferenceIndex { sheet, row, column })
+                {
+                    result.extend(dependents.iter().copied());
+                }
+                if let Some(ranges) = self.range_dependents.get(&sheet) {
+                    for (bounds, dependents) in ranges {
+                        if contains(bounds, row, column) {
+                            result.extend(dependents.iter().copied());
+                        }
+                    }
+                }
+            }
+            CellOrRange::Range((sheet, row1, column1, row2, column2)) => {
+                let area = (row1, column1, row2, column2);
+                for (position, dependents) in &self.cell_dependents {
+                    if position.sheet == sheet && contains(&area, position.row, position.column) {
+                        result.extend(dependents.iter().copied());
+                    }
+                }
+                if let Some(ranges) = self.range_dependents.get(&sheet) {
+                    for (bounds, dependents) in ranges {
+                        if intersects(bounds, &area) {
+                            result.extend(dependents.iter().copied());
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+}
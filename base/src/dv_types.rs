@@ -0,0 +1,89 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::cf_types::ValueOperator;
+
+/// What happens when a user types a value that breaks the rule
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub enum DataValidationErrorStyle {
+    /// The value is rejected
+    Stop,
+    /// The value is accepted after a warning
+    Warning,
+    /// The value is accepted, the user is just informed
+    Information,
+}
+
+/// The allowed values of a list rule
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+#[serde(tag = "type", content = "value")]
+pub enum DataValidationListSource {
+    /// A literal list of values, like "Yes,No"
+    Values(Vec<String>),
+    /// A formula that evaluates to a range or an array, like "$F$1:$F$5"
+    Formula(String),
+}
+
+/// Formulas are stored in English without the leading `=` and are relative to the top left
+/// cell of the first range of the rule, like in xlsx files.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum DataValidationRule {
+    List {
+        source: DataValidationListSource,
+        show_dropdown: bool,
+    },
+    WholeNumber {
+        operator: ValueOperator,
+        formula1: String,
+        // Only present for Between and NotBetween operators
+        formula2: Option<String>,
+    },
+    Decimal {
+        operator: ValueOperator,
+        formula1: String,
+        formula2: Option<String>,
+    },
+    Date {
+        operator: ValueOperator,
+        formula1: String,
+        formula2: Option<String>,
+    },
+    Time {
+        operator: ValueOperator,
+        formula1: String,
+        formula2: Option<String>,
+    },
+    TextLength {
+        operator: ValueOperator,
+        formula1: String,
+        formula2: Option<String>,
+    },
+    /// The value is valid if the formula evaluates to TRUE
+    Custom { formula: String },
+}
+
+/// A data validation rule attached to a space separated list of ranges (`sqref`), like "B2:B10 D2"
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct DataValidation {
+    pub range: String,
+    pub rule: DataValidationRule,
+    /// Whether empty cells are valid
+    pub allow_blank: bool,
+    pub error_style: DataValidationErrorStyle,
+    pub show_input_message: bool,
+    pub prompt_title: Option<String>,
+    pub prompt: Option<String>,
+    pub show_error_message: bool,
+    pub error_title: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The alert shown when a value breaks a data validation rule.
+/// This type is transient (never persisted).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct DataValidationAlert {
+    pub style: DataValidationErrorStyle,
+    pub title: Option<String>,
+    pub message: String,
+}
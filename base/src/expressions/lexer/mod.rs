@@ -51,7 +51,7 @@ use crate::expressions::token::{OpCompare, OpProduct, OpSum};
 use crate::language::Language;
 use crate::locale::Locale;
 
-use super::token::{Error, TokenType};
+use super::token::{Error, TableSpecifier, TokenType};
 use super::types::*;
 use super::utils;
 
@@ -194,6 +194,12 @@ impl<'a> Lexer<'a> {
         tk
     }
 
+    /// Returns true if there is whitespace right before the next token.
+    /// Between two references a space is the intersection operator: `B:B 3:3`
+    pub fn is_whitespace_next(&self) -> bool {
+        self.position < self.len && self.chars[self.position].is_whitespace()
+    }
+
     /// Advances position. This is used in conjunction with [`peek_token`](Self::peek_token)
     /// It is a noop if the has not been a previous peek_token
     pub fn advance_token(&mut self) {
@@ -220,7 +226,24 @@ impl<'a> Lexer<'a> {
                     '=' => TokenType::Compare(OpCompare::Equal),
                     '{' => TokenType::LeftBrace,
                     '}' => TokenType::RightBrace,
-                    '[' => TokenType::LeftBracket,
+                    '[' => {
+                        if let Some(token) = self.consume_external_reference() {
+                            token
+                        } else if self.peek_char() == Some('@') {
+                            // [@Column] in a formula inside a table
+                            self.position += 1;
+                            match self.consume_this_row_reference() {
+                                Ok(table_reference) => TokenType::StructuredReference {
+                                    table_name: "".to_string(),
+                                    specifier: Some(TableSpecifier::ThisRow),
+                                    table_reference,
+                                },
+                                Err(error) => TokenType::Illegal(error),
+                            }
+                        } else {
+                            TokenType::LeftBracket
+                        }
+                    }
                     ']' => TokenType::RightBracket,
                     ':' => TokenType::Colon,
                     ';' => TokenType::Semicolon,
@@ -345,6 +368,11 @@ impl<'a> Lexer<'a> {
                                 // reference
                                 self.position += 1;
                                 return self.consume_range(Some(name));
+                            } else if next_char_is_colon && !utils::name_needs_quoting(&name) {
+                                // 3D reference: Jan:Dec!B5
+                                if let Some(last_sheet) = self.consume_sheet_span_end() {
+                                    return self.consume_range_3d(name, last_sheet);
+                                }
                             } else if peek_char == Some('$') {
                                 self.position = position - 1;
                                 return self.consume_range(None);
@@ -427,7 +455,7 @@ impl<'a> Lexer<'a> {
                                         if pos > self.position {
                                             self.position = pos;
                                             if is_valid_r1c1_identifier(&name, self.peek_char()) {
-                                                return TokenType::Ident(name);
+                                                return self.consume_r1c1_identifier(name);
                                             } else {
                                                 self.position = self.len;
                                                 return TokenType::Illegal(
@@ -470,7 +498,7 @@ impl<'a> Lexer<'a> {
                                         self.position = pos;
 
                                         if is_valid_r1c1_identifier(&name, self.peek_char()) {
-                                            return TokenType::Ident(name);
+                                            return self.consume_r1c1_identifier(name);
                                         } else {
                                             return TokenType::Illegal(self.set_error(
                                                 &format!("Invalid identifier (R1C1): {name}"),
@@ -491,6 +519,19 @@ impl<'a> Lexer<'a> {
 
     // Private methods
 
+    // An identifier in R1C1 mode, it could be the name of a table in a structured reference
+    fn consume_r1c1_identifier(&mut self, name: String) -> TokenType {
+        if self.peek_char() == Some('[') {
+            if let Ok(r) = self.consume_structured_reference(&name) {
+                return r;
+            }
+            return TokenType::Illegal(
+                self.set_error("Invalid structured reference", self.position),
+            );
+        }
+        TokenType::Ident(name)
+    }
+
     fn set_error(&mut self, message: &str, position: usize) -> LexerError {
         self.position = self.len;
         LexerError {
@@ -787,9 +828,147 @@ impl<'a> Lexer<'a> {
         if self.next_token() != TokenType::Bang {
             return TokenType::Illegal(self.set_error("Expected '!'", self.position));
         }
+        // Sheet names cannot have brackets, so this is a reference to another workbook:
+        // '[Budget.xlsx]Summary'!C4 or 'C:\Reports\[Budget.xlsx]Summary'!C4
+        if let Some((path, rest)) = sheet_name.split_once('[') {
+            if let Some((file_name, sheet)) = rest.split_once(']') {
+                if file_name.is_empty() || sheet.is_empty() {
+                    return TokenType::Illegal(
+                        self.set_error("Invalid external reference", self.position),
+                    );
+                }
+                return self
+                    .consume_external_range(format!("{path}{file_name}"), sheet.to_string());
+            }
+        }
+        // Sheet names cannot have a colon, so this is a 3D reference: 'Jan 24:Dec 24'!B5
+        if let Some((first_sheet, last_sheet)) = sheet_name.split_once(':') {
+            return self.consume_range_3d(first_sheet.to_string(), last_sheet.to_string());
+        }
         self.consume_range(Some(sheet_name))
     }
 
+    // Consumes a reference to another workbook like '1]Summary!C4' in [1]Summary!C4, the '[' has already been read.
+    // Returns None, leaving the position untouched, if it is not an external reference.
+    fn consume_external_reference(&mut self) -> Option<TokenType> {
+        let start = self.position;
+        let mut position = start;
+        while position < self.len && !['[', ']', '!'].contains(&self.chars[position]) {
+            position += 1;
+        }
+        if position == start || position >= self.len || self.chars[position] != ']' {
+            return None;
+        }
+        let workbook: String = self.chars[start..position].iter().collect();
+        let sheet_start = position + 1;
+        position = sheet_start;
+        while position < self.len {
+            let next_char = self.chars[position];
+            if next_char.is_alphanumeric() || next_char == '_' || next_char == '.' {
+                position += 1;
+            } else {
+                break;
+            }
+        }
+        if position == sheet_start || position >= self.len || self.chars[position] != '!' {
+            return None;
+        }
+        let sheet: String = self.chars[sheet_start..position].iter().collect();
+        if utils::name_needs_quoting(&sheet) {
+            return None;
+        }
+        self.position = position + 1;
+        Some(self.consume_external_range(workbook, sheet))
+    }
+
+    fn consume_external_range(&mut self, workbook: String, sheet: String) -> TokenType {
+        match self.consume_range(None) {
+            TokenType::Reference {
+                row,
+                column,
+                absolute_row,
+                absolute_column,
+                ..
+            } => {
+                let reference = ParsedReference {
+                    column,
+                    row,
+                    absolute_column,
+                    absolute_row,
+                };
+                TokenType::ExternalReference {
+                    workbook,
+                    sheet,
+                    left: reference.clone(),
+                    right: reference,
+                }
+            }
+            TokenType::Range { left, right, .. } => TokenType::ExternalReference {
+                workbook,
+                sheet,
+                left,
+                right,
+            },
+            token => token,
+        }
+    }
+
+    // Consumes the second half of a sheet span like ':Dec!' in Jan:Dec!B5.
+    // Returns the name of the last sheet or None, leaving the position untouched, if it is not a sheet span.
+    fn consume_sheet_span_end(&mut self) -> Option<String> {
+        let start = self.position + 1;
+        let mut position = start;
+        while position < self.len {
+            let next_char = self.chars[position];
+            if next_char.is_alphanumeric() || next_char == '_' || next_char == '.' {
+                position += 1;
+            } else {
+                break;
+            }
+        }
+        if position == start || position >= self.len || self.chars[position] != '!' {
+            return None;
+        }
+        let last_sheet: String = self.chars[start..position].iter().collect();
+        if utils::name_needs_quoting(&last_sheet) {
+            return None;
+        }
+        self.position = position + 1;
+        Some(last_sheet)
+    }
+
+    fn consume_range_3d(&mut self, first_sheet: String, last_sheet: String) -> TokenType {
+        match self.consume_range(None) {
+            TokenType::Reference {
+                row,
+                column,
+                absolute_row,
+                absolute_column,
+                ..
+            } => {
+                let reference = ParsedReference {
+                    column,
+                    row,
+                    absolute_column,
+                    absolute_row,
+                };
+                TokenType::Range3D {
+                    first_sheet,
+                    last_sheet,
+                    left: reference.clone(),
+                    right: reference,
+                }
+            }
+            TokenType::Range { left, right, .. } => TokenType::Range3D {
+                first_sheet,
+                last_sheet,
+                left,
+                right,
+            },
+            token => token,
+        }
+    }
+
     fn consume_range(&mut self, sheet: Option<String>) -> TokenType {
         let m = if self.mode == LexerMode::A1 {
             self.consume_range_a1()
@@ -51,13 +51,19 @@ impl<'a> Lexer<'a> {
 
     fn consume_column_reference(&mut self) -> Result<String> {
         self.consume_whitespace();
-        let end_char = if self.peek_char() == Some('[') {
+        if self.peek_char() == Some('[') {
             self.position += 1;
-            ']'
+            let column_name = self.consume_column_name(']')?;
+            // skip the closing bracket
+            self.position += 1;
+            Ok(column_name)
         } else {
-            ')'
-        };
+            self.consume_column_name(')')
+        }
+    }
 
+    // Reads a column name up to `end_char` (not included), unescaping the special characters
+    fn consume_column_name(&mut self, end_char: char) -> Result<String> {
         let mut position = self.position;
         while position < self.len {
             let next_char = self.chars[position];
@@ -78,9 +84,6 @@ impl<'a> Lexer<'a> {
             }
         }
         let chars: String = self.chars[self.position..position].iter().collect();
-        if end_char == ']' {
-            position += 1;
-        }
         self.position = position;
         Ok(chars
             .replace("'[", "[")
@@ -90,6 +93,37 @@ impl<'a> Lexer<'a> {
             .replace("''", "'"))
     }
 
+    // Consumes the rest of the "this row" shorthand, the '@' has already been read:
+    //  * MyTable[@] => MyTable[#This Row]
+    //  * MyTable[@MyColumn] => MyTable[[#This Row], [MyColumn]]
+    //  * MyTable[@[My Column]] => MyTable[[#This Row], [My Column]]
+    //  * MyTable[@[Jan]:[Dec]] => MyTable[[#This Row], [Jan]:[Dec]]
+    pub(super) fn consume_this_row_reference(&mut self) -> Result<Option<TableReference>> {
+        match self.peek_char() {
+            Some(']') => {
+                self.position += 1;
+                Ok(None)
+            }
+            Some('[') => {
+                let left = self.consume_column_reference()?;
+                let table_reference = if self.peek_char() == Some(':') {
+                    self.position += 1;
+                    let right = self.consume_column_reference()?;
+                    TableReference::RangeReference((left, right))
+                } else {
+                    TableReference::ColumnReference(left)
+                };
+                self.expect(TokenType::RightBracket)?;
+                Ok(Some(table_reference))
+            }
+            _ => {
+                let column_name = self.consume_column_name(']')?;
+                self.expect(TokenType::RightBracket)?;
+                Ok(Some(TableReference::ColumnReference(column_name)))
+            }
+        }
+    }
+
     /// Possibilities:
     ///  1. MyTable[#Totals] or MyTable[#This Row]
     ///  2. MyTable[MyColumn]
@@ -98,9 +132,10 @@ impl<'a> Lexer<'a> {
     ///  5. MyTable[[#Totals], [MyColumn]]
     ///  6. MyTable[[#This Row], [Jan]:[Dec]]
     ///  7. MyTable[]
+    ///  8. MyTable[@MyColumn] or MyTable[@[Jan]:[Dec]], a shorthand for #This Row
+    ///  9. MyTable[[#Headers],[#Data],[MyColumn]] or MyTable[[#Data],[#Totals]]
     ///
-    /// Multiple specifiers are not supported yet:
-    ///  1. MyTable[[#Data], [#Totals], [MyColumn]]
+    /// Inside a table the name of the table can be omitted: [@MyColumn]. The table name is empty then.
     ///
     /// In particular note that names of columns are escaped only when they are in the first argument
     /// We use '[' and ']'
@@ -113,15 +148,24 @@ impl<'a> Lexer<'a> {
     // * MyTable[[#Totals]] is translated into MyTable[#Totals]
     // * Excel shows '@' instead of '#This Row':
     //     MyTable[[#This Row], [Jan]:[Dec]] => MyTable[@[Jan]:[Dec]]
-    //   But this is only a UI thing that we will ignore for now.
+    //   Both forms are accepted.
     pub(crate) fn consume_structured_reference(&mut self, table_name: &str) -> Result<TokenType> {
-        self.expect(TokenType::LeftBracket)?;
+        self.expect_char('[')?;
         let peek_char = self.peek_char();
         if peek_char == Some(']') {
             // This is just a reference to the full table
             self.expect(TokenType::RightBracket)?;
             return Ok(TokenType::Ident(table_name.to_string()));
         }
+        if peek_char == Some('@') {
+            self.position += 1;
+            let table_reference = self.consume_this_row_reference()?;
+            return Ok(TokenType::StructuredReference {
+                table_name: table_name.to_string(),
+                specifier: Some(TableSpecifier::ThisRow),
+                table_reference,
+            });
+        }
         if peek_char == Some('#') {
             // Expecting MyTable[#Totals]
             if let Some(specifier) = self.consume_table_specifier()? {
@@ -147,13 +191,37 @@ impl<'a> Lexer<'a> {
             });
         }
         self.expect(TokenType::LeftBracket)?;
-        let specifier = self.consume_table_specifier()?;
+        let mut specifier = self.consume_table_specifier()?;
         if specifier.is_some() {
-            let peek_token = self.peek_token();
+            let mut peek_token = self.peek_token();
             if peek_token == TokenType::Comma {
                 self.advance_token();
                 self.expect(TokenType::LeftBracket)?;
-            } else if peek_token == TokenType::RightBracket {
+                // A second specifier: [#Headers],[#Data] or [#Data],[#Totals]
+                if self.peek_char() == Some('#') {
+                    specifier = match (specifier, self.consume_table_specifier()?) {
+                        (Some(TableSpecifier::Headers), Some(TableSpecifier::Data)) => {
+                            Some(TableSpecifier::HeadersAndData)
+                        }
+                        (Some(TableSpecifier::Data), Some(TableSpecifier::Totals)) => {
+                            Some(TableSpecifier::DataAndTotals)
+                        }
+                        _ => {
+                            return Err(LexerError {
+                                position: self.position,
+                                message: "Invalid combination of table specifiers".to_string(),
+                            })
+                        }
+                    };
+                    peek_token = self.peek_token();
+                    if peek_token == TokenType::Comma {
+                        self.advance_token();
+                        self.expect(TokenType::LeftBracket)?;
+                    }
+                }
+            }
+            if peek_token == TokenType::RightBracket {
+                self.advance_token();
                 return Ok(TokenType::StructuredReference {
                     table_name: table_name.to_string(),
                     specifier,
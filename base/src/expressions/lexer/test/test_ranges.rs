@@ -518,3 +518,112 @@ fn range_operator() {
     assert!(matches!(lx.next_token(), RightParenthesis));
     assert_eq!(lx.next_token(), EOF);
 }
+
+#[test]
+fn sheet_span() {
+    let mut lx = new_lexer("Jan:Dec!B5");
+    let b5 = ParsedReference {
+        column: 2,
+        row: 5,
+        absolute_column: false,
+        absolute_row: false,
+    };
+    assert_eq!(
+        lx.next_token(),
+        Range3D {
+            first_sheet: "Jan".to_string(),
+            last_sheet: "Dec".to_string(),
+            left: b5.clone(),
+            right: b5,
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("'Jan 24:Dec''s'!$A$1:C3");
+    assert_eq!(
+        lx.next_token(),
+        Range3D {
+            first_sheet: "Jan 24".to_string(),
+            last_sheet: "Dec's".to_string(),
+            left: ParsedReference {
+                column: 1,
+                row: 1,
+                absolute_column: true,
+                absolute_row: true,
+            },
+            right: ParsedReference {
+                column: 3,
+                row: 3,
+                absolute_column: false,
+                absolute_row: false,
+            }
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("Sheet1:Sheet3!R[1]C2:R3C[4]");
+    lx.set_lexer_mode(LexerMode::R1C1);
+    assert!(matches!(lx.next_token(), Range3D { .. }));
+    assert_eq!(lx.next_token(), EOF);
+}
+
+#[test]
+fn sheet_span_lookalikes() {
+    // A range followed by a reference to another sheet is not a sheet span
+    let mut lx = new_lexer("A1:Sheet2!B3");
+    assert!(matches!(lx.next_token(), Reference { .. }));
+    assert!(matches!(lx.next_token(), Colon));
+    assert!(matches!(lx.next_token(), Reference { .. }));
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("B:D");
+    assert!(matches!(lx.next_token(), Range { .. }));
+    assert_eq!(lx.next_token(), EOF);
+}
+
+#[test]
+fn external_reference() {
+    let mut lx = new_lexer("[1]Summary!C4");
+    let c4 = ParsedReference {
+        column: 3,
+        row: 4,
+        absolute_column: false,
+        absolute_row: false,
+    };
+    assert_eq!(
+        lx.next_token(),
+        ExternalReference {
+            workbook: "1".to_string(),
+            sheet: "Summary".to_string(),
+            left: c4.clone(),
+            right: c4,
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("'C:\\Reports\\[Budget 2024.xlsx]Q1 ''24'!$A$1:C3");
+    assert_eq!(
+        lx.next_token(),
+        ExternalReference {
+            workbook: "C:\\Reports\\Budget 2024.xlsx".to_string(),
+            sheet: "Q1 '24".to_string(),
+            left: ParsedReference {
+                column: 1,
+                row: 1,
+                absolute_column: true,
+                absolute_row: true,
+            },
+            right: ParsedReference {
+                column: 3,
+                row: 3,
+                absolute_column: false,
+                absolute_row: false,
+            }
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    // Not an external reference
+    let mut lx = new_lexer("[Budget.xlsx]");
+    assert_eq!(lx.next_token(), LeftBracket);
+}
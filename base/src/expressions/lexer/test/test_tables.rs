@@ -71,3 +71,82 @@ fn table_totals_no_reference() {
     );
     assert_eq!(lx.next_token(), EOF);
 }
+
+#[test]
+fn table_this_row_shorthand() {
+    let mut lx = new_lexer("tbInfo[@Jan]");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "tbInfo".to_string(),
+            specifier: Some(TableSpecifier::ThisRow),
+            table_reference: Some(TableReference::ColumnReference("Jan".to_string()))
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("tbInfo[@[First Month]:[Dec]]+1");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "tbInfo".to_string(),
+            specifier: Some(TableSpecifier::ThisRow),
+            table_reference: Some(TableReference::RangeReference((
+                "First Month".to_string(),
+                "Dec".to_string()
+            )))
+        }
+    );
+    assert!(matches!(lx.next_token(), Addition(_)));
+
+    // Inside a table the name of the table can be omitted
+    let mut lx = new_lexer("[@[First Month]]*2");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "".to_string(),
+            specifier: Some(TableSpecifier::ThisRow),
+            table_reference: Some(TableReference::ColumnReference("First Month".to_string()))
+        }
+    );
+    assert!(matches!(lx.next_token(), Product(_)));
+
+    let mut lx = new_lexer("tbInfo[@]");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "tbInfo".to_string(),
+            specifier: Some(TableSpecifier::ThisRow),
+            table_reference: None
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+}
+
+#[test]
+fn table_two_specifiers() {
+    let mut lx = new_lexer("tbInfo[[#Headers],[#Data],[Jan]]");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "tbInfo".to_string(),
+            specifier: Some(TableSpecifier::HeadersAndData),
+            table_reference: Some(TableReference::ColumnReference("Jan".to_string()))
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("tbInfo[[#Data],[#Totals]]");
+    assert_eq!(
+        lx.next_token(),
+        StructuredReference {
+            table_name: "tbInfo".to_string(),
+            specifier: Some(TableSpecifier::DataAndTotals),
+            table_reference: None
+        }
+    );
+    assert_eq!(lx.next_token(), EOF);
+
+    let mut lx = new_lexer("tbInfo[[#Totals],[#Headers]]");
+    assert!(matches!(lx.next_token(), Illegal(_)));
+}
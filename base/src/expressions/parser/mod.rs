@@ -11,10 +11,11 @@ concat  => term ('&' term)*
 term    => factor (opFactor factor)*
 factor  => prod (opProd prod)*
 prod    => power ('^' power)*
-power   => (unaryOp)* range '%'*
+power   => (unaryOp)* isect '%'*
+isect   => range (' ' range)*
 range   => implicit (':' primary)?
 implicit=> '@' primary | primary '#' | primary
-primary => '(' expr ')'
+primary => '(' expr (',' expr)* ')'
         => number
         => function '(' f_args ')'
         => LAMBDA '(' f_args ')' '(' f_args ')'
@@ -44,6 +45,7 @@ use super::lexer;
 use super::token;
 use super::token::OpUnary;
 use super::token::TableReference;
+use super::token::TableSpecifier;
 use super::token::TokenType;
 use super::types::*;
 use super::utils::number_to_column;
@@ -80,14 +82,187 @@ pub(crate) fn parse_range(formula: &str) -> Result<(i32, i32, i32, i32), String>
 }
 
 fn get_table_column_by_name(table_column_name: &str, table: &Table) -> Option<i32> {
+    let table_column_name = table_column_name.to_lowercase();
     for (index, table_column) in table.columns.iter().enumerate() {
-        if table_column.name == table_column_name {
+        if table_column.name.to_lowercase() == table_column_name {
             return Some(index as i32);
         }
     }
     None
 }
 
+/// Returns the area (column1, row1, column2, row2) of the table a structured reference refers to.
+/// `row` is the row of the formula, used by `[#This Row]`.
+pub(crate) fn get_structured_reference_area(
+    table: &Table,
+    specifier: &Option<TableSpecifier>,
+    table_reference: &Option<TableReference>,
+    row: i32,
+) -> Result<(i32, i32, i32, i32), (token::Error, String)> {
+    let (column_start, mut row_start, column_end, mut row_end) =
+        parse_range(&table.reference).map_err(|message| (token::Error::REF, message))?;
+    let totals_row_count = table.totals_row_count as i32;
+    let header_row_count = table.header_row_count as i32;
+    row_end -= totals_row_count;
+
+    match specifier {
+        Some(TableSpecifier::ThisRow) => {
+            if row < row_start + header_row_count || row > row_end {
+                return Err((
+                    token::Error::VALUE,
+                    format!("Row {row} is not in the data of table '{}'", table.name),
+                ));
+            }
+            row_start = row;
+            row_end = row;
+        }
+        Some(TableSpecifier::Totals) => {
+            // Table1[#Totals] is #REF! if Table1 does not have totals
+            if totals_row_count == 0 {
+                return Err((
+                    token::Error::REF,
+                    format!("Table '{}' does not have a totals row", table.name),
+                ));
+            }
+            row_start = row_end + 1;
+            row_end = row_start;
+        }
+        Some(TableSpecifier::Headers) => {
+            // Table1[#Headers] is #REF! if the header row is hidden
+            if header_row_count == 0 {
+                return Err((
+                    token::Error::REF,
+                    format!("Table '{}' does not have a header row", table.name),
+                ));
+            }
+            row_end = row_start;
+        }
+        Some(TableSpecifier::Data) | None => {
+            row_start += header_row_count;
+        }
+        Some(TableSpecifier::All) => {
+            row_end += totals_row_count;
+        }
+        Some(TableSpecifier::HeadersAndData) => {}
+        Some(TableSpecifier::DataAndTotals) => {
+            row_start += header_row_count;
+            row_end += totals_row_count;
+        }
+    }
+
+    let column_index = |name: &str| match get_table_column_by_name(name, table) {
+        Some(index) => Ok(index + column_start),
+        None => Err((
+            token::Error::REF,
+            format!("Column '{name}' not found in table '{}'", table.name),
+        )),
+    };
+    let (column1, column2) = match table_reference {
+        None => (column_start, column_end),
+        Some(TableReference::ColumnReference(name)) => {
+            let column = column_index(name)?;
+            (column, column)
+        }
+        Some(TableReference::RangeReference((left, right))) => {
+            let left = column_index(left)?;
+            let right = column_index(right)?;
+            (left.min(right), left.max(right))
+        }
+    };
+    Ok((column1, row_start, column2, row_end))
+}
+
+/// Returns the operands of a union, flattening the nested ones: (A1,B1,C1) => [A1, B1, C1]
+pub(crate) fn get_union_operands(node: &Node) -> Vec<&Node> {
+    match node {
+        Node::OpUnionKind { left, right } => {
+            let mut operands = get_union_operands(left);
+            operands.push(right);
+            operands
+        }
+        _ => vec![node],
+    }
+}
+
+/// Returns one reference per sheet in the span of a 3D reference: Jan:Mar!B5 => [Jan!B5, Feb!B5, Mar!B5]
+/// Any other node is returned unchanged.
+pub(crate) fn get_sheet_span_operands(node: &Node) -> Vec<Node> {
+    match node {
+        Node::Range3DKind {
+            sheet_indices,
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+            ..
+        } => {
+            let (first, last) = match sheet_indices {
+                Some((first, last)) => (*first.min(last), *first.max(last)),
+                None => return vec![Node::ErrorKind(token::Error::REF)],
+            };
+            (first..=last)
+                .map(|sheet_index| {
+                    if row1 == row2
+                        && column1 == column2
+                        && absolute_row1 == absolute_row2
+                        && absolute_column1 == absolute_column2
+                    {
+                        Node::ReferenceKind {
+                            sheet_name: None,
+                            sheet_index,
+                            absolute_row: *absolute_row1,
+                            absolute_column: *absolute_column1,
+                            row: *row1,
+                            column: *column1,
+                        }
+                    } else {
+                        Node::RangeKind {
+                            sheet_name: None,
+                            sheet_index,
+                            absolute_row1: *absolute_row1,
+                            absolute_column1: *absolute_column1,
+                            row1: *row1,
+                            column1: *column1,
+                            absolute_row2: *absolute_row2,
+                            absolute_column2: *absolute_column2,
+                            row2: *row2,
+                            column2: *column2,
+                        }
+                    }
+                })
+                .collect()
+        }
+        _ => vec![node.clone()],
+    }
+}
+
+/// Returns true if the node can be an operand of the intersection operator without parentheses
+pub(crate) fn is_intersection_operand(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::ReferenceKind { .. }
+            | Node::RangeKind { .. }
+            | Node::Range3DKind { .. }
+            | Node::ExternalReferenceKind { .. }
+            | Node::WrongReferenceKind { .. }
+            | Node::WrongRangeKind { .. }
+            | Node::OpRangeKind { .. }
+            | Node::OpUnionKind { .. }
+            | Node::FunctionKind { .. }
+            | Node::NamedFunctionKind { .. }
+            | Node::DefinedNameKind(_)
+            | Node::TableNameKind(_)
+            | Node::StructuredReferenceKind { .. }
+            | Node::NamedVariableKind { .. }
+            | Node::ImplicitIntersection { .. }
+            | Node::SpillRangeOperator { .. }
+    )
+}
+
 // DefinedNameS is a tuple with the name of the defined name, the index of the sheet and the formula
 pub type DefinedNameS = (String, Option<u32>, String);
 
@@ -165,6 +340,33 @@ pub enum Node {
         row2: i32,
         column2: i32,
     },
+    // Jan:Dec!B5 or Jan:Dec!A1:C3. The sheet indices are None if any of the sheets does not exist
+    Range3DKind {
+        first_sheet_name: String,
+        last_sheet_name: String,
+        sheet_indices: Option<(u32, u32)>,
+        absolute_row1: bool,
+        absolute_column1: bool,
+        row1: i32,
+        column1: i32,
+        absolute_row2: bool,
+        absolute_column2: bool,
+        row2: i32,
+        column2: i32,
+    },
+    // [1]Summary!C4 or '[Budget.xlsx]Summary'!A1:C3. The workbook is kept as written.
+    ExternalReferenceKind {
+        workbook: String,
+        sheet_name: String,
+        absolute_row1: bool,
+        absolute_column1: bool,
+        row1: i32,
+        column1: i32,
+        absolute_row2: bool,
+        absolute_column2: bool,
+        row2: i32,
+        column2: i32,
+    },
     WrongReferenceKind {
         sheet_name: Option<String>,
         absolute_row: bool,
@@ -187,6 +389,16 @@ pub enum Node {
         left: Box<Node>,
         right: Box<Node>,
     },
+    // B:B 3:3
+    OpIntersectionKind {
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    // (A1:A3,C1:C3)
+    OpUnionKind {
+        left: Box<Node>,
+        right: Box<Node>,
+    },
     OpConcatenateKind {
         left: Box<Node>,
         right: Box<Node>,
@@ -227,6 +439,13 @@ pub enum Node {
     ArrayKind(Vec<Vec<ArrayNode>>),
     DefinedNameKind(DefinedNameS),
     TableNameKind(String),
+    // Table1[Amount], Table1[[#Totals],[Amount]] or Table1[@Amount]. The table and its columns
+    // are resolved when the formula is evaluated
+    StructuredReferenceKind {
+        table_name: String,
+        specifier: Option<TableSpecifier>,
+        table_reference: Option<TableReference>,
+    },
     NamedVariableKind {
         name: String,
         id: Option<u32>,
@@ -337,6 +556,10 @@ impl<'a> Parser<'a> {
         self.defined_names = defined_names;
     }
 
+    pub fn set_tables(&mut self, tables: HashMap<String, Table>) {
+        self.tables = tables;
+    }
+
     pub fn parse(&mut self, formula: &str, context: &CellReferenceRC) -> Node {
         self.lexer.set_formula(formula);
         self.context = context.clone();
@@ -412,6 +635,36 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Sorts the corners of a range and, in A1 mode, makes the non absolute coordinates relative to the context
+    fn get_relative_range(
+        &self,
+        left: ParsedReference,
+        right: ParsedReference,
+    ) -> (ParsedReference, ParsedReference) {
+        let (mut left, mut right) = (left, right);
+        if self.lexer.is_a1_mode() {
+            let context = &self.context;
+            if left.row > right.row {
+                (left.row, right.row) = (right.row, left.row);
+                (left.absolute_row, right.absolute_row) = (right.absolute_row, left.absolute_row);
+            }
+            if left.column > right.column {
+                (left.column, right.column) = (right.column, left.column);
+                (left.absolute_column, right.absolute_column) =
+                    (right.absolute_column, left.absolute_column);
+            }
+            for reference in [&mut left, &mut right] {
+                if !reference.absolute_row {
+                    reference.row -= context.row;
+                }
+                if !reference.absolute_column {
+                    reference.column -= context.column;
+                }
+            }
+        }
+        (left, right)
+    }
+
     fn get_sheet_index_by_name(&self, name: &str) -> Option<u32> {
         let worksheets = &self.worksheets;
         for (i, sheet) in worksheets.iter().enumerate() {
@@ -560,7 +813,7 @@ impl<'a> Parser<'a> {
             next_token = self.lexer.peek_token();
         }
 
-        let mut t = self.parse_range();
+        let mut t = self.parse_intersection();
         if let Node::ParseErrorKind { .. } = t {
             return t;
         }
@@ -582,6 +835,36 @@ impl<'a> Parser<'a> {
         t
     }
 
+    fn parse_intersection(&mut self) -> Node {
+        let mut t = self.parse_range();
+        if let Node::ParseErrorKind { .. } = t {
+            return t;
+        }
+        // A space followed by something that can be a reference is the intersection operator
+        while self.lexer.is_whitespace_next()
+            && matches!(
+                self.lexer.peek_token(),
+                TokenType::Reference { .. }
+                    | TokenType::Range { .. }
+                    | TokenType::Range3D { .. }
+                    | TokenType::ExternalReference { .. }
+                    | TokenType::StructuredReference { .. }
+                    | TokenType::Ident(_)
+                    | TokenType::LeftParenthesis
+            )
+        {
+            let p = self.parse_range();
+            if let Node::ParseErrorKind { .. } = p {
+                return p;
+            }
+            t = Node::OpIntersectionKind {
+                left: Box::new(t),
+                right: Box::new(p),
+            };
+        }
+        t
+    }
+
     fn parse_range(&mut self) -> Node {
         let t = self.parse_implicit();
         if let Node::ParseErrorKind { .. } = t {
@@ -708,10 +991,23 @@ impl<'a> Parser<'a> {
         let next_token = self.lexer.next_token();
         match next_token {
             TokenType::LeftParenthesis => {
-                let t = self.parse_expr();
+                let mut t = self.parse_expr();
                 if let Node::ParseErrorKind { .. } = t {
                     return t;
                 }
+                // Inside parentheses the argument separator is the union operator
+                let separator = self.get_argument_separator_token();
+                while self.lexer.peek_token() == separator {
+                    self.lexer.advance_token();
+                    let p = self.parse_expr();
+                    if let Node::ParseErrorKind { .. } = p {
+                        return p;
+                    }
+                    t = Node::OpUnionKind {
+                        left: Box::new(t),
+                        right: Box::new(p),
+                    };
+                }
 
                 if let Err(err) = self.lexer.expect(TokenType::RightParenthesis) {
                     return Node::ParseErrorKind {
@@ -875,6 +1171,54 @@ impl<'a> Parser<'a> {
                     },
                 }
             }
+            TokenType::Range3D {
+                first_sheet,
+                last_sheet,
+                left,
+                right,
+            } => {
+                let sheet_indices = match (
+                    self.get_sheet_index_by_name(&first_sheet),
+                    self.get_sheet_index_by_name(&last_sheet),
+                ) {
+                    (Some(first), Some(last)) => Some((first, last)),
+                    _ => None,
+                };
+                let (left, right) = self.get_relative_range(left, right);
+                Node::Range3DKind {
+                    first_sheet_name: first_sheet,
+                    last_sheet_name: last_sheet,
+                    sheet_indices,
+                    absolute_row1: left.absolute_row,
+                    absolute_column1: left.absolute_column,
+                    row1: left.row,
+                    column1: left.column,
+                    absolute_row2: right.absolute_row,
+                    absolute_column2: right.absolute_column,
+                    row2: right.row,
+                    column2: right.column,
+                }
+            }
+            TokenType::ExternalReference {
+                workbook,
+                sheet,
+                left,
+                right,
+            } => {
+                let (left, right) = self.get_relative_range(left, right);
+                Node::ExternalReferenceKind {
+                    workbook,
+                    sheet_name: sheet,
+                    absolute_row1: left.absolute_row,
+                    absolute_column1: left.absolute_column,
+                    row1: left.row,
+                    column1: left.column,
+                    absolute_row2: right.absolute_row,
+                    absolute_column2: right.absolute_column,
+                    row2: right.row,
+                    column2: right.column,
+                }
+            }
             TokenType::Ident(name) => {
                 let next_token = self.lexer.peek_token();
                 // If this identifier is the last token before the cursor (its
@@ -1133,23 +1477,27 @@ impl<'a> Parser<'a> {
                 specifier,
                 table_reference,
             } => {
-                // We will try to convert to a normal reference
-                // table_name[column_name] => cell1:cell2
-                // table_name[[#This Row], [column_name]:[column_name]] => cell1:cell2
+                // The table and the columns must exist when the formula is entered, but the
+                // reference is resolved when the formula is evaluated.
                 let context = &self.context;
-                let context_sheet_index = match self.get_sheet_index_by_name(&context.sheet) {
-                    Some(i) => i,
-                    None => {
-                        return Node::ParseErrorKind {
-                            formula: self.lexer.get_formula(),
-                            expecting: vec![ExpectedTokens::Other],
-                            position: 0,
-                            message: format!("sheet not found: {}", context.sheet),
-                        };
-                    }
+                // [@Column] without a table name refers to the table the formula is in
+                let table = if table_name.is_empty() {
+                    self.tables.values().find(|table| {
+                        table.sheet_name == context.sheet
+                            && parse_range(&table.reference).is_ok_and(
+                                |(column_start, row_start, column_end, row_end)| {
+                                    (row_start..=row_end).contains(&context.row)
+                                        && (column_start..=column_end).contains(&context.column)
+                                },
+                            )
+                    })
+                } else {
+                    let name_lower = table_name.to_lowercase();
+                    self.tables
+                        .values()
+                        .find(|table| table.name.to_lowercase() == name_lower)
                 };
-                // table-name => table
-                let table = match self.tables.get(&table_name) {
+                let table = match table {
                     Some(t) => t,
                     None => {
                         let message = format!(
@@ -1167,152 +1515,35 @@ impl<'a> Parser<'a> {
                         };
                     }
                 };
-                let table_sheet_index = match self.get_sheet_index_by_name(&table.sheet_name) {
-                    Some(i) => i,
-                    None => {
-                        return Node::ParseErrorKind {
-                            formula: self.lexer.get_formula(),
-                            expecting: vec![ExpectedTokens::Other],
-                            position: 0,
-                            message: format!("table sheet not found: {}", table.sheet_name),
-                        };
-                    }
-                };
-
-                let sheet_name = if table_sheet_index == context_sheet_index {
-                    None
-                } else {
-                    Some(table.sheet_name.clone())
+                // Names of tables and columns are case insensitive, we keep the ones in the table
+                let column_name = |name: &String| match get_table_column_by_name(name, table) {
+                    Some(index) => Ok(table.columns[index as usize].name.clone()),
+                    None => Err(Node::ParseErrorKind {
+                        formula: self.lexer.get_formula(),
+                        expecting: vec![ExpectedTokens::Other],
+                        position: self.lexer.get_position() as usize,
+                        message: format!("Expecting column: {name} in table {table_name}"),
+                    }),
                 };
-
-                // context must be with tables.reference
-                #[allow(clippy::expect_used)]
-                let (column_start, mut row_start, column_end, mut row_end) =
-                    parse_range(&table.reference).expect("Failed parsing range");
-
-                let totals_row_count = table.totals_row_count as i32;
-                let header_row_count = table.header_row_count as i32;
-                row_end -= totals_row_count;
-
-                match specifier {
-                    Some(token::TableSpecifier::ThisRow) => {
-                        row_start = context.row;
-                        row_end = context.row;
-                    }
-                    Some(token::TableSpecifier::Totals) => {
-                        if totals_row_count != 0 {
-                            row_start = row_end + 1;
-                            row_end = row_start;
-                        } else {
-                            // Table1[#Totals] is #REF! if Table1 does not have totals
-                            return Node::ErrorKind(token::Error::REF);
-                        }
-                    }
-                    Some(token::TableSpecifier::Headers) => {
-                        row_end = row_start;
-                    }
-                    Some(token::TableSpecifier::Data) => {
-                        row_start += header_row_count;
-                    }
-                    Some(token::TableSpecifier::All) => {
-                        if totals_row_count != 0 {
-                            row_end += 1;
-                        }
-                    }
-                    None => {
-                        // skip the headers
-                        row_start += header_row_count;
-                    }
-                }
-                match table_reference {
-                    None => Node::RangeKind {
-                        sheet_name,
-                        sheet_index: table_sheet_index,
-                        absolute_row1: true,
-                        absolute_column1: true,
-                        row1: row_start,
-                        column1: column_start,
-                        absolute_row2: true,
-                        absolute_column2: true,
-                        row2: row_end,
-                        column2: column_end,
+                let table_reference = match &table_reference {
+                    None => None,
+                    Some(TableReference::ColumnReference(name)) => match column_name(name) {
+                        Ok(name) => Some(TableReference::ColumnReference(name)),
+                        Err(error) => return error,
                     },
-                    Some(TableReference::ColumnReference(s)) => {
-                        let column_index = match get_table_column_by_name(&s, table) {
-                            Some(s) => s + column_start,
-                            None => {
-                                return Node::ParseErrorKind {
-                                    formula: self.lexer.get_formula(),
-                                    expecting: vec![ExpectedTokens::Other],
-                                    position: self.lexer.get_position() as usize,
-                                    message: format!("Expecting column: {s} in table {table_name}"),
-                                };
-                            }
-                        };
-                        if row_start == row_end {
-                            return Node::ReferenceKind {
-                                sheet_name,
-                                sheet_index: table_sheet_index,
-                                absolute_row: true,
-                                absolute_column: true,
-                                row: row_start,
-                                column: column_index,
-                            };
-                        }
-                        Node::RangeKind {
-                            sheet_name,
-                            sheet_index: table_sheet_index,
-                            absolute_row1: true,
-                            absolute_column1: true,
-                            row1: row_start,
-                            column1: column_index,
-                            absolute_row2: true,
-                            absolute_column2: true,
-                            row2: row_end,
-                            column2: column_index,
-                        }
-                    }
                     Some(TableReference::RangeReference((left, right))) => {
-                        let left_column_index = match get_table_column_by_name(&left, table) {
-                            Some(f) => f + column_start,
-                            None => {
-                                return Node::ParseErrorKind {
-                                    formula: self.lexer.get_formula(),
-                                    expecting: vec![ExpectedTokens::Other],
-                                    position: self.lexer.get_position() as usize,
-                                    message: format!(
-                                        "Expecting column: {left} in table {table_name}"
-                                    ),
-                                };
+                        match (column_name(left), column_name(right)) {
+                            (Ok(left), Ok(right)) => {
+                                Some(TableReference::RangeReference((left, right)))
                             }
-                        };
-
-                        let right_column_index = match get_table_column_by_name(&right, table) {
-                            Some(f) => f + column_start,
-                            None => {
-                                return Node::ParseErrorKind {
-                                    formula: self.lexer.get_formula(),
-                                    expecting: vec![ExpectedTokens::Other],
-                                    position: self.lexer.get_position() as usize,
-                                    message: format!(
-                                        "Expecting column: {right} in table {table_name}"
-                                    ),
-                                };
-                            }
-                        };
-                        Node::RangeKind {
-                            sheet_name,
-                            sheet_index: table_sheet_index,
-                            absolute_row1: true,
-                            absolute_column1: true,
-                            row1: row_start,
-                            column1: left_column_index,
-                            absolute_row2: true,
-                            absolute_column2: true,
-                            row2: row_end,
-                            column2: right_column_index,
+                            (Err(error), _) | (_, Err(error)) => return error,
                         }
                     }
+                };
+                Node::StructuredReferenceKind {
+                    table_name: table.name.clone(),
+                    specifier,
+                    table_reference,
                 }
             }
         }
@@ -1,5 +1,8 @@
 use super::{
-    stringify::{stringify_reference, DisplaceData},
+    get_union_operands, is_intersection_operand,
+    stringify::{
+        stringify_reference, stringify_structured_reference, to_localized_string, DisplaceData,
+    },
     ArrayNode, Node, Reference,
 };
 use crate::{
@@ -137,6 +140,7 @@ fn to_string_moved(
             let new_column;
             let mut ref_sheet_name = sheet_name;
             let source_sheet_name = &Some(move_context.source_sheet_name.to_string());
+            let target_sheet_name = &Some(move_context.target_sheet_name.to_string());
 
             if ref_is_in_area(
                 *sheet_index,
@@ -147,6 +151,11 @@ fn to_string_moved(
                 // if the reference is in the area we are moving we want to displace the reference
                 new_row = row + move_context.row_delta;
                 new_column = column + move_context.column_delta;
+                if move_context.target_sheet_name != move_context.source_sheet_name
+                    && sheet_name.is_some()
+                {
+                    ref_sheet_name = target_sheet_name;
+                }
             } else {
                 // If the reference is not in the area we are moving the reference remains unchanged
                 new_row = *row;
@@ -223,6 +232,7 @@ fn to_string_moved(
             let new_column2;
             let mut ref_sheet_name = sheet_name;
             let source_sheet_name = &Some(move_context.source_sheet_name.to_string());
+            let target_sheet_name = &Some(move_context.target_sheet_name.to_string());
             if ref_is_in_area(
                 *sheet_index,
                 reference_row1,
@@ -239,6 +249,11 @@ fn to_string_moved(
                 new_column1 = column1 + move_context.column_delta;
                 new_row2 = row2 + move_context.row_delta;
                 new_column2 = column2 + move_context.column_delta;
+                if move_context.target_sheet_name != move_context.source_sheet_name
+                    && sheet_name.is_some()
+                {
+                    ref_sheet_name = target_sheet_name;
+                }
             } else {
                 // If the reference is not in the area we are moving the context remains unchanged
                 new_row1 = *row1;
@@ -370,11 +385,43 @@ fn to_string_moved(
             );
             format!("{s1}:{s2}")
         }
+        Range3DKind { .. } | ExternalReferenceKind { .. } => {
+            // Moving cells within one sheet does not change the span of a 3D reference or an external reference
+            let context = CellReferenceRC {
+                sheet: move_context.source_sheet_name.to_string(),
+                column: move_context.column,
+                row: move_context.row,
+            };
+            to_localized_string(node, &context, locale, language)
+        }
         OpRangeKind { left, right } => format!(
             "{}:{}",
             to_string_moved(left, move_context, locale, language),
             to_string_moved(right, move_context, locale, language),
         ),
+        OpIntersectionKind { left, right } => {
+            let x = to_string_moved(left, move_context, locale, language);
+            let y = to_string_moved(right, move_context, locale, language);
+            let x = if is_intersection_operand(left) || matches!(**left, OpIntersectionKind { .. })
+            {
+                x
+            } else {
+                format!("({x})")
+            };
+            let y = if is_intersection_operand(right) {
+                y
+            } else {
+                format!("({y})")
+            };
+            format!("{x} {y}")
+        }
+        OpUnionKind { .. } => {
+            let operands: Vec<String> = get_union_operands(node)
+                .into_iter()
+                .map(|operand| to_string_moved(operand, move_context, locale, language))
+                .collect();
+            format!("({})", operands.join(","))
+        }
         OpConcatenateKind { left, right } => format!(
             "{}&{}",
             to_string_moved(left, move_context, locale, language),
@@ -476,6 +523,11 @@ fn to_string_moved(
         }
         DefinedNameKind((name, ..)) => name.to_string(),
         TableNameKind(name) => name.to_string(),
+        StructuredReferenceKind {
+            table_name,
+            specifier,
+            table_reference,
+        } => stringify_structured_reference(table_name, specifier, table_reference, false),
         NamedVariableKind { name, id: _ } => name.to_string(),
         CompareKind { kind, left, right } => format!(
             "{}{}{}",
@@ -500,10 +552,12 @@ fn to_string_moved(
             automatic: _,
             child,
         } => {
-            format!(
-                "@{}",
-                to_string_moved(child, move_context, locale, language)
-            )
+            let child_str = to_string_moved(child, move_context, locale, language);
+            if matches!(**child, OpIntersectionKind { .. }) {
+                format!("@({child_str})")
+            } else {
+                format!("@{child_str}")
+            }
         }
         SpillRangeOperator { child } => {
             format!(
@@ -1,3 +1,4 @@
+use crate::expressions::token::{TableReference, TableSpecifier};
 use crate::functions::Function;
 
 use super::Node;
@@ -56,6 +57,21 @@ fn is_range_reference(s: &str) -> bool {
  This formulas will not be compatible with old versions of the engine. The FG will stringify this as `=SUM(_xlfn.SIMPLE(A1:A7))`.
  */
 
+// Table1[@Amount], Table1[[#Totals],[Amount]] and Table1[[#Headers],[Amount]] are a single cell.
+// The size of other structured references depends on the table when the formula is evaluated.
+fn is_single_cell_structured_reference(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::StructuredReferenceKind {
+            specifier: Some(
+                TableSpecifier::ThisRow | TableSpecifier::Totals | TableSpecifier::Headers
+            ),
+            table_reference: Some(TableReference::ColumnReference(_)),
+            ..
+        }
+    )
+}
+
 /// Traverses the formula tree adding the implicit intersection operator in all arguments of functions that
 /// expect a scalar but get a range.
 ///  * A:A => @A:A
@@ -75,6 +91,9 @@ pub fn add_implicit_intersection(node: &mut Node, add: bool) {
         | Node::NamedFunctionKind { .. }
         | Node::ArrayKind(_)
         | Node::ReferenceKind { .. }
+        | Node::OpUnionKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
         | Node::LambdaDefKind { .. }
         | Node::LambdaCallKind { .. } => {}
         Node::ImplicitIntersection { child, .. } => {
@@ -129,6 +148,22 @@ pub fn add_implicit_intersection(node: &mut Node, add: bool) {
                 }
             }
         }
+        Node::StructuredReferenceKind { .. } => {
+            if add && !is_single_cell_structured_reference(node) {
+                *node = Node::ImplicitIntersection {
+                    automatic: true,
+                    child: Box::new(node.clone()),
+                }
+            }
+        }
+        Node::OpIntersectionKind { .. } => {
+            if add {
+                *node = Node::ImplicitIntersection {
+                    automatic: true,
+                    child: Box::new(node.clone()),
+                }
+            }
+        }
 
         // operations
         Node::UnaryKind { right, .. } => add_implicit_intersection(right, add),
@@ -233,10 +268,15 @@ pub fn remove_redundant_implicit_intersection(node: &mut Node, add: bool) {
         | Node::ArrayKind(_)
         | Node::ReferenceKind { .. }
         | Node::RangeKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
         | Node::OpRangeKind { .. }
+        | Node::OpIntersectionKind { .. }
+        | Node::OpUnionKind { .. }
         | Node::DefinedNameKind(_)
         | Node::NamedVariableKind { .. }
         | Node::TableNameKind(_)
+        | Node::StructuredReferenceKind { .. }
         | Node::LambdaDefKind { .. }
         | Node::LambdaCallKind { .. } => {}
         Node::ImplicitIntersection { child, .. } => {
@@ -362,7 +402,23 @@ pub(crate) fn run_static_analysis_on_node(node: &Node) -> StaticResult {
             column2,
             ..
         } => StaticResult::Range(row2 - row1, column2 - column1),
-        Node::OpRangeKind { .. } => {
+        Node::ExternalReferenceKind {
+            row1,
+            column1,
+            row2,
+            column2,
+            ..
+        } => {
+            if row1 == row2 && column1 == column2 {
+                StaticResult::Scalar
+            } else {
+                StaticResult::Range(row2 - row1, column2 - column1)
+            }
+        }
+        Node::OpRangeKind { .. }
+        | Node::OpIntersectionKind { .. }
+        | Node::OpUnionKind { .. }
+        | Node::Range3DKind { .. } => {
             // TODO: We could do a bit better here
             StaticResult::Unknown
         }
@@ -382,6 +438,13 @@ pub(crate) fn run_static_analysis_on_node(node: &Node) -> StaticResult {
         }
         Node::NamedVariableKind { .. } => StaticResult::Scalar,
         Node::TableNameKind(_) => StaticResult::Unknown,
+        Node::StructuredReferenceKind { .. } => {
+            if is_single_cell_structured_reference(node) {
+                StaticResult::Scalar
+            } else {
+                StaticResult::Unknown
+            }
+        }
         Node::FunctionKind { kind, args } => static_analysis_on_function(kind, args),
         Node::ImplicitIntersection { .. } => StaticResult::Scalar,
         Node::SpillRangeOperator { .. } => StaticResult::Unknown,
@@ -1,8 +1,11 @@
-use super::{super::utils::quote_name, Node, Reference};
+use super::{
+    super::utils::{quote_external_sheet, quote_name, quote_sheet_span},
+    get_union_operands, is_intersection_operand, Node, Reference,
+};
 use crate::constants::{LAST_COLUMN, LAST_ROW};
 use crate::expressions::parser::move_formula::to_string_array_node;
 use crate::expressions::parser::static_analysis::remove_redundant_implicit_intersection;
-use crate::expressions::token::{OpSum, OpUnary};
+use crate::expressions::token::{OpSum, OpUnary, TableReference, TableSpecifier};
 use crate::functions::Function;
 use crate::language::{get_language, Language};
 use crate::locale::{get_locale, Locale};
@@ -172,6 +175,8 @@ fn prefix_bound_variables(node: &mut Node, bound: &mut Vec<String>) {
             }
         }
         Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right }
         | Node::OpConcatenateKind { left, right }
         | Node::OpSumKind { left, right, .. }
         | Node::OpProductKind { left, right, .. }
@@ -189,17 +194,87 @@ fn prefix_bound_variables(node: &mut Node, bound: &mut Vec<String>) {
         | Node::StringKind(_)
         | Node::ReferenceKind { .. }
         | Node::RangeKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
         | Node::WrongReferenceKind { .. }
         | Node::WrongRangeKind { .. }
         | Node::ArrayKind(_)
         | Node::DefinedNameKind(_)
         | Node::TableNameKind(_)
+        | Node::StructuredReferenceKind { .. }
         | Node::ErrorKind(_)
         | Node::ParseErrorKind { .. }
         | Node::EmptyArgKind => {}
     }
 }
 
+// Escapes the special characters of a column name in a structured reference
+fn escape_column_name(name: &str) -> String {
+    name.replace('\'', "''")
+        .replace('[', "'[")
+        .replace(']', "']")
+        .replace('#', "'#")
+        .replace('@', "'@")
+}
+
+/// Table1[Amount], Table1[[#Totals],[Amount]], Table1[@Amount] or Table1[[#This Row],[Amount]].
+/// Excel files use `[#This Row]` instead of the `@` shorthand.
+pub(crate) fn stringify_structured_reference(
+    table_name: &str,
+    specifier: &Option<TableSpecifier>,
+    table_reference: &Option<TableReference>,
+    export_to_excel: bool,
+) -> String {
+    let columns = match table_reference {
+        None => None,
+        Some(TableReference::ColumnReference(name)) => {
+            Some(format!("[{}]", escape_column_name(name)))
+        }
+        Some(TableReference::RangeReference((left, right))) => Some(format!(
+            "[{}]:[{}]",
+            escape_column_name(left),
+            escape_column_name(right)
+        )),
+    };
+    let specifier = match specifier {
+        None => None,
+        Some(TableSpecifier::ThisRow) if !export_to_excel => {
+            return match (table_reference, columns) {
+                (Some(TableReference::ColumnReference(name)), _)
+                    if name
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '.') =>
+                {
+                    format!("{table_name}[@{name}]")
+                }
+                (_, Some(columns)) => format!("{table_name}[@{columns}]"),
+                (_, None) => format!("{table_name}[@]"),
+            };
+        }
+        Some(TableSpecifier::ThisRow) => Some("[#This Row]"),
+        Some(TableSpecifier::All) => Some("[#All]"),
+        Some(TableSpecifier::Data) => Some("[#Data]"),
+        Some(TableSpecifier::Headers) => Some("[#Headers]"),
+        Some(TableSpecifier::Totals) => Some("[#Totals]"),
+        Some(TableSpecifier::HeadersAndData) => Some("[#Headers],[#Data]"),
+        Some(TableSpecifier::DataAndTotals) => Some("[#Data],[#Totals]"),
+    };
+    match (specifier, columns) {
+        (Some(specifier), Some(columns)) => format!("{table_name}[{specifier},{columns}]"),
+        // A single specifier is not in brackets: Table1[#Totals]
+        (Some(specifier), None) if !specifier.contains(',') => format!("{table_name}{specifier}"),
+        (Some(specifier), None) => format!("{table_name}[{specifier}]"),
+        // A single column is not in brackets: Table1[Amount]
+        (None, Some(columns))
+            if matches!(table_reference, Some(TableReference::ColumnReference(_))) =>
+        {
+            format!("{table_name}{columns}")
+        }
+        (None, Some(columns)) => format!("{table_name}[{columns}]"),
+        (None, None) => format!("{table_name}[]"),
+    }
+}
+
 pub fn to_string_displaced(
     node: &Node,
     context: &CellReferenceRC,
@@ -413,6 +488,31 @@ pub(crate) fn stringify_reference(
     }
 }
 
+// Formats a range that is not displaced, prefixed by a sheet span or an external sheet: Jan:Dec!A1:C3, [1]Summary!C4
+fn stringify_prefixed_range(
+    context: Option<&CellReferenceRC>,
+    prefix: &str,
+    left: &Reference,
+    right: &Reference,
+) -> String {
+    let full_row =
+        left.absolute_row && right.absolute_row && (left.row == 1) && (right.row == LAST_ROW);
+    let full_column = left.absolute_column
+        && right.absolute_column
+        && (left.column == 1)
+        && (right.column == LAST_COLUMN);
+    let s1 = stringify_reference(context, &DisplaceData::None, left, full_row, full_column);
+    if left.row == right.row
+        && left.column == right.column
+        && left.absolute_row == right.absolute_row
+        && left.absolute_column == right.absolute_column
+    {
+        return format!("{prefix}!{s1}");
+    }
+    let s2 = stringify_reference(context, &DisplaceData::None, right, full_row, full_column);
+    format!("{prefix}!{s1}:{s2}")
+}
+
 fn format_function(
     name: &str,
     args: &Vec<Node>,
@@ -601,6 +701,75 @@ fn stringify(
             );
             format!("{s1}:{s2}")
         }
+        Range3DKind {
+            first_sheet_name,
+            last_sheet_name,
+            sheet_indices: _,
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+        } => {
+            // 3D references are not displaced when inserting or deleting rows or columns in one of the sheets
+            stringify_prefixed_range(
+                context,
+                &quote_sheet_span(first_sheet_name, last_sheet_name),
+                &Reference {
+                    sheet_name: &None,
+                    sheet_index: 0,
+                    row: *row1,
+                    column: *column1,
+                    absolute_row: *absolute_row1,
+                    absolute_column: *absolute_column1,
+                },
+                &Reference {
+                    sheet_name: &None,
+                    sheet_index: 0,
+                    row: *row2,
+                    column: *column2,
+                    absolute_row: *absolute_row2,
+                    absolute_column: *absolute_column2,
+                },
+            )
+        }
+        ExternalReferenceKind {
+            workbook,
+            sheet_name,
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+        } => {
+            // References to other workbooks are not displaced either
+            stringify_prefixed_range(
+                context,
+                &quote_external_sheet(workbook, sheet_name),
+                &Reference {
+                    sheet_name: &None,
+                    sheet_index: 0,
+                    row: *row1,
+                    column: *column1,
+                    absolute_row: *absolute_row1,
+                    absolute_column: *absolute_column1,
+                },
+                &Reference {
+                    sheet_name: &None,
+                    sheet_index: 0,
+                    row: *row2,
+                    column: *column2,
+                    absolute_row: *absolute_row2,
+                    absolute_column: *absolute_column2,
+                },
+            )
+        }
         WrongRangeKind {
             sheet_name,
             absolute_row1,
@@ -669,6 +838,57 @@ fn stringify(
                 language
             )
         ),
+        OpIntersectionKind { left, right } => {
+            let x = stringify(
+                left,
+                context,
+                displace_data,
+                export_to_excel,
+                locale,
+                language,
+            );
+            let y = stringify(
+                right,
+                context,
+                displace_data,
+                export_to_excel,
+                locale,
+                language,
+            );
+            let x = if is_intersection_operand(left) || matches!(**left, OpIntersectionKind { .. })
+            {
+                x
+            } else {
+                format!("({x})")
+            };
+            let y = if is_intersection_operand(right) {
+                y
+            } else {
+                format!("({y})")
+            };
+            format!("{x} {y}")
+        }
+        OpUnionKind { .. } => {
+            let separator = if locale.numbers.symbols.decimal == "." {
+                ","
+            } else {
+                ";"
+            };
+            let operands: Vec<String> = get_union_operands(node)
+                .into_iter()
+                .map(|operand| {
+                    stringify(
+                        operand,
+                        context,
+                        displace_data,
+                        export_to_excel,
+                        locale,
+                        language,
+                    )
+                })
+                .collect();
+            format!("({})", operands.join(separator))
+        }
         OpConcatenateKind { left, right } => format!(
             "{}&{}",
             stringify(
@@ -813,10 +1033,14 @@ fn stringify(
                 | StringKind(_)
                 | ReferenceKind { .. }
                 | RangeKind { .. }
+                | Range3DKind { .. }
+                | ExternalReferenceKind { .. }
                 | WrongReferenceKind { .. }
                 | DefinedNameKind(_)
                 | TableNameKind(_)
+                | StructuredReferenceKind { .. }
                 | NamedVariableKind { .. }
+                | OpUnionKind { .. }
                 | WrongRangeKind { .. } => stringify(
                     left,
                     context,
@@ -826,6 +1050,7 @@ fn stringify(
                     language,
                 ),
                 OpRangeKind { .. }
+                | OpIntersectionKind { .. }
                 | OpConcatenateKind { .. }
                 | OpProductKind { .. }
                 | OpPowerKind { .. }
@@ -858,10 +1083,14 @@ fn stringify(
                 | StringKind(_)
                 | ReferenceKind { .. }
                 | RangeKind { .. }
+                | Range3DKind { .. }
+                | ExternalReferenceKind { .. }
                 | WrongReferenceKind { .. }
                 | DefinedNameKind(_)
                 | TableNameKind(_)
+                | StructuredReferenceKind { .. }
                 | NamedVariableKind { .. }
+                | OpUnionKind { .. }
                 | WrongRangeKind { .. } => stringify(
                     right,
                     context,
@@ -871,6 +1100,7 @@ fn stringify(
                     language,
                 ),
                 OpRangeKind { .. }
+                | OpIntersectionKind { .. }
                 | OpConcatenateKind { .. }
                 | OpProductKind { .. }
                 | OpPowerKind { .. }
@@ -956,6 +1186,13 @@ fn stringify(
             format!("{{{matrix_string}}}")
         }
         TableNameKind(value) => value.to_string(),
+        StructuredReferenceKind {
+            table_name,
+            specifier,
+            table_reference,
+        } => {
+            stringify_structured_reference(table_name, specifier, table_reference, export_to_excel)
+        }
         DefinedNameKind((name, ..)) => name.to_string(),
         NamedVariableKind { name, id: _ } => name.to_string(),
         UnaryKind { kind, right } => match kind {
@@ -966,9 +1203,13 @@ fn stringify(
                     | StringKind(_)
                     | ReferenceKind { .. }
                     | RangeKind { .. }
+                    | Range3DKind { .. }
+                    | ExternalReferenceKind { .. }
                     | WrongReferenceKind { .. }
                     | WrongRangeKind { .. }
                     | OpRangeKind { .. }
+                    | OpIntersectionKind { .. }
+                    | OpUnionKind { .. }
                     | OpConcatenateKind { .. }
                     | OpProductKind { .. }
                     | FunctionKind { .. }
@@ -978,6 +1219,7 @@ fn stringify(
                     | ArrayKind(_)
                     | DefinedNameKind(_)
                     | TableNameKind(_)
+                    | StructuredReferenceKind { .. }
                     | NamedVariableKind { .. }
                     | ImplicitIntersection { .. }
                     | SpillRangeOperator { .. }
@@ -1140,87 +1382,165 @@ fn stringify(
                     )
                 );
             }
-            format!(
-                "@{}",
-                stringify(
-                    child,
-                    context,
-                    displace_data,
-                    export_to_excel,
-                    locale,
-                    language
-                )
-            )
+            let child_str = stringify(
+                child,
+                context,
+                displace_data,
+                export_to_excel,
+                locale,
+                language,
+            );
+            if matches!(**child, OpIntersectionKind { .. }) {
+                format!("@({child_str})")
+            } else {
+                format!("@{child_str}")
+            }
         }
     }
 }
 
+/// How the references to a sheet change after an operation on that sheet
+enum SheetUpdate<'a> {
+    /// The sheet is renamed
+    Rename(&'a str),
+    /// References point to a copy of the sheet. The span of 3D references is left untouched
+    Retarget(&'a str),
+    /// The sheet is deleted. 3D references starting or ending in it shrink towards their other end
+    Delete {
+        previous: Option<&'a str>,
+        next: Option<&'a str>,
+    },
+}
+
 pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name: &str) {
+    update_sheet_in_node(node, sheet_index, &SheetUpdate::Rename(new_name));
+}
+
+/// Makes the references to the sheet `sheet_index` point to the sheet `new_name`.
+/// Unlike [rename_sheet_in_node] the span of 3D references is left untouched.
+/// This is used when duplicating a sheet.
+pub(crate) fn retarget_sheet_in_node(node: &mut Node, sheet_index: u32, new_name: &str) {
+    update_sheet_in_node(node, sheet_index, &SheetUpdate::Retarget(new_name));
+}
+
+/// Updates the 3D references before deleting the sheet `sheet_index`: Jan:Dec!B5 becomes Feb:Dec!B5 when deleting Jan.
+/// `previous` and `next` are the names of the sheets around the deleted one.
+pub(crate) fn shrink_sheet_spans_in_node(
+    node: &mut Node,
+    sheet_index: u32,
+    previous: Option<&str>,
+    next: Option<&str>,
+) {
+    update_sheet_in_node(node, sheet_index, &SheetUpdate::Delete { previous, next });
+}
+
+fn update_sheet_in_node(node: &mut Node, sheet_index: u32, update: &SheetUpdate) {
+    let new_name = match update {
+        SheetUpdate::Rename(name) | SheetUpdate::Retarget(name) => Some(*name),
+        SheetUpdate::Delete { .. } => None,
+    };
     match node {
         // Rename
         Node::ReferenceKind {
             sheet_name,
             sheet_index: index,
             ..
-        } => {
-            if *index == sheet_index && sheet_name.is_some() {
-                *sheet_name = Some(new_name.to_owned());
-            }
         }
-        Node::RangeKind {
+        | Node::RangeKind {
             sheet_name,
             sheet_index: index,
             ..
         } => {
-            if *index == sheet_index && sheet_name.is_some() {
-                *sheet_name = Some(new_name.to_owned());
+            if let Some(new_name) = new_name {
+                if *index == sheet_index && sheet_name.is_some() {
+                    *sheet_name = Some(new_name.to_owned());
+                }
             }
         }
+        Node::Range3DKind {
+            first_sheet_name,
+            last_sheet_name,
+            sheet_indices: Some((first, last)),
+            ..
+        } => match update {
+            SheetUpdate::Rename(new_name) => {
+                if *first == sheet_index {
+                    *first_sheet_name = new_name.to_string();
+                }
+                if *last == sheet_index {
+                    *last_sheet_name = new_name.to_string();
+                }
+            }
+            SheetUpdate::Retarget(_) => {}
+            SheetUpdate::Delete { previous, next } => {
+                if first == last {
+                    // The reference will be broken
+                    return;
+                }
+                // The end of the span that is deleted moves one sheet towards the other end
+                let towards_last = if first < last { next } else { previous };
+                let towards_first = if first < last { previous } else { next };
+                if *first == sheet_index {
+                    if let Some(name) = towards_last {
+                        *first_sheet_name = name.to_string();
+                    }
+                }
+                if *last == sheet_index {
+                    if let Some(name) = towards_first {
+                        *last_sheet_name = name.to_string();
+                    }
+                }
+            }
+        },
         Node::WrongReferenceKind { sheet_name, .. } => {
-            if let Some(name) = sheet_name {
+            if let (Some(name), Some(new_name)) = (sheet_name.as_ref(), new_name) {
                 if name.to_uppercase() == new_name.to_uppercase() {
                     *sheet_name = Some(name.to_owned())
                 }
             }
         }
         Node::WrongRangeKind { sheet_name, .. } => {
-            if sheet_name.is_some() {
-                *sheet_name = Some(new_name.to_owned());
+            if let Some(new_name) = new_name {
+                if sheet_name.is_some() {
+                    *sheet_name = Some(new_name.to_owned());
+                }
             }
         }
 
         // Go next level
-        Node::OpRangeKind { left, right } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right } => {
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::OpConcatenateKind { left, right } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::OpSumKind {
             kind: _,
             left,
             right,
         } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::OpProductKind {
             kind: _,
             left,
             right,
         } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::OpPowerKind { left, right } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::FunctionKind { kind: _, args } => {
             for arg in args {
-                rename_sheet_in_node(arg, sheet_index, new_name);
+                update_sheet_in_node(arg, sheet_index, update);
             }
         }
         Node::NamedFunctionKind {
@@ -1229,7 +1549,7 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
             id: _,
         } => {
             for arg in args {
-                rename_sheet_in_node(arg, sheet_index, new_name);
+                update_sheet_in_node(arg, sheet_index, update);
             }
         }
         Node::CompareKind {
@@ -1237,20 +1557,20 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
             left,
             right,
         } => {
-            rename_sheet_in_node(left, sheet_index, new_name);
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(left, sheet_index, update);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::UnaryKind { kind: _, right } => {
-            rename_sheet_in_node(right, sheet_index, new_name);
+            update_sheet_in_node(right, sheet_index, update);
         }
         Node::ImplicitIntersection {
             automatic: _,
             child,
         } => {
-            rename_sheet_in_node(child, sheet_index, new_name);
+            update_sheet_in_node(child, sheet_index, update);
         }
         Node::SpillRangeOperator { child } => {
-            rename_sheet_in_node(child, sheet_index, new_name);
+            update_sheet_in_node(child, sheet_index, update);
         }
 
         // Do nothing
@@ -1262,20 +1582,272 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
         Node::ArrayKind(_) => {}
         Node::DefinedNameKind(_) => {}
         Node::TableNameKind(_) => {}
+        Node::StructuredReferenceKind { .. } => {}
         Node::NamedVariableKind { .. } => {}
         Node::EmptyArgKind => {}
+        Node::Range3DKind { .. } => {}
+        Node::ExternalReferenceKind { .. } => {}
         Node::LambdaDefKind {
             parameters: _,
             body,
         } => {
-            rename_sheet_in_node(body, sheet_index, new_name);
+            update_sheet_in_node(body, sheet_index, update);
+        }
+        Node::LambdaCallKind { lambda, args } => {
+            update_sheet_in_node(lambda, sheet_index, update);
+            for arg in args {
+                update_sheet_in_node(arg, sheet_index, update);
+            }
+        }
+    }
+}
+
+// Swaps the offsets of a relative reference (both row and column relative)
+fn transpose_cell(absolute_row: bool, absolute_column: bool, row: &mut i32, column: &mut i32) {
+    if !absolute_row && !absolute_column {
+        std::mem::swap(row, column);
+    }
+}
+
+/// Transposes the relative references of a formula, as when pasting with transpose:
+/// a reference one row up (R[-1]C) becomes a reference one column to the left (RC[-1]).
+/// References with an absolute row or column are left untouched.
+pub(crate) fn transpose_references_in_node(node: &mut Node) {
+    match node {
+        Node::ReferenceKind {
+            absolute_row,
+            absolute_column,
+            row,
+            column,
+            ..
+        } => transpose_cell(*absolute_row, *absolute_column, row, column),
+        Node::RangeKind {
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+            ..
+        }
+        | Node::Range3DKind {
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+            ..
+        } => {
+            transpose_cell(*absolute_row1, *absolute_column1, row1, column1);
+            transpose_cell(*absolute_row2, *absolute_column2, row2, column2);
+        }
+
+        // Go next level
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right }
+        | Node::OpConcatenateKind { left, right }
+        | Node::OpSumKind { left, right, .. }
+        | Node::OpProductKind { left, right, .. }
+        | Node::OpPowerKind { left, right }
+        | Node::CompareKind { left, right, .. } => {
+            transpose_references_in_node(left);
+            transpose_references_in_node(right);
+        }
+        Node::FunctionKind { args, .. } | Node::NamedFunctionKind { args, .. } => {
+            for arg in args {
+                transpose_references_in_node(arg);
+            }
+        }
+        Node::UnaryKind { right, .. } => transpose_references_in_node(right),
+        Node::ImplicitIntersection { child, .. } | Node::SpillRangeOperator { child } => {
+            transpose_references_in_node(child);
         }
+        Node::LambdaDefKind { body, .. } => transpose_references_in_node(body),
         Node::LambdaCallKind { lambda, args } => {
-            rename_sheet_in_node(lambda, sheet_index, new_name);
+            transpose_references_in_node(lambda);
             for arg in args {
-                rename_sheet_in_node(arg, sheet_index, new_name);
+                transpose_references_in_node(arg);
             }
         }
+
+        // Do nothing
+        Node::BooleanKind(_)
+        | Node::NumberKind(_)
+        | Node::StringKind(_)
+        | Node::ErrorKind(_)
+        | Node::ParseErrorKind { .. }
+        | Node::ArrayKind(_)
+        | Node::DefinedNameKind(_)
+        | Node::TableNameKind(_)
+        | Node::StructuredReferenceKind { .. }
+        | Node::NamedVariableKind { .. }
+        | Node::EmptyArgKind
+        | Node::ExternalReferenceKind { .. }
+        | Node::WrongReferenceKind { .. }
+        | Node::WrongRangeKind { .. } => {}
+    }
+}
+
+/// Points the references to sheets that do not exist to the sheets of another `workbook`, as when
+/// pasting cells copied from that workbook: `Sheet3!A1` becomes `'[Book1]Sheet3'!A1`.
+pub(crate) fn externalize_wrong_references_in_node(node: &mut Node, workbook: &str) {
+    match node {
+        Node::WrongReferenceKind {
+            sheet_name: Some(sheet_name),
+            absolute_row,
+            absolute_column,
+            row,
+            column,
+        } => {
+            *node = Node::ExternalReferenceKind {
+                workbook: workbook.to_string(),
+                sheet_name: sheet_name.clone(),
+                absolute_row1: *absolute_row,
+                absolute_column1: *absolute_column,
+                row1: *row,
+                column1: *column,
+                absolute_row2: *absolute_row,
+                absolute_column2: *absolute_column,
+                row2: *row,
+                column2: *column,
+            };
+        }
+        Node::WrongRangeKind {
+            sheet_name: Some(sheet_name),
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+        } => {
+            *node = Node::ExternalReferenceKind {
+                workbook: workbook.to_string(),
+                sheet_name: sheet_name.clone(),
+                absolute_row1: *absolute_row1,
+                absolute_column1: *absolute_column1,
+                row1: *row1,
+                column1: *column1,
+                absolute_row2: *absolute_row2,
+                absolute_column2: *absolute_column2,
+                row2: *row2,
+                column2: *column2,
+            };
+        }
+
+        // Go next level
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right }
+        | Node::OpConcatenateKind { left, right }
+        | Node::OpSumKind { left, right, .. }
+        | Node::OpProductKind { left, right, .. }
+        | Node::OpPowerKind { left, right }
+        | Node::CompareKind { left, right, .. } => {
+            externalize_wrong_references_in_node(left, workbook);
+            externalize_wrong_references_in_node(right, workbook);
+        }
+        Node::FunctionKind { args, .. } | Node::NamedFunctionKind { args, .. } => {
+            for arg in args {
+                externalize_wrong_references_in_node(arg, workbook);
+            }
+        }
+        Node::UnaryKind { right, .. } => externalize_wrong_references_in_node(right, workbook),
+        Node::ImplicitIntersection { child, .. } | Node::SpillRangeOperator { child } => {
+            externalize_wrong_references_in_node(child, workbook);
+        }
+        Node::LambdaDefKind { body, .. } => externalize_wrong_references_in_node(body, workbook),
+        Node::LambdaCallKind { lambda, args } => {
+            externalize_wrong_references_in_node(lambda, workbook);
+            for arg in args {
+                externalize_wrong_references_in_node(arg, workbook);
+            }
+        }
+
+        // Do nothing
+        Node::BooleanKind(_)
+        | Node::NumberKind(_)
+        | Node::StringKind(_)
+        | Node::ErrorKind(_)
+        | Node::ParseErrorKind { .. }
+        | Node::ArrayKind(_)
+        | Node::DefinedNameKind(_)
+        | Node::TableNameKind(_)
+        | Node::StructuredReferenceKind { .. }
+        | Node::NamedVariableKind { .. }
+        | Node::EmptyArgKind
+        | Node::ReferenceKind { .. }
+        | Node::RangeKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
+        | Node::WrongReferenceKind { .. }
+        | Node::WrongRangeKind { .. } => {}
+    }
+}
+
+/// Collects the names in the formula that are neither defined names nor tables
+pub(crate) fn collect_named_variables_in_node(node: &Node, names: &mut Vec<String>) {
+    match node {
+        Node::NamedVariableKind { name, .. } => {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        // Go next level
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right }
+        | Node::OpConcatenateKind { left, right }
+        | Node::OpSumKind { left, right, .. }
+        | Node::OpProductKind { left, right, .. }
+        | Node::OpPowerKind { left, right }
+        | Node::CompareKind { left, right, .. } => {
+            collect_named_variables_in_node(left, names);
+            collect_named_variables_in_node(right, names);
+        }
+        Node::FunctionKind { args, .. } | Node::NamedFunctionKind { args, .. } => {
+            for arg in args {
+                collect_named_variables_in_node(arg, names);
+            }
+        }
+        Node::UnaryKind { right, .. } => collect_named_variables_in_node(right, names),
+        Node::ImplicitIntersection { child, .. } | Node::SpillRangeOperator { child } => {
+            collect_named_variables_in_node(child, names);
+        }
+        Node::LambdaDefKind { body, .. } => collect_named_variables_in_node(body, names),
+        Node::LambdaCallKind { lambda, args } => {
+            collect_named_variables_in_node(lambda, names);
+            for arg in args {
+                collect_named_variables_in_node(arg, names);
+            }
+        }
+
+        // Do nothing
+        Node::BooleanKind(_)
+        | Node::NumberKind(_)
+        | Node::StringKind(_)
+        | Node::ErrorKind(_)
+        | Node::ParseErrorKind { .. }
+        | Node::ArrayKind(_)
+        | Node::DefinedNameKind(_)
+        | Node::TableNameKind(_)
+        | Node::StructuredReferenceKind { .. }
+        | Node::EmptyArgKind
+        | Node::ReferenceKind { .. }
+        | Node::RangeKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
+        | Node::WrongReferenceKind { .. }
+        | Node::WrongRangeKind { .. } => {}
     }
 }
 
@@ -1293,7 +1865,9 @@ pub(crate) fn rename_defined_name_in_node(
             }
         }
         // Go next level
-        Node::OpRangeKind { left, right } => {
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right } => {
             rename_defined_name_in_node(left, name, scope, new_name);
             rename_defined_name_in_node(right, name, scope, new_name);
         }
@@ -1365,9 +1939,12 @@ pub(crate) fn rename_defined_name_in_node(
         Node::EmptyArgKind => {}
         Node::ReferenceKind { .. } => {}
         Node::RangeKind { .. } => {}
+        Node::Range3DKind { .. } => {}
+        Node::ExternalReferenceKind { .. } => {}
         Node::WrongReferenceKind { .. } => {}
         Node::WrongRangeKind { .. } => {}
         Node::TableNameKind(_) => {}
+        Node::StructuredReferenceKind { .. } => {}
         Node::NamedVariableKind { .. } => {}
         Node::LambdaDefKind {
             parameters: _,
@@ -1383,3 +1960,70 @@ pub(crate) fn rename_defined_name_in_node(
         }
     }
 }
+
+/// Replaces the references to the table `name` (`=SUM(Sales)` or `=SUM(Sales[Amount])`) with
+/// the node returned by `replacement`. The table can be renamed, converted to a range or have
+/// its columns renamed.
+pub(crate) fn replace_table_name_in_node<F>(node: &mut Node, name: &str, replacement: &F)
+where
+    F: Fn(&Node) -> Node,
+{
+    match node {
+        Node::TableNameKind(table_name) | Node::StructuredReferenceKind { table_name, .. } => {
+            if table_name.to_lowercase() == name.to_lowercase() {
+                *node = replacement(node);
+            }
+        }
+        // Go next level
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right }
+        | Node::OpConcatenateKind { left, right }
+        | Node::OpPowerKind { left, right } => {
+            replace_table_name_in_node(left, name, replacement);
+            replace_table_name_in_node(right, name, replacement);
+        }
+        Node::OpSumKind { left, right, .. }
+        | Node::OpProductKind { left, right, .. }
+        | Node::CompareKind { left, right, .. } => {
+            replace_table_name_in_node(left, name, replacement);
+            replace_table_name_in_node(right, name, replacement);
+        }
+        Node::FunctionKind { args, .. } | Node::NamedFunctionKind { args, .. } => {
+            for arg in args {
+                replace_table_name_in_node(arg, name, replacement);
+            }
+        }
+        Node::UnaryKind { right, .. } => {
+            replace_table_name_in_node(right, name, replacement);
+        }
+        Node::ImplicitIntersection { child, .. } | Node::SpillRangeOperator { child } => {
+            replace_table_name_in_node(child, name, replacement);
+        }
+        Node::LambdaDefKind { body, .. } => {
+            replace_table_name_in_node(body, name, replacement);
+        }
+        Node::LambdaCallKind { lambda, args } => {
+            replace_table_name_in_node(lambda, name, replacement);
+            for arg in args {
+                replace_table_name_in_node(arg, name, replacement);
+            }
+        }
+        // Do nothing
+        Node::BooleanKind(_)
+        | Node::NumberKind(_)
+        | Node::StringKind(_)
+        | Node::ErrorKind(_)
+        | Node::ParseErrorKind { .. }
+        | Node::ArrayKind(_)
+        | Node::EmptyArgKind
+        | Node::ReferenceKind { .. }
+        | Node::RangeKind { .. }
+        | Node::Range3DKind { .. }
+        | Node::ExternalReferenceKind { .. }
+        | Node::WrongReferenceKind { .. }
+        | Node::WrongRangeKind { .. }
+        | Node::DefinedNameKind(_)
+        | Node::NamedVariableKind { .. } => {}
+    }
+}
@@ -1,7 +1,9 @@
+mod test_3d_references;
 mod test_add_implicit_intersection;
 mod test_arrays;
 mod test_complex_precedence;
 mod test_excel_export_lambda_let;
+mod test_external_references;
 mod test_general;
 mod test_implicit_intersection;
 mod test_implicit_intersection_export;
@@ -14,6 +16,7 @@ mod test_locales;
 mod test_move_formula;
 mod test_partial_parsing;
 mod test_ranges;
+mod test_reference_operators;
 mod test_stringify;
 mod test_tables;
 mod utils;
@@ -0,0 +1,127 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use crate::expressions::lexer::LexerMode;
+use crate::expressions::parser::stringify::{rename_sheet_in_node, to_excel_string, to_rc_format};
+use crate::expressions::parser::tests::utils::{new_parser, to_english_localized_string};
+use crate::expressions::parser::Node;
+use crate::expressions::types::CellReferenceRC;
+
+struct Formula<'a> {
+    formula_a1: &'a str,
+    formula_r1c1: &'a str,
+}
+
+#[test]
+fn sheet_span_roundtrip() {
+    let worksheets = vec![
+        "Jan".to_string(),
+        "Feb".to_string(),
+        "Mar".to_string(),
+        "Q1 Total".to_string(),
+    ];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+
+    let formulas = vec![
+        Formula {
+            formula_a1: "SUM(Jan:Mar!B5)",
+            formula_r1c1: "SUM(Jan:Mar!R[4]C[1])",
+        },
+        Formula {
+            formula_a1: "AVERAGE(Jan:Mar!$B$5:C10)",
+            formula_r1c1: "AVERAGE(Jan:Mar!R5C2:R[9]C[2])",
+        },
+        Formula {
+            formula_a1: "SUM('Feb:Q1 Total'!A:A)+1",
+            formula_r1c1: "SUM('Feb:Q1 Total'!R1C[0]:R1048576C[0])+1",
+        },
+        Formula {
+            formula_a1: "COUNT(Mar:Jan!A1,B2)",
+            formula_r1c1: "COUNT(Mar:Jan!R[0]C[0],R[1]C[1])",
+        },
+    ];
+
+    let cell_reference = CellReferenceRC {
+        sheet: "Q1 Total".to_string(),
+        row: 1,
+        column: 1,
+    };
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_a1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+        assert_eq!(to_excel_string(&t, &cell_reference), formula.formula_a1);
+    }
+
+    parser.set_lexer_mode(LexerMode::R1C1);
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_r1c1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+    }
+}
+
+#[test]
+fn sheet_indices() {
+    let worksheets = vec!["Jan".to_string(), "Feb".to_string(), "Mar".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Jan".to_string(),
+        row: 1,
+        column: 1,
+    };
+
+    let t = parser.parse("Feb:Mar!A1", &cell_reference);
+    assert!(matches!(
+        t,
+        Node::Range3DKind {
+            sheet_indices: Some((1, 2)),
+            ..
+        }
+    ));
+
+    // A span with a sheet that does not exist is kept as is, but it is broken
+    let t = parser.parse("Feb:Dec!A1", &cell_reference);
+    assert!(matches!(
+        t,
+        Node::Range3DKind {
+            sheet_indices: None,
+            ..
+        }
+    ));
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "Feb:Dec!A1"
+    );
+}
+
+#[test]
+fn rename_sheet_span() {
+    let worksheets = vec!["Jan".to_string(), "Feb".to_string(), "Mar".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Jan".to_string(),
+        row: 1,
+        column: 1,
+    };
+
+    let mut t = parser.parse("SUM(Jan:Mar!A1)", &cell_reference);
+    rename_sheet_in_node(&mut t, 2, "March 2024");
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM('Jan:March 2024'!A1)"
+    );
+    // Renaming a sheet inside the span does not change the reference
+    rename_sheet_in_node(&mut t, 1, "February");
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM('Jan:March 2024'!A1)"
+    );
+}
@@ -0,0 +1,131 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use crate::expressions::lexer::LexerMode;
+use crate::expressions::parser::move_formula::{move_formula, MoveContext};
+use crate::expressions::parser::stringify::{to_excel_string, to_rc_format};
+use crate::expressions::parser::tests::utils::{new_parser, to_english_localized_string};
+use crate::expressions::parser::Node;
+use crate::expressions::types::{Area, CellReferenceRC};
+use crate::language::get_default_language;
+use crate::locale::get_default_locale;
+
+struct Formula<'a> {
+    formula_a1: &'a str,
+    formula_r1c1: &'a str,
+}
+
+#[test]
+fn external_reference_roundtrip() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+
+    let formulas = vec![
+        Formula {
+            formula_a1: "[1]Summary!C4*2",
+            formula_r1c1: "[1]Summary!R[3]C[2]*2",
+        },
+        Formula {
+            formula_a1: "SUM([Budget.xlsx]Summary!$A$1:C3)",
+            formula_r1c1: "SUM([Budget.xlsx]Summary!R1C1:R[2]C[2])",
+        },
+        Formula {
+            formula_a1: "'[Budget 2024.xlsx]Q1 Total'!B:B",
+            formula_r1c1: "'[Budget 2024.xlsx]Q1 Total'!R1C[1]:R1048576C[1]",
+        },
+        Formula {
+            formula_a1: "'C:\\Reports\\[Budget.xlsx]Summary'!A1+Sheet1!A1",
+            formula_r1c1: "'C:\\Reports\\[Budget.xlsx]Summary'!R[0]C[0]+Sheet1!R[0]C[0]",
+        },
+    ];
+
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_a1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+        assert_eq!(to_excel_string(&t, &cell_reference), formula.formula_a1);
+    }
+
+    parser.set_lexer_mode(LexerMode::R1C1);
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_r1c1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+    }
+}
+
+#[test]
+fn external_reference_node() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 2,
+        column: 2,
+    };
+
+    // The workbook is kept as written, even if there is no link to it
+    let t = parser.parse("'[Budget.xlsx]Summary'!$C$4", &cell_reference);
+    assert_eq!(
+        t,
+        Node::ExternalReferenceKind {
+            workbook: "Budget.xlsx".to_string(),
+            sheet_name: "Summary".to_string(),
+            absolute_row1: true,
+            absolute_column1: true,
+            row1: 4,
+            column1: 3,
+            absolute_row2: true,
+            absolute_column2: true,
+            row2: 4,
+            column2: 3,
+        }
+    );
+}
+
+#[test]
+fn move_external_reference() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    // Moving the cells of this workbook does not change references to other workbooks
+    let t = parser.parse("[1]Sheet1!A1+A1", &cell_reference);
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 1,
+        height: 1,
+    };
+    let moved = move_formula(
+        &t,
+        &MoveContext {
+            source_sheet_name: "Sheet1",
+            row: 1,
+            column: 1,
+            area: &area,
+            target_sheet_name: "Sheet1",
+            row_delta: 2,
+            column_delta: 2,
+        },
+        get_default_locale(),
+        get_default_language(),
+    );
+    assert_eq!(moved, "[1]Sheet1!A1+C3");
+}
@@ -0,0 +1,181 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use crate::expressions::lexer::LexerMode;
+use crate::expressions::parser::move_formula::{move_formula, MoveContext};
+use crate::expressions::parser::stringify::{to_excel_string, to_localized_string, to_rc_format};
+use crate::expressions::parser::tests::utils::{new_parser, to_english_localized_string};
+use crate::expressions::parser::{Node, Parser};
+use crate::expressions::types::{Area, CellReferenceRC};
+use crate::language::{get_default_language, get_language};
+use crate::locale::{get_default_locale, get_locale};
+
+struct Formula<'a> {
+    formula_a1: &'a str,
+    formula_r1c1: &'a str,
+}
+
+#[test]
+fn intersection_and_union_roundtrip() {
+    let worksheets = vec!["Sheet1".to_string(), "Sheet2".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+
+    let formulas = vec![
+        Formula {
+            formula_a1: "B:B 3:3",
+            formula_r1c1: "R1C[1]:R1048576C[1] R[2]C1:R[2]C16384",
+        },
+        Formula {
+            formula_a1: "SUM((A1:A3,C1:C3))",
+            formula_r1c1: "SUM((R[0]C[0]:R[2]C[0],R[0]C[2]:R[2]C[2]))",
+        },
+        Formula {
+            formula_a1: "SUM((A1,B2,Sheet2!C3))",
+            formula_r1c1: "SUM((R[0]C[0],R[1]C[1],Sheet2!R[2]C[2]))",
+        },
+        Formula {
+            formula_a1: "SUM(A1:C3 B2:D4 C1:C9)*2",
+            formula_r1c1: "SUM(R[0]C[0]:R[2]C[2] R[1]C[1]:R[3]C[3] R[0]C[2]:R[8]C[2])*2",
+        },
+        Formula {
+            formula_a1: "INDEX((A1:B2,D1:E2),1,1,2)",
+            formula_r1c1: "INDEX((R[0]C[0]:R[1]C[1],R[0]C[3]:R[1]C[4]),1,1,2)",
+        },
+        Formula {
+            formula_a1: "(A1:B2,D1:E2) A:A",
+            formula_r1c1: "(R[0]C[0]:R[1]C[1],R[0]C[3]:R[1]C[4]) R1C[0]:R1048576C[0]",
+        },
+        Formula {
+            formula_a1: "A1:C3 (B2:D4 C1:C9)",
+            formula_r1c1: "R[0]C[0]:R[2]C[2] (R[1]C[1]:R[3]C[3] R[0]C[2]:R[8]C[2])",
+        },
+        Formula {
+            formula_a1: "-A1:B2 B1",
+            formula_r1c1: "-R[0]C[0]:R[1]C[1] R[0]C[1]",
+        },
+    ];
+
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_a1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+    }
+
+    parser.set_lexer_mode(LexerMode::R1C1);
+    for formula in &formulas {
+        let t = parser.parse(formula.formula_r1c1, &cell_reference);
+        assert_eq!(to_rc_format(&t), formula.formula_r1c1);
+        assert_eq!(
+            to_english_localized_string(&t, &cell_reference),
+            formula.formula_a1
+        );
+    }
+}
+
+#[test]
+fn spaces_are_not_always_intersections() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+
+    let t = parser.parse("SUM( A1 , B1 ) + C1 ", &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM(A1,B1)+C1"
+    );
+
+    let t = parser.parse("(A1 + B1)", &cell_reference);
+    assert_eq!(to_english_localized_string(&t, &cell_reference), "A1+B1");
+
+    let t = parser.parse("A1 B1", &cell_reference);
+    assert!(matches!(t, Node::OpIntersectionKind { .. }));
+
+    // A number is never the start of a reference
+    let t = parser.parse("A1 2", &cell_reference);
+    assert!(matches!(t, Node::ReferenceKind { .. }));
+}
+
+#[test]
+fn export_and_locales() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    let mut parser = new_parser(worksheets.clone(), vec![], HashMap::new());
+    let t = parser.parse("SUM((A1:A3,C1:C3) 2:2)", &cell_reference);
+    assert_eq!(
+        to_excel_string(&t, &cell_reference),
+        "SUM((A1:A3,C1:C3) 2:2)"
+    );
+
+    // In locales with a comma as decimal separator the union operator is a semicolon
+    let locale = get_locale("fr").unwrap();
+    let language = get_language("en").unwrap();
+    let mut parser = Parser::new(worksheets, vec![], HashMap::new(), locale, language);
+    let t = parser.parse("SUM((A1:A3;C1:C3))", &cell_reference);
+    assert_eq!(
+        to_rc_format(&t),
+        "SUM((R[0]C[0]:R[2]C[0],R[0]C[2]:R[2]C[2]))"
+    );
+    assert_eq!(
+        to_localized_string(&t, &cell_reference, locale, language),
+        "SUM((A1:A3;C1:C3))"
+    );
+}
+
+#[test]
+fn move_intersections_and_unions() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = new_parser(worksheets, vec![], HashMap::new());
+    let context = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    // We move B1:C3 three rows down
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 2,
+        width: 2,
+        height: 3,
+    };
+    let move_context = MoveContext {
+        source_sheet_name: "Sheet1",
+        row: 1,
+        column: 1,
+        area: &area,
+        target_sheet_name: "Sheet1",
+        row_delta: 3,
+        column_delta: 0,
+    };
+    let locale = get_default_locale();
+    let language = get_default_language();
+
+    let node = parser.parse("SUM((B1:C3,D1:D3))", &context);
+    assert_eq!(
+        move_formula(&node, &move_context, locale, language),
+        "SUM((B4:C6,D1:D3))"
+    );
+
+    let node = parser.parse("B2 A:C", &context);
+    assert_eq!(
+        move_formula(&node, &move_context, locale, language),
+        "B5 A:C"
+    );
+}
@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 
+use crate::expressions::parser::stringify::to_excel_string;
 use crate::expressions::parser::tests::utils::{new_parser, to_english_localized_string};
 use crate::expressions::types::CellReferenceRC;
 use crate::expressions::utils::{number_to_column, parse_reference_a1};
@@ -73,7 +74,7 @@ fn simple_table() {
     let t = parser.parse(formula, &cell_reference);
     assert_eq!(
         to_english_localized_string(&t, &cell_reference),
-        "SUM($A$2:$E$2)"
+        "SUM(tblIncome[@[Jan]:[Dec]])"
     );
 
     // Cell A3
@@ -86,7 +87,7 @@ fn simple_table() {
     let t = parser.parse(formula, &cell_reference);
     assert_eq!(
         to_english_localized_string(&t, &cell_reference),
-        "SUBTOTAL(109,$A$2:$A$3)"
+        "SUBTOTAL(109,tblIncome[Jan])"
     );
 
     // Cell A3 in 'Second Sheet'
@@ -99,6 +100,99 @@ fn simple_table() {
     let t = parser.parse(formula, &cell_reference);
     assert_eq!(
         to_english_localized_string(&t, &cell_reference),
-        "SUBTOTAL(109,'Sheet One'!$A$2:$A$3)"
+        "SUBTOTAL(109,tblIncome[Jan])"
     );
 }
+
+#[test]
+fn this_row_shorthand_and_specifiers() {
+    let worksheets = vec!["Sheet One".to_string(), "Second Sheet".to_string()];
+
+    // Table A1:F3 with headers
+    let column_names = ["Jan", "Feb", "Mar", "Apr", "Dec", "Year"];
+    let tables = create_test_table("tblIncome", &column_names, "A1", 3);
+
+    let mut parser = new_parser(worksheets, vec![], tables);
+    // Reference cell is 'Sheet One'!F2
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet One".to_string(),
+        row: 2,
+        column: 6,
+    };
+
+    let formula = "SUM(tblIncome[@[Jan]:[Dec]])";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM(tblIncome[@[Jan]:[Dec]])"
+    );
+
+    // Inside the table the name of the table can be omitted
+    let formula = "[@Jan]+[@[Feb]]";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "tblIncome[@Jan]+tblIncome[@Feb]"
+    );
+    // Excel files use [#This Row] instead of '@'
+    assert_eq!(
+        to_excel_string(&t, &cell_reference),
+        "tblIncome[[#This Row],[Jan]]+tblIncome[[#This Row],[Feb]]"
+    );
+
+    // Names of tables and columns are case insensitive
+    let formula = "SUM(TBLINCOME[jan])";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM(tblIncome[Jan])"
+    );
+
+    let formula = "COUNTA(tblIncome[[#Headers],[#Data],[Feb]])";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "COUNTA(tblIncome[[#Headers],[#Data],[Feb]])"
+    );
+
+    let formula = "SUM(tblIncome[[#Data],[#Totals],[Feb]])";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(
+        to_english_localized_string(&t, &cell_reference),
+        "SUM(tblIncome[[#Data],[#Totals],[Feb]])"
+    );
+
+    // Outside of a table [@Jan] is an error
+    let cell_reference = CellReferenceRC {
+        sheet: "Second Sheet".to_string(),
+        row: 2,
+        column: 6,
+    };
+    let formula = "[@Jan]";
+    let t = parser.parse(formula, &cell_reference);
+    assert!(matches!(
+        t,
+        crate::expressions::parser::Node::ParseErrorKind { .. }
+    ));
+}
+
+#[test]
+fn special_characters_in_column_names() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let column_names = ["Price #1", "Net [USD]"];
+    let tables = create_test_table("Prices", &column_names, "A1", 3);
+    let mut parser = new_parser(worksheets, vec![], tables);
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 2,
+        column: 2,
+    };
+
+    let formula = "SUM(Prices[Price '#1])+Prices[@[Net '[USD']]]";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(to_english_localized_string(&t, &cell_reference), formula);
+
+    let formula = "SUM(Prices[[Price '#1]:[Net '[USD']]])";
+    let t = parser.parse(formula, &cell_reference);
+    assert_eq!(to_english_localized_string(&t, &cell_reference), formula);
+}
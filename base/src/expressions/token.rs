@@ -207,6 +207,10 @@ pub enum TableSpecifier {
     Headers,
     ThisRow,
     Totals,
+    // [#Headers],[#Data]
+    HeadersAndData,
+    // [#Data],[#Totals]
+    DataAndTotals,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -255,6 +259,20 @@ pub enum TokenType {
         left: ParsedReference,
         right: ParsedReference,
     },
+    // Jan:Dec!B5, 'Jan 24:Dec 24'!A1:C3
+    Range3D {
+        first_sheet: String,
+        last_sheet: String,
+        left: ParsedReference,
+        right: ParsedReference,
+    },
+    // [1]Summary!C4, '[Budget.xlsx]Summary'!A1:C3
+    ExternalReference {
+        workbook: String,
+        sheet: String,
+        left: ParsedReference,
+        right: ParsedReference,
+    },
     StructuredReference {
         table_name: String,
         specifier: Option<TableSpecifier>,
@@ -255,7 +255,7 @@ pub fn is_valid_identifier(name: &str) -> bool {
     is_valid_a1_identifier(name)
 }
 
-fn name_needs_quoting(name: &str) -> bool {
+pub(crate) fn name_needs_quoting(name: &str) -> bool {
     let chars = name.chars();
     // it contains any of these characters: ()'$,;-+{} or space
     for (i, char) in chars.enumerate() {
@@ -287,6 +287,34 @@ pub fn quote_name(name: &str) -> String {
     name.to_string()
 }
 
+/// Quotes the span of sheets of a 3D reference if any of the names needs it: Jan:Dec or 'Jan 24:Dec 24'
+pub fn quote_sheet_span(first_sheet: &str, last_sheet: &str) -> String {
+    if name_needs_quoting(first_sheet) || name_needs_quoting(last_sheet) {
+        return format!(
+            "'{}:{}'",
+            first_sheet.replace('\'', "''"),
+            last_sheet.replace('\'', "''")
+        );
+    };
+    format!("{first_sheet}:{last_sheet}")
+}
+
+/// Formats the workbook and sheet of an external reference, quoting them if needed:
+/// `[1]Summary`, `'[Budget 2024.xlsx]Summary'` or `'C:\Reports\[Budget.xlsx]Summary'`
+pub fn quote_external_sheet(workbook: &str, sheet: &str) -> String {
+    let (path, file_name) = match workbook.rfind(['/', '\\']) {
+        Some(index) => workbook.split_at(index + 1),
+        None => ("", workbook),
+    };
+    let text = format!("{path}[{file_name}]{sheet}");
+    // The name of the workbook can start with a digit: [1]Summary!C4
+    if !path.is_empty() || name_needs_quoting(&format!("_{file_name}")) || name_needs_quoting(sheet)
+    {
+        return format!("'{}'", text.replace('\'', "''"));
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,277 @@
+//! References to other workbooks: `[1]Summary!C4` or `'[Budget.xlsx]Summary'!A1:C3`.
+//!
+//! The number between brackets is the position (1-based) of the link in [Workbook::external_links].
+//! The values are supplied by the host application through an [ExternalLinkResolver]. Without a
+//! resolver, or if the linked workbook is not available, the values cached in the workbook are used.
+//! The formulas are only recomputed after [Model::refresh_external_links].
+//!
+//! [Workbook::external_links]: crate::types::Workbook::external_links
+
+use std::collections::HashMap;
+
+use crate::{
+    calc_result::CalcResult,
+    expressions::{parser::ArrayNode, parser::Node, token::Error, types::CellReferenceIndex},
+    types::{Cell, ExternalLink, ExternalSheet, ExternalValue, FormulaValue, SpillValue},
+    Model,
+};
+
+/// Supplies the values of the cells in other workbooks
+pub trait ExternalLinkResolver: Send + Sync {
+    /// Returns the value of the cell (`row`, `column`) in `sheet` of `workbook`, or None if the cell is empty.
+    /// `workbook` is the target of the link ("Budget.xlsx") or the name written in the formula if there is no link.
+    /// Returns an error if the workbook is not available, the cached value is used then.
+    fn get_cell_value(
+        &self,
+        workbook: &str,
+        sheet: &str,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<ExternalValue>, String>;
+
+    /// Returns the last row and column with data in `sheet` of `workbook`.
+    /// Ranges are only read up to there, so `[1]Summary!A:A` does not visit every row.
+    fn get_sheet_dimension(&self, workbook: &str, sheet: &str) -> Result<(i32, i32), String>;
+}
+
+/// Resolves the references using the (evaluated) models of the linked workbooks, keyed by link target
+impl ExternalLinkResolver for HashMap<String, Model<'_>> {
+    fn get_cell_value(
+        &self,
+        workbook: &str,
+        sheet: &str,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<ExternalValue>, String> {
+        let model = self
+            .get(workbook)
+            .ok_or_else(|| format!("Workbook not found: '{workbook}'"))?;
+        let sheet_index = model
+            .get_sheet_index_by_name(sheet)
+            .ok_or_else(|| format!("Sheet not found: '{sheet}'"))?;
+        let cell = match model.workbook.worksheet(sheet_index)?.cell(row, column) {
+            Some(cell) => cell,
+            None => return Ok(None),
+        };
+        let shared_strings = &model.workbook.shared_strings;
+        let value = match cell {
+            Cell::EmptyCell { .. } => return Ok(None),
+            Cell::BooleanCell { v, .. } => ExternalValue::Boolean(*v),
+            Cell::NumberCell { v, .. } => ExternalValue::Number(*v),
+            Cell::ErrorCell { ei, .. } => ExternalValue::Error(ei.clone()),
+            Cell::SharedString { si, .. } => ExternalValue::Text(
+                shared_strings
+                    .get(*si as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            Cell::CellFormula { v, .. } | Cell::ArrayFormula { v, .. } => match v {
+                FormulaValue::Unevaluated => return Ok(None),
+                FormulaValue::Boolean(b) => ExternalValue::Boolean(*b),
+                FormulaValue::Number(n) => ExternalValue::Number(*n),
+                FormulaValue::Text(s) => ExternalValue::Text(s.clone()),
+                FormulaValue::Error { ei, .. } => ExternalValue::Error(ei.clone()),
+            },
+            Cell::SpillCell { v, .. } => match v {
+                SpillValue::Boolean(b) => ExternalValue::Boolean(*b),
+                SpillValue::Number(n) => ExternalValue::Number(*n),
+                SpillValue::Text(s) => ExternalValue::Text(s.clone()),
+                SpillValue::Error(e) => ExternalValue::Error(e.clone()),
+            },
+        };
+        Ok(Some(value))
+    }
+
+    fn get_sheet_dimension(&self, workbook: &str, sheet: &str) -> Result<(i32, i32), String> {
+        let model = self
+            .get(workbook)
+            .ok_or_else(|| format!("Workbook not found: '{workbook}'"))?;
+        let sheet_index = model
+            .get_sheet_index_by_name(sheet)
+            .ok_or_else(|| format!("Sheet not found: '{sheet}'"))?;
+        let dimension = model.workbook.worksheet(sheet_index)?.dimension();
+        Ok((dimension.max_row, dimension.max_column))
+    }
+}
+
+// The values of `sheet` cached in the link
+fn cached_sheet<'a>(
+    link: Option<&'a ExternalLink>,
+    workbook: &str,
+    sheet: &str,
+) -> Result<&'a ExternalSheet, String> {
+    let link = link.ok_or_else(|| format!("Unknown workbook: '{workbook}'"))?;
+    link.sheets
+        .iter()
+        .find(|s| s.name.to_lowercase() == sheet.to_lowercase())
+        .ok_or_else(|| format!("Unknown sheet: '{sheet}'"))
+}
+
+impl<'a> Model<'a> {
+    /// Sets the resolver used to evaluate the references to other workbooks.
+    /// With `None` the values cached in the workbook are used.
+    pub fn set_external_link_resolver(
+        &mut self,
+        resolver: Option<Box<dyn ExternalLinkResolver + 'a>>,
+    ) {
+        self.external_link_resolver = resolver;
+        self.refresh_external_links();
+    }
+
+    /// Flags the formulas that read other workbooks so that they are recomputed on the next evaluation.
+    /// Call it after the linked workbooks or the cached values in [Workbook::external_links] change.
+    ///
+    /// [Workbook::external_links]: crate::types::Workbook::external_links
+    pub fn refresh_external_links(&mut self) {
+        for cell in self.dependency_graph.external_cells() {
+            self.mark_cell_dirty(cell.sheet, cell.row, cell.column);
+        }
+    }
+
+    /// Returns the links to other workbooks
+    pub fn get_external_links(&self) -> &Vec<ExternalLink> {
+        &self.workbook.external_links
+    }
+
+    // Finds the link of the workbook as written in a formula: "1" (the first link) or "Budget.xlsx"
+    fn get_external_link(&self, workbook: &str) -> Option<&ExternalLink> {
+        let links = &self.workbook.external_links;
+        if let Ok(index) = workbook.parse::<usize>() {
+            return index.checked_sub(1).and_then(|index| links.get(index));
+        }
+        let file_name = |path: &str| {
+            let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+            name.to_lowercase()
+        };
+        links.iter().find(|link| {
+            link.target.eq_ignore_ascii_case(workbook)
+                || file_name(&link.target) == file_name(workbook)
+        })
+    }
+
+    fn get_external_value(
+        &self,
+        workbook: &str,
+        sheet: &str,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<ExternalValue>, String> {
+        let link = self.get_external_link(workbook);
+        if let Some(resolver) = &self.external_link_resolver {
+            let target = link.map_or(workbook, |link| link.target.as_str());
+            if let Ok(value) = resolver.get_cell_value(target, sheet, row, column) {
+                return Ok(value);
+            }
+        }
+        Ok(cached_sheet(link, workbook, sheet)?
+            .sheet_data
+            .get(&row)
+            .and_then(|data_row| data_row.get(&column))
+            .cloned())
+    }
+
+    // The last row and column with data in the linked sheet
+    fn get_external_dimension(&self, workbook: &str, sheet: &str) -> Result<(i32, i32), String> {
+        let link = self.get_external_link(workbook);
+        if let Some(resolver) = &self.external_link_resolver {
+            let target = link.map_or(workbook, |link| link.target.as_str());
+            if let Ok(dimension) = resolver.get_sheet_dimension(target, sheet) {
+                return Ok(dimension);
+            }
+        }
+        let sheet_data = &cached_sheet(link, workbook, sheet)?.sheet_data;
+        let max_row = sheet_data.keys().copied().max().unwrap_or(1);
+        let max_column = sheet_data
+            .values()
+            .flat_map(|data_row| data_row.keys().copied())
+            .max()
+            .unwrap_or(1);
+        Ok((max_row, max_column))
+    }
+
+    /// Evaluates a reference to another workbook: a single value or an array with the values of the range
+    pub(crate) fn evaluate_external_reference(
+        &mut self,
+        node: &Node,
+        cell: CellReferenceIndex,
+    ) -> CalcResult {
+        let Node::ExternalReferenceKind {
+            workbook,
+            sheet_name,
+            absolute_row1,
+            absolute_column1,
+            row1,
+            column1,
+            absolute_row2,
+            absolute_column2,
+            row2,
+            column2,
+        } = node
+        else {
+            return CalcResult::new_error(
+                Error::ERROR,
+                cell,
+                "Expecting an external reference".to_string(),
+            );
+        };
+        // Recomputed when the links change, see [Model::refresh_external_links]
+        self.dependency_graph.set_external(cell);
+        let row1 = if *absolute_row1 {
+            *row1
+        } else {
+            row1 + cell.row
+        };
+        let column1 = if *absolute_column1 {
+            *column1
+        } else {
+            column1 + cell.column
+        };
+        let mut row2 = if *absolute_row2 {
+            *row2
+        } else {
+            row2 + cell.row
+        };
+        let mut column2 = if *absolute_column2 {
+            *column2
+        } else {
+            column2 + cell.column
+        };
+        if row1 == row2 && column1 == column2 {
+            return match self.get_external_value(workbook, sheet_name, row1, column1) {
+                Ok(Some(ExternalValue::Number(value))) => CalcResult::Number(value),
+                Ok(Some(ExternalValue::Text(value))) => CalcResult::String(value),
+                Ok(Some(ExternalValue::Boolean(value))) => CalcResult::Boolean(value),
+                Ok(Some(ExternalValue::Error(error))) => {
+                    CalcResult::new_error(error, cell, "Error in linked workbook".to_string())
+                }
+                Ok(None) => CalcResult::EmptyCell,
+                Err(message) => CalcResult::new_error(Error::REF, cell, message),
+            };
+        }
+        // Nothing to read past the last cell with data (think of `[1]Summary!A:A`)
+        match self.get_external_dimension(workbook, sheet_name) {
+            Ok((max_row, max_column)) => {
+                row2 = row2.min(max_row).max(row1);
+                column2 = column2.min(max_column).max(column1);
+            }
+            Err(message) => return CalcResult::new_error(Error::REF, cell, message),
+        }
+        let mut array = Vec::new();
+        for row in row1..=row2 {
+            let mut data_row = Vec::new();
+            for column in column1..=column2 {
+                let value = match self.get_external_value(workbook, sheet_name, row, column) {
+                    Ok(Some(ExternalValue::Number(value))) => ArrayNode::Number(value),
+                    Ok(Some(ExternalValue::Text(value))) => ArrayNode::String(value),
+                    Ok(Some(ExternalValue::Boolean(value))) => ArrayNode::Boolean(value),
+                    Ok(Some(ExternalValue::Error(error))) => ArrayNode::Error(error),
+                    Ok(None) => ArrayNode::Empty,
+                    Err(message) => return CalcResult::new_error(Error::REF, cell, message),
+                };
+                data_row.push(value);
+            }
+            array.push(data_row);
+        }
+        CalcResult::Array(array)
+    }
+}
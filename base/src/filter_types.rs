@@ -0,0 +1,97 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Color;
+
+/// The part of a date a [DateGroupItem] matches
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub enum DateTimeGrouping {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Matches the dates in a year, a month, a day... like all the dates in March 2024.
+/// Only the fields up to the grouping are relevant.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct DateGroupItem {
+    pub grouping: DateTimeGrouping,
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub enum FilterOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+/// Compares the cells with a value. Text values can have the wildcards `*` and `?`.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct CustomFilter {
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub enum DynamicFilter {
+    AboveAverage,
+    BelowAverage,
+}
+
+/// The criteria of a column of an autofilter. The rows whose cell doesn't match are hidden.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum Filter {
+    /// Cells with one of the values, as displayed, or a date in one of the groups.
+    /// Empty cells match if `blank` is true.
+    Values {
+        values: Vec<String>,
+        date_groups: Vec<DateGroupItem>,
+        blank: bool,
+    },
+    /// The largest (or smallest if not `top`) `value` numbers, or `value` percent of the numbers
+    Top10 {
+        top: bool,
+        percent: bool,
+        value: f64,
+    },
+    /// The numbers above or below the average of the column
+    Dynamic { kind: DynamicFilter },
+    /// Cells that match one or two comparisons, both of them if `and` is true
+    Custom {
+        and: bool,
+        filters: Vec<CustomFilter>,
+    },
+    /// Cells with this fill color
+    CellColor { color: Color },
+    /// Cells with this font color
+    FontColor { color: Color },
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct FilterColumn {
+    /// The position of the column in the range, 0 for the first one
+    pub column: i32,
+    pub filter: Filter,
+}
+
+/// An autofilter on a range of the sheet. The first row of the range has the filter buttons.
+/// The autofilters of tables are stored in their columns.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct AutoFilter {
+    /// The range, including the header row, like "A1:D20"
+    pub range: String,
+    pub columns: Vec<FilterColumn>,
+}
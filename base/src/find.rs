@@ -0,0 +1,129 @@
+//! Finding the cells whose content, value or comment matches a text.
+
+#[cfg(target_arch = "wasm32")]
+use regex_lite as regex;
+
+use crate::{
+    expressions::{types::CellReferenceIndex, utils::parse_reference_a1},
+    find_types::{FindIn, FindMode, FindOptions, SearchOrder},
+    functions::util::from_wildcard_to_regex,
+    model::Model,
+    types::Cell,
+};
+
+/// The regular expression that matches the search text with those options
+pub(crate) fn get_find_regex(text: &str, options: &FindOptions) -> Result<regex::Regex, String> {
+    if text.is_empty() {
+        return Err("The search text is empty".to_string());
+    }
+    let pattern = match options.mode {
+        FindMode::Text => regex::escape(text),
+        FindMode::Wildcard => from_wildcard_to_regex(text, false)
+            .map_err(|error| error.to_string())?
+            .as_str()
+            .to_string(),
+        FindMode::Regex => text.to_string(),
+    };
+    let pattern = if options.whole_cell {
+        format!("^(?:{pattern})$")
+    } else {
+        pattern
+    };
+    let pattern = if options.match_case {
+        pattern
+    } else {
+        format!("(?i){pattern}")
+    };
+    regex::Regex::new(&pattern).map_err(|error| format!("Invalid search: {error}"))
+}
+
+impl<'a> Model<'a> {
+    /// Returns the cells whose content, value or comment matches `text`, in the order of the
+    /// options. When searching the whole workbook the sheets are searched in order.
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # use ironcalc_base::find_types::FindOptions;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// model.set_user_input(0, 1, 1, "Apples".to_string())?;
+    /// model.set_user_input(0, 2, 1, "=LEN(\"apple\")".to_string())?;
+    /// model.evaluate();
+    /// let found: Vec<_> = model.find("apple", &FindOptions::default())?.collect();
+    /// assert_eq!(found.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find(
+        &self,
+        text: &str,
+        options: &FindOptions,
+    ) -> Result<impl Iterator<Item = CellReferenceIndex>, String> {
+        let regex = get_find_regex(text, options)?;
+        let sheets = match options.sheet {
+            Some(sheet) => {
+                self.workbook.worksheet(sheet)?;
+                vec![sheet]
+            }
+            None => (0..self.workbook.worksheets.len() as u32).collect(),
+        };
+        let mut found = Vec::new();
+        for sheet in sheets {
+            let mut cells = self.get_searchable_cells(sheet, options.look_in)?;
+            match options.order {
+                SearchOrder::ByRows => cells.sort_by_key(|(row, column, _)| (*row, *column)),
+                SearchOrder::ByColumns => cells.sort_by_key(|(row, column, _)| (*column, *row)),
+            }
+            found.extend(
+                cells
+                    .into_iter()
+                    .filter(|(_, _, text)| regex.is_match(text))
+                    .map(|(row, column, _)| CellReferenceIndex { sheet, row, column }),
+            );
+        }
+        Ok(found.into_iter())
+    }
+
+    // The non empty texts the search looks at, with their cells
+    fn get_searchable_cells(
+        &self,
+        sheet: u32,
+        look_in: FindIn,
+    ) -> Result<Vec<(i32, i32, String)>, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        let mut cells = Vec::new();
+        if look_in == FindIn::Comments {
+            for comment in &worksheet.comments {
+                let Some(reference) = parse_reference_a1(&comment.cell_ref) else {
+                    continue;
+                };
+                let mut text = comment.text.clone();
+                if let Some(thread) = &comment.thread {
+                    for reply in &thread.replies {
+                        text.push('\n');
+                        text.push_str(&reply.text);
+                    }
+                }
+                cells.push((reference.row, reference.column, text));
+            }
+            return Ok(cells);
+        }
+        for (row, data) in &worksheet.sheet_data {
+            for (column, cell) in data {
+                let text = match (look_in, cell) {
+                    (_, Cell::EmptyCell { .. }) => continue,
+                    // The content of a spill cell is the formula of its anchor
+                    (FindIn::Formulas, Cell::SpillCell { .. }) => continue,
+                    (FindIn::Formulas, _) => {
+                        self.get_localized_cell_content(sheet, *row, *column)?
+                    }
+                    _ => self.get_formatted_cell_value(sheet, *row, *column)?,
+                };
+                if !text.is_empty() {
+                    cells.push((*row, *column, text));
+                }
+            }
+        }
+        Ok(cells)
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// What a search looks at in each cell
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum FindIn {
+    /// The content of the cells, as the user typed it: formulas for cells with a formula
+    #[default]
+    Formulas,
+    /// The formatted values of the cells
+    Values,
+    /// The text of the comments of the cells, and their replies
+    Comments,
+}
+
+/// How the search text is interpreted
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum FindMode {
+    /// The text as is
+    #[default]
+    Text,
+    /// The text can have the wildcards `*` and `?`. Use `~` to escape them.
+    Wildcard,
+    /// A regular expression. Replacements can reference its groups, like `$1`.
+    Regex,
+}
+
+/// The order of the cells found
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SearchOrder {
+    /// Row by row, left to right
+    #[default]
+    ByRows,
+    /// Column by column, top to bottom
+    ByColumns,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(default)]
+pub struct FindOptions {
+    /// The sheet to search, all the sheets of the workbook if `None`
+    pub sheet: Option<u32>,
+    pub look_in: FindIn,
+    pub mode: FindMode,
+    pub match_case: bool,
+    /// Whether the text must match the whole cell and not only part of it
+    pub whole_cell: bool,
+    pub order: SearchOrder,
+}
@@ -4,9 +4,11 @@ use chrono::Duration;
 use chrono::Months;
 use chrono::NaiveDate;
 
+use crate::constants::DATE_1904_OFFSET;
 use crate::constants::EXCEL_DATE_BASE;
 use crate::constants::MAXIMUM_DATE_SERIAL_NUMBER;
 use crate::constants::MINIMUM_DATE_SERIAL_NUMBER;
+use crate::types::DateSystem;
 
 pub const DATE_OUT_OF_RANGE_MESSAGE: &str = "Out of range parameters for date";
 
@@ -36,6 +38,27 @@ pub fn from_excel_date(days: i64) -> Result<NaiveDate, String> {
     Ok(dt + Duration::days(days - 2))
 }
 
+impl DateSystem {
+    /// Converts a serial number of this date system into the equivalent serial number of the
+    /// 1900 date system, the one used by [from_excel_date] and [date_to_serial_number].
+    /// Negative serial numbers are not valid dates in either system and are left untouched.
+    pub fn to_1900_serial(self, serial: i64) -> i64 {
+        match self {
+            DateSystem::Date1904 if serial >= 0 => serial + DATE_1904_OFFSET,
+            _ => serial,
+        }
+    }
+
+    /// Converts a serial number of the 1900 date system into a serial number of this system.
+    /// Dates before 1904-01-01 result in negative numbers in the 1904 date system.
+    pub fn from_1900_serial(self, serial: i64) -> i64 {
+        match self {
+            DateSystem::Date1900 => serial,
+            DateSystem::Date1904 => serial - DATE_1904_OFFSET,
+        }
+    }
+}
+
 pub fn date_to_serial_number(day: u32, month: u32, year: i32) -> Result<i32, String> {
     match NaiveDate::from_ymd_opt(year, month, day) {
         Some(native_date) => Ok(convert_to_serial_number(native_date)),
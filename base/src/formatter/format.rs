@@ -1,10 +1,14 @@
 use chrono::Datelike;
 
-use crate::{locale::Locale, number_format::to_precision};
+use crate::{
+    locale::{get_locale, Locale},
+    number_format::to_precision,
+    types::DateSystem,
+};
 
 use super::{
     dates::{date_to_serial_number, from_excel_date},
-    parser::{ParsePart, Parser, TextToken},
+    parser::{Denominator, Fraction, ParsePart, Parser, TextToken},
 };
 
 pub struct Formatted {
@@ -56,7 +60,109 @@ fn use_group_separator(use_thousands: bool, digit_index: i32, group_sizes: &str)
     false
 }
 
+/// Splits a *positive* value into its integer part and a fraction (numerator, denominator).
+/// Without integer part in the format the fraction holds the whole value: 5/4 instead of 1 1/4.
+/// A fixed denominator is kept as is (8/16), otherwise we look for the closest fraction
+/// with as many digits in the denominator as the format allows.
+fn get_fraction(value: f64, fraction: &Fraction, has_integer_part: bool) -> (f64, i64, i64) {
+    let (mut integer, fract) = if has_integer_part {
+        (value.floor(), value.fract())
+    } else {
+        (0.0, value)
+    };
+    let (mut numerator, denominator) = match &fraction.denominator {
+        Denominator::Fixed(denominator) => {
+            let denominator = *denominator as i64;
+            ((fract * denominator as f64).round() as i64, denominator)
+        }
+        Denominator::Digits(digits) => {
+            let max_denominator = 10_i64.pow(digits.len().min(5) as u32) - 1;
+            let mut best = (fract.round() as i64, 1);
+            let mut best_error = (fract - best.0 as f64).abs();
+            for denominator in 2..=max_denominator {
+                let numerator = (fract * denominator as f64).round() as i64;
+                let error = (fract - numerator as f64 / denominator as f64).abs();
+                if error < best_error - f64::EPSILON {
+                    best = (numerator, denominator);
+                    best_error = error;
+                }
+            }
+            best
+        }
+    };
+    if has_integer_part && numerator == denominator {
+        integer += 1.0;
+        numerator = 0;
+    }
+    (integer, numerator, denominator)
+}
+
+/// Pads the digits of a numerator (to the left) or a denominator (to the right) for the
+/// placeholders of the format: '0' pads with zeros, '?' with spaces and '#' with nothing
+fn pad_fraction_digits(value: i64, kinds: &[char], is_numerator: bool) -> String {
+    let digits = value.to_string();
+    let missing = kinds.len().saturating_sub(digits.len());
+    let zeros = "0".repeat(kinds[..missing].iter().filter(|&&k| k == '0').count());
+    let spaces = " ".repeat(kinds[..missing].iter().filter(|&&k| k == '?').count());
+    if is_numerator {
+        format!("{spaces}{zeros}{digits}")
+    } else {
+        format!("{zeros}{digits}{spaces}")
+    }
+}
+
+/// The locale of a locale tag in a format code, a hex LCID like 409 (en-US) or a BCP-47 tag
+/// like fr-FR. Only tags of the languages we have a locale for are recognized.
+fn get_tagged_locale(tag: &str) -> Option<&'static Locale> {
+    if let Ok(locale) = get_locale(tag) {
+        return Some(locale);
+    }
+    let id = match u32::from_str_radix(tag, 16) {
+        // The upper bits select the calendar and numeral system, we just use the language
+        Ok(lcid) => match lcid & 0xFFFF {
+            0x0809 => "en-GB",
+            lcid => match lcid & 0x03FF {
+                0x07 => "de",
+                0x09 => "en",
+                0x0A => "es",
+                0x0C => "fr",
+                0x10 => "it",
+                _ => return None,
+            },
+        },
+        Err(_) => tag.split('-').next()?,
+    };
+    get_locale(id).ok()
+}
+
+/// Replaces the digits of the formatted text with the numerals of [DBNum1], [DBNum2] or [DBNum3]
+fn to_numeral_system(text: &str, numeral_system: Option<i32>) -> String {
+    let numerals: Vec<char> = match numeral_system {
+        Some(1) => "〇一二三四五六七八九".chars().collect(),
+        Some(2) => "〇壱弐参四伍六七八九".chars().collect(),
+        Some(3) => "０１２３４５６７８９".chars().collect(),
+        _ => return text.to_string(),
+    };
+    text.chars()
+        .map(|c| match c.to_digit(10) {
+            Some(d) => numerals[d as usize],
+            None => c,
+        })
+        .collect()
+}
+
+/// Formats `value_original` using the 1900 date system for dates and times
 pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Formatted {
+    format_number_with_date_system(value_original, format, locale, DateSystem::Date1900)
+}
+
+/// Formats `value_original` interpreting serial numbers as dates in `date_system`
+pub fn format_number_with_date_system(
+    value_original: f64,
+    format: &str,
+    locale: &Locale,
+    date_system: DateSystem,
+) -> Formatted {
     let mut parser = Parser::new(format);
     parser.parse();
     let parts = parser.parts;
@@ -121,57 +227,58 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
             color: None,
             error: Some("Problem parsing format string".to_owned()),
         },
-        ParsePart::General(..) => {
+        ParsePart::General(p) => {
             // FIXME: This is "General formatting"
             // We should have different codepaths for general formatting and errors
             let value_abs = value.abs();
-            if (1.0e-8..1.0e+11).contains(&value_abs) {
-                let mut text = format!("{value:.9}");
-                text = text.trim_end_matches('0').trim_end_matches('.').to_string();
-                if locale.numbers.symbols.decimal != "." {
-                    text = text.replace('.', &locale.numbers.symbols.decimal.to_string());
-                }
-                Formatted {
-                    text,
-                    color: None,
-                    error: None,
-                }
+            let mut text = if (1.0e-8..1.0e+11).contains(&value_abs) {
+                let text = format!("{value:.9}");
+                text.trim_end_matches('0').trim_end_matches('.').to_string()
+            } else if value_abs == 0.0 {
+                "0".to_string()
             } else {
-                if value_abs == 0.0 {
-                    return Formatted {
-                        text: "0".to_string(),
-                        color: None,
-                        error: None,
-                    };
-                }
                 let exponent = value_abs.log10().floor();
                 value /= 10.0_f64.powf(exponent);
                 let sign = if exponent < 0.0 { '-' } else { '+' };
                 let s = format!("{value:.5}");
-                let mut text = format!(
+                format!(
                     "{}E{}{:02}",
                     s.trim_end_matches('0').trim_end_matches('.'),
                     sign,
                     exponent.abs()
-                );
-                if locale.numbers.symbols.decimal != "." {
-                    text = text.replace('.', &locale.numbers.symbols.decimal.to_string());
-                }
-                Formatted {
-                    text,
-                    color: None,
-                    error: None,
-                }
+                )
+            };
+            if locale.numbers.symbols.decimal != "." {
+                text = text.replace('.', &locale.numbers.symbols.decimal.to_string());
+            }
+            Formatted {
+                text: to_numeral_system(&text, p.numeral_system),
+                color: None,
+                error: None,
             }
         }
         ParsePart::Date(p) => {
             let tokens = &p.tokens;
             let mut text = "".to_string();
-            let time_fract = value.fract();
-            let hours = (time_fract * 24.0).floor();
-            let minutes = ((time_fract * 24.0 - hours) * 60.0).floor();
-            let seconds = ((((time_fract * 24.0 - hours) * 60.0) - minutes) * 60.0).round();
-            let date = from_excel_date(value as i64).ok();
+            // The value is rounded to the precision of the seconds before it is split,
+            // so 23:59:59.999 is 00:00:00.00 of the next day and not 24:00:00.00
+            let scale = 10.0_f64.powi(p.second_precision);
+            let total = (value * 86400.0 * scale).round();
+            let total_seconds = (total / scale).floor();
+            // The fractions of a second as an integer with second_precision digits
+            let second_fraction = (total - total_seconds * scale) as i64;
+            let days = (total_seconds / 86400.0).floor();
+            let day_seconds = total_seconds - days * 86400.0;
+            let hours = (day_seconds / 3600.0).floor();
+            let minutes = ((day_seconds / 60.0) % 60.0).floor();
+            let seconds = day_seconds % 60.0;
+            // Names of months and days in the language of [$-409] if there is one
+            let date_locale = p
+                .locale
+                .as_deref()
+                .and_then(get_tagged_locale)
+                .unwrap_or(locale);
+            let date = from_excel_date(date_system.to_1900_serial(days as i64)).ok();
             for token in tokens {
                 match token {
                     TextToken::Literal(c) => {
@@ -195,6 +302,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                     }
                     TextToken::Digit(_) => {}
                     TextToken::Period => {}
+                    TextToken::Fraction(_) => {}
                     TextToken::Day => match date {
                         Some(date) => {
                             let day = date.day() as usize;
@@ -237,7 +345,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                         if day == 7 {
                             day = 0;
                         }
-                        text = format!("{}{}", text, locale.dates.day_names_short[day]);
+                        text = format!("{}{}", text, date_locale.dates.day_names_short[day]);
                     }
                     TextToken::DayName => {
                         let date = match date {
@@ -254,7 +362,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                         if day == 7 {
                             day = 0;
                         }
-                        text = format!("{}{}", text, locale.dates.day_names[day]);
+                        text = format!("{}{}", text, date_locale.dates.day_names[day]);
                     }
                     TextToken::Month => {
                         let date = match date {
@@ -296,7 +404,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                             }
                         };
                         let month = date.month() as usize;
-                        text = format!("{}{}", text, locale.dates.months_short[month - 1]);
+                        text = format!("{}{}", text, date_locale.dates.months_short[month - 1]);
                     }
                     TextToken::MonthName => {
                         let date = match date {
@@ -310,7 +418,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                             }
                         };
                         let month = date.month() as usize;
-                        text = format!("{}{}", text, locale.dates.months[month - 1]);
+                        text = format!("{}{}", text, date_locale.dates.months[month - 1]);
                     }
                     TextToken::MonthLetter => {
                         let date = match date {
@@ -324,7 +432,7 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                             }
                         };
                         let month = date.month() as usize;
-                        let months_letter = &locale.dates.months_letter[month - 1];
+                        let months_letter = &date_locale.dates.months_letter[month - 1];
                         text = format!("{text}{months_letter}");
                     }
                     TextToken::YearShort => {
@@ -396,44 +504,68 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                         text = format!("{text}{minute:02}");
                     }
                     TextToken::ElapsedHour => {
-                        let hour = (value * 24.0).floor() as i32;
+                        let hour = (total_seconds / 3600.0).floor() as i32;
                         text = format!("{text}{hour}");
                     }
                     TextToken::ElapsedHourPadded => {
-                        let hour = (value * 24.0).floor() as i32;
+                        let hour = (total_seconds / 3600.0).floor() as i32;
                         text = format!("{text}{hour:02}");
                     }
                     TextToken::ElapsedMinute => {
-                        let minute = (value * 24.0 * 60.0).floor() as i32;
+                        let minute = (total_seconds / 60.0).floor() as i32;
                         text = format!("{text}{minute}");
                     }
                     TextToken::ElapsedMinutePadded => {
-                        let minute = (value * 24.0 * 60.0).floor() as i32;
+                        let minute = (total_seconds / 60.0).floor() as i32;
                         text = format!("{text}{minute:02}");
                     }
                     TextToken::ElapsedSecond => {
-                        let second = (value * 24.0 * 60.0 * 60.0).floor() as i32;
+                        let second = total_seconds as i32;
                         text = format!("{text}{second}");
                     }
                     TextToken::ElapsedSecondPadded => {
-                        let second = (value * 24.0 * 60.0 * 60.0).floor() as i32;
+                        let second = total_seconds as i32;
                         text = format!("{text}{second:02}");
                     }
+                    TextToken::FractionalSecond(digits) => {
+                        let fraction = format!(
+                            "{second_fraction:0width$}",
+                            width = p.second_precision as usize
+                        );
+                        text = format!(
+                            "{text}{}{}",
+                            locale.numbers.symbols.decimal,
+                            &fraction[..*digits as usize]
+                        );
+                    }
                 }
             }
             Formatted {
-                text,
+                text: to_numeral_system(&text, p.numeral_system),
                 color: p.color,
                 error: None,
             }
         }
         ParsePart::Number(p) => {
             let mut text = "".to_string();
-            if let Some(c) = p.currency {
-                text = format!("{c}");
-            }
             let tokens = &p.tokens;
             value = value * 100.0_f64.powi(p.percent) / (1000.0_f64.powi(p.comma));
+            // In a fraction the integer part is formatted with the digits of the format and the
+            // rest with the numerator and denominator of the Fraction token
+            let mut fraction_parts = None;
+            let mut fraction_is_negative = false;
+            if p.fraction {
+                if let Some(fraction) = tokens.iter().find_map(|token| match token {
+                    TextToken::Fraction(fraction) => Some(fraction),
+                    _ => None,
+                }) {
+                    let (integer, numerator, denominator) =
+                        get_fraction(value.abs(), fraction, p.digit_count > 0);
+                    fraction_is_negative = value < 0.0 && (integer > 0.0 || numerator > 0);
+                    fraction_parts = Some((integer, numerator, denominator));
+                    value = integer;
+                }
+            }
             // p.precision is the number of significant digits _after_ the decimal point
             value = to_precision(
                 value,
@@ -464,6 +596,10 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
             let mut int_part: Vec<char> = format!("{}", int_number).chars().collect();
             if int_number as i64 == 0 {
                 int_part = vec![];
+                // A zero is written as "0" even if the format has "# ?/?"
+                if let Some((_, 0, _)) = fraction_parts {
+                    int_part = vec!['0'];
+                }
             }
             let fract_part = get_fract_part(value_abs, p.precision, int_part.len());
             // ln is the number of digits of the integer part of the value
@@ -478,7 +614,11 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
             let group_separator = symbols.group.to_owned();
             let decimal_separator = symbols.decimal.to_owned();
             // There probably are better ways to check if a number at a given precision is negative :/
-            let is_negative = value < -(10.0_f64.powf(-(p.precision as f64)));
+            let is_negative = if p.fraction {
+                fraction_is_negative
+            } else {
+                value < -(10.0_f64.powf(-(p.precision as f64)))
+            };
             let mut needs_period = false;
 
             for token in tokens {
@@ -502,6 +642,31 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                     TextToken::Raw => {
                         text = format!("{text}{value}");
                     }
+                    TextToken::Fraction(fraction) => {
+                        let (_, numerator, denominator) = fraction_parts.unwrap_or((0.0, 0, 1));
+                        let denominator_kinds = match &fraction.denominator {
+                            Denominator::Digits(kinds) => kinds.clone(),
+                            Denominator::Fixed(d) => vec!['?'; d.to_string().len()],
+                        };
+                        if numerator == 0 && p.digit_count > 0 {
+                            // 2 is written as "2    " with # ?/?
+                            let width = fraction.numerator.len() + 1 + denominator_kinds.len();
+                            text = format!("{text}{}", " ".repeat(width));
+                        } else {
+                            if is_negative && p.digit_count == 0 {
+                                text = format!("-{text}");
+                            }
+                            let numerator =
+                                pad_fraction_digits(numerator, &fraction.numerator, true);
+                            let denominator = match fraction.denominator {
+                                Denominator::Fixed(d) => d.to_string(),
+                                Denominator::Digits(_) => {
+                                    pad_fraction_digits(denominator, &denominator_kinds, false)
+                                }
+                            };
+                            text = format!("{text}{numerator}/{denominator}");
+                        }
+                    }
                     TextToken::Period => {
                         // if !fract_part.is_empty() &&  {
                         //     text = format!("{text}{decimal_separator}");
@@ -644,10 +809,11 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                     TextToken::ElapsedMinutePadded => {}
                     TextToken::ElapsedSecond => {}
                     TextToken::ElapsedSecondPadded => {}
+                    TextToken::FractionalSecond(_) => {}
                 }
             }
             Formatted {
-                text,
+                text: to_numeral_system(&text, p.numeral_system),
                 color: p.color,
                 error: None,
             }
@@ -745,7 +911,11 @@ fn parse_year(year_str: &str) -> Result<(i32, String), String> {
 // NOTE 1: The separator has to be the same
 // NOTE 2: In some engines "2/3" is implemented ad "2/March of the present year"
 // NOTE 3: I did not implement the "short date"
-pub(crate) fn parse_date(value: &str, locale: &Locale) -> Result<(i32, String), String> {
+pub(crate) fn parse_date(
+    value: &str,
+    locale: &Locale,
+    date_system: DateSystem,
+) -> Result<(i32, String), String> {
     let separator = if value.contains('/') {
         '/'
     } else if value.contains('-') {
@@ -787,9 +957,12 @@ pub(crate) fn parse_date(value: &str, locale: &Locale) -> Result<(i32, String),
     let (month, month_format) = parse_month(month_str, locale)?;
     let (year, year_format) = parse_year(year_str)?;
     let serial_number = match date_to_serial_number(day, month, year) {
-        Ok(n) => n,
+        Ok(n) => date_system.from_1900_serial(n as i64) as i32,
         Err(_) => return Err("Not a valid date".to_string()),
     };
+    if serial_number < 0 {
+        return Err("Not a valid date".to_string());
+    }
     if is_iso_date {
         Ok((
             serial_number,
@@ -817,6 +990,7 @@ pub(crate) fn parse_formatted_number(
     original: &str,
     currencies: &[&str],
     locale: &Locale,
+    date_system: DateSystem,
 ) -> Result<(f64, Option<String>), String> {
     let value = original.trim();
     let scientific_format = "0.00E+00";
@@ -875,7 +1049,7 @@ pub(crate) fn parse_formatted_number(
     }
 
     // check if it is a date. NOTE: we don't trim the original here
-    if let Ok((serial_number, format)) = parse_date(original, locale) {
+    if let Ok((serial_number, format)) = parse_date(original, locale, date_system) {
         return Ok((serial_number as f64, Some(format)));
     }
 
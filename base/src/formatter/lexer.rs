@@ -8,10 +8,12 @@ pub struct Lexer {
 
 #[derive(PartialEq, Debug)]
 pub enum Token {
-    Color(i32),              // [Red] or [Color 23]
-    Condition(Compare, f64), // [<=100] (Comparator, number)
-    Currency(char),          // [$€] ($ currency symbol)
+    Color(i32),                       // [Red] or [Color 23]
+    Condition(Compare, f64),          // [<=100] (Comparator, number)
+    Currency(String, Option<String>), // [$€] or [$€-407] (currency symbol and locale tag)
+    NumeralSystem(i32),               // [DBNum1], [DBNum2] or [DBNum3]
     Literal(char), // €, $, (, ), /, :, +, -, ^, ', {, }, <, =, !, ~, > and space or escaped \X
+    Denominator(i32), // /16 (a fixed denominator of a fraction)
     Spacer(char),  // *X
     Ghost(char),   // _X
     Text(String),  // "Text"
@@ -283,6 +285,57 @@ impl Lexer {
         Some(token)
     }
 
+    // Consume the currency and locale tag after '[$': [$€], [$€-407], [$-409] or [$$-en-US].
+    // The locale is either a BCP-47 tag or a hex LCID (old format).
+    fn consume_currency(&mut self) -> Option<Token> {
+        let mut symbol = "".to_string();
+        let mut locale = None;
+        loop {
+            match self.read_next_char()? {
+                ']' => return Some(Token::Currency(symbol, locale)),
+                '-' if locale.is_none() => locale = Some("".to_string()),
+                c => match locale {
+                    Some(ref mut tag) => tag.push(c),
+                    None => symbol.push(c),
+                },
+            }
+        }
+    }
+
+    // Consume a numeral system like [DBNum1]. We are called right after '['
+    fn consume_numeral_system(&mut self) -> Option<i32> {
+        let start_pos = self.position;
+        let name: String = self.chars[start_pos..].iter().take(5).collect();
+        if !name.eq_ignore_ascii_case("dbnum") {
+            return None;
+        }
+        self.position += 5;
+        if let (Some(n), Some(']')) = (self.read_next_char(), self.read_next_char()) {
+            if let Some(n @ 1..=3) = n.to_digit(10) {
+                return Some(n as i32);
+            }
+        }
+        self.position = start_pos;
+        None
+    }
+
+    // Consume the digits of a fixed denominator after '/', like 16 in # ??/16
+    fn consume_denominator(&mut self) -> Option<i32> {
+        match self.peek_char() {
+            Some('1'..='9') => {}
+            _ => return None,
+        }
+        let mut chars = "".to_string();
+        while let Some(c) = self.peek_char() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            chars.push(c);
+            self.read_next_char();
+        }
+        chars.parse::<i32>().ok()
+    }
+
     fn consume_color(&mut self) -> Option<i32> {
         let colors = [
             "black", "white", "red", "green", "blue", "yellow", "magenta",
@@ -323,8 +376,12 @@ impl Lexer {
         let ch = self.read_next_char();
         match ch {
             Some(x) => match x {
-                '$' | '€' | '(' | ')' | '/' | ':' | '+' | '-' | '^' | '\'' | '{' | '}' | '<'
-                | '=' | '!' | '~' | '>' | ' ' => Token::Literal(x),
+                '/' => match self.consume_denominator() {
+                    Some(denominator) => Token::Denominator(denominator),
+                    None => Token::Literal('/'),
+                },
+                '$' | '€' | '(' | ')' | ':' | '+' | '-' | '^' | '\'' | '{' | '}' | '<' | '='
+                | '!' | '~' | '>' | ' ' => Token::Literal(x),
                 '?' => Token::QuestionMark,
                 ';' => Token::Separator,
                 '#' => Token::Sharp,
@@ -344,32 +401,14 @@ impl Lexer {
                                 Token::ILLEGAL
                             }
                         } else if c == '$' {
-                            // currency: [$<symbol>] or [$<symbol>-<locale>]
-                            // IronCalc ignores the currency/locale modifiers for now
-                            // They are of two types:
-                            //  * BCP-47 locale tag (like [$€-fr-FR])
-                            //  * hex LCID (old format)
-                            //      Like [$$-409] (=1033 for en-US) or
-                            //           [$$-40C] (=1036 for fr-FR)
                             self.read_next_char();
-                            if let Some(currency) = self.read_next_char() {
-                                // ignore the locale part for now, just check if it ends with ']'
-                                if Some('-') == self.peek_char() {
-                                    self.read_next_char();
-
-                                    while let Some(c) = self.peek_char() {
-                                        if c == ']' {
-                                            break;
-                                        }
-                                        self.read_next_char();
-                                    }
-                                }
-                                if self.read_next_char() == Some(']') {
-                                    return Token::Currency(currency);
-                                }
+                            if let Some(token) = self.consume_currency() {
+                                return token;
                             }
                             self.set_error("Failed to parse currency");
                             Token::ILLEGAL
+                        } else if let Some(n) = self.consume_numeral_system() {
+                            Token::NumeralSystem(n)
                         } else {
                             // try bracketed (elapsed) time
                             if let Some(token) = self.consume_elapsed_time() {
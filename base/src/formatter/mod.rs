@@ -66,15 +66,14 @@ mod test;
 //  | $      234 |
 //  | $     1234 |
 // We can't do this easily in IronCalc
-//
-// 3.- You can use ?/? to format fractions in Excel (this is probably not too hard)
 
 // TOKENs
 // ======
 //
 // * Color [Red] or [Color 23] or [Color23]
 // * Conditions [<100]
-// * Currency [$€]
+// * Currency and locale [$€], [$€-407] or [$-409]. The locale sets the names of months and days
+// * Numeral systems [DBNum1], [DBNum2] and [DBNum3]
 // * Space _X when X is any given char
 // * A spacer of chars: *X where X is repeated as much as possible
 // * Literals: $, (, ), :, +, - and space
@@ -87,6 +86,7 @@ mod test;
 // * @ inserts raw text
 // * Scientific literals E+, E-, e+, e-
 // * . period. First one is the decimal point, subsequent are literals.
+// * Fractions: # ?/? or # ??/16. Without integer part (?/?) the fraction holds the whole number
 
 // d day of the month
 // dd day of the month (padded i.e 05)
@@ -97,6 +97,7 @@ mod test;
 // mmmmm First letter of the month
 // y or yy 2-digit year
 // yyy+ 4 digit year
+// ss.000 seconds with up to three decimal digits
 
 // References
 // ==========
@@ -6,6 +6,19 @@ pub struct Digit {
     pub number: NumberState, // 'i' | 'd' | 'e' (integer, decimal or exponent)
 }
 
+/// The denominator of a fraction: digit placeholders (# ?/??) or a fixed number (# ?/16)
+pub enum Denominator {
+    Digits(Vec<char>),
+    Fixed(i32),
+}
+
+/// The numerator and denominator of a fraction like ?/? or ??/16.
+/// The digits of the numerator are '#', '?' or '0'
+pub struct Fraction {
+    pub numerator: Vec<char>,
+    pub denominator: Denominator,
+}
+
 pub enum TextToken {
     Literal(char),
     Text(String),
@@ -15,6 +28,7 @@ pub enum TextToken {
     Raw,
     Digit(Digit),
     Period,
+    Fraction(Fraction),
     // Dates
     Day,
     DayPadded,
@@ -39,6 +53,7 @@ pub enum TextToken {
     ElapsedMinutePadded,
     ElapsedSecond,
     ElapsedSecondPadded,
+    FractionalSecond(i32), // .0, .00 or .000 after the seconds
     AMPM,
 }
 pub struct NumberPart {
@@ -53,18 +68,26 @@ pub struct NumberPart {
     pub is_scientific: bool,
     pub scientific_minus: bool,
     pub exponent_digit_count: i32,
-    pub currency: Option<char>,
+    pub fraction: bool,
+    pub numeral_system: Option<i32>,
 }
 
 pub struct DatePart {
     pub color: Option<i32>,
     pub use_ampm: bool,
     pub tokens: Vec<TextToken>,
+    /// Number of digits of the fractions of a second (ss.000)
+    pub second_precision: i32,
+    /// The locale tag of [$-409] or [$-fr-FR], for the names of months and days
+    pub locale: Option<String>,
+    pub numeral_system: Option<i32>,
 }
 
 pub struct ErrorPart {}
 
-pub struct GeneralPart {}
+pub struct GeneralPart {
+    pub numeral_system: Option<i32>,
+}
 
 pub enum ParsePart {
     Number(NumberPart),
@@ -118,6 +141,30 @@ impl ParsePart {
     }
 }
 
+fn digit_kind(token: &Token) -> Option<char> {
+    match token {
+        Token::Sharp => Some('#'),
+        Token::QuestionMark => Some('?'),
+        Token::Zero => Some('0'),
+        _ => None,
+    }
+}
+
+// An 'm' right before the seconds (like in mm:ss) are minutes, not months
+fn month_to_minute(tokens: &mut [TextToken]) {
+    let last = tokens
+        .iter_mut()
+        .rev()
+        .find(|token| !matches!(token, TextToken::Literal(_) | TextToken::Text(_)));
+    if let Some(token) = last {
+        match token {
+            TextToken::Month => *token = TextToken::Minute,
+            TextToken::MonthPadded => *token = TextToken::MinutePadded,
+            _ => {}
+        }
+    }
+}
+
 // Numbers:
 // [integer section][decimal point][fractional section][optional exponent]
 // So #,##0.00 is valid but 0.00#,## is not.
@@ -135,6 +182,18 @@ impl Parser {
         }
     }
 
+    // The digit tokens right before the '/' of a fraction are its numerator, not part of the
+    // integer part of the number
+    fn take_numerator(&self, tokens: &mut Vec<TextToken>, digit_count: &mut i32) -> Vec<char> {
+        let mut numerator = vec![];
+        while let Some(TextToken::Digit(digit)) = tokens.last() {
+            numerator.insert(0, digit.kind);
+            tokens.pop();
+            *digit_count -= 1;
+        }
+        numerator
+    }
+
     fn parse_part(&mut self) -> ParsePart {
         let mut token = self.lexer.next_token();
         let mut digit_count = 0;
@@ -155,11 +214,14 @@ impl Parser {
         let mut exponent_digit_count = 0;
         let mut number = NumberState::Integer;
         let mut index = 0;
-        let mut currency = None;
         let mut is_time = false;
+        let mut fraction = false;
+        let mut second_precision = 0;
+        let mut locale = None;
+        let mut numeral_system = None;
 
         while token != Token::EOF && token != Token::Separator {
-            let next_token = self.lexer.next_token();
+            let mut next_token = self.lexer.next_token();
             let token_is_digit = token.is_digit();
             is_number = is_number || token_is_digit;
             let next_token_is_digit = next_token.is_digit();
@@ -175,7 +237,7 @@ impl Parser {
             match token {
                 Token::General => {
                     if tokens.is_empty() {
-                        return ParsePart::General(GeneralPart {});
+                        return ParsePart::General(GeneralPart { numeral_system });
                     } else {
                         return ParsePart::Error(ErrorPart {});
                     }
@@ -195,6 +257,30 @@ impl Parser {
                     tokens.push(TextToken::Literal('%'));
                     percent += 1;
                 }
+                Token::Period
+                    if next_token == Token::Zero
+                        && matches!(
+                            tokens.last(),
+                            Some(
+                                TextToken::Second
+                                    | TextToken::SecondPadded
+                                    | TextToken::ElapsedSecond
+                                    | TextToken::ElapsedSecondPadded
+                            )
+                        ) =>
+                {
+                    // Fractions of a second: ss.000
+                    let mut digits = 0;
+                    while next_token == Token::Zero {
+                        digits += 1;
+                        next_token = self.lexer.next_token();
+                    }
+                    if digits > 3 {
+                        return ParsePart::Error(ErrorPart {});
+                    }
+                    second_precision = i32::max(second_precision, digits);
+                    tokens.push(TextToken::FractionalSecond(digits));
+                }
                 Token::Period => {
                     if is_number && !found_decimal_dot {
                         tokens.push(TextToken::Period);
@@ -213,8 +299,52 @@ impl Parser {
                 Token::Condition(cmp, value) => {
                     condition = Some((cmp, value));
                 }
-                Token::Currency(c) => {
-                    currency = Some(c);
+                Token::Currency(symbol, tag) => {
+                    if !symbol.is_empty() {
+                        tokens.push(TextToken::Text(symbol));
+                    }
+                    if tag.is_some() {
+                        locale = tag;
+                    }
+                }
+                Token::NumeralSystem(n) => {
+                    numeral_system = Some(n);
+                }
+                Token::Literal('/')
+                    if last_token_is_digit
+                        && next_token.is_digit()
+                        && !fraction
+                        && !found_decimal_dot
+                        && !is_scientific =>
+                {
+                    // A fraction with digit placeholders in the denominator: # ??/??
+                    let numerator = self.take_numerator(&mut tokens, &mut digit_count);
+                    let mut digits = vec![];
+                    while let Some(kind) = digit_kind(&next_token) {
+                        digits.push(kind);
+                        next_token = self.lexer.next_token();
+                    }
+                    fraction = true;
+                    tokens.push(TextToken::Fraction(Fraction {
+                        numerator,
+                        denominator: Denominator::Digits(digits),
+                    }));
+                }
+                Token::Denominator(denominator) => {
+                    if last_token_is_digit && !fraction && !found_decimal_dot && !is_scientific {
+                        // A fraction with a fixed denominator: # ??/16
+                        let numerator = self.take_numerator(&mut tokens, &mut digit_count);
+                        fraction = true;
+                        tokens.push(TextToken::Fraction(Fraction {
+                            numerator,
+                            denominator: Denominator::Fixed(denominator),
+                        }));
+                    } else {
+                        tokens.push(TextToken::Literal('/'));
+                        for c in denominator.to_string().chars() {
+                            tokens.push(TextToken::Literal(c));
+                        }
+                    }
                 }
                 Token::QuestionMark => {
                     tokens.push(TextToken::Digit(Digit {
@@ -322,11 +452,13 @@ impl Parser {
                 Token::Second => {
                     is_date = true;
                     is_time = true;
+                    month_to_minute(&mut tokens);
                     tokens.push(TextToken::Second);
                 }
                 Token::SecondPadded => {
                     is_date = true;
                     is_time = true;
+                    month_to_minute(&mut tokens);
                     tokens.push(TextToken::SecondPadded);
                 }
                 Token::AMPM => {
@@ -392,13 +524,16 @@ impl Parser {
             token = next_token;
         }
         if is_date {
-            if is_number {
+            if is_number || fraction {
                 return ParsePart::Error(ErrorPart {});
             }
             ParsePart::Date(DatePart {
                 color,
                 use_ampm,
                 tokens,
+                second_precision,
+                locale,
+                numeral_system,
             })
         } else {
             ParsePart::Number(NumberPart {
@@ -413,7 +548,8 @@ impl Parser {
                 is_scientific,
                 scientific_minus,
                 exponent_digit_count,
-                currency,
+                fraction,
+                numeral_system,
             })
         }
     }
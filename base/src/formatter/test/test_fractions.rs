@@ -0,0 +1,50 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::{
+    formatter::format::format_number,
+    locale::{get_locale, Locale},
+};
+
+fn get_default_locale() -> &'static Locale {
+    get_locale("en").unwrap()
+}
+
+#[test]
+fn variable_denominator() {
+    let locale = get_default_locale();
+    assert_eq!(format_number(1.25, "# ?/?", locale).text, "1 1/4");
+    assert_eq!(format_number(0.5, "# ?/?", locale).text, " 1/2");
+    assert_eq!(format_number(2.3333, "# ??/??", locale).text, "2  1/3 ");
+    assert_eq!(format_number(0.3125, "# ??/??", locale).text, "  5/16");
+    assert_eq!(format_number(2.0, "# ?/?", locale).text, "2    ");
+    assert_eq!(format_number(0.0, "# ?/?", locale).text, "0    ");
+    // Rounds up to the next integer
+    assert_eq!(format_number(1.99, "# ?/?", locale).text, "2    ");
+    assert_eq!(format_number(-1.25, "# ?/?", locale).text, "-1 1/4");
+}
+
+#[test]
+fn fixed_denominator() {
+    let locale = get_default_locale();
+    assert_eq!(format_number(1.5, "# ??/16", locale).text, "1  8/16");
+    assert_eq!(format_number(2.3, "# ?/4", locale).text, "2 1/4");
+    assert_eq!(format_number(0.37, "0 ?/100", locale).text, "0 37/100");
+}
+
+#[test]
+fn improper_fractions() {
+    let locale = get_default_locale();
+    assert_eq!(format_number(1.25, "?/?", locale).text, "5/4");
+    assert_eq!(format_number(-0.75, "?/?", locale).text, "-3/4");
+    assert_eq!(format_number(2.5, "??/8", locale).text, "20/8");
+}
+
+#[test]
+fn slash_in_dates_is_a_literal() {
+    let locale = get_default_locale();
+    // 2023-03-14
+    assert_eq!(
+        format_number(45_000.0, "d/m/yyyy", locale).text,
+        "15/3/2023"
+    );
+}
@@ -0,0 +1,66 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::{
+    formatter::format::format_number,
+    locale::{get_locale, Locale},
+};
+
+fn get_default_locale() -> &'static Locale {
+    get_locale("en").unwrap()
+}
+
+#[test]
+fn month_and_day_names() {
+    let locale = get_default_locale();
+    // Tuesday 2023-03-14
+    let value = 44_999.0;
+    assert_eq!(
+        format_number(value, "[$-407]dddd, d. mmmm yyyy", locale).text,
+        "Dienstag, 14. März 2023"
+    );
+    assert_eq!(
+        format_number(value, "[$-fr-FR]d mmmm yyyy", locale).text,
+        "14 mars 2023"
+    );
+    let de = get_locale("de").unwrap();
+    assert_eq!(
+        format_number(value, "[$-409]mmmm d, yyyy", de).text,
+        "March 14, 2023"
+    );
+    // Unknown locales use the locale of the workbook
+    assert_eq!(
+        format_number(value, "[$-F800]mmmm d, yyyy", locale).text,
+        "March 14, 2023"
+    );
+}
+
+#[test]
+fn currency_placement() {
+    let locale = get_default_locale();
+    assert_eq!(
+        format_number(1234.5, "#,##0.00 [$€-407]", locale).text,
+        "1,234.50 €"
+    );
+    assert_eq!(
+        format_number(1234.5, "[$CHF-807] #,##0.00", locale).text,
+        "CHF 1,234.50"
+    );
+    assert_eq!(format_number(-3.0, "[$$-409]0.00", locale).text, "-$3.00");
+}
+
+#[test]
+fn numeral_systems() {
+    let locale = get_default_locale();
+    assert_eq!(format_number(123.0, "[DBNum1]0", locale).text, "一二三");
+    assert_eq!(format_number(2024.0, "[DBNum2]0", locale).text, "弐〇弐四");
+    assert_eq!(format_number(12.5, "[DBNum3]0.0", locale).text, "１２.５");
+    assert_eq!(
+        format_number(123.0, "[DBNum1][$-804]General", locale).text,
+        "一二三"
+    );
+    assert_eq!(format_number(0.5, "[DBNum3]General", locale).text, "０.５");
+    assert_eq!(
+        format_number(1.0, "[DBNum4]0", locale).error,
+        Some("Problem parsing format string".to_string())
+    );
+}
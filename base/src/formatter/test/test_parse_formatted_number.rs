@@ -3,23 +3,24 @@
 use crate::{
     formatter::format::parse_formatted_number,
     locale::{get_default_locale, get_locale},
+    types::DateSystem,
 };
 
 const PARSE_ERROR_MSG: &str = "Could not parse number";
 
 fn parse(input: &str, currencies: &[&str]) -> Result<(f64, Option<String>), String> {
     let locale = get_default_locale();
-    parse_formatted_number(input, currencies, locale)
+    parse_formatted_number(input, currencies, locale, DateSystem::Date1900)
 }
 
 fn parse_de(input: &str, currencies: &[&str]) -> Result<(f64, Option<String>), String> {
     let locale = get_locale("de").unwrap();
-    parse_formatted_number(input, currencies, locale)
+    parse_formatted_number(input, currencies, locale, DateSystem::Date1900)
 }
 
 fn parse_fr(input: &str, currencies: &[&str]) -> Result<(f64, Option<String>), String> {
     let locale = get_locale("fr").unwrap();
-    parse_formatted_number(input, currencies, locale)
+    parse_formatted_number(input, currencies, locale, DateSystem::Date1900)
 }
 
 #[test]
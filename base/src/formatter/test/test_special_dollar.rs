@@ -9,12 +9,28 @@ use crate::formatter::lexer::{Lexer, Token};
 fn dollar_en_us() {
     let mut lexer = Lexer::new("[$$-en-US]");
     let token = lexer.next_token();
-    assert!(matches!(token, Token::Currency('$')));
+    assert_eq!(
+        token,
+        Token::Currency("$".to_string(), Some("en-US".to_string()))
+    );
 }
 
 #[test]
 fn dollar_409() {
     let mut lexer = Lexer::new("[$$-409]");
     let token = lexer.next_token();
-    assert!(matches!(token, Token::Currency('$')));
+    assert_eq!(
+        token,
+        Token::Currency("$".to_string(), Some("409".to_string()))
+    );
+}
+
+#[test]
+fn locale_without_currency() {
+    let mut lexer = Lexer::new("[$-407]");
+    let token = lexer.next_token();
+    assert_eq!(
+        token,
+        Token::Currency("".to_string(), Some("407".to_string()))
+    );
 }
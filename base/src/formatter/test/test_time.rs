@@ -70,3 +70,49 @@ fn elapsed_hour_padded() {
     let formatted = format_number(value, format, locale);
     assert_eq!(formatted.text, "02:24:00");
 }
+
+#[test]
+fn fractional_seconds() {
+    let locale = get_default_locale();
+    // 01:02:03.456
+    let value = (3723.456) / 86400.0;
+    assert_eq!(
+        format_number(value, "hh:mm:ss.000", locale).text,
+        "01:02:03.456"
+    );
+    assert_eq!(format_number(value, "mm:ss.0", locale).text, "02:03.5");
+    assert_eq!(format_number(value, "[mm]:ss.00", locale).text, "62:03.46");
+    // Rounding carries over to the seconds
+    let value = 59.9996 / 86400.0;
+    assert_eq!(format_number(value, "mm:ss.000", locale).text, "01:00.000");
+    let de = get_locale("de").unwrap();
+    assert_eq!(format_number(3.5 / 86400.0, "ss.0", de).text, "03,5");
+    assert_eq!(
+        format_number(0.0, "ss.0000", locale).error,
+        Some("Problem parsing format string".to_string())
+    );
+}
+
+#[test]
+fn rounding_carries_into_the_date() {
+    let locale = get_default_locale();
+    // 2023-03-15 23:59:59.996
+    let value = 45000.9999999537;
+    assert_eq!(
+        format_number(value, "yyyy-mm-dd hh:mm:ss.00", locale).text,
+        "2023-03-16 00:00:00.00"
+    );
+    assert_eq!(
+        format_number(value, "yyyy-mm-dd hh:mm:ss", locale).text,
+        "2023-03-16 00:00:00"
+    );
+    assert_eq!(
+        format_number(value, "yyyy-mm-dd hh:mm:ss.000", locale).text,
+        "2023-03-15 23:59:59.996"
+    );
+    // Only elapsed hours go past 23
+    assert_eq!(
+        format_number(value - 45000.0, "[h]:mm:ss.00", locale).text,
+        "24:00:00.00"
+    );
+}
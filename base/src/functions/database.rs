@@ -543,7 +543,7 @@ impl<'a> Model<'a> {
             error @ CalcResult::Error { .. } => {
                 return Err(error);
             }
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                 return Err(CalcResult::Error {
                     error: Error::NIMPL,
                     origin: cell,
@@ -585,6 +585,7 @@ impl<'a> Model<'a> {
                 }
                 CalcResult::Error { .. }
                 | CalcResult::Range { .. }
+                | CalcResult::MultiRange(_)
                 | CalcResult::EmptyCell
                 | CalcResult::EmptyArg
                 | CalcResult::Array(_)
@@ -749,9 +750,10 @@ impl<'a> Model<'a> {
             }
             CalcResult::EmptyCell | CalcResult::EmptyArg => "".to_string(),
             CalcResult::Error { .. } => return false,
-            CalcResult::Range { .. } | CalcResult::Array(_) | CalcResult::Lambda(_) => {
-                return false
-            }
+            CalcResult::Range { .. }
+            | CalcResult::MultiRange(_)
+            | CalcResult::Array(_)
+            | CalcResult::Lambda(_) => return false,
         };
 
         // Detect operator prefix
@@ -765,7 +767,8 @@ impl<'a> Model<'a> {
             }
         }
 
-        let formatted_number = parse_formatted_number(&criteria, &[], self.locale);
+        let formatted_number =
+            parse_formatted_number(&criteria, &[], self.locale, self.date_system());
 
         match op {
             ">" | ">=" | "<" | "<=" => {
@@ -143,14 +143,13 @@ macro_rules! date_part_fn {
             // Convert the serial number to a NaiveDate and return the requested
             // component. Broadcasts element-wise over ranges/arrays. Uses the free
             // function from_excel_date to avoid borrowing self in the closure.
-            self.apply_number_unary(
-                &args[0],
-                cell,
-                |f| match from_excel_date(f.floor() as i64) {
+            let date_system = self.date_system();
+            self.apply_number_unary(&args[0], cell, |f| {
+                match from_excel_date(date_system.to_1900_serial(f.floor() as i64)) {
                     Ok(date) => Ok(date.$method() as f64),
                     Err(_) => Err(Error::NUM),
-                },
-            )
+                }
+            })
         }
     };
 }
@@ -188,6 +187,7 @@ use crate::formatter::dates::date_to_serial_number;
 use crate::formatter::dates::permissive_date_to_serial_number;
 use crate::formatter::dates::DATE_OUT_OF_RANGE_MESSAGE;
 use crate::number_format::to_precision;
+use crate::types::DateSystem;
 use crate::{
     calc_result::CalcResult,
     constants::EXCEL_DATE_BASE,
@@ -587,8 +587,8 @@ impl<'a> Model<'a> {
         let result = self.evaluate_node_in_context(node, cell);
         match result {
             CalcResult::Number(f) => Ok(f.floor() as i64),
-            CalcResult::String(s) => match parse_datevalue_text(&s) {
-                Ok(n) => Ok(n as i64),
+            CalcResult::String(s) => match self.parse_datevalue(&s) {
+                Ok(n) => Ok(n),
                 Err(_) => Err(CalcResult::Error {
                     error: Error::VALUE,
                     origin: cell,
@@ -603,6 +603,7 @@ impl<'a> Model<'a> {
                 }
             }
             error @ CalcResult::Error { .. } => Err(error),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { .. } => Err(CalcResult::Error {
                 error: Error::NIMPL,
                 origin: cell,
@@ -680,7 +681,17 @@ impl<'a> Model<'a> {
             year += 1;
         }
         match date_to_serial_number(1, month, year) {
-            Ok(serial_number) => CalcResult::Number(serial_number as f64 - 1.0),
+            Ok(serial_number) => {
+                let serial_number = self.date_system().from_1900_serial(serial_number as i64) - 1;
+                if serial_number < 0 {
+                    return CalcResult::Error {
+                        error: Error::NUM,
+                        origin: cell,
+                        message: DATE_OUT_OF_RANGE_MESSAGE.to_string(),
+                    };
+                }
+                CalcResult::Number(serial_number as f64)
+            }
             Err(message) => CalcResult::Error {
                 error: Error::NUM,
                 origin: cell,
@@ -709,7 +720,8 @@ impl<'a> Model<'a> {
             Err(e) => return e,
         };
 
-        fn date_node(year_f: f64, month_f: f64, day_f: f64) -> ArrayNode {
+        let date_system = self.date_system();
+        let date_node = |year_f: f64, month_f: f64, day_f: f64| -> ArrayNode {
             let year = year_f.floor() as i32;
             if year < 0 {
                 return ArrayNode::Error(Error::NUM);
@@ -719,10 +731,13 @@ impl<'a> Model<'a> {
                 month_f.floor() as i32,
                 year,
             ) {
-                Ok(n) => ArrayNode::Number(n as f64),
+                Ok(n) => match date_system.from_1900_serial(n as i64) {
+                    n if n < 0 => ArrayNode::Error(Error::NUM),
+                    n => ArrayNode::Number(n as f64),
+                },
                 Err(_) => ArrayNode::Error(Error::NUM),
             }
-        }
+        };
 
         match (&year_na, &month_na, &day_na) {
             (NumberOrArray::Number(yr), NumberOrArray::Number(mo), NumberOrArray::Number(dy)) => {
@@ -827,8 +842,8 @@ impl<'a> Model<'a> {
             date - Months::new(months_abs)
         };
 
-        let serial_number = native_date.num_days_from_ce() - EXCEL_DATE_BASE;
-        if serial_number < MINIMUM_DATE_SERIAL_NUMBER {
+        let serial_number = self.date_serial(native_date);
+        if serial_number < self.minimum_date_serial() {
             return CalcResult::Error {
                 error: Error::NUM,
                 origin: cell,
@@ -991,7 +1006,7 @@ impl<'a> Model<'a> {
         serial: i64,
         cell: CellReferenceIndex,
     ) -> Result<chrono::NaiveDate, CalcResult> {
-        match from_excel_date(serial) {
+        match from_excel_date(self.date_system().to_1900_serial(serial)) {
             Ok(date) => Ok(date),
             Err(_) => Err(CalcResult::Error {
                 error: Error::NUM,
@@ -1001,6 +1016,30 @@ impl<'a> Model<'a> {
         }
     }
 
+    // Serial number of `date` in the date system of the workbook
+    fn date_serial(&self, date: chrono::NaiveDate) -> i64 {
+        let serial = date.num_days_from_ce() - EXCEL_DATE_BASE;
+        self.date_system().from_1900_serial(serial as i64)
+    }
+
+    // Smallest serial number that is a valid date in the date system of the workbook
+    fn minimum_date_serial(&self) -> i64 {
+        match self.date_system() {
+            DateSystem::Date1900 => MINIMUM_DATE_SERIAL_NUMBER as i64,
+            DateSystem::Date1904 => 0,
+        }
+    }
+
+    // Parses a date text (as in DATEVALUE) into a serial number of the workbook date system
+    fn parse_datevalue(&self, value: &str) -> Result<i64, String> {
+        let serial = parse_datevalue_text(value)?;
+        let serial = self.date_system().from_1900_serial(serial as i64);
+        if serial < 0 {
+            return Err("Not a valid date".to_string());
+        }
+        Ok(serial)
+    }
+
     fn weekend_mask(
         &mut self,
         node: Option<&Node>,
@@ -1065,6 +1104,7 @@ impl<'a> Model<'a> {
                 "Invalid weekend".to_string(),
             )),
             e @ CalcResult::Error { .. } => Err(e),
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { .. } => Err(CalcResult::Error {
                 error: Error::VALUE,
                 origin: cell,
@@ -1162,7 +1202,10 @@ impl<'a> Model<'a> {
             None => &self.tz,
         };
         match crate::tz::excel_serial_for_now(tz) {
-            Some(serial) => CalcResult::Number(serial.floor()),
+            Some(serial) => {
+                let days = self.date_system().from_1900_serial(serial.floor() as i64);
+                CalcResult::Number(days as f64)
+            }
             None => CalcResult::Error {
                 error: Error::ERROR,
                 origin: cell,
@@ -1202,7 +1245,10 @@ impl<'a> Model<'a> {
             None => &self.tz,
         };
         match crate::tz::excel_serial_for_now(tz) {
-            Some(serial) => CalcResult::Number(serial),
+            Some(serial) => {
+                let days = self.date_system().from_1900_serial(serial.floor() as i64);
+                CalcResult::Number(days as f64 + serial.fract())
+            }
             None => CalcResult::Error {
                 error: Error::ERROR,
                 origin: cell,
@@ -1276,7 +1322,7 @@ impl<'a> Model<'a> {
             return CalcResult::new_args_number_error(cell);
         }
         match self.evaluate_node_in_context(&args[0], cell) {
-            CalcResult::String(s) => match parse_datevalue_text(&s) {
+            CalcResult::String(s) => match self.parse_datevalue(&s) {
                 Ok(n) => CalcResult::Number(n as f64),
                 Err(_) => CalcResult::Error {
                     error: Error::VALUE,
@@ -1293,13 +1339,14 @@ impl<'a> Model<'a> {
                 }
             }
             err @ CalcResult::Error { .. } => err,
-            CalcResult::Range { .. } | CalcResult::Array(_) | CalcResult::Lambda(_) => {
-                CalcResult::Error {
-                    error: Error::NIMPL,
-                    origin: cell,
-                    message: "Arrays not supported yet".to_string(),
-                }
-            }
+            CalcResult::Range { .. }
+            | CalcResult::MultiRange(_)
+            | CalcResult::Array(_)
+            | CalcResult::Lambda(_) => CalcResult::Error {
+                error: Error::NIMPL,
+                origin: cell,
+                message: "Arrays not supported yet".to_string(),
+            },
             CalcResult::EmptyCell | CalcResult::EmptyArg => CalcResult::Number(0.0),
         }
     }
@@ -1451,7 +1498,10 @@ impl<'a> Model<'a> {
         // Excel accepts any serial in 0..=MAXIMUM (a 0 serial is 1900-01-00) and
         // reports #NUM! otherwise. DAYS works directly on serials, so the dates
         // themselves are never materialised.
-        let range = 0..=MAXIMUM_DATE_SERIAL_NUMBER as i64;
+        let maximum = self
+            .date_system()
+            .from_1900_serial(MAXIMUM_DATE_SERIAL_NUMBER as i64);
+        let range = 0..=maximum;
         if !range.contains(&start_serial) || !range.contains(&end_serial) {
             return CalcResult::new_error(
                 Error::NUM,
@@ -1491,11 +1541,12 @@ impl<'a> Model<'a> {
                 "Out of range parameters for date".to_string(),
             )
         };
-        let start = match excel_serial_to_ymd(start_serial) {
+        let date_system = self.date_system();
+        let start = match excel_serial_to_ymd(date_system.to_1900_serial(start_serial)) {
             Some(ymd) => ymd,
             None => return out_of_range(),
         };
-        let end = match excel_serial_to_ymd(end_serial) {
+        let end = match excel_serial_to_ymd(date_system.to_1900_serial(end_serial)) {
             Some(ymd) => ymd,
             None => return out_of_range(),
         };
@@ -1520,8 +1571,10 @@ impl<'a> Model<'a> {
             1
         };
         // Broadcasts element-wise over a range/array date argument.
+        let date_system = self.date_system();
         self.apply_number_unary(&args[0], cell, move |serial| {
-            let date = from_excel_date(serial.floor() as i64).map_err(|_| Error::NUM)?;
+            let serial = date_system.to_1900_serial(serial.floor() as i64);
+            let date = from_excel_date(serial).map_err(|_| Error::NUM)?;
             weekday_number(date, return_type)
         })
     }
@@ -1586,8 +1639,10 @@ impl<'a> Model<'a> {
             return CalcResult::new_args_number_error(cell);
         }
         // Broadcasts element-wise over a range/array date argument.
+        let date_system = self.date_system();
         self.apply_number_unary(&args[0], cell, |serial| {
-            let date = from_excel_date(serial.floor() as i64).map_err(|_| Error::NUM)?;
+            let serial = date_system.to_1900_serial(serial.floor() as i64);
+            let date = from_excel_date(serial).map_err(|_| Error::NUM)?;
             Ok(date.iso_week().week() as f64)
         })
     }
@@ -1638,7 +1693,7 @@ impl<'a> Model<'a> {
                 }
             }
         }
-        let serial = date.num_days_from_ce() - EXCEL_DATE_BASE;
+        let serial = self.date_serial(date);
         CalcResult::Number(serial as f64)
     }
 
@@ -1650,16 +1705,19 @@ impl<'a> Model<'a> {
         let mut holiday_set = std::collections::HashSet::new();
 
         if let Some(arg) = arg_option {
-            self.collect_serial_numbers(arg, cell, |serial| match from_excel_date(serial) {
-                Ok(date) => {
-                    holiday_set.insert(date);
-                    Ok(())
+            let date_system = self.date_system();
+            self.collect_serial_numbers(arg, cell, |serial| {
+                match from_excel_date(date_system.to_1900_serial(serial)) {
+                    Ok(date) => {
+                        holiday_set.insert(date);
+                        Ok(())
+                    }
+                    Err(_) => Err(CalcResult::Error {
+                        error: Error::NUM,
+                        origin: cell,
+                        message: "Invalid holiday date".to_string(),
+                    }),
                 }
-                Err(_) => Err(CalcResult::Error {
-                    error: Error::NUM,
-                    origin: cell,
-                    message: "Invalid holiday date".to_string(),
-                }),
             })?;
         }
 
@@ -1719,7 +1777,7 @@ impl<'a> Model<'a> {
                 }
             }
         }
-        let serial = date.num_days_from_ce() - EXCEL_DATE_BASE;
+        let serial = self.date_serial(date);
         CalcResult::Number(serial as f64)
     }
 
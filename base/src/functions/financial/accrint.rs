@@ -377,15 +377,17 @@ impl<'a> Model<'a> {
             );
         }
 
-        let issue = match from_excel_date(issue_serial) {
+        let issue = match from_excel_date(self.date_system().to_1900_serial(issue_serial)) {
             Ok(d) => d,
             Err(e) => return CalcResult::new_error(Error::NUM, cell, e),
         };
-        let first_interest = match from_excel_date(first_interest_serial) {
-            Ok(d) => d,
-            Err(e) => return CalcResult::new_error(Error::NUM, cell, e),
-        };
-        let settlement = match from_excel_date(settlement_serial) {
+        let first_interest =
+            match from_excel_date(self.date_system().to_1900_serial(first_interest_serial)) {
+                Ok(d) => d,
+                Err(e) => return CalcResult::new_error(Error::NUM, cell, e),
+            };
+        let settlement = match from_excel_date(self.date_system().to_1900_serial(settlement_serial))
+        {
             Ok(d) => d,
             Err(e) => return CalcResult::new_error(Error::NUM, cell, e),
         };
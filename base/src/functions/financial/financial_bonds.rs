@@ -695,13 +695,15 @@ fn parse_coupon_args(
     if !(3..=4).contains(&arg_count) {
         return Err(CalcResult::new_args_number_error(cell));
     }
+    // Dates are converted to the 1900 date system
+    let date_system = model.date_system();
     let settlement = {
         let f = model.get_number_no_bools(&args[0], cell)?;
-        f.floor() as i64
+        date_system.to_1900_serial(f.floor() as i64)
     };
     let maturity = {
         let f = model.get_number_no_bools(&args[1], cell)?;
-        f.floor() as i64
+        date_system.to_1900_serial(f.floor() as i64)
     };
     let frequency = {
         let f = model.get_number_no_bools(&args[2], cell)?;
@@ -734,16 +736,17 @@ fn parse_coupon_args(
 impl<'a> Model<'a> {
     // DURATION(settlement, maturity, coupon, yld, frequency, [basis])
     pub(crate) fn fn_duration(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(5..=6).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[2], cell) {
@@ -850,16 +853,17 @@ impl<'a> Model<'a> {
 
     // PRICE(settlement, maturity, rate, yld, redemption, frequency, [basis])
     pub(crate) fn fn_price(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(6..=7).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[2], cell) {
@@ -918,16 +922,17 @@ impl<'a> Model<'a> {
 
     // YIELD(settlement, maturity, rate, pr, redemption, frequency, [basis])
     pub(crate) fn fn_yield(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(6..=7).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[2], cell) {
@@ -985,24 +990,25 @@ impl<'a> Model<'a> {
 
     // ODDFPRICE(settlement, maturity, issue, first_coupon, rate, yld, redemption, frequency, [basis])
     pub(crate) fn fn_oddfprice(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(8..=9).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let issue = match self.get_number_no_bools(&args[2], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let first_coupon = match self.get_number_no_bools(&args[3], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[4], cell) {
@@ -1065,24 +1071,25 @@ impl<'a> Model<'a> {
 
     // ODDFYIELD(settlement, maturity, issue, first_coupon, rate, pr, redemption, frequency, [basis])
     pub(crate) fn fn_oddfyield(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(8..=9).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let issue = match self.get_number_no_bools(&args[2], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let first_coupon = match self.get_number_no_bools(&args[3], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[4], cell) {
@@ -1150,20 +1157,21 @@ impl<'a> Model<'a> {
 
     // ODDLPRICE(settlement, maturity, last_interest, rate, yld, redemption, frequency, [basis])
     pub(crate) fn fn_oddlprice(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(7..=8).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let last_interest = match self.get_number_no_bools(&args[2], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[3], cell) {
@@ -1225,20 +1233,21 @@ impl<'a> Model<'a> {
 
     // ODDLYIELD(settlement, maturity, last_interest, rate, pr, redemption, frequency, [basis])
     pub(crate) fn fn_oddlyield(&mut self, args: &[Node], cell: CellReferenceIndex) -> CalcResult {
+        let date_system = self.date_system();
         let arg_count = args.len();
         if !(7..=8).contains(&arg_count) {
             return CalcResult::new_args_number_error(cell);
         }
         let settlement = match self.get_number_no_bools(&args[0], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let maturity = match self.get_number_no_bools(&args[1], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let last_interest = match self.get_number_no_bools(&args[2], cell) {
-            Ok(f) => f.floor() as i64,
+            Ok(f) => date_system.to_1900_serial(f.floor() as i64),
             Err(e) => return e,
         };
         let rate = match self.get_number_no_bools(&args[3], cell) {
@@ -1372,7 +1381,7 @@ impl<'a> Model<'a> {
             Err(e) => return e,
         };
         match next_coupon_date(settlement, maturity, frequency) {
-            Ok(serial) => CalcResult::Number(serial as f64),
+            Ok(serial) => CalcResult::Number(self.date_system().from_1900_serial(serial) as f64),
             Err(e) => map_date_err(e, cell),
         }
     }
@@ -1398,7 +1407,7 @@ impl<'a> Model<'a> {
             Err(e) => return e,
         };
         match prev_coupon_date(settlement, maturity, frequency) {
-            Ok(serial) => CalcResult::Number(serial as f64),
+            Ok(serial) => CalcResult::Number(self.date_system().from_1900_serial(serial) as f64),
             Err(e) => map_date_err(e, cell),
         }
     }
@@ -461,14 +461,16 @@ impl<'a> Model<'a> {
         if !(0..=4).contains(&basis) || basis == 2 {
             return CalcResult::new_error(Error::NUM, cell, "invalid basis".to_string());
         }
-        let date_purchased = match from_excel_date(date_purchased_serial) {
-            Ok(d) => d,
-            Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
-        };
-        let first_period = match from_excel_date(first_period_serial) {
-            Ok(d) => d,
-            Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
-        };
+        let date_purchased =
+            match from_excel_date(self.date_system().to_1900_serial(date_purchased_serial)) {
+                Ok(d) => d,
+                Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
+            };
+        let first_period =
+            match from_excel_date(self.date_system().to_1900_serial(first_period_serial)) {
+                Ok(d) => d,
+                Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
+            };
         match amor_linc(
             cost,
             date_purchased,
@@ -546,14 +548,16 @@ impl<'a> Model<'a> {
                 "asset life cannot be in [0,3] or [4,5]".to_string(),
             );
         }
-        let date_purchased = match from_excel_date(date_purchased_serial) {
-            Ok(d) => d,
-            Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
-        };
-        let first_period = match from_excel_date(first_period_serial) {
-            Ok(d) => d,
-            Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
-        };
+        let date_purchased =
+            match from_excel_date(self.date_system().to_1900_serial(date_purchased_serial)) {
+                Ok(d) => d,
+                Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
+            };
+        let first_period =
+            match from_excel_date(self.date_system().to_1900_serial(first_period_serial)) {
+                Ok(d) => d,
+                Err(s) => return CalcResult::new_error(Error::NUM, cell, s),
+            };
         match amor_degrc(
             cost,
             date_purchased,
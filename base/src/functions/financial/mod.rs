@@ -1412,7 +1412,10 @@ impl<'a> Model<'a> {
             Ok(f) => f,
             Err(s) => return s,
         };
-        let less_than_one_year = match is_less_than_one_year(settlement as i64, maturity as i64) {
+        let less_than_one_year = match is_less_than_one_year(
+            self.date_system().to_1900_serial(settlement as i64),
+            self.date_system().to_1900_serial(maturity as i64),
+        ) {
             Ok(f) => f,
             Err(_) => return CalcResult::new_error(Error::NUM, cell, "Invalid date".to_string()),
         };
@@ -1477,7 +1480,10 @@ impl<'a> Model<'a> {
             Ok(f) => f,
             Err(s) => return s,
         };
-        let less_than_one_year = match is_less_than_one_year(settlement as i64, maturity as i64) {
+        let less_than_one_year = match is_less_than_one_year(
+            self.date_system().to_1900_serial(settlement as i64),
+            self.date_system().to_1900_serial(maturity as i64),
+        ) {
             Ok(f) => f,
             Err(_) => return CalcResult::new_error(Error::NUM, cell, "Invalid date".to_string()),
         };
@@ -1528,7 +1534,10 @@ impl<'a> Model<'a> {
             Ok(f) => f,
             Err(s) => return s,
         };
-        let less_than_one_year = match is_less_than_one_year(settlement as i64, maturity as i64) {
+        let less_than_one_year = match is_less_than_one_year(
+            self.date_system().to_1900_serial(settlement as i64),
+            self.date_system().to_1900_serial(maturity as i64),
+        ) {
             Ok(f) => f,
             Err(_) => return CalcResult::new_error(Error::NUM, cell, "Invalid date".to_string()),
         };
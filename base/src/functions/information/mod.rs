@@ -129,9 +129,13 @@ impl<'a> Model<'a> {
             return CalcResult::new_args_number_error(cell);
         }
         match &args[0] {
-            Node::ReferenceKind { .. } | Node::RangeKind { .. } | Node::OpRangeKind { .. } => {
-                CalcResult::Boolean(true)
-            }
+            Node::ReferenceKind { .. }
+            | Node::RangeKind { .. }
+            | Node::OpRangeKind { .. }
+            | Node::OpIntersectionKind { .. }
+            | Node::OpUnionKind { .. }
+            | Node::Range3DKind { .. }
+            | Node::ExternalReferenceKind { .. } => CalcResult::Boolean(true),
             Node::FunctionKind { kind, args: _ } => CalcResult::Boolean(kind.returns_reference()),
             _ => CalcResult::Boolean(false),
         }
@@ -245,7 +249,7 @@ impl<'a> Model<'a> {
             CalcResult::Number(_) => CalcResult::Number(1.0),
             CalcResult::Boolean(_) => CalcResult::Number(4.0),
             CalcResult::Error { .. } => CalcResult::Number(16.0),
-            CalcResult::Range { .. } => CalcResult::Number(64.0),
+            CalcResult::Range { .. } | CalcResult::MultiRange(_) => CalcResult::Number(64.0),
             CalcResult::EmptyCell => CalcResult::Number(1.0),
             CalcResult::EmptyArg => {
                 // This cannot happen
@@ -301,7 +305,10 @@ impl<'a> Model<'a> {
                     };
                 }
             }
-            Node::TableNameKind(name) => {
+            Node::TableNameKind(name)
+            | Node::StructuredReferenceKind {
+                table_name: name, ..
+            } => {
                 // Now let's see if it is a table
                 for (table_name, table) in &self.workbook.tables {
                     if table_name == name {
@@ -355,7 +362,7 @@ impl<'a> Model<'a> {
             }
             CalcResult::EmptyCell | CalcResult::EmptyArg => 0.0,
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                 return CalcResult::Error {
                     error: Error::NIMPL,
                     origin: cell,
@@ -475,7 +482,7 @@ impl<'a> Model<'a> {
                     CalcResult::Number(_) => "v",
                     CalcResult::Boolean(_) => "v",
                     CalcResult::Error { .. } => "v",
-                    CalcResult::Range { .. } => "v",
+                    CalcResult::Range { .. } | CalcResult::MultiRange(_) => "v",
                     CalcResult::EmptyArg => "v",
                     CalcResult::Array(_) | CalcResult::Lambda(_) => "v",
                 };
@@ -77,6 +77,7 @@ impl<'a> Model<'a> {
             match self.evaluate_node_in_context(arg, cell) {
                 CalcResult::Boolean(value) => result = Some(fold_fn(result, value)),
                 CalcResult::Number(value) => result = Some(fold_fn(result, value != 0.0)),
+                CalcResult::MultiRange(_) => return CalcResult::new_multiple_areas_error(cell),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
                         return CalcResult::new_error(
@@ -99,6 +100,7 @@ impl<'a> Model<'a> {
                                 error @ CalcResult::Error { .. } => return error,
                                 CalcResult::EmptyArg => {} // unreachable
                                 CalcResult::Range { .. }
+                                | CalcResult::MultiRange(_)
                                 | CalcResult::String { .. }
                                 | CalcResult::EmptyCell => {}
                                 CalcResult::Array(_) | CalcResult::Lambda(_) => {
@@ -69,7 +69,9 @@ pub(super) fn assign_variable_ids(node: &mut Node, target: &str, id: u32) {
         Node::ImplicitIntersection { child, .. } | Node::SpillRangeOperator { child } => {
             assign_variable_ids(child, target, id);
         }
-        Node::OpRangeKind { left, right } => {
+        Node::OpRangeKind { left, right }
+        | Node::OpIntersectionKind { left, right }
+        | Node::OpUnionKind { left, right } => {
             assign_variable_ids(left, target, id);
             assign_variable_ids(right, target, id);
         }
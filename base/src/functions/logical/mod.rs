@@ -62,9 +62,10 @@ fn calc_result_to_array_node(result: CalcResult) -> ArrayNode {
         CalcResult::String(s) => ArrayNode::String(s),
         CalcResult::Error { error, .. } => ArrayNode::Error(error),
         CalcResult::EmptyCell | CalcResult::EmptyArg => ArrayNode::Empty,
-        CalcResult::Lambda(_) | CalcResult::Range { .. } | CalcResult::Array(_) => {
-            ArrayNode::Error(Error::VALUE)
-        }
+        CalcResult::Lambda(_)
+        | CalcResult::Range { .. }
+        | CalcResult::MultiRange(_)
+        | CalcResult::Array(_) => ArrayNode::Error(Error::VALUE),
     }
 }
 
@@ -127,10 +127,10 @@ impl<'a> Model<'a> {
         if args.len() != 1 {
             return CalcResult::new_args_number_error(cell);
         }
-        let result = self.evaluate_node_in_context(&args[0], cell);
-        if result.is_error() {
-            return result;
+        match self.evaluate_node_with_reference(&args[0], cell) {
+            CalcResult::MultiRange(areas) => CalcResult::Number(areas.len() as f64),
+            error @ CalcResult::Error { .. } => error,
+            _ => CalcResult::Number(1.0),
         }
-        CalcResult::Number(1.0)
     }
 }
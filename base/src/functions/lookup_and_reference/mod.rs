@@ -2,7 +2,7 @@ use crate::calc_result::Range;
 use crate::cast::{calc_result_to_array_node, NumberOrArray};
 use crate::constants::{LAST_COLUMN, LAST_ROW};
 use crate::expressions::parser::ArrayNode;
-use crate::expressions::types::CellReferenceIndex;
+use crate::expressions::types::{CellReferenceIndex, CellReferenceRC};
 use crate::implicit_intersection::implicit_intersection;
 use crate::{
     calc_result::CalcResult, expressions::parser::Node, expressions::token::Error, model::Model,
@@ -256,9 +256,8 @@ impl<'a> Model<'a> {
         if !(2..=4).contains(&args.len()) {
             return CalcResult::new_args_number_error(cell);
         }
-        // area_num (4th argument). We only support references with a single area,
-        // so anything other than 1 is out of range.
-        if args.len() == 4 {
+        // area_num (4th argument) selects one of the areas of a multi-area reference
+        let area_num = if args.len() == 4 {
             let area_num = match self.get_number(&args[3], cell) {
                 Ok(f) => f.floor() as i64,
                 Err(s) => return s,
@@ -270,14 +269,10 @@ impl<'a> Model<'a> {
                     message: "Argument must be >= 1".to_string(),
                 };
             }
-            if area_num != 1 {
-                return CalcResult::Error {
-                    error: Error::REF,
-                    origin: cell,
-                    message: "Area out of range".to_string(),
-                };
-            }
-        }
+            area_num as usize
+        } else {
+            1
+        };
         // A missing or empty argument evaluates to 0, which means "the whole
         // row/column". A negative index is an error.
         let row_num = match self.get_number(&args[1], cell) {
@@ -310,7 +305,30 @@ impl<'a> Model<'a> {
         let row_num = row_num.floor() as usize;
         let col_num = col_num.floor() as usize;
 
-        match self.evaluate_node_in_context(&args[0], cell) {
+        let reference = match self.evaluate_node_in_context(&args[0], cell) {
+            CalcResult::MultiRange(areas) => match areas.get(area_num - 1) {
+                Some(area) => CalcResult::Range {
+                    left: area.left,
+                    right: area.right,
+                },
+                None => {
+                    return CalcResult::Error {
+                        error: Error::REF,
+                        origin: cell,
+                        message: "Area out of range".to_string(),
+                    }
+                }
+            },
+            _ if area_num != 1 => {
+                return CalcResult::Error {
+                    error: Error::REF,
+                    origin: cell,
+                    message: "Area out of range".to_string(),
+                }
+            }
+            result => result,
+        };
+        match reference {
             CalcResult::Range { left, right } => {
                 let num_rows = (right.row - left.row + 1) as usize;
                 let (row_eff, col_eff) =
@@ -901,6 +919,9 @@ impl<'a> Model<'a> {
                 let parsed_reference = match parsed_reference {
                     Ok(reference) => reference,
                     Err(message) => {
+                        if let Some(result) = self.get_indirect_table_reference(&s, cell) {
+                            return result;
+                        }
                         return CalcResult::Error {
                             error: Error::REF,
                             origin: cell,
@@ -921,6 +942,32 @@ impl<'a> Model<'a> {
         }
     }
 
+    // The text of INDIRECT can also be a table name or a structured reference: "Sales[Amount]"
+    fn get_indirect_table_reference(
+        &mut self,
+        text: &str,
+        cell: CellReferenceIndex,
+    ) -> Option<CalcResult> {
+        let sheet_name = self
+            .workbook
+            .worksheets
+            .get(cell.sheet as usize)?
+            .name
+            .clone();
+        let context = CellReferenceRC {
+            sheet: sheet_name,
+            row: cell.row,
+            column: cell.column,
+        };
+        let node = self.parser.parse(text.trim(), &context);
+        match node {
+            Node::TableNameKind(_) | Node::StructuredReferenceKind { .. } => {
+                Some(self.evaluate_node_in_context(&node, cell))
+            }
+            _ => None,
+        }
+    }
+
     // OFFSET(reference, rows, cols, [height], [width])
     // Returns a reference to a range that is a specified number of rows and columns from a cell or range of cells.
     // The reference that is returned can be a single cell or a range of cells.
@@ -2,7 +2,11 @@ use std::array::IntoIter;
 
 use crate::{
     calc_result::CalcResult,
-    expressions::{parser::Node, token::Error, types::CellReferenceIndex},
+    expressions::{
+        parser::{get_sheet_span_operands, get_union_operands, Node},
+        token::Error,
+        types::CellReferenceIndex,
+    },
     language::{get_default_language, Functions, Language},
     model::Model,
 };
@@ -2297,6 +2301,68 @@ impl Function {
     pub(crate) fn returns_reference(&self) -> bool {
         matches!(self, Function::Indirect | Function::Offset)
     }
+
+    /// Functions whose arguments are all lists of values. A multi-area reference passed to
+    /// them is equivalent to passing each of its areas as a separate argument:
+    /// `SUM((A1:A3,C1:C3))` is `SUM(A1:A3,C1:C3)` and `SUM(Jan:Mar!B5)` is `SUM(Jan!B5,Feb!B5,Mar!B5)`
+    pub(crate) fn accepts_multiple_areas(&self) -> bool {
+        matches!(
+            self,
+            Function::Sum
+                | Function::Sumsq
+                | Function::Product
+                | Function::Count
+                | Function::Counta
+                | Function::Average
+                | Function::Averagea
+                | Function::Min
+                | Function::Max
+                | Function::Median
+                | Function::Avedev
+                | Function::Devsq
+                | Function::Geomean
+                | Function::Harmean
+                | Function::Kurt
+                | Function::Skew
+                | Function::SkewP
+                | Function::Stdev
+                | Function::StDevP
+                | Function::StDevS
+                | Function::Stdeva
+                | Function::Stdevpa
+                | Function::VarA
+                | Function::VarCompat
+                | Function::VarP
+                | Function::VarPCompat
+                | Function::VarS
+                | Function::VarpA
+                | Function::ModeCompat
+                | Function::ModeSingl
+                | Function::Gcd
+                | Function::Lcm
+                | Function::Concat
+                | Function::And
+                | Function::Or
+                | Function::Xor
+        )
+    }
+
+    /// Volatile functions are recomputed on every recalculation, regardless of
+    /// whether their arguments changed.
+    pub(crate) fn is_volatile(&self) -> bool {
+        matches!(
+            self,
+            Function::Now
+                | Function::Today
+                | Function::Rand
+                | Function::Randbetween
+                | Function::Randarray
+                | Function::Offset
+                | Function::Indirect
+                | Function::Cell
+                | Function::Info
+        )
+    }
 }
 
 impl<'a> Model<'a> {
@@ -2306,6 +2372,21 @@ impl<'a> Model<'a> {
         args: &[Node],
         cell: CellReferenceIndex,
     ) -> CalcResult {
+        if kind.is_volatile() {
+            self.dependency_graph.set_volatile(cell);
+        }
+        if kind.accepts_multiple_areas()
+            && args
+                .iter()
+                .any(|arg| matches!(arg, Node::OpUnionKind { .. } | Node::Range3DKind { .. }))
+        {
+            let args: Vec<Node> = args
+                .iter()
+                .flat_map(get_union_operands)
+                .flat_map(get_sheet_span_operands)
+                .collect();
+            return self.evaluate_function(kind, &args, cell);
+        }
         match kind {
             Function::And => self.fn_and(args, cell),
             Function::False => self.fn_false(args, cell),
@@ -152,6 +152,7 @@ impl<'a> Model<'a> {
     ) -> Result<Vec<Vec<ArrayNode>>, CalcResult> {
         let result = self.evaluate_node_in_context(node, cell);
         match result {
+            CalcResult::MultiRange(_) => Err(CalcResult::new_multiple_areas_error(cell)),
             CalcResult::Range { left, right } => Ok(self.evaluate_range(left, right)),
             CalcResult::Array(arr) => Ok(arr),
             CalcResult::Number(n) => Ok(vec![vec![ArrayNode::Number(n)]]),
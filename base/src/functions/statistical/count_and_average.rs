@@ -80,7 +80,7 @@ impl<'a> Model<'a> {
                                     f(value);
                                 }
                                 error @ CalcResult::Error { .. } => return Err(error),
-                                CalcResult::Range { .. } => {
+                                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                                     return Err(CalcResult::new_error(
                                         Error::ERROR,
                                         cell,
@@ -180,7 +180,7 @@ impl<'a> Model<'a> {
                                     f(0.0);
                                 }
                                 error @ CalcResult::Error { .. } => return Err(error),
-                                CalcResult::Range { .. } => {
+                                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                                     return Err(CalcResult::new_error(
                                         Error::ERROR,
                                         cell,
@@ -232,6 +232,7 @@ impl<'a> Model<'a> {
         let mut sum = 0.0;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::MultiRange(_) => return CalcResult::new_multiple_areas_error(cell),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
                         return CalcResult::new_error(
@@ -259,7 +260,7 @@ impl<'a> Model<'a> {
                                     count += 1.0;
                                 }
                                 error @ CalcResult::Error { .. } => return error,
-                                CalcResult::Range { .. } => {
+                                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                                     return CalcResult::new_error(
                                         Error::ERROR,
                                         cell,
@@ -89,7 +89,7 @@ impl<'a> Model<'a> {
             }
         }
         for criterion in criteria.iter() {
-            fn_criteria.push(build_criteria(criterion, self.locale));
+            fn_criteria.push(build_criteria(criterion, self.locale, self.date_system()));
         }
 
         let mut total = 0.0;
@@ -240,7 +240,7 @@ impl<'a> Model<'a> {
             }
         }
         for criterion in criteria.iter() {
-            fn_criteria.push(build_criteria(criterion, self.locale));
+            fn_criteria.push(build_criteria(criterion, self.locale, self.date_system()));
         }
 
         self.run_ifs(&sum_range, ranges.as_slice(), &fn_criteria, cell, apply)
@@ -419,7 +419,7 @@ impl<'a> Model<'a> {
         for criteria_row in &criteria_grid {
             let mut out_row: Vec<ArrayNode> = Vec::with_capacity(criteria_row.len());
             for criterion in criteria_row {
-                let fn_criteria = [build_criteria(criterion, self.locale)];
+                let fn_criteria = [build_criteria(criterion, self.locale, self.date_system())];
                 let mut total = 0.0;
                 let node = match self.run_ifs(
                     &sum_range,
@@ -50,6 +50,15 @@ impl<'a> Model<'a> {
                 }
             }
         }
+        // The autofilter of the sheet
+        if let Some(auto_filter) = &worksheet.auto_filter {
+            if let Ok((column1, row1, column2, row2)) = parse_range(&auto_filter.range) {
+                if ((column >= column1) && (column <= column2)) && ((row >= row1) && (row <= row2))
+                {
+                    return !auto_filter.columns.is_empty();
+                }
+            }
+        }
         false
     }
 
@@ -133,6 +142,9 @@ impl<'a> Model<'a> {
                         error @ CalcResult::Error { .. } => {
                             return Err(error);
                         }
+                        CalcResult::MultiRange(_) => {
+                            return Err(CalcResult::new_multiple_areas_error(cell))
+                        }
                         CalcResult::Range { left, right } => {
                             if left.sheet != right.sheet {
                                 return Err(CalcResult::new_error(
@@ -381,6 +393,9 @@ impl<'a> Model<'a> {
                         CalcResult::EmptyCell | CalcResult::EmptyArg => {
                             // skip
                         }
+                        CalcResult::MultiRange(_) => {
+                            return CalcResult::new_multiple_areas_error(cell)
+                        }
                         CalcResult::Range { left, right } => {
                             if left.sheet != right.sheet {
                                 return CalcResult::new_error(
@@ -28,6 +28,7 @@ impl<'a> Model<'a> {
         let strict = format == 1;
 
         let array = match self.evaluate_node_in_context(&args[0], cell) {
+            CalcResult::MultiRange(_) => return CalcResult::new_multiple_areas_error(cell),
             CalcResult::Range { left, right } => self.evaluate_range(left, right),
             CalcResult::Array(a) => a,
             CalcResult::Number(n) => {
@@ -8,7 +8,7 @@ use crate::{
         token::Error,
         types::CellReferenceIndex,
     },
-    formatter::format::{format_number, parse_formatted_number},
+    formatter::format::{format_number_with_date_system, parse_formatted_number},
     functions::{
         text::util::{substitute, text_after, text_before, Case},
         util::from_wildcard_to_regex,
@@ -217,6 +217,7 @@ impl<'a> Model<'a> {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
+                CalcResult::MultiRange(_) => return CalcResult::new_multiple_areas_error(cell),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
                         return CalcResult::new_error(
@@ -245,7 +246,7 @@ impl<'a> Model<'a> {
                                 }
                                 error @ CalcResult::Error { .. } => return error,
                                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
-                                CalcResult::Range { .. } => {}
+                                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {}
                                 CalcResult::Array(_) | CalcResult::Lambda(_) => {
                                     return CalcResult::Error {
                                         error: Error::NIMPL,
@@ -288,13 +289,19 @@ impl<'a> Model<'a> {
                     Err(e) => return e,
                 };
                 let locale = self.locale;
+                let date_system = self.date_system();
                 let mut output = Vec::with_capacity(arr.len());
                 for row in arr {
                     let mut data_row = Vec::with_capacity(row.len());
                     for node in row {
                         let out = match node {
                             ArrayNode::Number(f) => {
-                                let d = format_number(f, &format_code, locale);
+                                let d = format_number_with_date_system(
+                                    f,
+                                    &format_code,
+                                    locale,
+                                    date_system,
+                                );
                                 if d.error.is_some() {
                                     ArrayNode::Error(Error::VALUE)
                                 } else {
@@ -302,7 +309,12 @@ impl<'a> Model<'a> {
                                 }
                             }
                             ArrayNode::Empty => {
-                                let d = format_number(0.0, &format_code, locale);
+                                let d = format_number_with_date_system(
+                                    0.0,
+                                    &format_code,
+                                    locale,
+                                    date_system,
+                                );
                                 if d.error.is_some() {
                                     ArrayNode::Error(Error::VALUE)
                                 } else {
@@ -325,7 +337,7 @@ impl<'a> Model<'a> {
                     CalcResult::String(s) => return CalcResult::String(s),
                     CalcResult::Boolean(b) => return CalcResult::Boolean(b),
                     error @ CalcResult::Error { .. } => return error,
-                    CalcResult::Range { .. } => {
+                    CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                         return CalcResult::Error {
                             error: Error::NIMPL,
                             origin: cell,
@@ -339,7 +351,12 @@ impl<'a> Model<'a> {
                     Ok(s) => s,
                     Err(s) => return s,
                 };
-                let d = format_number(value, &format_code, self.locale);
+                let d = format_number_with_date_system(
+                    value,
+                    &format_code,
+                    self.locale,
+                    self.date_system(),
+                );
                 if let Some(_e) = d.error {
                     return CalcResult::Error {
                         error: Error::VALUE,
@@ -479,7 +496,7 @@ impl<'a> Model<'a> {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -514,7 +531,7 @@ impl<'a> Model<'a> {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -594,7 +611,7 @@ impl<'a> Model<'a> {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -987,6 +1004,7 @@ impl<'a> Model<'a> {
         for arg in &args[2..] {
             match self.evaluate_node_in_context(arg, cell) {
                 CalcResult::Number(value) => values.push(format!("{value}")),
+                CalcResult::MultiRange(_) => return CalcResult::new_multiple_areas_error(cell),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
                         return CalcResult::new_error(
@@ -1047,7 +1065,9 @@ impl<'a> Model<'a> {
                                     }
                                 }
                                 error @ CalcResult::Error { .. } => return error,
-                                CalcResult::EmptyArg | CalcResult::Range { .. } => {}
+                                CalcResult::EmptyArg
+                                | CalcResult::Range { .. }
+                                | CalcResult::MultiRange(_) => {}
                                 CalcResult::Array(_) | CalcResult::Lambda(_) => {
                                     return CalcResult::Error {
                                         error: Error::NIMPL,
@@ -1179,7 +1199,9 @@ impl<'a> Model<'a> {
         match self.evaluate_node_in_context(&args[0], cell) {
             CalcResult::String(text) => {
                 let currencies = vec!["$", "€"];
-                if let Ok((value, _)) = parse_formatted_number(&text, &currencies, self.locale) {
+                if let Ok((value, _)) =
+                    parse_formatted_number(&text, &currencies, self.locale, self.date_system())
+                {
                     return CalcResult::Number(value);
                 };
                 CalcResult::Error {
@@ -1195,7 +1217,7 @@ impl<'a> Model<'a> {
                 message: "Invalid number".to_string(),
             },
             error @ CalcResult::Error { .. } => error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                 // TODO Implicit Intersection
                 CalcResult::Error {
                     error: Error::VALUE,
@@ -32,12 +32,20 @@ pub mod calc_result;
 pub mod cell;
 pub mod cf_types;
 pub mod colors;
+pub mod dv_types;
 pub mod expressions;
+pub mod external_links;
+pub mod filter_types;
+pub mod find_types;
 pub mod formatter;
 pub mod language;
 pub mod locale;
 pub mod new_empty;
 pub mod number_format;
+pub mod outline_types;
+pub mod page_setup_types;
+pub mod rich_text_types;
+pub mod sort_types;
 pub mod themes;
 pub mod types;
 pub mod worksheet;
@@ -47,15 +55,27 @@ pub use crate::constants::ROW_HEIGHT_FACTOR;
 
 mod actions;
 mod arithmetic;
+mod auto_filter;
 mod cast;
+mod comments;
 mod conditional_formatting;
 mod constants;
 mod cut_paste;
+mod data_validation;
+mod dependency_graph;
+mod find;
 mod functions;
 mod implicit_intersection;
 pub mod links;
+mod merged_cells;
 mod model;
+mod outline;
+mod page_setup;
+mod protection;
+mod rich_text;
+mod sort;
 mod styles;
+mod tables;
 mod tz;
 mod units;
 mod user_model;
@@ -73,6 +93,7 @@ pub use model::get_milliseconds_since_epoch;
 pub use model::FmtSettings;
 pub use model::Model;
 pub use user_model::BorderArea;
-pub use user_model::ClipboardData;
 pub use user_model::UserModel;
+pub use user_model::{Clipboard, ClipboardData};
+pub use user_model::{PasteOperation, PasteOptions, PasteType};
 pub use utils::get_all_timezones;
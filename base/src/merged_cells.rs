@@ -0,0 +1,277 @@
+//! Merged cells: a rectangular block of cells displayed as a single cell.
+//!
+//! Only the top left cell of a merged area has content. The areas are stored in
+//! [Worksheet::merge_cells](crate::types::Worksheet::merge_cells) as references like "B2:D3", and
+//! they follow their cells when rows or columns are inserted, deleted or moved.
+
+use crate::{
+    expressions::{parser::parse_range, parser::stringify::DisplaceData, types::Area},
+    tables::{area_to_reference, areas_overlap},
+    types::Cell,
+    Model,
+};
+
+// Returns the new extremes of the interval [start, end] when `delta` rows (or columns) are
+// inserted (delta > 0) or deleted (delta < 0) at `at`, or None if all of it is deleted
+pub(crate) fn displace_interval(start: i32, end: i32, at: i32, delta: i32) -> Option<(i32, i32)> {
+    if delta > 0 {
+        if at <= start {
+            return Some((start + delta, end + delta));
+        }
+        if at <= end {
+            return Some((start, end + delta));
+        }
+        return Some((start, end));
+    }
+    let deleted_end = at - delta - 1;
+    if deleted_end < start {
+        return Some((start + delta, end + delta));
+    }
+    if at > end {
+        return Some((start, end));
+    }
+    let overlap = deleted_end.min(end) - at.max(start) + 1;
+    let length = end - start + 1 - overlap;
+    if length == 0 {
+        return None;
+    }
+    let new_start = start.min(at);
+    Some((new_start, new_start + length - 1))
+}
+
+// Returns the new extremes of the interval [start, end] when the rows (or columns)
+// [group_start, group_end] are moved by `delta`, or None if the move splits the interval
+pub(crate) fn move_interval(
+    start: i32,
+    end: i32,
+    group_start: i32,
+    group_end: i32,
+    delta: i32,
+) -> Option<(i32, i32)> {
+    let count = group_end - group_start + 1;
+    let (displaced_start, displaced_end, shift) = if delta > 0 {
+        (group_end + 1, group_end + delta, -count)
+    } else {
+        (group_start + delta, group_start - 1, count)
+    };
+    let inside = |a: i32, b: i32| a <= start && end <= b;
+    let outside = |a: i32, b: i32| end < a || b < start;
+    if inside(group_start, group_end) {
+        Some((start + delta, end + delta))
+    } else if inside(displaced_start, displaced_end) {
+        Some((start + shift, end + shift))
+    } else if outside(group_start, group_end) && outside(displaced_start, displaced_end) {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Returns the merged areas of the sheet
+    pub fn get_merged_cells(&self, sheet: u32) -> Result<Vec<Area>, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        let mut areas = Vec::new();
+        for reference in &worksheet.merge_cells {
+            let (column, row, column_end, row_end) = parse_range(reference)?;
+            areas.push(Area {
+                sheet,
+                row,
+                column,
+                width: column_end - column + 1,
+                height: row_end - row + 1,
+            });
+        }
+        Ok(areas)
+    }
+
+    /// Returns the merged area that contains the cell, if any
+    pub fn get_merged_area(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Area>, String> {
+        Ok(self.get_merged_cells(sheet)?.into_iter().find(|area| {
+            (area.row..area.row + area.height).contains(&row)
+                && (area.column..area.column + area.width).contains(&column)
+        }))
+    }
+
+    /// Merges the cells in `area`. Only the content of the top left cell is kept.
+    /// Merged areas inside `area` become part of the new one.
+    pub fn merge_cells(&mut self, area: &Area) -> Result<(), String> {
+        if area.width < 1 || area.height < 1 {
+            return Err("Invalid area".to_string());
+        }
+        if area.width == 1 && area.height == 1 {
+            return Err("Cannot merge a single cell".to_string());
+        }
+        let reference = area_to_reference(area)?;
+        let merged_areas = self.get_merged_cells(area.sheet)?;
+        for merged_area in &merged_areas {
+            let inside = area.row <= merged_area.row
+                && merged_area.row + merged_area.height <= area.row + area.height
+                && area.column <= merged_area.column
+                && merged_area.column + merged_area.width <= area.column + area.width;
+            if areas_overlap(area, merged_area) && !inside {
+                return Err("Cannot merge cells that overlap other merged cells".to_string());
+            }
+        }
+        for table in self.get_tables(area.sheet)? {
+            if areas_overlap(area, &self.get_table_area(&table)?) {
+                return Err("Cannot merge cells in a table".to_string());
+            }
+        }
+        let worksheet = self.workbook.worksheet(area.sheet)?;
+        for row in area.row..area.row + area.height {
+            for column in area.column..area.column + area.width {
+                if let Some(Cell::ArrayFormula { .. } | Cell::SpillCell { .. }) =
+                    worksheet.cell(row, column)
+                {
+                    return Err("Cannot merge cells of an array formula".to_string());
+                }
+            }
+        }
+
+        // Everything but the top left cell is emptied
+        if area.width > 1 {
+            self.range_clear_contents(&Area {
+                column: area.column + 1,
+                width: area.width - 1,
+                height: 1,
+                ..*area
+            })?;
+        }
+        if area.height > 1 {
+            self.range_clear_contents(&Area {
+                row: area.row + 1,
+                height: area.height - 1,
+                ..*area
+            })?;
+        }
+
+        let mut merge_cells: Vec<String> = Vec::new();
+        for merged_area in merged_areas {
+            if !areas_overlap(area, &merged_area) {
+                merge_cells.push(area_to_reference(&merged_area)?);
+            }
+        }
+        merge_cells.push(reference);
+        self.workbook.worksheet_mut(area.sheet)?.merge_cells = merge_cells;
+        Ok(())
+    }
+
+    /// Unmerges all the merged areas that overlap `area`
+    pub fn unmerge_cells(&mut self, area: &Area) -> Result<(), String> {
+        let mut merge_cells = Vec::new();
+        for merged_area in self.get_merged_cells(area.sheet)? {
+            if !areas_overlap(area, &merged_area) {
+                merge_cells.push(area_to_reference(&merged_area)?);
+            }
+        }
+        self.workbook.worksheet_mut(area.sheet)?.merge_cells = merge_cells;
+        Ok(())
+    }
+
+    // Replaces each merged area of the sheet with `map(area)`, removing it if it is None or a single cell
+    fn map_merged_cells<F>(&mut self, sheet: u32, map: F) -> Result<(), String>
+    where
+        F: Fn(&Area) -> Option<Area>,
+    {
+        let mut merge_cells = Vec::new();
+        for merged_area in self.get_merged_cells(sheet)? {
+            if let Some(new_area) = map(&merged_area) {
+                if new_area.width > 1 || new_area.height > 1 {
+                    merge_cells.push(area_to_reference(&new_area)?);
+                }
+            }
+        }
+        self.workbook.worksheet_mut(sheet)?.merge_cells = merge_cells;
+        Ok(())
+    }
+
+    /// Grows, shrinks or moves the merged areas when rows or columns are inserted or deleted
+    pub(crate) fn displace_merged_cells(
+        &mut self,
+        displace_data: &DisplaceData,
+    ) -> Result<(), String> {
+        match *displace_data {
+            DisplaceData::Row { sheet, row, delta } => self.map_merged_cells(sheet, |area| {
+                let (start, end) =
+                    displace_interval(area.row, area.row + area.height - 1, row, delta)?;
+                Some(Area {
+                    row: start,
+                    height: end - start + 1,
+                    ..*area
+                })
+            }),
+            DisplaceData::Column {
+                sheet,
+                column,
+                delta,
+            } => self.map_merged_cells(sheet, |area| {
+                let (start, end) =
+                    displace_interval(area.column, area.column + area.width - 1, column, delta)?;
+                Some(Area {
+                    column: start,
+                    width: end - start + 1,
+                    ..*area
+                })
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves the merged areas when the rows [row, row + row_count - 1] are moved by `delta`.
+    /// Fails without changes if the move would split a merged area.
+    pub(crate) fn move_merged_cells_rows(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        let group_end = row + row_count - 1;
+        for area in self.get_merged_cells(sheet)? {
+            if move_interval(area.row, area.row + area.height - 1, row, group_end, delta).is_none()
+            {
+                return Err("Cannot move rows because that would split merged cells".to_string());
+            }
+        }
+        self.map_merged_cells(sheet, |area| {
+            let (start, _) =
+                move_interval(area.row, area.row + area.height - 1, row, group_end, delta)?;
+            Some(Area {
+                row: start,
+                ..*area
+            })
+        })
+    }
+
+    /// Moves the merged areas when the columns [column, column + column_count - 1] are moved
+    /// by `delta`. Fails without changes if the move would split a merged area.
+    pub(crate) fn move_merged_cells_columns(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        column_count: i32,
+        delta: i32,
+    ) -> Result<(), String> {
+        let group_end = column + column_count - 1;
+        for area in self.get_merged_cells(sheet)? {
+            let end = area.column + area.width - 1;
+            if move_interval(area.column, end, column, group_end, delta).is_none() {
+                return Err("Cannot move columns because that would split merged cells".to_string());
+            }
+        }
+        self.map_merged_cells(sheet, |area| {
+            let end = area.column + area.width - 1;
+            let (start, _) = move_interval(area.column, end, column, group_end, delta)?;
+            Some(Area {
+                column: start,
+                ..*area
+            })
+        })
+    }
+}
@@ -1,6 +1,6 @@
 #![deny(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::vec::Vec;
 
 use crate::expressions::parser::static_analysis::run_static_analysis_on_node;
@@ -11,20 +11,24 @@ use crate::{
     expressions::{
         lexer::LexerMode,
         parser::{
+            get_sheet_span_operands, get_structured_reference_area,
             move_formula::{move_formula, MoveContext},
-            new_parser_english,
+            new_parser_english, parse_range,
             static_analysis::StaticResult,
             stringify::{
-                rename_defined_name_in_node, to_english_string, to_localized_string, to_rc_format,
+                externalize_wrong_references_in_node, rename_defined_name_in_node,
+                to_english_string, to_localized_string, to_rc_format, transpose_references_in_node,
             },
             ArrayNode, CompletionContext, NamedVariable, Node, Parser,
         },
-        token::{get_error_by_name, Error, OpProduct, OpSum, OpUnary},
+        token::{
+            get_error_by_name, Error, OpProduct, OpSum, OpUnary, TableReference, TableSpecifier,
+        },
         types::*,
         utils::{self, is_valid_column_number, is_valid_identifier, is_valid_row},
     },
     formatter::{
-        format::{format_number, parse_formatted_number},
+        format::{format_number, format_number_with_date_system, parse_formatted_number},
         lexer::is_likely_date_number_format,
     },
     implicit_intersection::implicit_intersection,
@@ -34,7 +38,8 @@ use crate::{
     utils as common,
 };
 
-use crate::{cf_types::CfCellResult, tz::Tz};
+use crate::external_links::ExternalLinkResolver;
+use crate::{cf_types::CfCellResult, dependency_graph::DependencyGraph, tz::Tz};
 
 #[cfg(any(test, feature = "mock_time"))]
 pub use crate::mock_time::get_milliseconds_since_epoch;
@@ -168,6 +173,20 @@ fn formula_value_to_spill_value(v: &FormulaValue) -> SpillValue {
     }
 }
 
+// Checks that the iterative calculation settings can be used to evaluate the workbook
+pub(crate) fn validate_iterative_calculation(
+    settings: &IterativeCalculation,
+) -> Result<(), String> {
+    if settings.max_iterations < 1 {
+        return Err("Maximum iterations must be a positive number".to_string());
+    }
+    if !settings.max_change.is_finite() || settings.max_change < 0.0 {
+        return Err("Maximum change must be a non negative number".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum CellOrRange {
     // (sheet, row, column)
     Cell((u32, i32, i32)),
@@ -218,13 +237,25 @@ pub struct Model<'a> {
     pub(crate) last_lambda_id: usize,
     /// The list of cells that might spill
     pub(crate) spill_cells: Vec<CellReferenceIndex>,
-    /// A dictionary to keep track of which cells or ranges support a given cell.
-    pub(crate) support: HashMap<CellReferenceIndex, Vec<CellOrRange>>,
+    /// The cells and ranges each formula reads, and the reverse index.
+    /// It persists between evaluations and drives the incremental recalculation.
+    pub(crate) dependency_graph: DependencyGraph,
+    /// Cells and ranges whose content changed since the last evaluation
+    pub(crate) dirty_cells: Vec<CellOrRange>,
+    /// If true the next evaluation must recompute every cell (the dependency graph is stale)
+    pub(crate) needs_full_evaluation: bool,
+    /// Cells that were read while still being evaluated, in iterative calculation mode
+    pub(crate) circular_cells: HashSet<CellReferenceIndex>,
+    /// Formula cells evaluated for the first time during a detached evaluation, as they were
+    /// before. `None` outside of [Model::evaluate_detached].
+    pub(crate) detached_cells: Option<Vec<(CellReferenceIndex, Cell)>>,
     /// Evaluated CF results per cell, keyed by (sheet_index, row, column).
     /// Rebuilt from scratch on every call to evaluate_conditional_formatting().
     pub(crate) cf_cache: HashMap<(u32, i32, i32), Vec<CfCellResult>>,
     /// Dynamic links: links created by formulas like HYPERLINK
     pub(crate) links: HashMap<(u32, i32, i32), Link>,
+    /// Supplies the values of the references to other workbooks
+    pub(crate) external_link_resolver: Option<Box<dyn ExternalLinkResolver + 'a>>,
 }
 
 // FIXME: Maybe this should be the same as CellReference
@@ -279,6 +310,8 @@ impl<'a> Model<'a> {
                 if !absolute_column {
                     column1 += cell.column;
                 }
+                self.dependency_graph
+                    .add_precedent(cell, CellOrRange::Cell((*sheet_index, row1, column1)));
                 CalcResult::Range {
                     left: CellReferenceIndex {
                         sheet: *sheet_index,
@@ -320,6 +353,16 @@ impl<'a> Model<'a> {
                 if !absolute_column2 {
                     column_right += cell.column;
                 }
+                self.dependency_graph.add_precedent(
+                    cell,
+                    CellOrRange::Range((
+                        *sheet_index,
+                        row_left.min(row_right),
+                        column_left.min(column_right),
+                        row_left.max(row_right),
+                        column_left.max(column_right),
+                    )),
+                );
                 // FIXME: HACK. The parser is currently parsing Sheet3!A1:A10 as Sheet3!A1:(present sheet)!A10
                 CalcResult::Range {
                     left: CellReferenceIndex {
@@ -345,6 +388,11 @@ impl<'a> Model<'a> {
                     format!("Error with Implicit Intersection in cell {cell:?}"),
                 ),
             },
+            Node::StructuredReferenceKind {
+                table_name,
+                specifier,
+                table_reference,
+            } => self.get_structured_reference_range(table_name, specifier, table_reference, cell),
             _ => self.evaluate_node_in_context(node, cell),
         }
     }
@@ -387,6 +435,239 @@ impl<'a> Model<'a> {
         }
     }
 
+    // Returns the areas a node refers to, normalized so that `left` is the top left corner
+    fn get_areas(
+        &mut self,
+        node: &Node,
+        cell: CellReferenceIndex,
+    ) -> Result<Vec<Range>, CalcResult> {
+        let areas = match self.evaluate_node_with_reference(node, cell) {
+            CalcResult::Range { left, right } => vec![Range { left, right }],
+            CalcResult::MultiRange(areas) => areas,
+            error @ CalcResult::Error { .. } => return Err(error),
+            _ => {
+                return Err(CalcResult::new_error(
+                    Error::VALUE,
+                    cell,
+                    "Expecting a reference".to_string(),
+                ))
+            }
+        };
+        Ok(areas
+            .into_iter()
+            .map(|Range { left, right }| Range {
+                left: CellReferenceIndex {
+                    sheet: left.sheet,
+                    row: left.row.min(right.row),
+                    column: left.column.min(right.column),
+                },
+                right: CellReferenceIndex {
+                    sheet: left.sheet,
+                    row: left.row.max(right.row),
+                    column: left.column.max(right.column),
+                },
+            })
+            .collect())
+    }
+
+    // The intersection operator (a space). Returns #NULL! if the references do not intersect.
+    fn get_intersection(
+        &mut self,
+        left: &Node,
+        right: &Node,
+        cell: CellReferenceIndex,
+    ) -> CalcResult {
+        let left_areas = match self.get_areas(left, cell) {
+            Ok(areas) => areas,
+            Err(error) => return error,
+        };
+        let right_areas = match self.get_areas(right, cell) {
+            Ok(areas) => areas,
+            Err(error) => return error,
+        };
+        let mut areas = Vec::new();
+        for area1 in &left_areas {
+            for area2 in &right_areas {
+                if area1.left.sheet != area2.left.sheet {
+                    continue;
+                }
+                let row1 = area1.left.row.max(area2.left.row);
+                let row2 = area1.right.row.min(area2.right.row);
+                let column1 = area1.left.column.max(area2.left.column);
+                let column2 = area1.right.column.min(area2.right.column);
+                if row1 <= row2 && column1 <= column2 {
+                    let sheet = area1.left.sheet;
+                    areas.push(Range {
+                        left: CellReferenceIndex {
+                            sheet,
+                            row: row1,
+                            column: column1,
+                        },
+                        right: CellReferenceIndex {
+                            sheet,
+                            row: row2,
+                            column: column2,
+                        },
+                    });
+                }
+            }
+        }
+        match areas.len() {
+            0 => CalcResult::new_error(Error::NULL, cell, "Empty intersection".to_string()),
+            1 => {
+                let Range { left, right } = areas.remove(0);
+                CalcResult::Range { left, right }
+            }
+            _ => CalcResult::MultiRange(areas),
+        }
+    }
+
+    // The union operator (a comma inside parentheses). All areas must be in the same sheet.
+    fn get_union(&mut self, left: &Node, right: &Node, cell: CellReferenceIndex) -> CalcResult {
+        let mut areas = match self.get_areas(left, cell) {
+            Ok(areas) => areas,
+            Err(error) => return error,
+        };
+        match self.get_areas(right, cell) {
+            Ok(right_areas) => areas.extend(right_areas),
+            Err(error) => return error,
+        };
+        let sheet = areas[0].left.sheet;
+        if areas.iter().any(|area| area.left.sheet != sheet) {
+            return CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "Union of references in different sheets".to_string(),
+            );
+        }
+        CalcResult::MultiRange(areas)
+    }
+
+    // A 3D reference (Jan:Dec!B5) is the same area in every sheet between the first and the last one
+    fn get_sheet_span(&mut self, node: &Node, cell: CellReferenceIndex) -> CalcResult {
+        let mut areas = Vec::new();
+        for operand in get_sheet_span_operands(node) {
+            match self.get_areas(&operand, cell) {
+                Ok(operand_areas) => areas.extend(operand_areas),
+                Err(error) => return error,
+            }
+        }
+        if areas.len() == 1 {
+            let Range { left, right } = areas.remove(0);
+            return CalcResult::Range { left, right };
+        }
+        CalcResult::MultiRange(areas)
+    }
+
+    // A table name (Sales) is the data body of the table: the headers and the totals are not included
+    fn get_table_data_range(&mut self, name: &str, cell: CellReferenceIndex) -> CalcResult {
+        let name_lower = name.to_lowercase();
+        let table = match self
+            .workbook
+            .tables
+            .values()
+            .find(|table| table.name.to_lowercase() == name_lower)
+        {
+            Some(table) => table,
+            None => {
+                return CalcResult::new_error(
+                    Error::NAME,
+                    cell,
+                    format!("Table \"{name}\" not found."),
+                )
+            }
+        };
+        let (column1, row1, column2, row2) = match parse_range(&table.reference) {
+            Ok(range) => range,
+            Err(message) => return CalcResult::new_error(Error::REF, cell, message),
+        };
+        let sheet = match self.get_sheet_index_by_name(&table.sheet_name) {
+            Some(sheet) => sheet,
+            None => {
+                return CalcResult::new_error(
+                    Error::REF,
+                    cell,
+                    format!("Sheet \"{}\" not found.", table.sheet_name),
+                )
+            }
+        };
+        let row1 = row1 + table.header_row_count as i32;
+        let row2 = row2 - table.totals_row_count as i32;
+        self.dependency_graph.add_precedent(
+            cell,
+            CellOrRange::Range((sheet, row1, column1, row2, column2)),
+        );
+        CalcResult::Range {
+            left: CellReferenceIndex {
+                sheet,
+                row: row1,
+                column: column1,
+            },
+            right: CellReferenceIndex {
+                sheet,
+                row: row2,
+                column: column2,
+            },
+        }
+    }
+
+    // Resolves a structured reference (Table1[Amount]) with the current area of the table
+    fn get_structured_reference_range(
+        &mut self,
+        table_name: &str,
+        specifier: &Option<TableSpecifier>,
+        table_reference: &Option<TableReference>,
+        cell: CellReferenceIndex,
+    ) -> CalcResult {
+        let name_lower = table_name.to_lowercase();
+        let table = match self
+            .workbook
+            .tables
+            .values()
+            .find(|table| table.name.to_lowercase() == name_lower)
+        {
+            Some(table) => table,
+            None => {
+                return CalcResult::new_error(
+                    Error::REF,
+                    cell,
+                    format!("Table \"{table_name}\" not found."),
+                )
+            }
+        };
+        let (column1, row1, column2, row2) =
+            match get_structured_reference_area(table, specifier, table_reference, cell.row) {
+                Ok(area) => area,
+                Err((error, message)) => return CalcResult::new_error(error, cell, message),
+            };
+        let sheet = match self.get_sheet_index_by_name(&table.sheet_name) {
+            Some(sheet) => sheet,
+            None => {
+                return CalcResult::new_error(
+                    Error::REF,
+                    cell,
+                    format!("Sheet \"{}\" not found.", table.sheet_name),
+                )
+            }
+        };
+        self.dependency_graph.add_precedent(
+            cell,
+            CellOrRange::Range((sheet, row1, column1, row2, column2)),
+        );
+        CalcResult::Range {
+            left: CellReferenceIndex {
+                sheet,
+                row: row1,
+                column: column1,
+            },
+            right: CellReferenceIndex {
+                sheet,
+                row: row2,
+                column: column2,
+            },
+        }
+    }
+
     pub(crate) fn formula_without_prefix<'b>(&self, value: &'b str) -> Option<&'b str> {
         if let Some(stripped) = value.strip_prefix('=') {
             if stripped.is_empty() {
@@ -578,10 +859,8 @@ impl<'a> Model<'a> {
                 if !absolute_column {
                     column1 += cell.column;
                 }
-                self.support
-                    .entry(cell)
-                    .or_default()
-                    .push(CellOrRange::Cell((*sheet_index, row1, column1)));
+                self.dependency_graph
+                    .add_precedent(cell, CellOrRange::Cell((*sheet_index, row1, column1)));
                 self.evaluate_cell(CellReferenceIndex {
                     sheet: *sheet_index,
                     row: row1,
@@ -592,6 +871,10 @@ impl<'a> Model<'a> {
                 CalcResult::new_error(Error::REF, cell, "Wrong reference".to_string())
             }
             OpRangeKind { left, right } => self.get_range(left, right, cell),
+            OpIntersectionKind { left, right } => self.get_intersection(left, right, cell),
+            OpUnionKind { left, right } => self.get_union(left, right, cell),
+            Range3DKind { .. } => self.get_sheet_span(node, cell),
+            ExternalReferenceKind { .. } => self.evaluate_external_reference(node, cell),
             WrongRangeKind { .. } => {
                 CalcResult::new_error(Error::REF, cell, "Wrong range".to_string())
             }
@@ -627,16 +910,16 @@ impl<'a> Model<'a> {
                 } else {
                     *column2 + cell.column
                 };
-                self.support
-                    .entry(cell)
-                    .or_default()
-                    .push(CellOrRange::Range((
+                self.dependency_graph.add_precedent(
+                    cell,
+                    CellOrRange::Range((
                         *sheet_index,
                         r1.min(r2),
                         c1.min(c2),
                         r1.max(r2),
                         c1.max(c2),
-                    )));
+                    )),
+                );
                 CalcResult::Range {
                     left: CellReferenceIndex {
                         sheet: *sheet_index,
@@ -666,7 +949,23 @@ impl<'a> Model<'a> {
             OpPowerKind { left, right } => {
                 self.handle_arithmetic(left, right, cell, &|f1, f2| Ok(f1.powf(f2)))
             }
-            FunctionKind { kind, args } => self.evaluate_function(kind, args, cell),
+            FunctionKind { kind, args } => {
+                let result = self.evaluate_function(kind, args, cell);
+                // Functions like INDEX or CHOOSE might return a reference that is read later on
+                if let CalcResult::Range { left, right } = &result {
+                    self.dependency_graph.add_precedent(
+                        cell,
+                        CellOrRange::Range((
+                            left.sheet,
+                            left.row,
+                            left.column,
+                            right.row,
+                            right.column,
+                        )),
+                    );
+                }
+                result
+            }
             NamedFunctionKind { name, args, id } => {
                 let lambda_result = if let Some(var_id) = id {
                     // Bound by LET — look up the variable, which should be a Lambda.
@@ -712,12 +1011,32 @@ impl<'a> Model<'a> {
                 if let Ok(Some(parsed_defined_name)) = self.get_parsed_defined_name(name, *scope) {
                     match parsed_defined_name {
                         ParsedDefinedName::CellReference(reference) => {
+                            self.dependency_graph.add_precedent(
+                                cell,
+                                CellOrRange::Cell((
+                                    reference.sheet,
+                                    reference.row,
+                                    reference.column,
+                                )),
+                            );
                             self.evaluate_cell(reference)
                         }
-                        ParsedDefinedName::RangeReference(range) => CalcResult::Range {
-                            left: range.left,
-                            right: range.right,
-                        },
+                        ParsedDefinedName::RangeReference(range) => {
+                            self.dependency_graph.add_precedent(
+                                cell,
+                                CellOrRange::Range((
+                                    range.left.sheet,
+                                    range.left.row,
+                                    range.left.column,
+                                    range.right.row,
+                                    range.right.column,
+                                )),
+                            );
+                            CalcResult::Range {
+                                left: range.left,
+                                right: range.right,
+                            }
+                        }
                         ParsedDefinedName::LambdaDefinition(param_names, body) => {
                             let lambda_id = self.get_next_lambda_id();
                             self.lambdas.insert(lambda_id, (param_names, body));
@@ -737,11 +1056,23 @@ impl<'a> Model<'a> {
                     )
                 }
             }
-            TableNameKind(s) => CalcResult::new_error(
-                Error::NAME,
-                cell,
-                format!("table name \"{s}\" not supported."),
-            ),
+            TableNameKind(name) => self.get_table_data_range(name, cell),
+            StructuredReferenceKind {
+                table_name,
+                specifier,
+                table_reference,
+            } => {
+                match self.get_structured_reference_range(
+                    table_name,
+                    specifier,
+                    table_reference,
+                    cell,
+                ) {
+                    // Table1[@Amount] is the value of the cell
+                    CalcResult::Range { left, right } if left == right => self.evaluate_cell(left),
+                    result => result,
+                }
+            }
             NamedVariableKind { name, id: Some(id) } => {
                 match self.variable_stack.get(&(*id as usize)) {
                     Some(v) => v.clone(),
@@ -1141,7 +1472,7 @@ impl<'a> Model<'a> {
                     m: message.to_string(),
                 }
             }
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::MultiRange(_) => {
                 // This should never happen
                 debug_assert!(false, "Unexpected range result in non-array formula");
                 return Err("Cannot set a range as cell value".to_string());
@@ -1252,7 +1583,11 @@ impl<'a> Model<'a> {
     }
 
     // Returns the 'single' value of a cell. Not arrays or ranges.
-    fn get_cell_value(&self, cell: &Cell, cell_reference: CellReferenceIndex) -> CalcResult {
+    pub(crate) fn get_cell_value(
+        &self,
+        cell: &Cell,
+        cell_reference: CellReferenceIndex,
+    ) -> CalcResult {
         use Cell::*;
         match cell {
             EmptyCell { .. } => CalcResult::EmptyCell,
@@ -1385,7 +1720,10 @@ impl<'a> Model<'a> {
                     CalcResult::String(s) => ArrayNode::String(s),
                     CalcResult::Error { error, .. } => ArrayNode::Error(error),
                     CalcResult::EmptyCell | CalcResult::EmptyArg => ArrayNode::Empty,
-                    CalcResult::Range { .. } | CalcResult::Array(_) | CalcResult::Lambda(_) => {
+                    CalcResult::Range { .. }
+                    | CalcResult::MultiRange(_)
+                    | CalcResult::Array(_)
+                    | CalcResult::Lambda(_) => {
                         // This should never happen, but we need to handle it anyway
                         debug_assert!(false, "Unexpected array result in non-array formula");
                         ArrayNode::Error(Error::NIMPL)
@@ -1444,6 +1782,33 @@ impl<'a> Model<'a> {
                 if let Some(state) = self.cells.get(&key) {
                     match state {
                         CellState::Evaluating => {
+                            if self.workbook.settings.iterative_calculation.enabled {
+                                // Use the value of the previous iteration. Cells that were never
+                                // computed (or were flagged as circular before) start at zero.
+                                self.circular_cells.insert(cell_reference);
+                                let starts_at_zero = matches!(
+                                    original_cell,
+                                    Cell::CellFormula {
+                                        v: FormulaValue::Unevaluated
+                                            | FormulaValue::Error {
+                                                ei: Error::CIRC,
+                                                ..
+                                            },
+                                        ..
+                                    } | Cell::ArrayFormula {
+                                        v: FormulaValue::Unevaluated
+                                            | FormulaValue::Error {
+                                                ei: Error::CIRC,
+                                                ..
+                                            },
+                                        ..
+                                    }
+                                );
+                                if starts_at_zero {
+                                    return CalcResult::Number(0.0);
+                                }
+                                return self.get_cell_value(&original_cell, cell_reference);
+                            }
                             return CalcResult::new_error(
                                 Error::CIRC,
                                 cell_reference,
@@ -1500,6 +1865,9 @@ impl<'a> Model<'a> {
                 }
                 // mark cell as being evaluated
                 self.cells.insert(key, CellState::Evaluating);
+                if let Some(detached_cells) = &mut self.detached_cells {
+                    detached_cells.push((cell_reference, original_cell.clone()));
+                }
                 let (node, _static_result) =
                     &self.parsed_formulas[cell_reference.sheet as usize][f as usize];
                 let result = self.evaluate_node_in_context(&node.clone(), cell_reference);
@@ -1528,6 +1896,8 @@ impl<'a> Model<'a> {
                             CalcResult::Array(array)
                         }
                     }
+                } else if matches!(result, CalcResult::MultiRange(_)) {
+                    CalcResult::new_multiple_areas_error(cell_reference)
                 } else if matches!(result, CalcResult::Lambda(_)) {
                     CalcResult::new_error(
                         Error::CALC,
@@ -1700,7 +2070,10 @@ impl<'a> Model<'a> {
         );
         let mut shared_strings = HashMap::new();
         for (index, s) in workbook.shared_strings.iter().enumerate() {
-            shared_strings.insert(s.to_string(), index);
+            // Plain texts are never stored in the shared strings of rich texts
+            if !workbook.rich_text.contains_key(&(index as i32)) {
+                shared_strings.insert(s.to_string(), index);
+            }
         }
 
         let mut model = Model {
@@ -1719,9 +2092,14 @@ impl<'a> Model<'a> {
             lambdas: HashMap::new(),
             last_lambda_id: 0,
             spill_cells: Vec::new(),
-            support: HashMap::new(),
+            dependency_graph: DependencyGraph::default(),
+            dirty_cells: Vec::new(),
+            needs_full_evaluation: true,
+            circular_cells: HashSet::new(),
+            detached_cells: None,
             cf_cache: HashMap::new(),
             links: HashMap::new(),
+            external_link_resolver: None,
         };
 
         model.parse_formulas();
@@ -1984,6 +2362,75 @@ impl<'a> Model<'a> {
         Ok(value.to_string())
     }
 
+    /// Like [Model::extend_copied_value()] but the relative references of the formula are
+    /// transposed, as when pasting a range with rows and columns swapped
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # use ironcalc_base::expressions::types::CellReferenceIndex;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// let source = CellReferenceIndex {sheet: 0, row: 2, column: 1};
+    /// let target = CellReferenceIndex {sheet: 0, row: 1, column: 4};
+    /// let result = model.transpose_copied_value("=A1*2+$B$1", &source, &target)?;
+    /// assert_eq!(&result, "=C1*2+$B$1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transpose_copied_value(
+        &mut self,
+        value: &str,
+        source: &CellReferenceIndex,
+        target: &CellReferenceIndex,
+    ) -> Result<String, String> {
+        let source_sheet_name = self.workbook.worksheet(source.sheet)?.get_name();
+        let target_sheet_name = self.workbook.worksheet(target.sheet)?.get_name();
+        if let Some(formula_str) = self.formula_without_prefix(value) {
+            let cell_reference = CellReferenceRC {
+                sheet: source_sheet_name,
+                row: source.row,
+                column: source.column,
+            };
+            let mut formula = self.parser.parse(formula_str, &cell_reference);
+            transpose_references_in_node(&mut formula);
+            let cell_reference = CellReferenceRC {
+                sheet: target_sheet_name,
+                row: target.row,
+                column: target.column,
+            };
+            return Ok(format!(
+                "={}",
+                to_localized_string(&formula, &cell_reference, self.locale, self.language)
+            ));
+        }
+        Ok(value.to_string())
+    }
+
+    /// Parses a formula copied from another workbook, written in the `language` and `locale` of
+    /// that workbook. The references to sheets missing in this workbook point to `workbook`.
+    /// Returns None if `value` is not a formula.
+    pub(crate) fn parse_external_copied_formula(
+        &mut self,
+        value: &str,
+        context: &CellReferenceRC,
+        (workbook, language, locale): (&str, &str, &str),
+    ) -> Option<Node> {
+        let formula = self.formula_without_prefix(value)?;
+        if let Ok(language) = get_language(language) {
+            self.parser.set_language(language);
+        }
+        if let Ok(locale) = get_locale(locale) {
+            self.parser.set_locale(locale);
+        }
+        let mut node = self.parser.parse(formula, context);
+        self.parser.set_language(self.language);
+        self.parser.set_locale(self.locale);
+        externalize_wrong_references_in_node(&mut node, workbook);
+        Some(node)
+    }
+
     /// Returns the formula in (`sheet`, `row`, `column`) if any
     ///
     /// # Examples
@@ -2103,23 +2550,32 @@ impl<'a> Model<'a> {
         column: i32,
         value: &str,
     ) -> Result<(), String> {
+        let new_style_index = self.get_text_style_index(sheet, row, column, value)?;
+        self.mark_cell_dirty(sheet, row, column);
+        self.set_cell_with_string(sheet, row, column, value, new_style_index)
+    }
+
+    /// The style of the cell once its value is the text `value`: with a quote prefix if the
+    /// text would not be read as a text otherwise.
+    pub(crate) fn get_text_style_index(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        value: &str,
+    ) -> Result<i32, String> {
         let style_index = self.get_cell_style_index(sheet, row, column)?;
-        let new_style_index;
         if common::value_needs_quoting(value, self.language) {
-            new_style_index = self
-                .workbook
+            self.workbook
                 .styles
-                .get_style_with_quote_prefix(style_index)?;
+                .get_style_with_quote_prefix(style_index)
         } else if self.workbook.styles.style_is_quote_prefix(style_index) {
-            new_style_index = self
-                .workbook
+            self.workbook
                 .styles
-                .get_style_without_quote_prefix(style_index)?;
+                .get_style_without_quote_prefix(style_index)
         } else {
-            new_style_index = style_index;
+            Ok(style_index)
         }
-
-        self.set_cell_with_string(sheet, row, column, value, new_style_index)
     }
 
     /// Updates the value of a cell with a boolean value
@@ -2161,6 +2617,7 @@ impl<'a> Model<'a> {
         } else {
             style_index
         };
+        self.mark_cell_dirty(sheet, row, column);
         self.set_cell_with_boolean(sheet, row, column, value, new_style_index)
     }
 
@@ -2203,6 +2660,7 @@ impl<'a> Model<'a> {
         } else {
             style_index
         };
+        self.mark_cell_dirty(sheet, row, column);
         self.set_cell_with_number(sheet, row, column, value, new_style_index)
     }
 
@@ -2250,6 +2708,7 @@ impl<'a> Model<'a> {
 
         if let Some(new_formula) = self.formula_without_prefix(&formula) {
             self.set_cell_with_formula(sheet, row, column, new_formula, style_index)?;
+            self.mark_cell_dirty(sheet, row, column);
             Ok(())
         } else {
             Err(format!("\"{formula}\" is not a valid formula"))
@@ -2263,7 +2722,7 @@ impl<'a> Model<'a> {
     // - Part of a dynamic array formula => we delete the formula and we clear the spill
     // - Anchor of a dynamic array formula
     //     => we clear the spill and we set an unevaluated dynamic formula.
-    fn prepare_cell_for_user_input(
+    pub(crate) fn prepare_cell_for_user_input(
         &mut self,
         sheet: u32,
         row: i32,
@@ -2376,6 +2835,7 @@ impl<'a> Model<'a> {
     ) -> Result<(), String> {
         // first we make sure we can write in the cell and clear the spills.
         self.prepare_cell_for_user_input(sheet, row, column)?;
+        self.mark_cell_dirty(sheet, row, column);
         if value.is_empty() {
             // If the value is empty we just clear the cell.
             // Deleting the contents of a cell also removes its link.
@@ -2426,7 +2886,7 @@ impl<'a> Model<'a> {
 
                 //  We try to parse as number
                 if let Ok((v, number_format)) =
-                    parse_formatted_number(&value, &currencies, self.locale)
+                    parse_formatted_number(&value, &currencies, self.locale, self.date_system())
                 {
                     if let Some(num_fmt) = number_format {
                         // Should not apply the format in the following cases:
@@ -2482,6 +2942,13 @@ impl<'a> Model<'a> {
         value: &str,
     ) -> Result<(), String> {
         self.prepare_cell_for_user_input(sheet, row, column)?;
+        self.mark_area_dirty(&Area {
+            sheet,
+            row,
+            column,
+            width,
+            height,
+        });
         // If value starts with "'" then we force the style to be quote_prefix
         let style_index = self.get_cell_style_index(sheet, row, column)?;
         if value.strip_prefix('\'').is_none() {
@@ -2829,7 +3296,13 @@ impl<'a> Model<'a> {
                 let format = self.get_style_for_cell(sheet_index, row, column)?.num_fmt;
                 let formatted_value =
                     cell.formatted_value(&self.workbook.shared_strings, self.language, |value| {
-                        format_number(value, &format, self.locale).text
+                        format_number_with_date_system(
+                            value,
+                            &format,
+                            self.locale,
+                            self.date_system(),
+                        )
+                        .text
                     });
                 Ok(formatted_value)
             }
@@ -2892,7 +3365,12 @@ impl<'a> Model<'a> {
                     if is_likely_date_number_format(&style.num_fmt) {
                         let value = cell.value(&self.workbook.shared_strings, self.language);
                         if let CellValue::Number(n) = value {
-                            let formatted = format_number(n, &style.num_fmt, self.locale);
+                            let formatted = format_number_with_date_system(
+                                n,
+                                &style.num_fmt,
+                                self.locale,
+                                self.date_system(),
+                            );
                             if formatted.error.is_none() {
                                 return Ok(formatted.text);
                             }
@@ -2993,11 +3471,7 @@ impl<'a> Model<'a> {
         cell: CellReferenceIndex,
         positions: &[CellReferenceIndex],
     ) -> bool {
-        let deps = match self.support.get(&cell) {
-            Some(d) => d,
-            None => return false,
-        };
-        for dep in deps {
+        for dep in self.dependency_graph.precedents(&cell) {
             match *dep {
                 CellOrRange::Cell((sheet, row, col)) => {
                     if positions
@@ -3045,7 +3519,8 @@ impl<'a> Model<'a> {
         while retry && restart_count < max_restarts {
             retry = false;
             self.cells.clear();
-            self.support.clear();
+            self.circular_cells.clear();
+            self.dependency_graph.clear();
             // dynamic links (HYPERLINK) are rebuilt on every evaluation
             self.links.clear();
             self.clear_variable_stack();
@@ -3079,6 +3554,16 @@ impl<'a> Model<'a> {
 
         // Phase 2: evaluate everything else; spill cells are already Evaluated and skipped.
         // Fallback when max restarts is exceeded (circular spill dependency).
+        self.evaluate_all_cells();
+        if !self.circular_cells.is_empty() {
+            self.iterate_circular_references();
+        }
+        self.dirty_cells.clear();
+        self.needs_full_evaluation = false;
+        self.evaluate_conditional_formatting();
+    }
+
+    fn evaluate_all_cells(&mut self) {
         let all_cells = self.get_all_cells();
         for cell in all_cells {
             self.evaluate_cell(CellReferenceIndex {
@@ -3087,9 +3572,246 @@ impl<'a> Model<'a> {
                 column: cell.column,
             });
         }
+    }
+
+    // Recalculates the cells in circular references, and the cells that depend on them, until
+    // their values converge or the maximum number of iterations is reached.
+    // The first evaluation counts as the first iteration.
+    fn iterate_circular_references(&mut self) {
+        let IterativeCalculation {
+            max_iterations,
+            max_change,
+            ..
+        } = self.workbook.settings.iterative_calculation;
+        let mut iteration = 1;
+        while iteration < max_iterations && !self.circular_cells.is_empty() {
+            let circular_cells: Vec<CellReferenceIndex> = self.circular_cells.drain().collect();
+            let previous: Vec<(CellReferenceIndex, CalcResult)> = circular_cells
+                .iter()
+                .map(|&cell| (cell, self.get_stored_value(cell)))
+                .collect();
+            let changed = circular_cells
+                .iter()
+                .map(|cell| CellOrRange::Cell((cell.sheet, cell.row, cell.column)))
+                .collect();
+            match self.cells_to_recalculate(changed) {
+                Some(cells) => self.recalculate_cells(cells),
+                None => {
+                    // Dynamic arrays can change the shape of the cycle, we recompute everything
+                    self.cells.clear();
+                    self.dependency_graph.clear();
+                    self.links.clear();
+                    self.clear_variable_stack();
+                    self.clear_lambdas();
+                    for i in 0..self.spill_cells.len() {
+                        let spill_cell = self.spill_cells[i];
+                        self.evaluate_cell(spill_cell);
+                    }
+                    self.evaluate_all_cells();
+                }
+            }
+            iteration += 1;
+            let converged = previous.iter().all(|(cell, old_value)| {
+                match (old_value, self.get_stored_value(*cell)) {
+                    (CalcResult::Number(a), CalcResult::Number(b)) => (a - b).abs() <= max_change,
+                    (a, b) => *a == b,
+                }
+            });
+            if converged {
+                break;
+            }
+        }
+    }
+
+    // The value currently stored in a cell, without evaluating it
+    fn get_stored_value(&self, cell_reference: CellReferenceIndex) -> CalcResult {
+        match self.fetch_cell(cell_reference) {
+            Some(cell) => self.get_cell_value(cell, cell_reference),
+            None => CalcResult::EmptyCell,
+        }
+    }
+
+    /// Records that the content of a cell changed since the last evaluation.
+    ///
+    /// See also:
+    /// * [Model::evaluate_incremental]
+    pub(crate) fn mark_cell_dirty(&mut self, sheet: u32, row: i32, column: i32) {
+        self.dirty_cells
+            .push(CellOrRange::Cell((sheet, row, column)));
+    }
+
+    /// Records that the content of the cells in an area changed since the last evaluation.
+    pub(crate) fn mark_area_dirty(&mut self, area: &Area) {
+        self.dirty_cells.push(CellOrRange::Range((
+            area.sheet,
+            area.row,
+            area.column,
+            area.row + area.height - 1,
+            area.column + area.width - 1,
+        )));
+    }
+
+    /// Runs `f`, that evaluates formulas on a state of the workbook that is not meant to last
+    /// (for instance with a value temporarily written in a cell), without leaving a trace:
+    /// nothing is recorded in the dependency graph and the formula cells that were not yet
+    /// evaluated are restored, so they are evaluated again next time.
+    pub(crate) fn evaluate_detached<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.detached_cells.replace(Vec::new());
+        self.dependency_graph.pause();
+        let result = f(self);
+        let detached_cells = std::mem::replace(&mut self.detached_cells, outer);
+        if self.detached_cells.is_none() {
+            self.dependency_graph.resume();
+        }
+        for (cell_reference, cell) in detached_cells.unwrap_or_default().into_iter().rev() {
+            let CellReferenceIndex { sheet, row, column } = cell_reference;
+            self.cells.remove(&(sheet, row, column));
+            if let Ok(worksheet) = self.workbook.worksheet_mut(sheet) {
+                let _ = worksheet.update_cell(row, column, cell);
+            }
+        }
+        result
+    }
+
+    /// Flags the dependency graph as stale: the next [Model::evaluate_incremental]
+    /// will recompute the whole workbook. Used after structural changes (inserting or
+    /// deleting rows, moving columns, ...) that shift the cells the graph points to.
+    pub(crate) fn invalidate_dependency_graph(&mut self) {
+        self.needs_full_evaluation = true;
+    }
+
+    /// Recomputes only the cells that changed since the last evaluation, their
+    /// (transitive) dependents and all the cells with volatile functions.
+    ///
+    /// The model falls back to a full [Model::evaluate] when the dependency graph is not
+    /// available (the model was never evaluated or there was a structural change) or
+    /// when dynamic arrays are involved, since their spill areas can change in size.
+    /// In iterative calculation mode only the cells in circular references and their
+    /// dependents are recomputed at each iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// model.set_user_input(0, 1, 1, "2".to_string())?;
+    /// model.set_user_input(0, 1, 2, "=A1*3".to_string())?;
+    /// model.evaluate();
+    /// model.set_user_input(0, 1, 1, "5".to_string())?;
+    /// model.evaluate_incremental();
+    /// assert_eq!(model.get_formatted_cell_value(0, 1, 2)?, "15");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn evaluate_incremental(&mut self) {
+        if self.needs_full_evaluation || !self.spill_cells.is_empty() {
+            self.evaluate();
+            return;
+        }
+        let changed = std::mem::take(&mut self.dirty_cells);
+        let cells = match self.cells_to_recalculate(changed) {
+            Some(cells) => cells,
+            None => {
+                self.evaluate();
+                return;
+            }
+        };
+        self.circular_cells.clear();
+        self.recalculate_cells(cells);
+        if !self.circular_cells.is_empty() {
+            self.iterate_circular_references();
+        }
         self.evaluate_conditional_formatting();
     }
 
+    // Forgets the values and precedents of the cells and evaluates them again
+    fn recalculate_cells(&mut self, mut cells: Vec<CellReferenceIndex>) {
+        self.clear_variable_stack();
+        self.clear_lambdas();
+        for cell in &cells {
+            let key = (cell.sheet, cell.row, cell.column);
+            self.cells.remove(&key);
+            self.links.remove(&key);
+            self.dependency_graph.remove_cell(cell);
+        }
+        cells.sort_unstable_by_key(|c| (c.sheet, c.row, c.column));
+        for cell in cells {
+            self.evaluate_cell(cell);
+        }
+    }
+
+    /// Returns the cells that need to be recomputed after the cells and ranges in
+    /// `changed` were modified. Returns `None` if a full evaluation is needed.
+    fn cells_to_recalculate(&self, changed: Vec<CellOrRange>) -> Option<Vec<CellReferenceIndex>> {
+        let mut result = HashSet::new();
+        let mut queue = Vec::new();
+        for position in changed {
+            // The modified cells themselves need to be evaluated if they are formulas
+            match position {
+                CellOrRange::Cell((sheet, row, column)) => {
+                    result.insert(CellReferenceIndex { sheet, row, column });
+                }
+                CellOrRange::Range((sheet, row1, column1, row2, column2)) => {
+                    let worksheet = self.workbook.worksheet(sheet).ok()?;
+                    for (row, row_data) in &worksheet.sheet_data {
+                        if *row < row1 || *row > row2 {
+                            continue;
+                        }
+                        for column in row_data.keys() {
+                            if *column >= column1 && *column <= column2 {
+                                result.insert(CellReferenceIndex {
+                                    sheet,
+                                    row: *row,
+                                    column: *column,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            queue.push(position);
+        }
+        for cell in self.dependency_graph.volatile_cells() {
+            if result.insert(cell) {
+                queue.push(CellOrRange::Cell((cell.sheet, cell.row, cell.column)));
+            }
+        }
+        // Array formulas also write on their spill area, whose dependents change too
+        let mut visited_anchors = HashSet::new();
+        while let Some(position) = queue.pop() {
+            let mut affected = self.dependency_graph.dependents(&position);
+            if let CellOrRange::Cell((sheet, row, column)) = position {
+                affected.push(CellReferenceIndex { sheet, row, column });
+            }
+            for cell in affected {
+                if result.insert(cell) {
+                    queue.push(CellOrRange::Cell((cell.sheet, cell.row, cell.column)));
+                }
+                if !visited_anchors.insert(cell) {
+                    continue;
+                }
+                match self.fetch_cell(cell) {
+                    Some(Cell::ArrayFormula {
+                        kind: ArrayKind::Dynamic,
+                        ..
+                    }) => return None,
+                    Some(Cell::ArrayFormula {
+                        r: (width, height), ..
+                    }) => queue.push(CellOrRange::Range((
+                        cell.sheet,
+                        cell.row,
+                        cell.column,
+                        cell.row + height - 1,
+                        cell.column + width - 1,
+                    ))),
+                    _ => {}
+                }
+            }
+        }
+        Some(result.into_iter().collect())
+    }
+
     /// Removes the content of every cell in the range but leaves the style.
     ///
     /// See also:
@@ -3122,6 +3844,7 @@ impl<'a> Model<'a> {
         if !self.can_clear_range(range)? {
             return Err("Cannot clear the range because it contains array formulas".to_string());
         }
+        self.mark_area_dirty(range);
         let sheet = range.sheet;
         let ws = self.workbook.worksheet_mut(sheet)?;
         for row in range.row..range.row + range.height {
@@ -3224,6 +3947,7 @@ impl<'a> Model<'a> {
         if !self.can_clear_range(area)? {
             return Err("Cannot clear the range because it contains array formulas".to_string());
         }
+        self.mark_area_dirty(area);
         let worksheet = self.workbook.worksheet_mut(area.sheet)?;
 
         let sheet_data = &mut worksheet.sheet_data;
@@ -3843,6 +4567,61 @@ impl<'a> Model<'a> {
         Ok(())
     }
 
+    /// Sets the iterative calculation settings and recalculates the workbook
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # use ironcalc_base::types::IterativeCalculation;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// model.set_user_input(0, 1, 1, "=A1/2+1".to_string())?;
+    /// model.evaluate();
+    /// assert_eq!(model.get_formatted_cell_value(0, 1, 1)?, "#CIRC!");
+    /// model.set_iterative_calculation(IterativeCalculation {
+    ///     enabled: true,
+    ///     max_iterations: 100,
+    ///     max_change: 0.001,
+    /// })?;
+    /// assert_eq!(model.get_formatted_cell_value(0, 1, 1)?, "1.999023438");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_iterative_calculation(
+        &mut self,
+        settings: IterativeCalculation,
+    ) -> Result<(), String> {
+        validate_iterative_calculation(&settings)?;
+        self.workbook.settings.iterative_calculation = settings;
+        self.evaluate();
+        Ok(())
+    }
+
+    /// Gets the iterative calculation settings
+    pub fn get_iterative_calculation(&self) -> IterativeCalculation {
+        self.workbook.settings.iterative_calculation.clone()
+    }
+
+    /// Sets the date system of the workbook and recalculates it.
+    ///
+    /// Note that the serial numbers stored in the cells are not changed, so existing dates
+    /// shift by four years and one day.
+    pub fn set_date_system(&mut self, date_system: DateSystem) {
+        self.workbook.settings.date_system = date_system;
+        self.evaluate();
+    }
+
+    /// Gets the date system of the workbook
+    pub fn get_date_system(&self) -> DateSystem {
+        self.workbook.settings.date_system
+    }
+
+    // Shorthand used by the formatter and the date functions
+    pub(crate) fn date_system(&self) -> DateSystem {
+        self.workbook.settings.date_system
+    }
+
     /// Sets the language
     pub fn set_language(&mut self, language_id: &str) -> Result<(), String> {
         let language = match get_language(language_id) {
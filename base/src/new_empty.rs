@@ -1,16 +1,18 @@
 use chrono::DateTime;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     calc_result::Range,
     constants::{DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH},
+    dependency_graph::DependencyGraph,
     expressions::{
         lexer::LexerMode,
         parser::{
             static_analysis::run_static_analysis_on_node,
             stringify::{
-                rename_sheet_in_node, to_english_string, to_localized_string, to_rc_format,
+                rename_sheet_in_node, retarget_sheet_in_node, shrink_sheet_spans_in_node,
+                to_english_string, to_localized_string, to_rc_format,
             },
             Node, Parser,
         },
@@ -20,8 +22,8 @@ use crate::{
     locale::{get_default_locale, get_locale},
     model::{get_milliseconds_since_epoch, Model, ParsedDefinedName},
     types::{
-        DefinedName, Metadata, SheetState, Workbook, WorkbookSettings, WorkbookView, Worksheet,
-        WorksheetView,
+        DateSystem, DefinedName, IterativeCalculation, Metadata, SheetState, Workbook,
+        WorkbookSettings, WorkbookView, Worksheet, WorksheetView,
     },
     utils::ParsedReference,
 };
@@ -73,7 +75,13 @@ impl<'a> Model<'a> {
             show_grid_lines: true,
             views,
             conditional_formatting: vec![],
+            data_validations: vec![],
             links: HashMap::new(),
+            protection: None,
+            sort_state: None,
+            auto_filter: None,
+            outline_properties: Default::default(),
+            page_setup: Default::default(),
         }
     }
 
@@ -368,7 +376,7 @@ impl<'a> Model<'a> {
         let mut shared_formulas = Vec::with_capacity(new_worksheet.shared_formulas.len());
         for formula in &new_worksheet.shared_formulas {
             let mut t = self.parser.parse(formula, &cell_reference);
-            rename_sheet_in_node(&mut t, source_index, &new_name);
+            retarget_sheet_in_node(&mut t, source_index, &new_name);
             shared_formulas.push(to_rc_format(&t));
         }
         new_worksheet.shared_formulas = shared_formulas;
@@ -418,7 +426,7 @@ impl<'a> Model<'a> {
                 .unwrap_or(&defined_name.formula);
             let mut node = self.parse_internal_formula(body, &context);
             let before = to_english_string(&node, &context);
-            rename_sheet_in_node(&mut node, source_index, &new_name);
+            retarget_sheet_in_node(&mut node, source_index, &new_name);
             let after = to_english_string(&node, &context);
 
             // Global names are only duplicated when they actually reference the
@@ -520,8 +528,14 @@ impl<'a> Model<'a> {
         }
         self.workbook.defined_names = defined_names;
 
-        // Update the name of the worksheet
+        // Update the name of the worksheet and of its tables
         self.workbook.worksheet_mut(sheet_index)?.set_name(new_name);
+        for table in self.workbook.tables.values_mut() {
+            if table.sheet_name == old_name {
+                table.sheet_name = new_name.to_string();
+            }
+        }
+        self.parser.set_tables(self.workbook.tables.clone());
         self.reset_parsed_structures();
         Ok(())
     }
@@ -538,11 +552,66 @@ impl<'a> Model<'a> {
         if sheet_index >= sheet_count {
             return Err("Sheet index too large".to_string());
         };
-        self.workbook.worksheets.remove(sheet_index as usize);
+        self.shrink_sheet_spans(sheet_index);
+        let worksheet = self.workbook.worksheets.remove(sheet_index as usize);
+        // The tables of the sheet are deleted with it
+        self.workbook
+            .tables
+            .retain(|_, table| table.sheet_name != worksheet.name);
+        self.parser.set_tables(self.workbook.tables.clone());
         self.reset_parsed_structures();
         Ok(())
     }
 
+    // 3D references that start or end in a sheet that is about to be deleted
+    // now start or end in the adjacent sheet within the span (like Excel does)
+    fn shrink_sheet_spans(&mut self, sheet_index: u32) {
+        let worksheet_names = self.workbook.get_worksheet_names();
+        let previous = sheet_index
+            .checked_sub(1)
+            .and_then(|index| worksheet_names.get(index as usize))
+            .map(|name| name.as_str());
+        let next = worksheet_names
+            .get(sheet_index as usize + 1)
+            .map(|name| name.as_str());
+
+        // All internal formulas are R1C1
+        self.parser.set_lexer_mode(LexerMode::R1C1);
+        for worksheet in &mut self.workbook.worksheets {
+            let cell_reference = &CellReferenceRC {
+                sheet: worksheet.get_name(),
+                row: 1,
+                column: 1,
+            };
+            let mut formulas = Vec::new();
+            for formula in &worksheet.shared_formulas {
+                let mut t = self.parser.parse(formula, cell_reference);
+                shrink_sheet_spans_in_node(&mut t, sheet_index, previous, next);
+                formulas.push(to_rc_format(&t));
+            }
+            worksheet.shared_formulas = formulas;
+        }
+        self.parser.set_lexer_mode(LexerMode::A1);
+
+        // Defined names are stored in English
+        let context = self.defined_name_context();
+        let mut defined_names = self.workbook.defined_names.clone();
+        for defined_name in &mut defined_names {
+            let body = defined_name
+                .formula
+                .strip_prefix('=')
+                .unwrap_or(&defined_name.formula);
+            let mut node = self.parse_internal_formula(body, &context);
+            let before = to_english_string(&node, &context);
+            shrink_sheet_spans_in_node(&mut node, sheet_index, previous, next);
+            let after = to_english_string(&node, &context);
+            if before != after {
+                defined_name.formula = defined_name.formula.replacen(body, &after, 1);
+            }
+        }
+        self.workbook.defined_names = defined_names;
+    }
+
     /// Moves the worksheet at `sheet_index` to `new_index`, shifting the other
     /// sheets to accommodate. The moved worksheet ends up at exactly `new_index`.
     ///
@@ -652,6 +721,7 @@ impl<'a> Model<'a> {
         // String versions of the locale are added here to simplify the serialize/deserialize logic
         let workbook = Workbook {
             shared_strings: vec![],
+            rich_text: HashMap::new(),
             defined_names: vec![],
             worksheets: vec![Model::new_empty_worksheet(&sheet_name, 1, &[&0])],
             styles: Default::default(),
@@ -659,6 +729,8 @@ impl<'a> Model<'a> {
             settings: WorkbookSettings {
                 tz: timezone.to_string(),
                 locale: locale_id.to_string(),
+                iterative_calculation: IterativeCalculation::default(),
+                date_system: DateSystem::Date1900,
             },
             metadata: Metadata {
                 application: APPLICATION.to_string(),
@@ -671,6 +743,8 @@ impl<'a> Model<'a> {
             tables: HashMap::new(),
             views,
             theme: Default::default(),
+            external_links: vec![],
+            protection: None,
         };
         let parsed_formulas = Vec::new();
         let worksheets = &workbook.worksheets;
@@ -694,9 +768,14 @@ impl<'a> Model<'a> {
             lambdas: HashMap::new(),
             last_lambda_id: 0,
             spill_cells: Vec::new(),
-            support: HashMap::new(),
+            dependency_graph: DependencyGraph::default(),
+            dirty_cells: Vec::new(),
+            needs_full_evaluation: true,
+            circular_cells: HashSet::new(),
+            detached_cells: None,
             cf_cache: HashMap::new(),
             links: HashMap::new(),
+            external_link_resolver: None,
         };
         model.parse_formulas();
         model.evaluate_conditional_formatting();
@@ -718,6 +797,7 @@ mod tests {
             font: None,
             fill: Some(Fill {
                 color: Color::Rgb("#FF0000".to_string()),
+                ..Default::default()
             }),
             border: None,
             num_fmt: None,
@@ -0,0 +1,435 @@
+//! Outline groups of rows and columns.
+//!
+//! The outline level of each row (or column) is stored in [Row](crate::types::Row) (or
+//! [Col](crate::types::Col)). A group of level `n` is a maximal run of consecutive rows with a
+//! level of `n` or more. Like in Excel, the state of a group is stored in its summary row, the
+//! one next to it (below or above depending on
+//! [OutlineProperties](crate::outline_types::OutlineProperties)): the group is collapsed if its
+//! summary row is. Collapsing a group hides its rows and expanding it shows them again, except
+//! those in collapsed groups nested in it.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    constants::{LAST_COLUMN, LAST_ROW},
+    expressions::utils::{is_valid_column_number, is_valid_row},
+    model::Model,
+    outline_types::{Outline, OutlineGroup, MAX_OUTLINE_LEVEL},
+    types::Worksheet,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Rows,
+    Columns,
+}
+
+impl Axis {
+    fn name(self) -> &'static str {
+        match self {
+            Axis::Rows => "Row",
+            Axis::Columns => "Column",
+        }
+    }
+
+    fn last(self) -> i32 {
+        match self {
+            Axis::Rows => LAST_ROW,
+            Axis::Columns => LAST_COLUMN,
+        }
+    }
+
+    fn check(self, index: i32) -> Result<(), String> {
+        let is_valid = match self {
+            Axis::Rows => is_valid_row(index),
+            Axis::Columns => is_valid_column_number(index),
+        };
+        if !is_valid {
+            return Err(format!("{} number '{index}' is not valid.", self.name()));
+        }
+        Ok(())
+    }
+
+    // The rows (or columns) with an outline level, sorted
+    fn levels(self, worksheet: &Worksheet) -> BTreeMap<i32, u8> {
+        let mut levels = BTreeMap::new();
+        match self {
+            Axis::Rows => {
+                for row in worksheet.rows.iter().filter(|r| r.outline_level > 0) {
+                    levels.insert(row.r, row.outline_level);
+                }
+            }
+            Axis::Columns => {
+                for col in worksheet.cols.iter().filter(|c| c.outline_level > 0) {
+                    for column in col.min..=col.max {
+                        levels.insert(column, col.outline_level);
+                    }
+                }
+            }
+        }
+        levels
+    }
+
+    // The summary row (or column) of a group, it might be out of the sheet
+    fn summary(self, worksheet: &Worksheet, start: i32, end: i32) -> i32 {
+        let summary_after = match self {
+            Axis::Rows => worksheet.outline_properties.summary_below,
+            Axis::Columns => worksheet.outline_properties.summary_right,
+        };
+        if summary_after {
+            end + 1
+        } else {
+            start - 1
+        }
+    }
+
+    fn is_hidden(self, worksheet: &Worksheet, index: i32) -> Result<bool, String> {
+        match self {
+            Axis::Rows => worksheet.is_row_hidden(index),
+            Axis::Columns => worksheet.is_column_hidden(index),
+        }
+    }
+
+    fn set_hidden(self, worksheet: &mut Worksheet, index: i32, hidden: bool) -> Result<(), String> {
+        if self.is_hidden(worksheet, index)? == hidden {
+            return Ok(());
+        }
+        match self {
+            Axis::Rows => worksheet.set_row_hidden(index, hidden),
+            Axis::Columns => worksheet.set_column_hidden(index, hidden),
+        }
+    }
+
+    fn is_collapsed(self, worksheet: &Worksheet, index: i32) -> Result<bool, String> {
+        match self {
+            Axis::Rows => worksheet.is_row_collapsed(index),
+            Axis::Columns => worksheet.is_column_collapsed(index),
+        }
+    }
+
+    fn set_collapsed(
+        self,
+        worksheet: &mut Worksheet,
+        index: i32,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        match self {
+            Axis::Rows => worksheet.set_row_collapsed(index, collapsed),
+            Axis::Columns => worksheet.set_column_collapsed(index, collapsed),
+        }
+    }
+
+    fn set_level(self, worksheet: &mut Worksheet, index: i32, level: u8) -> Result<(), String> {
+        match self {
+            Axis::Rows => worksheet.set_row_outline_level(index, level),
+            Axis::Columns => worksheet.set_column_outline_level(index, level),
+        }
+    }
+
+    fn is_group_collapsed(
+        self,
+        worksheet: &Worksheet,
+        start: i32,
+        end: i32,
+    ) -> Result<bool, String> {
+        let summary = self.summary(worksheet, start, end);
+        if (1..=self.last()).contains(&summary) {
+            return self.is_collapsed(worksheet, summary);
+        }
+        // There is no summary row to store the state, the group is collapsed if it is hidden
+        for index in start..=end {
+            if !self.is_hidden(worksheet, index)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+// The first and last rows of the group of `level` that contains `index`
+fn get_group(levels: &BTreeMap<i32, u8>, index: i32, level: u8) -> (i32, i32) {
+    let level_of = |i: i32| levels.get(&i).copied().unwrap_or(0);
+    let mut start = index;
+    while level_of(start - 1) >= level {
+        start -= 1;
+    }
+    let mut end = index;
+    while level_of(end + 1) >= level {
+        end += 1;
+    }
+    (start, end)
+}
+
+fn get_outline(levels: &BTreeMap<i32, u8>) -> Vec<(u8, i32, i32)> {
+    let mut groups = Vec::new();
+    // The first row of the open groups, by level
+    let mut open: Vec<i32> = Vec::new();
+    let mut previous = 0;
+    for (&index, &level) in levels {
+        if index != previous + 1 {
+            while let Some(start) = open.pop() {
+                groups.push((open.len() as u8 + 1, start, previous));
+            }
+        }
+        while open.len() > level as usize {
+            let start = open.pop().unwrap_or(index);
+            groups.push((open.len() as u8 + 1, start, previous));
+        }
+        while open.len() < level as usize {
+            open.push(index);
+        }
+        previous = index;
+    }
+    while let Some(start) = open.pop() {
+        groups.push((open.len() as u8 + 1, start, previous));
+    }
+    groups.sort();
+    groups
+}
+
+impl<'a> Model<'a> {
+    fn group(&mut self, sheet: u32, axis: Axis, start: i32, end: i32) -> Result<(), String> {
+        axis.check(start)?;
+        axis.check(end)?;
+        if start > end {
+            return Err(format!("Invalid range: {start} is after {end}"));
+        }
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let levels = axis.levels(worksheet);
+        if levels
+            .range(start..=end)
+            .any(|(_, &level)| level >= MAX_OUTLINE_LEVEL)
+        {
+            return Err(format!(
+                "Groups can't be nested more than {MAX_OUTLINE_LEVEL} levels"
+            ));
+        }
+        for index in start..=end {
+            let level = levels.get(&index).copied().unwrap_or(0);
+            axis.set_level(worksheet, index, level + 1)?;
+        }
+        Ok(())
+    }
+
+    fn ungroup(&mut self, sheet: u32, axis: Axis, start: i32, end: i32) -> Result<(), String> {
+        axis.check(start)?;
+        axis.check(end)?;
+        if start > end {
+            return Err(format!("Invalid range: {start} is after {end}"));
+        }
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        for (index, level) in axis.levels(worksheet).range(start..=end) {
+            axis.set_level(worksheet, *index, level - 1)?;
+        }
+        Ok(())
+    }
+
+    fn set_group_collapsed(
+        &mut self,
+        sheet: u32,
+        axis: Axis,
+        index: i32,
+        level: u8,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        axis.check(index)?;
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let levels = axis.levels(worksheet);
+        if level == 0 || levels.get(&index).copied().unwrap_or(0) < level {
+            return Err(format!(
+                "{} {index} is not in a group of level {level}",
+                axis.name()
+            ));
+        }
+        let (start, end) = get_group(&levels, index, level);
+        let summary = axis.summary(worksheet, start, end);
+        if (1..=axis.last()).contains(&summary) {
+            axis.set_collapsed(worksheet, summary, collapsed)?;
+        }
+        if collapsed {
+            for index in start..=end {
+                axis.set_hidden(worksheet, index, true)?;
+            }
+        } else {
+            expand(worksheet, axis, &levels, start, end, level)?;
+        }
+        Ok(())
+    }
+
+    fn show_outline_level(&mut self, sheet: u32, axis: Axis, level: u8) -> Result<(), String> {
+        if level == 0 || level > MAX_OUTLINE_LEVEL + 1 {
+            return Err(format!("Invalid outline level: {level}"));
+        }
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let levels = axis.levels(worksheet);
+        let groups = get_outline(&levels);
+        // Nested groups can share their summary row, it is collapsed if any of them is
+        for (_, start, end) in &groups {
+            let summary = axis.summary(worksheet, *start, *end);
+            if (1..=axis.last()).contains(&summary) {
+                axis.set_collapsed(worksheet, summary, false)?;
+            }
+        }
+        for (group_level, start, end) in &groups {
+            let summary = axis.summary(worksheet, *start, *end);
+            if *group_level >= level && (1..=axis.last()).contains(&summary) {
+                axis.set_collapsed(worksheet, summary, true)?;
+            }
+        }
+        for (index, index_level) in &levels {
+            axis.set_hidden(worksheet, *index, *index_level >= level)?;
+        }
+        Ok(())
+    }
+
+    fn get_outline(&self, sheet: u32, axis: Axis) -> Result<Outline, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        let levels = axis.levels(worksheet);
+        let mut outline = Outline::default();
+        for (level, start, end) in get_outline(&levels) {
+            outline.max_level = outline.max_level.max(level);
+            outline.groups.push(OutlineGroup {
+                level,
+                start,
+                end,
+                summary: axis.summary(worksheet, start, end),
+                collapsed: axis.is_group_collapsed(worksheet, start, end)?,
+            });
+        }
+        Ok(outline)
+    }
+
+    // New rows (or columns) inside a group are added to it
+    fn extend_outline(
+        &mut self,
+        sheet: u32,
+        axis: Axis,
+        index: i32,
+        count: i32,
+    ) -> Result<(), String> {
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        let levels = axis.levels(worksheet);
+        let level_of = |i: i32| levels.get(&i).copied().unwrap_or(0);
+        let level = level_of(index - 1).min(level_of(index + count));
+        for i in index..index + count {
+            axis.set_level(worksheet, i, level)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the rows from `start` to `end` to a new group, one level deeper than the groups they
+    /// are in. Fails if any of the rows is already in a group of the maximum level,
+    /// [MAX_OUTLINE_LEVEL].
+    pub fn group_rows(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.group(sheet, Axis::Rows, start, end)
+    }
+
+    /// Decreases the outline level of the rows from `start` to `end` that are in a group.
+    /// Rows hidden by a collapsed group stay hidden.
+    pub fn ungroup_rows(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.ungroup(sheet, Axis::Rows, start, end)
+    }
+
+    /// Adds the columns from `start` to `end` to a new group, one level deeper than the groups
+    /// they are in. Fails if any of the columns is already in a group of the maximum level,
+    /// [MAX_OUTLINE_LEVEL].
+    pub fn group_columns(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.group(sheet, Axis::Columns, start, end)
+    }
+
+    /// Decreases the outline level of the columns from `start` to `end` that are in a group.
+    /// Columns hidden by a collapsed group stay hidden.
+    pub fn ungroup_columns(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.ungroup(sheet, Axis::Columns, start, end)
+    }
+
+    /// Collapses (hides) or expands (shows) the group of rows of outline `level` that contains
+    /// `row`. When expanding, the rows of the collapsed groups nested in it stay hidden.
+    pub fn set_row_group_collapsed(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        level: u8,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        self.set_group_collapsed(sheet, Axis::Rows, row, level, collapsed)
+    }
+
+    /// Collapses (hides) or expands (shows) the group of columns of outline `level` that contains
+    /// `column`. When expanding, the columns of the collapsed groups nested in it stay hidden.
+    pub fn set_column_group_collapsed(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        level: u8,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        self.set_group_collapsed(sheet, Axis::Columns, column, level, collapsed)
+    }
+
+    /// Shows the rows with an outline level lower than `level` and collapses the rest of the
+    /// groups, like the level buttons of the outline in Excel. Level 1 shows only the rows that
+    /// are not in a group, `MAX_OUTLINE_LEVEL + 1` shows all of them.
+    pub fn show_row_outline_level(&mut self, sheet: u32, level: u8) -> Result<(), String> {
+        self.show_outline_level(sheet, Axis::Rows, level)
+    }
+
+    /// Shows the columns with an outline level lower than `level` and collapses the rest of the
+    /// groups. See [Model::show_row_outline_level].
+    pub fn show_column_outline_level(&mut self, sheet: u32, level: u8) -> Result<(), String> {
+        self.show_outline_level(sheet, Axis::Columns, level)
+    }
+
+    /// Returns the groups of rows of the sheet, to draw the outline symbols
+    pub fn get_row_outline(&self, sheet: u32) -> Result<Outline, String> {
+        self.get_outline(sheet, Axis::Rows)
+    }
+
+    /// Returns the groups of columns of the sheet, to draw the outline symbols
+    pub fn get_column_outline(&self, sheet: u32) -> Result<Outline, String> {
+        self.get_outline(sheet, Axis::Columns)
+    }
+
+    pub(crate) fn extend_row_outline(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+    ) -> Result<(), String> {
+        self.extend_outline(sheet, Axis::Rows, row, row_count)
+    }
+
+    pub(crate) fn extend_column_outline(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        column_count: i32,
+    ) -> Result<(), String> {
+        self.extend_outline(sheet, Axis::Columns, column, column_count)
+    }
+}
+
+// Shows the rows of a group of `level`, except those in collapsed groups nested in it
+fn expand(
+    worksheet: &mut Worksheet,
+    axis: Axis,
+    levels: &BTreeMap<i32, u8>,
+    start: i32,
+    end: i32,
+    level: u8,
+) -> Result<(), String> {
+    let mut index = start;
+    while index <= end {
+        if levels.get(&index).copied().unwrap_or(0) > level {
+            let (nested_start, nested_end) = get_group(levels, index, level + 1);
+            if !axis.is_group_collapsed(worksheet, nested_start, nested_end)? {
+                expand(worksheet, axis, levels, nested_start, nested_end, level + 1)?;
+            }
+            index = nested_end + 1;
+        } else {
+            axis.set_hidden(worksheet, index, false)?;
+            index += 1;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// Groups of rows and columns can have up to seven levels
+pub const MAX_OUTLINE_LEVEL: u8 = 7;
+
+/// Where the summary row (or column) of a group is, the one with the button to expand or
+/// collapse it
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct OutlineProperties {
+    /// The summary rows are below their groups, otherwise above
+    pub summary_below: bool,
+    /// The summary columns are to the right of their groups, otherwise to the left
+    pub summary_right: bool,
+}
+
+impl Default for OutlineProperties {
+    fn default() -> Self {
+        OutlineProperties {
+            summary_below: true,
+            summary_right: true,
+        }
+    }
+}
+
+/// A group of consecutive rows (or columns) with at least its outline level
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct OutlineGroup {
+    pub level: u8,
+    /// The first row (or column) of the group
+    pub start: i32,
+    /// The last row (or column) of the group
+    pub end: i32,
+    /// The row (or column) with the button to expand or collapse the group. It can be out of
+    /// the sheet if the group is at the end (or the beginning) of it.
+    pub summary: i32,
+    pub collapsed: bool,
+}
+
+/// What the UI needs to draw the outline symbols of the rows (or the columns) of a sheet
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct Outline {
+    /// The highest outline level in the sheet, 0 if there are no groups
+    pub max_level: u8,
+    /// The groups, sorted by level and then by start
+    pub groups: Vec<OutlineGroup>,
+}
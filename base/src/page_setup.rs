@@ -0,0 +1,186 @@
+//! How the sheets are printed: page layout, print area, print titles and page breaks.
+//!
+//! In xlsx files the print area and the print titles are the defined names `_xlnm.Print_Area`
+//! and `_xlnm.Print_Titles` local to the sheet. IronCalc keeps them in the
+//! [PageSetup](crate::page_setup_types::PageSetup) of the sheet, so they are not in the list of
+//! defined names and they move with the rows and columns like the rest of the sheet.
+
+use crate::{
+    constants::{LAST_COLUMN, LAST_ROW},
+    expressions::{
+        parser::{parse_range, stringify::DisplaceData},
+        types::Area,
+    },
+    merged_cells::displace_interval,
+    model::Model,
+    page_setup_types::PageSetup,
+    tables::area_to_reference,
+};
+
+// Excel doesn't allow longer headers and footers
+const MAX_HEADER_FOOTER_LENGTH: usize = 255;
+
+fn check_interval((start, end): (i32, i32), last: i32, description: &str) -> Result<(), String> {
+    if start < 1 || end > last || start > end {
+        return Err(format!("Invalid {description}: {start} to {end}"));
+    }
+    Ok(())
+}
+
+fn check_page_setup(page_setup: &PageSetup) -> Result<(), String> {
+    if !(10..=400).contains(&page_setup.scale) {
+        return Err(format!(
+            "The scale must be between 10 and 400: {}",
+            page_setup.scale
+        ));
+    }
+    if page_setup.paper_size == 0 {
+        return Err("Invalid paper size: 0".to_string());
+    }
+    let margins = &page_setup.margins;
+    for margin in [
+        margins.left,
+        margins.right,
+        margins.top,
+        margins.bottom,
+        margins.header,
+        margins.footer,
+    ] {
+        if !margin.is_finite() || margin < 0.0 {
+            return Err(format!("Invalid margin: {margin}"));
+        }
+    }
+    let header_footer = &page_setup.header_footer;
+    for text in [
+        &header_footer.odd_header,
+        &header_footer.odd_footer,
+        &header_footer.even_header,
+        &header_footer.even_footer,
+        &header_footer.first_header,
+        &header_footer.first_footer,
+    ] {
+        if text.chars().count() > MAX_HEADER_FOOTER_LENGTH {
+            return Err(format!(
+                "Headers and footers can't be longer than {MAX_HEADER_FOOTER_LENGTH} characters"
+            ));
+        }
+    }
+    for range in &page_setup.print_area {
+        if parse_range(range).is_err() {
+            return Err(format!("Invalid print area: '{range}'"));
+        }
+    }
+    if let Some(rows) = page_setup.print_title_rows {
+        check_interval(rows, LAST_ROW, "print title rows")?;
+    }
+    if let Some(columns) = page_setup.print_title_columns {
+        check_interval(columns, LAST_COLUMN, "print title columns")?;
+    }
+    for &row in &page_setup.row_breaks {
+        check_interval((row, row), LAST_ROW, "page break")?;
+    }
+    for &column in &page_setup.column_breaks {
+        check_interval((column, column), LAST_COLUMN, "page break")?;
+    }
+    Ok(())
+}
+
+// The page breaks after the rows (or columns) that are not deleted, at their new positions
+fn displace_breaks(breaks: &[i32], at: i32, delta: i32) -> Vec<i32> {
+    breaks
+        .iter()
+        .filter_map(|&index| displace_interval(index, index, at, delta).map(|(index, _)| index))
+        .collect()
+}
+
+impl<'a> Model<'a> {
+    /// Returns how the sheet is printed
+    pub fn get_page_setup(&self, sheet: u32) -> Result<PageSetup, String> {
+        Ok(self.workbook.worksheet(sheet)?.page_setup.clone())
+    }
+
+    /// Changes how the sheet is printed. The page breaks are sorted.
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # use ironcalc_base::page_setup_types::PageOrientation;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// let mut page_setup = model.get_page_setup(0)?;
+    /// page_setup.orientation = PageOrientation::Landscape;
+    /// page_setup.print_area = vec!["A1:F40".to_string()];
+    /// page_setup.print_title_rows = Some((1, 2));
+    /// model.set_page_setup(0, page_setup)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_page_setup(&mut self, sheet: u32, page_setup: PageSetup) -> Result<(), String> {
+        check_page_setup(&page_setup)?;
+        let mut page_setup = page_setup;
+        page_setup.row_breaks.sort_unstable();
+        page_setup.row_breaks.dedup();
+        page_setup.column_breaks.sort_unstable();
+        page_setup.column_breaks.dedup();
+        self.workbook.worksheet_mut(sheet)?.page_setup = page_setup;
+        Ok(())
+    }
+
+    /// Moves the print area, the print titles and the page breaks with the rows and columns
+    pub(crate) fn displace_page_setup(
+        &mut self,
+        displace_data: &DisplaceData,
+    ) -> Result<(), String> {
+        let (sheet, is_row, at, delta) = match *displace_data {
+            DisplaceData::Row { sheet, row, delta } => (sheet, true, row, delta),
+            DisplaceData::Column {
+                sheet,
+                column,
+                delta,
+            } => (sheet, false, column, delta),
+            _ => return Ok(()),
+        };
+        let page_setup = &mut self.workbook.worksheet_mut(sheet)?.page_setup;
+        let mut print_area = Vec::new();
+        for range in &page_setup.print_area {
+            let Ok((column, row, column_end, row_end)) = parse_range(range) else {
+                continue;
+            };
+            let area = Area {
+                sheet,
+                row,
+                column,
+                width: column_end - column + 1,
+                height: row_end - row + 1,
+            };
+            let new_area = if is_row {
+                displace_interval(row, row_end, at, delta).map(|(start, end)| Area {
+                    row: start,
+                    height: end - start + 1,
+                    ..area
+                })
+            } else {
+                displace_interval(column, column_end, at, delta).map(|(start, end)| Area {
+                    column: start,
+                    width: end - start + 1,
+                    ..area
+                })
+            };
+            if let Some(new_area) = new_area {
+                print_area.push(area_to_reference(&new_area)?);
+            }
+        }
+        page_setup.print_area = print_area;
+        if is_row {
+            page_setup.print_title_rows = page_setup
+                .print_title_rows
+                .and_then(|(start, end)| displace_interval(start, end, at, delta));
+            page_setup.row_breaks = displace_breaks(&page_setup.row_breaks, at, delta);
+        } else {
+            page_setup.print_title_columns = page_setup
+                .print_title_columns
+                .and_then(|(start, end)| displace_interval(start, end, at, delta));
+            page_setup.column_breaks = displace_breaks(&page_setup.column_breaks, at, delta);
+        }
+        Ok(())
+    }
+}
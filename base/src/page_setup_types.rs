@@ -0,0 +1,114 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PageOrientation {
+    /// The orientation of the printer
+    #[default]
+    Default,
+    Portrait,
+    Landscape,
+}
+
+/// The margins of the printed pages, in inches
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct PageMargins {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    /// The distance from the top of the page to the header
+    pub header: f64,
+    /// The distance from the bottom of the page to the footer
+    pub footer: f64,
+}
+
+impl Default for PageMargins {
+    // The "Normal" margins of Excel
+    fn default() -> Self {
+        PageMargins {
+            left: 0.7,
+            right: 0.7,
+            top: 0.75,
+            bottom: 0.75,
+            header: 0.3,
+            footer: 0.3,
+        }
+    }
+}
+
+/// The headers and footers of the printed pages. They use Excel's format codes, like `&P` for
+/// the page number or `&L`, `&C` and `&R` for the left, center and right sections.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+pub struct HeaderFooter {
+    /// The header of all the pages, or of the odd pages if `different_odd_even`
+    pub odd_header: String,
+    pub odd_footer: String,
+    pub even_header: String,
+    pub even_footer: String,
+    pub first_header: String,
+    pub first_footer: String,
+    pub different_odd_even: bool,
+    /// Whether the first page uses `first_header` and `first_footer`
+    pub different_first: bool,
+}
+
+/// How a sheet is printed
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct PageSetup {
+    pub orientation: PageOrientation,
+    /// The paper size, with the codes of Excel: 1 is Letter, 9 is A4, ...
+    pub paper_size: u32,
+    /// The print scale in percent, from 10 to 400. Not used if `fit_to_page`.
+    pub scale: u32,
+    /// Whether the sheet is scaled to fit in `fit_to_width` by `fit_to_height` pages
+    pub fit_to_page: bool,
+    /// The number of pages wide, 0 for as many as needed
+    pub fit_to_width: u32,
+    /// The number of pages tall, 0 for as many as needed
+    pub fit_to_height: u32,
+    /// The number of the first page, `None` for automatic
+    pub first_page_number: Option<u32>,
+    pub horizontal_centered: bool,
+    pub vertical_centered: bool,
+    pub print_grid_lines: bool,
+    /// Whether the row numbers and column letters are printed
+    pub print_headings: bool,
+    pub margins: PageMargins,
+    pub header_footer: HeaderFooter,
+    /// The ranges that are printed, like "A1:F40". The whole sheet if empty.
+    pub print_area: Vec<String>,
+    /// The rows repeated at the top of each page, first and last
+    pub print_title_rows: Option<(i32, i32)>,
+    /// The columns repeated at the left of each page, first and last
+    pub print_title_columns: Option<(i32, i32)>,
+    /// The manual page breaks after each of these rows, sorted
+    pub row_breaks: Vec<i32>,
+    /// The manual page breaks after each of these columns, sorted
+    pub column_breaks: Vec<i32>,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        PageSetup {
+            orientation: PageOrientation::Default,
+            paper_size: 1,
+            scale: 100,
+            fit_to_page: false,
+            fit_to_width: 1,
+            fit_to_height: 1,
+            first_page_number: None,
+            horizontal_centered: false,
+            vertical_centered: false,
+            print_grid_lines: false,
+            print_headings: false,
+            margins: PageMargins::default(),
+            header_footer: HeaderFooter::default(),
+            print_area: vec![],
+            print_title_rows: None,
+            print_title_columns: None,
+            row_breaks: vec![],
+            column_breaks: vec![],
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Cell, sheet and workbook protection.
+//!
+//! Cells carry a [Protection](crate::types::Protection) in their style: a locked cell can't be
+//! edited and a hidden cell doesn't show its formula. Those attributes only take effect while
+//! the sheet is protected ([Worksheet::protection](crate::types::Worksheet::protection)).
+//! Protecting the workbook locks its structure: sheets can't be added, deleted or renamed.
+//!
+//! Passwords are never stored, only a hash. New passwords use the legacy 16 bit hash that
+//! every spreadsheet application understands; salted SHA-512 hashes from Excel files can be verified.
+
+use std::collections::{HashMap, HashSet};
+
+use sha2::{Digest, Sha512};
+
+use crate::{
+    types::{Cell, PasswordHash, Protection, SheetProtection, WorkbookProtection},
+    Model,
+};
+
+const LOCKED_CELL_MESSAGE: &str =
+    "The cell you are trying to change is on a protected sheet. Unprotect the sheet to change it.";
+
+const PROTECTED_SHEET_MESSAGE: &str =
+    "The sheet is protected. Unprotect the sheet to perform this operation.";
+
+const PROTECTED_WORKBOOK_MESSAGE: &str =
+    "The workbook structure is protected. Unprotect the workbook to perform this operation.";
+
+const WRONG_PASSWORD_MESSAGE: &str = "The password you supplied is not correct.";
+
+// The legacy 16 bit password hash (ECMA-376-4:2016 section 14.7.1)
+fn legacy_hash(password: &str) -> u16 {
+    // Rotates the 15 low bits one position to the left
+    let rotate = |hash: u16| ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+    let bytes: Vec<u16> = password.encode_utf16().map(|c| c & 0xFF).collect();
+    let mut hash = bytes.iter().rev().fold(0, |hash, c| rotate(hash) ^ c);
+    hash = rotate(hash) ^ (bytes.len() as u16);
+    hash ^ 0xCE4B
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+    {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+// The salted hash: H0 = SHA-512(salt + password), Hn = SHA-512(Hn-1 + n)
+fn salted_hash(password: &str, salt: &[u8], spin_count: u32) -> Vec<u8> {
+    let password: Vec<u8> = password
+        .encode_utf16()
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    let mut hash = Sha512::new()
+        .chain_update(salt)
+        .chain_update(&password)
+        .finalize();
+    for iterator in 0..spin_count {
+        hash = Sha512::new()
+            .chain_update(hash)
+            .chain_update(iterator.to_le_bytes())
+            .finalize();
+    }
+    hash.to_vec()
+}
+
+impl PasswordHash {
+    /// Hashes a new password
+    pub fn new(password: &str) -> PasswordHash {
+        PasswordHash::Legacy {
+            hash: format!("{:04X}", legacy_hash(password)),
+        }
+    }
+
+    /// Returns true if `password` matches the hash.
+    /// Salted hashes with an algorithm other than SHA-512 can't be verified.
+    pub fn verify(&self, password: &str) -> bool {
+        match self {
+            PasswordHash::Legacy { hash } => {
+                u16::from_str_radix(hash, 16) == Ok(legacy_hash(password))
+            }
+            PasswordHash::Salted {
+                algorithm_name,
+                hash_value,
+                salt_value,
+                spin_count,
+            } => {
+                if algorithm_name != "SHA-512" {
+                    return false;
+                }
+                match (decode_base64(hash_value), decode_base64(salt_value)) {
+                    (Some(hash), Some(salt)) => salted_hash(password, &salt, *spin_count) == hash,
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+fn check_password(password_hash: &Option<PasswordHash>, password: &str) -> Result<(), String> {
+    match password_hash {
+        Some(password_hash) if !password_hash.verify(password) => {
+            Err(WRONG_PASSWORD_MESSAGE.to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Returns the protection of the sheet, if it is protected
+    pub fn get_sheet_protection(&self, sheet: u32) -> Result<Option<SheetProtection>, String> {
+        Ok(self.workbook.worksheet(sheet)?.protection.clone())
+    }
+
+    /// Protects the sheet. Use [PasswordHash::new] to set a password.
+    pub fn protect_sheet(&mut self, sheet: u32, protection: SheetProtection) -> Result<(), String> {
+        self.workbook.worksheet_mut(sheet)?.protection = Some(protection);
+        Ok(())
+    }
+
+    /// Removes the protection of the sheet. Fails if the password is not correct.
+    pub fn unprotect_sheet(&mut self, sheet: u32, password: &str) -> Result<(), String> {
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        if let Some(protection) = &worksheet.protection {
+            check_password(&protection.password, password)?;
+        }
+        worksheet.protection = None;
+        Ok(())
+    }
+
+    /// Returns the protection of the workbook, if it is protected
+    pub fn get_workbook_protection(&self) -> Option<WorkbookProtection> {
+        self.workbook.protection.clone()
+    }
+
+    /// Protects the workbook. Use [PasswordHash::new] to set a password.
+    pub fn protect_workbook(&mut self, protection: WorkbookProtection) {
+        self.workbook.protection = Some(protection);
+    }
+
+    /// Removes the protection of the workbook. Fails if the password is not correct.
+    pub fn unprotect_workbook(&mut self, password: &str) -> Result<(), String> {
+        if let Some(protection) = &self.workbook.protection {
+            check_password(&protection.password, password)?;
+        }
+        self.workbook.protection = None;
+        Ok(())
+    }
+
+    /// Returns true if the sheet is protected and the cell is locked
+    pub fn is_cell_locked(&self, sheet: u32, row: i32, column: i32) -> Result<bool, String> {
+        if self.workbook.worksheet(sheet)?.protection.is_none() {
+            return Ok(false);
+        }
+        let style_index = self.get_cell_style_index(sheet, row, column)?;
+        Ok(self.cell_protection(style_index).locked)
+    }
+
+    /// Returns true if the sheet is protected and the formula of the cell is hidden
+    pub fn is_cell_formula_hidden(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<bool, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        if worksheet.protection.is_none() {
+            return Ok(false);
+        }
+        match worksheet.cell(row, column) {
+            Some(cell) if cell.has_formula() => Ok(self.cell_protection(cell.get_style()).hidden),
+            _ => Ok(false),
+        }
+    }
+
+    fn cell_protection(&self, style_index: i32) -> Protection {
+        self.workbook
+            .styles
+            .cell_xfs
+            .get(style_index as usize)
+            .and_then(|cell_xf| cell_xf.protection.clone())
+            .unwrap_or_default()
+    }
+
+    /// Fails if the sheet is protected and any cell in the range is locked
+    pub(crate) fn check_cells_editable(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        if worksheet.protection.is_none() {
+            return Ok(());
+        }
+        let last_row = row + height - 1;
+        let last_column = column + width - 1;
+        let locked = |style_index: i32| self.cell_protection(style_index).locked;
+
+        // Cells in the range, checked one by one. We walk whichever is smaller, the range or the data.
+        let mut data_rows = Vec::new();
+        if (height as usize) < worksheet.sheet_data.len() {
+            for r in row..=last_row {
+                if let Some(data_row) = worksheet.sheet_data.get(&r) {
+                    data_rows.push((r, data_row));
+                }
+            }
+        } else {
+            for (r, data_row) in &worksheet.sheet_data {
+                if (row..=last_row).contains(r) {
+                    data_rows.push((*r, data_row));
+                }
+            }
+        }
+        let cells_in_columns = |data_row: &HashMap<i32, Cell>, min: i32, max: i32| {
+            if ((max - min + 1) as usize) < data_row.len() {
+                (min..=max).filter(|c| data_row.contains_key(c)).count()
+            } else {
+                data_row.keys().filter(|c| (min..=max).contains(*c)).count()
+            }
+        };
+        let mut cell_count = HashMap::new();
+        for (r, data_row) in &data_rows {
+            for (c, cell) in data_row.iter() {
+                if (column..=last_column).contains(c) && locked(cell.get_style()) {
+                    return Err(LOCKED_CELL_MESSAGE.to_string());
+                }
+            }
+            cell_count.insert(*r, cells_in_columns(data_row, column, last_column));
+        }
+
+        // The empty cells of a row with a style of its own have the style of the row
+        let mut styled_rows = HashSet::new();
+        for r in &worksheet.rows {
+            if r.custom_format && (row..=last_row).contains(&r.r) {
+                styled_rows.insert(r.r);
+                let cells = cell_count.get(&r.r).copied().unwrap_or(0);
+                if cells < width as usize && locked(r.s) {
+                    return Err(LOCKED_CELL_MESSAGE.to_string());
+                }
+            }
+        }
+
+        // All other empty cells have the style of the column
+        let mut segments = Vec::new();
+        let mut next_column = column;
+        let mut cols: Vec<_> = worksheet
+            .cols
+            .iter()
+            .filter(|c| c.max >= column && c.min <= last_column)
+            .collect();
+        cols.sort_by_key(|c| c.min);
+        for col in cols {
+            let min = col.min.max(next_column);
+            let max = col.max.min(last_column);
+            if min > max {
+                continue;
+            }
+            if min > next_column {
+                segments.push((next_column, min - 1, 0));
+            }
+            segments.push((min, max, col.style.unwrap_or(0)));
+            next_column = max + 1;
+        }
+        if next_column <= last_column {
+            segments.push((next_column, last_column, 0));
+        }
+        let plain_rows = height as usize - styled_rows.len();
+        let plain_rows_with_data = data_rows
+            .iter()
+            .filter(|(r, _)| !styled_rows.contains(r))
+            .count();
+        for (min, max, style) in segments {
+            if !locked(style) {
+                continue;
+            }
+            // The column style is visible if an unstyled row has an empty cell in the segment
+            let has_empty_cell = plain_rows_with_data < plain_rows
+                || data_rows.iter().any(|(r, data_row)| {
+                    !styled_rows.contains(r)
+                        && cells_in_columns(data_row, min, max) < (max - min + 1) as usize
+                });
+            if has_empty_cell {
+                return Err(LOCKED_CELL_MESSAGE.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails if the sheet is protected and the operation is not allowed
+    pub(crate) fn check_sheet_operation(
+        &self,
+        sheet: u32,
+        allowed: fn(&SheetProtection) -> bool,
+    ) -> Result<(), String> {
+        match &self.workbook.worksheet(sheet)?.protection {
+            Some(protection) if !allowed(protection) => Err(PROTECTED_SHEET_MESSAGE.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if the sheet is protected, formatting cells is not allowed and any cell in the range is locked
+    pub(crate) fn check_cells_formattable(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        match &self.workbook.worksheet(sheet)?.protection {
+            Some(protection) if !protection.format_cells => {
+                self.check_cells_editable(sheet, row, column, width, height)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if the structure of the workbook is protected
+    pub(crate) fn check_workbook_structure(&self) -> Result<(), String> {
+        match &self.workbook.protection {
+            Some(protection) if protection.lock_structure => {
+                Err(PROTECTED_WORKBOOK_MESSAGE.to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Cells with rich text, a text with fragments in different fonts.
+//!
+//! The shared string of a rich text cell is its plain text, so functions like `LEN` or `MID`
+//! work as with any other text. The runs are kept in
+//! [Workbook::rich_text](crate::types::Workbook::rich_text) by shared string index.
+
+use crate::{
+    model::Model,
+    rich_text_types::{get_rich_text_plain_text, RichTextRun},
+    types::{Cell, Color},
+};
+
+// Excel doesn't allow larger fonts
+const MAX_FONT_SIZE: i32 = 409;
+
+fn check_rich_text(runs: &[RichTextRun]) -> Result<(), String> {
+    if runs.is_empty() {
+        return Err("A rich text needs at least one run".to_string());
+    }
+    for run in runs {
+        if run.text.is_empty() {
+            return Err("The runs of a rich text can't be empty".to_string());
+        }
+        let font = &run.font;
+        if let Some(sz) = font.sz {
+            if !(1..=MAX_FONT_SIZE).contains(&sz) {
+                return Err(format!("Invalid font size: {sz}"));
+            }
+        }
+        if let Color::Rgb(color) = &font.color {
+            Color::from_rgb(color)?;
+        }
+        if font.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err("Invalid font name: ''".to_string());
+        }
+    }
+    Ok(())
+}
+
+// Consecutive runs with the same font are joined
+fn join_runs(runs: Vec<RichTextRun>) -> Vec<RichTextRun> {
+    let mut joined: Vec<RichTextRun> = Vec::with_capacity(runs.len());
+    for run in runs {
+        match joined.last_mut() {
+            Some(last) if last.font == run.font => last.text.push_str(&run.text),
+            _ => joined.push(run),
+        }
+    }
+    joined
+}
+
+impl<'a> Model<'a> {
+    /// Returns the runs of the text of the cell if it is a rich text
+    pub fn get_cell_rich_text(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Vec<RichTextRun>>, String> {
+        match self.workbook.worksheet(sheet)?.cell(row, column) {
+            Some(Cell::SharedString { si, .. }) => Ok(self.workbook.rich_text.get(si).cloned()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Sets the value of the cell to a rich text. If no run has a font of its own the cell
+    /// gets a plain text.
+    ///
+    /// ```rust
+    /// # use ironcalc_base::Model;
+    /// # use ironcalc_base::rich_text_types::{RichTextFont, RichTextRun};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut model = Model::new_empty("model", "en", "UTC", "en")?;
+    /// let runs = vec![
+    ///     RichTextRun {
+    ///         text: "Hello ".to_string(),
+    ///         font: RichTextFont::default(),
+    ///     },
+    ///     RichTextRun {
+    ///         text: "World".to_string(),
+    ///         font: RichTextFont {
+    ///             b: Some(true),
+    ///             ..Default::default()
+    ///         },
+    ///     },
+    /// ];
+    /// model.set_cell_rich_text(0, 1, 1, runs.clone())?;
+    /// model.set_user_input(0, 1, 2, "=LEN(A1)".to_string())?;
+    /// model.evaluate();
+    /// assert_eq!(model.get_formatted_cell_value(0, 1, 1)?, "Hello World");
+    /// assert_eq!(model.get_formatted_cell_value(0, 1, 2)?, "11");
+    /// assert_eq!(model.get_cell_rich_text(0, 1, 1)?, Some(runs));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cell_rich_text(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        runs: Vec<RichTextRun>,
+    ) -> Result<(), String> {
+        check_rich_text(&runs)?;
+        let runs = join_runs(runs);
+        let text = get_rich_text_plain_text(&runs);
+        self.prepare_cell_for_user_input(sheet, row, column)?;
+        if runs.iter().all(|run| run.font.is_empty()) {
+            return self.update_cell_with_text(sheet, row, column, &text);
+        }
+        let style_index = self.get_text_style_index(sheet, row, column, &text)?;
+        self.mark_cell_dirty(sheet, row, column);
+        // Cells with the same rich text share it
+        let existing = self
+            .workbook
+            .rich_text
+            .iter()
+            .find(|(_, other)| **other == runs)
+            .map(|(si, _)| *si);
+        let si = match existing {
+            Some(si) => si,
+            None => {
+                let si = self.workbook.shared_strings.len() as i32;
+                self.workbook.shared_strings.push(text);
+                self.workbook.rich_text.insert(si, runs);
+                si
+            }
+        };
+        self.workbook
+            .worksheet_mut(sheet)?
+            .set_cell_with_string(row, column, si, style_index)
+    }
+}
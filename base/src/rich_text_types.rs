@@ -0,0 +1,47 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Color;
+
+/// The formatting of a fragment of a rich text. The properties that are not set are the ones
+/// of the font of the cell.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone, Default)]
+pub struct RichTextFont {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub u: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sz: Option<i32>,
+    #[serde(skip_serializing_if = "Color::is_none")]
+    #[serde(default)]
+    pub color: Color,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl RichTextFont {
+    /// True if the fragment has the font of the cell
+    pub fn is_empty(&self) -> bool {
+        *self == RichTextFont::default()
+    }
+}
+
+/// A fragment of the text of a cell with its own formatting, like the bold word in
+/// "Hello **World**"
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone, Default)]
+pub struct RichTextRun {
+    pub text: String,
+    #[serde(skip_serializing_if = "RichTextFont::is_empty")]
+    #[serde(default)]
+    pub font: RichTextFont,
+}
+
+/// The plain text of a rich text, the text of all its runs
+pub fn get_rich_text_plain_text(runs: &[RichTextRun]) -> String {
+    runs.iter().map(|run| run.text.as_str()).collect()
+}
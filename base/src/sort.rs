@@ -0,0 +1,426 @@
+//! Sorting a range of cells in place.
+//!
+//! The rows of the range (or its columns when sorting left to right) are reordered by a list of
+//! [SortKey]s. Cells move with their styles, links and comments. A formula that moves keeps
+//! referencing the cells of its own row (or column), like when rows are moved, while its
+//! references to other rows stay fixed. Formulas outside the range are not changed.
+//!
+//! The last sort is remembered in [Worksheet::sort_state](crate::types::Worksheet::sort_state).
+
+use std::cmp::Ordering;
+
+use crate::{
+    actions::displace_links,
+    calc_result::CalcResult,
+    constants::{LAST_COLUMN, LAST_ROW},
+    expressions::types::{Area, CellReferenceIndex},
+    functions::util::compare_values,
+    sort_types::{SortKey, SortOn, SortState},
+    tables::{area_to_reference, areas_overlap},
+    types::Cell,
+    Model,
+};
+
+// The value of a cell for one of the keys: its rank and then its value.
+// Empty cells always go last.
+enum SortValue {
+    Empty,
+    Ranked(usize, CalcResult),
+}
+
+fn compare_sort_values(left: &SortValue, right: &SortValue, descending: bool) -> Ordering {
+    match (left, right) {
+        (SortValue::Empty, SortValue::Empty) => Ordering::Equal,
+        (SortValue::Empty, _) => Ordering::Greater,
+        (_, SortValue::Empty) => Ordering::Less,
+        (SortValue::Ranked(rank1, value1), SortValue::Ranked(rank2, value2)) => {
+            let ordering = rank1
+                .cmp(rank2)
+                .then_with(|| compare_values(value1, value2).cmp(&0));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+    }
+}
+
+// Returns the (row, column) of the cell at `position` of the line. Lines are rows, or columns if
+// sorting left to right.
+fn line_cell(line: i32, position: i32, left_to_right: bool) -> (i32, i32) {
+    if left_to_right {
+        (position, line)
+    } else {
+        (line, position)
+    }
+}
+
+// Returns the new (row, column) of a cell of the area whose line moves, if the line at position
+// `i` goes to position `targets[i]`
+fn sorted_cell(
+    area: &Area,
+    left_to_right: bool,
+    targets: &[i32],
+    row: i32,
+    column: i32,
+) -> Option<(i32, i32)> {
+    if row < area.row
+        || row >= area.row + area.height
+        || column < area.column
+        || column >= area.column + area.width
+    {
+        return None;
+    }
+    let start = if left_to_right { area.column } else { area.row };
+    let (line, position) = line_cell(row, column, left_to_right);
+    let target = start + targets[(line - start) as usize];
+    if target == line {
+        return None;
+    }
+    Some(line_cell(target, position, left_to_right))
+}
+
+impl<'a> Model<'a> {
+    /// Sorts the rows of the range in place, or its columns if `left_to_right` is true.
+    /// If `has_headers` the first row (or column) is not sorted.
+    ///
+    /// Returns the new order of the sorted lines: the line at position `i` comes from position `order[i]`.
+    /// The positions are relative to the sorted range, without the header and without the empty
+    /// lines at the end of the range.
+    pub fn sort_range(
+        &mut self,
+        area: &Area,
+        has_headers: bool,
+        left_to_right: bool,
+        keys: &[SortKey],
+    ) -> Result<Vec<i32>, String> {
+        let (start, end) = if left_to_right {
+            (area.row, area.row + area.height - 1)
+        } else {
+            (area.column, area.column + area.width - 1)
+        };
+        if keys.is_empty() {
+            return Err("At least one sort key is needed".to_string());
+        }
+        if keys.iter().any(|key| key.index < start || key.index > end) {
+            return Err("The sort key is outside the range".to_string());
+        }
+        let Some(sort_area) = self.get_sort_area(area, has_headers, left_to_right)? else {
+            return Ok(vec![]);
+        };
+        let order = self.get_sort_order(&sort_area, left_to_right, keys)?;
+        self.apply_sort_order(&sort_area, left_to_right, &order)?;
+
+        for key in keys {
+            match &key.sort_on {
+                SortOn::CellColor(color) => self.add_color_dxf(color, false),
+                SortOn::FontColor(color) => self.add_color_dxf(color, true),
+                SortOn::Value | SortOn::CustomList(_) => {}
+            }
+        }
+        self.workbook.worksheet_mut(area.sheet)?.sort_state = Some(SortState {
+            range: area_to_reference(&sort_area)?,
+            left_to_right,
+            keys: keys.to_vec(),
+        });
+        Ok(order)
+    }
+
+    /// Returns the last sort applied to the sheet, if any
+    pub fn get_sort_state(&self, sheet: u32) -> Result<Option<SortState>, String> {
+        Ok(self.workbook.worksheet(sheet)?.sort_state.clone())
+    }
+
+    /// Returns the part of the range that is sorted: without the header and without the empty
+    /// lines at the end. Returns None if there is nothing to sort.
+    pub(crate) fn get_sort_area(
+        &self,
+        area: &Area,
+        has_headers: bool,
+        left_to_right: bool,
+    ) -> Result<Option<Area>, String> {
+        if area.width < 1
+            || area.height < 1
+            || area.row < 1
+            || area.column < 1
+            || area.row + area.height - 1 > LAST_ROW
+            || area.column + area.width - 1 > LAST_COLUMN
+        {
+            return Err("Invalid area".to_string());
+        }
+        let worksheet = self.workbook.worksheet(area.sheet)?;
+        let header = if has_headers { 1 } else { 0 };
+        let mut sort_area = Area { ..*area };
+        if left_to_right {
+            sort_area.column += header;
+            sort_area.width -= header;
+            let last_column = worksheet
+                .sheet_data
+                .values()
+                .filter_map(|data_row| data_row.keys().max())
+                .max()
+                .copied()
+                .unwrap_or(0);
+            sort_area.width = sort_area.width.min(last_column - sort_area.column + 1);
+        } else {
+            sort_area.row += header;
+            sort_area.height -= header;
+            let last_row = worksheet.sheet_data.keys().max().copied().unwrap_or(0);
+            sort_area.height = sort_area.height.min(last_row - sort_area.row + 1);
+        }
+        if sort_area.width < 1 || sort_area.height < 1 {
+            return Ok(None);
+        }
+        Ok(Some(sort_area))
+    }
+
+    // Fails if the cells of the area can't be moved independently
+    fn check_sortable(&self, area: &Area) -> Result<(), String> {
+        let sheet = area.sheet;
+        if self
+            .get_merged_cells(sheet)?
+            .iter()
+            .any(|merged| areas_overlap(merged, area))
+        {
+            return Err("Cannot sort a range with merged cells".to_string());
+        }
+        let has_arrays = self
+            .area_cells(area)?
+            .iter()
+            .any(|(_, _, cell)| matches!(cell, Cell::ArrayFormula { .. } | Cell::SpillCell { .. }));
+        if has_arrays {
+            return Err("Cannot sort a range with array formulas".to_string());
+        }
+        Ok(())
+    }
+
+    // Returns the cells of the area, with their row and column
+    fn area_cells(&self, area: &Area) -> Result<Vec<(i32, i32, &Cell)>, String> {
+        let worksheet = self.workbook.worksheet(area.sheet)?;
+        let rows = area.row..area.row + area.height;
+        let columns = area.column..area.column + area.width;
+        Ok(worksheet
+            .sheet_data
+            .iter()
+            .filter(|(row, _)| rows.contains(row))
+            .flat_map(|(row, data_row)| {
+                data_row
+                    .iter()
+                    .filter(|(column, _)| columns.contains(column))
+                    .map(|(column, cell)| (*row, *column, cell))
+            })
+            .collect())
+    }
+
+    fn get_sort_value(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        sort_on: &SortOn,
+    ) -> Result<SortValue, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        let value = match worksheet.cell(row, column) {
+            None | Some(Cell::EmptyCell { .. }) => None,
+            Some(cell) => {
+                Some(self.get_cell_value(cell, CellReferenceIndex { sheet, row, column }))
+            }
+        };
+        match sort_on {
+            SortOn::Value => Ok(match value {
+                Some(value) => SortValue::Ranked(0, value),
+                None => SortValue::Empty,
+            }),
+            SortOn::CellColor(color) => {
+                let style = self.get_style_for_cell(sheet, row, column)?;
+                let rank = if &style.fill.color == color { 0 } else { 1 };
+                Ok(SortValue::Ranked(rank, CalcResult::EmptyCell))
+            }
+            SortOn::FontColor(color) => {
+                let style = self.get_style_for_cell(sheet, row, column)?;
+                let rank = if &style.font.color == color { 0 } else { 1 };
+                Ok(SortValue::Ranked(rank, CalcResult::EmptyCell))
+            }
+            SortOn::CustomList(list) => {
+                let Some(value) = value else {
+                    return Ok(SortValue::Empty);
+                };
+                let text = self
+                    .get_formatted_cell_value(sheet, row, column)?
+                    .to_uppercase();
+                let rank = list
+                    .iter()
+                    .position(|item| item.to_uppercase() == text)
+                    .unwrap_or(list.len());
+                Ok(SortValue::Ranked(rank, value))
+            }
+        }
+    }
+
+    /// Returns the order of the lines of the area after sorting them by the keys.
+    /// The sort is stable: lines that compare equal keep their relative order.
+    pub(crate) fn get_sort_order(
+        &self,
+        area: &Area,
+        left_to_right: bool,
+        keys: &[SortKey],
+    ) -> Result<Vec<i32>, String> {
+        self.check_sortable(area)?;
+        let (start, count) = if left_to_right {
+            (area.column, area.width)
+        } else {
+            (area.row, area.height)
+        };
+        let mut lines = Vec::new();
+        for offset in 0..count {
+            let mut values = Vec::new();
+            for key in keys {
+                let (row, column) = line_cell(start + offset, key.index, left_to_right);
+                values.push(self.get_sort_value(area.sheet, row, column, &key.sort_on)?);
+            }
+            lines.push((offset, values));
+        }
+        lines.sort_by(|(_, values1), (_, values2)| {
+            keys.iter()
+                .enumerate()
+                .map(|(index, key)| {
+                    compare_sort_values(&values1[index], &values2[index], key.descending)
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Ok(lines.into_iter().map(|(offset, _)| offset).collect())
+    }
+
+    /// Reorders the lines of the area: the line at position `i` is taken from position `order[i]`
+    pub(crate) fn apply_sort_order(
+        &mut self,
+        area: &Area,
+        left_to_right: bool,
+        order: &[i32],
+    ) -> Result<(), String> {
+        let sheet = area.sheet;
+        // The new position of each line
+        let mut targets = vec![0; order.len()];
+        for (target, source) in order.iter().enumerate() {
+            targets[*source as usize] = target as i32;
+        }
+        let moved_cells: Vec<_> = self
+            .area_cells(area)?
+            .into_iter()
+            .filter_map(|(row, column, cell)| {
+                sorted_cell(area, left_to_right, &targets, row, column)
+                    .map(|target| ((row, column), target, cell.clone()))
+            })
+            .collect();
+
+        let mut new_cells = Vec::new();
+        for ((row, column), (target_row, target_column), cell) in moved_cells {
+            // References to the row (or column) of the cell follow it, the rest stay fixed
+            let formula = match self.get_cell_formula(sheet, row, column)? {
+                Some(formula) => {
+                    let line_area = if left_to_right {
+                        Area {
+                            sheet,
+                            row: 1,
+                            column,
+                            width: 1,
+                            height: LAST_ROW,
+                        }
+                    } else {
+                        Area {
+                            sheet,
+                            row,
+                            column: 1,
+                            width: LAST_COLUMN,
+                            height: 1,
+                        }
+                    };
+                    Some(self.move_cell_value_to_area(
+                        &formula,
+                        &CellReferenceIndex { sheet, row, column },
+                        &CellReferenceIndex {
+                            sheet,
+                            row: target_row,
+                            column: target_column,
+                        },
+                        &line_area,
+                    )?)
+                }
+                None => None,
+            };
+            self.workbook
+                .worksheet_mut(sheet)?
+                .remove_cell(row, column)?;
+            new_cells.push((target_row, target_column, cell, formula));
+        }
+        for (row, column, cell, formula) in new_cells {
+            let style = cell.get_style();
+            match formula {
+                Some(formula) => {
+                    self.set_user_input(sheet, row, column, formula)?;
+                    self.workbook
+                        .worksheet_mut(sheet)?
+                        .set_cell_style(row, column, style)?;
+                }
+                None => self
+                    .workbook
+                    .worksheet_mut(sheet)?
+                    .update_cell(row, column, cell)?,
+            }
+        }
+        self.move_sorted_links_and_comments(area, left_to_right, &targets)
+    }
+
+    /// Undoes [Model::apply_sort_order]: puts back the cells of the area as they were before the sort.
+    /// The formulas are not moved back, as that would not give back the original formulas.
+    pub(crate) fn undo_sort_order(
+        &mut self,
+        area: &Area,
+        left_to_right: bool,
+        order: &[i32],
+        old_cells: &[(i32, i32, Cell)],
+    ) -> Result<(), String> {
+        let sheet = area.sheet;
+        let cells: Vec<(i32, i32)> = self
+            .area_cells(area)?
+            .into_iter()
+            .map(|(row, column, _)| (row, column))
+            .collect();
+        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        for (row, column) in cells {
+            worksheet.remove_cell(row, column)?;
+        }
+        for (row, column, cell) in old_cells {
+            worksheet.update_cell(*row, *column, cell.clone())?;
+        }
+        // The line at position `i` goes back to position `order[i]`
+        self.move_sorted_links_and_comments(area, left_to_right, order)
+    }
+
+    /// Returns a copy of the cells of the area, with their row and column
+    pub(crate) fn get_area_cells(&self, area: &Area) -> Result<Vec<(i32, i32, Cell)>, String> {
+        Ok(self
+            .area_cells(area)?
+            .into_iter()
+            .map(|(row, column, cell)| (row, column, cell.clone()))
+            .collect())
+    }
+
+    // Links and comments follow their cells: the line at position `i` goes to position `targets[i]`
+    fn move_sorted_links_and_comments(
+        &mut self,
+        area: &Area,
+        left_to_right: bool,
+        targets: &[i32],
+    ) -> Result<(), String> {
+        let worksheet = self.workbook.worksheet_mut(area.sheet)?;
+        displace_links(worksheet, |row, column| {
+            Some(sorted_cell(area, left_to_right, targets, row, column).unwrap_or((row, column)))
+        });
+        self.map_comments(area.sheet, |row, column| {
+            Some(sorted_cell(area, left_to_right, targets, row, column).unwrap_or((row, column)))
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Color;
+
+/// What a sort key compares
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+#[serde(tag = "type", content = "value")]
+pub enum SortOn {
+    /// The values of the cells: numbers, then text, then booleans and then errors.
+    /// Text is compared ignoring case.
+    Value,
+    /// Cells with this fill color go first
+    CellColor(Color),
+    /// Cells with this font color go first
+    FontColor(Color),
+    /// Values in the list go first, in the order of the list, like "Low,Medium,High".
+    /// The rest of the values follow, sorted by value.
+    CustomList(Vec<String>),
+}
+
+/// A key of a sort. Keys are compared in order: the second key only breaks the ties of the first.
+/// Empty cells always go last, whatever the order.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct SortKey {
+    /// The column of the sheet, or the row if sorting left to right
+    pub index: i32,
+    /// Reverses the order of the key
+    pub descending: bool,
+    pub sort_on: SortOn,
+}
+
+/// The last sort applied to the sheet, like the `sortState` of xlsx files
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct SortState {
+    /// The sorted range, without the header, like "A2:D20"
+    pub range: String,
+    /// Whether the columns of the range were sorted instead of the rows
+    pub left_to_right: bool,
+    pub keys: Vec<SortKey>,
+}
@@ -118,6 +118,7 @@ impl Styles {
             // it is not a named-style category in Excel and is normalized out.
             quote_prefix: false,
             alignment: style.alignment.clone(),
+            protection: style.protection.clone(),
         });
         xf_id
     }
@@ -138,6 +139,7 @@ impl Styles {
             apply_fill: false,
             quote_prefix: style.quote_prefix,
             alignment: style.alignment.clone(),
+            protection: style.protection.clone(),
         });
         self.cell_xfs.len() as i32 - 1
     }
@@ -158,6 +160,7 @@ impl Styles {
             if style
                 == &(Style {
                     alignment: cell_xf.alignment.clone(),
+                    protection: cell_xf.protection.clone(),
                     num_fmt: get_num_fmt(num_fmt_id, &self.num_fmts),
                     fill: self.fills[fill_id].clone(),
                     font: self.fonts[font_id].clone(),
@@ -267,6 +270,7 @@ impl Styles {
             apply_fill: false,
             quote_prefix: false,
             alignment: None,
+            protection: None,
         });
         Ok(self.cell_xfs.len() as i32 - 1)
     }
@@ -296,11 +300,13 @@ impl Styles {
             .get(current_index as usize)
             .ok_or("Invalid index provided".to_string())?
             .clone();
-        // The style's alignment lives on its plain representative.
-        let style_alignment = self
+        // The style's alignment and protection live on its plain representative.
+        let style_xf = self
             .get_style_index_by_name(style_name)
             .ok()
-            .and_then(|index| self.cell_xfs[index as usize].alignment.clone());
+            .map(|index| self.cell_xfs[index as usize].clone());
+        let style_alignment = style_xf.as_ref().and_then(|xf| xf.alignment.clone());
+        let style_protection = style_xf.and_then(|xf| xf.protection);
 
         let new_xf = CellXfs {
             xf_id,
@@ -329,6 +335,11 @@ impl Styles {
             } else {
                 current.alignment.clone()
             },
+            protection: if record.apply_protection {
+                style_protection
+            } else {
+                current.protection.clone()
+            },
             apply_number_format: !record.apply_number_format,
             apply_font: !record.apply_font,
             apply_fill: !record.apply_fill,
@@ -376,6 +387,7 @@ impl Styles {
             .ok_or_else(|| format!("Style '{style_name}' points to an invalid xf id"))?;
         Ok(Style {
             alignment: None,
+            protection: None,
             num_fmt: get_num_fmt(style_xf.num_fmt_id, &self.num_fmts),
             fill: self.fills[style_xf.fill_id as usize].clone(),
             font: self.fonts[style_xf.font_id as usize].clone(),
@@ -479,6 +491,19 @@ impl Styles {
         cell_xf.quote_prefix
     }
 
+    // Returns the name of the named style the format at `index` derives from, if it is not the
+    // default one ("Normal")
+    pub(crate) fn get_style_name(&self, index: i32) -> Option<String> {
+        let xf_id = self.cell_xfs.get(index as usize)?.xf_id;
+        if xf_id == 0 {
+            return None;
+        }
+        self.cell_styles
+            .iter()
+            .find(|cell_style| cell_style.xf_id == xf_id)
+            .map(|cell_style| cell_style.name.clone())
+    }
+
     pub(crate) fn get_style(&self, index: i32) -> Result<Style, String> {
         let cell_xf = &self
             .cell_xfs
@@ -490,9 +515,11 @@ impl Styles {
         let num_fmt_id = cell_xf.num_fmt_id;
         let quote_prefix = cell_xf.quote_prefix;
         let alignment = cell_xf.alignment.clone();
+        let protection = cell_xf.protection.clone();
 
         Ok(Style {
             alignment,
+            protection,
             num_fmt: get_num_fmt(num_fmt_id, &self.num_fmts),
             fill: self.fills[fill_id].clone(),
             font: self.fonts[font_id].clone(),
@@ -692,6 +719,9 @@ impl<'a> Model<'a> {
             if !cell_xf.apply_alignment {
                 cell_xf.alignment = style.alignment.clone();
             }
+            if !cell_xf.apply_protection {
+                cell_xf.protection = style.protection.clone();
+            }
         }
 
         if name != new_name {
@@ -0,0 +1,921 @@
+//! Tables: a range of cells with named columns, an optional header row and an optional totals row.
+//!
+//! Formulas can refer to a table by name (`=SUM(Sales)`) or with structured references
+//! (`=Sales[Amount]`). Both are kept in the formula and resolved with the current area of the
+//! table when the formula is evaluated. Renaming a table or a column updates the formulas.
+
+use crate::{
+    constants::{LAST_COLUMN, LAST_ROW},
+    expressions::{
+        lexer::LexerMode,
+        parser::{
+            get_structured_reference_area, parse_range,
+            stringify::{
+                replace_table_name_in_node, to_english_string, to_rc_format, DisplaceData,
+            },
+            Node,
+        },
+        token::{TableReference, TableSpecifier},
+        types::{Area, CellReferenceRC},
+        utils::{is_valid_identifier, number_to_column},
+    },
+    types::{Table, TableColumn, TableStyleInfo},
+    Model,
+};
+
+const DEFAULT_TABLE_STYLE: &str = "TableStyleMedium2";
+
+// A1:C5
+pub(crate) fn area_to_reference(area: &Area) -> Result<String, String> {
+    let column_start = number_to_column(area.column).ok_or("Invalid column")?;
+    let column_end = number_to_column(area.column + area.width - 1).ok_or("Invalid column")?;
+    Ok(format!(
+        "{column_start}{}:{column_end}{}",
+        area.row,
+        area.row + area.height - 1
+    ))
+}
+
+pub(crate) fn areas_overlap(a: &Area, b: &Area) -> bool {
+    a.sheet == b.sheet
+        && a.row < b.row + b.height
+        && b.row < a.row + a.height
+        && a.column < b.column + b.width
+        && b.column < a.column + a.width
+}
+
+// Returns `name`, or `name` followed by the first number that makes it different from all the `names`.
+// An empty name is replaced by the first free "Column1", "Column2", ...
+fn unique_column_name(name: &str, names: &[String]) -> String {
+    let exists = |candidate: &str| {
+        names
+            .iter()
+            .any(|n| n.to_lowercase() == candidate.to_lowercase())
+    };
+    if name.is_empty() {
+        let mut index = 1;
+        while exists(&format!("Column{index}")) {
+            index += 1;
+        }
+        return format!("Column{index}");
+    }
+    if !exists(name) {
+        return name.to_string();
+    }
+    let mut index = 2;
+    while exists(&format!("{name}{index}")) {
+        index += 1;
+    }
+    format!("{name}{index}")
+}
+
+// Adds `count` columns with new names at `index`
+fn insert_table_columns(table: &mut Table, index: usize, count: i32) {
+    let mut names: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
+    let max_id = table.columns.iter().map(|c| c.id).max().unwrap_or(0);
+    for offset in 0..count as usize {
+        let name = unique_column_name("", &names);
+        names.push(name.clone());
+        table.columns.insert(
+            index + offset,
+            TableColumn {
+                id: max_id + offset as u32 + 1,
+                name,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+impl<'a> Model<'a> {
+    /// Returns the tables in the sheet, sorted by name
+    pub fn get_tables(&self, sheet: u32) -> Result<Vec<Table>, String> {
+        let sheet_name = self.workbook.worksheet(sheet)?.get_name();
+        let mut tables: Vec<Table> = self
+            .workbook
+            .tables
+            .values()
+            .filter(|table| table.sheet_name == sheet_name)
+            .cloned()
+            .collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tables)
+    }
+
+    /// Returns the table with that name. Names of tables are case insensitive.
+    pub fn get_table(&self, name: &str) -> Result<Table, String> {
+        let name_lower = name.to_lowercase();
+        self.workbook
+            .tables
+            .values()
+            .find(|table| table.name.to_lowercase() == name_lower)
+            .cloned()
+            .ok_or_else(|| format!("Table not found: '{name}'"))
+    }
+
+    /// Returns the name of the table the cell is in, if any
+    pub fn get_table_name_at(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<String>, String> {
+        for table in self.get_tables(sheet)? {
+            let area = self.get_table_area(&table)?;
+            if (area.row..area.row + area.height).contains(&row)
+                && (area.column..area.column + area.width).contains(&column)
+            {
+                return Ok(Some(table.name));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the area of the table, including the header and the totals rows
+    pub(crate) fn get_table_area(&self, table: &Table) -> Result<Area, String> {
+        let sheet = self
+            .get_sheet_index_by_name(&table.sheet_name)
+            .ok_or_else(|| format!("Sheet not found: '{}'", table.sheet_name))?;
+        let (column, row, column_end, row_end) = parse_range(&table.reference)?;
+        Ok(Area {
+            sheet,
+            row,
+            column,
+            width: column_end - column + 1,
+            height: row_end - row + 1,
+        })
+    }
+
+    /// Checks that `name` can be used as the name of a new table.
+    /// It must be a valid name and different from the names of other tables and defined names.
+    pub fn is_valid_table_name(&self, name: &str) -> Result<(), String> {
+        if !is_valid_identifier(name) {
+            return Err(format!("Invalid name for a table: '{name}'."));
+        }
+        let name_lower = name.to_lowercase();
+        if self
+            .workbook
+            .tables
+            .values()
+            .any(|table| table.name.to_lowercase() == name_lower)
+        {
+            return Err(format!("A table named '{name}' already exists."));
+        }
+        if self
+            .workbook
+            .defined_names
+            .iter()
+            .any(|defined_name| defined_name.name.to_lowercase() == name_lower)
+        {
+            return Err(format!("There is a defined name '{name}'."));
+        }
+        Ok(())
+    }
+
+    // The first free name of the list Table1, Table2, ...
+    fn get_new_table_name(&self) -> String {
+        let mut index = 1;
+        while self.is_valid_table_name(&format!("Table{index}")).is_err() {
+            index += 1;
+        }
+        format!("Table{index}")
+    }
+
+    // Fails if the area is not valid or overlaps with a table other than `name`
+    fn check_table_area(&self, area: &Area, name: Option<&str>) -> Result<(), String> {
+        if area.width < 1
+            || area.height < 1
+            || area.row < 1
+            || area.column < 1
+            || area.row + area.height - 1 > LAST_ROW
+            || area.column + area.width - 1 > LAST_COLUMN
+        {
+            return Err("Invalid area for a table".to_string());
+        }
+        for table in self.get_tables(area.sheet)? {
+            if Some(table.name.as_str()) == name {
+                continue;
+            }
+            if areas_overlap(area, &self.get_table_area(&table)?) {
+                return Err(format!(
+                    "The area overlaps with the table '{}'.",
+                    table.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Returns true if all the cells in the row between the two columns are empty
+    fn is_row_empty(
+        &self,
+        sheet: u32,
+        row: i32,
+        column_start: i32,
+        column_end: i32,
+    ) -> Result<bool, String> {
+        let worksheet = self.workbook.worksheet(sheet)?;
+        for column in column_start..=column_end {
+            if !worksheet.is_empty_cell(row, column)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Names of the columns of a table in `area` from the text in the header row.
+    // Empty and repeated names are replaced by new names.
+    fn get_column_names_from_header(&self, area: &Area) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = Vec::new();
+        for column in area.column..area.column + area.width {
+            let text = self
+                .get_formatted_cell_value(area.sheet, area.row, column)?
+                .trim()
+                .to_string();
+            names.push(unique_column_name(&text, &names));
+        }
+        Ok(names)
+    }
+
+    // Writes the names of the columns in the header cells that do not have them already
+    fn write_table_headers(&mut self, table: &Table) -> Result<(), String> {
+        if table.header_row_count == 0 {
+            return Ok(());
+        }
+        let area = self.get_table_area(table)?;
+        for (index, table_column) in table.columns.iter().enumerate() {
+            let column = area.column + index as i32;
+            if self.get_formatted_cell_value(area.sheet, area.row, column)? != table_column.name {
+                self.set_user_input(area.sheet, area.row, column, table_column.name.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the table `old_name`, if it exists, with `table` and reparses all formulas.
+    /// If `table` is None the table is removed. The cells are not changed.
+    /// Structured references to the columns that change their name are updated.
+    pub(crate) fn set_table(
+        &mut self,
+        old_name: Option<&str>,
+        table: Option<Table>,
+    ) -> Result<(), String> {
+        if let Some(Ok(old_table)) = old_name.map(|name| self.get_table(name)) {
+            if let Some(table) = &table {
+                self.rename_columns_in_formulas(&old_table, table);
+            }
+            self.workbook.tables.remove(&old_table.name);
+        }
+        if let Some(table) = table {
+            self.workbook.tables.insert(table.name.clone(), table);
+        }
+        self.parser.set_tables(self.workbook.tables.clone());
+        self.reset_parsed_structures();
+        Ok(())
+    }
+
+    /// Creates a table in `area` and returns its name.
+    /// If `has_headers` the first row of the area are the headers of the table, and the names of
+    /// the columns are the text in those cells. Otherwise the table has no header row.
+    pub fn create_table(&mut self, area: &Area, has_headers: bool) -> Result<String, String> {
+        self.check_table_area(area, None)?;
+        if has_headers && area.height < 2 {
+            return Err("A table with headers needs at least two rows".to_string());
+        }
+        let names = if has_headers {
+            self.get_column_names_from_header(area)?
+        } else {
+            (1..=area.width)
+                .map(|index| format!("Column{index}"))
+                .collect()
+        };
+        let name = self.get_new_table_name();
+        let columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| TableColumn {
+                id: index as u32 + 1,
+                name,
+                ..Default::default()
+            })
+            .collect();
+        let table = Table {
+            name: name.clone(),
+            display_name: name.clone(),
+            sheet_name: self.workbook.worksheet(area.sheet)?.get_name(),
+            reference: area_to_reference(area)?,
+            totals_row_count: 0,
+            header_row_count: if has_headers { 1 } else { 0 },
+            header_row_dxf_id: None,
+            data_dxf_id: None,
+            totals_row_dxf_id: None,
+            columns,
+            style_info: TableStyleInfo {
+                name: Some(DEFAULT_TABLE_STYLE.to_string()),
+                show_row_stripes: true,
+                ..Default::default()
+            },
+            has_filters: false,
+        };
+        self.write_table_headers(&table)?;
+        self.set_table(None, Some(table))?;
+        Ok(name)
+    }
+
+    /// Changes the area of the table. The header row cannot move: the new area must start in the
+    /// same row. Columns are added or removed on the right.
+    pub fn resize_table(&mut self, name: &str, area: &Area) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        let old_area = self.get_table_area(&table)?;
+        self.check_table_area(area, Some(&table.name))?;
+        if area.sheet != old_area.sheet || area.row != old_area.row {
+            return Err("The headers of the table must remain in the same row".to_string());
+        }
+        if area.height <= (table.header_row_count + table.totals_row_count) as i32 {
+            return Err("The table needs at least one row of data".to_string());
+        }
+        let width = area.width as usize;
+        if width < table.columns.len() {
+            table.columns.truncate(width);
+        } else if width > table.columns.len() {
+            let new_columns = Area {
+                column: area.column + table.columns.len() as i32,
+                width: area.width - table.columns.len() as i32,
+                ..*area
+            };
+            let names = if table.header_row_count > 0 {
+                self.get_column_names_from_header(&new_columns)?
+            } else {
+                vec![String::new(); new_columns.width as usize]
+            };
+            for name in names {
+                let existing: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
+                let id = table.columns.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+                table.columns.push(TableColumn {
+                    id,
+                    name: unique_column_name(&name, &existing),
+                    ..Default::default()
+                });
+            }
+        }
+        table.reference = area_to_reference(area)?;
+        self.write_table_headers(&table)?;
+        let old_name = table.name.clone();
+        self.set_table(Some(&old_name), Some(table))
+    }
+
+    // Replaces the references to the table in all formulas and defined names.
+    // `replacement` gives the new node for a reference to the table in a formula of a sheet.
+    fn replace_table_name_in_formulas<F>(&mut self, name: &str, replacement: F)
+    where
+        F: Fn(u32, &Node) -> Node,
+    {
+        // All internal formulas are R1C1
+        self.parser.set_lexer_mode(LexerMode::R1C1);
+        for sheet in 0..self.workbook.worksheets.len() {
+            let worksheet = &self.workbook.worksheets[sheet];
+            let cell_reference = CellReferenceRC {
+                sheet: worksheet.get_name(),
+                row: 1,
+                column: 1,
+            };
+            let mut formulas = Vec::new();
+            for formula in worksheet.shared_formulas.clone() {
+                let mut t = self.parse_internal_formula(&formula, &cell_reference);
+                replace_table_name_in_node(&mut t, name, &|node: &Node| {
+                    replacement(sheet as u32, node)
+                });
+                formulas.push(to_rc_format(&t));
+            }
+            self.workbook.worksheets[sheet].shared_formulas = formulas;
+        }
+        self.parser.set_lexer_mode(LexerMode::A1);
+
+        // Defined names are stored in English
+        let context = self.defined_name_context();
+        let mut defined_names = self.workbook.defined_names.clone();
+        for defined_name in &mut defined_names {
+            let body = defined_name
+                .formula
+                .strip_prefix('=')
+                .unwrap_or(&defined_name.formula);
+            let mut node = self.parse_internal_formula(body, &context);
+            let before = to_english_string(&node, &context);
+            replace_table_name_in_node(&mut node, name, &|node: &Node| replacement(0, node));
+            let after = to_english_string(&node, &context);
+            if before != after {
+                defined_name.formula = defined_name.formula.replacen(body, &after, 1);
+            }
+        }
+        self.workbook.defined_names = defined_names;
+    }
+
+    // Updates the structured references to the columns of `old_table` that have a different name
+    // in `table`. Columns are matched by id.
+    fn rename_columns_in_formulas(&mut self, old_table: &Table, table: &Table) {
+        let renames: Vec<(String, String)> = old_table
+            .columns
+            .iter()
+            .filter_map(|old_column| {
+                table
+                    .columns
+                    .iter()
+                    .find(|column| column.id == old_column.id && column.name != old_column.name)
+                    .map(|column| (old_column.name.to_lowercase(), column.name.clone()))
+            })
+            .collect();
+        if renames.is_empty() {
+            return;
+        }
+        let rename = |name: &String| {
+            let name_lower = name.to_lowercase();
+            match renames.iter().find(|(old_name, _)| *old_name == name_lower) {
+                Some((_, new_name)) => new_name.clone(),
+                None => name.clone(),
+            }
+        };
+        self.replace_table_name_in_formulas(&old_table.name, |_, node| match node {
+            Node::StructuredReferenceKind {
+                table_name,
+                specifier,
+                table_reference,
+            } => Node::StructuredReferenceKind {
+                table_name: table_name.clone(),
+                specifier: specifier.clone(),
+                table_reference: match table_reference {
+                    Some(TableReference::ColumnReference(name)) => {
+                        Some(TableReference::ColumnReference(rename(name)))
+                    }
+                    Some(TableReference::RangeReference((left, right))) => Some(
+                        TableReference::RangeReference((rename(left), rename(right))),
+                    ),
+                    None => None,
+                },
+            },
+            _ => node.clone(),
+        });
+    }
+
+    /// Renames the table and updates the formulas that use its name
+    pub fn rename_table(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        if table.name == new_name {
+            return Ok(());
+        }
+        if table.name.to_lowercase() != new_name.to_lowercase() {
+            self.is_valid_table_name(new_name)?;
+        } else if !is_valid_identifier(new_name) {
+            return Err(format!("Invalid name for a table: '{new_name}'."));
+        }
+        let old_name = table.name.clone();
+        self.replace_table_name_in_formulas(&old_name, |_, node| match node {
+            Node::StructuredReferenceKind {
+                specifier,
+                table_reference,
+                ..
+            } => Node::StructuredReferenceKind {
+                table_name: new_name.to_string(),
+                specifier: specifier.clone(),
+                table_reference: table_reference.clone(),
+            },
+            _ => Node::TableNameKind(new_name.to_string()),
+        });
+        table.name = new_name.to_string();
+        table.display_name = new_name.to_string();
+        self.set_table(Some(&old_name), Some(table))
+    }
+
+    /// Deletes the table and the contents of its cells.
+    /// Formulas that use the name of the table will be #NAME?
+    pub fn delete_table(&mut self, name: &str) -> Result<(), String> {
+        let table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        self.range_clear_contents(&area)?;
+        self.set_table(Some(&table.name), None)
+    }
+
+    /// Converts the table into a normal range of cells. The cells are not changed.
+    /// Formulas that use the name of the table or structured references will use ranges instead.
+    pub fn convert_table_to_range(&mut self, name: &str) -> Result<(), String> {
+        let table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        let data_row = area.row + table.header_row_count as i32;
+        let table_sheet = area.sheet;
+        self.replace_table_name_in_formulas(&table.name, |sheet, node| {
+            let sheet_name = if sheet == table_sheet {
+                None
+            } else {
+                Some(table.sheet_name.clone())
+            };
+            let (specifier, table_reference) = match node {
+                Node::StructuredReferenceKind {
+                    specifier,
+                    table_reference,
+                    ..
+                } => (specifier.clone(), table_reference.clone()),
+                _ => (None, None),
+            };
+            // Table1[@Amount] is a reference to the same row of the formula
+            let this_row = specifier == Some(TableSpecifier::ThisRow);
+            let (column1, row1, column2, row2) =
+                match get_structured_reference_area(&table, &specifier, &table_reference, data_row)
+                {
+                    Ok(area) => area,
+                    Err((error, _)) => return Node::ErrorKind(error),
+                };
+            let (row1, row2) = if this_row { (0, 0) } else { (row1, row2) };
+            if row1 == row2 && column1 == column2 {
+                return Node::ReferenceKind {
+                    sheet_name,
+                    sheet_index: table_sheet,
+                    absolute_row: !this_row,
+                    absolute_column: true,
+                    row: row1,
+                    column: column1,
+                };
+            }
+            Node::RangeKind {
+                sheet_name,
+                sheet_index: table_sheet,
+                absolute_row1: !this_row,
+                absolute_column1: true,
+                row1,
+                column1,
+                absolute_row2: !this_row,
+                absolute_column2: true,
+                row2,
+                column2,
+            }
+        });
+        self.set_table(Some(&table.name), None)
+    }
+
+    /// Shows or hides the header row of the table.
+    /// The header row is added above the table, those cells must be empty.
+    pub fn set_table_header_row(&mut self, name: &str, show: bool) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        let column_end = area.column + area.width - 1;
+        let row_end = area.row + area.height - 1;
+        let column_start = number_to_column(area.column).ok_or("Invalid column")?;
+        let column_end_name = number_to_column(column_end).ok_or("Invalid column")?;
+        match (show, table.header_row_count > 0) {
+            (true, false) => {
+                let row = area.row - 1;
+                if row < 1 || !self.is_row_empty(area.sheet, row, area.column, column_end)? {
+                    return Err(
+                        "Cannot show the header row: the cells above the table are not empty"
+                            .to_string(),
+                    );
+                }
+                self.check_table_area(
+                    &Area {
+                        row,
+                        height: 1,
+                        ..area
+                    },
+                    Some(&table.name),
+                )?;
+                table.header_row_count = 1;
+                table.reference = format!("{column_start}{row}:{column_end_name}{row_end}");
+                self.write_table_headers(&table)?;
+            }
+            (false, true) => {
+                self.range_clear_contents(&Area { height: 1, ..area })?;
+                table.header_row_count = 0;
+                table.reference =
+                    format!("{column_start}{}:{column_end_name}{row_end}", area.row + 1);
+            }
+            _ => return Ok(()),
+        }
+        let old_name = table.name.clone();
+        self.set_table(Some(&old_name), Some(table))
+    }
+
+    /// Shows or hides the totals row of the table.
+    /// The totals row is added below the table, those cells must be empty. It has the label
+    /// "Total" in the first column and the sum of the last column.
+    pub fn set_table_totals_row(&mut self, name: &str, show: bool) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        let column_end = area.column + area.width - 1;
+        let row_end = area.row + area.height - 1;
+        let column_start = number_to_column(area.column).ok_or("Invalid column")?;
+        let column_end_name = number_to_column(column_end).ok_or("Invalid column")?;
+        let old_name = table.name.clone();
+        match (show, table.totals_row_count > 0) {
+            (true, false) => {
+                let row = row_end + 1;
+                if row > LAST_ROW || !self.is_row_empty(area.sheet, row, area.column, column_end)? {
+                    return Err(
+                        "Cannot show the totals row: the cells below the table are not empty"
+                            .to_string(),
+                    );
+                }
+                self.check_table_area(
+                    &Area {
+                        row,
+                        height: 1,
+                        ..area
+                    },
+                    Some(&table.name),
+                )?;
+                table.totals_row_count = 1;
+                table.reference = format!("{column_start}{}:{column_end_name}{row}", area.row);
+                let column_count = table.columns.len();
+                if column_count > 1 {
+                    table.columns[0].totals_row_label = Some("Total".to_string());
+                }
+                let last_column = &mut table.columns[column_count - 1];
+                last_column.totals_row_function = Some("sum".to_string());
+                // The formula uses the new area of the table
+                let formula = format!(
+                    "=SUBTOTAL(109,{}[{}])",
+                    table.name,
+                    last_column
+                        .name
+                        .replace('\'', "''")
+                        .replace('[', "'[")
+                        .replace(']', "']")
+                        .replace('#', "'#")
+                        .replace('@', "'@")
+                );
+                self.set_table(Some(&old_name), Some(table))?;
+                if column_count > 1 {
+                    self.set_user_input(area.sheet, row, area.column, "Total".to_string())?;
+                }
+                self.set_user_input(area.sheet, row, column_end, formula)?;
+                Ok(())
+            }
+            (false, true) => {
+                self.range_clear_contents(&Area {
+                    row: row_end,
+                    height: 1,
+                    ..area
+                })?;
+                table.totals_row_count = 0;
+                table.reference = format!(
+                    "{column_start}{}:{column_end_name}{}",
+                    area.row,
+                    row_end - 1
+                );
+                for table_column in &mut table.columns {
+                    table_column.totals_row_label = None;
+                    table_column.totals_row_function = None;
+                }
+                self.set_table(Some(&old_name), Some(table))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Renames the column of the table that is in the sheet column `column`.
+    /// The header cell and the structured references to the column are updated.
+    pub fn rename_table_column(
+        &mut self,
+        name: &str,
+        column: i32,
+        new_name: &str,
+    ) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        let index = column - area.column;
+        if index < 0 || index >= area.width {
+            return Err(format!("Column {column} is not in the table '{name}'"));
+        }
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("The name of a column cannot be empty".to_string());
+        }
+        let new_name_lower = new_name.to_lowercase();
+        if table
+            .columns
+            .iter()
+            .enumerate()
+            .any(|(i, c)| i as i32 != index && c.name.to_lowercase() == new_name_lower)
+        {
+            return Err(format!("The table already has a column named '{new_name}'"));
+        }
+        table.columns[index as usize].name = new_name.to_string();
+        self.write_table_headers(&table)?;
+        let old_name = table.name.clone();
+        self.set_table(Some(&old_name), Some(table))
+    }
+
+    /// Updates the name of a column after the header cell (`row`, `column`) has changed.
+    /// Returns the table before the change if the cell is a header.
+    pub(crate) fn update_table_column_from_header(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Table>, String> {
+        let name = match self.get_table_name_at(sheet, row, column)? {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let old_table = self.get_table(&name)?;
+        let area = self.get_table_area(&old_table)?;
+        if old_table.header_row_count == 0 || row != area.row {
+            return Ok(None);
+        }
+        let index = (column - area.column) as usize;
+        let text = self.get_formatted_cell_value(sheet, row, column)?;
+        let others: Vec<String> = old_table
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, c)| c.name.clone())
+            .collect();
+        let new_name = unique_column_name(text.trim(), &others);
+        if new_name == old_table.columns[index].name {
+            return Ok(None);
+        }
+        let mut table = old_table.clone();
+        table.columns[index].name = new_name;
+        self.set_table(Some(&name), Some(table))?;
+        Ok(Some(old_table))
+    }
+
+    /// Returns the name of the table that is expanded if the user writes in the cell, and its new
+    /// area. A table is expanded with a value right below it, if it has no totals row, or right
+    /// to its right.
+    pub(crate) fn get_table_expansion(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<(String, Area)>, String> {
+        if self.get_table_name_at(sheet, row, column)?.is_some() {
+            return Ok(None);
+        }
+        for table in self.get_tables(sheet)? {
+            let area = self.get_table_area(&table)?;
+            let row_end = area.row + area.height - 1;
+            let column_end = area.column + area.width - 1;
+            let new_area = if row == row_end + 1
+                && table.totals_row_count == 0
+                && (area.column..=column_end).contains(&column)
+            {
+                Area {
+                    height: area.height + 1,
+                    ..area
+                }
+            } else if column == column_end + 1 && (area.row..=row_end).contains(&row) {
+                Area {
+                    width: area.width + 1,
+                    ..area
+                }
+            } else {
+                continue;
+            };
+            if self.check_table_area(&new_area, Some(&table.name)).is_ok() {
+                return Ok(Some((table.name, new_area)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Adds the sheet column `column`, right before or right after the table, to the table
+    pub(crate) fn add_column_to_table(&mut self, name: &str, column: i32) -> Result<(), String> {
+        let mut table = self.get_table(name)?;
+        let area = self.get_table_area(&table)?;
+        let (index, new_area) = if column == area.column - 1 {
+            (
+                0,
+                Area {
+                    column,
+                    width: area.width + 1,
+                    ..area
+                },
+            )
+        } else if column == area.column + area.width {
+            (
+                table.columns.len(),
+                Area {
+                    width: area.width + 1,
+                    ..area
+                },
+            )
+        } else {
+            return Err(format!("Column {column} is not next to the table '{name}'"));
+        };
+        self.check_table_area(&new_area, Some(&table.name))?;
+        insert_table_columns(&mut table, index, 1);
+        table.reference = area_to_reference(&new_area)?;
+        self.write_table_headers(&table)?;
+        let old_name = table.name.clone();
+        self.set_table(Some(&old_name), Some(table))
+    }
+
+    /// Moves the tables when rows or columns are inserted or deleted.
+    /// Columns inserted in a table are new columns of the table. A table is deleted if all its
+    /// rows or columns are deleted.
+    pub(crate) fn displace_tables(&mut self, displace_data: &DisplaceData) -> Result<(), String> {
+        let sheet = match displace_data {
+            DisplaceData::Row { sheet, .. } | DisplaceData::Column { sheet, .. } => *sheet,
+            _ => return Ok(()),
+        };
+        let mut changed = false;
+        let mut new_headers = Vec::new();
+        for mut table in self.get_tables(sheet)? {
+            let area = self.get_table_area(&table)?;
+            let mut row_start = area.row;
+            let mut row_end = area.row + area.height - 1;
+            let mut column_start = area.column;
+            let mut column_end = area.column + area.width - 1;
+            match *displace_data {
+                DisplaceData::Row { row, delta, .. } if delta > 0 => {
+                    if row <= row_start {
+                        row_start += delta;
+                        row_end += delta;
+                    } else if row <= row_end {
+                        row_end += delta;
+                    }
+                }
+                DisplaceData::Row { row, delta, .. } => {
+                    let deleted_end = row - delta - 1;
+                    if deleted_end < row_start {
+                        row_start += delta;
+                        row_end += delta;
+                    } else if row <= row_end {
+                        let overlap = deleted_end.min(row_end) - row.max(row_start) + 1;
+                        if overlap == row_end - row_start + 1 {
+                            self.workbook.tables.remove(&table.name);
+                            changed = true;
+                            continue;
+                        }
+                        if table.header_row_count > 0 && row <= row_start {
+                            table.header_row_count = 0;
+                        }
+                        if table.totals_row_count > 0 && deleted_end >= row_end {
+                            table.totals_row_count = 0;
+                        }
+                        row_start = row_start.min(row);
+                        row_end = row_start + (area.height - overlap) - 1;
+                    }
+                }
+                DisplaceData::Column { column, delta, .. } if delta > 0 => {
+                    if column <= column_start {
+                        column_start += delta;
+                        column_end += delta;
+                    } else if column <= column_end {
+                        column_end += delta;
+                        let index = (column - column_start) as usize;
+                        insert_table_columns(&mut table, index, delta);
+                        if table.header_row_count > 0 {
+                            for offset in 0..delta {
+                                new_headers.push((
+                                    row_start,
+                                    column + offset,
+                                    table.columns[index + offset as usize].name.clone(),
+                                ));
+                            }
+                        }
+                    }
+                }
+                DisplaceData::Column { column, delta, .. } => {
+                    let deleted_end = column - delta - 1;
+                    if deleted_end < column_start {
+                        column_start += delta;
+                        column_end += delta;
+                    } else if column <= column_end {
+                        let first = column.max(column_start);
+                        let last = deleted_end.min(column_end);
+                        if last - first + 1 == area.width {
+                            self.workbook.tables.remove(&table.name);
+                            changed = true;
+                            continue;
+                        }
+                        table.columns.drain(
+                            (first - column_start) as usize..=(last - column_start) as usize,
+                        );
+                        column_start = column_start.min(column);
+                        column_end = column_start + table.columns.len() as i32 - 1;
+                    }
+                }
+                _ => {}
+            }
+            let reference = area_to_reference(&Area {
+                sheet,
+                row: row_start,
+                column: column_start,
+                width: column_end - column_start + 1,
+                height: row_end - row_start + 1,
+            })?;
+            if reference != table.reference || table.columns.len() != area.width as usize {
+                table.reference = reference;
+                self.workbook.tables.insert(table.name.clone(), table);
+                changed = true;
+            }
+        }
+        if changed {
+            self.parser.set_tables(self.workbook.tables.clone());
+        }
+        for (row, column, name) in new_headers {
+            self.set_user_input(sheet, row, column, name)?;
+        }
+        Ok(())
+    }
+}
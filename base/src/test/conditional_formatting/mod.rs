@@ -69,6 +69,7 @@ fn red_fill() -> Dxf {
     Dxf {
         fill: Some(Fill {
             color: Color::Rgb("#FF0000".to_string()),
+            ..Default::default()
         }),
         font: None,
         border: None,
@@ -474,6 +475,7 @@ fn test_list_index_resolves_dxf_after_priority_swap() {
     let blue_fill = Dxf {
         fill: Some(Fill {
             color: Color::Rgb("#0000FF".to_string()),
+            ..Default::default()
         }),
         ..Dxf::default()
     };
@@ -1,4 +1,5 @@
 mod conditional_formatting;
+mod test_3d_references;
 mod test_actions;
 mod test_arabic_roman;
 mod test_binary_search;
@@ -14,6 +15,7 @@ mod test_datedif_leap_month_end;
 mod test_days360_month_end;
 mod test_degrees_radians;
 mod test_error_propagation;
+mod test_external_references;
 mod test_fact_factdouble;
 mod test_fn_accrint;
 mod test_fn_accrintm;
@@ -51,6 +53,7 @@ mod test_gcd_lcm;
 mod test_general;
 mod test_inverted_ranges;
 mod test_issue_623;
+mod test_iterative_calculation;
 mod test_math;
 mod test_metadata;
 mod test_model_cell_clear_all;
@@ -92,6 +95,7 @@ mod test_arrays;
 mod test_cell_info_n_sheets;
 mod test_combin_combina;
 mod test_cycle_reference;
+mod test_date_system;
 mod test_datetime_format;
 mod test_escape_quotes;
 mod test_even_odd;
@@ -130,9 +134,11 @@ mod test_networkdays;
 mod test_now;
 mod test_percentage;
 mod test_range_evaluation;
+mod test_reference_operators;
 mod test_set_functions_error_handling;
 mod test_sheet_names;
 mod test_spill_functions;
+mod test_table_references;
 mod test_today;
 mod test_trigonometric_reciprocals;
 mod test_types;
@@ -0,0 +1,78 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+#[test]
+fn aggregates() {
+    let mut model = new_empty_model();
+    model.rename_sheet("Sheet1", "Summary").unwrap();
+    for (name, value) in [("Jan", "1"), ("Feb", "2"), ("Mar", "3")] {
+        model.add_sheet(name).unwrap();
+        model._set(&format!("{name}!B5"), value);
+        model._set(&format!("{name}!C5"), "10");
+    }
+    model._set("Feb!B6", "text");
+    model._set("A1", "=SUM(Jan:Mar!B5)");
+    model._set("A2", "=AVERAGE(Jan:Mar!B5)");
+    model._set("A3", "=COUNT(Jan:Mar!B5:C6)");
+    model._set("A4", "=COUNTA(Jan:Mar!B5:B6)");
+    model._set("A5", "=MAX(Jan:Mar!B5)");
+    model._set("A6", "=MIN(Feb:Mar!B5,7)");
+    model._set("A7", "=SUM(Mar:Jan!B5)");
+    model._set("A8", "=SUM(Feb:Feb!B5:C5)");
+    model._set("A9", "=PRODUCT(Jan:Mar!B5)");
+    model._set("A10", "=STDEV.P(Jan:Mar!B5)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "6");
+    assert_eq!(model._get_text("A2"), "2");
+    assert_eq!(model._get_text("A3"), "6");
+    assert_eq!(model._get_text("A4"), "4");
+    assert_eq!(model._get_text("A5"), "3");
+    assert_eq!(model._get_text("A6"), "2");
+    // The order of the sheets in the span does not matter
+    assert_eq!(model._get_text("A7"), "6");
+    assert_eq!(model._get_text("A8"), "12");
+    assert_eq!(model._get_text("A9"), "6");
+    assert_eq!(model._get_text("A10"), "0.816496581");
+}
+
+#[test]
+fn other_uses() {
+    let mut model = new_empty_model();
+    model.add_sheet("Jan").unwrap();
+    model.add_sheet("Feb").unwrap();
+    model._set("Jan!A1", "1");
+    model._set("Feb!A1", "2");
+    model._set("A1", "=Jan:Feb!A1");
+    model._set("A2", "=ABS(Jan:Feb!A1)");
+    model._set("A3", "=SUM(Jan:Dec!A1)");
+    model._set("A4", "=ISREF(Jan:Feb!A1)");
+    model._set("A5", "=AREAS(Jan:Feb!A1)");
+    model._set("A6", "=Feb:Feb!A1");
+    model._set("A7", "=SUM((Jan:Feb!A1,Jan!A1))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "#VALUE!");
+    assert_eq!(model._get_text("A2"), "#VALUE!");
+    assert_eq!(model._get_text("A3"), "#REF!");
+    assert_eq!(model._get_text("A4"), "TRUE");
+    assert_eq!(model._get_text("A5"), "2");
+    assert_eq!(model._get_text("A6"), "2");
+    assert_eq!(model._get_text("A7"), "4");
+    assert_eq!(model._get_formula("A3"), "=SUM(Jan:Dec!A1)");
+}
+
+#[test]
+fn dependencies() {
+    let mut model = new_empty_model();
+    model.add_sheet("Jan").unwrap();
+    model.add_sheet("Feb").unwrap();
+    model._set("A1", "=SUM(Jan:Feb!B2)");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "0");
+
+    model._set("Feb!B2", "5");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "5");
+}
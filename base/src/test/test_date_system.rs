@@ -0,0 +1,119 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+use crate::types::DateSystem;
+
+#[test]
+fn default_is_1900() {
+    let model = new_empty_model();
+    assert_eq!(model.get_date_system(), DateSystem::Date1900);
+}
+
+#[test]
+fn date_functions() {
+    let mut model = new_empty_model();
+    model.set_date_system(DateSystem::Date1904);
+    model._set("A1", "=TEXT(DATE(2024,1,15),\"0\")");
+    model._set("A2", "=YEAR(0)");
+    model._set("A3", "=MONTH(0)");
+    model._set("A4", "=DAY(0)");
+    model._set("A5", "=DATE(1903,12,31)");
+    model._set("A6", "=TEXT(DATEVALUE(\"2024-01-15\"),\"0\")");
+    model._set("A7", "=TEXT(EDATE(0,1),\"0\")");
+    model._set("A8", "=TEXT(EOMONTH(0,0),\"0\")");
+    model._set("A9", "=WEEKDAY(0)");
+    model._set("A10", "=YEAR(A1)&\"-\"&MONTH(A1)&\"-\"&DAY(A1)");
+    model._set("A11", "=DAYS360(0,DATE(1905,1,1))");
+    model._set("A12", "=YEARFRAC(DATE(2024,1,1),DATE(2024,7,1))");
+    model._set("A13", "=TEXT(WORKDAY(DATE(2024,1,5),1),\"0\")");
+    model.evaluate();
+
+    // 45306 in the 1900 date system
+    assert_eq!(model._get_text("A1"), "43844");
+    assert_eq!(model._get_text("A2"), "1904");
+    assert_eq!(model._get_text("A3"), "1");
+    assert_eq!(model._get_text("A4"), "1");
+    assert_eq!(model._get_text("A5"), "#NUM!");
+    assert_eq!(model._get_text("A6"), "43844");
+    assert_eq!(model._get_text("A7"), "31");
+    assert_eq!(model._get_text("A8"), "30");
+    // 1904-01-01 was a Friday
+    assert_eq!(model._get_text("A9"), "6");
+    assert_eq!(model._get_text("A10"), "2024-1-15");
+    assert_eq!(model._get_text("A11"), "360");
+    assert_eq!(model._get_text("A12"), "0.5");
+    // Friday 2024-01-05 + 1 working day is Monday 2024-01-08
+    assert_eq!(model._get_text("A13"), "43837");
+}
+
+#[test]
+fn financial_functions() {
+    let mut model = new_empty_model();
+    model._set(
+        "A1",
+        "=TEXT(COUPNCD(DATE(2024,1,15),DATE(2030,6,30),2),\"0\")",
+    );
+    model._set("A2", "=TEXT(A1*1,\"yyyy-mm-dd\")");
+    model._set("A3", "=COUPDAYBS(DATE(2024,1,15),DATE(2030,6,30),2)");
+    model._set("A4", "=ACCRINTM(DATE(2024,1,1),DATE(2024,7,1),0.1,1000)");
+    model.evaluate();
+    let coupon_date_1900 = model._get_text("A1");
+    assert_eq!(model._get_text("A2"), "2024-06-30");
+    assert_eq!(model._get_text("A3"), "15");
+    assert_eq!(model._get_text("A4"), "50");
+
+    model.set_date_system(DateSystem::Date1904);
+    let coupon_date_1904: f64 = model._get_text("A1").parse().unwrap();
+    let coupon_date_1900: f64 = coupon_date_1900.parse().unwrap();
+    assert_eq!(coupon_date_1900 - coupon_date_1904, 1462.0);
+    assert_eq!(model._get_text("A2"), "2024-06-30");
+    assert_eq!(model._get_text("A3"), "15");
+    assert_eq!(model._get_text("A4"), "50");
+}
+
+#[test]
+fn formatting() {
+    let mut model = new_empty_model();
+    model._set("A1", "43844");
+    model._set("A2", "=TEXT(A1,\"yyyy-mm-dd\")");
+    model.evaluate();
+    assert_eq!(model._get_text("A2"), "2020-01-14");
+
+    model.set_date_system(DateSystem::Date1904);
+    assert_eq!(model._get_text("A2"), "2024-01-15");
+
+    let (sheet, row, column) = (0, 1, 1);
+    model
+        .set_cell_style(sheet, row, column, &{
+            let mut style = model.get_style_for_cell(sheet, row, column).unwrap();
+            style.num_fmt = "dd/mm/yyyy".to_string();
+            style
+        })
+        .unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(sheet, row, column).unwrap(),
+        "15/01/2024"
+    );
+}
+
+#[test]
+fn user_input() {
+    let mut model = new_empty_model();
+    model.set_date_system(DateSystem::Date1904);
+    model
+        .set_user_input(0, 1, 1, "2024-01-15".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 2, 1, "1903-12-31".to_string())
+        .unwrap();
+    model._set("A3", "=TEXT(A1,\"0\")");
+    model._set("A4", "=COUNTIF(A1:A1,\">=2024-01-15\")");
+    model._set("A5", "=TEXT(VALUE(\"2024-01-15\"),\"0\")");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "2024-01-15");
+    assert_eq!(model._get_text("A3"), "43844");
+    // Dates before 1904 are not dates in the 1904 date system
+    assert_eq!(model._get_text("A2"), "1903-12-31");
+    assert_eq!(model._get_text("A4"), "1");
+    assert_eq!(model._get_text("A5"), "43844");
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+
+use crate::expressions::token::Error;
+use crate::test::util::new_empty_model;
+use crate::types::{ExternalLink, ExternalSheet, ExternalValue};
+use crate::{Model, UserModel};
+
+// A link to Budget.xlsx with the cached values Summary!C4:C6 = 100, "n/a", #DIV/0!
+fn new_linked_model<'a>() -> Model<'a> {
+    let mut model = new_empty_model();
+    let mut sheet_data = HashMap::new();
+    for (row, value) in [
+        (4, ExternalValue::Number(100.0)),
+        (5, ExternalValue::Text("n/a".to_string())),
+        (6, ExternalValue::Error(Error::DIV)),
+        (7, ExternalValue::Boolean(true)),
+    ] {
+        sheet_data.insert(row, HashMap::from([(3, value)]));
+    }
+    model.workbook.external_links = vec![ExternalLink {
+        target: "Budget.xlsx".to_string(),
+        sheets: vec![ExternalSheet {
+            name: "Summary".to_string(),
+            sheet_data,
+        }],
+    }];
+    model
+}
+
+#[test]
+fn cached_values() {
+    let mut model = new_linked_model();
+    model._set("A1", "=[1]Summary!C4*2");
+    model._set("A2", "=[1]Summary!C5");
+    model._set("A3", "=[1]Summary!C6");
+    model._set("A4", "=[1]Summary!C7");
+    model._set("A5", "='[Budget.xlsx]Summary'!$C$4");
+    model._set("A6", "=SUM([1]Summary!C4:C5)");
+    model._set("A7", "=COUNTA([1]Summary!C1:C10)");
+    model._set("A8", "=[1]Summary!Z100");
+    model._set("A9", "=COUNTA([1]Summary!C:C)");
+    model._set("A10", "=COUNTA([1]Summary!A:XFD)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "200");
+    assert_eq!(model._get_text("A2"), "n/a");
+    assert_eq!(model._get_text("A3"), "#DIV/0!");
+    assert_eq!(model._get_text("A4"), "TRUE");
+    assert_eq!(model._get_text("A5"), "100");
+    assert_eq!(model._get_text("A6"), "100");
+    assert_eq!(model._get_text("A7"), "4");
+    assert_eq!(model._get_text("A8"), "0");
+    assert_eq!(model._get_text("A9"), "4");
+    assert_eq!(model._get_text("A10"), "4");
+    assert_eq!(model._get_formula("A5"), "=[Budget.xlsx]Summary!$C$4");
+}
+
+#[test]
+fn broken_links() {
+    let mut model = new_linked_model();
+    model._set("A1", "=[2]Summary!C4");
+    model._set("A2", "=[1]Details!C4");
+    model._set("A3", "=[Other.xlsx]Summary!C4");
+    model._set("A4", "=ISREF([1]Summary!C4)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "#REF!");
+    assert_eq!(model._get_text("A2"), "#REF!");
+    assert_eq!(model._get_text("A3"), "#REF!");
+    assert_eq!(model._get_text("A4"), "TRUE");
+}
+
+#[test]
+fn resolver_with_models() {
+    let mut budget = new_empty_model();
+    budget.rename_sheet("Sheet1", "Summary").unwrap();
+    budget._set("C3", "40");
+    budget._set("C4", "=C3*10");
+    budget.evaluate();
+    let mut other = new_empty_model();
+    other._set("A1", "Hello");
+
+    let mut model = new_linked_model();
+    model._set("A1", "=[1]Summary!C4*2");
+    model._set("A2", "=SUM([1]Summary!C3:C4)");
+    model._set("A3", "=[Other.xlsx]Sheet1!A1");
+    model._set("A4", "=[1]Summary!C5");
+    model._set("A5", "=SUM([1]Summary!C:C)");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "200");
+
+    let models = HashMap::from([
+        ("Budget.xlsx".to_string(), budget),
+        ("Other.xlsx".to_string(), other),
+    ]);
+    model.set_external_link_resolver(Some(Box::new(models)));
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "800");
+    assert_eq!(model._get_text("A2"), "440");
+    assert_eq!(model._get_text("A3"), "Hello");
+    // Empty in the linked model
+    assert_eq!(model._get_text("A4"), "0");
+    assert_eq!(model._get_text("A5"), "440");
+
+    // Back to the cached values
+    model.set_external_link_resolver(None);
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "200");
+    assert_eq!(model._get_text("A3"), "#REF!");
+}
+
+#[test]
+fn unavailable_workbook_uses_cache() {
+    let mut model = new_linked_model();
+    model._set("A1", "=[1]Summary!C4");
+    let models: HashMap<String, Model> = HashMap::new();
+    model.set_external_link_resolver(Some(Box::new(models)));
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "100");
+}
+
+#[test]
+fn recalculation() {
+    let mut model = UserModel::from_model(new_linked_model());
+    model.set_user_input(0, 1, 1, "=[1]Summary!C4").unwrap();
+    model.set_user_input(0, 1, 2, "=A1+1").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("101".to_string())
+    );
+
+    // Updating the cached values alone does not recompute the formulas
+    model.model.workbook.external_links[0].sheets[0]
+        .sheet_data
+        .get_mut(&4)
+        .unwrap()
+        .insert(3, ExternalValue::Number(7.0));
+    model.set_user_input(0, 1, 3, "3").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("101".to_string())
+    );
+
+    model.model.refresh_external_links();
+    model.set_user_input(0, 1, 3, "4").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2), Ok("8".to_string()));
+}
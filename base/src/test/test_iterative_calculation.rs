@@ -0,0 +1,160 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+use crate::types::{Cell, FormulaValue, IterativeCalculation};
+
+fn iterative(max_iterations: i32, max_change: f64) -> IterativeCalculation {
+    IterativeCalculation {
+        enabled: true,
+        max_iterations,
+        max_change,
+    }
+}
+
+#[test]
+fn disabled_by_default() {
+    let mut model = new_empty_model();
+    assert_eq!(
+        model.get_iterative_calculation(),
+        IterativeCalculation::default()
+    );
+    model._set("A1", "=A1/2+1");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "#CIRC!");
+}
+
+#[test]
+fn self_reference_converges() {
+    let mut model = new_empty_model();
+    model._set("A1", "=A1/2+1");
+    model._set("A2", "=A1*10");
+    model
+        .set_iterative_calculation(iterative(100, 0.001))
+        .unwrap();
+    // 1, 1.5, 1.75, ... stops when the change is below 0.001
+    assert_eq!(model._get_text("A1"), "1.999023438");
+    assert_eq!(model._get_text("A2"), "19.990234375");
+}
+
+#[test]
+fn interest_on_average_balance() {
+    let mut model = new_empty_model();
+    model
+        .set_iterative_calculation(iterative(100, 0.000001))
+        .unwrap();
+    // Opening balance, interest on the average balance and closing balance
+    model._set("B1", "1000");
+    model._set("B2", "=(B1+B3)/2*10%");
+    model._set("B3", "=B1+B2");
+    model._set("B4", "=ROUND(B3,4)");
+    model.evaluate();
+    // closing = 1000 + (1000 + closing) * 0.05 => closing = 1050 / 0.95
+    assert_eq!(model._get_text("B4"), "1105.2632");
+}
+
+#[test]
+fn maximum_iterations() {
+    let mut model = new_empty_model();
+    model
+        .set_iterative_calculation(iterative(10, 0.001))
+        .unwrap();
+    model._set("A1", "=A1+1");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "10");
+    // Like Excel, every recalculation continues from the previous values
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "20");
+}
+
+#[test]
+fn disabling_restores_circ_errors() {
+    let mut model = new_empty_model();
+    model
+        .set_iterative_calculation(iterative(100, 0.001))
+        .unwrap();
+    model._set("A1", "=B1+1");
+    model._set("B1", "=MIN(A1,5)");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "6");
+    assert_eq!(model._get_text("B1"), "5");
+
+    model
+        .set_iterative_calculation(IterativeCalculation::default())
+        .unwrap();
+    assert_eq!(model._get_text("A1"), "#CIRC!");
+    assert_eq!(model._get_text("B1"), "#CIRC!");
+}
+
+#[test]
+fn errors_in_cycles() {
+    let mut model = new_empty_model();
+    model
+        .set_iterative_calculation(iterative(100, 0.001))
+        .unwrap();
+    model._set("A1", "=B1+1");
+    model._set("B1", "=A1/0");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "#DIV/0!");
+    assert_eq!(model._get_text("B1"), "#DIV/0!");
+}
+
+#[test]
+fn invalid_settings() {
+    let mut model = new_empty_model();
+    assert_eq!(
+        model.set_iterative_calculation(iterative(0, 0.001)),
+        Err("Maximum iterations must be a positive number".to_string())
+    );
+    assert_eq!(
+        model.set_iterative_calculation(iterative(100, -1.0)),
+        Err("Maximum change must be a non negative number".to_string())
+    );
+    assert_eq!(
+        model.get_iterative_calculation(),
+        IterativeCalculation::default()
+    );
+}
+
+#[test]
+fn only_the_cycle_is_iterated() {
+    let mut model = new_empty_model();
+    model._set("A1", "1000");
+    model._set("A2", "=(A1+A3)/2*10%");
+    model._set("A3", "=A1+A2");
+    model._set("A4", "=ROUND(A3,2)");
+    model._set("B1", "=C1*2");
+    model._set("C1", "3");
+    model
+        .set_iterative_calculation(iterative(100, 0.000001))
+        .unwrap();
+    assert_eq!(model._get_text("A4"), "1105.26");
+    assert_eq!(model._get_text("B1"), "6");
+
+    // A stale value outside of the cycle is not recomputed while iterating
+    let cell = model.workbook.worksheet(0).unwrap().cell(1, 2).cloned();
+    let Some(Cell::CellFormula { f, s, .. }) = cell else {
+        panic!("B1 should be a formula");
+    };
+    model
+        .workbook
+        .worksheet_mut(0)
+        .unwrap()
+        .update_cell(
+            1,
+            2,
+            Cell::CellFormula {
+                f,
+                s,
+                v: FormulaValue::Number(42.0),
+            },
+        )
+        .unwrap();
+    model._set("A1", "2000");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("A4"), "2210.53");
+    assert_eq!(model._get_text("B1"), "42");
+
+    model.evaluate();
+    assert_eq!(model._get_text("A4"), "2210.53");
+    assert_eq!(model._get_text("B1"), "6");
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+#[test]
+fn intersection() {
+    let mut model = new_empty_model();
+    model._set("B3", "42");
+    model._set("C2", "1");
+    model._set("C3", "2");
+    model._set("D3", "3");
+    model._set("A1", "=B:B 3:3");
+    model._set("A2", "=SUM(B2:D3 C:D)");
+    model._set("A3", "=SUM(B2:D3 C:D 3:3)");
+    model._set("A4", "=A1:B2 C3:D4");
+    model._set("A5", "=COUNT(B1:D9 C:C)");
+    model._set("A6", "=ISREF(B:B 3:3)");
+    model._set("A7", "=Sheet1!B:B Sheet1!3:3");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "42");
+    assert_eq!(model._get_text("A2"), "6");
+    assert_eq!(model._get_text("A3"), "5");
+    assert_eq!(model._get_text("A4"), "#NULL!");
+    assert_eq!(model._get_text("A5"), "2");
+    assert_eq!(model._get_text("A6"), "TRUE");
+    assert_eq!(model._get_text("A7"), "42");
+}
+
+#[test]
+fn union() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    model._set("B1", "1");
+    model._set("B2", "2");
+    model._set("C1", "3");
+    model._set("D1", "4");
+    model._set("D2", "text");
+    model._set("A1", "=SUM((B1:B2,D1))");
+    model._set("A2", "=COUNT((B1:B2,C1,D1:D2))");
+    model._set("A3", "=MAX((B1,C1),D1)");
+    model._set("A4", "=COUNTA((B1:B2,D1:D2))");
+    model._set("A5", "=(B1,C1)");
+    model._set("A6", "=ABS((B1,C1))");
+    model._set("A7", "=AREAS((B1,Sheet2!B1))");
+    model._set("A8", "=SUM((B1:B2,C1) C:C)");
+    model._set("A9", "=SUM((B1,NoSheet!B1))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "7");
+    assert_eq!(model._get_text("A2"), "4");
+    assert_eq!(model._get_text("A3"), "4");
+    assert_eq!(model._get_text("A4"), "4");
+    assert_eq!(model._get_text("A5"), "#VALUE!");
+    assert_eq!(model._get_text("A6"), "#VALUE!");
+    // All the areas in a union must be in the same sheet
+    assert_eq!(model._get_text("A7"), "#VALUE!");
+    assert_eq!(model._get_text("A8"), "3");
+    assert_eq!(model._get_text("A9"), "#REF!");
+}
+
+#[test]
+fn areas_and_index() {
+    let mut model = new_empty_model();
+    model._set("B1", "1");
+    model._set("C2", "2");
+    model._set("E1", "3");
+    model._set("F2", "4");
+    model._set("A1", "=AREAS((B1,C1:D2))");
+    model._set("A2", "=AREAS(B1:D2)");
+    model._set("A3", "=AREAS((B1:C2,E1:F2,B1))");
+    model._set("A4", "=INDEX((B1:C2,E1:F2),2,2,2)");
+    model._set("A5", "=INDEX((B1:C2,E1:F2),2,2,1)");
+    model._set("A6", "=INDEX((B1:C2,E1:F2),1,1,3)");
+    model._set("A7", "=INDEX(B1:C2,2,2,2)");
+    model._set("A8", "=SUM(INDEX((B1:C2,E1:F2),0,0,2))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), "2");
+    assert_eq!(model._get_text("A2"), "1");
+    assert_eq!(model._get_text("A3"), "3");
+    assert_eq!(model._get_text("A4"), "4");
+    assert_eq!(model._get_text("A5"), "2");
+    assert_eq!(model._get_text("A6"), "#REF!");
+    assert_eq!(model._get_text("A7"), "#REF!");
+    assert_eq!(model._get_text("A8"), "7");
+}
+
+#[test]
+fn dependencies() {
+    let mut model = new_empty_model();
+    model._set("B3", "1");
+    model._set("C1", "2");
+    model._set("A1", "=B:B 3:3");
+    model._set("A2", "=SUM((B3,C1))");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "1");
+    assert_eq!(model._get_text("A2"), "3");
+
+    model._set("B3", "5");
+    model._set("C1", "10");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), "5");
+    assert_eq!(model._get_text("A2"), "15");
+}
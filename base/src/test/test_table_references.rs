@@ -0,0 +1,110 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+use crate::types::{Table, TableColumn, TableStyleInfo};
+use crate::Model;
+
+// Table "Sales" in A1:C5: headers in row 1, data in rows 2 to 4 and totals in row 5
+fn new_sales_model<'a>() -> Model<'a> {
+    let mut model = new_empty_model();
+    let columns = ["Region", "Amount", "Notes"]
+        .iter()
+        .enumerate()
+        .map(|(id, name)| TableColumn {
+            id: id as u32 + 1,
+            name: name.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    model.workbook.tables.insert(
+        "Sales".to_string(),
+        Table {
+            name: "Sales".to_string(),
+            display_name: "Sales".to_string(),
+            sheet_name: "Sheet1".to_string(),
+            reference: "A1:C5".to_string(),
+            totals_row_count: 1,
+            header_row_count: 1,
+            header_row_dxf_id: None,
+            data_dxf_id: None,
+            columns,
+            style_info: TableStyleInfo::default(),
+            totals_row_dxf_id: None,
+            has_filters: true,
+        },
+    );
+    model.parser.set_tables(model.workbook.tables.clone());
+    model._set("A1", "Region");
+    model._set("B1", "Amount");
+    model._set("C1", "Notes");
+    model._set("A2", "North");
+    model._set("B2", "10");
+    model._set("A3", "South");
+    model._set("B3", "20");
+    model._set("A4", "East");
+    model._set("B4", "30");
+    model._set("A5", "Total");
+    model._set("B5", "=SUBTOTAL(109,Sales[Amount])");
+    model
+}
+
+#[test]
+fn table_name_is_the_data_body() {
+    let mut model = new_sales_model();
+    model._set("D1", "=SUM(Sales)");
+    model._set("D2", "=ROWS(Sales)");
+    model._set("D3", "=COLUMNS(Sales)");
+    model._set("D4", "=SUM(sales[amount])");
+    model._set("D5", "=INDEX(Sales,2,1)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B5"), *"60");
+    assert_eq!(model._get_text("D1"), *"60");
+    assert_eq!(model._get_text("D2"), *"3");
+    assert_eq!(model._get_text("D3"), *"3");
+    assert_eq!(model._get_text("D4"), *"60");
+    assert_eq!(model._get_text("D5"), *"South");
+}
+
+#[test]
+fn table_specifiers() {
+    let mut model = new_sales_model();
+    model._set("D1", "=ROWS(Sales[#All])");
+    model._set("D2", "=Sales[[#Headers],[Amount]]");
+    model._set("D3", "=Sales[[#Totals],[Amount]]");
+    model._set("D4", "=COUNTA(Sales[[#Headers],[#Data],[Region]])");
+    model._set("D5", "=SUM(Sales[[#Data],[#Totals],[Amount]])");
+    model._set("D6", "=ROWS(Sales[#Data])");
+    model._set("C3", "=Sales[@Amount]*2");
+    // Inside the table the name of the table can be omitted
+    model._set("C4", "=[@Region]&\"!\"");
+    model._set("E4", "=[@Region]");
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"5");
+    assert_eq!(model._get_text("D2"), *"Amount");
+    assert_eq!(model._get_text("D3"), *"60");
+    assert_eq!(model._get_text("D4"), *"4");
+    assert_eq!(model._get_text("D5"), *"120");
+    assert_eq!(model._get_text("D6"), *"3");
+    assert_eq!(model._get_text("C3"), *"40");
+    assert_eq!(model._get_text("C4"), *"East!");
+    assert_eq!(model._get_text("E4"), *"#ERROR!");
+}
+
+#[test]
+fn table_references_in_indirect() {
+    let mut model = new_sales_model();
+    model._set("D1", "=SUM(INDIRECT(\"Sales[Amount]\"))");
+    model._set("D2", "=ROWS(INDIRECT(\"Sales\"))");
+    model._set("D3", "=INDIRECT(\"Sales[[#Totals],[Amount]]\")");
+    model._set("D4", "=INDIRECT(\"Sales[Price]\")");
+    model._set("D5", "=INDIRECT(\"Purchases\")");
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"60");
+    assert_eq!(model._get_text("D2"), *"3");
+    assert_eq!(model._get_text("D3"), *"60");
+    assert_eq!(model._get_text("D4"), *"#REF!");
+    assert_eq!(model._get_text("D5"), *"#REF!");
+}
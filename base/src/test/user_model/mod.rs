@@ -1,5 +1,7 @@
+mod test_3d_references;
 mod test_add_delete_sheets;
 mod test_array_formulas;
+mod test_auto_filter;
 mod test_auto_link;
 mod test_autofill_columns;
 mod test_autofill_rows;
@@ -7,35 +9,51 @@ mod test_batch_row_column_diff;
 mod test_border;
 mod test_clear_cells;
 mod test_column_style;
+mod test_comments;
 mod test_conditional_formatting;
 mod test_cut_n_paste;
+mod test_data_validation;
+mod test_date_system;
 mod test_defined_names;
 mod test_delete_row_column_formatting;
 mod test_diff_queue;
 mod test_duplicate_sheet;
 mod test_dynamic_arrays;
 mod test_evaluation;
+mod test_find;
 mod test_fn_formulatext;
 mod test_general;
 mod test_grid_lines;
 mod test_hidden_columns;
+mod test_incremental_evaluation;
+mod test_iterative_calculation;
 mod test_keyboard_navigation;
 mod test_language_switch;
 mod test_last_empty_cell;
 mod test_links;
+mod test_merged_cells;
 mod test_move_sheet;
 mod test_multi_row_column;
 mod test_named_styles;
 mod test_on_area_selection;
 mod test_on_expand_selected_range;
 mod test_on_paste_styles;
+mod test_outline;
+mod test_page_setup;
 mod test_paste_csv;
+mod test_paste_external;
+mod test_paste_html;
+mod test_paste_special;
+mod test_protection;
 mod test_recursive;
 mod test_rename_sheet;
+mod test_rich_text;
 mod test_row_column;
 mod test_sheet_state;
 mod test_sheets_undo_redo;
+mod test_sort;
 mod test_styles;
+mod test_tables;
 mod test_to_from_bytes;
 mod test_undo_redo;
 mod test_user_input_dates_quote;
@@ -0,0 +1,144 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::UserModel;
+
+// Summary, Jan, Feb, Mar with B5 = 1, 2, 3 and Summary!A1 = SUM(Jan:Mar!B5)
+fn new_quarter_model<'a>() -> UserModel<'a> {
+    let mut model = new_empty_user_model();
+    model.rename_sheet(0, "Summary").unwrap();
+    for (index, name) in ["Jan", "Feb", "Mar"].iter().enumerate() {
+        model.new_sheet().unwrap();
+        let sheet = index as u32 + 1;
+        model.rename_sheet(sheet, name).unwrap();
+        model
+            .set_user_input(sheet, 5, 2, &format!("{}", sheet))
+            .unwrap();
+    }
+    model.set_user_input(0, 1, 1, "=SUM(Jan:Mar!B5)").unwrap();
+    model
+}
+
+#[test]
+fn insert_and_move_sheets() {
+    let mut model = new_quarter_model();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+
+    // A new sheet between Jan and Mar is part of the span
+    model.new_sheet().unwrap();
+    model.set_user_input(4, 5, 2, "10").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+    model.move_sheet(4, 2).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("16".to_string())
+    );
+
+    // Moving it out of the span
+    model.move_sheet(2, 4).unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+
+    // Moving the last sheet of the span changes the span
+    model.move_sheet(3, 4).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("16".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(Jan:Mar!B5)".to_string())
+    );
+}
+
+#[test]
+fn rename_sheets() {
+    let mut model = new_quarter_model();
+    model.rename_sheet(1, "January").unwrap();
+    model.rename_sheet(3, "March 2024").unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM('January:March 2024'!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(January:Mar!B5)".to_string())
+    );
+}
+
+#[test]
+fn delete_sheets() {
+    let mut model = new_quarter_model();
+    model.set_user_input(0, 2, 1, "=SUM(Feb:Mar!B5)").unwrap();
+
+    // Deleting a sheet in the middle of a span and the first sheet of another
+    model.delete_sheet(2).unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("4".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(Jan:Mar!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("3".to_string()));
+
+    // Deleting the first sheet of the span
+    model.delete_sheet(1).unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(Mar:Mar!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("3".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 2, 1),
+        Ok("=SUM(Mar:Mar!B5)".to_string())
+    );
+
+    // Deleting the only sheet in the span breaks the reference
+    model.new_sheet().unwrap();
+    model.delete_sheet(1).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#REF!".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("3".to_string()));
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(Jan:Mar!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("4".to_string()));
+    model.undo().unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("6".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 2, 1),
+        Ok("=SUM(Feb:Mar!B5)".to_string())
+    );
+
+    model.redo().unwrap();
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 1),
+        Ok("=SUM(Mar:Mar!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("3".to_string()));
+}
+
+#[test]
+fn duplicate_sheet() {
+    let mut model = new_quarter_model();
+    model.set_user_input(1, 1, 1, "=SUM(Jan:Mar!B5)").unwrap();
+    model.duplicate_sheet(1).unwrap();
+    // The copy is placed right after Jan, inside the span
+    assert_eq!(
+        model.get_cell_content(2, 1, 1),
+        Ok("=SUM(Jan:Mar!B5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("7".to_string()));
+}
@@ -0,0 +1,412 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::filter_types::{
+    CustomFilter, DateGroupItem, DateTimeGrouping, DynamicFilter, Filter, FilterOperator,
+};
+use crate::sort_types::{SortKey, SortOn};
+use crate::test::util::{area, hidden_rows, new_user_model_with_data};
+use crate::types::{Color, SheetProtection};
+use crate::UserModel;
+
+fn values_filter(values: &[&str]) -> Filter {
+    Filter::Values {
+        values: values.iter().map(|value| value.to_string()).collect(),
+        date_groups: vec![],
+        blank: false,
+    }
+}
+
+// A1:C7 with a header in row 1
+fn new_model_with_data() -> UserModel<'static> {
+    new_user_model_with_data(&[
+        &["Fruit", "Price", "Date"],
+        &["pear", "3", "2024-03-01"],
+        &["Apple", "5", "2024-03-15"],
+        &["fig", "8", "2023-12-24"],
+        &["", "1", "2024-04-02"],
+        &["cherry", "10", "2024-03-20"],
+        &["apple", "2", ""],
+    ])
+}
+
+#[test]
+fn values_and_blanks() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap().unwrap().range, "A1:C7");
+
+    model
+        .set_filter(0, 1, 1, Some(values_filter(&["APPLE", "fig"])))
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 6]);
+    model
+        .set_filter(
+            0,
+            1,
+            1,
+            Some(Filter::Values {
+                values: vec!["pear".to_string()],
+                date_groups: vec![],
+                blank: true,
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [3, 4, 6, 7]);
+
+    // All the filters must match
+    model
+        .set_filter(0, 1, 2, Some(values_filter(&["1"])))
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 3, 4, 6, 7]);
+    assert!(model.get_filter(0, 4, 2).unwrap().is_some());
+    assert!(model.get_filter(0, 4, 3).unwrap().is_none());
+
+    // One undo per change
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [3, 4, 6, 7]);
+    model.set_filter(0, 1, 1, None).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), Vec::<i32>::new());
+    model.undo().unwrap();
+    model.redo().unwrap();
+    assert!(model.get_filter(0, 1, 1).unwrap().is_none());
+
+    // Cells outside of the autofilter
+    assert!(model.set_filter(0, 8, 1, None).is_err());
+    assert!(model.set_filter(0, 1, 4, None).is_err());
+}
+
+#[test]
+fn top10_and_average() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    // Prices: 3, 5, 8, 1, 10, 2
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Top10 {
+                top: true,
+                percent: false,
+                value: 2.0,
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 3, 5, 7]);
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Top10 {
+                top: false,
+                percent: true,
+                value: 50.0,
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [3, 4, 6]);
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Dynamic {
+                kind: DynamicFilter::AboveAverage,
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 7]);
+    assert!(model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Top10 {
+                top: true,
+                percent: true,
+                value: 120.0,
+            }),
+        )
+        .is_err());
+}
+
+#[test]
+fn custom_filters() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    let condition = |operator, value: &str| CustomFilter {
+        operator,
+        value: value.to_string(),
+    };
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Custom {
+                and: true,
+                filters: vec![
+                    condition(FilterOperator::GreaterThan, "2"),
+                    condition(FilterOperator::LessThanOrEqual, "8"),
+                ],
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [5, 6, 7]);
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Custom {
+                and: false,
+                filters: vec![
+                    condition(FilterOperator::LessThan, "2"),
+                    condition(FilterOperator::Equal, "10"),
+                ],
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 3, 4, 7]);
+    model.set_filter(0, 1, 2, None).unwrap();
+
+    // Wildcards, ignoring case
+    model
+        .set_filter(
+            0,
+            1,
+            1,
+            Some(Filter::Custom {
+                and: false,
+                filters: vec![condition(FilterOperator::Equal, "A*")],
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 4, 5, 6]);
+    model
+        .set_filter(
+            0,
+            1,
+            1,
+            Some(Filter::Custom {
+                and: false,
+                filters: vec![condition(FilterOperator::NotEqual, "?i?")],
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [4]);
+}
+
+#[test]
+fn dates_and_colors() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    model
+        .set_filter(
+            0,
+            1,
+            3,
+            Some(Filter::Values {
+                values: vec![],
+                date_groups: vec![DateGroupItem {
+                    grouping: DateTimeGrouping::Month,
+                    year: 2024,
+                    month: 3,
+                    day: 0,
+                    hour: 0,
+                    minute: 0,
+                    second: 0,
+                }],
+                blank: false,
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [4, 5, 7]);
+    model.set_filter(0, 1, 3, None).unwrap();
+
+    model
+        .update_range_style(&area(3, 1, 1, 2), "fill.color", "#FF0000")
+        .unwrap();
+    model
+        .update_range_style(&area(6, 1, 1, 1), "font.color", "#0000FF")
+        .unwrap();
+    model
+        .set_filter(
+            0,
+            1,
+            1,
+            Some(Filter::CellColor {
+                color: Color::Rgb("#FF0000".to_string()),
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 6, 7]);
+    model
+        .set_filter(
+            0,
+            1,
+            1,
+            Some(Filter::FontColor {
+                color: Color::Rgb("#0000FF".to_string()),
+            }),
+        )
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 3, 4, 5, 7]);
+}
+
+#[test]
+fn reapply_and_subtotal() {
+    let mut model = new_model_with_data();
+    model.set_user_input(0, 9, 2, "=SUBTOTAL(9,B2:B7)").unwrap();
+    model
+        .set_user_input(0, 10, 2, "=SUBTOTAL(109,B2:B7)")
+        .unwrap();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    model
+        .set_filter(
+            0,
+            1,
+            2,
+            Some(Filter::Custom {
+                and: false,
+                filters: vec![CustomFilter {
+                    operator: FilterOperator::GreaterThan,
+                    value: "4".to_string(),
+                }],
+            }),
+        )
+        .unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 9, 2).unwrap(), "23");
+    assert_eq!(model.get_formatted_cell_value(0, 10, 2).unwrap(), "23");
+
+    // Filters are not dynamic
+    model.set_user_input(0, 2, 2, "30").unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 7]);
+    model.reapply_filters(0).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [5, 7]);
+    assert_eq!(model.get_formatted_cell_value(0, 9, 2).unwrap(), "53");
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 7]);
+
+    // Removing the autofilter shows the filtered rows
+    model.remove_auto_filter(0).unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap(), None);
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), Vec::<i32>::new());
+    assert_eq!(model.get_formatted_cell_value(0, 9, 2).unwrap(), "56");
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 5, 7]);
+    assert!(model.get_auto_filter(0).unwrap().is_some());
+}
+
+#[test]
+fn tables() {
+    let mut model = new_model_with_data();
+    let name = model.create_table(&area(1, 1, 3, 7), true).unwrap();
+    assert!(model.set_auto_filter(&area(1, 2, 3, 7)).is_err());
+    model
+        .set_filter(0, 3, 1, Some(values_filter(&["apple"])))
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 4, 5, 6]);
+    let table = model.get_tables(0).unwrap().remove(0);
+    assert_eq!(table.name, name);
+    assert!(table.has_filters);
+    assert!(table.columns[0].filter.is_some());
+    assert!(model.get_filter(0, 1, 1).unwrap().is_some());
+
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), Vec::<i32>::new());
+    let table = model.get_tables(0).unwrap().remove(0);
+    assert!(!table.has_filters);
+    assert!(table.columns[0].filter.is_none());
+}
+
+#[test]
+fn insert_and_delete() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    model
+        .set_filter(0, 1, 3, Some(values_filter(&["x"])))
+        .unwrap();
+    model.insert_columns(0, 2, 1).unwrap();
+    model.insert_rows(0, 3, 2).unwrap();
+    let auto_filter = model.get_auto_filter(0).unwrap().unwrap();
+    assert_eq!(auto_filter.range, "A1:D9");
+    assert_eq!(auto_filter.columns[0].column, 3);
+
+    model.delete_columns(0, 4, 1).unwrap();
+    let auto_filter = model.get_auto_filter(0).unwrap().unwrap();
+    assert_eq!(auto_filter.range, "A1:C9");
+    assert!(auto_filter.columns.is_empty());
+    model.undo().unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap().unwrap().columns.len(), 1);
+
+    model.delete_rows(0, 1, 9).unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap(), None);
+    model.undo().unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap().unwrap().range, "A1:D9");
+}
+
+#[test]
+fn protected_sheet() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert!(model
+        .set_filter(0, 1, 1, Some(values_filter(&["fig"])))
+        .is_err());
+    assert!(model.remove_auto_filter(0).is_err());
+    model.unprotect_sheet(0, "").unwrap();
+    model
+        .protect_sheet(
+            0,
+            SheetProtection {
+                auto_filter: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    model
+        .set_filter(0, 1, 1, Some(values_filter(&["fig"])))
+        .unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), [2, 3, 5, 6, 7]);
+}
+
+#[test]
+fn undo_delete_sheet_with_filter() {
+    let mut model = new_model_with_data();
+    model.set_auto_filter(&area(1, 1, 3, 7)).unwrap();
+    model
+        .set_filter(0, 1, 1, Some(values_filter(&["APPLE", "fig"])))
+        .unwrap();
+    model
+        .sort_range(
+            &area(1, 1, 3, 7),
+            true,
+            false,
+            &[SortKey {
+                index: 2,
+                descending: false,
+                sort_on: SortOn::Value,
+            }],
+        )
+        .unwrap();
+    let sort_state = model.get_sort_state(0).unwrap();
+    assert!(sort_state.is_some());
+    let hidden = hidden_rows(model.get_model(), 1..=7);
+    assert!(!hidden.is_empty());
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert_eq!(model.get_auto_filter(0), Ok(None));
+    assert_eq!(model.get_sort_state(0), Ok(None));
+
+    model.undo().unwrap();
+    assert_eq!(model.get_auto_filter(0).unwrap().unwrap().range, "A1:C7");
+    assert_eq!(
+        model.get_filter(0, 1, 1),
+        Ok(Some(values_filter(&["APPLE", "fig"])))
+    );
+    assert_eq!(model.get_sort_state(0), Ok(sort_state));
+    assert_eq!(hidden_rows(model.get_model(), 1..=7), hidden);
+}
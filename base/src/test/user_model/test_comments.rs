@@ -0,0 +1,181 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::UserModel;
+
+fn comment_cells(model: &UserModel) -> Vec<String> {
+    model
+        .get_comments(0)
+        .unwrap()
+        .iter()
+        .map(|comment| comment.cell_ref.clone())
+        .collect()
+}
+
+fn comment_text(model: &UserModel, row: i32, column: i32) -> Option<String> {
+    model
+        .get_comment(0, row, column)
+        .unwrap()
+        .map(|comment| comment.text)
+}
+
+#[test]
+fn set_and_delete_comment() {
+    let mut model = new_empty_user_model();
+    model.set_comment(0, 2, 3, "Check this", "Ann").unwrap();
+    let comment = model.get_comment(0, 2, 3).unwrap().unwrap();
+    assert_eq!(comment.cell_ref, "C2");
+    assert_eq!(comment.author_name, "Ann");
+    assert_eq!(comment.thread, None);
+
+    // Editing keeps the author
+    model.set_comment(0, 2, 3, "Checked", "Bob").unwrap();
+    let comment = model.get_comment(0, 2, 3).unwrap().unwrap();
+    assert_eq!(comment.text, "Checked");
+    assert_eq!(comment.author_name, "Ann");
+
+    model.delete_comment(0, 2, 3).unwrap();
+    assert_eq!(model.get_comment(0, 2, 3), Ok(None));
+
+    model.undo().unwrap();
+    assert_eq!(comment_text(&model, 2, 3), Some("Checked".to_string()));
+    model.undo().unwrap();
+    assert_eq!(comment_text(&model, 2, 3), Some("Check this".to_string()));
+    model.undo().unwrap();
+    assert_eq!(model.get_comment(0, 2, 3), Ok(None));
+    model.redo().unwrap();
+    model.redo().unwrap();
+    model.redo().unwrap();
+    assert_eq!(model.get_comment(0, 2, 3), Ok(None));
+}
+
+#[test]
+fn threaded_comments() {
+    let mut model = new_empty_user_model();
+    model
+        .add_threaded_comment(0, 1, 1, "Is this right?", "Ann")
+        .unwrap();
+    model.add_comment_reply(0, 1, 1, "Yes", "Bob").unwrap();
+    model.add_comment_reply(0, 1, 1, "Thanks", "Ann").unwrap();
+    model.set_comment_thread_done(0, 1, 1, true).unwrap();
+
+    let comment = model.get_comment(0, 1, 1).unwrap().unwrap();
+    let thread = comment.thread.unwrap();
+    assert!(thread.done);
+    assert_eq!(thread.date, "2022-11-08T11:13:28.578");
+    let replies: Vec<(&str, &str)> = thread
+        .replies
+        .iter()
+        .map(|reply| (reply.text.as_str(), reply.author_name.as_str()))
+        .collect();
+    assert_eq!(replies, vec![("Yes", "Bob"), ("Thanks", "Ann")]);
+    // Ann has the same id in the comment and in her reply
+    assert_eq!(comment.author_id, thread.replies[1].author_id);
+    assert_ne!(comment.author_id, thread.replies[0].author_id);
+    let mut ids = vec![
+        comment.author_id.clone().unwrap(),
+        thread.id.clone(),
+        thread.replies[0].id.clone(),
+        thread.replies[0].author_id.clone().unwrap(),
+        thread.replies[1].id.clone(),
+    ];
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 5);
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    let thread = model.get_comment(0, 1, 1).unwrap().unwrap().thread.unwrap();
+    assert!(!thread.done);
+    assert_eq!(thread.replies.len(), 1);
+    model.redo().unwrap();
+    let thread = model.get_comment(0, 1, 1).unwrap().unwrap().thread.unwrap();
+    assert_eq!(thread.replies.len(), 2);
+}
+
+#[test]
+fn comment_errors() {
+    let mut model = new_empty_user_model();
+    model.set_comment(0, 1, 1, "A note", "Ann").unwrap();
+    assert_eq!(
+        model.add_threaded_comment(0, 1, 1, "Hi", "Ann"),
+        Err("The cell already has a comment".to_string())
+    );
+    assert_eq!(
+        model.add_comment_reply(0, 1, 1, "Hi", "Ann"),
+        Err("Only threaded comments have replies".to_string())
+    );
+    assert_eq!(
+        model.add_comment_reply(0, 2, 1, "Hi", "Ann"),
+        Err("The cell has no comment".to_string())
+    );
+    assert_eq!(
+        model.set_comment_thread_done(0, 1, 1, true),
+        Err("The comment is not a threaded comment".to_string())
+    );
+}
+
+#[test]
+fn comments_follow_their_cells() {
+    let mut model = new_empty_user_model();
+    model.set_comment(0, 3, 3, "C3", "Ann").unwrap();
+    model.set_comment(0, 5, 2, "B5", "Ann").unwrap();
+
+    model.insert_rows(0, 4, 2).unwrap();
+    assert_eq!(comment_cells(&model), vec!["C3", "B7"]);
+    model.insert_columns(0, 1, 1).unwrap();
+    assert_eq!(comment_cells(&model), vec!["D3", "C7"]);
+
+    model.move_rows_action(0, 7, 1, -6).unwrap();
+    assert_eq!(comment_cells(&model), vec!["D4", "C1"]);
+    model.move_columns_action(0, 4, 1, 2).unwrap();
+    assert_eq!(comment_cells(&model), vec!["F4", "C1"]);
+
+    for _ in 0..4 {
+        model.undo().unwrap();
+    }
+    assert_eq!(comment_cells(&model), vec!["C3", "B5"]);
+}
+
+#[test]
+fn delete_rows_and_columns_with_comments() {
+    let mut model = new_empty_user_model();
+    model.set_comment(0, 3, 3, "C3", "Ann").unwrap();
+    model.set_comment(0, 5, 2, "B5", "Ann").unwrap();
+
+    model.delete_rows(0, 2, 2).unwrap();
+    assert_eq!(comment_cells(&model), vec!["B3"]);
+    model.delete_columns(0, 1, 1).unwrap();
+    assert_eq!(comment_cells(&model), vec!["A3"]);
+
+    model.undo().unwrap();
+    assert_eq!(comment_cells(&model), vec!["B3"]);
+    model.undo().unwrap();
+    assert_eq!(comment_text(&model, 3, 3), Some("C3".to_string()));
+    assert_eq!(comment_text(&model, 5, 2), Some("B5".to_string()));
+
+    model.redo().unwrap();
+    assert_eq!(comment_cells(&model), vec!["B3"]);
+}
+
+#[test]
+fn undo_delete_sheet_with_comments() {
+    let mut model = new_empty_user_model();
+    model.set_comment(0, 2, 3, "Check this", "Ann").unwrap();
+    model
+        .add_threaded_comment(0, 4, 1, "Is this right?", "Ann")
+        .unwrap();
+    model.add_comment_reply(0, 4, 1, "Yes", "Bob").unwrap();
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert_eq!(comment_cells(&model), Vec::<String>::new());
+
+    model.undo().unwrap();
+    assert_eq!(comment_cells(&model), vec!["C2", "A4"]);
+    let thread = model.get_comment(0, 4, 1).unwrap().unwrap().thread.unwrap();
+    assert_eq!(thread.replies.len(), 1);
+
+    model.redo().unwrap();
+    assert_eq!(comment_cells(&model), Vec::<String>::new());
+}
@@ -1063,6 +1063,7 @@ fn multi_area_formula_anchor_is_min_row_min_col() {
     let format = Dxf {
         fill: Some(Fill {
             color: Color::Rgb("#FF0000".to_string()),
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -0,0 +1,350 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::cf_types::ValueOperator;
+use crate::dv_types::{
+    DataValidation, DataValidationErrorStyle, DataValidationListSource, DataValidationRule,
+};
+use crate::expressions::types::{Area, CellReferenceIndex};
+use crate::test::user_model::util::new_empty_user_model;
+use crate::UserModel;
+
+fn data_validation(range: &str, rule: DataValidationRule) -> DataValidation {
+    DataValidation {
+        range: range.to_string(),
+        rule,
+        allow_blank: true,
+        error_style: DataValidationErrorStyle::Stop,
+        show_input_message: false,
+        prompt_title: None,
+        prompt: None,
+        show_error_message: true,
+        error_title: None,
+        error: Some("Not allowed".to_string()),
+    }
+}
+
+fn whole_between(range: &str, min: &str, max: &str) -> DataValidation {
+    data_validation(
+        range,
+        DataValidationRule::WholeNumber {
+            operator: ValueOperator::Between,
+            formula1: min.to_string(),
+            formula2: Some(max.to_string()),
+        },
+    )
+}
+
+fn ranges(model: &UserModel) -> Vec<String> {
+    model
+        .get_data_validations(0)
+        .unwrap()
+        .into_iter()
+        .map(|data_validation| data_validation.range)
+        .collect()
+}
+
+#[test]
+fn stop_rejects_invalid_input() {
+    let mut model = new_empty_user_model();
+    model
+        .add_data_validation(0, whole_between("B2:B10", "1", "10"))
+        .unwrap();
+
+    model.set_user_input(0, 3, 2, "7").unwrap();
+    assert_eq!(
+        model.set_user_input(0, 4, 2, "11"),
+        Err("Not allowed".to_string())
+    );
+    assert_eq!(
+        model.set_user_input(0, 4, 2, "2.5"),
+        Err("Not allowed".to_string())
+    );
+    assert_eq!(
+        model.set_user_input(0, 4, 2, "hello"),
+        Err("Not allowed".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 4, 2), Ok("".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 3, 2), Ok("7".to_string()));
+
+    // Clearing a cell and formulas are always accepted
+    model.set_user_input(0, 3, 2, "").unwrap();
+    model.set_user_input(0, 4, 2, "=20").unwrap();
+
+    // Outside of the range anything goes
+    model.set_user_input(0, 4, 3, "11").unwrap();
+}
+
+#[test]
+fn warning_and_information_accept_input() {
+    let mut model = new_empty_user_model();
+    let mut rule = data_validation(
+        "A1:A5",
+        DataValidationRule::TextLength {
+            operator: ValueOperator::LessThanOrEqual,
+            formula1: "3".to_string(),
+            formula2: None,
+        },
+    );
+    rule.error_style = DataValidationErrorStyle::Warning;
+    rule.error = None;
+    model.add_data_validation(0, rule).unwrap();
+
+    assert_eq!(model.validate_user_input(0, 1, 1, "abc"), Ok(None));
+    let alert = model.validate_user_input(0, 1, 1, "abcd").unwrap().unwrap();
+    assert_eq!(alert.style, DataValidationErrorStyle::Warning);
+    assert_eq!(
+        alert.message,
+        "The value doesn't match the data validation restrictions defined for this cell."
+    );
+    model.set_user_input(0, 1, 1, "abcd").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("abcd".to_string())
+    );
+    // Checking did not leave new strings behind
+    assert!(model
+        .validate_user_input(0, 2, 1, "fghij")
+        .unwrap()
+        .is_some());
+    assert!(!model
+        .get_model()
+        .workbook
+        .shared_strings
+        .contains(&"fghij".to_string()));
+}
+
+#[test]
+fn list_rules() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 6, "Red").unwrap();
+    model.set_user_input(0, 2, 6, "Green").unwrap();
+    model.set_user_input(0, 3, 6, "Blue").unwrap();
+    model
+        .add_data_validation(
+            0,
+            data_validation(
+                "A1:A10",
+                DataValidationRule::List {
+                    source: DataValidationListSource::Formula("$F$1:$F$3".to_string()),
+                    show_dropdown: true,
+                },
+            ),
+        )
+        .unwrap();
+    model
+        .add_data_validation(
+            0,
+            data_validation(
+                "B1:B10",
+                DataValidationRule::List {
+                    source: DataValidationListSource::Values(vec![
+                        "Yes".to_string(),
+                        "No".to_string(),
+                    ]),
+                    show_dropdown: true,
+                },
+            ),
+        )
+        .unwrap();
+
+    assert_eq!(
+        model.get_data_validation_list(0, 4, 1),
+        Ok(vec![
+            "Red".to_string(),
+            "Green".to_string(),
+            "Blue".to_string()
+        ])
+    );
+    assert_eq!(
+        model.get_data_validation_list(0, 4, 2),
+        Ok(vec!["Yes".to_string(), "No".to_string()])
+    );
+    assert_eq!(model.get_data_validation_list(0, 4, 3), Ok(vec![]));
+
+    model.set_user_input(0, 1, 1, "green").unwrap();
+    assert!(model.set_user_input(0, 2, 1, "Purple").is_err());
+    model.set_user_input(0, 1, 2, "No").unwrap();
+    assert!(model.set_user_input(0, 2, 2, "Maybe").is_err());
+}
+
+#[test]
+fn custom_formula_and_invalid_cells() {
+    let mut model = new_empty_user_model();
+    // Values that are unique in the column
+    model
+        .add_data_validation(
+            0,
+            data_validation(
+                "C2:C10",
+                DataValidationRule::Custom {
+                    formula: "=COUNTIF($C$2:$C$10,C2)=1".to_string(),
+                },
+            ),
+        )
+        .unwrap();
+    model.set_user_input(0, 2, 3, "10").unwrap();
+    model.set_user_input(0, 3, 3, "20").unwrap();
+    assert!(model.set_user_input(0, 4, 3, "10").is_err());
+    assert_eq!(model.get_invalid_cells(0), Ok(vec![]));
+
+    // Values typed before the rule are not checked, but they show as invalid
+    model
+        .add_data_validation(0, whole_between("D1:D5", "$E$1", "$E$2"))
+        .unwrap();
+    model.set_user_input(0, 1, 5, "5").unwrap();
+    model.set_user_input(0, 2, 5, "8").unwrap();
+    model.set_user_input(0, 1, 4, "6").unwrap();
+    model.set_user_input(0, 2, 4, "7").unwrap();
+    model.set_user_input(0, 2, 5, "6").unwrap();
+    assert_eq!(model.get_invalid_cells(0), Ok(vec![(2, 4)]));
+}
+
+#[test]
+fn add_remove_and_undo() {
+    let mut model = new_empty_user_model();
+    model
+        .add_data_validation(0, whole_between("B2:D4", "1", "10"))
+        .unwrap();
+    // The new rule replaces the old one in C3:C4
+    model
+        .add_data_validation(0, whole_between("C3:C4", "100", "200"))
+        .unwrap();
+    assert_eq!(ranges(&model), vec!["B2:D2 B3:B4 D3:D4", "C3:C4"]);
+    assert!(model.set_user_input(0, 3, 3, "5").is_err());
+    model.set_user_input(0, 3, 3, "150").unwrap();
+
+    model
+        .remove_data_validations(&Area {
+            sheet: 0,
+            row: 1,
+            column: 1,
+            width: 10,
+            height: 2,
+        })
+        .unwrap();
+    assert_eq!(ranges(&model), vec!["B3:B4 D3:D4", "C3:C4"]);
+    assert_eq!(model.get_data_validation(0, 2, 2), Ok(None));
+
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), vec!["B2:D2 B3:B4 D3:D4", "C3:C4"]);
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), vec!["B2:D4"]);
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), Vec::<String>::new());
+    model.redo().unwrap();
+    assert_eq!(ranges(&model), vec!["B2:D4"]);
+}
+
+#[test]
+fn rows_and_columns_displace_rules() {
+    let mut model = new_empty_user_model();
+    model
+        .add_data_validation(0, whole_between("B2:B5", "$E$1", "E2"))
+        .unwrap();
+    model.insert_rows(0, 1, 2).unwrap();
+    let rule = model.get_data_validation(0, 4, 2).unwrap().unwrap();
+    assert_eq!(rule.range, "B4:B7");
+    assert_eq!(
+        rule.rule,
+        DataValidationRule::WholeNumber {
+            operator: ValueOperator::Between,
+            formula1: "$E$3".to_string(),
+            formula2: Some("E4".to_string()),
+        }
+    );
+
+    model.insert_columns(0, 1, 1).unwrap();
+    assert_eq!(ranges(&model), vec!["C4:C7"]);
+
+    model.delete_columns(0, 3, 1).unwrap();
+    assert_eq!(ranges(&model), Vec::<String>::new());
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), vec!["C4:C7"]);
+
+    // Deleting the first rows moves the anchor of the formulas
+    model.delete_rows(0, 4, 1).unwrap();
+    let rule = model.get_data_validation(0, 4, 3).unwrap().unwrap();
+    assert_eq!(rule.range, "C4:C6");
+    assert_eq!(
+        rule.rule,
+        DataValidationRule::WholeNumber {
+            operator: ValueOperator::Between,
+            formula1: "$F$3".to_string(),
+            formula2: Some("F4".to_string()),
+        }
+    );
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), vec!["C4:C7"]);
+}
+
+#[test]
+fn rejected_input_leaves_no_trace() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 1, 2, "=A1*2").unwrap();
+    model
+        .add_data_validation(
+            0,
+            data_validation(
+                "A1",
+                DataValidationRule::Custom {
+                    formula: "=AND(C1<50,TODAY()>0)".to_string(),
+                },
+            ),
+        )
+        .unwrap();
+    model.pause_evaluation();
+    model.set_user_input(0, 1, 3, "=A1*10").unwrap();
+    // The rule sees C1 computed with the proposed value
+    assert_eq!(
+        model.set_user_input(0, 1, 1, "100"),
+        Err("Not allowed".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 3),
+        Ok("#ERROR!".to_string())
+    );
+
+    // Nothing was recorded for A1 in the dependency graph
+    let a1 = CellReferenceIndex {
+        sheet: 0,
+        row: 1,
+        column: 1,
+    };
+    let graph = &model.model.dependency_graph;
+    assert!(graph.precedents(&a1).is_empty());
+    assert!(graph.volatile_cells().is_empty());
+
+    model.resume_evaluation();
+    model.model.evaluate_incremental();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 3),
+        Ok("10".to_string())
+    );
+    model.set_user_input(0, 1, 1, "3").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2), Ok("6".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 3),
+        Ok("30".to_string())
+    );
+}
+
+#[test]
+fn undo_delete_sheet_with_validations() {
+    let mut model = new_empty_user_model();
+    model
+        .add_data_validation(0, whole_between("B2:B10", "1", "10"))
+        .unwrap();
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert!(ranges(&model).is_empty());
+
+    model.undo().unwrap();
+    assert_eq!(ranges(&model), vec!["B2:B10"]);
+    assert_eq!(
+        model.set_user_input(0, 4, 2, "11"),
+        Err("Not allowed".to_string())
+    );
+}
@@ -0,0 +1,59 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::types::DateSystem;
+
+#[test]
+fn undo_redo() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=YEAR(100)").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1900".to_string())
+    );
+
+    model.set_date_system(DateSystem::Date1904);
+    assert_eq!(model.get_date_system(), DateSystem::Date1904);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1904".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_date_system(), DateSystem::Date1900);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1900".to_string())
+    );
+
+    model.redo().unwrap();
+    assert_eq!(model.get_date_system(), DateSystem::Date1904);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1904".to_string())
+    );
+}
+
+#[test]
+fn paused_evaluation() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=YEAR(100)").unwrap();
+    model.pause_evaluation();
+    model.set_date_system(DateSystem::Date1904);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1900".to_string())
+    );
+    model.undo().unwrap();
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1900".to_string())
+    );
+    model.resume_evaluation();
+    model.evaluate();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1904".to_string())
+    );
+}
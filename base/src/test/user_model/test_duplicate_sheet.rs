@@ -9,6 +9,7 @@ fn red_fill() -> Dxf {
         font: None,
         fill: Some(Fill {
             color: Color::Rgb("#FF0000".to_string()),
+            ..Default::default()
         }),
         border: None,
         num_fmt: None,
@@ -0,0 +1,180 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::expressions::types::CellReferenceIndex;
+use crate::find_types::{FindIn, FindMode, FindOptions, SearchOrder};
+use crate::test::util::new_user_model_with_data;
+use crate::types::SheetProtection;
+use crate::UserModel;
+
+fn cells(model: &UserModel, text: &str, options: &FindOptions) -> Vec<(u32, i32, i32)> {
+    model
+        .find(text, options)
+        .unwrap()
+        .map(|CellReferenceIndex { sheet, row, column }| (sheet, row, column))
+        .collect()
+}
+
+fn new_model_with_data() -> UserModel<'static> {
+    let mut model = new_user_model_with_data(&[
+        &["Apple pie", "", "apple"],
+        &["=LEN(\"apple\")"],
+        &["", "APPLE"],
+        &["12.5"],
+    ]);
+    model.new_sheet().unwrap();
+    model.set_user_input(1, 2, 2, "pineapple").unwrap();
+    model
+}
+
+#[test]
+fn find_in_sheet_and_workbook() {
+    let model = new_model_with_data();
+    let sheet = FindOptions {
+        sheet: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        cells(&model, "apple", &sheet),
+        [(0, 1, 1), (0, 1, 3), (0, 2, 1), (0, 3, 2)]
+    );
+    let by_columns = FindOptions {
+        order: SearchOrder::ByColumns,
+        ..sheet.clone()
+    };
+    assert_eq!(
+        cells(&model, "apple", &by_columns),
+        [(0, 1, 1), (0, 2, 1), (0, 3, 2), (0, 1, 3)]
+    );
+    assert_eq!(
+        cells(&model, "apple", &FindOptions::default()),
+        [(0, 1, 1), (0, 1, 3), (0, 2, 1), (0, 3, 2), (1, 2, 2)]
+    );
+    assert!(model.find("", &sheet).is_err());
+    let missing = FindOptions {
+        sheet: Some(5),
+        ..Default::default()
+    };
+    assert!(model.find("apple", &missing).is_err());
+}
+
+#[test]
+fn match_case_and_whole_cell() {
+    let model = new_model_with_data();
+    let match_case = FindOptions {
+        match_case: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        cells(&model, "apple", &match_case),
+        [(0, 1, 3), (0, 2, 1), (1, 2, 2)]
+    );
+    let whole_cell = FindOptions {
+        whole_cell: true,
+        ..Default::default()
+    };
+    assert_eq!(cells(&model, "apple", &whole_cell), [(0, 1, 3), (0, 3, 2)]);
+}
+
+#[test]
+fn wildcards_and_regex() {
+    let model = new_model_with_data();
+    let wildcard = FindOptions {
+        mode: FindMode::Wildcard,
+        whole_cell: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        cells(&model, "*apple", &wildcard),
+        [(0, 1, 3), (0, 3, 2), (1, 2, 2)]
+    );
+    assert_eq!(cells(&model, "app?e p*", &wildcard), [(0, 1, 1)]);
+    let regex = FindOptions {
+        mode: FindMode::Regex,
+        match_case: true,
+        ..Default::default()
+    };
+    assert_eq!(cells(&model, "^[A-Z]+$", &regex), [(0, 3, 2)]);
+    assert!(model.find("(apple", &regex).is_err());
+}
+
+#[test]
+fn values_and_comments() {
+    let mut model = new_model_with_data();
+    model
+        .set_comment(1, 5, 5, "Check the apples", "Ann")
+        .unwrap();
+    let values = FindOptions {
+        look_in: FindIn::Values,
+        ..Default::default()
+    };
+    // The value of the formula is 5
+    assert_eq!(cells(&model, "5", &values), [(0, 2, 1), (0, 4, 1)]);
+    let formulas = FindOptions::default();
+    assert_eq!(cells(&model, "5", &formulas), [(0, 4, 1)]);
+    let comments = FindOptions {
+        look_in: FindIn::Comments,
+        ..Default::default()
+    };
+    assert_eq!(cells(&model, "apple", &comments), [(1, 5, 5)]);
+}
+
+#[test]
+fn replace_all() {
+    let mut model = new_model_with_data();
+    let options = FindOptions {
+        sheet: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(model.replace_all("apple", "pear", &options).unwrap(), 4);
+    assert_eq!(model.get_cell_content(0, 1, 1).unwrap(), "pear pie");
+    assert_eq!(model.get_cell_content(0, 3, 2).unwrap(), "pear");
+    assert_eq!(model.get_cell_content(0, 2, 1).unwrap(), "=LEN(\"pear\")");
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "4");
+    assert_eq!(model.get_cell_content(1, 2, 2).unwrap(), "pineapple");
+
+    // A single undo restores all the cells
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1).unwrap(), "Apple pie");
+    assert_eq!(model.get_cell_content(0, 2, 1).unwrap(), "=LEN(\"apple\")");
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "5");
+    model.redo().unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 3).unwrap(), "pear");
+
+    // Groups in regular expressions
+    let regex = FindOptions {
+        mode: FindMode::Regex,
+        ..Default::default()
+    };
+    assert_eq!(
+        model.replace_all("(pine)(apple)", "$2 $1", &regex).unwrap(),
+        1
+    );
+    assert_eq!(model.get_cell_content(1, 2, 2).unwrap(), "apple pine");
+    // But not in plain text
+    assert_eq!(
+        model
+            .replace_all("pine", "$1", &FindOptions::default())
+            .unwrap(),
+        1
+    );
+    assert_eq!(model.get_cell_content(1, 2, 2).unwrap(), "apple $1");
+
+    assert_eq!(model.replace_all("banana", "kiwi", &options).unwrap(), 0);
+    let values = FindOptions {
+        look_in: FindIn::Values,
+        ..Default::default()
+    };
+    assert!(model.replace_all("pear", "kiwi", &values).is_err());
+}
+
+#[test]
+fn replace_in_protected_sheet() {
+    let mut model = new_model_with_data();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert!(model
+        .replace_all("apple", "pear", &FindOptions::default())
+        .is_err());
+    // Nothing changed, not even in the sheet that is not protected
+    assert_eq!(model.get_cell_content(1, 2, 2).unwrap(), "pineapple");
+    assert_eq!(model.get_cell_content(0, 1, 3).unwrap(), "apple");
+}
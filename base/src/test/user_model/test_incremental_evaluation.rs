@@ -0,0 +1,297 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::new_empty_model;
+use crate::types::{Cell, FormulaValue};
+use crate::UserModel;
+
+// Overwrites the value stored in a formula cell without touching the formula.
+// If a later recalculation does not recompute the cell the stale value survives.
+fn tamper_cell_value(model: &mut UserModel, row: i32, column: i32, value: f64) {
+    let cell = model.model.workbook.worksheets[0]
+        .sheet_data
+        .get_mut(&row)
+        .unwrap()
+        .get_mut(&column)
+        .unwrap();
+    match cell {
+        Cell::CellFormula { v, .. } => *v = FormulaValue::Number(value),
+        _ => panic!("Expected a formula cell"),
+    }
+}
+
+#[test]
+fn chain_of_dependents() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "2").unwrap();
+    model.set_user_input(0, 1, 2, "=A1*10").unwrap();
+    model.set_user_input(0, 1, 3, "=B1+1").unwrap();
+    model.set_user_input(0, 2, 3, "=SUM(A1:C1)").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("43".to_string())
+    );
+
+    model.set_user_input(0, 1, 1, "3").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("30".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 3),
+        Ok("31".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("64".to_string())
+    );
+}
+
+#[test]
+fn only_dependents_are_recomputed() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "2").unwrap();
+    model.set_user_input(0, 1, 2, "=A1*10").unwrap();
+    model.set_user_input(0, 5, 5, "=1+1").unwrap();
+
+    tamper_cell_value(&mut model, 1, 2, 99.0);
+    tamper_cell_value(&mut model, 5, 5, 99.0);
+
+    model.set_user_input(0, 1, 1, "3").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("30".to_string())
+    );
+    // E5 does not depend on A1
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 5),
+        Ok("99".to_string())
+    );
+
+    // A full evaluation fixes it
+    model.evaluate();
+    assert_eq!(model.get_formatted_cell_value(0, 5, 5), Ok("2".to_string()));
+}
+
+#[test]
+fn volatile_functions_are_always_recomputed() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=RAND()*0+5").unwrap();
+    model.set_user_input(0, 2, 1, "=INDIRECT(\"C1\")").unwrap();
+    model.set_user_input(0, 3, 1, "=A1+1").unwrap();
+
+    tamper_cell_value(&mut model, 1, 1, 99.0);
+    tamper_cell_value(&mut model, 3, 1, 99.0);
+
+    model.set_user_input(0, 1, 3, "7").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("5".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("7".to_string()));
+    // Dependents of volatile cells are recomputed too
+    assert_eq!(model.get_formatted_cell_value(0, 3, 1), Ok("6".to_string()));
+}
+
+#[test]
+fn new_cells_in_referenced_ranges() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=SUM(B:B)").unwrap();
+    model.set_user_input(0, 2, 1, "=COUNT(C1:C100)").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("0".to_string()));
+
+    model.set_user_input(0, 1000, 2, "4").unwrap();
+    model.set_user_input(0, 50, 3, "4").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("4".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("1".to_string()));
+}
+
+#[test]
+fn defined_names_and_other_sheets() {
+    let mut model = new_empty_user_model();
+    model.new_sheet().unwrap();
+    model.new_defined_name("Rate", None, "Sheet2!$A$1").unwrap();
+    model.set_user_input(1, 1, 1, "0.5").unwrap();
+    model.set_user_input(0, 1, 1, "=Rate*100").unwrap();
+    model.set_user_input(0, 2, 1, "=Sheet2!A2&\"!\"").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("50".to_string())
+    );
+
+    model.set_user_input(1, 1, 1, "0.25").unwrap();
+    model.set_user_input(1, 2, 1, "Hi").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("25".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 1),
+        Ok("Hi!".to_string())
+    );
+}
+
+#[test]
+fn formulas_returning_references() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 2, 1, "2").unwrap();
+    model
+        .set_user_input(0, 1, 2, "=SUM(INDEX(A1:A2,0,1))")
+        .unwrap();
+    model.set_user_input(0, 1, 3, "=@A1:A2").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2), Ok("3".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 1, 3), Ok("1".to_string()));
+
+    model.set_user_input(0, 1, 1, "10").unwrap();
+    model.set_user_input(0, 2, 1, "20").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("30".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 3),
+        Ok("10".to_string())
+    );
+}
+
+#[test]
+fn formula_replaced_by_value() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 1, 2, "=A1+1").unwrap();
+    model.set_user_input(0, 1, 3, "=B1+1").unwrap();
+
+    model.set_user_input(0, 1, 2, "7").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 3), Ok("8".to_string()));
+    // B1 no longer depends on A1
+    model.set_user_input(0, 1, 1, "100").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2), Ok("7".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 1, 3), Ok("8".to_string()));
+}
+
+#[test]
+fn circular_references() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=B1").unwrap();
+    model.set_user_input(0, 1, 2, "=A1").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+    model.set_user_input(0, 1, 2, "3").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("3".to_string()));
+}
+
+#[test]
+fn undo_redo_and_structural_changes() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 2, 1, "=A1*2").unwrap();
+    model.set_user_input(0, 1, 1, "5").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 1),
+        Ok("10".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("2".to_string()));
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 1),
+        Ok("10".to_string())
+    );
+
+    model.insert_rows(0, 1, 2).unwrap();
+    assert_eq!(model.get_cell_content(0, 4, 1), Ok("=A3*2".to_string()));
+    model.set_user_input(0, 3, 1, "6").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 4, 1),
+        Ok("12".to_string())
+    );
+
+    model
+        .range_clear_contents(&crate::expressions::types::Area {
+            sheet: 0,
+            row: 3,
+            column: 1,
+            width: 1,
+            height: 1,
+        })
+        .unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 4, 1), Ok("0".to_string()));
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 4, 1),
+        Ok("12".to_string())
+    );
+}
+
+#[test]
+fn paused_evaluation() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 1, 2, "=A1+1").unwrap();
+    model.set_user_input(0, 2, 2, "=A2+1").unwrap();
+    model.pause_evaluation();
+    model.set_user_input(0, 1, 1, "10").unwrap();
+    model.resume_evaluation();
+    model.set_user_input(0, 2, 1, "20").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("11".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 2),
+        Ok("21".to_string())
+    );
+}
+
+#[test]
+fn external_diffs() {
+    let mut model1 = UserModel::from_model(new_empty_model());
+    let mut model2 = UserModel::from_model(new_empty_model());
+    model1.set_user_input(0, 1, 1, "1").unwrap();
+    model1.set_user_input(0, 1, 2, "=A1*3").unwrap();
+    model2
+        .apply_external_diffs(&model1.flush_send_queue())
+        .unwrap();
+    assert_eq!(
+        model2.get_formatted_cell_value(0, 1, 2),
+        Ok("3".to_string())
+    );
+
+    model1.set_user_input(0, 1, 1, "4").unwrap();
+    model2
+        .apply_external_diffs(&model1.flush_send_queue())
+        .unwrap();
+    assert_eq!(
+        model2.get_formatted_cell_value(0, 1, 2),
+        Ok("12".to_string())
+    );
+}
+
+#[test]
+fn array_formulas() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 2, 1, "2").unwrap();
+    model
+        .set_user_array_formula(0, 1, 2, 1, 2, "=A1:A2*2")
+        .unwrap();
+    model.set_user_input(0, 3, 2, "=B2+1").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 3, 2), Ok("5".to_string()));
+
+    model.set_user_input(0, 2, 1, "5").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 2),
+        Ok("10".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 3, 2),
+        Ok("11".to_string())
+    );
+
+    // Dynamic arrays fall back to a full evaluation
+    model.set_user_input(0, 1, 4, "=A1:A2+1").unwrap();
+    model.set_user_input(0, 3, 4, "=D2").unwrap();
+    model.set_user_input(0, 2, 1, "7").unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 3, 4), Ok("8".to_string()));
+}
@@ -0,0 +1,119 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::types::IterativeCalculation;
+
+#[test]
+fn undo_redo() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=A1/2+1").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+
+    let settings = IterativeCalculation {
+        enabled: true,
+        max_iterations: 100,
+        max_change: 0.001,
+    };
+    model.set_iterative_calculation(settings.clone()).unwrap();
+    assert_eq!(model.get_iterative_calculation(), settings);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1.999023438".to_string())
+    );
+
+    model.undo().unwrap();
+    assert!(!model.get_iterative_calculation().enabled);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+
+    model.redo().unwrap();
+    assert_eq!(model.get_iterative_calculation(), settings);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1.999023438".to_string())
+    );
+}
+
+#[test]
+fn editing_cells_in_a_cycle() {
+    let mut model = new_empty_user_model();
+    model
+        .set_iterative_calculation(IterativeCalculation {
+            enabled: true,
+            max_iterations: 100,
+            max_change: 0.000001,
+        })
+        .unwrap();
+    model.set_user_input(0, 1, 1, "1000").unwrap();
+    model.set_user_input(0, 2, 1, "=(A1+A3)/2*10%").unwrap();
+    model.set_user_input(0, 3, 1, "=A1+A2").unwrap();
+    model.set_user_input(0, 4, 1, "=ROUND(A3,2)").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 4, 1),
+        Ok("1105.26".to_string())
+    );
+
+    model.set_user_input(0, 1, 1, "2000").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 4, 1),
+        Ok("2210.53".to_string())
+    );
+}
+
+#[test]
+fn invalid_settings_are_not_recorded() {
+    let mut model = new_empty_user_model();
+    let result = model.set_iterative_calculation(IterativeCalculation {
+        enabled: true,
+        max_iterations: -3,
+        max_change: 0.001,
+    });
+    assert!(result.is_err());
+    assert!(!model.can_undo());
+}
+
+#[test]
+fn paused_evaluation() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=A1/2+1").unwrap();
+    model.pause_evaluation();
+    let settings = IterativeCalculation {
+        enabled: true,
+        max_iterations: 100,
+        max_change: 0.001,
+    };
+    model.set_iterative_calculation(settings.clone()).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+    model.evaluate();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1.999023438".to_string())
+    );
+
+    // Neither undo nor redo evaluate the workbook while paused
+    model.undo().unwrap();
+    assert!(!model.get_iterative_calculation().enabled);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("1.999023438".to_string())
+    );
+    model.evaluate();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+    model.redo().unwrap();
+    assert_eq!(model.get_iterative_calculation(), settings);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("#CIRC!".to_string())
+    );
+}
@@ -16,6 +16,7 @@ fn red_fill() -> Dxf {
     Dxf {
         fill: Some(Fill {
             color: Color::Rgb("#FF0000".to_string()),
+            ..Default::default()
         }),
         font: None,
         border: None,
@@ -0,0 +1,203 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::area;
+use crate::UserModel;
+
+fn merge_cells(model: &UserModel) -> Vec<String> {
+    model
+        .get_model()
+        .workbook
+        .worksheet(0)
+        .unwrap()
+        .merge_cells
+        .clone()
+}
+
+#[test]
+fn merge_and_unmerge() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 2, 2, "Title").unwrap();
+    model.set_user_input(0, 2, 3, "lost").unwrap();
+    model.set_user_input(0, 3, 2, "lost too").unwrap();
+    model.set_user_input(0, 5, 5, "=C2&B3").unwrap();
+
+    model.merge_cells(&area(2, 2, 3, 2)).unwrap();
+    assert_eq!(merge_cells(&model), vec!["B2:D3"]);
+    assert_eq!(model.get_merged_cells(0), Ok(vec![area(2, 2, 3, 2)]));
+    assert_eq!(model.get_merged_area(0, 3, 4), Ok(Some(area(2, 2, 3, 2))));
+    assert_eq!(model.get_merged_area(0, 4, 4), Ok(None));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 2),
+        Ok("Title".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 2, 3), Ok("".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 3, 2), Ok("".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 5, 5), Ok("".to_string()));
+
+    model.undo().unwrap();
+    assert!(merge_cells(&model).is_empty());
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("lost".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 5),
+        Ok("lostlost too".to_string())
+    );
+
+    model.redo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B2:D3"]);
+    assert_eq!(model.get_formatted_cell_value(0, 3, 2), Ok("".to_string()));
+
+    // Any cell of the merged area unmerges it
+    model.unmerge_cells(&area(3, 4, 1, 1)).unwrap();
+    assert!(merge_cells(&model).is_empty());
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B2:D3"]);
+    model.redo().unwrap();
+    assert!(merge_cells(&model).is_empty());
+}
+
+#[test]
+fn merge_absorbs_inner_merges() {
+    let mut model = new_empty_user_model();
+    model.merge_cells(&area(2, 2, 2, 1)).unwrap();
+    model.merge_cells(&area(5, 5, 2, 2)).unwrap();
+    model.merge_cells(&area(1, 1, 4, 4)).unwrap();
+    assert_eq!(merge_cells(&model), vec!["E5:F6", "A1:D4"]);
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B2:C2", "E5:F6"]);
+}
+
+#[test]
+fn merge_errors() {
+    let mut model = new_empty_user_model();
+    assert_eq!(
+        model.merge_cells(&area(1, 1, 1, 1)),
+        Err("Cannot merge a single cell".to_string())
+    );
+    model.merge_cells(&area(2, 2, 2, 2)).unwrap();
+    assert_eq!(
+        model.merge_cells(&area(3, 3, 2, 2)),
+        Err("Cannot merge cells that overlap other merged cells".to_string())
+    );
+
+    model.set_user_input(0, 10, 1, "Name").unwrap();
+    model.set_user_input(0, 11, 1, "Ann").unwrap();
+    model.create_table(&area(10, 1, 1, 2), true).unwrap();
+    assert_eq!(
+        model.merge_cells(&area(11, 1, 2, 2)),
+        Err("Cannot merge cells in a table".to_string())
+    );
+    assert_eq!(merge_cells(&model), vec!["B2:C3"]);
+}
+
+#[test]
+fn insert_rows_and_columns() {
+    let mut model = new_empty_user_model();
+    model.merge_cells(&area(3, 3, 2, 2)).unwrap();
+
+    // before the merged area: moves it
+    model.insert_rows(0, 1, 2).unwrap();
+    assert_eq!(merge_cells(&model), vec!["C5:D6"]);
+    // inside the merged area: grows it
+    model.insert_columns(0, 4, 1).unwrap();
+    assert_eq!(merge_cells(&model), vec!["C5:E6"]);
+    // after the merged area: nothing changes
+    model.insert_rows(0, 7, 1).unwrap();
+    assert_eq!(merge_cells(&model), vec!["C5:E6"]);
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["C5:D6"]);
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["C3:D4"]);
+}
+
+#[test]
+fn delete_rows_and_columns() {
+    let mut model = new_empty_user_model();
+    model.merge_cells(&area(3, 3, 3, 3)).unwrap();
+    model.merge_cells(&area(10, 1, 2, 1)).unwrap();
+
+    // shrinks the first area and moves the second one
+    model.delete_rows(0, 4, 1).unwrap();
+    assert_eq!(merge_cells(&model), vec!["C3:E4", "A9:B9"]);
+    model.delete_columns(0, 2, 2).unwrap();
+    // A9:B9 is now a single cell and it is removed
+    assert_eq!(merge_cells(&model), vec!["B3:C4"]);
+
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["C3:E4", "A9:B9"]);
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["C3:E5", "A10:B10"]);
+    model.redo().unwrap();
+    model.redo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B3:C4"]);
+}
+
+#[test]
+fn move_rows_and_columns() {
+    let mut model = new_empty_user_model();
+    model.merge_cells(&area(2, 2, 2, 2)).unwrap();
+
+    // the merged area moves with its rows
+    model.move_rows_action(0, 2, 2, 3).unwrap();
+    assert_eq!(merge_cells(&model), vec!["B5:C6"]);
+    // and it is displaced by the moved column
+    model.move_columns_action(0, 5, 1, -4).unwrap();
+    assert_eq!(merge_cells(&model), vec!["C5:D6"]);
+
+    // moving part of a merged area is not allowed
+    assert_eq!(
+        model.move_rows_action(0, 5, 1, 2),
+        Err("Cannot move rows because that would split merged cells".to_string())
+    );
+    assert_eq!(
+        model.move_columns_action(0, 2, 2, 1),
+        Err("Cannot move columns because that would split merged cells".to_string())
+    );
+    assert_eq!(merge_cells(&model), vec!["C5:D6"]);
+
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B5:C6"]);
+    model.undo().unwrap();
+    assert_eq!(merge_cells(&model), vec!["B2:C3"]);
+}
+
+#[test]
+fn navigation() {
+    let mut model = new_empty_user_model();
+    model.merge_cells(&area(2, 2, 3, 2)).unwrap();
+
+    model.set_selected_cell(3, 3).unwrap();
+    let view = model.get_selected_view();
+    assert_eq!((view.row, view.column), (2, 2));
+    assert_eq!(view.range, [2, 2, 3, 4]);
+
+    model.on_arrow_right().unwrap();
+    let view = model.get_selected_view();
+    assert_eq!((view.row, view.column), (2, 5));
+    assert_eq!(view.range, [2, 5, 2, 5]);
+
+    model.on_arrow_left().unwrap();
+    let view = model.get_selected_view();
+    assert_eq!((view.row, view.column), (2, 2));
+    assert_eq!(view.range, [2, 2, 3, 4]);
+
+    model.on_arrow_down().unwrap();
+    let view = model.get_selected_view();
+    assert_eq!((view.row, view.column), (4, 2));
+
+    model.set_selected_cell(4, 3).unwrap();
+    model.on_arrow_up().unwrap();
+    let view = model.get_selected_view();
+    assert_eq!((view.row, view.column), (2, 2));
+    assert_eq!(view.range, [2, 2, 3, 4]);
+
+    // Selecting an area includes the whole merged block
+    model.set_selected_cell(1, 1).unwrap();
+    model.on_area_selecting(2, 3).unwrap();
+    assert_eq!(model.get_selected_view().range, [1, 1, 3, 4]);
+}
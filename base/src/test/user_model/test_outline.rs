@@ -0,0 +1,297 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::outline_types::{OutlineGroup, MAX_OUTLINE_LEVEL};
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::hidden_rows;
+use crate::types::SheetProtection;
+use crate::UserModel;
+
+fn row_groups(model: &UserModel) -> Vec<(u8, i32, i32, bool)> {
+    model
+        .get_row_outline(0)
+        .unwrap()
+        .groups
+        .iter()
+        .map(|g| (g.level, g.start, g.end, g.collapsed))
+        .collect()
+}
+
+#[test]
+fn group_and_ungroup_rows() {
+    let mut model = new_empty_user_model();
+    model.group_rows(0, 2, 8).unwrap();
+    model.group_rows(0, 3, 4).unwrap();
+    model.group_rows(0, 6, 7).unwrap();
+    let outline = model.get_row_outline(0).unwrap();
+    assert_eq!(outline.max_level, 2);
+    assert_eq!(
+        outline.groups[0],
+        OutlineGroup {
+            level: 1,
+            start: 2,
+            end: 8,
+            summary: 9,
+            collapsed: false,
+        }
+    );
+    assert_eq!(
+        row_groups(&model),
+        [(1, 2, 8, false), (2, 3, 4, false), (2, 6, 7, false)]
+    );
+
+    // Ungrouping rows in the middle splits the groups
+    model.ungroup_rows(0, 4, 6).unwrap();
+    assert_eq!(
+        row_groups(&model),
+        [
+            (1, 2, 4, false),
+            (1, 6, 8, false),
+            (2, 3, 3, false),
+            (2, 7, 7, false)
+        ]
+    );
+    model.undo().unwrap();
+    assert_eq!(row_groups(&model).len(), 3);
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(row_groups(&model), [(1, 2, 8, false)]);
+    model.redo().unwrap();
+    assert_eq!(row_groups(&model).len(), 2);
+
+    // Ungrouping rows that are not in a group does nothing
+    model.ungroup_rows(0, 20, 30).unwrap();
+    assert_eq!(row_groups(&model).len(), 2);
+}
+
+#[test]
+fn maximum_outline_level() {
+    let mut model = new_empty_user_model();
+    for _ in 0..MAX_OUTLINE_LEVEL {
+        model.group_rows(0, 5, 10).unwrap();
+    }
+    assert_eq!(model.get_row_outline(0).unwrap().max_level, 7);
+    assert!(model.group_rows(0, 1, 5).is_err());
+    // Nothing changed
+    assert_eq!(model.get_row_outline(0).unwrap().groups[0].start, 5);
+    model.group_rows(0, 1, 4).unwrap();
+    assert!(model.group_rows(0, 0, 4).is_err());
+    assert!(model.group_rows(0, 4, 1).is_err());
+}
+
+#[test]
+fn collapse_and_expand_rows() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 2, 1, "1").unwrap();
+    model.set_user_input(0, 3, 1, "2").unwrap();
+    model
+        .set_user_input(0, 9, 1, "=SUBTOTAL(103, A2:A8)")
+        .unwrap();
+    model.group_rows(0, 2, 8).unwrap();
+    model.group_rows(0, 3, 4).unwrap();
+
+    // The nested group first
+    model.set_row_group_collapsed(0, 3, 2, true).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4]);
+    assert!(model.get_row_outline(0).unwrap().groups[1].collapsed);
+    assert_eq!(model.get_formatted_cell_value(0, 9, 1).unwrap(), "1");
+
+    model.set_row_group_collapsed(0, 6, 1, true).unwrap();
+    assert_eq!(
+        hidden_rows(model.get_model(), 1..=10),
+        [2, 3, 4, 5, 6, 7, 8]
+    );
+    assert_eq!(row_groups(&model), [(1, 2, 8, true), (2, 3, 4, true)]);
+
+    // Expanding the outer group keeps the nested group collapsed
+    model.set_row_group_collapsed(0, 2, 1, false).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4]);
+
+    model.undo().unwrap();
+    assert_eq!(
+        hidden_rows(model.get_model(), 1..=10),
+        [2, 3, 4, 5, 6, 7, 8]
+    );
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4]);
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), Vec::<i32>::new());
+    assert_eq!(model.get_formatted_cell_value(0, 9, 1).unwrap(), "2");
+    assert_eq!(row_groups(&model), [(1, 2, 8, false), (2, 3, 4, false)]);
+
+    assert!(model.set_row_group_collapsed(0, 2, 2, true).is_err());
+    assert!(model.set_row_group_collapsed(0, 20, 1, true).is_err());
+}
+
+#[test]
+fn summary_rows_above() {
+    let mut model = new_empty_user_model();
+    model.model.workbook.worksheets[0]
+        .outline_properties
+        .summary_below = false;
+    model.group_rows(0, 1, 3).unwrap();
+    model.group_rows(0, 6, 7).unwrap();
+    let outline = model.get_row_outline(0).unwrap();
+    assert_eq!(outline.groups[0].summary, 0);
+    assert_eq!(outline.groups[1].summary, 5);
+
+    model.set_row_group_collapsed(0, 7, 1, true).unwrap();
+    assert!(model.model.workbook.worksheets[0]
+        .is_row_collapsed(5)
+        .unwrap());
+    // A group without summary row is collapsed if its rows are hidden
+    model.set_row_group_collapsed(0, 1, 1, true).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=8), [1, 2, 3, 6, 7]);
+    assert_eq!(row_groups(&model), [(1, 1, 3, true), (1, 6, 7, true)]);
+    model.set_row_group_collapsed(0, 1, 1, false).unwrap();
+    assert_eq!(row_groups(&model), [(1, 1, 3, false), (1, 6, 7, true)]);
+}
+
+#[test]
+fn show_outline_levels() {
+    let mut model = new_empty_user_model();
+    model.group_rows(0, 2, 8).unwrap();
+    model.group_rows(0, 3, 4).unwrap();
+    model.group_rows(0, 6, 7).unwrap();
+
+    model.show_row_outline_level(0, 2).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4, 6, 7]);
+    assert_eq!(
+        row_groups(&model),
+        [(1, 2, 8, false), (2, 3, 4, true), (2, 6, 7, true)]
+    );
+    model.show_row_outline_level(0, 1).unwrap();
+    assert_eq!(
+        hidden_rows(model.get_model(), 1..=10),
+        [2, 3, 4, 5, 6, 7, 8]
+    );
+
+    // Expanding the outer group keeps the nested ones collapsed
+    model.set_row_group_collapsed(0, 2, 1, false).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4, 6, 7]);
+
+    model.show_row_outline_level(0, 3).unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), Vec::<i32>::new());
+    model.undo().unwrap();
+    assert_eq!(hidden_rows(model.get_model(), 1..=10), [3, 4, 6, 7]);
+
+    assert!(model.show_row_outline_level(0, 0).is_err());
+    assert!(model.show_row_outline_level(0, 9).is_err());
+}
+
+#[test]
+fn group_and_collapse_columns() {
+    let mut model = new_empty_user_model();
+    model.set_columns_width(0, 2, 6, 120.0).unwrap();
+    model.group_columns(0, 2, 5).unwrap();
+    model.group_columns(0, 3, 3).unwrap();
+    let outline = model.get_column_outline(0).unwrap();
+    assert_eq!(outline.max_level, 2);
+    assert_eq!(outline.groups[0].summary, 6);
+    assert!(model.get_row_outline(0).unwrap().groups.is_empty());
+
+    model.set_column_group_collapsed(0, 4, 1, true).unwrap();
+    for column in 2..=5 {
+        assert!(model.model.is_column_hidden(0, column).unwrap());
+    }
+    assert!(!model.model.is_column_hidden(0, 6).unwrap());
+    assert!(model.get_column_outline(0).unwrap().groups[0].collapsed);
+
+    model.set_column_group_collapsed(0, 4, 1, false).unwrap();
+    assert!(!model.model.is_column_hidden(0, 3).unwrap());
+    // The width is kept
+    assert_eq!(model.get_column_width(0, 3).unwrap(), 120.0);
+
+    model.undo().unwrap();
+    assert!(model.model.is_column_hidden(0, 3).unwrap());
+    model.undo().unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert!(!model.model.is_column_hidden(0, 3).unwrap());
+    assert!(model.get_column_outline(0).unwrap().groups.is_empty());
+    assert_eq!(model.get_column_width(0, 3).unwrap(), 120.0);
+}
+
+#[test]
+fn insert_and_delete_rows() {
+    let mut model = new_empty_user_model();
+    model.group_rows(0, 3, 6).unwrap();
+
+    // New rows inside a group are added to it
+    model.insert_rows(0, 4, 2).unwrap();
+    assert_eq!(row_groups(&model), [(1, 3, 8, false)]);
+    // But not the ones at its edges
+    model.insert_rows(0, 3, 1).unwrap();
+    model.insert_rows(0, 10, 1).unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 9, false)]);
+
+    model.delete_rows(0, 5, 2).unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 7, false)]);
+    model.undo().unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 9, false)]);
+    model.redo().unwrap();
+
+    // Deleting a row between two groups merges them, but not when undoing it
+    model.group_rows(0, 9, 10).unwrap();
+    model.delete_rows(0, 8, 1).unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 9, false)]);
+    model.undo().unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 7, false), (1, 9, 10, false)]);
+    model.undo().unwrap();
+
+    model.delete_rows(0, 4, 4).unwrap();
+    assert!(row_groups(&model).is_empty());
+    model.undo().unwrap();
+    assert_eq!(row_groups(&model), [(1, 4, 7, false)]);
+}
+
+#[test]
+fn insert_and_delete_columns() {
+    let mut model = new_empty_user_model();
+    model.group_columns(0, 3, 5).unwrap();
+    model.insert_columns(0, 4, 1).unwrap();
+    model.insert_columns(0, 3, 1).unwrap();
+    let groups = model.get_column_outline(0).unwrap().groups;
+    assert_eq!((groups[0].start, groups[0].end), (4, 7));
+
+    model.delete_columns(0, 3, 2).unwrap();
+    let groups = model.get_column_outline(0).unwrap().groups;
+    assert_eq!((groups[0].start, groups[0].end), (3, 5));
+    model.undo().unwrap();
+    let groups = model.get_column_outline(0).unwrap().groups;
+    assert_eq!((groups[0].start, groups[0].end), (4, 7));
+}
+
+#[test]
+fn outline_in_protected_sheet() {
+    let mut model = new_empty_user_model();
+    model.group_rows(0, 2, 3).unwrap();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert!(model.group_rows(0, 5, 6).is_err());
+    assert!(model.group_columns(0, 5, 6).is_err());
+    assert!(model.set_row_group_collapsed(0, 2, 1, true).is_err());
+    assert_eq!(row_groups(&model), [(1, 2, 3, false)]);
+}
+
+#[test]
+fn undo_delete_sheet_with_outline() {
+    let mut model = new_empty_user_model();
+    model.model.workbook.worksheets[0]
+        .outline_properties
+        .summary_below = false;
+    model.group_rows(0, 6, 7).unwrap();
+    model.set_row_group_collapsed(0, 7, 1, true).unwrap();
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert_eq!(row_groups(&model), []);
+
+    model.undo().unwrap();
+    assert!(
+        !model.model.workbook.worksheets[0]
+            .outline_properties
+            .summary_below
+    );
+    assert_eq!(model.get_row_outline(0).unwrap().groups[0].summary, 5);
+    assert_eq!(row_groups(&model), [(1, 6, 7, true)]);
+    assert_eq!(hidden_rows(model.get_model(), 1..=8), [6, 7]);
+}
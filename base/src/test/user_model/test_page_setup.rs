@@ -0,0 +1,157 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::expressions::types::Area;
+use crate::page_setup_types::{PageOrientation, PageSetup};
+use crate::test::user_model::util::new_empty_user_model;
+
+#[test]
+fn set_page_setup_and_undo() {
+    let mut model = new_empty_user_model();
+    assert_eq!(model.get_page_setup(0).unwrap(), PageSetup::default());
+
+    let mut page_setup = model.get_page_setup(0).unwrap();
+    page_setup.orientation = PageOrientation::Landscape;
+    page_setup.paper_size = 9;
+    page_setup.margins.left = 0.25;
+    page_setup.header_footer.odd_footer = "&CPage &P of &N".to_string();
+    page_setup.row_breaks = vec![40, 20, 40];
+    model.set_page_setup(0, page_setup).unwrap();
+
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.orientation, PageOrientation::Landscape);
+    assert_eq!(page_setup.margins.left, 0.25);
+    // The page breaks are sorted
+    assert_eq!(page_setup.row_breaks, [20, 40]);
+
+    model.undo().unwrap();
+    assert_eq!(model.get_page_setup(0).unwrap(), PageSetup::default());
+    model.redo().unwrap();
+    assert_eq!(model.get_page_setup(0).unwrap(), page_setup);
+}
+
+#[test]
+fn invalid_page_setup() {
+    let mut model = new_empty_user_model();
+    let page_setup = model.get_page_setup(0).unwrap();
+
+    let mut invalid = page_setup.clone();
+    invalid.scale = 5;
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    let mut invalid = page_setup.clone();
+    invalid.margins.top = -1.0;
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    let mut invalid = page_setup.clone();
+    invalid.header_footer.odd_header = "x".repeat(256);
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    let mut invalid = page_setup.clone();
+    invalid.print_area = vec!["A1:".to_string()];
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    let mut invalid = page_setup.clone();
+    invalid.print_title_rows = Some((3, 2));
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    let mut invalid = page_setup;
+    invalid.column_breaks = vec![0];
+    assert!(model.set_page_setup(0, invalid).is_err());
+
+    assert!(model.set_page_setup(5, PageSetup::default()).is_err());
+    assert_eq!(model.get_page_setup(0).unwrap(), PageSetup::default());
+}
+
+#[test]
+fn print_area_and_page_breaks() {
+    let mut model = new_empty_user_model();
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 6,
+        height: 40,
+    };
+    model.set_print_area(0, Some(&area)).unwrap();
+    model.set_row_page_break(0, 20, true).unwrap();
+    model.set_column_page_break(0, 3, true).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["A1:F40"]);
+    assert_eq!(page_setup.row_breaks, [20]);
+    assert_eq!(page_setup.column_breaks, [3]);
+
+    model.set_row_page_break(0, 20, false).unwrap();
+    assert!(model.get_page_setup(0).unwrap().row_breaks.is_empty());
+    model.set_print_area(0, None).unwrap();
+    assert!(model.get_page_setup(0).unwrap().print_area.is_empty());
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["A1:F40"]);
+    assert_eq!(page_setup.row_breaks, [20]);
+}
+
+#[test]
+fn insert_and_delete_rows_and_columns() {
+    let mut model = new_empty_user_model();
+    let mut page_setup = model.get_page_setup(0).unwrap();
+    page_setup.print_area = vec!["B2:F40".to_string()];
+    page_setup.print_title_rows = Some((1, 2));
+    page_setup.print_title_columns = Some((1, 1));
+    page_setup.row_breaks = vec![10, 20];
+    page_setup.column_breaks = vec![4];
+    model.set_page_setup(0, page_setup).unwrap();
+
+    model.insert_rows(0, 2, 3).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["B5:F43"]);
+    assert_eq!(page_setup.print_title_rows, Some((1, 5)));
+    assert_eq!(page_setup.row_breaks, [13, 23]);
+
+    model.insert_columns(0, 1, 1).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["C5:G43"]);
+    assert_eq!(page_setup.print_title_columns, Some((2, 2)));
+    assert_eq!(page_setup.column_breaks, [5]);
+
+    // Deleting the row of a page break removes it
+    model.delete_rows(0, 12, 2).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["C5:G41"]);
+    assert_eq!(page_setup.row_breaks, [21]);
+
+    model.undo().unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_area, ["C5:G43"]);
+    assert_eq!(page_setup.row_breaks, [13, 23]);
+
+    // Deleting the print title columns removes them
+    model.delete_columns(0, 1, 2).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_title_columns, None);
+    assert_eq!(page_setup.print_area, ["A5:E43"]);
+    model.undo().unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    assert_eq!(page_setup.print_title_columns, Some((2, 2)));
+    assert_eq!(page_setup.print_area, ["C5:G43"]);
+}
+
+#[test]
+fn undo_delete_sheet_with_page_setup() {
+    let mut model = new_empty_user_model();
+    let mut page_setup = model.get_page_setup(0).unwrap();
+    page_setup.orientation = PageOrientation::Landscape;
+    page_setup.margins.left = 0.25;
+    page_setup.print_area = vec!["A1:F40".to_string()];
+    page_setup.row_breaks = vec![20];
+    model.set_page_setup(0, page_setup).unwrap();
+    let page_setup = model.get_page_setup(0).unwrap();
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert_eq!(model.get_page_setup(0).unwrap(), PageSetup::default());
+
+    model.undo().unwrap();
+    assert_eq!(model.get_page_setup(0).unwrap(), page_setup);
+}
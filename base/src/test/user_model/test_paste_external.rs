@@ -0,0 +1,222 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::types::StyleIncludes;
+use crate::UserModel;
+
+#[test]
+fn cut_to_another_sheet() {
+    let mut model = new_empty_user_model();
+    model.new_sheet().unwrap();
+    model.set_selected_sheet(0).unwrap();
+    model.set_user_input(0, 1, 1, "2").unwrap();
+    model.set_user_input(0, 2, 1, "=A1*C5").unwrap();
+    model.set_user_input(0, 3, 1, "=Sheet1!A1+1").unwrap();
+    model.set_user_input(0, 5, 3, "10").unwrap();
+
+    model.set_selected_range(1, 1, 3, 1).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_sheet(1).unwrap();
+    model.set_selected_cell(1, 2).unwrap();
+    model
+        .paste_from_clipboard(0, (1, 1, 3, 1), &copy.data, true)
+        .unwrap();
+
+    // References inside the moved area follow the cells, the rest stay in Sheet1
+    assert_eq!(
+        model.get_cell_content(1, 2, 2),
+        Ok("=B1*Sheet1!C5".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(1, 3, 2),
+        Ok("=Sheet2!B1+1".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(1, 2, 2),
+        Ok("20".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(1, 3, 2), Ok("3".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 1), Ok("".to_string()));
+}
+
+#[test]
+fn copy_to_another_workbook() {
+    let mut source = new_empty_user_model();
+    source.new_sheet().unwrap();
+    source.rename_sheet(1, "Data").unwrap();
+    source.set_selected_sheet(0).unwrap();
+    source.set_user_input(0, 1, 1, "3").unwrap();
+    source.set_user_input(0, 2, 1, "=A1*2+Data!B2").unwrap();
+    source.set_user_input(0, 3, 1, "=SUM($A$1:A2)").unwrap();
+    source.set_user_input(1, 2, 2, "4").unwrap();
+
+    source.set_selected_range(1, 1, 3, 1).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 5, 3, "keep?").unwrap();
+    model.set_selected_cell(4, 3).unwrap();
+    model.paste_from_external_clipboard(&copy).unwrap();
+
+    assert_eq!(model.get_cell_content(0, 4, 3), Ok("3".to_string()));
+    // "Data" does not exist in this workbook, it is a reference to the source workbook
+    assert_eq!(
+        model.get_cell_content(0, 5, 3),
+        Ok("=C4*2+[model]Data!D5".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(0, 6, 3),
+        Ok("=SUM($A$1:C5)".to_string())
+    );
+    assert_eq!(model.get_selected_view().range, [4, 3, 6, 3]);
+
+    // Everything is undone at once
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 4, 3), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 5, 3), Ok("keep?".to_string()));
+    model.redo().unwrap();
+    assert_eq!(model.get_cell_content(0, 4, 3), Ok("3".to_string()));
+}
+
+#[test]
+fn existing_sheets_are_kept() {
+    let mut source = new_empty_user_model();
+    source.new_sheet().unwrap();
+    source.set_selected_sheet(0).unwrap();
+    source.set_user_input(0, 1, 1, "=Sheet2!A1").unwrap();
+    source.set_selected_range(1, 1, 1, 1).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    let mut model = new_empty_user_model();
+    model.new_sheet().unwrap();
+    model.set_selected_sheet(0).unwrap();
+    model.set_user_input(1, 1, 2, "42").unwrap();
+    model.set_selected_cell(1, 2).unwrap();
+    model.paste_from_external_clipboard(&copy).unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 1, 2),
+        Ok("=Sheet2!B1".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("42".to_string())
+    );
+}
+
+#[test]
+fn defined_names_are_added() {
+    let mut source = new_empty_user_model();
+    source
+        .new_defined_name("rate", None, "Sheet1!$B$1")
+        .unwrap();
+    source
+        .new_defined_name("unused", None, "Sheet1!$B$2")
+        .unwrap();
+    source.set_user_input(0, 1, 1, "=100*rate").unwrap();
+    source.set_selected_range(1, 1, 1, 1).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    // The name refers to the cells of this workbook
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 2, "0.2").unwrap();
+    model.paste_from_external_clipboard(&copy).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("20".to_string())
+    );
+    let names = model.get_defined_name_list();
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].0, "rate");
+
+    model.undo().unwrap();
+    assert!(model.get_defined_name_list().is_empty());
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("20".to_string())
+    );
+
+    // A name that already exists keeps its definition
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 3, 2, "0.5").unwrap();
+    model.new_defined_name("rate", None, "Sheet1!$B$3").unwrap();
+    model.paste_from_external_clipboard(&copy).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("50".to_string())
+    );
+}
+
+#[test]
+fn styles_are_merged() {
+    let mut source = new_empty_user_model();
+    let mut style = source.get_cell_style(0, 1, 1).unwrap();
+    style.font.b = true;
+    source
+        .create_named_style("Heading", &style, StyleIncludes::default())
+        .unwrap();
+    source.set_user_input(0, 1, 1, "Title").unwrap();
+    source.set_selected_cell(1, 1).unwrap();
+    source.on_apply_named_style("Heading").unwrap();
+    source.set_user_input(0, 1, 2, "0.25").unwrap();
+    source
+        .update_range_style(
+            &crate::expressions::types::Area {
+                sheet: 0,
+                row: 1,
+                column: 2,
+                width: 1,
+                height: 1,
+            },
+            "num_fmt",
+            "0.00%",
+        )
+        .unwrap();
+    source.set_selected_range(1, 1, 1, 2).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    let mut model = new_empty_user_model();
+    model.set_selected_cell(2, 2).unwrap();
+    model.paste_from_external_clipboard(&copy).unwrap();
+    assert!(model
+        .get_named_style_list()
+        .contains(&"Heading".to_string()));
+    assert!(model.get_cell_style(0, 2, 2).unwrap().font.b);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("25.00%".to_string())
+    );
+
+    // The cell is tied to the named style of the destination
+    let mut bold_italic = style.clone();
+    bold_italic.font.i = true;
+    model
+        .update_named_style("Heading", "Heading", &bold_italic, StyleIncludes::default())
+        .unwrap();
+    assert!(model.get_cell_style(0, 2, 2).unwrap().font.i);
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert!(!model
+        .get_named_style_list()
+        .contains(&"Heading".to_string()));
+    assert!(!model.get_cell_style(0, 2, 2).unwrap().font.b);
+}
+
+#[test]
+fn formulas_in_another_language() {
+    let mut source = UserModel::new_empty("model", "es", "UTC", "es").unwrap();
+    source.set_user_input(0, 1, 1, "1,5").unwrap();
+    source.set_user_input(0, 2, 1, "=SUMA(A1;2,5)").unwrap();
+    source.set_selected_range(1, 1, 2, 1).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    let mut model = new_empty_user_model();
+    model.paste_from_external_clipboard(&copy).unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("1.5".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 2, 1),
+        Ok("=SUM(A1,2.5)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1), Ok("4".to_string()));
+}
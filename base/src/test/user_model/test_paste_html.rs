@@ -0,0 +1,191 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::area;
+use crate::types::{BorderStyle, Color, HorizontalAlignment, VerticalAlignment};
+
+#[test]
+fn copy_html() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Q1 <total> & more").unwrap();
+    model.set_user_input(0, 2, 1, "0.25").unwrap();
+    model.set_user_input(0, 2, 2, "x").unwrap();
+    model
+        .update_range_style(&area(1, 1, 1, 1), "font.b", "true")
+        .unwrap();
+    model
+        .update_range_style(&area(1, 1, 1, 1), "fill.fg_color", "#FFFF00")
+        .unwrap();
+    model
+        .update_range_style(&area(2, 1, 1, 1), "num_fmt", "0.0%")
+        .unwrap();
+    model.merge_cells(&area(1, 1, 2, 1)).unwrap();
+
+    model.set_selected_range(1, 1, 2, 2).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    let html = &copy.html;
+    assert!(html.starts_with("<table><tbody><tr><td colspan=\"2\" style=\""));
+    assert!(html.contains("font-weight:bold"));
+    assert!(html.contains("background-color:#FFFF00"));
+    assert!(html.contains(">Q1 &lt;total&gt; &amp; more</td></tr>"));
+    assert!(html.contains("mso-number-format:&quot;0.0%&quot;"));
+    assert!(html.contains(">25.0%</td>"));
+    assert!(html.ends_with(">x</td></tr></tbody></table>"));
+    // The cell covered by the merged area is not written
+    assert_eq!(html.matches("<td").count(), 3);
+}
+
+#[test]
+fn paste_html_inline_styles() {
+    let mut model = new_empty_user_model();
+    let html = r##"<meta charset="utf-8"><google-sheets-html-origin>
+        <table xmlns="http://www.w3.org/1999/xhtml" cellspacing="0" border="0">
+        <colgroup><col width="100"/><col width="100"/></colgroup>
+        <tbody>
+          <tr style="height:21px;">
+            <td style="overflow:hidden;font-family:Arial;font-size:10pt;font-weight:bold;color:rgb(255,0,0);text-align:center;" colspan="2">Sales&nbsp;&amp;&nbsp;costs</td>
+          </tr>
+          <tr>
+            <td bgcolor="#00ff00" valign="top"><span style="font-style:italic">12</span></td>
+            <td style="border-bottom:2px solid #0000FF;white-space:normal">one<br>two</td>
+          </tr>
+        </tbody></table>"##;
+    model.set_selected_cell(2, 2).unwrap();
+    model.paste_html_string(&area(2, 2, 1, 1), html).unwrap();
+
+    assert_eq!(
+        model.get_cell_content(0, 2, 2),
+        Ok("Sales & costs".to_string())
+    );
+    let style = model.get_cell_style(0, 2, 2).unwrap();
+    assert!(style.font.b);
+    assert_eq!(style.font.name, "Arial");
+    assert_eq!(style.font.sz, 10);
+    assert_eq!(style.font.color, Color::Rgb("#FF0000".to_string()));
+    assert_eq!(
+        style.alignment.unwrap().horizontal,
+        HorizontalAlignment::Center
+    );
+    assert_eq!(model.get_merged_cells(0).unwrap(), vec![area(2, 2, 2, 1)]);
+
+    assert_eq!(model.get_cell_content(0, 3, 2), Ok("12".to_string()));
+    let style = model.get_cell_style(0, 3, 2).unwrap();
+    assert!(style.font.i);
+    assert_eq!(style.fill.color, Color::Rgb("#00FF00".to_string()));
+    assert_eq!(style.alignment.unwrap().vertical, VerticalAlignment::Top);
+
+    assert_eq!(model.get_cell_content(0, 3, 3), Ok("one\ntwo".to_string()));
+    let style = model.get_cell_style(0, 3, 3).unwrap();
+    assert!(style.alignment.unwrap().wrap_text);
+    let bottom = style.border.bottom.unwrap();
+    assert_eq!(bottom.style, BorderStyle::Medium);
+    assert_eq!(bottom.color, Color::Rgb("#0000FF".to_string()));
+
+    assert_eq!(model.get_selected_view().range, [2, 2, 3, 3]);
+
+    // A single undo
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 2, 2), Ok("".to_string()));
+    assert!(!model.get_cell_style(0, 2, 2).unwrap().font.b);
+    assert!(model.get_merged_cells(0).unwrap().is_empty());
+    model.redo().unwrap();
+    assert_eq!(model.get_cell_content(0, 3, 3), Ok("one\ntwo".to_string()));
+    assert_eq!(model.get_merged_cells(0).unwrap().len(), 1);
+}
+
+#[test]
+fn paste_html_style_sheet() {
+    // The kind of HTML Excel puts in the clipboard
+    let mut model = new_empty_user_model();
+    let html = r##"<html xmlns:x="urn:schemas-microsoft-com:office:excel">
+<head><style>
+<!--table
+	{mso-displayed-decimal-separator:"\.";}
+td
+	{font-size:11.0pt; mso-number-format:General;}
+.xl65
+	{mso-number-format:"0\.00%";}
+.xl66
+	{mso-number-format:"\@"; font-weight:700;}
+-->
+</style></head>
+<body>
+<!--StartFragment-->
+<table border=0 cellpadding=0 cellspacing=0 width=128>
+ <tr height=20>
+  <td height=20 class=xl65 align=right>12.50%</td>
+  <td class=xl66>007</td>
+ </tr>
+<!--EndFragment-->
+</table>
+</body>
+</html>"##;
+    model.paste_html_string(&area(1, 1, 1, 1), html).unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("12.50%".to_string())
+    );
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().num_fmt, "0.00%");
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().font.sz, 11);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 2),
+        Ok("007".to_string())
+    );
+    assert!(model.get_cell_style(0, 1, 2).unwrap().font.b);
+}
+
+#[test]
+fn html_round_trip() {
+    let mut source = new_empty_user_model();
+    source.set_user_input(0, 1, 1, "Title").unwrap();
+    source.set_user_input(0, 2, 1, "3").unwrap();
+    source.set_user_input(0, 2, 2, "=A2*2").unwrap();
+    source
+        .update_range_style(&area(1, 1, 1, 1), "font.i", "true")
+        .unwrap();
+    source
+        .update_range_style(&area(1, 1, 1, 1), "alignment.horizontal", "right")
+        .unwrap();
+    source
+        .update_range_style(&area(2, 2, 1, 1), "font.color", "#336699")
+        .unwrap();
+    source.merge_cells(&area(1, 1, 2, 1)).unwrap();
+    source.set_selected_range(1, 1, 2, 2).unwrap();
+    let copy = source.copy_to_clipboard().unwrap();
+
+    let mut model = new_empty_user_model();
+    model
+        .paste_html_string(&area(1, 1, 1, 1), &copy.html)
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("Title".to_string()));
+    // Formulas are pasted as their values
+    assert_eq!(model.get_cell_content(0, 2, 2), Ok("6".to_string()));
+    assert_eq!(
+        model.get_cell_style(0, 1, 1).unwrap(),
+        source.get_cell_style(0, 1, 1).unwrap()
+    );
+    assert_eq!(
+        model.get_cell_style(0, 2, 2).unwrap(),
+        source.get_cell_style(0, 2, 2).unwrap()
+    );
+    assert_eq!(
+        model.get_merged_cells(0).unwrap(),
+        source.get_merged_cells(0).unwrap()
+    );
+}
+
+#[test]
+fn paste_html_without_table() {
+    let mut model = new_empty_user_model();
+    let result = model.paste_html_string(&area(1, 1, 1, 1), "<p>just <b>text</b></p>");
+    assert_eq!(result, Err("No table found in HTML".to_string()));
+}
+
+#[test]
+fn paste_html_non_ascii_style() {
+    // 'K' (Kelvin sign) is three bytes long but lowercases to a one byte 'k'
+    let mut model = new_empty_user_model();
+    let html = "<style>\u{212A}\u{212A}€</style><table><tr><td>Ω €</td></tr></table>";
+    model.paste_html_string(&area(1, 1, 1, 1), html).unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("Ω €".to_string()));
+}
@@ -0,0 +1,262 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::area;
+use crate::user_model::{PasteOperation, PasteOptions, PasteType};
+
+fn options(paste: PasteType) -> PasteOptions {
+    PasteOptions {
+        paste,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn paste_values_only() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "21").unwrap();
+    model.set_user_input(0, 1, 2, "=A1*2").unwrap();
+    model.set_user_input(0, 1, 3, "=1/0").unwrap();
+    model.set_user_input(0, 1, 4, "'007").unwrap();
+    model
+        .update_range_style(&area(1, 2, 1, 1), "font.b", "true")
+        .unwrap();
+    model.set_user_input(0, 5, 2, "keep me?").unwrap();
+
+    model.set_selected_range(1, 1, 1, 5).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(5, 1).unwrap();
+    model
+        .paste_special_from_clipboard(0, (1, 1, 1, 5), &copy.data, &options(PasteType::Values))
+        .unwrap();
+
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("21".to_string()));
+    assert_eq!(model.get_cell_content(0, 5, 2), Ok("42".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 3),
+        Ok("#DIV/0!".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 4),
+        Ok("007".to_string())
+    );
+    // Styles are not pasted
+    assert!(!model.get_cell_style(0, 5, 2).unwrap().font.b);
+
+    // A single undo reverts the whole paste
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 5, 2), Ok("keep me?".to_string()));
+    model.redo().unwrap();
+    assert_eq!(model.get_cell_content(0, 5, 2), Ok("42".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 3),
+        Ok("#DIV/0!".to_string())
+    );
+}
+
+#[test]
+fn paste_formats_only() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model
+        .update_range_style(&area(1, 1, 1, 1), "font.b", "true")
+        .unwrap();
+    model.set_user_input(0, 3, 3, "target").unwrap();
+
+    model.set_selected_range(1, 1, 1, 1).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(3, 3).unwrap();
+    model
+        .paste_special_from_clipboard(0, (1, 1, 1, 1), &copy.data, &options(PasteType::Formats))
+        .unwrap();
+
+    assert_eq!(model.get_cell_content(0, 3, 3), Ok("target".to_string()));
+    assert!(model.get_cell_style(0, 3, 3).unwrap().font.b);
+
+    model.undo().unwrap();
+    assert!(!model.get_cell_style(0, 3, 3).unwrap().font.b);
+}
+
+#[test]
+fn paste_formulas_only() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "3").unwrap();
+    model.set_user_input(0, 2, 1, "=A1+1").unwrap();
+    model
+        .update_range_style(&area(2, 1, 1, 1), "font.i", "true")
+        .unwrap();
+
+    model.set_selected_cell(2, 1).unwrap();
+    model.set_selected_range(2, 1, 2, 1).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(2, 3).unwrap();
+    model
+        .paste_special_from_clipboard(0, (2, 1, 2, 1), &copy.data, &options(PasteType::Formulas))
+        .unwrap();
+
+    assert_eq!(model.get_cell_content(0, 2, 3), Ok("=C1+1".to_string()));
+    assert!(!model.get_cell_style(0, 2, 3).unwrap().font.i);
+}
+
+#[test]
+fn paste_transpose() {
+    let mut model = new_empty_user_model();
+    // A1:C2
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 1, 2, "2").unwrap();
+    model.set_user_input(0, 1, 3, "3").unwrap();
+    model.set_user_input(0, 2, 1, "=A1*10").unwrap();
+    model.set_user_input(0, 2, 2, "=B1*$A$1").unwrap();
+    model.set_user_input(0, 2, 3, "=SUM(A1:C1)").unwrap();
+
+    model.set_selected_range(1, 1, 2, 3).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(5, 5).unwrap();
+    let transpose = PasteOptions {
+        transpose: true,
+        ..Default::default()
+    };
+    model
+        .paste_special_from_clipboard(0, (1, 1, 2, 3), &copy.data, &transpose)
+        .unwrap();
+
+    // E5:F7
+    assert_eq!(model.get_cell_content(0, 5, 5), Ok("1".to_string()));
+    assert_eq!(model.get_cell_content(0, 6, 5), Ok("2".to_string()));
+    assert_eq!(model.get_cell_content(0, 7, 5), Ok("3".to_string()));
+    assert_eq!(model.get_cell_content(0, 5, 6), Ok("=E5*10".to_string()));
+    assert_eq!(model.get_cell_content(0, 6, 6), Ok("=E6*$A$1".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 7, 6),
+        Ok("=SUM(E5:E7)".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 7, 6), Ok("6".to_string()));
+    assert_eq!(model.get_selected_view().range, [5, 5, 7, 6]);
+}
+
+#[test]
+fn paste_skip_blanks() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+    model.set_user_input(0, 3, 1, "3").unwrap();
+    model.set_user_input(0, 2, 4, "keep").unwrap();
+
+    model.set_selected_range(1, 1, 3, 1).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(1, 4).unwrap();
+    let skip_blanks = PasteOptions {
+        skip_blanks: true,
+        ..Default::default()
+    };
+    model
+        .paste_special_from_clipboard(0, (1, 1, 3, 1), &copy.data, &skip_blanks)
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 4), Ok("1".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 4), Ok("keep".to_string()));
+    assert_eq!(model.get_cell_content(0, 3, 4), Ok("3".to_string()));
+
+    // without skip blanks the empty cell clears the destination
+    model.undo().unwrap();
+    model
+        .paste_special_from_clipboard(0, (1, 1, 3, 1), &copy.data, &options(PasteType::All))
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 2, 4), Ok("".to_string()));
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 2, 4), Ok("keep".to_string()));
+}
+
+#[test]
+fn paste_operations() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "10").unwrap();
+    model.set_user_input(0, 2, 1, "0").unwrap();
+
+    // destination: a number, a formula, a text and an empty cell
+    model.set_user_input(0, 1, 3, "5").unwrap();
+    model.set_user_input(0, 2, 3, "=C1*2").unwrap();
+    model.set_user_input(0, 1, 4, "text").unwrap();
+
+    model.set_selected_range(1, 1, 2, 1).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+
+    let operation = |operation| PasteOptions {
+        operation,
+        ..Default::default()
+    };
+
+    model.set_selected_cell(1, 3).unwrap();
+    model
+        .paste_special_from_clipboard(0, (1, 1, 2, 1), &copy.data, &operation(PasteOperation::Add))
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("15".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 3), Ok("=C1*2+0".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("30".to_string())
+    );
+
+    model.set_selected_cell(1, 4).unwrap();
+    model
+        .paste_special_from_clipboard(
+            0,
+            (1, 1, 2, 1),
+            &copy.data,
+            &operation(PasteOperation::Multiply),
+        )
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 4), Ok("text".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 4), Ok("0".to_string()));
+
+    model.set_selected_cell(1, 3).unwrap();
+    model
+        .paste_special_from_clipboard(
+            0,
+            (1, 1, 2, 1),
+            &copy.data,
+            &operation(PasteOperation::Divide),
+        )
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("1.5".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 2, 3),
+        Ok("=(C1*2+0)/0".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 2, 3),
+        Ok("#DIV/0!".to_string())
+    );
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("5".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 3), Ok("=C1*2".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 4), Ok("".to_string()));
+}
+
+#[test]
+fn paste_column_widths() {
+    let mut model = new_empty_user_model();
+    model.set_columns_width(0, 1, 1, 120.0).unwrap();
+    model.set_columns_width(0, 2, 2, 60.0).unwrap();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+
+    model.set_selected_range(1, 1, 1, 2).unwrap();
+    let copy = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(1, 5).unwrap();
+    model
+        .paste_special_from_clipboard(
+            0,
+            (1, 1, 1, 2),
+            &copy.data,
+            &options(PasteType::ColumnWidths),
+        )
+        .unwrap();
+    assert_eq!(model.get_column_width(0, 5), Ok(120.0));
+    assert_eq!(model.get_column_width(0, 6), Ok(60.0));
+    assert_eq!(model.get_cell_content(0, 1, 5), Ok("".to_string()));
+
+    model.undo().unwrap();
+    assert_ne!(model.get_column_width(0, 5), Ok(120.0));
+}
@@ -0,0 +1,225 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::constants::{LAST_COLUMN, LAST_ROW};
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::area;
+use crate::types::{PasswordHash, SheetProtection, WorkbookProtection};
+
+#[test]
+fn locked_cells_refuse_edits() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Title").unwrap();
+    // B2:B3 are the input cells
+    model
+        .update_range_style(&area(2, 2, 1, 2), "protection.locked", "false")
+        .unwrap();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+
+    assert!(model.is_cell_locked(0, 1, 1).unwrap());
+    assert!(!model.is_cell_locked(0, 2, 2).unwrap());
+    assert!(model.set_user_input(0, 1, 1, "New title").is_err());
+    assert!(model.set_user_input(0, 5, 5, "1").is_err());
+    assert!(model.range_clear_contents(&area(1, 1, 1, 1)).is_err());
+    assert!(model
+        .update_range_style(&area(1, 1, 1, 1), "font.b", "true")
+        .is_err());
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1),
+        Ok("Title".to_string())
+    );
+
+    // Unlocked cells can be edited and formatted
+    model.set_user_input(0, 2, 2, "42").unwrap();
+    model
+        .update_range_style(&area(2, 2, 1, 2), "font.b", "true")
+        .unwrap();
+    model.range_clear_contents(&area(2, 2, 1, 2)).unwrap();
+    // ... but not if the range contains a locked cell
+    assert!(model.range_clear_contents(&area(2, 2, 1, 3)).is_err());
+
+    model.unprotect_sheet(0, "").unwrap();
+    model.set_user_input(0, 1, 1, "New title").unwrap();
+}
+
+#[test]
+fn allowed_operations() {
+    let mut model = new_empty_user_model();
+    model
+        .protect_sheet(
+            0,
+            SheetProtection {
+                format_cells: true,
+                insert_rows: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    model
+        .update_range_style(&area(1, 1, 2, 2), "font.i", "true")
+        .unwrap();
+    model.insert_rows(0, 1, 1).unwrap();
+    assert!(model.insert_columns(0, 1, 1).is_err());
+    assert!(model.delete_rows(0, 1, 1).is_err());
+    assert!(model.set_rows_height(0, 1, 1, 40.0).is_err());
+    assert!(model.merge_cells(&area(1, 1, 2, 2)).is_err());
+
+    // Rows can be deleted if they are allowed and all their cells unlocked
+    model.unprotect_sheet(0, "").unwrap();
+    model
+        .update_range_style(&area(3, 1, LAST_COLUMN, 1), "protection.locked", "false")
+        .unwrap();
+    model
+        .protect_sheet(
+            0,
+            SheetProtection {
+                delete_rows: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(model.delete_rows(0, 2, 2).is_err());
+    model.delete_rows(0, 3, 1).unwrap();
+}
+
+#[test]
+fn row_and_column_styles() {
+    let mut model = new_empty_user_model();
+    // Columns A:C and row 5 are unlocked, B10 and D1 have a style of their own
+    model
+        .update_range_style(&area(1, 1, 3, LAST_ROW), "protection.locked", "false")
+        .unwrap();
+    model
+        .update_range_style(&area(5, 1, LAST_COLUMN, 1), "protection.locked", "false")
+        .unwrap();
+    model.set_user_input(0, 10, 2, "locked").unwrap();
+    model
+        .update_range_style(&area(10, 2, 1, 1), "protection.locked", "true")
+        .unwrap();
+    model.set_user_input(0, 1, 4, "unlocked").unwrap();
+    model
+        .update_range_style(&area(1, 4, 1, 1), "protection.locked", "false")
+        .unwrap();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+
+    let check = |row, column, width, height| {
+        model
+            .model
+            .check_cells_editable(0, row, column, width, height)
+            .is_ok()
+    };
+    assert!(check(1, 1, 3, 9));
+    assert!(!check(1, 1, 3, LAST_ROW));
+    assert!(check(11, 1, 3, LAST_ROW - 10));
+    assert!(check(5, 1, LAST_COLUMN, 1));
+    assert!(!check(6, 1, LAST_COLUMN, 1));
+    assert!(check(10, 1, 1, 1));
+    assert!(!check(10, 1, 3, 1));
+    assert!(check(1, 4, 1, 1));
+    assert!(!check(1, 4, 1, 2));
+    assert!(check(5, 4, 1, 1));
+    assert!(!check(1, 1, LAST_COLUMN, LAST_ROW));
+}
+
+#[test]
+fn hidden_formulas() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "=2+3").unwrap();
+    model.set_user_input(0, 2, 1, "=2*3").unwrap();
+    model
+        .update_range_style(&area(1, 1, 1, 1), "protection.hidden", "true")
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("=2+3".to_string()));
+
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 2, 1), Ok("=2*3".to_string()));
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1), Ok("5".to_string()));
+}
+
+#[test]
+fn passwords_and_undo() {
+    let mut model = new_empty_user_model();
+    model
+        .protect_sheet(
+            0,
+            SheetProtection {
+                password: Some(PasswordHash::new("secret")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        model.get_sheet_protection(0).unwrap().unwrap().password,
+        Some(PasswordHash::Legacy {
+            hash: "DAA7".to_string()
+        })
+    );
+    assert_eq!(
+        model.unprotect_sheet(0, "wrong"),
+        Err("The password you supplied is not correct.".to_string())
+    );
+    assert!(model.set_user_input(0, 1, 1, "1").is_err());
+    model.unprotect_sheet(0, "secret").unwrap();
+    model.set_user_input(0, 1, 1, "1").unwrap();
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert!(model.get_sheet_protection(0).unwrap().is_some());
+    model.undo().unwrap();
+    assert_eq!(model.get_sheet_protection(0), Ok(None));
+    model.redo().unwrap();
+    assert!(model.get_sheet_protection(0).unwrap().is_some());
+}
+
+#[test]
+fn salted_password() {
+    let password = PasswordHash::Salted {
+        algorithm_name: "SHA-512".to_string(),
+        hash_value: "k9BhlCsJFfxaNSu4u4rHdyjPGlFYi4NxkOK+BNrt7tzuGhaVF8Kv1V7GO1cMuKy8OBGPPwOxZSByBRqIgRIrnQ==".to_string(),
+        salt_value: "AAECAwQFBgcICQoLDA0ODw==".to_string(),
+        spin_count: 100,
+    };
+    assert!(password.verify("secret"));
+    assert!(!password.verify("Secret"));
+}
+
+#[test]
+fn workbook_structure() {
+    let mut model = new_empty_user_model();
+    model.new_sheet().unwrap();
+    model.protect_workbook(WorkbookProtection {
+        password: Some(PasswordHash::new("pass")),
+        lock_structure: true,
+        lock_windows: false,
+    });
+    assert!(model.new_sheet().is_err());
+    assert!(model.delete_sheet(1).is_err());
+    assert!(model.rename_sheet(1, "Data").is_err());
+    assert!(model.hide_sheet(1).is_err());
+    assert!(model.move_sheet(1, 0).is_err());
+    // Cells can still be edited
+    model.set_user_input(0, 1, 1, "1").unwrap();
+
+    assert!(model.unprotect_workbook("").is_err());
+    model.unprotect_workbook("pass").unwrap();
+    model.rename_sheet(1, "Data").unwrap();
+    assert_eq!(model.get_workbook_protection(), None);
+}
+
+#[test]
+fn undo_delete_protected_sheet() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Title").unwrap();
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    model.new_sheet().unwrap();
+
+    model.delete_sheet(0).unwrap();
+    assert_eq!(model.get_sheet_protection(0), Ok(None));
+
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_sheet_protection(0),
+        Ok(Some(SheetProtection::default()))
+    );
+    assert!(model.set_user_input(0, 1, 1, "New title").is_err());
+}
@@ -0,0 +1,156 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::rich_text_types::{RichTextFont, RichTextRun};
+use crate::test::user_model::util::new_empty_user_model;
+use crate::types::{Color, SheetProtection};
+use crate::UserModel;
+
+fn run(text: &str, font: RichTextFont) -> RichTextRun {
+    RichTextRun {
+        text: text.to_string(),
+        font,
+    }
+}
+
+fn hello_world() -> Vec<RichTextRun> {
+    vec![
+        run("Hello ", RichTextFont::default()),
+        run(
+            "World",
+            RichTextFont {
+                b: Some(true),
+                color: Color::Rgb("#FF0000".to_string()),
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+#[test]
+fn set_rich_text_and_undo() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Old").unwrap();
+    model.set_user_input(0, 1, 2, "=LEN(A1)").unwrap();
+    model.set_user_input(0, 1, 3, "=MID(A1, 7, 3)").unwrap();
+
+    model.set_cell_rich_text(0, 1, 1, hello_world()).unwrap();
+    assert_eq!(
+        model.get_cell_rich_text(0, 1, 1).unwrap(),
+        Some(hello_world())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1).unwrap(),
+        "Hello World"
+    );
+    assert_eq!(model.get_cell_content(0, 1, 1).unwrap(), "Hello World");
+    // Formulas see the plain text
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2).unwrap(), "11");
+    assert_eq!(model.get_formatted_cell_value(0, 1, 3).unwrap(), "Wor");
+
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_rich_text(0, 1, 1).unwrap(), None);
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1).unwrap(), "Old");
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2).unwrap(), "3");
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_cell_rich_text(0, 1, 1).unwrap(),
+        Some(hello_world())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2).unwrap(), "11");
+
+    // Typing in the cell replaces the rich text
+    model.set_user_input(0, 1, 1, "Hello World").unwrap();
+    assert_eq!(model.get_cell_rich_text(0, 1, 1).unwrap(), None);
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_cell_rich_text(0, 1, 1).unwrap(),
+        Some(hello_world())
+    );
+}
+
+#[test]
+fn plain_text_is_not_rich_text() {
+    let mut model = new_empty_user_model();
+    model.set_cell_rich_text(0, 1, 1, hello_world()).unwrap();
+    // The same text without runs is a different shared string
+    model.set_user_input(0, 2, 1, "Hello World").unwrap();
+    assert_eq!(model.get_cell_rich_text(0, 2, 1).unwrap(), None);
+
+    // The same rich text is shared
+    model.set_cell_rich_text(0, 3, 1, hello_world()).unwrap();
+    assert_eq!(model.get_model().workbook.rich_text.len(), 1);
+
+    // Runs without fonts are a plain text, consecutive runs with the same font are joined
+    let runs = vec![
+        run("Hello ", RichTextFont::default()),
+        run("World", RichTextFont::default()),
+    ];
+    model.set_cell_rich_text(0, 4, 1, runs).unwrap();
+    assert_eq!(model.get_cell_rich_text(0, 4, 1).unwrap(), None);
+    let bold = RichTextFont {
+        b: Some(true),
+        ..Default::default()
+    };
+    let runs = vec![run("Hello ", bold.clone()), run("World", bold.clone())];
+    model.set_cell_rich_text(0, 5, 1, runs).unwrap();
+    assert_eq!(
+        model.get_cell_rich_text(0, 5, 1).unwrap(),
+        Some(vec![run("Hello World", bold)])
+    );
+
+    // Also after loading the model again
+    let bytes = model.to_bytes();
+    let mut model = UserModel::from_bytes(&bytes, "en").unwrap();
+    model.set_user_input(0, 6, 1, "Hello World").unwrap();
+    assert_eq!(model.get_cell_rich_text(0, 6, 1).unwrap(), None);
+    assert_eq!(
+        model.get_cell_rich_text(0, 1, 1).unwrap(),
+        Some(hello_world())
+    );
+}
+
+#[test]
+fn invalid_rich_text() {
+    let mut model = new_empty_user_model();
+    assert!(model.set_cell_rich_text(0, 1, 1, vec![]).is_err());
+    assert!(model
+        .set_cell_rich_text(0, 1, 1, vec![run("", RichTextFont::default())])
+        .is_err());
+    let font = RichTextFont {
+        sz: Some(0),
+        ..Default::default()
+    };
+    assert!(model
+        .set_cell_rich_text(0, 1, 1, vec![run("a", font)])
+        .is_err());
+    let font = RichTextFont {
+        color: Color::Rgb("red".to_string()),
+        ..Default::default()
+    };
+    assert!(model
+        .set_cell_rich_text(0, 1, 1, vec![run("a", font)])
+        .is_err());
+    assert!(model.set_cell_rich_text(0, 0, 1, hello_world()).is_err());
+    assert_eq!(model.get_cell_content(0, 1, 1).unwrap(), "");
+
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert!(model.set_cell_rich_text(0, 1, 1, hello_world()).is_err());
+}
+
+#[test]
+fn copy_and_paste_rich_text() {
+    let mut model = new_empty_user_model();
+    model.set_cell_rich_text(0, 1, 1, hello_world()).unwrap();
+    model.set_selected_range(1, 1, 1, 1).unwrap();
+    let clipboard = model.copy_to_clipboard().unwrap();
+    model.set_selected_cell(3, 2).unwrap();
+    model
+        .paste_from_clipboard(0, (1, 1, 1, 1), &clipboard.data, false)
+        .unwrap();
+    assert_eq!(
+        model.get_cell_rich_text(0, 3, 2).unwrap(),
+        Some(hello_world())
+    );
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 3, 2).unwrap(), "");
+}
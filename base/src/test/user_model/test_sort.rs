@@ -0,0 +1,268 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::sort_types::{SortKey, SortOn};
+use crate::test::user_model::util::new_empty_user_model;
+use crate::test::util::{area, new_user_model_with_data};
+use crate::types::{Color, Link, SheetProtection};
+use crate::UserModel;
+
+fn key(index: i32, descending: bool, sort_on: SortOn) -> SortKey {
+    SortKey {
+        index,
+        descending,
+        sort_on,
+    }
+}
+
+fn column_values(
+    model: &UserModel,
+    column: i32,
+    rows: std::ops::RangeInclusive<i32>,
+) -> Vec<String> {
+    rows.map(|row| model.get_formatted_cell_value(0, row, column).unwrap())
+        .collect()
+}
+
+// A2:C6 with a header in row 1
+fn new_model_with_data() -> UserModel<'static> {
+    new_user_model_with_data(&[
+        &["Name", "Size", "Price"],
+        &["pear", "Medium", "3"],
+        &["Apple", "Low", "5"],
+        &["fig", "High", "3"],
+        &["banana", "", "1"],
+        &["cherry", "Low", "TRUE"],
+    ])
+}
+
+#[test]
+fn sort_by_value() {
+    let mut model = new_model_with_data();
+    model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(1, false, SortOn::Value)],
+        )
+        .unwrap();
+    assert_eq!(
+        column_values(&model, 1, 1..=6),
+        ["Name", "Apple", "banana", "cherry", "fig", "pear"]
+    );
+    // The rest of the row moves with the key
+    assert_eq!(
+        column_values(&model, 3, 2..=6),
+        ["5", "1", "TRUE", "3", "3"]
+    );
+
+    // Numbers go before booleans, empty cells always last
+    model
+        .sort_range(
+            &area(2, 1, 3, 5),
+            false,
+            false,
+            &[key(3, true, SortOn::Value)],
+        )
+        .unwrap();
+    assert_eq!(
+        column_values(&model, 3, 2..=6),
+        ["TRUE", "5", "3", "3", "1"]
+    );
+    model
+        .sort_range(
+            &area(2, 1, 3, 5),
+            false,
+            false,
+            &[key(2, true, SortOn::Value)],
+        )
+        .unwrap();
+    assert_eq!(
+        column_values(&model, 2, 2..=6),
+        ["Medium", "Low", "Low", "High", ""]
+    );
+}
+
+#[test]
+fn multiple_keys_and_custom_list() {
+    let mut model = new_model_with_data();
+    let list = SortOn::CustomList(vec![
+        "Low".to_string(),
+        "Medium".to_string(),
+        "High".to_string(),
+    ]);
+    model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(2, false, list), key(1, true, SortOn::Value)],
+        )
+        .unwrap();
+    assert_eq!(
+        column_values(&model, 1, 2..=6),
+        ["cherry", "Apple", "pear", "fig", "banana"]
+    );
+    let state = model.get_sort_state(0).unwrap().unwrap();
+    assert_eq!(state.range, "A2:C6");
+    assert_eq!(state.keys.len(), 2);
+}
+
+#[test]
+fn sort_by_color() {
+    let mut model = new_model_with_data();
+    model
+        .update_range_style(&area(4, 1, 1, 1), "fill.color", "#FF0000")
+        .unwrap();
+    model
+        .update_range_style(&area(6, 1, 1, 1), "font.color", "#0000FF")
+        .unwrap();
+    let red = Color::Rgb("#FF0000".to_string());
+    model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(1, false, SortOn::CellColor(red.clone()))],
+        )
+        .unwrap();
+    // The style moves with the cell
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "fig");
+    assert_eq!(model.get_cell_style(0, 2, 1).unwrap().fill.color, red);
+    assert_eq!(
+        column_values(&model, 1, 3..=6),
+        ["pear", "Apple", "banana", "cherry"]
+    );
+
+    model
+        .sort_range(
+            &area(2, 1, 3, 5),
+            false,
+            false,
+            &[key(
+                1,
+                true,
+                SortOn::FontColor(Color::Rgb("#0000FF".to_string())),
+            )],
+        )
+        .unwrap();
+    assert_eq!(model.get_formatted_cell_value(0, 6, 1).unwrap(), "cherry");
+}
+
+#[test]
+fn left_to_right() {
+    let mut model = new_empty_user_model();
+    for (column, value) in ["Key", "c", "a", "b"].iter().enumerate() {
+        model
+            .set_user_input(0, 1, column as i32 + 1, value)
+            .unwrap();
+    }
+    for (column, value) in ["Value", "3", "1", "2"].iter().enumerate() {
+        model
+            .set_user_input(0, 2, column as i32 + 1, value)
+            .unwrap();
+    }
+    model
+        .sort_range(
+            &area(1, 1, 4, 2),
+            true,
+            true,
+            &[key(1, false, SortOn::Value)],
+        )
+        .unwrap();
+    for (column, value) in ["Key", "a", "b", "c"].iter().enumerate() {
+        assert_eq!(
+            model
+                .get_formatted_cell_value(0, 1, column as i32 + 1)
+                .unwrap(),
+            *value
+        );
+    }
+    assert_eq!(model.get_formatted_cell_value(0, 2, 2).unwrap(), "1");
+    assert!(model.get_sort_state(0).unwrap().unwrap().left_to_right);
+}
+
+#[test]
+fn formulas_links_and_comments() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "3").unwrap();
+    model.set_user_input(0, 2, 1, "1").unwrap();
+    model.set_user_input(0, 3, 1, "2").unwrap();
+    model.set_user_input(0, 1, 2, "=A1*10").unwrap();
+    model.set_user_input(0, 2, 2, "=A2*10+$D$1").unwrap();
+    model.set_user_input(0, 3, 2, "=A3*10").unwrap();
+    model.set_user_input(0, 4, 2, "=SUM(B1:B3)").unwrap();
+    model.set_user_input(0, 1, 4, "1").unwrap();
+    model.set_comment(0, 1, 1, "Three", "Ann").unwrap();
+    let link = Link::External {
+        target: "https://example.com".to_string(),
+        tooltip: None,
+    };
+    model.set_cell_link(0, 2, 1, link.clone(), None).unwrap();
+
+    model
+        .sort_range(
+            &area(1, 1, 2, 3),
+            false,
+            false,
+            &[key(1, false, SortOn::Value)],
+        )
+        .unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 2).unwrap(), "=A1*10+$D$1");
+    assert_eq!(model.get_cell_content(0, 2, 2).unwrap(), "=A2*10");
+    assert_eq!(model.get_cell_content(0, 3, 2).unwrap(), "=A3*10");
+    assert_eq!(column_values(&model, 2, 1..=3), ["11", "20", "30"]);
+    // Formulas outside the range are not changed
+    assert_eq!(model.get_cell_content(0, 4, 2).unwrap(), "=SUM(B1:B3)");
+    assert!(model.get_comment(0, 3, 1).unwrap().is_some());
+    assert!(model.get_comment(0, 1, 1).unwrap().is_none());
+    assert_eq!(model.get_cell_link(0, 1, 1).unwrap(), Some(link.clone()));
+
+    // A single undo restores the original order
+    model.undo().unwrap();
+    assert_eq!(column_values(&model, 1, 1..=3), ["3", "1", "2"]);
+    assert_eq!(model.get_cell_content(0, 2, 2).unwrap(), "=A2*10+$D$1");
+    assert!(model.get_comment(0, 1, 1).unwrap().is_some());
+    assert_eq!(model.get_cell_link(0, 2, 1).unwrap(), Some(link));
+    assert_eq!(model.get_sort_state(0), Ok(None));
+    model.redo().unwrap();
+    assert_eq!(column_values(&model, 1, 1..=3), ["1", "2", "3"]);
+    assert!(model.get_sort_state(0).unwrap().is_some());
+}
+
+#[test]
+fn invalid_sorts() {
+    let mut model = new_model_with_data();
+    assert!(model
+        .sort_range(&area(1, 1, 3, 6), true, false, &[])
+        .is_err());
+    assert!(model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(5, false, SortOn::Value)]
+        )
+        .is_err());
+    model.merge_cells(&area(3, 2, 2, 1)).unwrap();
+    assert!(model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(1, false, SortOn::Value)]
+        )
+        .is_err());
+    model.unmerge_cells(&area(3, 2, 2, 1)).unwrap();
+
+    model.protect_sheet(0, SheetProtection::default()).unwrap();
+    assert!(model
+        .sort_range(
+            &area(1, 1, 3, 6),
+            true,
+            false,
+            &[key(1, false, SortOn::Value)]
+        )
+        .is_err());
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "pear");
+}
@@ -4,7 +4,10 @@ use crate::types::Color;
 use crate::{
     expressions::types::Area,
     test::user_model::util::new_empty_user_model,
-    types::{Alignment, HorizontalAlignment, VerticalAlignment},
+    types::{
+        Alignment, Fill, GradientFill, GradientStop, GradientType, HorizontalAlignment,
+        PatternType, ReadingOrder, VerticalAlignment,
+    },
 };
 
 #[test]
@@ -179,6 +182,111 @@ fn fill_errors() {
     );
 }
 
+#[test]
+fn pattern_and_gradient_fills() {
+    let mut model = new_empty_user_model();
+    let range = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 1,
+        height: 1,
+    };
+    // A hatch pattern: red lines over a yellow background
+    model
+        .update_range_style(&range, "fill.pattern", "lightUp")
+        .unwrap();
+    model
+        .update_range_style(&range, "fill.fg_color", "#FF0000")
+        .unwrap();
+    model
+        .update_range_style(&range, "fill.bg_color", "#FFFF00")
+        .unwrap();
+    let pattern_fill = Fill {
+        color: Color::Rgb("#FF0000".to_string()),
+        pattern: PatternType::LightUp,
+        bg_color: Color::Rgb("#FFFF00".to_string()),
+        gradient: None,
+    };
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().fill, pattern_fill);
+    let extended = model.get_extended_cell_style(0, 1, 1).unwrap();
+    assert_eq!(extended.style.fill, pattern_fill);
+
+    model
+        .update_range_style(&range, "fill.gradient", "linear 90; 0 #FFFFFF; 1 [4, 0.4]")
+        .unwrap();
+    let fill = model.get_cell_style(0, 1, 1).unwrap().fill;
+    assert_eq!(
+        fill.gradient,
+        Some(GradientFill {
+            r#type: GradientType::Linear,
+            degree: 90.0,
+            stops: vec![
+                GradientStop {
+                    position: 0.0,
+                    color: Color::Rgb("#FFFFFF".to_string()),
+                },
+                GradientStop {
+                    position: 1.0,
+                    color: Color::Theme(4, 0.4),
+                },
+            ],
+            ..Default::default()
+        })
+    );
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().fill, pattern_fill);
+    model.redo().unwrap();
+
+    model
+        .update_range_style(
+            &range,
+            "fill.gradient",
+            "path 0.5 0.5 0.5 0.5; 0 #FFFFFF; 1 #4472C4",
+        )
+        .unwrap();
+    let gradient = model
+        .get_cell_style(0, 1, 1)
+        .unwrap()
+        .fill
+        .gradient
+        .unwrap();
+    assert_eq!(gradient.r#type, GradientType::Path);
+    assert_eq!(gradient.left, 0.5);
+    assert_eq!(gradient.bottom, 0.5);
+
+    // A solid color replaces the pattern and the gradient
+    model
+        .update_range_style(&range, "fill.color", "#F3F4F5")
+        .unwrap();
+    assert_eq!(
+        model.get_cell_style(0, 1, 1).unwrap().fill,
+        Fill {
+            color: Color::Rgb("#F3F4F5".to_string()),
+            ..Default::default()
+        }
+    );
+    model.update_range_style(&range, "fill", "").unwrap();
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().fill, Fill::default());
+
+    assert_eq!(
+        model.update_range_style(&range, "fill.pattern", "hatch"),
+        Err("Invalid value for fill pattern: 'hatch'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "fill.gradient", "radial 90; 0 #FFFFFF; 1 #000000"),
+        Err("Invalid value for gradient: 'radial 90; 0 #FFFFFF; 1 #000000'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "fill.gradient", "linear 90; 0 #FFFFFF; 2 #000000"),
+        Err("Invalid value for gradient: '2'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "fill.gradient", "linear 45; 0 #FFFFFF"),
+        Err("A gradient needs at least two colors: 'linear 45; 0 #FFFFFF'.".to_string())
+    );
+}
+
 #[test]
 fn basic_format() {
     let mut model = new_empty_user_model();
@@ -241,7 +349,8 @@ fn basic_alignment() {
         Some(Alignment {
             horizontal: HorizontalAlignment::Center,
             vertical: VerticalAlignment::Bottom,
-            wrap_text: false
+            wrap_text: false,
+            ..Default::default()
         })
     );
 
@@ -254,7 +363,8 @@ fn basic_alignment() {
         Some(Alignment {
             horizontal: HorizontalAlignment::CenterContinuous,
             vertical: VerticalAlignment::Bottom,
-            wrap_text: false
+            wrap_text: false,
+            ..Default::default()
         })
     );
 
@@ -275,7 +385,8 @@ fn basic_alignment() {
         Some(Alignment {
             horizontal: HorizontalAlignment::General,
             vertical: VerticalAlignment::Distributed,
-            wrap_text: false
+            wrap_text: false,
+            ..Default::default()
         })
     );
 
@@ -288,7 +399,8 @@ fn basic_alignment() {
         Some(Alignment {
             horizontal: HorizontalAlignment::General,
             vertical: VerticalAlignment::Justify,
-            wrap_text: false
+            wrap_text: false,
+            ..Default::default()
         })
     );
 
@@ -304,7 +416,8 @@ fn basic_alignment() {
         Some(Alignment {
             horizontal: HorizontalAlignment::General,
             vertical: VerticalAlignment::Justify,
-            wrap_text: false
+            wrap_text: false,
+            ..Default::default()
         })
     );
 }
@@ -357,6 +470,78 @@ fn alignment_errors() {
     );
 }
 
+#[test]
+fn extended_alignment() {
+    let mut model = new_empty_user_model();
+    let range = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 1,
+    };
+    model
+        .update_range_style(&range, "alignment.horizontal", "distributed")
+        .unwrap();
+    model
+        .update_range_style(&range, "alignment.text_rotation", "135")
+        .unwrap();
+    model
+        .update_range_style(&range, "alignment.indent", "2")
+        .unwrap();
+    model
+        .update_range_style(&range, "alignment.shrink_to_fit", "true")
+        .unwrap();
+    model
+        .update_range_style(&range, "alignment.reading_order", "rightToLeft")
+        .unwrap();
+    model
+        .update_range_style(&range, "alignment.justify_last_line", "true")
+        .unwrap();
+    let expected = Some(Alignment {
+        horizontal: HorizontalAlignment::Distributed,
+        text_rotation: 135,
+        indent: 2,
+        shrink_to_fit: true,
+        reading_order: ReadingOrder::RightToLeft,
+        justify_last_line: true,
+        ..Default::default()
+    });
+    assert_eq!(model.get_cell_style(0, 1, 2).unwrap().alignment, expected);
+
+    // Vertical text
+    model
+        .update_range_style(&range, "alignment.text_rotation", "255")
+        .unwrap();
+    let alignment = model.get_cell_style(0, 1, 1).unwrap().alignment.unwrap();
+    assert_eq!(alignment.text_rotation, 255);
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().alignment, expected);
+
+    for _ in 0..6 {
+        model.undo().unwrap();
+    }
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().alignment, None);
+
+    assert_eq!(
+        model.update_range_style(&range, "alignment.text_rotation", "200"),
+        Err("Invalid value for text rotation: '200'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "alignment.text_rotation", "up"),
+        Err("Invalid value for text rotation: 'up'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "alignment.indent", "-1"),
+        Err("Invalid value for indent: '-1'.".to_string())
+    );
+    assert_eq!(
+        model.update_range_style(&range, "alignment.reading_order", "rtl"),
+        Err("Invalid value for reading order: 'rtl'.".to_string())
+    );
+    assert_eq!(model.get_cell_style(0, 1, 1).unwrap().alignment, None);
+}
+
 #[test]
 fn basic_wrap_text() {
     let mut model = new_empty_user_model();
@@ -380,7 +565,8 @@ fn basic_wrap_text() {
         Some(Alignment {
             horizontal: HorizontalAlignment::General,
             vertical: VerticalAlignment::Bottom,
-            wrap_text: true
+            wrap_text: true,
+            ..Default::default()
         })
     );
     model.undo().unwrap();
@@ -395,7 +581,8 @@ fn basic_wrap_text() {
         Some(Alignment {
             horizontal: HorizontalAlignment::General,
             vertical: VerticalAlignment::Bottom,
-            wrap_text: true
+            wrap_text: true,
+            ..Default::default()
         })
     );
 
@@ -0,0 +1,628 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::expressions::types::Area;
+use crate::test::user_model::util::new_empty_user_model;
+use crate::UserModel;
+
+// A1:B4 with headers "Region" and "Amount" and three rows of data
+fn new_sales_model<'a>() -> UserModel<'a> {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Region").unwrap();
+    model.set_user_input(0, 1, 2, "Amount").unwrap();
+    model.set_user_input(0, 2, 1, "North").unwrap();
+    model.set_user_input(0, 2, 2, "10").unwrap();
+    model.set_user_input(0, 3, 1, "South").unwrap();
+    model.set_user_input(0, 3, 2, "20").unwrap();
+    model.set_user_input(0, 4, 1, "East").unwrap();
+    model.set_user_input(0, 4, 2, "30").unwrap();
+    model
+}
+
+fn sales_area() -> Area {
+    Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 4,
+    }
+}
+
+fn column_names(model: &UserModel, name: &str) -> Vec<String> {
+    model
+        .get_model()
+        .get_table(name)
+        .unwrap()
+        .columns
+        .iter()
+        .map(|column| column.name.clone())
+        .collect()
+}
+
+fn reference(model: &UserModel, name: &str) -> String {
+    model.get_model().get_table(name).unwrap().reference
+}
+
+#[test]
+fn create_table_with_headers() {
+    let mut model = new_sales_model();
+    let name = model.create_table(&sales_area(), true).unwrap();
+    assert_eq!(name, "Table1");
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(
+        model.get_table_name_at(0, 3, 2),
+        Ok(Some("Table1".to_string()))
+    );
+    assert_eq!(model.get_table_name_at(0, 5, 2), Ok(None));
+
+    model
+        .set_user_input(0, 7, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+    model.set_user_input(0, 8, 1, "=ROWS(Table1)").unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 8, 1), Ok("3".to_string()));
+
+    // Tables cannot overlap
+    assert!(model.create_table(&sales_area(), true).is_err());
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert!(model.get_tables(0).unwrap().is_empty());
+    model.redo().unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+}
+
+#[test]
+fn create_table_without_headers() {
+    let mut model = new_sales_model();
+    let area = Area {
+        row: 2,
+        height: 3,
+        ..sales_area()
+    };
+    model.create_table(&area, false).unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Column1", "Column2"]);
+    let table = model.get_model().get_table("Table1").unwrap();
+    assert_eq!(table.header_row_count, 0);
+    // The data is not changed
+    assert_eq!(model.get_cell_content(0, 2, 1), Ok("North".to_string()));
+}
+
+#[test]
+fn empty_and_repeated_headers() {
+    let mut model = new_empty_user_model();
+    model.set_user_input(0, 1, 1, "Name").unwrap();
+    model.set_user_input(0, 1, 3, "name").unwrap();
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 3,
+        height: 3,
+    };
+    model.create_table(&area, true).unwrap();
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Name", "Column1", "name2"]
+    );
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Column1".to_string()));
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("name2".to_string()));
+
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("name".to_string()));
+}
+
+#[test]
+fn rename_table() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.set_user_input(0, 7, 1, "=SUM(Table1)").unwrap();
+    model
+        .set_user_input(0, 8, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+
+    assert!(model.rename_table("Table1", "A1").is_err());
+    assert!(model.rename_table("Table1", "Sales Data").is_err());
+
+    model.rename_table("table1", "Sales").unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Sales)".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(0, 8, 1),
+        Ok("=SUM(Sales[Amount])".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 8, 1),
+        Ok("60".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1)".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(0, 8, 1),
+        Ok("=SUM(Table1[Amount])".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Sales)".to_string())
+    );
+}
+
+#[test]
+fn delete_table() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.set_user_input(0, 7, 1, "=SUM(Table1)").unwrap();
+
+    model.delete_table("Table1").unwrap();
+    assert!(model.get_tables(0).unwrap().is_empty());
+    assert_eq!(model.get_cell_content(0, 2, 1), Ok("".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("#NAME?".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_cell_content(0, 2, 1), Ok("North".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+}
+
+#[test]
+fn convert_table_to_range() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.set_user_input(0, 7, 1, "=SUM(Table1)").unwrap();
+    model
+        .set_user_input(0, 8, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+    model.set_user_input(0, 3, 4, "=Table1[@Amount]*2").unwrap();
+    model.new_sheet().unwrap();
+    model.set_user_input(1, 1, 1, "=COUNTA(Table1)").unwrap();
+
+    model.convert_table_to_range("Table1").unwrap();
+    assert!(model.get_tables(0).unwrap().is_empty());
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM($A$2:$B$4)".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(0, 8, 1),
+        Ok("=SUM($B$2:$B$4)".to_string())
+    );
+    assert_eq!(model.get_cell_content(0, 3, 4), Ok("=$B3*2".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 3, 4),
+        Ok("40".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(1, 1, 1),
+        Ok("=COUNTA(Sheet1!$A$2:$B$4)".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+    assert_eq!(model.get_formatted_cell_value(1, 1, 1), Ok("6".to_string()));
+
+    model.undo().unwrap();
+    assert_eq!(model.get_tables(0).unwrap().len(), 1);
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1)".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(1, 1, 1),
+        Ok("=COUNTA(Table1)".to_string())
+    );
+    assert_eq!(
+        model.get_cell_content(0, 3, 4),
+        Ok("=Table1[@Amount]*2".to_string())
+    );
+
+    model.redo().unwrap();
+    assert!(model.get_tables(0).unwrap().is_empty());
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM($A$2:$B$4)".to_string())
+    );
+}
+
+#[test]
+fn resize_table() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    let area = Area {
+        width: 3,
+        height: 5,
+        ..sales_area()
+    };
+    model.resize_table("Table1", &area).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C5");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Region", "Amount", "Column1"]
+    );
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("Column1".to_string()));
+
+    // The headers cannot move
+    let area = Area {
+        row: 2,
+        ..sales_area()
+    };
+    assert!(model.resize_table("Table1", &area).is_err());
+
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("".to_string()));
+}
+
+#[test]
+fn insert_and_delete_table_columns() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model
+        .set_user_input(0, 7, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+
+    // In the middle of the table
+    model.insert_table_column("Table1", 2).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C4");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Region", "Column1", "Amount"]
+    );
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Column1".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+
+    // Before the first column
+    model.insert_table_column("Table1", 1).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:D4");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Column2", "Region", "Column1", "Amount"]
+    );
+
+    // After the last column
+    model.insert_table_column("Table1", 5).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:E4");
+    assert_eq!(model.get_cell_content(0, 1, 5), Ok("Column3".to_string()));
+
+    assert!(model.insert_table_column("Table1", 7).is_err());
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C4");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Region", "Column1", "Amount"]
+    );
+    model.redo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:D4");
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("Column2".to_string()));
+
+    model.delete_table_column("Table1", 3).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C4");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Column2", "Region", "Amount"]
+    );
+    model.undo().unwrap();
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Column2", "Region", "Column1", "Amount"]
+    );
+    assert_eq!(model.get_cell_content(0, 1, 3), Ok("Column1".to_string()));
+}
+
+#[test]
+fn rename_table_column() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model
+        .set_user_input(0, 7, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+
+    assert!(model.rename_table_column("Table1", 2, "region").is_err());
+    assert!(model.rename_table_column("Table1", 3, "Price").is_err());
+
+    model.rename_table_column("Table1", 2, "Price").unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Price"]);
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Price".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1[Price])".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+    model
+        .set_user_input(0, 8, 1, "=SUM(Table1[Price])")
+        .unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 8, 1),
+        Ok("60".to_string())
+    );
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Amount".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1[Amount])".to_string())
+    );
+}
+
+#[test]
+fn typing_in_a_header_renames_the_column() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model
+        .set_user_input(0, 7, 1, "=SUM(Table1[Amount])")
+        .unwrap();
+
+    model.set_user_input(0, 1, 2, "Price").unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Price"]);
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1[Price])".to_string())
+    );
+
+    model.set_user_input(0, 1, 2, "").unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Column1"]);
+
+    model.undo().unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Price"]);
+    model.undo().unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+    assert_eq!(
+        model.get_cell_content(0, 7, 1),
+        Ok("=SUM(Table1[Amount])".to_string())
+    );
+    model.redo().unwrap();
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Price"]);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 1),
+        Ok("60".to_string())
+    );
+}
+
+#[test]
+fn header_row() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+
+    model.set_table_header_row("Table1", false).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A2:B4");
+    assert_eq!(model.get_cell_content(0, 1, 1), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("".to_string()));
+
+    model.set_table_header_row("Table1", true).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Amount".to_string()));
+
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A2:B4");
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("".to_string()));
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 1, 2), Ok("Amount".to_string()));
+
+    // There is no room for the header row
+    let mut model = new_sales_model();
+    let area = Area {
+        row: 2,
+        height: 3,
+        ..sales_area()
+    };
+    model.create_table(&area, false).unwrap();
+    assert!(model.set_table_header_row("Table1", true).is_err());
+}
+
+#[test]
+fn totals_row() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model
+        .set_user_input(0, 8, 4, "=Table1[[#Totals],[Amount]]")
+        .unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 8, 4),
+        Ok("#REF!".to_string())
+    );
+
+    model.set_table_totals_row("Table1", true).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+    assert_eq!(
+        model.get_formatted_cell_value(0, 8, 4),
+        Ok("60".to_string())
+    );
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("Total".to_string()));
+    assert_eq!(
+        model.get_cell_content(0, 5, 2),
+        Ok("=SUBTOTAL(109,Table1[Amount])".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 2),
+        Ok("60".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("".to_string()));
+    assert_eq!(model.get_cell_content(0, 5, 2), Ok("".to_string()));
+
+    model.redo().unwrap();
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 2),
+        Ok("60".to_string())
+    );
+
+    model.set_table_totals_row("Table1", false).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 5, 2), Ok("".to_string()));
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+    assert_eq!(
+        model.get_formatted_cell_value(0, 5, 2),
+        Ok("60".to_string())
+    );
+}
+
+#[test]
+fn auto_expand() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model
+        .set_user_input(0, 7, 4, "=SUM(Table1[Amount])")
+        .unwrap();
+
+    // Right below the table
+    model.set_user_input(0, 5, 1, "West").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+    model.set_user_input(0, 5, 2, "40").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+    // Structured references use the new area of the table
+    assert_eq!(
+        model.get_cell_content(0, 7, 4),
+        Ok("=SUM(Table1[Amount])".to_string())
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 4),
+        Ok("100".to_string())
+    );
+
+    // Right to the right of the table
+    model.set_user_input(0, 1, 3, "Notes").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C5");
+    assert_eq!(
+        column_names(&model, "Table1"),
+        vec!["Region", "Amount", "Notes"]
+    );
+    model.set_user_input(0, 3, 4, "x").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:D5");
+    assert_eq!(model.get_cell_content(0, 1, 4), Ok("Column1".to_string()));
+
+    // Not next to the table
+    model.set_user_input(0, 7, 1, "far").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:D5");
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:C5");
+    assert_eq!(model.get_cell_content(0, 1, 4), Ok("".to_string()));
+    model.undo().unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("".to_string()));
+    assert_eq!(
+        model.get_formatted_cell_value(0, 7, 4),
+        Ok("60".to_string())
+    );
+
+    model.redo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+    assert_eq!(model.get_cell_content(0, 5, 1), Ok("West".to_string()));
+}
+
+#[test]
+fn no_auto_expand_below_totals_row() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.set_table_totals_row("Table1", true).unwrap();
+    model.set_user_input(0, 6, 1, "West").unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B5");
+}
+
+#[test]
+fn insert_and_delete_rows() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.set_user_input(0, 7, 4, "=ROWS(Table1)").unwrap();
+
+    model.insert_rows(0, 1, 2).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A3:B6");
+    model.insert_rows(0, 4, 1).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A3:B7");
+    assert_eq!(
+        model.get_formatted_cell_value(0, 10, 4),
+        Ok("4".to_string())
+    );
+
+    // Deleting the header row removes it from the table
+    model.delete_rows(0, 3, 1).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A3:B6");
+    let table = model.get_model().get_table("Table1").unwrap();
+    assert_eq!(table.header_row_count, 0);
+
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A3:B7");
+    let table = model.get_model().get_table("Table1").unwrap();
+    assert_eq!(table.header_row_count, 1);
+
+    // Deleting all the rows deletes the table
+    model.delete_rows(0, 3, 5).unwrap();
+    assert!(model.get_tables(0).unwrap().is_empty());
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A3:B7");
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+}
+
+#[test]
+fn delete_columns() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+
+    model.delete_columns(0, 1, 1).unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:A4");
+    assert_eq!(column_names(&model, "Table1"), vec!["Amount"]);
+    assert!(model.delete_table_column("Table1", 1).is_err());
+
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+    assert_eq!(column_names(&model, "Table1"), vec!["Region", "Amount"]);
+}
+
+#[test]
+fn delete_sheet_with_table() {
+    let mut model = new_sales_model();
+    model.create_table(&sales_area(), true).unwrap();
+    model.new_sheet().unwrap();
+    model.delete_sheet(0).unwrap();
+    assert!(model.get_model().get_table("Table1").is_err());
+    model.undo().unwrap();
+    assert_eq!(reference(&model, "Table1"), "A1:B4");
+}
@@ -3,11 +3,42 @@
 use crate::expressions::types::{Area, CellReferenceIndex};
 use crate::model::Model;
 use crate::types::Cell;
+use crate::UserModel;
 
 pub fn new_empty_model<'a>() -> Model<'a> {
     Model::new_empty("model", "en", "UTC", "en").unwrap()
 }
 
+/// A user model with `data` in the first sheet, starting at A1
+pub fn new_user_model_with_data<'a>(data: &[&[&str]]) -> UserModel<'a> {
+    let mut model = UserModel::from_model(new_empty_model());
+    for (row, values) in data.iter().enumerate() {
+        for (column, value) in values.iter().enumerate() {
+            model
+                .set_user_input(0, row as i32 + 1, column as i32 + 1, value)
+                .unwrap();
+        }
+    }
+    model
+}
+
+/// The area of the first sheet with its top left corner at (`row`, `column`)
+pub fn area(row: i32, column: i32, width: i32, height: i32) -> Area {
+    Area {
+        sheet: 0,
+        row,
+        column,
+        width,
+        height,
+    }
+}
+
+/// The rows of the first sheet in `rows` that are hidden
+pub fn hidden_rows(model: &Model, rows: std::ops::RangeInclusive<i32>) -> Vec<i32> {
+    rows.filter(|&row| model.is_row_hidden(0, row).unwrap())
+        .collect()
+}
+
 impl<'a> Model<'a> {
     pub fn _parse_reference(&self, cell: &str) -> CellReferenceIndex {
         if cell.contains('!') {
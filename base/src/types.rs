@@ -2,7 +2,16 @@ use bitcode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Display};
 
-use crate::{cf_types::ConditionalFormatting, expressions::token::Error};
+use crate::{
+    cf_types::ConditionalFormatting,
+    dv_types::DataValidation,
+    expressions::token::Error,
+    filter_types::{AutoFilter, Filter},
+    outline_types::OutlineProperties,
+    page_setup_types::PageSetup,
+    rich_text_types::RichTextRun,
+    sort_types::SortState,
+};
 
 fn default_as_false() -> bool {
     false
@@ -12,6 +21,10 @@ fn is_false(b: &bool) -> bool {
     !*b
 }
 
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone, Default)]
 #[serde(untagged)]
 pub enum Color {
@@ -102,10 +115,45 @@ pub struct Metadata {
     pub last_modified: String, //"2020-11-20T16:24:35"
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
 pub struct WorkbookSettings {
     pub tz: String,
     pub locale: String,
+    pub iterative_calculation: IterativeCalculation,
+    pub date_system: DateSystem,
+}
+
+/// The epoch used to convert serial numbers into dates.
+///
+/// In the 1900 date system (the default) the serial number 1 is 1900-01-01. In the 1904 date
+/// system, used by workbooks created in old versions of Excel for Mac, 0 is 1904-01-01.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DateSystem {
+    #[default]
+    Date1900,
+    Date1904,
+}
+
+/// Controls how formulas with circular references are computed.
+///
+/// When disabled a circular reference evaluates to `#CIRC!`. When enabled the cells in the
+/// cycle are recalculated using the values of the previous iteration until no value changes
+/// more than `max_change` or `max_iterations` is reached.
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub struct IterativeCalculation {
+    pub enabled: bool,
+    pub max_iterations: i32,
+    pub max_change: f64,
+}
+
+impl Default for IterativeCalculation {
+    fn default() -> Self {
+        IterativeCalculation {
+            enabled: false,
+            max_iterations: 100,
+            max_change: 0.001,
+        }
+    }
 }
 
 /// A Workbook View tracks of the selected sheet for each view
@@ -122,7 +170,10 @@ pub struct WorkbookView {
 /// An internal representation of an IronCalc Workbook
 #[derive(Encode, Decode, Debug, PartialEq, Clone)]
 pub struct Workbook {
+    /// The texts of the cells. For rich texts it is the plain text.
     pub shared_strings: Vec<String>,
+    /// The runs of the shared strings that are rich texts, by index
+    pub rich_text: HashMap<i32, Vec<RichTextRun>>,
     pub defined_names: Vec<DefinedName>,
     pub worksheets: Vec<Worksheet>,
     pub styles: Styles,
@@ -132,6 +183,34 @@ pub struct Workbook {
     pub tables: HashMap<String, Table>,
     pub views: HashMap<u32, WorkbookView>,
     pub theme: Theme,
+    pub external_links: Vec<ExternalLink>,
+    pub protection: Option<WorkbookProtection>,
+}
+
+/// A link to another workbook. In formulas `[1]Summary!C4` refers to the first link.
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub struct ExternalLink {
+    /// The path or url of the linked workbook, for instance "Budget.xlsx"
+    pub target: String,
+    /// The sheets of the linked workbook with the cached values of the referenced cells
+    pub sheets: Vec<ExternalSheet>,
+}
+
+/// A sheet of a linked workbook
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub struct ExternalSheet {
+    pub name: String,
+    /// The cached values by row and column
+    pub sheet_data: HashMap<i32, HashMap<i32, ExternalValue>>,
+}
+
+/// The value of a cell in a linked workbook
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub enum ExternalValue {
+    Number(f64),
+    Text(String),
+    Boolean(bool),
+    Error(Error),
 }
 
 /// A defined name. The `sheet_id` is the sheet index in case the name is local
@@ -220,8 +299,84 @@ pub struct Worksheet {
     /// Whether or not to show the grid lines in the worksheet
     pub show_grid_lines: bool,
     pub conditional_formatting: Vec<ConditionalFormatting>,
+    pub data_validations: Vec<DataValidation>,
     /// Hyperlinks in the worksheet, keyed by (row, column) of the cell they are attached to
     pub links: HashMap<(i32, i32), Link>,
+    /// If the sheet is protected, locked cells can't be edited
+    pub protection: Option<SheetProtection>,
+    /// The last sort applied to a range of the sheet
+    pub sort_state: Option<SortState>,
+    /// The autofilter of the sheet. Tables have their own.
+    pub auto_filter: Option<AutoFilter>,
+    /// Where the summary rows and columns of the groups are
+    pub outline_properties: OutlineProperties,
+    /// How the sheet is printed
+    pub page_setup: PageSetup,
+}
+
+/// The hash of a protection password. The password itself is never stored.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type")]
+pub enum PasswordHash {
+    /// The legacy 16 bit hash in hexadecimal, like the `password="CBEB"` attribute
+    Legacy { hash: String },
+    /// A salted hash, like the `algorithmName="SHA-512" hashValue="..." saltValue="..." spinCount="100000"` attributes.
+    /// Both the hash and the salt are base64 encoded.
+    Salted {
+        algorithm_name: String,
+        hash_value: String,
+        salt_value: String,
+        spin_count: u32,
+    },
+}
+
+/// Protection of a worksheet (ECMA-376-1:2016 section 18.3.1.85).
+/// While the sheet is protected locked cells can't be edited.
+/// The flags tell which operations are still allowed.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct SheetProtection {
+    pub password: Option<PasswordHash>,
+    pub select_locked_cells: bool,
+    pub select_unlocked_cells: bool,
+    pub format_cells: bool,
+    pub format_columns: bool,
+    pub format_rows: bool,
+    pub insert_columns: bool,
+    pub insert_rows: bool,
+    pub insert_hyperlinks: bool,
+    pub delete_columns: bool,
+    pub delete_rows: bool,
+    pub sort: bool,
+    pub auto_filter: bool,
+}
+
+impl Default for SheetProtection {
+    fn default() -> Self {
+        SheetProtection {
+            password: None,
+            select_locked_cells: true,
+            select_unlocked_cells: true,
+            format_cells: false,
+            format_columns: false,
+            format_rows: false,
+            insert_columns: false,
+            insert_rows: false,
+            insert_hyperlinks: false,
+            delete_columns: false,
+            delete_rows: false,
+            sort: false,
+            auto_filter: false,
+        }
+    }
+}
+
+/// Protection of the workbook (ECMA-376-1:2016 section 18.2.29).
+/// A locked structure means sheets can't be added, deleted, renamed, moved, hidden or unhidden.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+pub struct WorkbookProtection {
+    pub password: Option<PasswordHash>,
+    pub lock_structure: bool,
+    pub lock_windows: bool,
 }
 
 /// Internal representation of Excel's sheet_data
@@ -238,6 +393,10 @@ pub struct Row {
     pub custom_height: bool,
     pub s: i32,
     pub hidden: bool,
+    /// The outline level of the row, 0 if it is not in a group
+    pub outline_level: u8,
+    /// Whether the group next to the row, of which it is the summary, is collapsed
+    pub collapsed: bool,
 }
 
 // ECMA-376-1:2016 section 18.3.1.13
@@ -252,6 +411,10 @@ pub struct Col {
     pub custom_width: bool,
     pub hidden: bool,
     pub style: Option<i32>,
+    /// The outline level of the columns, 0 if they are not in a group
+    pub outline_level: u8,
+    /// Whether the group next to the columns, of which they are the summary, is collapsed
+    pub collapsed: bool,
 }
 
 /// Cell type enum matching Excel TYPE() function values.
@@ -363,16 +526,43 @@ impl Default for Cell {
     }
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+/// A comment attached to a cell, like "C4".
+///
+/// Plain comments (Excel calls them notes) are just a text and an author. Threaded comments
+/// also have an id, a timestamp and a list of replies, and their `author_id` is the id of the
+/// person that wrote them.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
 pub struct Comment {
     pub text: String,
     pub author_name: String,
     pub author_id: Option<String>,
     pub cell_ref: String,
+    pub thread: Option<CommentThread>,
+}
+
+/// The data of a threaded comment. Ids are GUIDs like "{4C6CD6B0-0C05-4C2B-9A8B-1B6D2B6D1E70}"
+/// and dates are ISO 8601 timestamps like "2024-03-05T10:20:30.00"
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct CommentThread {
+    pub id: String,
+    pub date: String,
+    /// Whether the thread has been resolved
+    pub done: bool,
+    pub replies: Vec<CommentReply>,
+}
+
+/// A reply in a threaded comment
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct CommentReply {
+    pub id: String,
+    pub text: String,
+    pub author_name: String,
+    pub author_id: Option<String>,
+    pub date: String,
 }
 
 // ECMA-376-1:2016 section 18.5.1.2
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
 pub struct Table {
     pub name: String,
     pub display_name: String,
@@ -390,7 +580,7 @@ pub struct Table {
 
 // totals_row_label vs totals_row_function might be mutually exclusive. Use an enum?
 // the totals_row_function is an enum not String methinks
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
 pub struct TableColumn {
     pub id: u32,
     pub name: String,
@@ -399,6 +589,12 @@ pub struct TableColumn {
     pub data_dxf_id: Option<u32>,
     pub totals_row_dxf_id: Option<u32>,
     pub totals_row_function: Option<String>,
+    /// The formula of the totals row when the function is "custom", without the leading `=`
+    pub totals_row_formula: Option<String>,
+    /// The formula shared by all the cells of a calculated column, without the leading `=`
+    pub calculated_column_formula: Option<String>,
+    /// The criteria of the autofilter of the table in this column
+    pub filter: Option<Filter>,
 }
 
 impl Default for TableColumn {
@@ -408,14 +604,17 @@ impl Default for TableColumn {
             name: "Column".to_string(),
             totals_row_label: None,
             totals_row_function: None,
+            totals_row_formula: None,
+            calculated_column_formula: None,
             data_dxf_id: None,
             header_row_dxf_id: None,
             totals_row_dxf_id: None,
+            filter: None,
         }
     }
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
 pub struct TableStyleInfo {
     pub name: Option<String>,
     pub show_first_column: bool,
@@ -485,6 +684,9 @@ impl Default for Styles {
 pub struct Style {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alignment: Option<Alignment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection: Option<Protection>,
     pub num_fmt: String,
     pub fill: Fill,
     pub font: Font,
@@ -496,6 +698,7 @@ impl Default for Style {
     fn default() -> Self {
         Style {
             alignment: None,
+            protection: None,
             num_fmt: "general".to_string(),
             fill: Fill::default(),
             font: Font::default(),
@@ -587,11 +790,144 @@ impl Default for Font {
     }
 }
 
+/// The fill of a cell. A solid fill of `color` unless it has a pattern or a gradient.
+/// There is no fill if the pattern is solid and there is no color.
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone, Default)]
 pub struct Fill {
+    /// The color of a solid fill, or the color of the lines and dots of a pattern
     #[serde(skip_serializing_if = "Color::is_none")]
     #[serde(default)]
     pub color: Color,
+    #[serde(skip_serializing_if = "PatternType::is_default")]
+    #[serde(default)]
+    pub pattern: PatternType,
+    /// The color behind the lines and dots of a pattern
+    #[serde(skip_serializing_if = "Color::is_none")]
+    #[serde(default)]
+    pub bg_color: Color,
+    /// If set the cell is filled with the gradient and not with the pattern
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub gradient: Option<GradientFill>,
+}
+
+/// The patterns of Excel's pattern fills, see section 18.18.55 of the standard
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternType {
+    #[default]
+    Solid,
+    MediumGray,
+    DarkGray,
+    LightGray,
+    DarkHorizontal,
+    DarkVertical,
+    DarkDown,
+    DarkUp,
+    DarkGrid,
+    DarkTrellis,
+    LightHorizontal,
+    LightVertical,
+    LightDown,
+    LightUp,
+    LightGrid,
+    LightTrellis,
+    Gray125,
+    Gray0625,
+}
+
+impl Display for PatternType {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            PatternType::Solid => "solid",
+            PatternType::MediumGray => "mediumGray",
+            PatternType::DarkGray => "darkGray",
+            PatternType::LightGray => "lightGray",
+            PatternType::DarkHorizontal => "darkHorizontal",
+            PatternType::DarkVertical => "darkVertical",
+            PatternType::DarkDown => "darkDown",
+            PatternType::DarkUp => "darkUp",
+            PatternType::DarkGrid => "darkGrid",
+            PatternType::DarkTrellis => "darkTrellis",
+            PatternType::LightHorizontal => "lightHorizontal",
+            PatternType::LightVertical => "lightVertical",
+            PatternType::LightDown => "lightDown",
+            PatternType::LightUp => "lightUp",
+            PatternType::LightGrid => "lightGrid",
+            PatternType::LightTrellis => "lightTrellis",
+            PatternType::Gray125 => "gray125",
+            PatternType::Gray0625 => "gray0625",
+        };
+        write!(formatter, "{name}")
+    }
+}
+
+impl PatternType {
+    fn is_default(&self) -> bool {
+        self == &PatternType::default()
+    }
+
+    /// Parses the name of the pattern, as written in xlsx files
+    pub fn from_name(name: &str) -> Option<PatternType> {
+        let pattern = match name {
+            "solid" => PatternType::Solid,
+            "mediumGray" => PatternType::MediumGray,
+            "darkGray" => PatternType::DarkGray,
+            "lightGray" => PatternType::LightGray,
+            "darkHorizontal" => PatternType::DarkHorizontal,
+            "darkVertical" => PatternType::DarkVertical,
+            "darkDown" => PatternType::DarkDown,
+            "darkUp" => PatternType::DarkUp,
+            "darkGrid" => PatternType::DarkGrid,
+            "darkTrellis" => PatternType::DarkTrellis,
+            "lightHorizontal" => PatternType::LightHorizontal,
+            "lightVertical" => PatternType::LightVertical,
+            "lightDown" => PatternType::LightDown,
+            "lightUp" => PatternType::LightUp,
+            "lightGrid" => PatternType::LightGrid,
+            "lightTrellis" => PatternType::LightTrellis,
+            "gray125" => PatternType::Gray125,
+            "gray0625" => PatternType::Gray0625,
+            _ => return None,
+        };
+        Some(pattern)
+    }
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientType {
+    /// The colors change along a line at an angle
+    #[default]
+    Linear,
+    /// The colors change from a rectangle inside the cell to the borders of the cell
+    Path,
+}
+
+/// A gradient fill, see section 18.8.24 of the standard
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone, Default)]
+pub struct GradientFill {
+    pub r#type: GradientType,
+    /// The angle of a linear gradient in degrees, 0 is left to right and 90 top to bottom
+    #[serde(default)]
+    pub degree: f64,
+    /// The rectangle where a path gradient starts, in fractions of the cell
+    #[serde(default)]
+    pub left: f64,
+    #[serde(default)]
+    pub right: f64,
+    #[serde(default)]
+    pub top: f64,
+    #[serde(default)]
+    pub bottom: f64,
+    pub stops: Vec<GradientStop>,
+}
+
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Clone)]
+pub struct GradientStop {
+    /// From 0 to 1
+    pub position: f64,
+    pub color: Color,
 }
 
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -675,6 +1011,72 @@ pub struct Alignment {
     #[serde(default = "default_as_false")]
     #[serde(skip_serializing_if = "is_false")]
     pub wrap_text: bool,
+    /// The rotation of the text: 0 to 90 are degrees counterclockwise, 91 to 180 are 1 to 90
+    /// degrees clockwise and 255 is vertical text, one letter under the other
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub text_rotation: i32,
+    /// The indentation of the text, in steps of three spaces, from 0 to 250
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub indent: i32,
+    /// The font is made smaller until the text fits in the cell
+    #[serde(default = "default_as_false")]
+    #[serde(skip_serializing_if = "is_false")]
+    pub shrink_to_fit: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "ReadingOrder::is_default")]
+    pub reading_order: ReadingOrder,
+    /// The last line of a `distributed` text is also distributed
+    #[serde(default = "default_as_false")]
+    #[serde(skip_serializing_if = "is_false")]
+    pub justify_last_line: bool,
+}
+
+/// The direction of the text
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadingOrder {
+    /// Given by the first strong character of the text
+    #[default]
+    ContextDependent,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl ReadingOrder {
+    fn is_default(&self) -> bool {
+        self == &ReadingOrder::default()
+    }
+}
+
+impl Display for ReadingOrder {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReadingOrder::ContextDependent => write!(formatter, "contextDependent"),
+            ReadingOrder::LeftToRight => write!(formatter, "leftToRight"),
+            ReadingOrder::RightToLeft => write!(formatter, "rightToLeft"),
+        }
+    }
+}
+
+/// Protection attributes of a cell. They only take effect when the sheet is protected.
+/// A missing `<protection>` element means the cell is locked and the formula visible.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct Protection {
+    /// The cell can't be edited
+    pub locked: bool,
+    /// The formula of the cell is not shown
+    pub hidden: bool,
+}
+
+impl Default for Protection {
+    fn default() -> Self {
+        Protection {
+            locked: true,
+            hidden: false,
+        }
+    }
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -752,6 +1154,7 @@ pub struct CellXfs {
     pub apply_fill: bool,
     pub quote_prefix: bool,
     pub alignment: Option<Alignment>,
+    pub protection: Option<Protection>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -288,6 +288,10 @@ impl<'a> Model<'a> {
             Node::WrongReferenceKind { .. } => None,
             Node::WrongRangeKind { .. } => None,
             Node::OpRangeKind { .. } => None,
+            Node::OpIntersectionKind { .. } => None,
+            Node::OpUnionKind { .. } => None,
+            Node::Range3DKind { .. } => None,
+            Node::ExternalReferenceKind { .. } => None,
             Node::OpConcatenateKind { .. } => None,
             Node::ErrorKind(_) => None,
             Node::ParseErrorKind { .. } => None,
@@ -296,6 +300,7 @@ impl<'a> Model<'a> {
             Node::ArrayKind(_) => None,
             Node::DefinedNameKind(_) => None,
             Node::TableNameKind(_) => None,
+            Node::StructuredReferenceKind { .. } => None,
             Node::NamedVariableKind { .. } => None,
             Node::CompareKind { .. } => None,
             Node::OpPowerKind { .. } => None,
@@ -0,0 +1,125 @@
+use crate::{
+    expressions::types::Area,
+    filter_types::{AutoFilter, Filter},
+};
+
+use super::{common::UserModel, history::Diff};
+
+fn row_hidden_diffs(sheet: u32, changes: &[(i32, bool)]) -> Vec<Diff> {
+    changes
+        .iter()
+        .map(|&(row, hidden)| Diff::SetRowHidden {
+            sheet,
+            row,
+            new_value: hidden,
+            old_value: !hidden,
+        })
+        .collect()
+}
+
+impl<'a> UserModel<'a> {
+    /// Returns the autofilter of the sheet, if any
+    pub fn get_auto_filter(&self, sheet: u32) -> Result<Option<AutoFilter>, String> {
+        self.model.get_auto_filter(sheet)
+    }
+
+    /// Adds an autofilter to the sheet in `area`, replacing the previous one
+    ///
+    /// See also:
+    /// * [Model::set_auto_filter](crate::Model::set_auto_filter)
+    pub fn set_auto_filter(&mut self, area: &Area) -> Result<(), String> {
+        let sheet = area.sheet;
+        self.model.check_sheet_operation(sheet, |p| p.auto_filter)?;
+        let old_value = self.model.get_auto_filter(sheet)?;
+        let shown = self.model.set_auto_filter(area)?;
+        let new_value = self.model.get_auto_filter(sheet)?;
+        self.push_auto_filter_diffs(sheet, old_value, new_value, &shown)
+    }
+
+    /// Removes the autofilter of the sheet and shows the rows it filtered
+    pub fn remove_auto_filter(&mut self, sheet: u32) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |p| p.auto_filter)?;
+        let old_value = self.model.get_auto_filter(sheet)?;
+        if old_value.is_none() {
+            return Ok(());
+        }
+        let shown = self.model.remove_auto_filter(sheet)?;
+        self.push_auto_filter_diffs(sheet, old_value, None, &shown)
+    }
+
+    fn push_auto_filter_diffs(
+        &mut self,
+        sheet: u32,
+        old_value: Option<AutoFilter>,
+        new_value: Option<AutoFilter>,
+        shown: &[i32],
+    ) -> Result<(), String> {
+        let changes: Vec<(i32, bool)> = shown.iter().map(|&row| (row, false)).collect();
+        let mut diff_list = row_hidden_diffs(sheet, &changes);
+        diff_list.push(Diff::SetAutoFilter {
+            sheet,
+            old_value,
+            new_value,
+        });
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Returns the criteria of the autofilter in the column of the cell, if any
+    pub fn get_filter(&self, sheet: u32, row: i32, column: i32) -> Result<Option<Filter>, String> {
+        self.model.get_filter(sheet, row, column)
+    }
+
+    /// Sets (or removes if `None`) the criteria of the autofilter in the column of the cell.
+    /// The cell can be in a table or in the autofilter of the sheet.
+    ///
+    /// See also:
+    /// * [Model::set_filter](crate::Model::set_filter)
+    pub fn set_filter(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        filter: Option<Filter>,
+    ) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |p| p.auto_filter)?;
+        let table = match self.model.get_table_name_at(sheet, row, column)? {
+            Some(name) => Some(self.model.get_table(&name)?),
+            None => None,
+        };
+        let old_auto_filter = self.model.get_auto_filter(sheet)?;
+        let changes = self.model.set_filter(sheet, row, column, filter)?;
+        let mut diff_list = vec![match table {
+            Some(old_table) => {
+                let new_table = self.model.get_table(&old_table.name)?;
+                Diff::UpdateTable {
+                    old_value: Box::new(Some(old_table)),
+                    new_value: Box::new(Some(new_table)),
+                }
+            }
+            None => Diff::SetAutoFilter {
+                sheet,
+                old_value: old_auto_filter,
+                new_value: self.model.get_auto_filter(sheet)?,
+            },
+        }];
+        diff_list.extend(row_hidden_diffs(sheet, &changes));
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Applies again the autofilters of the sheet and of its tables.
+    /// Rows are hidden or shown according to the current values of the cells.
+    pub fn reapply_filters(&mut self, sheet: u32) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |p| p.auto_filter)?;
+        let changes = self.model.apply_filters(sheet)?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        self.push_diff_list(row_hidden_diffs(sheet, &changes));
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+}
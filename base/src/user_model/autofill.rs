@@ -121,6 +121,22 @@ impl<'a> UserModel<'a> {
             return Err(format!("Invalid row: '{to_row}'"));
         }
 
+        // The filled cells can't be locked
+        let (first_row, end_row) = if to_row > last_row {
+            (last_row + 1, to_row)
+        } else {
+            (to_row, row1 - 1)
+        };
+        if first_row <= end_row {
+            self.model.check_cells_editable(
+                sheet,
+                first_row,
+                column1,
+                width,
+                end_row - first_row + 1,
+            )?;
+        }
+
         // anchor_row is the first row that repeats in each case.
         let anchor_row;
         let sign;
@@ -263,6 +279,22 @@ impl<'a> UserModel<'a> {
             return Err(format!("Invalid column: '{to_column}'"));
         }
 
+        // The filled cells can't be locked
+        let (first_column, end_column) = if to_column > last_column {
+            (last_column + 1, to_column)
+        } else {
+            (to_column, column1 - 1)
+        };
+        if first_column <= end_column {
+            self.model.check_cells_editable(
+                sheet,
+                row1,
+                first_column,
+                end_column - first_column + 1,
+                height,
+            )?;
+        }
+
         // anchor_column is the first column that repeats in each case.
         let anchor_column;
         let sign;
@@ -353,6 +353,13 @@ impl<'a> UserModel<'a> {
         let first_column = range.column;
         let last_row = first_row + range.height - 1;
         let last_column = first_column + range.width - 1;
+        self.model.check_cells_formattable(
+            sheet,
+            first_row,
+            first_column,
+            range.width,
+            range.height,
+        )?;
         if first_row == 1 && last_row == LAST_ROW {
             // full columns
             self.set_columns_with_border(sheet, first_column, last_column, border_area)?;
@@ -8,16 +8,26 @@ use std::{
 use csv::{ReaderBuilder, WriterBuilder};
 use serde::{Deserialize, Serialize};
 
+use bitcode::{Decode, Encode};
+
 use crate::{
+    cell::CellValue,
     cf_types::ConditionalFormatting,
-    expressions::types::{Area, CellReferenceIndex},
-    model::CellStructure,
-    types::{ArrayKind, Cell, Link, Style},
+    expressions::{
+        parser::stringify::{collect_named_variables_in_node, to_localized_string},
+        token::Error,
+        types::{Area, CellReferenceIndex, CellReferenceRC},
+    },
+    model::{CellStructure, Model},
+    rich_text_types::RichTextRun,
+    types::{ArrayKind, Cell, CellType, Link, Style, StyleIncludes},
     UserModel,
 };
 
 use crate::user_model::history::Diff;
 
+use super::clipboard_html::html_cell;
+
 /// Data for the clipboard
 pub type ClipboardData = HashMap<i32, HashMap<i32, ClipboardCell>>;
 
@@ -32,14 +42,165 @@ pub struct ClipboardCell {
     // payloads without the field deserializable)
     #[serde(default)]
     link: Option<Link>,
+    // the runs of the text if it is a rich text
+    #[serde(default)]
+    rich_text: Option<Vec<RichTextRun>>,
+    // the evaluated value, for pasting values only
+    #[serde(default)]
+    value: Option<ClipboardValue>,
+    // the named style the format of the cell derives from, if not "Normal"
+    #[serde(default)]
+    named_style: Option<String>,
+}
+
+/// The evaluated value of a copied cell
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq)]
+pub enum ClipboardValue {
+    /// A number
+    Number(f64),
+    /// A text, it is never parsed as a number or a formula
+    Text(String),
+    /// TRUE or FALSE
+    Boolean(bool),
+    /// An error in the language of the workbook, like #DIV/0!
+    Error(String),
+}
+
+impl ClipboardValue {
+    pub(crate) fn set_in_cell(
+        &self,
+        model: &mut Model,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<(), String> {
+        model.prepare_cell_for_user_input(sheet, row, column)?;
+        match self {
+            ClipboardValue::Number(value) => {
+                model.update_cell_with_number(sheet, row, column, *value)
+            }
+            ClipboardValue::Text(value) => model.update_cell_with_text(sheet, row, column, value),
+            ClipboardValue::Boolean(value) => {
+                model.update_cell_with_bool(sheet, row, column, *value)
+            }
+            ClipboardValue::Error(value) => {
+                model.set_user_input(sheet, row, column, value.to_string())
+            }
+        }
+    }
+}
+
+/// What is pasted with [UserModel::paste_special_from_clipboard]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteType {
+    /// Values, formulas, styles and links, like a normal paste
+    #[default]
+    All,
+    /// The evaluated values instead of the formulas
+    Values,
+    /// Only the styles (and conditional formatting)
+    Formats,
+    /// Values and formulas, without styles
+    Formulas,
+    /// Only the widths of the copied columns
+    ColumnWidths,
+}
+
+/// Arithmetic operation between the copied values and the values of the destination
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteOperation {
+    /// The copied values replace the destination
+    #[default]
+    None,
+    /// destination + copied value
+    Add,
+    /// destination - copied value
+    Subtract,
+    /// destination * copied value
+    Multiply,
+    /// destination / copied value
+    Divide,
+}
+
+/// The options of a paste special
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct PasteOptions {
+    /// What is pasted
+    #[serde(default)]
+    pub paste: PasteType,
+    /// Combine the copied values with the values of the destination
+    #[serde(default)]
+    pub operation: PasteOperation,
+    /// Empty copied cells leave the destination untouched
+    #[serde(default)]
+    pub skip_blanks: bool,
+    /// Rows become columns and columns become rows
+    #[serde(default)]
+    pub transpose: bool,
+}
+
+impl PasteOperation {
+    fn symbol(self) -> Option<char> {
+        match self {
+            PasteOperation::None => None,
+            PasteOperation::Add => Some('+'),
+            PasteOperation::Subtract => Some('-'),
+            PasteOperation::Multiply => Some('*'),
+            PasteOperation::Divide => Some('/'),
+        }
+    }
+
+    fn apply(self, left: f64, right: f64) -> Option<f64> {
+        let result = match self {
+            PasteOperation::None => right,
+            PasteOperation::Add => left + right,
+            PasteOperation::Subtract => left - right,
+            PasteOperation::Multiply => left * right,
+            PasteOperation::Divide => {
+                if right == 0.0 {
+                    return None;
+                }
+                left / right
+            }
+        };
+        Some(result)
+    }
+}
+
+impl ClipboardCell {
+    fn is_blank(&self) -> bool {
+        !self.is_spill && self.text.is_empty()
+    }
 }
 
+/// A copy of a range of cells, as returned by [UserModel::copy_to_clipboard]
 #[derive(Serialize, Deserialize)]
 pub struct Clipboard {
     pub(crate) csv: String,
+    // the cells as an HTML table with the styles inlined
+    #[serde(default)]
+    pub(crate) html: String,
     pub(crate) data: ClipboardData,
     pub(crate) sheet: u32,
     pub(crate) range: (i32, i32, i32, i32),
+    // what is needed to paste the cells in another workbook
+    #[serde(default)]
+    pub(crate) origin: ClipboardOrigin,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ClipboardOrigin {
+    // the name of the workbook
+    workbook: String,
+    // the language and locale the formulas are written in
+    language: String,
+    locale: String,
+    // the workbook scoped defined names and their (internal) formulas
+    defined_names: Vec<(String, String)>,
+    // the definitions of the named styles used by the copied cells
+    named_styles: Vec<(String, Style, StyleIncludes)>,
 }
 
 impl<'a> UserModel<'a> {
@@ -50,13 +211,17 @@ impl<'a> UserModel<'a> {
         let mut wtr = WriterBuilder::new().delimiter(b'\t').from_writer(vec![]);
 
         let mut data = HashMap::new();
+        let mut named_styles = Vec::new();
         let [row_start, column_start, row_end, column_end] = selected_area.range;
         let dimension = self.model.workbook.worksheet(sheet)?.dimension();
         let row_end = row_end.min(dimension.max_row).max(row_start);
         let column_end = column_end.min(dimension.max_column).max(column_start);
+        let merged_cells = self.model.get_merged_cells(sheet)?;
+        let mut html = String::from("<table><tbody>");
         for row in row_start..=row_end {
             let mut data_row = HashMap::new();
             let mut text_row = Vec::new();
+            html.push_str("<tr>");
             for column in column_start..=column_end {
                 let text = self.get_formatted_cell_value(sheet, row, column)?;
                 let content = self.get_cell_content(sheet, row, column)?;
@@ -66,6 +231,30 @@ impl<'a> UserModel<'a> {
                     CellStructure::SpillArray { .. } | CellStructure::SpillDynamic { .. }
                 );
                 let link = self.model.get_cell_link(sheet, row, column)?;
+                let rich_text = self.model.get_cell_rich_text(sheet, row, column)?;
+                let style_index = self.model.get_cell_style_index(sheet, row, column)?;
+                let named_style = self.model.workbook.styles.get_style_name(style_index);
+                if let Some(name) = &named_style {
+                    if !named_styles.iter().any(|(n, _, _)| n == name) {
+                        named_styles.push((
+                            name.clone(),
+                            self.model.get_named_style(name)?,
+                            self.model.get_named_style_includes(name)?,
+                        ));
+                    }
+                }
+                let value = match self.model.get_cell_value_by_index(sheet, row, column)? {
+                    CellValue::None => None,
+                    CellValue::Number(value) => Some(ClipboardValue::Number(value)),
+                    CellValue::Boolean(value) => Some(ClipboardValue::Boolean(value)),
+                    CellValue::String(value) => {
+                        if self.model.get_cell_type(sheet, row, column)? == CellType::ErrorValue {
+                            Some(ClipboardValue::Error(value))
+                        } else {
+                            Some(ClipboardValue::Text(value))
+                        }
+                    }
+                };
                 data_row.insert(
                     column,
                     ClipboardCell {
@@ -73,10 +262,42 @@ impl<'a> UserModel<'a> {
                         is_spill,
                         style,
                         link,
+                        rich_text,
+                        value,
+                        named_style,
                     },
                 );
+                // Merged cells span several columns and rows, clipped to the copied area
+                let merged_area = merged_cells.iter().find(|area| {
+                    (area.row..area.row + area.height).contains(&row)
+                        && (area.column..area.column + area.width).contains(&column)
+                });
+                let span = match merged_area {
+                    // covered by the top left cell of the merged area
+                    Some(area)
+                        if (area.row.max(row_start), area.column.max(column_start))
+                            != (row, column) =>
+                    {
+                        None
+                    }
+                    Some(area) => Some((
+                        (area.column + area.width - 1).min(column_end) - column + 1,
+                        (area.row + area.height - 1).min(row_end) - row + 1,
+                    )),
+                    None => Some((1, 1)),
+                };
+                if let Some((colspan, rowspan)) = span {
+                    html.push_str(&html_cell(
+                        &text,
+                        &data_row[&column].style,
+                        &self.model.workbook.theme,
+                        colspan,
+                        rowspan,
+                    ));
+                }
                 text_row.push(text);
             }
+            html.push_str("</tr>");
             wtr.write_record(text_row)
                 .map_err(|e| format!("Error while processing csv: {e}"))?;
             data.insert(row, data_row);
@@ -88,11 +309,31 @@ impl<'a> UserModel<'a> {
         )
         .map_err(|e| format!("Error converting from utf8: '{e}'"))?;
 
+        let defined_names = self
+            .model
+            .workbook
+            .get_defined_names_with_scope()
+            .into_iter()
+            .filter(|(_, scope, _)| scope.is_none())
+            .map(|(name, _, formula)| (name, formula))
+            .collect();
+        let origin = ClipboardOrigin {
+            workbook: self.model.workbook.name.clone(),
+            language: self.model.get_language(),
+            locale: self.model.get_locale(),
+            defined_names,
+            named_styles,
+        };
+
+        html.push_str("</tbody></table>");
+
         Ok(Clipboard {
             csv: csv.trim().to_string(),
+            html,
             data,
             sheet,
             range: (row_start, column_start, row_end, column_end),
+            origin,
         })
     }
 
@@ -112,8 +353,14 @@ impl<'a> UserModel<'a> {
         let [selected_row, selected_column, _, _] = view.range;
         let mut max_row = selected_row;
         let mut max_column = selected_column;
+        // Formulas are read in the sheet they were copied from (that sheet might be gone by now)
+        let source_sheet = if is_cut || self.model.workbook.worksheet(source_sheet).is_ok() {
+            source_sheet
+        } else {
+            sheet
+        };
         let area = &Area {
-            sheet,
+            sheet: source_sheet,
             row: source_first_row,
             column: source_first_column,
             width: source_last_column - source_first_column + 1,
@@ -126,6 +373,22 @@ impl<'a> UserModel<'a> {
             width: source_last_column - source_first_column + 1,
             height: source_last_row - source_first_row + 1,
         };
+        self.model.check_cells_editable(
+            sheet,
+            target_area.row,
+            target_area.column,
+            target_area.width,
+            target_area.height,
+        )?;
+        if is_cut {
+            self.model.check_cells_editable(
+                source_sheet,
+                area.row,
+                area.column,
+                area.width,
+                area.height,
+            )?;
+        }
 
         let mut seen_cells = HashSet::new();
         // Compute all changes
@@ -151,6 +414,7 @@ impl<'a> UserModel<'a> {
                         old_style,
                         None,
                         value.style.clone(),
+                        None,
                     ));
                     seen_cells.insert((target_row, target_column));
                     continue;
@@ -162,7 +426,7 @@ impl<'a> UserModel<'a> {
 
                 // remain in the copied area
                 let source = &CellReferenceIndex {
-                    sheet,
+                    sheet: source_sheet,
                     column: *source_column,
                     row: *source_row,
                 };
@@ -196,6 +460,7 @@ impl<'a> UserModel<'a> {
                     old_style.clone(),
                     Some(new_value.clone()),
                     value.style.clone(),
+                    value.rich_text.clone(),
                 ));
                 seen_cells.insert((target_row, target_column));
             }
@@ -205,8 +470,20 @@ impl<'a> UserModel<'a> {
         // clear the whole area (this resets array formulas)
         self.model.range_clear_contents(target_area)?;
         // set the new values and styles
-        for (target_row, target_column, old_value, old_style, new_value, style) in changes {
-            if let Some(ref v) = new_value {
+        for (target_row, target_column, old_value, old_style, new_value, style, rich_text) in
+            changes
+        {
+            if let Some(runs) = rich_text {
+                self.model
+                    .set_cell_rich_text(sheet, target_row, target_column, runs.clone())?;
+                diff_list.push(Diff::SetCellRichText {
+                    sheet,
+                    row: target_row,
+                    column: target_column,
+                    new_value: runs,
+                    old_value: Box::new(old_value),
+                });
+            } else if let Some(ref v) = new_value {
                 self.model
                     .set_user_input(sheet, target_row, target_column, v.clone())?;
                 diff_list.push(Diff::SetCellValue {
@@ -468,6 +745,559 @@ impl<'a> UserModel<'a> {
         Ok(())
     }
 
+    /// Pastes cells copied from another workbook in the selected cell. A cut is pasted as a copy.
+    ///
+    /// Formulas are read in the language of the source workbook and their relative references
+    /// are displaced to the new location. References to sheets that do not exist in this workbook
+    /// point to the source workbook: `=Sheet3!A1` is pasted as `='[Book1]Sheet3'!A1`.
+    /// The workbook scoped defined names and the named styles used by the copied cells are added
+    /// if missing. If they already exist the definitions in this workbook are kept.
+    pub fn paste_from_external_clipboard(&mut self, clipboard: &Clipboard) -> Result<(), String> {
+        let view = self.get_selected_view();
+        let sheet = view.sheet;
+        let [selected_row, selected_column, _, _] = view.range;
+        let (source_first_row, source_first_column, source_last_row, source_last_column) =
+            clipboard.range;
+        let width = source_last_column - source_first_column + 1;
+        let height = source_last_row - source_first_row + 1;
+        self.model
+            .check_cells_editable(sheet, selected_row, selected_column, width, height)?;
+        let origin = &clipboard.origin;
+        let origin_names = (
+            origin.workbook.as_str(),
+            origin.language.as_str(),
+            origin.locale.as_str(),
+        );
+        let sheet_name = self.model.workbook.worksheet(sheet)?.get_name();
+        let mut diff_list = Vec::new();
+
+        for (name, style, includes) in &origin.named_styles {
+            if self.model.workbook.styles.get_xf_id_by_name(name).is_err() {
+                self.model.create_named_style(name, style, *includes)?;
+                diff_list.push(Diff::CreateNamedStyle {
+                    name: name.clone(),
+                    style: Box::new(style.clone()),
+                    includes: *includes,
+                });
+            }
+        }
+
+        // Unqualified references in the formulas refer to the sheet they are pasted in
+        let source_context = |row: &i32, column: &i32| CellReferenceRC {
+            sheet: sheet_name.clone(),
+            row: *row,
+            column: *column,
+        };
+        let mut unknown_names = Vec::new();
+        for (source_row, data_row) in &clipboard.data {
+            for (source_column, value) in data_row {
+                if let Some(node) = self.model.parse_external_copied_formula(
+                    &value.text,
+                    &source_context(source_row, source_column),
+                    origin_names,
+                ) {
+                    collect_named_variables_in_node(&node, &mut unknown_names);
+                }
+            }
+        }
+        for (name, formula) in &origin.defined_names {
+            let name_lower = name.to_lowercase();
+            if !unknown_names.iter().any(|n| n.to_lowercase() == name_lower) {
+                continue;
+            }
+            // A name that cannot be defined here (it clashes with a sheet scoped one, for
+            // instance) is left out and the formulas using it evaluate to #NAME?
+            if self.model.new_defined_name(name, None, formula).is_ok() {
+                let value = self.model.get_defined_name_formula(name, None)?;
+                diff_list.push(Diff::CreateDefinedName {
+                    name: name.clone(),
+                    scope: None,
+                    value,
+                });
+            }
+        }
+
+        let target_area = &Area {
+            sheet,
+            row: selected_row,
+            column: selected_column,
+            width,
+            height,
+        };
+        let mut old_values = Vec::new();
+        for row in selected_row..selected_row + height {
+            let mut old_row = Vec::new();
+            for column in selected_column..selected_column + width {
+                old_row.push(
+                    self.model
+                        .workbook
+                        .worksheet(sheet)?
+                        .cell(row, column)
+                        .cloned(),
+                );
+            }
+            old_values.push(old_row);
+        }
+        diff_list.extend(self.range_link_diffs(target_area)?);
+        self.model.range_clear_contents(target_area)?;
+        diff_list.push(Diff::RangeClearContents {
+            sheet,
+            row: selected_row,
+            column: selected_column,
+            width,
+            height,
+            old_value: old_values,
+        });
+
+        for (source_row, data_row) in &clipboard.data {
+            let row = selected_row + (source_row - source_first_row);
+            for (source_column, value) in data_row {
+                let column = selected_column + (source_column - source_first_column);
+                let old_value = self
+                    .model
+                    .workbook
+                    .worksheet(sheet)?
+                    .cell(row, column)
+                    .cloned();
+                let formula = self.model.parse_external_copied_formula(
+                    &value.text,
+                    &source_context(source_row, source_column),
+                    origin_names,
+                );
+                if value.is_spill {
+                    // Spill cells only carry their style
+                } else if let Some(runs) = &value.rich_text {
+                    self.model
+                        .set_cell_rich_text(sheet, row, column, runs.clone())?;
+                    diff_list.push(Diff::SetCellRichText {
+                        sheet,
+                        row,
+                        column,
+                        new_value: runs.clone(),
+                        old_value: Box::new(old_value),
+                    });
+                } else if let Some(node) = formula {
+                    let context = CellReferenceRC {
+                        sheet: sheet_name.clone(),
+                        row,
+                        column,
+                    };
+                    let new_value = format!(
+                        "={}",
+                        to_localized_string(
+                            &node,
+                            &context,
+                            self.model.locale,
+                            self.model.language
+                        )
+                    );
+                    self.model
+                        .set_user_input(sheet, row, column, new_value.clone())?;
+                    diff_list.push(Diff::SetCellValue {
+                        sheet,
+                        row,
+                        column,
+                        new_value,
+                        old_value: Box::new(old_value),
+                    });
+                } else if let Some(new_value) = &value.value {
+                    // Values are pasted as they are, the text might be in another locale
+                    new_value.set_in_cell(&mut self.model, sheet, row, column)?;
+                    diff_list.push(Diff::SetCellPastedValue {
+                        sheet,
+                        row,
+                        column,
+                        new_value: new_value.clone(),
+                        old_value: Box::new(old_value),
+                    });
+                }
+
+                let old_style = self.model.get_cell_style_or_none(sheet, row, column)?;
+                self.model
+                    .set_cell_style(sheet, row, column, &value.style)?;
+                diff_list.push(Diff::SetCellStyle {
+                    sheet,
+                    row,
+                    column,
+                    old_value: Box::new(old_style),
+                    new_value: Box::new(value.style.clone()),
+                });
+                if let Some(name) = &value.named_style {
+                    let current_index =
+                        self.model.workbook.worksheet(sheet)?.get_style(row, column);
+                    let style_index = self
+                        .model
+                        .workbook
+                        .styles
+                        .get_style_index_for_applied_style(name, current_index)?;
+                    self.model.workbook.worksheet_mut(sheet)?.set_cell_style(
+                        row,
+                        column,
+                        style_index,
+                    )?;
+                    diff_list.push(Diff::ApplyNamedStyle {
+                        sheet,
+                        row,
+                        column,
+                        old_value: Box::new(Some(value.style.clone())),
+                        name: name.clone(),
+                    });
+                }
+
+                if let Some(link) = &value.link {
+                    let old_link = self.model.get_cell_link(sheet, row, column)?;
+                    if old_link.as_ref() != Some(link) {
+                        self.model.set_cell_link(sheet, row, column, link.clone())?;
+                        diff_list.push(Diff::SetCellLink {
+                            sheet,
+                            row,
+                            column,
+                            old_value: Box::new(old_link),
+                            new_value: Box::new(Some(link.clone())),
+                        });
+                    }
+                }
+            }
+        }
+        self.push_diff_list(diff_list);
+        self.set_selected_range(
+            selected_row,
+            selected_column,
+            selected_row + height - 1,
+            selected_column + width - 1,
+        )?;
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Pastes the copied cells with the options of a paste special: values only, formats only,
+    /// transposed, combined with the destination values... All the changes are undone at once.
+    ///
+    /// With an operation, the copied values are combined with the numbers of the destination.
+    /// Empty cells count as zero, texts are left untouched and formulas in the destination are
+    /// wrapped: `=(A1*2)+5`.
+    pub fn paste_special_from_clipboard(
+        &mut self,
+        source_sheet: u32,
+        source_range: ClipboardTuple,
+        clipboard: &ClipboardData,
+        options: &PasteOptions,
+    ) -> Result<(), String> {
+        let view = self.get_selected_view();
+        let sheet = view.sheet;
+        let [selected_row, selected_column, _, _] = view.range;
+        let (source_first_row, source_first_column, source_last_row, source_last_column) =
+            source_range;
+        let width = source_last_column - source_first_column + 1;
+        let height = source_last_row - source_first_row + 1;
+        let mut diff_list = Vec::new();
+
+        if options.paste == PasteType::ColumnWidths {
+            for delta in 0..width {
+                let new_value = self
+                    .model
+                    .get_column_width(source_sheet, source_first_column + delta)?;
+                let column = selected_column + delta;
+                let old_value = self.model.get_column_width(sheet, column)?;
+                if old_value != new_value {
+                    self.model.set_column_width(sheet, column, new_value)?;
+                    diff_list.push(Diff::SetColumnWidth {
+                        sheet,
+                        column,
+                        new_value,
+                        old_value,
+                    });
+                }
+            }
+            self.push_diff_list(diff_list);
+            return Ok(());
+        }
+
+        let (target_width, target_height) = if options.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        self.model.check_cells_editable(
+            sheet,
+            selected_row,
+            selected_column,
+            target_width,
+            target_height,
+        )?;
+        let paste_content = options.paste != PasteType::Formats;
+        let paste_style = matches!(options.paste, PasteType::All | PasteType::Formats);
+        let mut rows: Vec<&i32> = clipboard.keys().collect();
+        rows.sort();
+        for source_row in rows {
+            let data_row = &clipboard[source_row];
+            let mut columns: Vec<&i32> = data_row.keys().collect();
+            columns.sort();
+            for source_column in columns {
+                let value = &data_row[source_column];
+                if options.skip_blanks && value.is_blank() {
+                    continue;
+                }
+                let (delta_row, delta_column) = if options.transpose {
+                    (
+                        source_column - source_first_column,
+                        source_row - source_first_row,
+                    )
+                } else {
+                    (
+                        source_row - source_first_row,
+                        source_column - source_first_column,
+                    )
+                };
+                let row = selected_row + delta_row;
+                let column = selected_column + delta_column;
+                if paste_content && !value.is_spill {
+                    self.paste_special_content(
+                        (source_sheet, *source_row, *source_column),
+                        (sheet, row, column),
+                        value,
+                        options,
+                        &mut diff_list,
+                    )?;
+                }
+                if paste_style {
+                    let old_value = self.model.get_cell_style_or_none(sheet, row, column)?;
+                    self.model
+                        .set_cell_style(sheet, row, column, &value.style)?;
+                    diff_list.push(Diff::SetCellStyle {
+                        sheet,
+                        row,
+                        column,
+                        old_value: Box::new(old_value),
+                        new_value: Box::new(value.style.clone()),
+                    });
+                }
+                if options.paste == PasteType::All {
+                    let old_link = self.model.get_cell_link(sheet, row, column)?;
+                    if old_link != value.link {
+                        match &value.link {
+                            Some(link) => {
+                                self.model.set_cell_link(sheet, row, column, link.clone())?
+                            }
+                            None => self.model.delete_cell_link(sheet, row, column)?,
+                        }
+                        diff_list.push(Diff::SetCellLink {
+                            sheet,
+                            row,
+                            column,
+                            old_value: Box::new(old_link),
+                            new_value: Box::new(value.link.clone()),
+                        });
+                    }
+                }
+            }
+        }
+        if paste_style && !options.transpose {
+            let cf_copies = self.model.get_cf_rules_to_copy(
+                source_sheet,
+                source_first_row,
+                source_first_column,
+                source_last_row,
+                source_last_column,
+                selected_row,
+                selected_column,
+            );
+            for (range, rule) in cf_copies {
+                let worksheet = self.model.workbook.worksheet_mut(sheet)?;
+                let priority = worksheet
+                    .conditional_formatting
+                    .iter()
+                    .map(|cf| cf.priority)
+                    .max()
+                    .map(|m| m + 1)
+                    .unwrap_or(1);
+                worksheet
+                    .conditional_formatting
+                    .push(ConditionalFormatting {
+                        range: range.clone(),
+                        cf_rule: rule.clone(),
+                        priority,
+                    });
+                diff_list.push(Diff::AddConditionalFormatting {
+                    sheet,
+                    range,
+                    rule: Box::new(rule),
+                    priority,
+                });
+            }
+        }
+        self.push_diff_list(diff_list);
+        self.set_selected_range(
+            selected_row,
+            selected_column,
+            selected_row + target_height - 1,
+            selected_column + target_width - 1,
+        )?;
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    // Pastes the value or formula of a copied cell for a paste special
+    fn paste_special_content(
+        &mut self,
+        (source_sheet, source_row, source_column): (u32, i32, i32),
+        (sheet, row, column): (u32, i32, i32),
+        value: &ClipboardCell,
+        options: &PasteOptions,
+        diff_list: &mut Vec<Diff>,
+    ) -> Result<(), String> {
+        let old_value = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .cell(row, column)
+            .cloned();
+        if let Some(symbol) = options.operation.symbol() {
+            let operand = match &value.value {
+                Some(ClipboardValue::Number(number)) => *number,
+                None => 0.0,
+                // Texts, booleans and errors are not combined
+                Some(_) => return Ok(()),
+            };
+            if let Some(formula) = self.model.get_cell_formula(sheet, row, column)? {
+                let decimal = &self.model.locale.numbers.symbols.decimal;
+                let operand = operand.to_string().replace('.', decimal);
+                let new_value = format!("=({}){symbol}{operand}", &formula[1..]);
+                self.model
+                    .set_user_input(sheet, row, column, new_value.clone())?;
+                diff_list.push(Diff::SetCellValue {
+                    sheet,
+                    row,
+                    column,
+                    new_value,
+                    old_value: Box::new(old_value),
+                });
+                return Ok(());
+            }
+            let destination = match self.model.get_cell_value_by_index(sheet, row, column)? {
+                CellValue::Number(number) => number,
+                CellValue::None => 0.0,
+                _ => return Ok(()),
+            };
+            let new_value = match options.operation.apply(destination, operand) {
+                Some(result) => ClipboardValue::Number(result),
+                None => {
+                    ClipboardValue::Error(Error::DIV.to_localized_error_string(self.model.language))
+                }
+            };
+            return self.set_pasted_value(sheet, row, column, new_value, old_value, diff_list);
+        }
+        match options.paste {
+            PasteType::Values => match &value.value {
+                Some(new_value) => self.set_pasted_value(
+                    sheet,
+                    row,
+                    column,
+                    new_value.clone(),
+                    old_value,
+                    diff_list,
+                ),
+                None => self.clear_pasted_cell(sheet, row, column, diff_list),
+            },
+            _ => {
+                if value.is_blank() {
+                    return self.clear_pasted_cell(sheet, row, column, diff_list);
+                }
+                if let (Some(runs), PasteType::All) = (&value.rich_text, options.paste) {
+                    self.model
+                        .set_cell_rich_text(sheet, row, column, runs.clone())?;
+                    diff_list.push(Diff::SetCellRichText {
+                        sheet,
+                        row,
+                        column,
+                        new_value: runs.clone(),
+                        old_value: Box::new(old_value),
+                    });
+                    return Ok(());
+                }
+                let source = &CellReferenceIndex {
+                    sheet: source_sheet,
+                    row: source_row,
+                    column: source_column,
+                };
+                let target = &CellReferenceIndex { sheet, row, column };
+                let new_value = if options.transpose {
+                    self.model
+                        .transpose_copied_value(&value.text, source, target)?
+                } else {
+                    self.model
+                        .extend_copied_value(&value.text, source, target)?
+                };
+                self.model
+                    .set_user_input(sheet, row, column, new_value.clone())?;
+                diff_list.push(Diff::SetCellValue {
+                    sheet,
+                    row,
+                    column,
+                    new_value,
+                    old_value: Box::new(old_value),
+                });
+                Ok(())
+            }
+        }
+    }
+
+    fn set_pasted_value(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        new_value: ClipboardValue,
+        old_value: Option<Cell>,
+        diff_list: &mut Vec<Diff>,
+    ) -> Result<(), String> {
+        new_value.set_in_cell(&mut self.model, sheet, row, column)?;
+        diff_list.push(Diff::SetCellPastedValue {
+            sheet,
+            row,
+            column,
+            new_value,
+            old_value: Box::new(old_value),
+        });
+        Ok(())
+    }
+
+    fn clear_pasted_cell(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        diff_list: &mut Vec<Diff>,
+    ) -> Result<(), String> {
+        let area = Area {
+            sheet,
+            row,
+            column,
+            width: 1,
+            height: 1,
+        };
+        let old_value = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .cell(row, column)
+            .cloned();
+        if old_value.is_none() {
+            return Ok(());
+        }
+        diff_list.extend(self.range_link_diffs(&area)?);
+        self.model.range_clear_contents(&area)?;
+        diff_list.push(Diff::RangeClearContents {
+            sheet,
+            row,
+            column,
+            width: 1,
+            height: 1,
+            old_value: vec![vec![old_value]],
+        });
+        Ok(())
+    }
+
     /// Paste a csv-string into the model
     pub fn paste_csv_string(&mut self, area: &Area, csv: &str) -> Result<(), String> {
         let sheet = area.sheet;
@@ -497,6 +1327,13 @@ impl<'a> UserModel<'a> {
             width: max_width,
             height: records.len() as i32,
         };
+        self.model.check_cells_editable(
+            sheet,
+            paste_area.row,
+            paste_area.column,
+            paste_area.width,
+            paste_area.height,
+        )?;
 
         // Capture old values BEFORE clearing so undo can restore them correctly.
         let mut old_values: HashMap<(i32, i32), Option<Cell>> = HashMap::new();
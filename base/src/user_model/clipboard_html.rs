@@ -0,0 +1,958 @@
+//! The `text/html` flavour of the clipboard.
+//!
+//! Copying writes a `<table>` with the styles inlined in each cell and merged cells as colspans and
+//! rowspans, which is what email clients, word processors and other spreadsheets understand.
+//! Pasting reads the first table of an HTML fragment (from a browser, Google Sheets or Excel) and
+//! maps the inline CSS, the `<style>` rules of the cell classes and the `<td>` attributes onto
+//! [Style]. Anything else in the document is ignored.
+
+use std::collections::HashMap;
+
+use crate::{
+    expressions::types::Area,
+    types::{
+        Alignment, BorderItem, BorderStyle, Color, HorizontalAlignment, PatternType, Style, Theme,
+        VerticalAlignment,
+    },
+    UserModel,
+};
+
+use super::history::Diff;
+
+/// Returns the `<td>` element of a copied cell with its style inlined
+pub(crate) fn html_cell(
+    text: &str,
+    style: &Style,
+    theme: &Theme,
+    colspan: i32,
+    rowspan: i32,
+) -> String {
+    let mut attributes = String::new();
+    if colspan > 1 {
+        attributes.push_str(&format!(" colspan=\"{colspan}\""));
+    }
+    if rowspan > 1 {
+        attributes.push_str(&format!(" rowspan=\"{rowspan}\""));
+    }
+    let css = style_to_css(style, theme);
+    if !css.is_empty() {
+        attributes.push_str(&format!(" style=\"{}\"", escape_html(&css)));
+    }
+    let text = escape_html(text).replace('\n', "<br>");
+    format!("<td{attributes}>{text}</td>")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn border_to_css(item: &BorderItem, theme: &Theme) -> String {
+    let (width, line) = match item.style {
+        BorderStyle::Thin => (1, "solid"),
+        BorderStyle::Medium => (2, "solid"),
+        BorderStyle::Thick => (3, "solid"),
+        BorderStyle::Double => (3, "double"),
+        BorderStyle::Dotted => (1, "dotted"),
+        BorderStyle::SlantDashDot
+        | BorderStyle::MediumDashed
+        | BorderStyle::MediumDashDotDot
+        | BorderStyle::MediumDashDot => (2, "dashed"),
+    };
+    let color = match item.color.to_rgb(theme) {
+        color if color.is_empty() => "#000000".to_string(),
+        color => color,
+    };
+    format!("{width}px {line} {color}")
+}
+
+fn style_to_css(style: &Style, theme: &Theme) -> String {
+    let mut css = Vec::new();
+    let font = &style.font;
+    css.push(format!("font-family:'{}'", font.name.replace('\'', "")));
+    css.push(format!("font-size:{}pt", font.sz));
+    if font.b {
+        css.push("font-weight:bold".to_string());
+    }
+    if font.i {
+        css.push("font-style:italic".to_string());
+    }
+    match (font.u, font.strike) {
+        (true, true) => css.push("text-decoration:underline line-through".to_string()),
+        (true, false) => css.push("text-decoration:underline".to_string()),
+        (false, true) => css.push("text-decoration:line-through".to_string()),
+        (false, false) => {}
+    }
+    let color = font.color.to_rgb(theme);
+    if !color.is_empty() {
+        css.push(format!("color:{color}"));
+    }
+    let fill = &style.fill;
+    let background = match &fill.gradient {
+        Some(gradient) => gradient
+            .stops
+            .first()
+            .map(|stop| stop.color.to_rgb(theme))
+            .unwrap_or_default(),
+        None if fill.pattern == PatternType::Solid => fill.color.to_rgb(theme),
+        // A pattern is approximated by the color behind it
+        None => fill.bg_color.to_rgb(theme),
+    };
+    if !background.is_empty() {
+        css.push(format!("background-color:{background}"));
+    }
+    if let Some(alignment) = &style.alignment {
+        let horizontal = match alignment.horizontal {
+            HorizontalAlignment::Left => Some("left"),
+            HorizontalAlignment::Center | HorizontalAlignment::CenterContinuous => Some("center"),
+            HorizontalAlignment::Right => Some("right"),
+            HorizontalAlignment::Justify | HorizontalAlignment::Distributed => Some("justify"),
+            HorizontalAlignment::General | HorizontalAlignment::Fill => None,
+        };
+        if let Some(horizontal) = horizontal {
+            css.push(format!("text-align:{horizontal}"));
+        }
+        let vertical = match alignment.vertical {
+            VerticalAlignment::Top => Some("top"),
+            VerticalAlignment::Center
+            | VerticalAlignment::Justify
+            | VerticalAlignment::Distributed => Some("middle"),
+            VerticalAlignment::Bottom => None,
+        };
+        if let Some(vertical) = vertical {
+            css.push(format!("vertical-align:{vertical}"));
+        }
+        if alignment.wrap_text {
+            css.push("white-space:normal".to_string());
+        }
+    }
+    let border = &style.border;
+    for (side, item) in [
+        ("top", &border.top),
+        ("right", &border.right),
+        ("bottom", &border.bottom),
+        ("left", &border.left),
+    ] {
+        if let Some(item) = item {
+            css.push(format!("border-{side}:{}", border_to_css(item, theme)));
+        }
+    }
+    if style.num_fmt.to_lowercase() != "general" {
+        let num_fmt = style.num_fmt.replace('\\', "\\\\").replace('"', "\\\"");
+        css.push(format!("mso-number-format:\"{num_fmt}\""));
+    }
+    css.join(";")
+}
+
+enum Token {
+    Start {
+        name: String,
+        attributes: HashMap<String, String>,
+    },
+    End(String),
+    Text(String),
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let decoded = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#').and_then(|d| d.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (entity, decoded) {
+            (Some(entity), Some(c)) => {
+                result.push(if c == '\u{a0}' { ' ' } else { c });
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn parse_attributes(text: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut position = 0;
+    while position < chars.len() {
+        while position < chars.len() && (chars[position].is_whitespace() || chars[position] == '/')
+        {
+            position += 1;
+        }
+        let start = position;
+        while position < chars.len()
+            && !chars[position].is_whitespace()
+            && chars[position] != '='
+            && chars[position] != '/'
+        {
+            position += 1;
+        }
+        let name: String = chars[start..position].iter().collect();
+        while position < chars.len() && chars[position].is_whitespace() {
+            position += 1;
+        }
+        let mut value = String::new();
+        if position < chars.len() && chars[position] == '=' {
+            position += 1;
+            while position < chars.len() && chars[position].is_whitespace() {
+                position += 1;
+            }
+            if position < chars.len() && (chars[position] == '"' || chars[position] == '\'') {
+                let quote = chars[position];
+                position += 1;
+                while position < chars.len() && chars[position] != quote {
+                    value.push(chars[position]);
+                    position += 1;
+                }
+                position += 1;
+            } else {
+                while position < chars.len() && !chars[position].is_whitespace() {
+                    value.push(chars[position]);
+                    position += 1;
+                }
+            }
+        }
+        if !name.is_empty() {
+            attributes.insert(name.to_lowercase(), decode_entities(&value));
+        }
+    }
+    attributes
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest.to_string()));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            // doctype, processing instructions and conditional comments
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::End(name.trim().to_lowercase()));
+            continue;
+        }
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_lowercase();
+        let attributes = parse_attributes(&tag[name_end..]);
+        if name == "style" || name == "script" {
+            // raw text up to the closing tag
+            let close = format!("</{name}");
+            let content_end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+            if name == "style" {
+                tokens.push(Token::Start {
+                    name: name.clone(),
+                    attributes,
+                });
+                tokens.push(Token::Text(rest[..content_end].to_string()));
+                tokens.push(Token::End(name));
+            }
+            rest = &rest[content_end..];
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        tokens.push(Token::Start { name, attributes });
+    }
+    tokens
+}
+
+// Splits a CSS declaration list on the semicolons that are not inside a string
+fn parse_declarations(css: &str) -> Vec<(String, String)> {
+    let mut declarations = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut chars = css.chars();
+    let mut push = |current: &mut String| {
+        if let Some((name, value)) = current.split_once(':') {
+            let value = value.trim();
+            let value = value.strip_suffix("!important").unwrap_or(value).trim();
+            declarations.push((name.trim().to_lowercase(), value.to_string()));
+        }
+        current.clear();
+    };
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (';', None) => push(&mut current),
+            _ => current.push(c),
+        }
+    }
+    push(&mut current);
+    declarations
+}
+
+// Parses the rules of a `<style>` element, keyed by tag name ("td") or class (".xl65")
+fn parse_style_sheet(css: &str, rules: &mut HashMap<String, Vec<(String, String)>>) {
+    let mut css = css.to_string();
+    while let Some(start) = css.find("/*") {
+        let end = css[start..]
+            .find("*/")
+            .map_or(css.len(), |end| start + end + 2);
+        css.replace_range(start..end, "");
+    }
+    let mut rest = css.as_str();
+    while let Some(open) = rest.find('{') {
+        let selectors = &rest[..open];
+        let close = rest[open..]
+            .find('}')
+            .map_or(rest.len(), |close| open + close);
+        let declarations = parse_declarations(&rest[open + 1..close]);
+        for selector in selectors.split(',') {
+            let selector = selector.trim().to_lowercase();
+            // "td.xl65" applies to the cells with class "xl65"
+            let key = match selector.split_once('.') {
+                Some((_, class)) => format!(".{class}"),
+                None => selector,
+            };
+            rules
+                .entry(key)
+                .or_default()
+                .extend(declarations.iter().cloned());
+        }
+        rest = rest.get(close + 1..).unwrap_or("");
+    }
+}
+
+fn parse_css_string(value: &str) -> String {
+    let value = value.trim();
+    let inner = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+            .unwrap_or(value),
+        _ => value,
+    };
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        let hex = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        return Color::from_rgb(&format!("#{}", hex.to_uppercase())).ok();
+    }
+    if let Some(arguments) = value
+        .strip_prefix("rgba(")
+        .or(value.strip_prefix("rgb("))
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        let parts: Vec<&str> = arguments
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.len() == 4 && parts[3].parse::<f64>().ok() == Some(0.0) {
+            // fully transparent
+            return None;
+        }
+        let mut rgb = String::from("#");
+        for part in parts.iter().take(3) {
+            let component = match part.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+                None => part.parse::<f64>().ok()?,
+            };
+            rgb.push_str(&format!(
+                "{:02X}",
+                component.round().clamp(0.0, 255.0) as u8
+            ));
+        }
+        return Color::from_rgb(&rgb).ok();
+    }
+    let rgb = match value.as_str() {
+        "black" => "#000000",
+        "white" => "#FFFFFF",
+        "red" => "#FF0000",
+        "green" => "#008000",
+        "blue" => "#0000FF",
+        "yellow" => "#FFFF00",
+        "orange" => "#FFA500",
+        "purple" => "#800080",
+        "gray" | "grey" => "#808080",
+        "silver" => "#C0C0C0",
+        "maroon" => "#800000",
+        "navy" => "#000080",
+        "teal" => "#008080",
+        "olive" => "#808000",
+        "lime" => "#00FF00",
+        "aqua" | "cyan" => "#00FFFF",
+        "fuchsia" | "magenta" => "#FF00FF",
+        _ => return None,
+    };
+    Color::from_rgb(rgb).ok()
+}
+
+// Returns the width in points of a CSS length (`1px`, `0.5pt`, `thin`...)
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "thin" => return Some(0.75),
+        "medium" => return Some(1.5),
+        "thick" => return Some(2.25),
+        _ => {}
+    }
+    if let Some(pt) = value.strip_suffix("pt") {
+        return pt.trim().parse().ok();
+    }
+    if let Some(px) = value.strip_suffix("px") {
+        return px.trim().parse::<f64>().ok().map(|px| px * 0.75);
+    }
+    value.parse::<f64>().ok().map(|px| px * 0.75)
+}
+
+// Parses a `border` shorthand: "1px solid #000000" in any order
+fn parse_border(value: &str) -> Option<Option<BorderItem>> {
+    let mut width = None;
+    let mut line = None;
+    let mut color = Color::None;
+    for part in value.split_whitespace() {
+        let lower = part.to_lowercase();
+        match lower.as_str() {
+            "none" | "hidden" => return Some(None),
+            "solid" | "double" | "dotted" | "dashed" | "groove" | "ridge" | "inset" | "outset" => {
+                line = Some(lower)
+            }
+            _ => {
+                if let Some(length) = parse_length(&lower) {
+                    width = Some(length);
+                } else if let Some(c) = parse_color(&lower) {
+                    color = c;
+                }
+            }
+        }
+    }
+    let width = width.unwrap_or(0.75);
+    if width <= 0.0 {
+        return Some(None);
+    }
+    let style = match line.as_deref() {
+        Some("double") => BorderStyle::Double,
+        Some("dotted") => BorderStyle::Dotted,
+        Some("dashed") => BorderStyle::MediumDashed,
+        _ if width >= 2.25 => BorderStyle::Thick,
+        _ if width >= 1.5 => BorderStyle::Medium,
+        _ => BorderStyle::Thin,
+    };
+    Some(Some(BorderItem { style, color }))
+}
+
+fn parse_number_format(value: &str) -> String {
+    let format = parse_css_string(value);
+    match format.to_lowercase().as_str() {
+        "general" => "general".to_string(),
+        "percent" => "0.00%".to_string(),
+        "fixed" => "0.00".to_string(),
+        "standard" => "#,##0.00".to_string(),
+        "scientific" => "0.00E+00".to_string(),
+        "short date" => "m/d/yyyy".to_string(),
+        "\\@" | "@" => "@".to_string(),
+        _ => format,
+    }
+}
+
+fn alignment(style: &mut Style) -> &mut Alignment {
+    style.alignment.get_or_insert_with(Alignment::default)
+}
+
+// Applies a CSS declaration to the style. Unknown properties are ignored.
+fn apply_declaration(style: &mut Style, name: &str, value: &str) {
+    let lower = value.to_lowercase();
+    match name {
+        "font-weight" => {
+            style.font.b = match lower.as_str() {
+                "bold" | "bolder" => true,
+                "normal" | "lighter" => false,
+                weight => weight.parse::<i32>().is_ok_and(|w| w >= 600),
+            }
+        }
+        "font-style" => style.font.i = lower == "italic" || lower == "oblique",
+        "text-decoration" | "text-decoration-line" => {
+            style.font.u = lower.contains("underline");
+            style.font.strike = lower.contains("line-through");
+        }
+        "color" => {
+            if let Some(color) = parse_color(&lower) {
+                style.font.color = color;
+            }
+        }
+        "font-size" => {
+            if let Some(size) = parse_length(&lower) {
+                style.font.sz = size.round() as i32;
+            }
+        }
+        "font-family" => {
+            let family = value.split(',').next().map(parse_css_string);
+            if let Some(family) = family.filter(|f| !f.trim().is_empty()) {
+                style.font.name = family.trim().to_string();
+            }
+        }
+        "background-color" | "background" => {
+            if lower == "none" || lower == "transparent" {
+                style.fill.color = Color::None;
+            } else if let Some(color) = lower.split_whitespace().find_map(parse_color) {
+                style.fill.color = color;
+                style.fill.pattern = PatternType::Solid;
+                style.fill.gradient = None;
+            }
+        }
+        "text-align" => {
+            alignment(style).horizontal = match lower.as_str() {
+                "left" | "start" => HorizontalAlignment::Left,
+                "center" => HorizontalAlignment::Center,
+                "right" | "end" => HorizontalAlignment::Right,
+                "justify" => HorizontalAlignment::Justify,
+                _ => HorizontalAlignment::General,
+            }
+        }
+        "vertical-align" => {
+            alignment(style).vertical = match lower.as_str() {
+                "top" => VerticalAlignment::Top,
+                "middle" => VerticalAlignment::Center,
+                _ => VerticalAlignment::Bottom,
+            }
+        }
+        "white-space" => {
+            alignment(style).wrap_text = matches!(lower.as_str(), "normal" | "pre-wrap");
+        }
+        "border" => {
+            if let Some(item) = parse_border(value) {
+                style.border.top = item.clone();
+                style.border.right = item.clone();
+                style.border.bottom = item.clone();
+                style.border.left = item;
+            }
+        }
+        "border-top" | "border-right" | "border-bottom" | "border-left" => {
+            if let Some(item) = parse_border(value) {
+                match name {
+                    "border-top" => style.border.top = item,
+                    "border-right" => style.border.right = item,
+                    "border-bottom" => style.border.bottom = item,
+                    _ => style.border.left = item,
+                }
+            }
+        }
+        "mso-number-format" => style.num_fmt = parse_number_format(value),
+        _ => {}
+    }
+}
+
+fn apply_declarations(style: &mut Style, declarations: &[(String, String)]) {
+    for (name, value) in declarations {
+        apply_declaration(style, name, value);
+    }
+}
+
+// Applies the formatting of an element: its tag, its class and its style attribute
+fn apply_element(
+    style: &mut Style,
+    name: &str,
+    attributes: &HashMap<String, String>,
+    rules: &HashMap<String, Vec<(String, String)>>,
+) {
+    match name {
+        "b" | "strong" | "th" => style.font.b = true,
+        "i" | "em" => style.font.i = true,
+        "u" | "ins" => style.font.u = true,
+        "s" | "strike" | "del" => style.font.strike = true,
+        _ => {}
+    }
+    if let Some(declarations) = rules.get(name) {
+        apply_declarations(style, declarations);
+    }
+    for (attribute, value) in attributes {
+        match attribute.as_str() {
+            "align" => apply_declaration(style, "text-align", value),
+            "valign" => apply_declaration(style, "vertical-align", value),
+            "bgcolor" => apply_declaration(style, "background-color", value),
+            "color" if name == "font" => apply_declaration(style, "color", value),
+            _ => {}
+        }
+    }
+    if let Some(classes) = attributes.get("class") {
+        for class in classes.split_whitespace() {
+            if let Some(declarations) = rules.get(&format!(".{}", class.to_lowercase())) {
+                apply_declarations(style, declarations);
+            }
+        }
+    }
+    if let Some(css) = attributes.get("style") {
+        apply_declarations(style, &parse_declarations(css));
+    }
+}
+
+/// A cell of a pasted HTML table
+pub(crate) struct HtmlCell {
+    pub(crate) row: i32,
+    pub(crate) column: i32,
+    pub(crate) text: String,
+    pub(crate) style: Style,
+    pub(crate) colspan: i32,
+    pub(crate) rowspan: i32,
+}
+
+fn get_span(attributes: &HashMap<String, String>, name: &str) -> i32 {
+    attributes
+        .get(name)
+        .and_then(|span| span.trim().parse::<i32>().ok())
+        .unwrap_or(1)
+        .clamp(1, 1000)
+}
+
+/// Reads the cells of the first table in the HTML. Rows and columns are 0-based, the cells covered
+/// by a colspan or a rowspan are left out.
+pub(crate) fn parse_html_table(html: &str, base_style: &Style) -> Vec<HtmlCell> {
+    let tokens = tokenize(html);
+    let mut rules = HashMap::new();
+    for (index, token) in tokens.iter().enumerate() {
+        if let (Token::Start { name, .. }, Some(Token::Text(css))) = (token, tokens.get(index + 1))
+        {
+            if name == "style" {
+                parse_style_sheet(css, &mut rules);
+            }
+        }
+    }
+
+    let mut cells: Vec<HtmlCell> = Vec::new();
+    // cells taken by a rowspan of a previous row: (row, column)
+    let mut covered: Vec<(i32, i32)> = Vec::new();
+    let mut table_depth = 0;
+    let mut row = -1;
+    let mut column = 0;
+    let mut row_style = base_style.clone();
+    // the styles of the open elements inside the current cell
+    let mut cell_styles: Vec<(String, Style)> = Vec::new();
+    let mut in_cell = false;
+    for token in &tokens {
+        match token {
+            Token::Start { name, attributes } => match name.as_str() {
+                "table" => {
+                    table_depth += 1;
+                    if table_depth == 1 && !cells.is_empty() {
+                        // only the first table is read
+                        break;
+                    }
+                }
+                _ if table_depth != 1 => {}
+                "tr" => {
+                    row += 1;
+                    column = 0;
+                    in_cell = false;
+                    row_style = base_style.clone();
+                    apply_element(&mut row_style, name, attributes, &rules);
+                }
+                "td" | "th" => {
+                    if row < 0 {
+                        row = 0;
+                    }
+                    while covered.contains(&(row, column)) {
+                        column += 1;
+                    }
+                    let mut style = row_style.clone();
+                    apply_element(&mut style, name, attributes, &rules);
+                    let colspan = get_span(attributes, "colspan");
+                    let rowspan = get_span(attributes, "rowspan");
+                    for r in row..row + rowspan {
+                        for c in column..column + colspan {
+                            covered.push((r, c));
+                        }
+                    }
+                    cells.push(HtmlCell {
+                        row,
+                        column,
+                        text: String::new(),
+                        style: style.clone(),
+                        colspan,
+                        rowspan,
+                    });
+                    cell_styles = vec![(name.clone(), style)];
+                    column += colspan;
+                    in_cell = true;
+                }
+                "br" if in_cell => {
+                    if let Some(cell) = cells.last_mut() {
+                        cell.text.push('\n');
+                    }
+                }
+                // elements without content
+                "img" | "input" | "hr" | "wbr" | "meta" | "link" | "col" | "source" => {}
+                _ if in_cell => {
+                    let mut style = cell_styles
+                        .last()
+                        .map(|(_, style)| style.clone())
+                        .unwrap_or_else(|| base_style.clone());
+                    apply_element(&mut style, name, attributes, &rules);
+                    cell_styles.push((name.clone(), style));
+                }
+                _ => {}
+            },
+            Token::End(name) => match name.as_str() {
+                "table" => {
+                    table_depth -= 1;
+                    in_cell = false;
+                }
+                _ if table_depth != 1 => {}
+                "td" | "th" | "tr" => {
+                    in_cell = false;
+                }
+                "p" | "div" | "li" if in_cell => {
+                    if let Some(cell) = cells.last_mut() {
+                        if !cell.text.is_empty() && !cell.text.ends_with('\n') {
+                            cell.text.push('\n');
+                        }
+                    }
+                    if cell_styles.len() > 1 {
+                        cell_styles.pop();
+                    }
+                }
+                _ if in_cell && cell_styles.len() > 1 => {
+                    cell_styles.pop();
+                }
+                _ => {}
+            },
+            Token::Text(text) if in_cell && table_depth == 1 => {
+                let collapsed = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                if collapsed.is_empty() {
+                    if !text.is_empty() {
+                        if let Some(cell) = cells.last_mut() {
+                            if !cell.text.is_empty() && !cell.text.ends_with([' ', '\n']) {
+                                cell.text.push(' ');
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if let Some(cell) = cells.last_mut() {
+                    let starts_with_space = text.starts_with(char::is_whitespace);
+                    if starts_with_space
+                        && !cell.text.is_empty()
+                        && !cell.text.ends_with([' ', '\n'])
+                    {
+                        cell.text.push(' ');
+                    }
+                    cell.text.push_str(&decode_entities(&collapsed));
+                    if text.ends_with(char::is_whitespace) {
+                        cell.text.push(' ');
+                    }
+                    // The formatting of the text inside the cell applies to the whole cell
+                    if let Some((_, style)) = cell_styles.last() {
+                        cell.style = style.clone();
+                    }
+                }
+            }
+            Token::Text(_) => {}
+        }
+    }
+    for cell in &mut cells {
+        let lines: Vec<&str> = cell.text.split('\n').map(str::trim).collect();
+        cell.text = lines.join("\n").trim_matches('\n').to_string();
+        if cell.text.contains('\n') {
+            alignment(&mut cell.style).wrap_text = true;
+        }
+    }
+    cells
+}
+
+impl UserModel<'_> {
+    /// Pastes the first table of an HTML fragment, as copied from a browser, Google Sheets or
+    /// Excel, with its top left cell in (`area.row`, `area.column`).
+    ///
+    /// The inline CSS, the `<style>` rules of the cells and the `align`, `valign` and `bgcolor`
+    /// attributes become the styles of the cells. Cells with a colspan or a rowspan are merged.
+    /// Fails if there is no table in `html`.
+    pub fn paste_html_string(&mut self, area: &Area, html: &str) -> Result<(), String> {
+        let sheet = area.sheet;
+        let cells = parse_html_table(html, &Style::default());
+        if cells.is_empty() {
+            return Err("No table found in HTML".to_string());
+        }
+        let height = cells.iter().map(|c| c.row + c.rowspan).max().unwrap_or(1);
+        let width = cells
+            .iter()
+            .map(|c| c.column + c.colspan)
+            .max()
+            .unwrap_or(1);
+        let paste_area = Area {
+            sheet,
+            row: area.row,
+            column: area.column,
+            width,
+            height,
+        };
+        self.model
+            .check_cells_editable(sheet, area.row, area.column, width, height)?;
+
+        let mut old_values = Vec::new();
+        for row in area.row..area.row + height {
+            let mut old_row = Vec::new();
+            for column in area.column..area.column + width {
+                old_row.push(
+                    self.model
+                        .workbook
+                        .worksheet(sheet)?
+                        .cell(row, column)
+                        .cloned(),
+                );
+            }
+            old_values.push(old_row);
+        }
+        let old_merged_cells = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+
+        // Clearing the target area also removes its links: capture them for undo
+        let mut diff_list = self.range_link_diffs(&paste_area)?;
+        self.model.range_clear_contents(&paste_area)?;
+        diff_list.push(Diff::RangeClearContents {
+            sheet,
+            row: area.row,
+            column: area.column,
+            width,
+            height,
+            old_value: old_values,
+        });
+
+        for cell in &cells {
+            let row = area.row + cell.row;
+            let column = area.column + cell.column;
+            if !cell.text.is_empty() {
+                // Cells formatted as text ("@") are not parsed as numbers or formulas
+                let text = if cell.style.num_fmt == "@" && !cell.text.starts_with('\'') {
+                    format!("'{}", cell.text)
+                } else {
+                    cell.text.clone()
+                };
+                let old_value = self
+                    .model
+                    .workbook
+                    .worksheet(sheet)?
+                    .cell(row, column)
+                    .cloned();
+                diff_list.push(Diff::SetCellValue {
+                    sheet,
+                    row,
+                    column,
+                    new_value: text.clone(),
+                    old_value: Box::new(old_value),
+                });
+                // pasted URLs are auto-linked: capture the link and style diffs too
+                self.set_user_input_with_link_diffs(sheet, row, column, text, &mut diff_list)?;
+            }
+            // A cell spanning several columns or rows paints all of them
+            for r in row..row + cell.rowspan {
+                for c in column..column + cell.colspan {
+                    let mut style = cell.style.clone();
+                    // A text like '007 keeps its quote prefix
+                    style.quote_prefix = self.model.get_style_for_cell(sheet, r, c)?.quote_prefix;
+                    let old_style = self.model.get_cell_style_or_none(sheet, r, c)?;
+                    self.model.set_cell_style(sheet, r, c, &style)?;
+                    diff_list.push(Diff::SetCellStyle {
+                        sheet,
+                        row: r,
+                        column: c,
+                        old_value: Box::new(old_style),
+                        new_value: Box::new(style),
+                    });
+                }
+            }
+        }
+
+        // The merged cells of the table replace the ones in the pasted area
+        self.model.unmerge_cells(&paste_area)?;
+        for cell in cells.iter().filter(|c| c.colspan > 1 || c.rowspan > 1) {
+            self.model.merge_cells(&Area {
+                sheet,
+                row: area.row + cell.row,
+                column: area.column + cell.column,
+                width: cell.colspan,
+                height: cell.rowspan,
+            })?;
+        }
+        let new_merged_cells = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+        if new_merged_cells != old_merged_cells {
+            diff_list.push(Diff::SetMergedCells {
+                sheet,
+                old_value: old_merged_cells,
+                new_value: new_merged_cells,
+            });
+        }
+
+        self.push_diff_list(diff_list);
+        // select the pasted area
+        self.set_selected_range(
+            area.row,
+            area.column,
+            area.row + height - 1,
+            area.column + width - 1,
+        )?;
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+}
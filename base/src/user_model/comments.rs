@@ -0,0 +1,204 @@
+use crate::{
+    expressions::utils::parse_reference_a1,
+    types::{Comment, CommentReply, CommentThread},
+};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the comments of the sheet
+    pub fn get_comments(&self, sheet: u32) -> Result<Vec<Comment>, String> {
+        self.model.get_comments(sheet)
+    }
+
+    /// Returns the comment attached to the cell, if any
+    pub fn get_comment(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Comment>, String> {
+        self.model.get_comment(sheet, row, column)
+    }
+
+    /// Adds a comment (a note) to the cell, or changes the text of its comment if it already has one
+    pub fn set_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), String> {
+        let new_value = match self.model.get_comment(sheet, row, column)? {
+            Some(comment) => Comment {
+                text: text.to_string(),
+                ..comment
+            },
+            None => Comment {
+                text: text.to_string(),
+                author_name: author_name.to_string(),
+                author_id: None,
+                cell_ref: "".to_string(),
+                thread: None,
+            },
+        };
+        self.update_comment(sheet, row, column, Some(new_value))
+    }
+
+    /// Starts a threaded comment in a cell without comments
+    pub fn add_threaded_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), String> {
+        if self.model.get_comment(sheet, row, column)?.is_some() {
+            return Err("The cell already has a comment".to_string());
+        }
+        let author_id = self.get_person_id(author_name);
+        let thread = CommentThread {
+            id: self.model.new_comment_id(&[&author_id]),
+            date: self.model.get_comment_date(),
+            done: false,
+            replies: vec![],
+        };
+        let comment = Comment {
+            text: text.to_string(),
+            author_name: author_name.to_string(),
+            author_id: Some(author_id),
+            cell_ref: "".to_string(),
+            thread: Some(thread),
+        };
+        self.update_comment(sheet, row, column, Some(comment))
+    }
+
+    /// Adds a reply at the end of the threaded comment of the cell
+    pub fn add_comment_reply(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), String> {
+        let mut comment = match self.model.get_comment(sheet, row, column)? {
+            Some(comment) if comment.thread.is_some() => comment,
+            Some(_) => return Err("Only threaded comments have replies".to_string()),
+            None => return Err("The cell has no comment".to_string()),
+        };
+        let author_id = self.get_person_id(author_name);
+        let reply = CommentReply {
+            id: self.model.new_comment_id(&[&author_id]),
+            text: text.to_string(),
+            author_name: author_name.to_string(),
+            author_id: Some(author_id),
+            date: self.model.get_comment_date(),
+        };
+        if let Some(thread) = &mut comment.thread {
+            thread.replies.push(reply);
+        }
+        self.update_comment(sheet, row, column, Some(comment))
+    }
+
+    /// Marks the threaded comment of the cell as resolved (`done`) or reopens it
+    pub fn set_comment_thread_done(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        done: bool,
+    ) -> Result<(), String> {
+        let mut comment = match self.model.get_comment(sheet, row, column)? {
+            Some(comment) if comment.thread.is_some() => comment,
+            Some(_) => return Err("The comment is not a threaded comment".to_string()),
+            None => return Err("The cell has no comment".to_string()),
+        };
+        if let Some(thread) = &mut comment.thread {
+            thread.done = done;
+        }
+        self.update_comment(sheet, row, column, Some(comment))
+    }
+
+    /// Removes the comment of the cell, with all its replies
+    pub fn delete_comment(&mut self, sheet: u32, row: i32, column: i32) -> Result<(), String> {
+        if self.model.get_comment(sheet, row, column)?.is_none() {
+            return Ok(());
+        }
+        self.update_comment(sheet, row, column, None)
+    }
+
+    /// The diffs that remove the comments in the rows [row_start, row_end] and
+    /// columns [column_start, column_end]
+    pub(super) fn delete_comments_diffs(
+        &self,
+        sheet: u32,
+        (row_start, row_end): (i32, i32),
+        (column_start, column_end): (i32, i32),
+    ) -> Result<Vec<Diff>, String> {
+        let mut diff_list = Vec::new();
+        for comment in self.model.get_comments(sheet)? {
+            let Some(reference) = parse_reference_a1(&comment.cell_ref) else {
+                continue;
+            };
+            let (row, column) = (reference.row, reference.column);
+            if (row_start..=row_end).contains(&row) && (column_start..=column_end).contains(&column)
+            {
+                diff_list.push(Diff::SetComment {
+                    sheet,
+                    row,
+                    column,
+                    old_value: Some(Box::new(comment)),
+                    new_value: None,
+                });
+            }
+        }
+        Ok(diff_list)
+    }
+
+    fn update_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        new_value: Option<Comment>,
+    ) -> Result<(), String> {
+        let old_value = self.model.get_comment(sheet, row, column)?;
+        self.model
+            .set_comment(sheet, row, column, new_value.clone())?;
+        self.push_diff_list(vec![Diff::SetComment {
+            sheet,
+            row,
+            column,
+            old_value: old_value.map(Box::new),
+            new_value: new_value.map(Box::new),
+        }]);
+        Ok(())
+    }
+
+    // The id of the person with that name in the threaded comments of the workbook or a new one
+    fn get_person_id(&self, author_name: &str) -> String {
+        for worksheet in &self.model.workbook.worksheets {
+            for comment in &worksheet.comments {
+                if comment.thread.is_none() {
+                    continue;
+                }
+                if comment.author_name == author_name {
+                    if let Some(author_id) = &comment.author_id {
+                        return author_id.clone();
+                    }
+                }
+                for reply in comment.thread.iter().flat_map(|thread| &thread.replies) {
+                    if reply.author_name == author_name {
+                        if let Some(author_id) = &reply.author_id {
+                            return author_id.clone();
+                        }
+                    }
+                }
+            }
+        }
+        self.model.new_comment_id(&[])
+    }
+}
@@ -7,15 +7,17 @@ use serde::{Deserialize, Serialize};
 use crate::{
     cf_types::ExtendedStyle,
     constants::{LAST_COLUMN, LAST_ROW},
+    dv_types::DataValidationErrorStyle,
     expressions::{
         parser::CompletionContext,
         types::Area,
         utils::{is_valid_column_number, is_valid_row},
     },
-    model::{FmtSettings, Model},
+    model::{validate_iterative_calculation, FmtSettings, Model},
     types::{
-        Alignment, ArrayKind, BorderItem, Cell, CellType, Col, Color, HorizontalAlignment,
-        SheetProperties, SheetState, Style, Theme, VerticalAlignment,
+        Alignment, ArrayKind, BorderItem, Cell, CellType, Col, Color, DateSystem, Fill,
+        GradientFill, GradientStop, GradientType, HorizontalAlignment, IterativeCalculation,
+        PatternType, ReadingOrder, SheetProperties, SheetState, Style, Theme, VerticalAlignment,
     },
 };
 
@@ -96,6 +98,58 @@ fn vertical(value: &str) -> Result<VerticalAlignment, String> {
     }
 }
 
+fn reading_order(value: &str) -> Result<ReadingOrder, String> {
+    match value {
+        "contextDependent" => Ok(ReadingOrder::ContextDependent),
+        "leftToRight" => Ok(ReadingOrder::LeftToRight),
+        "rightToLeft" => Ok(ReadingOrder::RightToLeft),
+        _ => Err(format!("Invalid value for reading order: '{value}'.")),
+    }
+}
+
+fn parse_fraction(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        _ => Err(format!("Invalid value for gradient: '{value}'.")),
+    }
+}
+
+// A linear gradient with its angle and stops: "linear 90; 0 #FFFFFF; 1 #4472C4"
+// or a path gradient with its inner rectangle (left right top bottom):
+// "path 0.5 0.5 0.5 0.5; 0 #FFFFFF; 1 [4, 0.4]"
+fn gradient(value: &str) -> Result<GradientFill, String> {
+    let invalid = || format!("Invalid value for gradient: '{value}'.");
+    let mut parts = value.split(';');
+    let header: Vec<&str> = parts.next().unwrap_or("").split_whitespace().collect();
+    let mut gradient = match header.as_slice() {
+        ["linear", degree] => GradientFill {
+            r#type: GradientType::Linear,
+            degree: degree.parse::<f64>().map_err(|_| invalid())?,
+            ..Default::default()
+        },
+        ["path", left, right, top, bottom] => GradientFill {
+            r#type: GradientType::Path,
+            left: parse_fraction(left)?,
+            right: parse_fraction(right)?,
+            top: parse_fraction(top)?,
+            bottom: parse_fraction(bottom)?,
+            ..Default::default()
+        },
+        _ => return Err(invalid()),
+    };
+    for stop in parts {
+        let (position, color) = stop.trim().split_once(' ').ok_or_else(invalid)?;
+        gradient.stops.push(GradientStop {
+            position: parse_fraction(position)?,
+            color: Color::from_param(color.trim())?,
+        });
+    }
+    if gradient.stops.len() < 2 {
+        return Err(format!("A gradient needs at least two colors: '{value}'."));
+    }
+    Ok(gradient)
+}
+
 fn update_style(old_value: &Style, style_path: &str, value: &str) -> Result<Style, String> {
     let mut style = old_value.clone();
     match style_path {
@@ -134,8 +188,38 @@ fn update_style(old_value: &Style, style_path: &str, value: &str) -> Result<Styl
             }
             style.font.sz = new_size;
         }
-        "fill.color" | "fill.bg_color" | "fill.fg_color" => {
+        "fill" => {
+            if !value.is_empty() {
+                return Err(format!("Fill must be empty, but found: '{value}'."));
+            }
+            style.fill = Fill::default();
+        }
+        "fill.color" => {
+            // A solid fill
+            style.fill = Fill {
+                color: Color::from_param(value)?,
+                ..Default::default()
+            };
+        }
+        "fill.fg_color" => {
             style.fill.color = Color::from_param(value)?;
+            style.fill.gradient = None;
+        }
+        "fill.bg_color" => {
+            style.fill.bg_color = Color::from_param(value)?;
+            style.fill.gradient = None;
+        }
+        "fill.pattern" => {
+            style.fill.pattern = PatternType::from_name(value)
+                .ok_or_else(|| format!("Invalid value for fill pattern: '{value}'."))?;
+            style.fill.gradient = None;
+        }
+        "fill.gradient" => {
+            style.fill.gradient = if value.is_empty() {
+                None
+            } else {
+                Some(gradient(value)?)
+            };
         }
         "num_fmt" => {
             value.clone_into(&mut style.num_fmt);
@@ -176,6 +260,55 @@ fn update_style(old_value: &Style, style_path: &str, value: &str) -> Result<Styl
                 style.alignment = Some(alignment)
             }
         },
+        "alignment.text_rotation" => {
+            let rotation: i32 = value
+                .parse()
+                .map_err(|_| format!("Invalid value for text rotation: '{value}'."))?;
+            if !(0..=180).contains(&rotation) && rotation != 255 {
+                return Err(format!("Invalid value for text rotation: '{rotation}'."));
+            }
+            style
+                .alignment
+                .get_or_insert_with(Default::default)
+                .text_rotation = rotation;
+        }
+        "alignment.indent" => {
+            let indent: i32 = value
+                .parse()
+                .map_err(|_| format!("Invalid value for indent: '{value}'."))?;
+            if !(0..=250).contains(&indent) {
+                return Err(format!("Invalid value for indent: '{indent}'."));
+            }
+            style.alignment.get_or_insert_with(Default::default).indent = indent;
+        }
+        "alignment.shrink_to_fit" => {
+            style
+                .alignment
+                .get_or_insert_with(Default::default)
+                .shrink_to_fit = boolean(value)?;
+        }
+        "alignment.reading_order" => {
+            style
+                .alignment
+                .get_or_insert_with(Default::default)
+                .reading_order = reading_order(value)?;
+        }
+        "alignment.justify_last_line" => {
+            style
+                .alignment
+                .get_or_insert_with(Default::default)
+                .justify_last_line = boolean(value)?;
+        }
+        "protection.locked" => {
+            let mut protection = style.protection.unwrap_or_default();
+            protection.locked = boolean(value)?;
+            style.protection = Some(protection);
+        }
+        "protection.hidden" => {
+            let mut protection = style.protection.unwrap_or_default();
+            protection.hidden = boolean(value)?;
+            style.protection = Some(protection);
+        }
         _ => {
             return Err(format!("Invalid style path: '{style_path}'."));
         }
@@ -422,8 +555,12 @@ impl<'a> UserModel<'a> {
 
     /// Set the input in a cell
     ///
+    /// Fails with the error message of the data validation rule of the cell if the value breaks it
+    /// and the rule has the [Stop](crate::dv_types::DataValidationErrorStyle::Stop) style.
+    ///
     /// See also:
     /// * [Model::set_user_input]
+    /// * [UserModel::validate_user_input]
     pub fn set_user_input(
         &mut self,
         sheet: u32,
@@ -431,12 +568,34 @@ impl<'a> UserModel<'a> {
         column: i32,
         value: &str,
     ) -> Result<(), String> {
+        let diff_list = self.set_user_input_diffs(sheet, row, column, value)?;
+        self.evaluate_incremental_if_not_paused();
+        self.push_diff_list(diff_list);
+        Ok(())
+    }
+
+    /// Sets the input in a cell like [UserModel::set_user_input] and returns the diffs of the
+    /// change, without adding them to the history nor evaluating the model.
+    pub(super) fn set_user_input_diffs(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        value: &str,
+    ) -> Result<Vec<Diff>, String> {
         if !is_valid_column_number(column) {
             return Err("Invalid column".to_string());
         }
         if !is_valid_row(row) {
             return Err("Invalid row".to_string());
         }
+        self.model.check_cells_editable(sheet, row, column, 1, 1)?;
+        // Values that break a data validation rule with the Stop style are rejected
+        if let Some(alert) = self.model.validate_user_input(sheet, row, column, value)? {
+            if alert.style == DataValidationErrorStyle::Stop {
+                return Err(alert.message);
+            }
+        }
         let old_value = self
             .model
             .workbook
@@ -449,6 +608,12 @@ impl<'a> UserModel<'a> {
         } else {
             old_value
         };
+        // Writing right next to a table expands it
+        let table_expansion = if value.is_empty() {
+            None
+        } else {
+            self.model.get_table_expansion(sheet, row, column)?
+        };
         let mut diff_list = vec![Diff::SetCellValue {
             sheet,
             row,
@@ -457,8 +622,7 @@ impl<'a> UserModel<'a> {
             old_value: Box::new(old_value),
         }];
         self.set_user_input_with_link_diffs(sheet, row, column, value.to_string(), &mut diff_list)?;
-
-        self.evaluate_if_not_paused();
+        diff_list.extend(self.update_tables_after_input(sheet, row, column, table_expansion)?);
 
         let style = self.model.get_style_for_cell(sheet, row, column)?;
 
@@ -477,9 +641,7 @@ impl<'a> UserModel<'a> {
             });
             self.model.set_row_height(sheet, row, cell_height)?;
         }
-
-        self.push_diff_list(diff_list);
-        Ok(())
+        Ok(diff_list)
     }
 
     /// Calls [`Model::set_user_input`] and appends to `diff_list` the diffs for the
@@ -523,12 +685,15 @@ impl<'a> UserModel<'a> {
         Ok(())
     }
 
-    /// Returns the content of a cell
+    /// Returns the content of a cell.
+    /// If the sheet is protected and the formula of the cell is hidden, returns an empty string.
     ///
     /// See also:
     /// * [Model::get_localized_cell_content]
-    #[inline]
     pub fn get_cell_content(&self, sheet: u32, row: i32, column: i32) -> Result<String, String> {
+        if self.model.is_cell_formula_hidden(sheet, row, column)? {
+            return Ok("".to_string());
+        }
         self.model.get_localized_cell_content(sheet, row, column)
     }
 
@@ -595,6 +760,7 @@ impl<'a> UserModel<'a> {
     /// See also:
     /// * [Model::new_sheet]
     pub fn new_sheet(&mut self) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let (name, index) = self.model.new_sheet();
         self.set_selected_sheet(index)?;
         self.push_diff_list(vec![Diff::NewSheet { index, name }]);
@@ -607,6 +773,7 @@ impl<'a> UserModel<'a> {
     /// See also:
     /// * [Model::duplicate_sheet]
     pub fn duplicate_sheet(&mut self, sheet: u32) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let (_name, new_index) = self.model.duplicate_sheet(sheet)?;
         self.set_selected_sheet(new_index)?;
         self.push_diff_list(vec![Diff::DuplicateSheet {
@@ -621,12 +788,18 @@ impl<'a> UserModel<'a> {
     /// See also:
     /// * [Model::delete_sheet]
     pub fn delete_sheet(&mut self, sheet: u32) -> Result<(), String> {
-        let worksheet = self.model.workbook.worksheet(sheet)?;
-
-        self.push_diff_list(vec![Diff::DeleteSheet {
-            sheet,
-            old_data: Box::new(worksheet.clone()),
-        }]);
+        self.model.check_workbook_structure()?;
+        let old_data = Box::new(self.model.workbook.worksheet(sheet)?.clone());
+        // Deleting a sheet might change the 3D references in other sheets and defined names
+        let old_formulas: Vec<(u32, Vec<String>)> = self
+            .model
+            .workbook
+            .worksheets
+            .iter()
+            .map(|worksheet| (worksheet.sheet_id, worksheet.shared_formulas.clone()))
+            .collect();
+        let old_defined_names = self.model.workbook.defined_names.clone();
+        let old_tables = self.model.get_tables(sheet)?;
 
         let sheet_count = self.model.workbook.worksheets.len() as u32;
         // If we are deleting the last sheet we need to change the selected sheet
@@ -637,6 +810,28 @@ impl<'a> UserModel<'a> {
         }
 
         self.model.delete_sheet(sheet)?;
+
+        let worksheets = &self.model.workbook.worksheets;
+        let old_formulas = old_formulas
+            .into_iter()
+            .filter(|(sheet_id, formulas)| {
+                worksheets
+                    .iter()
+                    .any(|ws| ws.sheet_id == *sheet_id && ws.shared_formulas != *formulas)
+            })
+            .collect();
+        let old_defined_names = if self.model.workbook.defined_names != old_defined_names {
+            Some(old_defined_names)
+        } else {
+            None
+        };
+        self.push_diff_list(vec![Diff::DeleteSheet {
+            sheet,
+            old_data,
+            old_formulas,
+            old_defined_names,
+            old_tables,
+        }]);
         Ok(())
     }
 
@@ -645,6 +840,7 @@ impl<'a> UserModel<'a> {
     /// See also:
     /// * [Model::rename_sheet_by_index]
     pub fn rename_sheet(&mut self, sheet: u32, new_name: &str) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let old_value = self.model.workbook.worksheet(sheet)?.name.clone();
         if old_value == new_name {
             return Ok(());
@@ -673,6 +869,7 @@ impl<'a> UserModel<'a> {
     /// See also:
     /// * [Model::move_sheet]
     pub fn move_sheet(&mut self, sheet_index: u32, new_index: u32) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let sheet_count = self.model.workbook.worksheets.len() as u32;
         if sheet_index >= sheet_count {
             return Err(format!("Invalid sheet index {sheet_index}"));
@@ -699,6 +896,7 @@ impl<'a> UserModel<'a> {
     /// * [Model::set_sheet_state]
     /// * [UserModel::unhide_sheet]
     pub fn hide_sheet(&mut self, sheet: u32) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let sheet_count = self.model.workbook.worksheets.len() as u32;
         for index in 1..sheet_count {
             let sheet_index = (sheet + index) % sheet_count;
@@ -725,6 +923,7 @@ impl<'a> UserModel<'a> {
     /// * [Model::set_sheet_state]
     /// * [UserModel::hide_sheet]
     pub fn unhide_sheet(&mut self, sheet: u32) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let old_value = self.model.workbook.worksheet(sheet)?.state.clone();
         self.push_diff_list(vec![Diff::SetSheetState {
             index: sheet,
@@ -743,6 +942,7 @@ impl<'a> UserModel<'a> {
     /// * [Model::set_sheet_color]
     /// * [UserModel::get_worksheets_properties]
     pub fn set_sheet_color(&mut self, sheet: u32, color: &Color) -> Result<(), String> {
+        self.model.check_workbook_structure()?;
         let old_value = self.model.workbook.worksheet(sheet)?.color.clone();
         self.model.set_sheet_color(sheet, color)?;
         self.push_diff_list(vec![Diff::SetSheetColor {
@@ -759,6 +959,13 @@ impl<'a> UserModel<'a> {
     /// * [Model::range_clear_all]
     pub fn range_clear_all(&mut self, range: &Area) -> Result<(), String> {
         let sheet = range.sheet;
+        self.model.check_cells_editable(
+            sheet,
+            range.row,
+            range.column,
+            range.width,
+            range.height,
+        )?;
         // TODO: full rows/columns
         let mut old_value = Vec::new();
         let mut old_style = Vec::new();
@@ -795,7 +1002,7 @@ impl<'a> UserModel<'a> {
         diff_list.extend(link_diffs);
 
         self.push_diff_list(diff_list);
-        self.evaluate_if_not_paused();
+        self.evaluate_incremental_if_not_paused();
         Ok(())
     }
 
@@ -805,6 +1012,13 @@ impl<'a> UserModel<'a> {
     /// * [Model::range_clear_contents]
     pub fn range_clear_contents(&mut self, range: &Area) -> Result<(), String> {
         let sheet = range.sheet;
+        self.model.check_cells_editable(
+            sheet,
+            range.row,
+            range.column,
+            range.width,
+            range.height,
+        )?;
         // TODO: full rows/columns
         let mut old_value = Vec::new();
         for row in range.row..range.row + range.height {
@@ -833,7 +1047,7 @@ impl<'a> UserModel<'a> {
         }];
         diff_list.extend(link_diffs);
         self.push_diff_list(diff_list);
-        self.evaluate_if_not_paused();
+        self.evaluate_incremental_if_not_paused();
         Ok(())
     }
 
@@ -1008,6 +1222,13 @@ impl<'a> UserModel<'a> {
     /// * [UserModel::range_clear_contents]
     pub fn range_clear_formatting(&mut self, range: &Area) -> Result<(), String> {
         let sheet = range.sheet;
+        self.model.check_cells_formattable(
+            sheet,
+            range.row,
+            range.column,
+            range.width,
+            range.height,
+        )?;
         let mut diff_list = Vec::new();
         if range.row == 1 && range.height == LAST_ROW {
             for column in range.column..range.column + range.width {
@@ -1072,6 +1293,8 @@ impl<'a> UserModel<'a> {
     ///
     /// See also [`Model::insert_rows`].
     pub fn insert_rows(&mut self, sheet: u32, row: i32, row_count: i32) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.insert_rows)?;
         self.model.insert_rows(sheet, row, row_count)?;
 
         let diff_list = vec![Diff::InsertRows {
@@ -1102,6 +1325,8 @@ impl<'a> UserModel<'a> {
         column: i32,
         column_count: i32,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.insert_columns)?;
         self.model.insert_columns(sheet, column, column_count)?;
 
         let diff_list = vec![Diff::InsertColumns {
@@ -1122,6 +1347,10 @@ impl<'a> UserModel<'a> {
     ///
     /// See also [`Model::delete_rows`].
     pub fn delete_rows(&mut self, sheet: u32, row: i32, row_count: i32) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.delete_rows)?;
+        self.model
+            .check_cells_editable(sheet, row, 1, LAST_COLUMN, row_count)?;
         let worksheet = self.model.workbook.worksheet(sheet)?;
         let mut old_data = Vec::new();
         // Collect data for all rows to be deleted
@@ -1158,16 +1387,82 @@ impl<'a> UserModel<'a> {
         // The links of the deleted rows cannot be restored by re-inserting the
         // rows: capture them for undo. Links below the deleted rows just shift
         // with their cells, [`Model::delete_rows`] takes care of them.
-        let mut diff_list = self.range_link_diffs(&Area {
+        let deleted_area = Area {
             sheet,
             row,
             column: 1,
             width: LAST_COLUMN,
             height: row_count,
-        })?;
-
+        };
+        let mut diff_list = self.range_link_diffs(&deleted_area)?;
+        // Same for the tables that lose rows
+        diff_list.extend(self.table_snapshot_diffs(&deleted_area)?);
+
+        // The comments of the deleted cells are restored after the rows are re-inserted
+        diff_list.splice(
+            0..0,
+            self.delete_comments_diffs(sheet, (row, row + row_count - 1), (1, LAST_COLUMN))?,
+        );
+        let old_merged_cells = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+
+        let old_data_validations = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .clone();
+        let old_auto_filter = self.model.workbook.worksheet(sheet)?.auto_filter.clone();
+        let old_page_setup = self.model.workbook.worksheet(sheet)?.page_setup.clone();
         self.model.delete_rows(sheet, row, row_count)?;
 
+        // Re-inserting the rows does not restore the merged cells
+        if self.model.workbook.worksheet(sheet)?.merge_cells != old_merged_cells {
+            diff_list.insert(
+                0,
+                Diff::SetMergedCells {
+                    sheet,
+                    old_value: old_merged_cells.clone(),
+                    new_value: old_merged_cells,
+                },
+            );
+        }
+
+        // Nor the data validation rules of the deleted cells
+        if self.model.workbook.worksheet(sheet)?.data_validations != old_data_validations {
+            diff_list.insert(
+                0,
+                Diff::SetDataValidations {
+                    sheet,
+                    old_value: old_data_validations.clone(),
+                    new_value: old_data_validations,
+                },
+            );
+        }
+
+        // Nor the autofilter of the sheet
+        if self.model.workbook.worksheet(sheet)?.auto_filter != old_auto_filter {
+            diff_list.insert(
+                0,
+                Diff::SetAutoFilter {
+                    sheet,
+                    old_value: old_auto_filter.clone(),
+                    new_value: old_auto_filter,
+                },
+            );
+        }
+
+        // Nor the print area, print titles and page breaks
+        if self.model.workbook.worksheet(sheet)?.page_setup != old_page_setup {
+            diff_list.insert(
+                0,
+                Diff::SetPageSetup {
+                    sheet,
+                    old_value: Box::new(old_page_setup.clone()),
+                    new_value: Box::new(old_page_setup),
+                },
+            );
+        }
+
         diff_list.push(Diff::DeleteRows {
             sheet,
             row,
@@ -1192,6 +1487,10 @@ impl<'a> UserModel<'a> {
         column: i32,
         column_count: i32,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.delete_columns)?;
+        self.model
+            .check_cells_editable(sheet, 1, column, column_count, LAST_ROW)?;
         let worksheet = self.model.workbook.worksheet(sheet)?;
         let mut old_data = Vec::new();
         // Collect data for all columns to be deleted
@@ -1206,6 +1505,8 @@ impl<'a> UserModel<'a> {
                         custom_width: col.custom_width,
                         style: col.style,
                         hidden: col.hidden,
+                        outline_level: col.outline_level,
+                        collapsed: col.collapsed,
                     });
                     break;
                 }
@@ -1235,16 +1536,82 @@ impl<'a> UserModel<'a> {
         // the columns: capture them for undo. Links to the right of the deleted
         // columns just shift with their cells, [`Model::delete_columns`] takes
         // care of them.
-        let mut diff_list = self.range_link_diffs(&Area {
+        let deleted_area = Area {
             sheet,
             row: 1,
             column,
             width: column_count,
             height: LAST_ROW,
-        })?;
-
+        };
+        let mut diff_list = self.range_link_diffs(&deleted_area)?;
+        // Same for the tables that lose columns
+        diff_list.extend(self.table_snapshot_diffs(&deleted_area)?);
+
+        // The comments of the deleted cells are restored after the columns are re-inserted
+        diff_list.splice(
+            0..0,
+            self.delete_comments_diffs(sheet, (1, LAST_ROW), (column, column + column_count - 1))?,
+        );
+        let old_merged_cells = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+
+        let old_data_validations = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .clone();
+        let old_auto_filter = self.model.workbook.worksheet(sheet)?.auto_filter.clone();
+        let old_page_setup = self.model.workbook.worksheet(sheet)?.page_setup.clone();
         self.model.delete_columns(sheet, column, column_count)?;
 
+        // Re-inserting the columns does not restore the merged cells
+        if self.model.workbook.worksheet(sheet)?.merge_cells != old_merged_cells {
+            diff_list.insert(
+                0,
+                Diff::SetMergedCells {
+                    sheet,
+                    old_value: old_merged_cells.clone(),
+                    new_value: old_merged_cells,
+                },
+            );
+        }
+
+        // Nor the data validation rules of the deleted cells
+        if self.model.workbook.worksheet(sheet)?.data_validations != old_data_validations {
+            diff_list.insert(
+                0,
+                Diff::SetDataValidations {
+                    sheet,
+                    old_value: old_data_validations.clone(),
+                    new_value: old_data_validations,
+                },
+            );
+        }
+
+        // Nor the autofilter of the sheet
+        if self.model.workbook.worksheet(sheet)?.auto_filter != old_auto_filter {
+            diff_list.insert(
+                0,
+                Diff::SetAutoFilter {
+                    sheet,
+                    old_value: old_auto_filter.clone(),
+                    new_value: old_auto_filter,
+                },
+            );
+        }
+
+        // Nor the print area, print titles and page breaks
+        if self.model.workbook.worksheet(sheet)?.page_setup != old_page_setup {
+            diff_list.insert(
+                0,
+                Diff::SetPageSetup {
+                    sheet,
+                    old_value: Box::new(old_page_setup.clone()),
+                    new_value: Box::new(old_page_setup),
+                },
+            );
+        }
+
         diff_list.push(Diff::DeleteColumns {
             sheet,
             column,
@@ -1267,6 +1634,7 @@ impl<'a> UserModel<'a> {
         if delta == 0 || column_count <= 0 {
             return Ok(());
         }
+        self.model.check_sheet_operation(sheet, |_| false)?;
         // Adjust delta to skip hidden columns in the landing zone
         let mut new_delta = delta;
         let worksheet = self.model.workbook.worksheet(sheet)?;
@@ -1308,6 +1676,7 @@ impl<'a> UserModel<'a> {
         if delta == 0 || row_count <= 0 {
             return Ok(());
         }
+        self.model.check_sheet_operation(sheet, |_| false)?;
         let mut new_delta = delta;
         let worksheet = self.model.workbook.worksheet(sheet)?;
         if delta > 0 {
@@ -1348,6 +1717,8 @@ impl<'a> UserModel<'a> {
         column_end: i32,
         width: f64,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_columns)?;
         let mut diff_list = Vec::new();
         for column in column_start..=column_end {
             let old_value = self.model.get_column_width(sheet, column)?;
@@ -1374,6 +1745,8 @@ impl<'a> UserModel<'a> {
         column_end: i32,
         hidden: bool,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_columns)?;
         let mut diff_list = Vec::new();
         for column in column_start..=column_end {
             let old_value = self
@@ -1442,6 +1815,8 @@ impl<'a> UserModel<'a> {
         row_end: i32,
         hidden: bool,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_rows)?;
         let mut diff_list = Vec::new();
         for row in row_start..=row_end {
             let old_value = self.model.workbook.worksheet(sheet)?.is_row_hidden(row)?;
@@ -1497,6 +1872,8 @@ impl<'a> UserModel<'a> {
         row_end: i32,
         height: f64,
     ) -> Result<(), String> {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_rows)?;
         let mut diff_list = Vec::new();
         for row in row_start..=row_end {
             let old_value = self.model.get_row_height(sheet, row)?;
@@ -1603,6 +1980,13 @@ impl<'a> UserModel<'a> {
         let [row_start, column_start, row_end, column_end] = range;
         let last_row = row_end.max(row_start + styles_height - 1);
         let last_column = column_end.max(column_start + styles_width - 1);
+        self.model.check_cells_formattable(
+            sheet,
+            row_start,
+            column_start,
+            last_column - column_start + 1,
+            last_row - row_start + 1,
+        )?;
 
         let mut diff_list = Vec::new();
         for row in row_start..=last_row {
@@ -1670,6 +2054,13 @@ impl<'a> UserModel<'a> {
         value: &str,
     ) -> Result<(), String> {
         let sheet = range.sheet;
+        self.model.check_cells_formattable(
+            sheet,
+            range.row,
+            range.column,
+            range.width,
+            range.height,
+        )?;
         let mut diff_list = Vec::new();
         if range.row == 1 && range.height == LAST_ROW {
             // Full columns
@@ -2126,6 +2517,8 @@ impl<'a> UserModel<'a> {
         height: i32,
         formula: &str,
     ) -> Result<(), String> {
+        self.model
+            .check_cells_editable(sheet, row, column, width, height)?;
         let ws = self.model.workbook.worksheet(sheet)?;
         let mut old_values = Vec::new();
         for r in row..row + height {
@@ -2262,6 +2655,43 @@ impl<'a> UserModel<'a> {
         self.model.set_locale(locale)
     }
 
+    /// Sets the iterative calculation settings used for circular references
+    pub fn set_iterative_calculation(
+        &mut self,
+        settings: IterativeCalculation,
+    ) -> Result<(), String> {
+        validate_iterative_calculation(&settings)?;
+        let diff_list = vec![Diff::SetIterativeCalculation {
+            old_value: self.get_iterative_calculation(),
+            new_value: settings.clone(),
+        }];
+        self.model.workbook.settings.iterative_calculation = settings;
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Gets the iterative calculation settings
+    pub fn get_iterative_calculation(&self) -> IterativeCalculation {
+        self.model.get_iterative_calculation()
+    }
+
+    /// Sets the date system (1900 or 1904) of the workbook
+    pub fn set_date_system(&mut self, date_system: DateSystem) {
+        let diff_list = vec![Diff::SetDateSystem {
+            old_value: self.get_date_system(),
+            new_value: date_system,
+        }];
+        self.push_diff_list(diff_list);
+        self.model.workbook.settings.date_system = date_system;
+        self.evaluate_if_not_paused();
+    }
+
+    /// Gets the date system of the workbook
+    pub fn get_date_system(&self) -> DateSystem {
+        self.model.get_date_system()
+    }
+
     /// Gets the timezone of the model
     pub fn get_timezone(&self) -> String {
         self.model.get_timezone()
@@ -2300,6 +2730,17 @@ impl<'a> UserModel<'a> {
     pub(super) fn evaluate_if_not_paused(&mut self) {
         if !self.pause_evaluation {
             self.model.evaluate();
+        } else {
+            // Whatever changed was not tracked cell by cell
+            self.model.invalidate_dependency_graph();
+        }
+    }
+
+    /// Recomputes the cells that changed since the last evaluation and their dependents.
+    /// Only valid when the changes were cell contents, see [Model::evaluate_incremental].
+    pub(super) fn evaluate_incremental_if_not_paused(&mut self) {
+        if !self.pause_evaluation {
+            self.model.evaluate_incremental();
         }
     }
 }
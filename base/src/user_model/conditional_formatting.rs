@@ -35,6 +35,7 @@ impl<'a> UserModel<'a> {
         range: &str,
         rule: CfRuleInput,
     ) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |_| false)?;
         let priority = self.model.add_conditional_formatting(sheet, range, rule)?;
         // Read back the stored entry so the Diff contains the dxf_id that was assigned.
         let stored_rule = self
@@ -64,6 +65,7 @@ impl<'a> UserModel<'a> {
 
     /// Removes the CF rule at `index` from `sheet`.
     pub fn delete_conditional_formatting(&mut self, sheet: u32, index: u32) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |_| false)?;
         let old = self
             .model
             .delete_conditional_formatting(sheet, index as usize)?;
@@ -86,6 +88,7 @@ impl<'a> UserModel<'a> {
         new_range: &str,
         new_rule: CfRuleInput,
     ) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |_| false)?;
         let old =
             self.model
                 .update_conditional_formatting(sheet, index as usize, new_range, new_rule)?;
@@ -149,6 +152,7 @@ impl<'a> UserModel<'a> {
         index: u32,
         raise: bool,
     ) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |_| false)?;
         let before: Vec<u32> = self
             .model
             .workbook
@@ -0,0 +1,109 @@
+use crate::{
+    dv_types::{DataValidation, DataValidationAlert},
+    expressions::types::Area,
+};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the data validation rules of the sheet
+    pub fn get_data_validations(&self, sheet: u32) -> Result<Vec<DataValidation>, String> {
+        self.model.get_data_validations(sheet)
+    }
+
+    /// Returns the data validation rule of the cell, if any
+    pub fn get_data_validation(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<DataValidation>, String> {
+        self.model.get_data_validation(sheet, row, column)
+    }
+
+    /// Adds a data validation rule. The cells in its range lose their previous rule.
+    ///
+    /// See also:
+    /// * [Model::add_data_validation](crate::Model::add_data_validation)
+    pub fn add_data_validation(
+        &mut self,
+        sheet: u32,
+        data_validation: DataValidation,
+    ) -> Result<(), String> {
+        self.model.check_sheet_operation(sheet, |_| false)?;
+        let old_value = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .clone();
+        self.model.add_data_validation(sheet, data_validation)?;
+        self.push_data_validations_diff(sheet, old_value)
+    }
+
+    /// Removes the data validation rules from the cells in `area`
+    pub fn remove_data_validations(&mut self, area: &Area) -> Result<(), String> {
+        let sheet = area.sheet;
+        self.model.check_sheet_operation(sheet, |_| false)?;
+        let old_value = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .clone();
+        self.model.remove_data_validations(area)?;
+        self.push_data_validations_diff(sheet, old_value)
+    }
+
+    /// Checks the value the user wants to type in a cell against its data validation rule.
+    /// Returns the alert that should be shown if the value is not valid.
+    ///
+    /// See also:
+    /// * [Model::validate_user_input](crate::Model::validate_user_input)
+    pub fn validate_user_input(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        input: &str,
+    ) -> Result<Option<DataValidationAlert>, String> {
+        self.model.validate_user_input(sheet, row, column, input)
+    }
+
+    /// Returns the allowed values of the cell if it has a list rule
+    pub fn get_data_validation_list(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Vec<String>, String> {
+        self.model.get_data_validation_list(sheet, row, column)
+    }
+
+    /// Returns the non empty cells of the sheet that break their data validation rule
+    pub fn get_invalid_cells(&mut self, sheet: u32) -> Result<Vec<(i32, i32)>, String> {
+        self.model.get_invalid_cells(sheet)
+    }
+
+    fn push_data_validations_diff(
+        &mut self,
+        sheet: u32,
+        old_value: Vec<DataValidation>,
+    ) -> Result<(), String> {
+        let new_value = self
+            .model
+            .workbook
+            .worksheet(sheet)?
+            .data_validations
+            .clone();
+        if new_value == old_value {
+            return Ok(());
+        }
+        self.push_diff_list(vec![Diff::SetDataValidations {
+            sheet,
+            old_value,
+            new_value,
+        }]);
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(target_arch = "wasm32")]
+use regex_lite as regex;
+
+use crate::{
+    dv_types::DataValidationErrorStyle,
+    expressions::types::CellReferenceIndex,
+    find::get_find_regex,
+    find_types::{FindIn, FindMode, FindOptions},
+};
+
+use super::common::UserModel;
+
+impl<'a> UserModel<'a> {
+    /// Returns the cells whose content, value or comment matches `text`.
+    /// The formulas of cells that hide them in a protected sheet are not searched.
+    ///
+    /// See also:
+    /// * [Model::find](crate::Model::find)
+    pub fn find(
+        &self,
+        text: &str,
+        options: &FindOptions,
+    ) -> Result<impl Iterator<Item = CellReferenceIndex>, String> {
+        let mut found = Vec::new();
+        for cell in self.model.find(text, options)? {
+            if options.look_in == FindIn::Formulas
+                && self
+                    .model
+                    .is_cell_formula_hidden(cell.sheet, cell.row, cell.column)?
+            {
+                continue;
+            }
+            found.push(cell);
+        }
+        Ok(found.into_iter())
+    }
+
+    /// Replaces `text` with `replacement` in the content of all the cells that match it, as if
+    /// the user typed the new content. All the changes are undone at once.
+    /// Returns the number of cells that changed.
+    ///
+    /// Only the content of the cells can be replaced, `options.look_in` must be
+    /// [FindIn::Formulas]. Nothing is replaced if any of the cells can't be edited.
+    pub fn replace_all(
+        &mut self,
+        text: &str,
+        replacement: &str,
+        options: &FindOptions,
+    ) -> Result<usize, String> {
+        if options.look_in != FindIn::Formulas {
+            return Err("Only the content of the cells can be replaced".to_string());
+        }
+        let regex = get_find_regex(text, options)?;
+        let mut inputs = Vec::new();
+        for cell in self.find(text, options)? {
+            let CellReferenceIndex { sheet, row, column } = cell;
+            let content = self.model.get_localized_cell_content(sheet, row, column)?;
+            let new_content = match options.mode {
+                FindMode::Regex => regex.replace_all(&content, replacement),
+                FindMode::Text | FindMode::Wildcard => {
+                    regex.replace_all(&content, regex::NoExpand(replacement))
+                }
+            };
+            if new_content != content {
+                inputs.push((cell, new_content.into_owned()));
+            }
+        }
+        for (cell, value) in &inputs {
+            self.model
+                .check_cells_editable(cell.sheet, cell.row, cell.column, 1, 1)?;
+            if let Some(alert) =
+                self.model
+                    .validate_user_input(cell.sheet, cell.row, cell.column, value)?
+            {
+                if alert.style == DataValidationErrorStyle::Stop {
+                    return Err(alert.message);
+                }
+            }
+        }
+        let mut diff_list = Vec::new();
+        for (cell, value) in &inputs {
+            diff_list.extend(self.set_user_input_diffs(
+                cell.sheet,
+                cell.row,
+                cell.column,
+                value,
+            )?);
+        }
+        if !diff_list.is_empty() {
+            self.evaluate_incremental_if_not_paused();
+            self.push_diff_list(diff_list);
+        }
+        Ok(inputs.len())
+    }
+}
@@ -2,9 +2,20 @@ use std::collections::HashMap;
 
 use bitcode::{Decode, Encode};
 
+use super::clipboard::ClipboardValue;
+
 use crate::{
     cf_types::CfRule,
-    types::{Cell, Col, Color, Link, Row, SheetState, Style, StyleIncludes, Theme, Worksheet},
+    dv_types::DataValidation,
+    filter_types::AutoFilter,
+    page_setup_types::PageSetup,
+    rich_text_types::RichTextRun,
+    sort_types::SortState,
+    types::{
+        Cell, Col, Color, Comment, DateSystem, DefinedName, IterativeCalculation, Link, Row,
+        SheetProtection, SheetState, Style, StyleIncludes, Table, Theme, WorkbookProtection,
+        Worksheet,
+    },
 };
 
 #[derive(Clone, Encode, Decode)]
@@ -29,6 +40,20 @@ pub(crate) enum Diff {
         new_value: String,
         old_value: Box<Option<Cell>>,
     },
+    SetCellRichText {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        new_value: Vec<RichTextRun>,
+        old_value: Box<Option<Cell>>,
+    },
+    SetCellPastedValue {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        new_value: ClipboardValue,
+        old_value: Box<Option<Cell>>,
+    },
     SetArrayValue {
         sheet: u32,
         row: i32,
@@ -149,6 +174,12 @@ pub(crate) enum Diff {
     DeleteSheet {
         sheet: u32,
         old_data: Box<Worksheet>,
+        /// Shared formulas of other sheets (by sheet id) that changed because a 3D reference was shrunk
+        old_formulas: Vec<(u32, Vec<String>)>,
+        /// The defined names before deleting the sheet, if any of them changed
+        old_defined_names: Option<Vec<DefinedName>>,
+        /// The tables in the sheet
+        old_tables: Vec<Table>,
     },
     SetFrozenRowsCount {
         sheet: u32,
@@ -242,6 +273,14 @@ pub(crate) enum Diff {
         old_value: String,
         new_value: String,
     },
+    SetIterativeCalculation {
+        old_value: IterativeCalculation,
+        new_value: IterativeCalculation,
+    },
+    SetDateSystem {
+        old_value: DateSystem,
+        new_value: DateSystem,
+    },
     // Named style diffs
     CreateNamedStyle {
         name: String,
@@ -301,6 +340,105 @@ pub(crate) enum Diff {
         priority_a: u32,
         priority_b: u32,
     },
+    // Table diffs
+    /// Replaces the table `old_value` with `new_value`. A new table has no old value and
+    /// a deleted table has no new value. The cells of the table are not changed.
+    UpdateTable {
+        old_value: Box<Option<Table>>,
+        new_value: Box<Option<Table>>,
+    },
+    /// Renames a table and the formulas that use its name
+    RenameTable {
+        old_value: String,
+        new_value: String,
+    },
+    /// Deletes a table, the formulas that use its name use its range instead
+    ConvertTableToRange {
+        table: Box<Table>,
+        /// Shared formulas of the sheets (by sheet id) that used the name of the table
+        old_formulas: Vec<(u32, Vec<String>)>,
+        /// The defined names before the conversion, if any of them changed
+        old_defined_names: Option<Vec<DefinedName>>,
+    },
+    /// Replaces the merged areas of the sheet, like "B2:D3"
+    SetMergedCells {
+        sheet: u32,
+        old_value: Vec<String>,
+        new_value: Vec<String>,
+    },
+    /// Sets, edits or removes the comment of a cell
+    SetComment {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        old_value: Option<Box<Comment>>,
+        new_value: Option<Box<Comment>>,
+    },
+    /// Replaces the data validation rules of the sheet
+    SetDataValidations {
+        sheet: u32,
+        old_value: Vec<DataValidation>,
+        new_value: Vec<DataValidation>,
+    },
+    /// Protects or unprotects a sheet
+    SetSheetProtection {
+        sheet: u32,
+        old_value: Option<SheetProtection>,
+        new_value: Option<SheetProtection>,
+    },
+    /// Protects or unprotects the workbook
+    SetWorkbookProtection {
+        old_value: Option<WorkbookProtection>,
+        new_value: Option<WorkbookProtection>,
+    },
+    /// Sorts the lines of a range. The line at position `i` comes from position `order[i]`.
+    SortRange {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        width: i32,
+        height: i32,
+        left_to_right: bool,
+        order: Vec<i32>,
+        old_cells: Vec<(i32, i32, Cell)>,
+        old_value: Option<SortState>,
+        new_value: Option<SortState>,
+    },
+    /// Replaces the autofilter of the sheet. The hidden rows are not changed.
+    SetAutoFilter {
+        sheet: u32,
+        old_value: Option<AutoFilter>,
+        new_value: Option<AutoFilter>,
+    },
+    SetRowOutlineLevel {
+        sheet: u32,
+        row: i32,
+        new_value: u8,
+        old_value: u8,
+    },
+    SetRowCollapsed {
+        sheet: u32,
+        row: i32,
+        new_value: bool,
+        old_value: bool,
+    },
+    SetColumnOutlineLevel {
+        sheet: u32,
+        column: i32,
+        new_value: u8,
+        old_value: u8,
+    },
+    SetColumnCollapsed {
+        sheet: u32,
+        column: i32,
+        new_value: bool,
+        old_value: bool,
+    },
+    SetPageSetup {
+        sheet: u32,
+        old_value: Box<PageSetup>,
+        new_value: Box<PageSetup>,
+    },
     // FIXME: we are missing SetViewDiffs
 }
 
@@ -0,0 +1,104 @@
+use crate::expressions::types::Area;
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the merged areas of the sheet
+    pub fn get_merged_cells(&self, sheet: u32) -> Result<Vec<Area>, String> {
+        self.model.get_merged_cells(sheet)
+    }
+
+    /// Returns the merged area that contains the cell, if any
+    pub fn get_merged_area(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Area>, String> {
+        self.model.get_merged_area(sheet, row, column)
+    }
+
+    /// Merges the cells in `area`. Only the content of the top left cell is kept.
+    ///
+    /// See also:
+    /// * [Model::merge_cells](crate::Model::merge_cells)
+    pub fn merge_cells(&mut self, area: &Area) -> Result<(), String> {
+        let sheet = area.sheet;
+        self.model.check_sheet_operation(sheet, |_| false)?;
+        let old_value = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+        // The areas that are emptied: right of the top left cell and below it
+        let mut cleared_areas = Vec::new();
+        if area.width > 1 {
+            cleared_areas.push(Area {
+                column: area.column + 1,
+                width: area.width - 1,
+                height: 1,
+                ..*area
+            });
+        }
+        if area.height > 1 {
+            cleared_areas.push(Area {
+                row: area.row + 1,
+                height: area.height - 1,
+                ..*area
+            });
+        }
+        let mut diff_list = Vec::new();
+        for cleared_area in &cleared_areas {
+            diff_list.extend(self.clear_contents_diffs(cleared_area)?);
+        }
+
+        self.model.merge_cells(area)?;
+
+        let new_value = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+        diff_list.push(Diff::SetMergedCells {
+            sheet,
+            old_value,
+            new_value,
+        });
+        self.push_diff_list(diff_list);
+        self.evaluate_incremental_if_not_paused();
+        Ok(())
+    }
+
+    /// Unmerges all the merged areas that overlap `area`
+    pub fn unmerge_cells(&mut self, area: &Area) -> Result<(), String> {
+        let sheet = area.sheet;
+        self.model.check_sheet_operation(sheet, |_| false)?;
+        let old_value = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+        self.model.unmerge_cells(area)?;
+        let new_value = self.model.workbook.worksheet(sheet)?.merge_cells.clone();
+        if new_value == old_value {
+            return Ok(());
+        }
+        self.push_diff_list(vec![Diff::SetMergedCells {
+            sheet,
+            old_value,
+            new_value,
+        }]);
+        Ok(())
+    }
+
+    // The diffs that restore the contents and links of `area` after it is cleared
+    fn clear_contents_diffs(&self, area: &Area) -> Result<Vec<Diff>, String> {
+        let worksheet = self.model.workbook.worksheet(area.sheet)?;
+        let mut old_value = Vec::new();
+        for row in area.row..area.row + area.height {
+            let mut data_row = Vec::new();
+            for column in area.column..area.column + area.width {
+                data_row.push(worksheet.cell(row, column).cloned());
+            }
+            old_value.push(data_row);
+        }
+        let mut diff_list = vec![Diff::RangeClearContents {
+            sheet: area.sheet,
+            row: area.row,
+            column: area.column,
+            width: area.width,
+            height: area.height,
+            old_value,
+        }];
+        diff_list.extend(self.range_link_diffs(area)?);
+        Ok(diff_list)
+    }
+}
@@ -1,15 +1,27 @@
 #![deny(missing_docs)]
 
+mod auto_filter;
 mod autofill;
 mod border;
 mod border_utils;
 mod clipboard;
+mod clipboard_html;
+mod comments;
 mod common;
 mod conditional_formatting;
+mod data_validation;
+mod find;
 pub(crate) mod history;
 mod links;
+mod merged_cells;
 mod named_cell_styles;
+mod outline;
+mod page_setup;
+mod protection;
+mod rich_text;
 mod sequence_detector;
+mod sort;
+mod tables;
 mod ui;
 mod undo_redo;
 
@@ -18,5 +30,5 @@ pub use common::UserModel;
 #[cfg(test)]
 pub use ui::SelectedView;
 
-pub use clipboard::ClipboardData;
+pub use clipboard::{Clipboard, ClipboardData, PasteOperation, PasteOptions, PasteType};
 pub use common::BorderArea;
@@ -128,6 +128,13 @@ impl<'a> UserModel<'a> {
         };
 
         let [row_start, column_start, row_end, column_end] = range;
+        self.model.check_cells_formattable(
+            sheet,
+            row_start,
+            column_start,
+            column_end - column_start + 1,
+            row_end - row_start + 1,
+        )?;
         for row in row_start..=row_end {
             for column in column_start..=column_end {
                 let old_value = self.model.get_cell_style_or_none(sheet, row, column)?;
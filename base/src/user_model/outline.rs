@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{model::Model, outline_types::Outline};
+
+use super::{common::UserModel, history::Diff};
+
+// The outline level, collapsed flag and hidden flag of a row (or column)
+type OutlineValues = (u8, bool, bool);
+
+// The values of each row (or column) that has properties
+type OutlineState = BTreeMap<i32, OutlineValues>;
+
+impl<'a> UserModel<'a> {
+    fn get_row_outline_state(&self, sheet: u32) -> Result<OutlineState, String> {
+        let worksheet = self.model.workbook.worksheet(sheet)?;
+        Ok(worksheet
+            .rows
+            .iter()
+            .map(|row| (row.r, (row.outline_level, row.collapsed, row.hidden)))
+            .collect())
+    }
+
+    fn get_column_outline_state(&self, sheet: u32) -> Result<OutlineState, String> {
+        let worksheet = self.model.workbook.worksheet(sheet)?;
+        let mut state = OutlineState::new();
+        for col in &worksheet.cols {
+            for column in col.min..=col.max {
+                state.insert(column, (col.outline_level, col.collapsed, col.hidden));
+            }
+        }
+        Ok(state)
+    }
+
+    // Runs an outline operation on the rows and pushes the changes as a single undo step
+    fn update_row_outline<F>(&mut self, sheet: u32, update: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Model<'a>) -> Result<(), String>,
+    {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_rows)?;
+        let old_state = self.get_row_outline_state(sheet)?;
+        update(&mut self.model)?;
+        let new_state = self.get_row_outline_state(sheet)?;
+        let mut diff_list = Vec::new();
+        for (row, (old, new)) in outline_changes(&old_state, &new_state) {
+            if old.0 != new.0 {
+                diff_list.push(Diff::SetRowOutlineLevel {
+                    sheet,
+                    row,
+                    new_value: new.0,
+                    old_value: old.0,
+                });
+            }
+            if old.1 != new.1 {
+                diff_list.push(Diff::SetRowCollapsed {
+                    sheet,
+                    row,
+                    new_value: new.1,
+                    old_value: old.1,
+                });
+            }
+            if old.2 != new.2 {
+                diff_list.push(Diff::SetRowHidden {
+                    sheet,
+                    row,
+                    new_value: new.2,
+                    old_value: old.2,
+                });
+            }
+        }
+        if !diff_list.is_empty() {
+            self.push_diff_list(diff_list);
+            self.evaluate_if_not_paused();
+        }
+        Ok(())
+    }
+
+    // Runs an outline operation on the columns and pushes the changes as a single undo step
+    fn update_column_outline<F>(&mut self, sheet: u32, update: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Model<'a>) -> Result<(), String>,
+    {
+        self.model
+            .check_sheet_operation(sheet, |protection| protection.format_columns)?;
+        let old_state = self.get_column_outline_state(sheet)?;
+        update(&mut self.model)?;
+        let new_state = self.get_column_outline_state(sheet)?;
+        let mut diff_list = Vec::new();
+        for (column, (old, new)) in outline_changes(&old_state, &new_state) {
+            if old.0 != new.0 {
+                diff_list.push(Diff::SetColumnOutlineLevel {
+                    sheet,
+                    column,
+                    new_value: new.0,
+                    old_value: old.0,
+                });
+            }
+            if old.1 != new.1 {
+                diff_list.push(Diff::SetColumnCollapsed {
+                    sheet,
+                    column,
+                    new_value: new.1,
+                    old_value: old.1,
+                });
+            }
+            if old.2 != new.2 {
+                diff_list.push(Diff::SetColumnHidden {
+                    sheet,
+                    column,
+                    new_value: new.2,
+                    old_value: old.2,
+                });
+            }
+        }
+        if !diff_list.is_empty() {
+            self.push_diff_list(diff_list);
+            self.evaluate_if_not_paused();
+        }
+        Ok(())
+    }
+
+    /// Groups the rows from `start` to `end`, one level deeper than the groups they are in
+    ///
+    /// See also:
+    /// * [Model::group_rows](crate::Model::group_rows)
+    pub fn group_rows(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.update_row_outline(sheet, |model| model.group_rows(sheet, start, end))
+    }
+
+    /// Removes the rows from `start` to `end` from their innermost group
+    pub fn ungroup_rows(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.update_row_outline(sheet, |model| model.ungroup_rows(sheet, start, end))
+    }
+
+    /// Groups the columns from `start` to `end`, one level deeper than the groups they are in
+    ///
+    /// See also:
+    /// * [Model::group_columns](crate::Model::group_columns)
+    pub fn group_columns(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.update_column_outline(sheet, |model| model.group_columns(sheet, start, end))
+    }
+
+    /// Removes the columns from `start` to `end` from their innermost group
+    pub fn ungroup_columns(&mut self, sheet: u32, start: i32, end: i32) -> Result<(), String> {
+        self.update_column_outline(sheet, |model| model.ungroup_columns(sheet, start, end))
+    }
+
+    /// Collapses or expands the group of rows of outline `level` that contains `row`
+    ///
+    /// See also:
+    /// * [Model::set_row_group_collapsed](crate::Model::set_row_group_collapsed)
+    pub fn set_row_group_collapsed(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        level: u8,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        self.update_row_outline(sheet, |model| {
+            model.set_row_group_collapsed(sheet, row, level, collapsed)
+        })
+    }
+
+    /// Collapses or expands the group of columns of outline `level` that contains `column`
+    ///
+    /// See also:
+    /// * [Model::set_column_group_collapsed](crate::Model::set_column_group_collapsed)
+    pub fn set_column_group_collapsed(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        level: u8,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        self.update_column_outline(sheet, |model| {
+            model.set_column_group_collapsed(sheet, column, level, collapsed)
+        })
+    }
+
+    /// Collapses the groups of rows of outline `level` or deeper and expands the rest
+    ///
+    /// See also:
+    /// * [Model::show_row_outline_level](crate::Model::show_row_outline_level)
+    pub fn show_row_outline_level(&mut self, sheet: u32, level: u8) -> Result<(), String> {
+        self.update_row_outline(sheet, |model| model.show_row_outline_level(sheet, level))
+    }
+
+    /// Collapses the groups of columns of outline `level` or deeper and expands the rest
+    ///
+    /// See also:
+    /// * [Model::show_column_outline_level](crate::Model::show_column_outline_level)
+    pub fn show_column_outline_level(&mut self, sheet: u32, level: u8) -> Result<(), String> {
+        self.update_column_outline(sheet, |model| model.show_column_outline_level(sheet, level))
+    }
+
+    /// Returns the groups of rows of the sheet, to draw the outline symbols
+    pub fn get_row_outline(&self, sheet: u32) -> Result<Outline, String> {
+        self.model.get_row_outline(sheet)
+    }
+
+    /// Returns the groups of columns of the sheet, to draw the outline symbols
+    pub fn get_column_outline(&self, sheet: u32) -> Result<Outline, String> {
+        self.model.get_column_outline(sheet)
+    }
+}
+
+// The rows (or columns) whose state changed, with the old and the new one
+fn outline_changes(
+    old_state: &OutlineState,
+    new_state: &OutlineState,
+) -> Vec<(i32, (OutlineValues, OutlineValues))> {
+    let indices: BTreeSet<i32> = old_state.keys().chain(new_state.keys()).copied().collect();
+    let mut changes = Vec::new();
+    for index in indices {
+        let old = old_state.get(&index).copied().unwrap_or_default();
+        let new = new_state.get(&index).copied().unwrap_or_default();
+        if old != new {
+            changes.push((index, (old, new)));
+        }
+    }
+    changes
+}
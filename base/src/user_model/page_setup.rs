@@ -0,0 +1,68 @@
+use crate::{expressions::types::Area, page_setup_types::PageSetup, tables::area_to_reference};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns how the sheet is printed
+    pub fn get_page_setup(&self, sheet: u32) -> Result<PageSetup, String> {
+        self.model.get_page_setup(sheet)
+    }
+
+    /// Changes how the sheet is printed
+    ///
+    /// See also:
+    /// * [Model::set_page_setup](crate::Model::set_page_setup)
+    pub fn set_page_setup(&mut self, sheet: u32, page_setup: PageSetup) -> Result<(), String> {
+        let old_value = self.model.get_page_setup(sheet)?;
+        self.model.set_page_setup(sheet, page_setup)?;
+        let new_value = self.model.get_page_setup(sheet)?;
+        if new_value != old_value {
+            self.push_diff_list(vec![Diff::SetPageSetup {
+                sheet,
+                old_value: Box::new(old_value),
+                new_value: Box::new(new_value),
+            }]);
+        }
+        Ok(())
+    }
+
+    /// Prints only `area` of the sheet, or the whole sheet if `None`
+    pub fn set_print_area(&mut self, sheet: u32, area: Option<&Area>) -> Result<(), String> {
+        let mut page_setup = self.model.get_page_setup(sheet)?;
+        page_setup.print_area = match area {
+            Some(area) => vec![area_to_reference(area)?],
+            None => vec![],
+        };
+        self.set_page_setup(sheet, page_setup)
+    }
+
+    /// Adds (or removes) a manual page break after `row`
+    pub fn set_row_page_break(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        page_break: bool,
+    ) -> Result<(), String> {
+        let mut page_setup = self.model.get_page_setup(sheet)?;
+        page_setup.row_breaks.retain(|&r| r != row);
+        if page_break {
+            page_setup.row_breaks.push(row);
+        }
+        self.set_page_setup(sheet, page_setup)
+    }
+
+    /// Adds (or removes) a manual page break after `column`
+    pub fn set_column_page_break(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        page_break: bool,
+    ) -> Result<(), String> {
+        let mut page_setup = self.model.get_page_setup(sheet)?;
+        page_setup.column_breaks.retain(|&c| c != column);
+        if page_break {
+            page_setup.column_breaks.push(column);
+        }
+        self.set_page_setup(sheet, page_setup)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::types::{SheetProtection, WorkbookProtection};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the protection of the sheet, if it is protected
+    pub fn get_sheet_protection(&self, sheet: u32) -> Result<Option<SheetProtection>, String> {
+        self.model.get_sheet_protection(sheet)
+    }
+
+    /// Protects the sheet. While the sheet is protected locked cells can't be edited.
+    ///
+    /// See also:
+    /// * [Model::protect_sheet](crate::Model::protect_sheet)
+    pub fn protect_sheet(&mut self, sheet: u32, protection: SheetProtection) -> Result<(), String> {
+        let old_value = self.model.get_sheet_protection(sheet)?;
+        self.model.protect_sheet(sheet, protection.clone())?;
+        self.push_diff_list(vec![Diff::SetSheetProtection {
+            sheet,
+            old_value,
+            new_value: Some(protection),
+        }]);
+        Ok(())
+    }
+
+    /// Removes the protection of the sheet. Fails if the password is not correct.
+    ///
+    /// See also:
+    /// * [Model::unprotect_sheet](crate::Model::unprotect_sheet)
+    pub fn unprotect_sheet(&mut self, sheet: u32, password: &str) -> Result<(), String> {
+        let old_value = self.model.get_sheet_protection(sheet)?;
+        if old_value.is_none() {
+            return Ok(());
+        }
+        self.model.unprotect_sheet(sheet, password)?;
+        self.push_diff_list(vec![Diff::SetSheetProtection {
+            sheet,
+            old_value,
+            new_value: None,
+        }]);
+        Ok(())
+    }
+
+    /// Returns the protection of the workbook, if it is protected
+    pub fn get_workbook_protection(&self) -> Option<WorkbookProtection> {
+        self.model.get_workbook_protection()
+    }
+
+    /// Protects the workbook. With a locked structure sheets can't be added, deleted or renamed.
+    pub fn protect_workbook(&mut self, protection: WorkbookProtection) {
+        let old_value = self.model.get_workbook_protection();
+        self.model.protect_workbook(protection.clone());
+        self.push_diff_list(vec![Diff::SetWorkbookProtection {
+            old_value,
+            new_value: Some(protection),
+        }]);
+    }
+
+    /// Removes the protection of the workbook. Fails if the password is not correct.
+    pub fn unprotect_workbook(&mut self, password: &str) -> Result<(), String> {
+        let old_value = self.model.get_workbook_protection();
+        if old_value.is_none() {
+            return Ok(());
+        }
+        self.model.unprotect_workbook(password)?;
+        self.push_diff_list(vec![Diff::SetWorkbookProtection {
+            old_value,
+            new_value: None,
+        }]);
+        Ok(())
+    }
+
+    /// Returns true if the sheet is protected and the cell is locked
+    pub fn is_cell_locked(&self, sheet: u32, row: i32, column: i32) -> Result<bool, String> {
+        self.model.is_cell_locked(sheet, row, column)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    dv_types::DataValidationErrorStyle,
+    expressions::utils::{is_valid_column_number, is_valid_row},
+    rich_text_types::{get_rich_text_plain_text, RichTextRun},
+    types::Cell,
+};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the runs of the text of the cell if it is a rich text, for the frontend to
+    /// render each fragment with its font
+    pub fn get_cell_rich_text(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<Vec<RichTextRun>>, String> {
+        self.model.get_cell_rich_text(sheet, row, column)
+    }
+
+    /// Sets the value of the cell to a rich text
+    ///
+    /// See also:
+    /// * [Model::set_cell_rich_text](crate::Model::set_cell_rich_text)
+    pub fn set_cell_rich_text(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        runs: Vec<RichTextRun>,
+    ) -> Result<(), String> {
+        if !is_valid_column_number(column) {
+            return Err("Invalid column".to_string());
+        }
+        if !is_valid_row(row) {
+            return Err("Invalid row".to_string());
+        }
+        self.model.check_cells_editable(sheet, row, column, 1, 1)?;
+        let text = get_rich_text_plain_text(&runs);
+        if let Some(alert) = self.model.validate_user_input(sheet, row, column, &text)? {
+            if alert.style == DataValidationErrorStyle::Stop {
+                return Err(alert.message);
+            }
+        }
+        let old_value = match self.model.workbook.worksheet(sheet)?.cell(row, column) {
+            // The value of a spill cell comes from its anchor cell
+            Some(Cell::SpillCell { .. }) => None,
+            cell => cell.cloned(),
+        };
+        self.model
+            .set_cell_rich_text(sheet, row, column, runs.clone())?;
+        self.evaluate_incremental_if_not_paused();
+        self.push_diff_list(vec![Diff::SetCellRichText {
+            sheet,
+            row,
+            column,
+            new_value: runs,
+            old_value: Box::new(old_value),
+        }]);
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    expressions::types::Area,
+    sort_types::{SortKey, SortState},
+};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Sorts the rows of the range in place, or its columns if `left_to_right` is true.
+    /// If `has_headers` the first row (or column) is not sorted.
+    ///
+    /// See also:
+    /// * [Model::sort_range](crate::Model::sort_range)
+    pub fn sort_range(
+        &mut self,
+        area: &Area,
+        has_headers: bool,
+        left_to_right: bool,
+        keys: &[SortKey],
+    ) -> Result<(), String> {
+        let sheet = area.sheet;
+        self.model.check_sheet_operation(sheet, |p| p.sort)?;
+        let Some(sort_area) = self.model.get_sort_area(area, has_headers, left_to_right)? else {
+            return Ok(());
+        };
+        self.model.check_cells_editable(
+            sheet,
+            sort_area.row,
+            sort_area.column,
+            sort_area.width,
+            sort_area.height,
+        )?;
+        let old_cells = self.model.get_area_cells(&sort_area)?;
+        let old_value = self.model.get_sort_state(sheet)?;
+        let order = self
+            .model
+            .sort_range(area, has_headers, left_to_right, keys)?;
+        let new_value = self.model.get_sort_state(sheet)?;
+        self.push_diff_list(vec![Diff::SortRange {
+            sheet,
+            row: sort_area.row,
+            column: sort_area.column,
+            width: sort_area.width,
+            height: sort_area.height,
+            left_to_right,
+            order,
+            old_cells,
+            old_value,
+            new_value,
+        }]);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Returns the last sort applied to the sheet, if any
+    pub fn get_sort_state(&self, sheet: u32) -> Result<Option<SortState>, String> {
+        self.model.get_sort_state(sheet)
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{
+    expressions::types::Area,
+    types::{Cell, Table},
+};
+
+use super::{common::UserModel, history::Diff};
+
+impl<'a> UserModel<'a> {
+    /// Returns the tables in the sheet, sorted by name
+    ///
+    /// See also:
+    /// * [Model::get_tables](crate::Model::get_tables)
+    pub fn get_tables(&self, sheet: u32) -> Result<Vec<Table>, String> {
+        self.model.get_tables(sheet)
+    }
+
+    /// Returns the name of the table the cell belongs to, if any
+    pub fn get_table_name_at(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<String>, String> {
+        self.model.get_table_name_at(sheet, row, column)
+    }
+
+    /// Returns an error if `name` can't be used as the name of a new table
+    pub fn is_valid_table_name(&self, name: &str) -> Result<(), String> {
+        self.model.is_valid_table_name(name)
+    }
+
+    /// Creates a table in `area` and returns its name
+    ///
+    /// See also:
+    /// * [Model::create_table](crate::Model::create_table)
+    pub fn create_table(&mut self, area: &Area, has_headers: bool) -> Result<String, String> {
+        let header = Area { height: 1, ..*area };
+        let old_cells = self.get_area_cells(&header)?;
+        let name = self.model.create_table(area, has_headers)?;
+        self.push_table_diffs(None, &name, &[(header, old_cells)])?;
+        Ok(name)
+    }
+
+    /// Changes the area of the table. The new area must start in the same row.
+    ///
+    /// See also:
+    /// * [Model::resize_table](crate::Model::resize_table)
+    pub fn resize_table(&mut self, name: &str, area: &Area) -> Result<(), String> {
+        let old_table = self.model.get_table(name)?;
+        let header = Area { height: 1, ..*area };
+        let old_cells = self.get_area_cells(&header)?;
+        self.model.resize_table(name, area)?;
+        self.push_table_diffs(Some(old_table), name, &[(header, old_cells)])
+    }
+
+    /// Renames the table and the formulas that use its name
+    pub fn rename_table(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        let old_value = self.model.get_table(name)?.name;
+        self.model.rename_table(name, new_name)?;
+        let new_value = self.model.get_table(new_name)?.name;
+        if old_value == new_value {
+            return Ok(());
+        }
+        self.push_diff_list(vec![Diff::RenameTable {
+            old_value,
+            new_value,
+        }]);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Deletes the table and the contents of its cells
+    pub fn delete_table(&mut self, name: &str) -> Result<(), String> {
+        let table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&table)?;
+        let old_value = self.get_area_cells(&area)?;
+        let link_diffs = self.range_link_diffs(&area)?;
+        self.model.delete_table(name)?;
+        let mut diff_list = vec![Diff::RangeClearContents {
+            sheet: area.sheet,
+            row: area.row,
+            column: area.column,
+            width: area.width,
+            height: area.height,
+            old_value,
+        }];
+        diff_list.extend(link_diffs);
+        diff_list.push(Diff::UpdateTable {
+            old_value: Box::new(Some(table)),
+            new_value: Box::new(None),
+        });
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Converts the table into a normal range. The cells are not changed and formulas that use
+    /// the name of the table use the range of its data instead.
+    pub fn convert_table_to_range(&mut self, name: &str) -> Result<(), String> {
+        let table = self.model.get_table(name)?;
+        let old_formulas: Vec<(u32, Vec<String>)> = self
+            .model
+            .workbook
+            .worksheets
+            .iter()
+            .map(|worksheet| (worksheet.sheet_id, worksheet.shared_formulas.clone()))
+            .collect();
+        let old_defined_names = self.model.workbook.defined_names.clone();
+
+        self.model.convert_table_to_range(name)?;
+
+        let worksheets = &self.model.workbook.worksheets;
+        let old_formulas = old_formulas
+            .into_iter()
+            .filter(|(sheet_id, formulas)| {
+                worksheets
+                    .iter()
+                    .any(|ws| ws.sheet_id == *sheet_id && ws.shared_formulas != *formulas)
+            })
+            .collect();
+        let old_defined_names = if self.model.workbook.defined_names != old_defined_names {
+            Some(old_defined_names)
+        } else {
+            None
+        };
+        self.push_diff_list(vec![Diff::ConvertTableToRange {
+            table: Box::new(table),
+            old_formulas,
+            old_defined_names,
+        }]);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Inserts a column in the table at the sheet column `column`.
+    /// The whole sheet column is inserted. `column` can be the column right after the table
+    /// to add a column at the end.
+    pub fn insert_table_column(&mut self, name: &str, column: i32) -> Result<(), String> {
+        let table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&table)?;
+        let column_end = area.column + area.width - 1;
+        if column < area.column || column > column_end + 1 {
+            return Err(format!("Column {column} is not in the table '{name}'"));
+        }
+        let sheet = area.sheet;
+        if column > area.column && column <= column_end {
+            // The table takes the new column
+            return self.insert_columns(sheet, column, 1);
+        }
+        self.model.insert_columns(sheet, column, 1)?;
+        let mut diff_list = vec![Diff::InsertColumns {
+            sheet,
+            column,
+            count: 1,
+        }];
+        let old_table = self.model.get_table(name)?;
+        let header = Area {
+            sheet,
+            row: area.row,
+            column,
+            width: 1,
+            height: 1,
+        };
+        let old_cells = self.get_area_cells(&header)?;
+        self.model.add_column_to_table(name, column)?;
+        diff_list.extend(self.get_table_diffs(Some(old_table), name, &[(header, old_cells)])?);
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Deletes the column of the table at the sheet column `column`.
+    /// The whole sheet column is deleted.
+    pub fn delete_table_column(&mut self, name: &str, column: i32) -> Result<(), String> {
+        let table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&table)?;
+        if column < area.column || column >= area.column + area.width {
+            return Err(format!("Column {column} is not in the table '{name}'"));
+        }
+        if area.width == 1 {
+            return Err("Cannot delete the only column of the table".to_string());
+        }
+        self.delete_columns(area.sheet, column, 1)
+    }
+
+    /// Renames the column of the table at the sheet column `column` and updates its header
+    pub fn rename_table_column(
+        &mut self,
+        name: &str,
+        column: i32,
+        new_name: &str,
+    ) -> Result<(), String> {
+        let old_table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&old_table)?;
+        let header = Area {
+            column,
+            width: 1,
+            height: 1,
+            ..area
+        };
+        let old_cells = self.get_area_cells(&header)?;
+        self.model.rename_table_column(name, column, new_name)?;
+        self.push_table_diffs(Some(old_table), name, &[(header, old_cells)])
+    }
+
+    /// Shows or hides the header row of the table
+    ///
+    /// See also:
+    /// * [Model::set_table_header_row](crate::Model::set_table_header_row)
+    pub fn set_table_header_row(&mut self, name: &str, show: bool) -> Result<(), String> {
+        let old_table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&old_table)?;
+        let header = Area {
+            row: if show { area.row - 1 } else { area.row },
+            height: 1,
+            ..area
+        };
+        if header.row < 1 {
+            return Err("Cannot show the header row: the table is in the first row".to_string());
+        }
+        let old_cells = self.get_area_cells(&header)?;
+        let link_diffs = self.range_link_diffs(&header)?;
+        self.model.set_table_header_row(name, show)?;
+        let mut diff_list = self.get_table_diffs(Some(old_table), name, &[(header, old_cells)])?;
+        diff_list.extend(link_diffs);
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Shows or hides the totals row of the table
+    ///
+    /// See also:
+    /// * [Model::set_table_totals_row](crate::Model::set_table_totals_row)
+    pub fn set_table_totals_row(&mut self, name: &str, show: bool) -> Result<(), String> {
+        let old_table = self.model.get_table(name)?;
+        let area = self.model.get_table_area(&old_table)?;
+        let row_end = area.row + area.height - 1;
+        let totals = Area {
+            row: if show { row_end + 1 } else { row_end },
+            height: 1,
+            ..area
+        };
+        let old_cells = self.get_area_cells(&totals)?;
+        let link_diffs = self.range_link_diffs(&totals)?;
+        self.model.set_table_totals_row(name, show)?;
+        let mut diff_list = self.get_table_diffs(Some(old_table), name, &[(totals, old_cells)])?;
+        diff_list.extend(link_diffs);
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+
+    /// Expands a table if the user writes right below or to the right of it, or updates the
+    /// name of a column if the user writes in a header.
+    /// Must be called after the cell (`row`, `column`) is updated. `expansion` is the value of
+    /// [Model::get_table_expansion](crate::Model::get_table_expansion) before the update.
+    pub(super) fn update_tables_after_input(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        expansion: Option<(String, Area)>,
+    ) -> Result<Vec<Diff>, String> {
+        if let Some((name, area)) = expansion {
+            let old_table = self.model.get_table(&name)?;
+            let header = Area { height: 1, ..area };
+            let old_cells = self.get_area_cells(&header)?;
+            self.model.resize_table(&name, &area)?;
+            return self.get_table_diffs(Some(old_table), &name, &[(header, old_cells)]);
+        }
+        match self
+            .model
+            .update_table_column_from_header(sheet, row, column)?
+        {
+            Some(old_table) => {
+                let new_table = self.model.get_table(&old_table.name)?;
+                Ok(vec![Diff::UpdateTable {
+                    old_value: Box::new(Some(old_table)),
+                    new_value: Box::new(Some(new_table)),
+                }])
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Returns diffs that restore the tables that overlap `area`.
+    /// They go before a deletion of rows or columns, that cannot be undone by re-inserting them.
+    pub(super) fn table_snapshot_diffs(&self, area: &Area) -> Result<Vec<Diff>, String> {
+        let mut diffs = Vec::new();
+        for table in self.model.get_tables(area.sheet)? {
+            let table_area = self.model.get_table_area(&table)?;
+            if table_area.row < area.row + area.height
+                && area.row < table_area.row + table_area.height
+                && table_area.column < area.column + area.width
+                && area.column < table_area.column + table_area.width
+            {
+                diffs.push(Diff::UpdateTable {
+                    old_value: Box::new(Some(table.clone())),
+                    new_value: Box::new(Some(table)),
+                });
+            }
+        }
+        Ok(diffs)
+    }
+
+    fn get_area_cells(&self, area: &Area) -> Result<Vec<Vec<Option<Cell>>>, String> {
+        let worksheet = self.model.workbook.worksheet(area.sheet)?;
+        let mut cells = Vec::new();
+        for row in area.row..area.row + area.height {
+            let mut data_row = Vec::new();
+            for column in area.column..area.column + area.width {
+                let cell = match worksheet.cell(row, column) {
+                    // The value of a spill cell is determined by its anchor
+                    Some(Cell::SpillCell { .. }) | None => None,
+                    Some(cell) => Some(cell.clone()),
+                };
+                data_row.push(cell);
+            }
+            cells.push(data_row);
+        }
+        Ok(cells)
+    }
+
+    // Returns the diffs of the change of the table `name` and of the cells that changed in the areas
+    fn get_table_diffs(
+        &self,
+        old_table: Option<Table>,
+        name: &str,
+        areas: &[(Area, Vec<Vec<Option<Cell>>>)],
+    ) -> Result<Vec<Diff>, String> {
+        let new_table = self.model.get_table(name)?;
+        let mut diff_list = vec![Diff::UpdateTable {
+            old_value: Box::new(old_table),
+            new_value: Box::new(Some(new_table)),
+        }];
+        for (area, old_cells) in areas {
+            let new_cells = self.get_area_cells(area)?;
+            for (row_offset, (old_row, new_row)) in old_cells.iter().zip(new_cells).enumerate() {
+                for (column_offset, (old_value, new_value)) in
+                    old_row.iter().zip(new_row).enumerate()
+                {
+                    if *old_value == new_value {
+                        continue;
+                    }
+                    let row = area.row + row_offset as i32;
+                    let column = area.column + column_offset as i32;
+                    diff_list.push(Diff::SetCellValue {
+                        sheet: area.sheet,
+                        row,
+                        column,
+                        new_value: self
+                            .model
+                            .get_localized_cell_content(area.sheet, row, column)?,
+                        old_value: Box::new(old_value.clone()),
+                    });
+                }
+            }
+        }
+        Ok(diff_list)
+    }
+
+    fn push_table_diffs(
+        &mut self,
+        old_table: Option<Table>,
+        name: &str,
+        areas: &[(Area, Vec<Vec<Option<Cell>>>)],
+    ) -> Result<(), String> {
+        let diff_list = self.get_table_diffs(old_table, name, areas)?;
+        self.push_diff_list(diff_list);
+        self.evaluate_if_not_paused();
+        Ok(())
+    }
+}
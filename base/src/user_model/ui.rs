@@ -35,6 +35,29 @@ impl<'a> UserModel<'a> {
         self.model.get_column_width(sheet, column).map(f64::round)
     }
 
+    // A merged area is selected as a single cell: returns the top left cell of the merged area
+    // that contains (row, column) and the range of the merged area.
+    fn get_cell_selection(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<(i32, i32, [i32; 4]), String> {
+        match self.model.get_merged_area(sheet, row, column)? {
+            Some(area) => Ok((
+                area.row,
+                area.column,
+                [
+                    area.row,
+                    area.column,
+                    area.row + area.height - 1,
+                    area.column + area.width - 1,
+                ],
+            )),
+            None => Ok((row, column, [row, column, row, column])),
+        }
+    }
+
     /// Returns the selected sheet index
     pub fn get_selected_sheet(&self) -> u32 {
         if let Some(view) = self.model.workbook.views.get(&self.model.view_id) {
@@ -117,11 +140,12 @@ impl<'a> UserModel<'a> {
         if self.model.workbook.worksheet(sheet).is_err() {
             return Err(format!("Invalid worksheet index {sheet}"));
         }
+        let (row, column, range) = self.get_cell_selection(sheet, row, column)?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&0) {
                 view.row = row;
                 view.column = column;
-                view.range = [row, column, row, column];
+                view.range = range;
             }
         }
         Ok(())
@@ -431,7 +455,10 @@ impl<'a> UserModel<'a> {
             Some(s) => s,
             None => return Err("View not found".to_string()),
         };
-        let mut new_column = view.column + 1;
+        // We step out of the merged area the cell might be in
+        let (_, _, [_, _, _, column_end]) =
+            self.get_cell_selection(sheet, view.row, view.column)?;
+        let mut new_column = column_end + 1;
         while new_column <= LAST_COLUMN
             && self
                 .model
@@ -451,10 +478,12 @@ impl<'a> UserModel<'a> {
             width += self.ui_column_width(sheet, column)?;
             column += 1;
         }
+        let (new_row, new_column, range) = self.get_cell_selection(sheet, view.row, new_column)?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
+                view.row = new_row;
                 view.column = new_column;
-                view.range = [view.row, new_column, view.row, new_column];
+                view.range = range;
                 if width > window_width as f64 {
                     view.left_column += 1;
                 }
@@ -491,11 +520,13 @@ impl<'a> UserModel<'a> {
         if !is_valid_column_number(new_column) {
             return Ok(());
         }
+        let (new_row, new_column, range) = self.get_cell_selection(sheet, view.row, new_column)?;
         // if the column is not fully visible we 'scroll' right until it is
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
+                view.row = new_row;
                 view.column = new_column;
-                view.range = [view.row, new_column, view.row, new_column];
+                view.range = range;
                 if new_column < view.left_column {
                     view.left_column = new_column;
                 }
@@ -532,11 +563,13 @@ impl<'a> UserModel<'a> {
         if !is_valid_row(new_row) {
             return Ok(());
         }
+        let (new_row, new_column, range) = self.get_cell_selection(sheet, new_row, view.column)?;
         // if the column is not fully visible we 'scroll' right until it is
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
                 view.row = new_row;
-                view.range = [new_row, view.column, new_row, view.column];
+                view.column = new_column;
+                view.range = range;
                 if new_row < view.top_row {
                     view.top_row = new_row;
                 }
@@ -561,7 +594,9 @@ impl<'a> UserModel<'a> {
             Some(s) => s,
             None => return Err("View not found".to_string()),
         };
-        let mut new_row = view.row + 1;
+        // We step out of the merged area the cell might be in
+        let (_, _, [_, _, row_end, _]) = self.get_cell_selection(sheet, view.row, view.column)?;
+        let mut new_row = row_end + 1;
         while new_row <= LAST_ROW
             && self
                 .model
@@ -581,10 +616,12 @@ impl<'a> UserModel<'a> {
             height += self.ui_row_height(sheet, row)?;
             row += 1;
         }
+        let (new_row, new_column, range) = self.get_cell_selection(sheet, new_row, view.column)?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
                 view.row = new_row;
-                view.range = [new_row, view.column, new_row, view.column];
+                view.column = new_column;
+                view.range = range;
                 if height > window_height as f64 {
                     view.top_row += 1;
                 }
@@ -666,11 +703,14 @@ impl<'a> UserModel<'a> {
             return Ok(());
         }
         let row_delta = view.row - view.top_row;
+        let (new_row, new_column, range) =
+            self.get_cell_selection(sheet, last_row + row_delta, view.column)?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
                 view.top_row = last_row;
-                view.row = view.top_row + row_delta;
-                view.range = [view.row, view.column, view.row, view.column];
+                view.row = new_row;
+                view.column = new_column;
+                view.range = range;
             }
         }
         Ok(())
@@ -701,16 +741,69 @@ impl<'a> UserModel<'a> {
         }
 
         let row_delta = view.row - view.top_row;
+        let (new_row, new_column, range) =
+            self.get_cell_selection(sheet, first_row + row_delta, view.column)?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
                 view.top_row = first_row;
-                view.row = view.top_row + row_delta;
-                view.range = [view.row, view.column, view.row, view.column];
+                view.row = new_row;
+                view.column = new_column;
+                view.range = range;
             }
         }
         Ok(())
     }
 
+    // Grows the range [start_row, start_column, end_row, end_column] until no merged area is
+    // partially selected, keeping the direction in which it was selected
+    fn expand_range_to_merged_cells(
+        &self,
+        sheet: u32,
+        range: [i32; 4],
+    ) -> Result<[i32; 4], String> {
+        let [start_row, start_column, end_row, end_column] = range;
+        let mut row1 = start_row.min(end_row);
+        let mut row2 = start_row.max(end_row);
+        let mut column1 = start_column.min(end_column);
+        let mut column2 = start_column.max(end_column);
+        let merged_areas = self.model.get_merged_cells(sheet)?;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for area in &merged_areas {
+                let area_row2 = area.row + area.height - 1;
+                let area_column2 = area.column + area.width - 1;
+                let overlaps = area.row <= row2
+                    && row1 <= area_row2
+                    && area.column <= column2
+                    && column1 <= area_column2;
+                if overlaps
+                    && (area.row < row1
+                        || area_row2 > row2
+                        || area.column < column1
+                        || area_column2 > column2)
+                {
+                    row1 = row1.min(area.row);
+                    row2 = row2.max(area_row2);
+                    column1 = column1.min(area.column);
+                    column2 = column2.max(area_column2);
+                    changed = true;
+                }
+            }
+        }
+        let (start_row, end_row) = if end_row >= start_row {
+            (row1, row2)
+        } else {
+            (row2, row1)
+        };
+        let (start_column, end_column) = if end_column >= start_column {
+            (column1, column2)
+        } else {
+            (column2, column1)
+        };
+        Ok([start_row, start_column, end_row, end_column])
+    }
+
     /// We extend the selection to cell (target_row, target_column)
     pub fn on_area_selecting(&mut self, target_row: i32, target_column: i32) -> Result<(), String> {
         let (sheet, window_width, window_height) =
@@ -773,9 +866,13 @@ impl<'a> UserModel<'a> {
             new_top_row = target_row;
         }
 
+        let range = self.expand_range_to_merged_cells(
+            sheet,
+            [row_start, column_start, target_row, target_column],
+        )?;
         if let Ok(worksheet) = self.model.workbook.worksheet_mut(sheet) {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
-                view.range = [row_start, column_start, target_row, target_column];
+                view.range = range;
                 if new_top_row != top_row {
                     view.top_row = new_top_row;
                 }
@@ -820,6 +917,7 @@ impl<'a> UserModel<'a> {
         if new_row == row && new_column == column {
             return Ok(()); // No change in selection
         }
+        let (new_row, new_column, range) = self.get_cell_selection(sheet, new_row, new_column)?;
 
         let mut top_row = view.top_row;
         let mut left_column = view.left_column;
@@ -869,7 +967,7 @@ impl<'a> UserModel<'a> {
             if let Some(view) = worksheet.views.get_mut(&self.model.view_id) {
                 view.row = new_row;
                 view.column = new_column;
-                view.range = [new_row, new_column, new_row, new_column];
+                view.range = range;
 
                 view.top_row = top_row;
                 view.left_column = left_column;
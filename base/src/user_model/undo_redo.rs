@@ -14,6 +14,8 @@ use crate::user_model::history::{Diff, DiffList};
 impl<'a> UserModel<'a> {
     pub(super) fn apply_undo_diff_list(&mut self, diff_list: &DiffList) -> Result<(), String> {
         let mut needs_evaluation = false;
+        // Only cell contents changed: recompute just those cells and their dependents
+        let mut needs_incremental_evaluation = false;
         for diff in diff_list.iter().rev() {
             match diff {
                 Diff::SetCellValue {
@@ -23,7 +25,8 @@ impl<'a> UserModel<'a> {
                     new_value: _,
                     old_value,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
+                    self.model.mark_cell_dirty(*sheet, *row, *column);
                     // If the current cell is a dynamic ArrayFormula anchor, its
                     // spill cells are not tracked in the diff (they are created
                     // as a side-effect of evaluate()). Clear them now so that
@@ -85,6 +88,28 @@ impl<'a> UserModel<'a> {
                         }
                     }
                 }
+                Diff::SetCellRichText {
+                    sheet,
+                    row,
+                    column,
+                    new_value: _,
+                    old_value,
+                }
+                | Diff::SetCellPastedValue {
+                    sheet,
+                    row,
+                    column,
+                    new_value: _,
+                    old_value,
+                } => {
+                    needs_incremental_evaluation = true;
+                    self.model.mark_cell_dirty(*sheet, *row, *column);
+                    let worksheet = self.model.workbook.worksheet_mut(*sheet)?;
+                    match *old_value.clone() {
+                        Some(value) => worksheet.update_cell(*row, *column, value)?,
+                        None => worksheet.cell_clear_contents(*row, *column)?,
+                    }
+                }
                 Diff::SetArrayValue {
                     sheet,
                     row,
@@ -94,7 +119,14 @@ impl<'a> UserModel<'a> {
                     new_value: _,
                     old_values,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
+                    self.model.mark_area_dirty(&Area {
+                        sheet: *sheet,
+                        row: *row,
+                        column: *column,
+                        width: *width,
+                        height: *height,
+                    });
                     // Clear all cells in the array formula range (anchor + spill cells).
                     let ws = self.model.workbook.worksheet_mut(*sheet)?;
                     for r in *row..*row + *height {
@@ -138,6 +170,8 @@ impl<'a> UserModel<'a> {
                     old_value,
                 } => {
                     self.model.set_row_hidden(*sheet, *row, *old_value)?;
+                    // SUBTOTAL ignores hidden rows
+                    needs_evaluation = true;
                 }
                 Diff::SetRowHeight {
                     sheet,
@@ -153,7 +187,14 @@ impl<'a> UserModel<'a> {
                     height,
                     old_value,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
+                    self.model.mark_area_dirty(&Area {
+                        sheet: *sheet,
+                        row: *row,
+                        column: *column,
+                        width: *width,
+                        height: *height,
+                    });
                     for r in *row..*row + *height {
                         for c in *column..*column + *width {
                             let row_index = (r - *row) as usize;
@@ -176,7 +217,14 @@ impl<'a> UserModel<'a> {
                     old_value,
                     old_style,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
+                    self.model.mark_area_dirty(&Area {
+                        sheet: *sheet,
+                        row: *row,
+                        column: *column,
+                        width: *width,
+                        height: *height,
+                    });
                     for r in *row..*row + *height {
                         for c in *column..*column + *width {
                             let row_index = (r - *row) as usize;
@@ -255,6 +303,8 @@ impl<'a> UserModel<'a> {
                     let worksheet = self.model.workbook.worksheet_mut(*sheet)?;
                     for (i, row_data) in old_data.iter().enumerate() {
                         let r = *row + i as i32;
+                        // The inserted row might have been added to a group
+                        worksheet.rows.retain(|x| x.r != r);
                         if let Some(row_style) = row_data.row.clone() {
                             worksheet.rows.push(row_style);
                         }
@@ -290,6 +340,12 @@ impl<'a> UserModel<'a> {
                             let hidden = col.hidden;
                             worksheet.set_column_width_and_style(c, width, hidden, style)?;
                         }
+                        let (outline_level, collapsed) = col_data
+                            .column
+                            .as_ref()
+                            .map_or((0, false), |col| (col.outline_level, col.collapsed));
+                        worksheet.set_column_outline_level(c, outline_level)?;
+                        worksheet.set_column_collapsed(c, collapsed)?;
                     }
                 }
                 Diff::SetFrozenRowsCount {
@@ -418,28 +474,46 @@ impl<'a> UserModel<'a> {
                         self.model.range_clear_all(&area)?;
                     }
                 }
-                Diff::DeleteSheet { sheet, old_data } => {
+                Diff::DeleteSheet {
+                    sheet,
+                    old_data,
+                    old_formulas,
+                    old_defined_names,
+                    old_tables,
+                } => {
                     needs_evaluation = true;
                     let sheet_name = &old_data.name.clone();
                     let sheet_index = *sheet;
                     let sheet_id = old_data.sheet_id;
                     self.model
                         .insert_sheet(sheet_name, sheet_index, Some(sheet_id))?;
+                    // The diff keeps the whole worksheet: cells, comments, validations,
+                    // protection, filters, page setup, ...
+                    // Only the views opened after the sheet was deleted are new.
                     let worksheet = self.model.workbook.worksheet_mut(*sheet)?;
-                    for (row, row_data) in &old_data.sheet_data {
-                        for (column, cell) in row_data {
-                            worksheet.update_cell(*row, *column, cell.clone())?;
+                    let views = std::mem::take(&mut worksheet.views);
+                    *worksheet = (**old_data).clone();
+                    for (view_id, view) in views {
+                        worksheet.views.entry(view_id).or_insert(view);
+                    }
+                    for (sheet_id, formulas) in old_formulas {
+                        if let Some(index) = self.model.get_sheet_index_by_sheet_id(*sheet_id) {
+                            self.model.workbook.worksheet_mut(index)?.shared_formulas =
+                                formulas.clone();
                         }
                     }
-                    worksheet.rows = old_data.rows.clone();
-                    worksheet.cols = old_data.cols.clone();
-                    worksheet.show_grid_lines = old_data.show_grid_lines;
-                    worksheet.frozen_columns = old_data.frozen_columns;
-                    worksheet.frozen_rows = old_data.frozen_rows;
-                    worksheet.state = old_data.state.clone();
-                    worksheet.color = old_data.color.clone();
-                    worksheet.merge_cells = old_data.merge_cells.clone();
-                    worksheet.shared_formulas = old_data.shared_formulas.clone();
+                    if let Some(defined_names) = old_defined_names {
+                        self.model.workbook.defined_names = defined_names.clone();
+                    }
+                    for table in old_tables {
+                        self.model
+                            .workbook
+                            .tables
+                            .insert(table.name.clone(), table.clone());
+                    }
+                    self.model
+                        .parser
+                        .set_tables(self.model.workbook.tables.clone());
                     self.model.reset_parsed_structures();
 
                     self.set_selected_sheet(sheet_index)?;
@@ -525,6 +599,20 @@ impl<'a> UserModel<'a> {
                 } => {
                     self.model.set_timezone(old_value)?;
                 }
+                Diff::SetIterativeCalculation {
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.settings.iterative_calculation = old_value.clone();
+                    needs_evaluation = true;
+                }
+                Diff::SetDateSystem {
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.settings.date_system = *old_value;
+                    needs_evaluation = true;
+                }
                 Diff::SetWorkbookName {
                     old_value,
                     new_value: _,
@@ -634,10 +722,160 @@ impl<'a> UserModel<'a> {
                         .set_cell_link(*sheet, *row, *column, link.clone())?,
                     None => self.model.delete_cell_link(*sheet, *row, *column)?,
                 },
+                Diff::UpdateTable {
+                    old_value,
+                    new_value,
+                } => {
+                    let name = new_value.as_ref().as_ref().map(|table| table.name.as_str());
+                    self.model.set_table(name, *old_value.clone())?;
+                }
+                Diff::RenameTable {
+                    old_value,
+                    new_value,
+                } => {
+                    self.model.rename_table(new_value, old_value)?;
+                }
+                Diff::ConvertTableToRange {
+                    table,
+                    old_formulas,
+                    old_defined_names,
+                } => {
+                    for (sheet_id, formulas) in old_formulas {
+                        if let Some(index) = self.model.get_sheet_index_by_sheet_id(*sheet_id) {
+                            self.model.workbook.worksheet_mut(index)?.shared_formulas =
+                                formulas.clone();
+                        }
+                    }
+                    if let Some(defined_names) = old_defined_names {
+                        self.model.workbook.defined_names = defined_names.clone();
+                    }
+                    self.model.set_table(None, Some(*table.clone()))?;
+                }
+                Diff::SetMergedCells {
+                    sheet,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.merge_cells = old_value.clone();
+                }
+                Diff::SetDataValidations {
+                    sheet,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.data_validations = old_value.clone();
+                }
+                Diff::SetSheetProtection {
+                    sheet,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.protection = old_value.clone();
+                }
+                Diff::SetWorkbookProtection {
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.protection = old_value.clone();
+                }
+                Diff::SetAutoFilter {
+                    sheet,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.auto_filter = old_value.clone();
+                }
+                Diff::SetRowOutlineLevel {
+                    sheet,
+                    row,
+                    new_value: _,
+                    old_value,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_row_outline_level(*row, *old_value)?;
+                }
+                Diff::SetRowCollapsed {
+                    sheet,
+                    row,
+                    new_value: _,
+                    old_value,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_row_collapsed(*row, *old_value)?;
+                }
+                Diff::SetColumnOutlineLevel {
+                    sheet,
+                    column,
+                    new_value: _,
+                    old_value,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_column_outline_level(*column, *old_value)?;
+                }
+                Diff::SetPageSetup {
+                    sheet,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.page_setup = *old_value.clone();
+                }
+                Diff::SetColumnCollapsed {
+                    sheet,
+                    column,
+                    new_value: _,
+                    old_value,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_column_collapsed(*column, *old_value)?;
+                }
+                Diff::SortRange {
+                    sheet,
+                    row,
+                    column,
+                    width,
+                    height,
+                    left_to_right,
+                    order,
+                    old_cells,
+                    old_value,
+                    new_value: _,
+                } => {
+                    let area = Area {
+                        sheet: *sheet,
+                        row: *row,
+                        column: *column,
+                        width: *width,
+                        height: *height,
+                    };
+                    self.model
+                        .undo_sort_order(&area, *left_to_right, order, old_cells)?;
+                    self.model.workbook.worksheet_mut(*sheet)?.sort_state = old_value.clone();
+                    needs_evaluation = true;
+                }
+                Diff::SetComment {
+                    sheet,
+                    row,
+                    column,
+                    old_value,
+                    new_value: _,
+                } => {
+                    self.model
+                        .set_comment(*sheet, *row, *column, old_value.as_deref().cloned())?;
+                }
             }
         }
         if needs_evaluation {
             self.evaluate_if_not_paused();
+        } else if needs_incremental_evaluation {
+            self.evaluate_incremental_if_not_paused();
         }
         Ok(())
     }
@@ -645,6 +883,8 @@ impl<'a> UserModel<'a> {
     /// Applies diff list
     pub(super) fn apply_diff_list(&mut self, diff_list: &DiffList) -> Result<(), String> {
         let mut needs_evaluation = false;
+        // Only cell contents changed: recompute just those cells and their dependents
+        let mut needs_incremental_evaluation = false;
         for diff in diff_list {
             match diff {
                 Diff::SetCellValue {
@@ -654,10 +894,31 @@ impl<'a> UserModel<'a> {
                     new_value,
                     old_value: _,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
                     self.model
                         .set_user_input(*sheet, *row, *column, new_value.to_string())?;
                 }
+                Diff::SetCellRichText {
+                    sheet,
+                    row,
+                    column,
+                    new_value,
+                    old_value: _,
+                } => {
+                    needs_incremental_evaluation = true;
+                    self.model
+                        .set_cell_rich_text(*sheet, *row, *column, new_value.clone())?;
+                }
+                Diff::SetCellPastedValue {
+                    sheet,
+                    row,
+                    column,
+                    new_value,
+                    old_value: _,
+                } => {
+                    needs_incremental_evaluation = true;
+                    new_value.set_in_cell(&mut self.model, *sheet, *row, *column)?;
+                }
                 Diff::SetArrayValue {
                     sheet,
                     row,
@@ -667,7 +928,7 @@ impl<'a> UserModel<'a> {
                     new_value,
                     old_values: _,
                 } => {
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
                     self.model.set_user_array_formula(
                         *sheet, *row, *column, *width, *height, new_value,
                     )?;
@@ -695,6 +956,8 @@ impl<'a> UserModel<'a> {
                     old_value: _,
                 } => {
                     self.model.set_row_hidden(*sheet, *row, *new_value)?;
+                    // SUBTOTAL ignores hidden rows
+                    needs_evaluation = true;
                 }
                 Diff::SetRowHeight {
                     sheet,
@@ -720,7 +983,7 @@ impl<'a> UserModel<'a> {
                         height: *height,
                     };
                     self.model.range_clear_contents(&area)?;
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
                 }
                 Diff::RangeClearAll {
                     sheet,
@@ -739,7 +1002,7 @@ impl<'a> UserModel<'a> {
                         height: *height,
                     };
                     self.model.range_clear_all(&area)?;
-                    needs_evaluation = true;
+                    needs_incremental_evaluation = true;
                 }
                 Diff::SetCellStyle {
                     sheet,
@@ -815,7 +1078,7 @@ impl<'a> UserModel<'a> {
                     new_value,
                     old_value: _,
                 } => self.model.set_frozen_columns(*sheet, *new_value)?,
-                Diff::DeleteSheet { sheet, old_data: _ } => {
+                Diff::DeleteSheet { sheet, .. } => {
                     self.model.delete_sheet(*sheet)?;
                     if *sheet > 0 {
                         self.set_selected_sheet(*sheet - 1)?;
@@ -974,6 +1237,20 @@ impl<'a> UserModel<'a> {
                 } => {
                     self.model.set_timezone(new_value)?;
                 }
+                Diff::SetIterativeCalculation {
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.settings.iterative_calculation = new_value.clone();
+                    needs_evaluation = true;
+                }
+                Diff::SetDateSystem {
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.settings.date_system = *new_value;
+                    needs_evaluation = true;
+                }
                 Diff::SetWorkbookName {
                     old_value: _,
                     new_value,
@@ -1076,11 +1353,147 @@ impl<'a> UserModel<'a> {
                         .set_cell_link(*sheet, *row, *column, link.clone())?,
                     None => self.model.delete_cell_link(*sheet, *row, *column)?,
                 },
+                Diff::UpdateTable {
+                    old_value,
+                    new_value,
+                } => {
+                    let name = old_value.as_ref().as_ref().map(|table| table.name.as_str());
+                    self.model.set_table(name, *new_value.clone())?;
+                }
+                Diff::RenameTable {
+                    old_value,
+                    new_value,
+                } => {
+                    self.model.rename_table(old_value, new_value)?;
+                }
+                Diff::ConvertTableToRange { table, .. } => {
+                    self.model.convert_table_to_range(&table.name)?;
+                }
+                Diff::SetMergedCells {
+                    sheet,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.merge_cells = new_value.clone();
+                }
+                Diff::SetDataValidations {
+                    sheet,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.data_validations = new_value.clone();
+                }
+                Diff::SetSheetProtection {
+                    sheet,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.protection = new_value.clone();
+                }
+                Diff::SetWorkbookProtection {
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.protection = new_value.clone();
+                }
+                Diff::SetAutoFilter {
+                    sheet,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.auto_filter = new_value.clone();
+                }
+                Diff::SetRowOutlineLevel {
+                    sheet,
+                    row,
+                    new_value,
+                    old_value: _,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_row_outline_level(*row, *new_value)?;
+                }
+                Diff::SetRowCollapsed {
+                    sheet,
+                    row,
+                    new_value,
+                    old_value: _,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_row_collapsed(*row, *new_value)?;
+                }
+                Diff::SetColumnOutlineLevel {
+                    sheet,
+                    column,
+                    new_value,
+                    old_value: _,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_column_outline_level(*column, *new_value)?;
+                }
+                Diff::SetPageSetup {
+                    sheet,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model.workbook.worksheet_mut(*sheet)?.page_setup = *new_value.clone();
+                }
+                Diff::SetColumnCollapsed {
+                    sheet,
+                    column,
+                    new_value,
+                    old_value: _,
+                } => {
+                    self.model
+                        .workbook
+                        .worksheet_mut(*sheet)?
+                        .set_column_collapsed(*column, *new_value)?;
+                }
+                Diff::SortRange {
+                    sheet,
+                    row,
+                    column,
+                    width,
+                    height,
+                    left_to_right,
+                    order,
+                    old_cells: _,
+                    old_value: _,
+                    new_value,
+                } => {
+                    let area = Area {
+                        sheet: *sheet,
+                        row: *row,
+                        column: *column,
+                        width: *width,
+                        height: *height,
+                    };
+                    self.model.apply_sort_order(&area, *left_to_right, order)?;
+                    self.model.workbook.worksheet_mut(*sheet)?.sort_state = new_value.clone();
+                    needs_evaluation = true;
+                }
+                Diff::SetComment {
+                    sheet,
+                    row,
+                    column,
+                    old_value: _,
+                    new_value,
+                } => {
+                    self.model
+                        .set_comment(*sheet, *row, *column, new_value.as_deref().cloned())?;
+                }
             }
         }
 
         if needs_evaluation {
             self.evaluate_if_not_paused();
+        } else if needs_incremental_evaluation {
+            self.evaluate_incremental_if_not_paused();
         }
         Ok(())
     }
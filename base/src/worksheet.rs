@@ -112,6 +112,8 @@ impl Worksheet {
             custom_width: false,
             style: Some(style_index),
             hidden: false,
+            outline_level: 0,
+            collapsed: false,
         }];
         Ok(())
     }
@@ -141,6 +143,8 @@ impl Worksheet {
             custom_height: false,
             s: style_index,
             hidden: false,
+            outline_level: 0,
+            collapsed: false,
         });
         Ok(())
     }
@@ -196,6 +200,8 @@ impl Worksheet {
                 custom_width,
                 style: cols[index].style,
                 hidden: cols[index].hidden,
+                outline_level: cols[index].outline_level,
+                collapsed: cols[index].collapsed,
             };
             let col = Col {
                 min: column,
@@ -204,6 +210,8 @@ impl Worksheet {
                 custom_width,
                 style: None,
                 hidden: false,
+                outline_level: cols[index].outline_level,
+                collapsed: cols[index].collapsed,
             };
             let post = Col {
                 min: column + 1,
@@ -212,12 +220,14 @@ impl Worksheet {
                 custom_width,
                 style: cols[index].style,
                 hidden: cols[index].hidden,
+                outline_level: cols[index].outline_level,
+                collapsed: cols[index].collapsed,
             };
             cols.remove(index);
             if column != max {
                 cols.insert(index, post);
             }
-            if custom_width {
+            if custom_width || col.outline_level > 0 {
                 cols.insert(index, col);
             }
             if column != min {
@@ -397,6 +407,8 @@ impl Worksheet {
             custom_height: false,
             s: 0,
             hidden,
+            outline_level: 0,
+            collapsed: false,
         });
         Ok(())
     }
@@ -429,6 +441,8 @@ impl Worksheet {
             custom_height: true,
             s: 0,
             hidden,
+            outline_level: 0,
+            collapsed: false,
         });
         Ok(())
     }
@@ -473,6 +487,8 @@ impl Worksheet {
             custom_width: width != constants::DEFAULT_COLUMN_WIDTH,
             style,
             hidden,
+            outline_level: 0,
+            collapsed: false,
         };
         let mut index = 0;
         let mut split = false;
@@ -506,6 +522,8 @@ impl Worksheet {
                 custom_width: cols[index].custom_width,
                 style: cols[index].style,
                 hidden: cols[index].hidden,
+                outline_level: cols[index].outline_level,
+                collapsed: cols[index].collapsed,
             };
             let post = Col {
                 min: column + 1,
@@ -514,8 +532,12 @@ impl Worksheet {
                 custom_width: cols[index].custom_width,
                 style: cols[index].style,
                 hidden: cols[index].hidden,
+                outline_level: cols[index].outline_level,
+                collapsed: cols[index].collapsed,
             };
             col.style = cols[index].style;
+            col.outline_level = cols[index].outline_level;
+            col.collapsed = cols[index].collapsed;
             cols.remove(index);
             if column != max {
                 cols.insert(index, post);
@@ -604,6 +626,160 @@ impl Worksheet {
         Ok(false)
     }
 
+    /// Returns the outline level of a row, 0 if it is not in a group
+    pub fn get_row_outline_level(&self, row: i32) -> Result<u8, String> {
+        if !is_valid_row(row) {
+            return Err(format!("Row number '{row}' is not valid."));
+        }
+        Ok(self
+            .rows
+            .iter()
+            .find(|r| r.r == row)
+            .map_or(0, |r| r.outline_level))
+    }
+
+    /// Returns true if the group of rows next to this summary row is collapsed
+    pub fn is_row_collapsed(&self, row: i32) -> Result<bool, String> {
+        if !is_valid_row(row) {
+            return Err(format!("Row number '{row}' is not valid."));
+        }
+        Ok(self.rows.iter().any(|r| r.r == row && r.collapsed))
+    }
+
+    /// Returns the outline level of a column, 0 if it is not in a group
+    pub fn get_column_outline_level(&self, column: i32) -> Result<u8, String> {
+        if !is_valid_column_number(column) {
+            return Err(format!("Column number '{column}' is not valid."));
+        }
+        Ok(self
+            .cols
+            .iter()
+            .find(|c| c.min <= column && column <= c.max)
+            .map_or(0, |c| c.outline_level))
+    }
+
+    /// Returns true if the group of columns next to this summary column is collapsed
+    pub fn is_column_collapsed(&self, column: i32) -> Result<bool, String> {
+        if !is_valid_column_number(column) {
+            return Err(format!("Column number '{column}' is not valid."));
+        }
+        Ok(self
+            .cols
+            .iter()
+            .any(|c| c.min <= column && column <= c.max && c.collapsed))
+    }
+
+    pub(crate) fn set_row_outline_level(&mut self, row: i32, level: u8) -> Result<(), String> {
+        if self.get_row_outline_level(row)? != level {
+            self.row_mut(row).outline_level = level;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_row_collapsed(&mut self, row: i32, collapsed: bool) -> Result<(), String> {
+        if self.is_row_collapsed(row)? != collapsed {
+            self.row_mut(row).collapsed = collapsed;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_column_outline_level(
+        &mut self,
+        column: i32,
+        level: u8,
+    ) -> Result<(), String> {
+        if self.get_column_outline_level(column)? != level {
+            self.column_mut(column).outline_level = level;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_column_collapsed(
+        &mut self,
+        column: i32,
+        collapsed: bool,
+    ) -> Result<(), String> {
+        if self.is_column_collapsed(column)? != collapsed {
+            self.column_mut(column).collapsed = collapsed;
+        }
+        Ok(())
+    }
+
+    // The properties of a row, added with the default values if it has none
+    fn row_mut(&mut self, row: i32) -> &mut Row {
+        let index = match self.rows.iter().position(|r| r.r == row) {
+            Some(index) => index,
+            None => {
+                self.rows.push(Row {
+                    height: constants::DEFAULT_ROW_HEIGHT / constants::ROW_HEIGHT_FACTOR,
+                    r: row,
+                    custom_format: false,
+                    custom_height: false,
+                    s: 0,
+                    hidden: false,
+                    outline_level: 0,
+                    collapsed: false,
+                });
+                self.rows.len() - 1
+            }
+        };
+        &mut self.rows[index]
+    }
+
+    // The properties of a single column, splitting the range it belongs to if needed
+    fn column_mut(&mut self, column: i32) -> &mut Col {
+        let cols = &mut self.cols;
+        let index = match cols.iter().position(|c| c.min <= column && column <= c.max) {
+            Some(index) => {
+                let col = cols[index].clone();
+                let mut index = index;
+                if col.max > column {
+                    cols.insert(
+                        index + 1,
+                        Col {
+                            min: column + 1,
+                            ..col.clone()
+                        },
+                    );
+                }
+                cols[index].min = column;
+                cols[index].max = column;
+                if col.min < column {
+                    cols.insert(
+                        index,
+                        Col {
+                            max: column - 1,
+                            ..col
+                        },
+                    );
+                    index += 1;
+                }
+                index
+            }
+            None => {
+                let index = cols
+                    .iter()
+                    .position(|c| c.min > column)
+                    .unwrap_or(cols.len());
+                cols.insert(
+                    index,
+                    Col {
+                        min: column,
+                        max: column,
+                        width: constants::DEFAULT_COLUMN_WIDTH / constants::COLUMN_WIDTH_FACTOR,
+                        custom_width: false,
+                        style: None,
+                        hidden: false,
+                        outline_level: 0,
+                        collapsed: false,
+                    },
+                );
+                index
+            }
+        };
+        &mut cols[index]
+    }
+
     /// Returns the column style index if present
     pub fn get_column_style(&self, column: i32) -> Result<Option<i32>, String> {
         if !is_valid_column_number(column) {
@@ -235,3 +235,74 @@ test('cell link label and style are one undo step', (t) => {
     tooltip: null,
   });
 });
+
+test('tables', (t) => {
+  const model = new UserModel("Workbook1");
+  model.setUserInput(0, 1, 1, "Amount");
+  model.setUserInput(0, 2, 1, "3");
+  model.setUserInput(0, 3, 1, "5");
+
+  t.is(model.createTable(0, 1, 1, 3, 1, true), "Table1");
+  t.is(model.getTableNameAt(0, 2, 1), "Table1");
+  t.is(model.getTableNameAt(0, 5, 5), null);
+  t.is(model.getTables(0)[0].reference, "A1:A3");
+  model.setUserInput(0, 5, 1, "=SUM(Table1[Amount])");
+  t.is(model.getFormattedCellValue(0, 5, 1), "8");
+});
+
+test('merged cells', (t) => {
+  const model = new UserModel("Workbook1");
+  const area = { sheet: 0, row: 1, column: 3, width: 2, height: 2 };
+  model.mergeCells(0, 1, 3, 2, 4);
+  t.deepEqual(model.getMergedCells(0), [area]);
+  t.deepEqual(model.getMergedArea(0, 2, 4), area);
+  t.is(model.getMergedArea(0, 5, 5), null);
+});
+
+test('comments', (t) => {
+  const model = new UserModel("Workbook1");
+  model.setComment(0, 1, 1, "Check this", "Ada");
+  t.is(model.getComment(0, 1, 1).text, "Check this");
+  t.is(model.getComments(0).length, 1);
+  model.deleteComment(0, 1, 1);
+  t.is(model.getComment(0, 1, 1), null);
+});
+
+test('find and replace', (t) => {
+  const model = new UserModel("Workbook1");
+  model.setUserInput(0, 1, 1, "apples");
+  model.setUserInput(0, 2, 1, "green apples");
+  t.deepEqual(model.find("apples", { whole_cell: true }), [{ sheet: 0, row: 1, column: 1 }]);
+  t.is(model.replaceAll("apples", "pears", null), 2);
+  t.is(model.getCellContent(0, 2, 1), "green pears");
+});
+
+test('rich text', (t) => {
+  const model = new UserModel("Workbook1");
+  const runs = [{ text: "Hello " }, { text: "world", font: { b: true } }];
+  model.setCellRichText(0, 3, 1, runs);
+  t.deepEqual(model.getCellRichText(0, 3, 1), runs);
+  t.is(model.getFormattedCellValue(0, 3, 1), "Hello world");
+});
+
+test('paste special', (t) => {
+  const model = new UserModel("Workbook1");
+  model.setUserInput(0, 1, 3, "=1+1");
+  model.setSelectedCell(1, 3);
+  const clipboard = model.copyToClipboard();
+  model.setSelectedCell(1, 4);
+  model.pasteSpecialFromClipboard(clipboard.sheet, clipboard.range, clipboard.data, { paste: "values" });
+  t.is(model.getCellContent(0, 1, 4), "2");
+});
+
+test('html clipboard', (t) => {
+  const model = new UserModel("Workbook1");
+  model.setUserInput(0, 1, 1, "apples");
+  model.setSelectedCell(1, 1);
+  t.true(model.copyToClipboard().html.includes("<table"));
+
+  model.setSelectedCell(3, 1);
+  model.pasteHtmlString(0, 3, 1, 3, 2, "<table><tr><td>pears</td><td>3</td></tr></table>");
+  t.is(model.getFormattedCellValue(0, 3, 1), "pears");
+  t.is(model.getFormattedCellValue(0, 3, 2), "3");
+});
@@ -348,7 +348,7 @@ export interface SelectedView {
 export interface ClipboardCell {
   text: string;
   style: CellStyle;
-  /** The link attached to the cell, if any */
+  /** The link attached to the cell, when present */
   link?: Link | null;
 }
 
@@ -357,16 +357,129 @@ export type ClipboardData = Record<string, Record<string, ClipboardCell>>;
 
 export interface Clipboard {
   csv: string;
+  /** The cells as an HTML table with the styles inlined */
+  html: string;
   data: ClipboardData;
+  sheet: number;
   range: [number, number, number, number];
 }
 
+/** Options for `pasteSpecialFromClipboard`. All the fields are optional. */
+export interface PasteOptions {
+  paste?: "all" | "values" | "formats" | "formulas" | "columnWidths";
+  operation?: "none" | "add" | "subtract" | "multiply" | "divide";
+  skip_blanks?: boolean;
+  transpose?: boolean;
+}
+
 export interface DefinedName {
   name: string;
   scope?: number;
   formula: string;
 }
 
+export interface Area {
+  sheet: number;
+  row: number;
+  column: number;
+  width: number;
+  height: number;
+}
+
+export interface RichTextFont {
+  b?: boolean;
+  i?: boolean;
+  u?: boolean;
+  strike?: boolean;
+  sz?: number;
+  color?: Color;
+  name?: string;
+}
+
+/** A run of text with the same font in a rich text cell */
+export interface RichTextRun {
+  text: string;
+  font?: RichTextFont;
+}
+
+export interface TableStyleInfo {
+  name: string | null;
+  show_first_column: boolean;
+  show_last_column: boolean;
+  show_row_stripes: boolean;
+  show_column_stripes: boolean;
+}
+
+export interface TableColumn {
+  id: number;
+  name: string;
+  totals_row_label: string | null;
+  header_row_dxf_id: number | null;
+  data_dxf_id: number | null;
+  totals_row_dxf_id: number | null;
+  totals_row_function: string | null;
+  totals_row_formula: string | null;
+  calculated_column_formula: string | null;
+  filter: unknown | null;
+}
+
+export interface Table {
+  name: string;
+  display_name: string;
+  sheet_name: string;
+  /** The range of the table, like "A1:D10" */
+  reference: string;
+  totals_row_count: number;
+  header_row_count: number;
+  header_row_dxf_id: number | null;
+  data_dxf_id: number | null;
+  totals_row_dxf_id: number | null;
+  columns: TableColumn[];
+  style_info: TableStyleInfo;
+  has_filters: boolean;
+}
+
+export interface CommentReply {
+  id: string;
+  text: string;
+  author_name: string;
+  author_id: string | null;
+  date: string;
+}
+
+/** Present in threaded comments only */
+export interface CommentThread {
+  id: string;
+  date: string;
+  done: boolean;
+  replies: CommentReply[];
+}
+
+export interface Comment {
+  text: string;
+  author_name: string;
+  author_id: string | null;
+  cell_ref: string;
+  thread: CommentThread | null;
+}
+
+/** Options for `find` and `replaceAll`. All the fields are optional. */
+export interface FindOptions {
+  /** Search only this sheet, all the sheets if absent */
+  sheet?: number | null;
+  look_in?: "Formulas" | "Values" | "Comments";
+  mode?: "Text" | "Wildcard" | "Regex";
+  match_case?: boolean;
+  whole_cell?: boolean;
+  order?: "ByRows" | "ByColumns";
+}
+
+export interface CellReference {
+  sheet: number;
+  row: number;
+  column: number;
+}
+
 /**
  * A cell hyperlink. The link is cell metadata: the text displayed in the cell
  * is the cell content, not part of the link.
@@ -825,6 +938,57 @@ export declare class UserModel {
   pasteFromClipboard(sourceSheet: number, sourceRange: [number, number, number, number], clipboard: ClipboardData, isCut: boolean): void
   /** Pastes a csv string starting at the top-left corner of the given area */
   pasteCsvString(sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number, csv: string): void
+  /**
+   * Pastes data copied with `copyToClipboard` into the selected area.
+   * `options` selects what is pasted; null pastes everything, like `pasteFromClipboard`.
+   */
+  pasteSpecialFromClipboard(sourceSheet: number, sourceRange: [number, number, number, number], clipboard: ClipboardData, options?: PasteOptions | null): void
+  /** Pastes an HTML table starting at the top-left corner of the given area */
+  pasteHtmlString(sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number, html: string): void
+  /** Returns the runs of a rich text cell or null if the cell is not rich text */
+  getCellRichText(sheet: number, row: number, column: number): Array<RichTextRun> | null
+  /** Sets the cell to rich text, i.e. [{text: "Hello "}, {text: "world", font: {b: true}}] */
+  setCellRichText(sheet: number, row: number, column: number, runs: Array<RichTextRun>): void
+  /** Returns the merged areas of the sheet */
+  getMergedCells(sheet: number): Array<Area>
+  /** Returns the merged area the cell belongs to or null */
+  getMergedArea(sheet: number, row: number, column: number): Area | null
+  mergeCells(sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number): void
+  unmergeCells(sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number): void
+  /** Returns the tables of the sheet */
+  getTables(sheet: number): Array<Table>
+  /** Returns the name of the table the cell belongs to or null */
+  getTableNameAt(sheet: number, row: number, column: number): string | null
+  isValidTableName(name: string): void
+  /** Creates a table in the given area and returns its name */
+  createTable(sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number, hasHeaders: boolean): string
+  resizeTable(name: string, sheet: number, startRow: number, startColumn: number, endRow: number, endColumn: number): void
+  renameTable(name: string, newName: string): void
+  deleteTable(name: string): void
+  /** Removes the table keeping the cells and their values */
+  convertTableToRange(name: string): void
+  insertTableColumn(name: string, column: number): void
+  deleteTableColumn(name: string, column: number): void
+  renameTableColumn(name: string, column: number, newName: string): void
+  setTableHeaderRow(name: string, show: boolean): void
+  setTableTotalsRow(name: string, show: boolean): void
+  /** Returns the comments of the sheet */
+  getComments(sheet: number): Array<Comment>
+  /** Returns the comment of the cell or null */
+  getComment(sheet: number, row: number, column: number): Comment | null
+  /** Sets a plain comment (a note) in the cell, replacing the existing one */
+  setComment(sheet: number, row: number, column: number, text: string, authorName: string): void
+  addThreadedComment(sheet: number, row: number, column: number, text: string, authorName: string): void
+  addCommentReply(sheet: number, row: number, column: number, text: string, authorName: string): void
+  setCommentThreadDone(sheet: number, row: number, column: number, done: boolean): void
+  deleteComment(sheet: number, row: number, column: number): void
+  /**
+   * Returns the cells matching `text`. `options` can be null for a plain
+   * text search in the formulas of all the sheets.
+   */
+  find(text: string, options?: FindOptions | null): Array<CellReference>
+  /** Replaces `text` in all the matching cells and returns the number of cells changed */
+  replaceAll(text: string, replacement: string, options?: FindOptions | null): number
 }
 
 /** The type of the content of a cell, following Excel's TYPE() convention. */
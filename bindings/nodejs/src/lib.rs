@@ -18,6 +18,7 @@ use ironcalc::base::expressions::utils::{
   column_to_number, number_to_column, quote_name as quote_name_ic,
 };
 use ironcalc::base::types::Color;
+use ironcalc::base::ClipboardData;
 
 pub(crate) fn to_js_error(error: impl std::fmt::Display) -> Error {
   Error::new(Status::Unknown, error.to_string())
@@ -68,6 +69,14 @@ pub(crate) fn js_to_color(env: &Env, value: Option<Unknown>) -> Result<Color> {
   Ok(color)
 }
 
+/// Converts the `data` of a clipboard back into `ClipboardData`.
+/// Object keys are always strings in JS, so the row and column numbers are
+/// parsed by going through a JSON value.
+pub(crate) fn js_to_clipboard_data(env: &Env, value: Unknown) -> Result<ClipboardData> {
+  let value: serde_json::Value = env.from_js_value(value).map_err(to_js_error)?;
+  serde_json::from_value(value).map_err(to_js_error)
+}
+
 #[derive(Serialize)]
 pub(crate) struct DefinedName {
   pub name: String,
@@ -5,14 +5,19 @@ use napi::{self, bindgen_prelude::Uint8Array, Env, Result, Unknown};
 use ironcalc::{
   base::{
     cf_types::CfRuleInput,
+    find_types::FindOptions,
+    rich_text_types::RichTextRun,
     types::{Link, Style, StyleIncludes, Theme},
-    BorderArea, ClipboardData, UserModel as BaseModel,
+    BorderArea, PasteOptions, UserModel as BaseModel,
   },
   export::{save_to_icalc, save_to_xlsx},
   import::{load_from_icalc, load_from_xlsx},
 };
 
-use crate::{area, js_to_color, leak_str, to_js_error, CellType, DefinedName, FmtSettings};
+use crate::{
+  area, js_to_clipboard_data, js_to_color, leak_str, to_js_error, CellType, DefinedName,
+  FmtSettings,
+};
 
 #[derive(Serialize)]
 struct NamedStyleEntry {
@@ -1159,7 +1164,7 @@ impl UserModel {
   ) -> Result<()> {
     let source_range: (i32, i32, i32, i32) =
       env.from_js_value(source_range).map_err(to_js_error)?;
-    let clipboard: ClipboardData = env.from_js_value(clipboard).map_err(to_js_error)?;
+    let clipboard = js_to_clipboard_data(&env, clipboard)?;
     self
       .model
       .paste_from_clipboard(source_sheet, source_range, &clipboard, is_cut)
@@ -1185,4 +1190,398 @@ impl UserModel {
       )
       .map_err(to_js_error)
   }
+
+  /// Pastes data copied with `copyToClipboard` into the selected area.
+  /// `options` selects what is pasted; null pastes everything, like `pasteFromClipboard`.
+  #[napi]
+  pub fn paste_special_from_clipboard(
+    &mut self,
+    env: Env,
+    source_sheet: u32,
+    #[napi(ts_arg_type = "[number, number, number, number]")] source_range: Unknown,
+    #[napi(ts_arg_type = "ClipboardData")] clipboard: Unknown,
+    #[napi(ts_arg_type = "PasteOptions | null")] options: Option<Unknown>,
+  ) -> Result<()> {
+    let source_range: (i32, i32, i32, i32) =
+      env.from_js_value(source_range).map_err(to_js_error)?;
+    let clipboard = js_to_clipboard_data(&env, clipboard)?;
+    let options: PasteOptions = match options {
+      Some(obj) => env.from_js_value(obj).map_err(to_js_error)?,
+      None => PasteOptions::default(),
+    };
+    self
+      .model
+      .paste_special_from_clipboard(source_sheet, source_range, &clipboard, &options)
+      .map_err(to_js_error)
+  }
+
+  /// Pastes an HTML table starting at the top-left corner of the given area
+  #[napi]
+  pub fn paste_html_string(
+    &mut self,
+    sheet: u32,
+    start_row: i32,
+    start_column: i32,
+    end_row: i32,
+    end_column: i32,
+    html: String,
+  ) -> Result<()> {
+    self
+      .model
+      .paste_html_string(
+        &area(sheet, start_row, start_column, end_row, end_column),
+        &html,
+      )
+      .map_err(to_js_error)
+  }
+
+  // Rich text
+
+  /// Returns the runs of a rich text cell or null if the cell is not rich text
+  #[napi(ts_return_type = "Array<RichTextRun> | null")]
+  pub fn get_cell_rich_text<'e>(
+    &self,
+    env: &'e Env,
+    sheet: u32,
+    row: i32,
+    column: i32,
+  ) -> Result<Unknown<'e>> {
+    let runs = self
+      .model
+      .get_cell_rich_text(sheet, row, column)
+      .map_err(to_js_error)?;
+    env.to_js_value(&runs).map_err(to_js_error)
+  }
+
+  /// Sets the cell to rich text, i.e. [{text: "Hello "}, {text: "world", font: {b: true}}]
+  #[napi]
+  pub fn set_cell_rich_text(
+    &mut self,
+    env: Env,
+    sheet: u32,
+    row: i32,
+    column: i32,
+    #[napi(ts_arg_type = "Array<RichTextRun>")] runs: Unknown,
+  ) -> Result<()> {
+    let runs: Vec<RichTextRun> = env.from_js_value(runs).map_err(to_js_error)?;
+    self
+      .model
+      .set_cell_rich_text(sheet, row, column, runs)
+      .map_err(to_js_error)
+  }
+
+  // Merged cells
+
+  /// Returns the merged areas of the sheet
+  #[napi(ts_return_type = "Array<Area>")]
+  pub fn get_merged_cells<'e>(&self, env: &'e Env, sheet: u32) -> Result<Unknown<'e>> {
+    let areas = self.model.get_merged_cells(sheet).map_err(to_js_error)?;
+    env.to_js_value(&areas).map_err(to_js_error)
+  }
+
+  /// Returns the merged area the cell belongs to or null
+  #[napi(ts_return_type = "Area | null")]
+  pub fn get_merged_area<'e>(
+    &self,
+    env: &'e Env,
+    sheet: u32,
+    row: i32,
+    column: i32,
+  ) -> Result<Unknown<'e>> {
+    let merged = self
+      .model
+      .get_merged_area(sheet, row, column)
+      .map_err(to_js_error)?;
+    env.to_js_value(&merged).map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn merge_cells(
+    &mut self,
+    sheet: u32,
+    start_row: i32,
+    start_column: i32,
+    end_row: i32,
+    end_column: i32,
+  ) -> Result<()> {
+    self
+      .model
+      .merge_cells(&area(sheet, start_row, start_column, end_row, end_column))
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn unmerge_cells(
+    &mut self,
+    sheet: u32,
+    start_row: i32,
+    start_column: i32,
+    end_row: i32,
+    end_column: i32,
+  ) -> Result<()> {
+    self
+      .model
+      .unmerge_cells(&area(sheet, start_row, start_column, end_row, end_column))
+      .map_err(to_js_error)
+  }
+
+  // Tables
+
+  /// Returns the tables of the sheet
+  #[napi(ts_return_type = "Array<Table>")]
+  pub fn get_tables<'e>(&self, env: &'e Env, sheet: u32) -> Result<Unknown<'e>> {
+    let tables = self.model.get_tables(sheet).map_err(to_js_error)?;
+    env.to_js_value(&tables).map_err(to_js_error)
+  }
+
+  /// Returns the name of the table the cell belongs to or null
+  #[napi]
+  pub fn get_table_name_at(&self, sheet: u32, row: i32, column: i32) -> Result<Option<String>> {
+    self
+      .model
+      .get_table_name_at(sheet, row, column)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn is_valid_table_name(&self, name: String) -> Result<()> {
+    self.model.is_valid_table_name(&name).map_err(to_js_error)
+  }
+
+  /// Creates a table in the given area and returns its name
+  #[napi]
+  pub fn create_table(
+    &mut self,
+    sheet: u32,
+    start_row: i32,
+    start_column: i32,
+    end_row: i32,
+    end_column: i32,
+    has_headers: bool,
+  ) -> Result<String> {
+    self
+      .model
+      .create_table(
+        &area(sheet, start_row, start_column, end_row, end_column),
+        has_headers,
+      )
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn resize_table(
+    &mut self,
+    name: String,
+    sheet: u32,
+    start_row: i32,
+    start_column: i32,
+    end_row: i32,
+    end_column: i32,
+  ) -> Result<()> {
+    self
+      .model
+      .resize_table(
+        &name,
+        &area(sheet, start_row, start_column, end_row, end_column),
+      )
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn rename_table(&mut self, name: String, new_name: String) -> Result<()> {
+    self
+      .model
+      .rename_table(&name, &new_name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn delete_table(&mut self, name: String) -> Result<()> {
+    self.model.delete_table(&name).map_err(to_js_error)
+  }
+
+  /// Removes the table keeping the cells and their values
+  #[napi]
+  pub fn convert_table_to_range(&mut self, name: String) -> Result<()> {
+    self
+      .model
+      .convert_table_to_range(&name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn insert_table_column(&mut self, name: String, column: i32) -> Result<()> {
+    self
+      .model
+      .insert_table_column(&name, column)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn delete_table_column(&mut self, name: String, column: i32) -> Result<()> {
+    self
+      .model
+      .delete_table_column(&name, column)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn rename_table_column(&mut self, name: String, column: i32, new_name: String) -> Result<()> {
+    self
+      .model
+      .rename_table_column(&name, column, &new_name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn set_table_header_row(&mut self, name: String, show: bool) -> Result<()> {
+    self
+      .model
+      .set_table_header_row(&name, show)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn set_table_totals_row(&mut self, name: String, show: bool) -> Result<()> {
+    self
+      .model
+      .set_table_totals_row(&name, show)
+      .map_err(to_js_error)
+  }
+
+  // Comments
+
+  /// Returns the comments of the sheet
+  #[napi(ts_return_type = "Array<Comment>")]
+  pub fn get_comments<'e>(&self, env: &'e Env, sheet: u32) -> Result<Unknown<'e>> {
+    let comments = self.model.get_comments(sheet).map_err(to_js_error)?;
+    env.to_js_value(&comments).map_err(to_js_error)
+  }
+
+  /// Returns the comment of the cell or null
+  #[napi(ts_return_type = "Comment | null")]
+  pub fn get_comment<'e>(
+    &self,
+    env: &'e Env,
+    sheet: u32,
+    row: i32,
+    column: i32,
+  ) -> Result<Unknown<'e>> {
+    let comment = self
+      .model
+      .get_comment(sheet, row, column)
+      .map_err(to_js_error)?;
+    env.to_js_value(&comment).map_err(to_js_error)
+  }
+
+  /// Sets a plain comment (a note) in the cell, replacing the existing one
+  #[napi]
+  pub fn set_comment(
+    &mut self,
+    sheet: u32,
+    row: i32,
+    column: i32,
+    text: String,
+    author_name: String,
+  ) -> Result<()> {
+    self
+      .model
+      .set_comment(sheet, row, column, &text, &author_name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn add_threaded_comment(
+    &mut self,
+    sheet: u32,
+    row: i32,
+    column: i32,
+    text: String,
+    author_name: String,
+  ) -> Result<()> {
+    self
+      .model
+      .add_threaded_comment(sheet, row, column, &text, &author_name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn add_comment_reply(
+    &mut self,
+    sheet: u32,
+    row: i32,
+    column: i32,
+    text: String,
+    author_name: String,
+  ) -> Result<()> {
+    self
+      .model
+      .add_comment_reply(sheet, row, column, &text, &author_name)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn set_comment_thread_done(
+    &mut self,
+    sheet: u32,
+    row: i32,
+    column: i32,
+    done: bool,
+  ) -> Result<()> {
+    self
+      .model
+      .set_comment_thread_done(sheet, row, column, done)
+      .map_err(to_js_error)
+  }
+
+  #[napi]
+  pub fn delete_comment(&mut self, sheet: u32, row: i32, column: i32) -> Result<()> {
+    self
+      .model
+      .delete_comment(sheet, row, column)
+      .map_err(to_js_error)
+  }
+
+  // Find and replace
+
+  /// Returns the cells matching `text`. `options` can be null for a plain
+  /// text search in the formulas of all the sheets.
+  #[napi(ts_return_type = "Array<CellReference>")]
+  pub fn find<'e>(
+    &self,
+    env: &'e Env,
+    text: String,
+    #[napi(ts_arg_type = "FindOptions | null")] options: Option<Unknown>,
+  ) -> Result<Unknown<'e>> {
+    let options: FindOptions = match options {
+      Some(obj) => env.from_js_value(obj).map_err(to_js_error)?,
+      None => FindOptions::default(),
+    };
+    let cells: Vec<_> = self
+      .model
+      .find(&text, &options)
+      .map_err(to_js_error)?
+      .collect();
+    env.to_js_value(&cells).map_err(to_js_error)
+  }
+
+  /// Replaces `text` in all the matching cells and returns the number of cells changed
+  #[napi]
+  pub fn replace_all(
+    &mut self,
+    env: Env,
+    text: String,
+    replacement: String,
+    #[napi(ts_arg_type = "FindOptions | null")] options: Option<Unknown>,
+  ) -> Result<u32> {
+    let options: FindOptions = match options {
+      Some(obj) => env.from_js_value(obj).map_err(to_js_error)?,
+      None => FindOptions::default(),
+    };
+    let count = self
+      .model
+      .replace_all(&text, &replacement, &options)
+      .map_err(to_js_error)?;
+    Ok(count as u32)
+  }
 }
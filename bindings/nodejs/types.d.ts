@@ -357,16 +357,129 @@ export type ClipboardData = Record<string, Record<string, ClipboardCell>>;
 
 export interface Clipboard {
   csv: string;
+  /** The cells as an HTML table with the styles inlined */
+  html: string;
   data: ClipboardData;
+  sheet: number;
   range: [number, number, number, number];
 }
 
+/** Options for `pasteSpecialFromClipboard`. All the fields are optional. */
+export interface PasteOptions {
+  paste?: "all" | "values" | "formats" | "formulas" | "columnWidths";
+  operation?: "none" | "add" | "subtract" | "multiply" | "divide";
+  skip_blanks?: boolean;
+  transpose?: boolean;
+}
+
 export interface DefinedName {
   name: string;
   scope?: number;
   formula: string;
 }
 
+export interface Area {
+  sheet: number;
+  row: number;
+  column: number;
+  width: number;
+  height: number;
+}
+
+export interface RichTextFont {
+  b?: boolean;
+  i?: boolean;
+  u?: boolean;
+  strike?: boolean;
+  sz?: number;
+  color?: Color;
+  name?: string;
+}
+
+/** A run of text with the same font in a rich text cell */
+export interface RichTextRun {
+  text: string;
+  font?: RichTextFont;
+}
+
+export interface TableStyleInfo {
+  name: string | null;
+  show_first_column: boolean;
+  show_last_column: boolean;
+  show_row_stripes: boolean;
+  show_column_stripes: boolean;
+}
+
+export interface TableColumn {
+  id: number;
+  name: string;
+  totals_row_label: string | null;
+  header_row_dxf_id: number | null;
+  data_dxf_id: number | null;
+  totals_row_dxf_id: number | null;
+  totals_row_function: string | null;
+  totals_row_formula: string | null;
+  calculated_column_formula: string | null;
+  filter: unknown | null;
+}
+
+export interface Table {
+  name: string;
+  display_name: string;
+  sheet_name: string;
+  /** The range of the table, like "A1:D10" */
+  reference: string;
+  totals_row_count: number;
+  header_row_count: number;
+  header_row_dxf_id: number | null;
+  data_dxf_id: number | null;
+  totals_row_dxf_id: number | null;
+  columns: TableColumn[];
+  style_info: TableStyleInfo;
+  has_filters: boolean;
+}
+
+export interface CommentReply {
+  id: string;
+  text: string;
+  author_name: string;
+  author_id: string | null;
+  date: string;
+}
+
+/** Present in threaded comments only */
+export interface CommentThread {
+  id: string;
+  date: string;
+  done: boolean;
+  replies: CommentReply[];
+}
+
+export interface Comment {
+  text: string;
+  author_name: string;
+  author_id: string | null;
+  cell_ref: string;
+  thread: CommentThread | null;
+}
+
+/** Options for `find` and `replaceAll`. All the fields are optional. */
+export interface FindOptions {
+  /** Search only this sheet, all the sheets if absent */
+  sheet?: number | null;
+  look_in?: "Formulas" | "Values" | "Comments";
+  mode?: "Text" | "Wildcard" | "Regex";
+  match_case?: boolean;
+  whole_cell?: boolean;
+  order?: "ByRows" | "ByColumns";
+}
+
+export interface CellReference {
+  sheet: number;
+  row: number;
+  column: number;
+}
+
 /**
  * A cell hyperlink. The link is cell metadata: the text displayed in the cell
  * is the cell content, not part of the link.
@@ -105,6 +105,40 @@ class CellLinkDict(LinkDict, total=False):
     column: int
     dynamic: bool
 
+class AreaDict(TypedDict):
+    sheet: int
+    row: int
+    column: int
+    width: int
+    height: int
+
+class CellReferenceDict(TypedDict):
+    sheet: int
+    row: int
+    column: int
+
+class RichTextRunDict(TypedDict, total=False):
+    """A run of text with the same font in a rich text cell. The font keys
+    are the ones of FontDict: "b", "i", "u", "strike", "sz", "color", "name".
+    """
+
+    text: str
+    font: FontDict
+
+class FindOptionsDict(TypedDict, total=False):
+    sheet: int | None  # None searches all the sheets
+    look_in: str  # "Formulas", "Values" or "Comments"
+    mode: str  # "Text", "Wildcard" or "Regex"
+    match_case: bool
+    whole_cell: bool
+    order: str  # "ByRows" or "ByColumns"
+
+class PasteOptionsDict(TypedDict, total=False):
+    paste: str  # "all", "values", "formats", "formulas" or "columnWidths"
+    operation: str  # "none", "add", "subtract", "multiply" or "divide"
+    skip_blanks: bool
+    transpose: bool
+
 # Conditional formatting rules, i.e.:
 #   {"type": "CellIs", "operator": "GreaterThan", "formula": "5",
 #    "formula2": None, "format": {"fill": {"color": "#FFC7CE"}},
@@ -533,3 +567,93 @@ class UserModel:
         end_column: int,
         csv: str,
     ) -> None: ...
+    def paste_special_from_clipboard(
+        self,
+        source_sheet: int,
+        source_range: tuple[int, int, int, int],
+        clipboard: dict[Any, Any],
+        options: PasteOptionsDict | None = None,
+    ) -> None: ...
+    def paste_html_string(
+        self,
+        sheet: int,
+        start_row: int,
+        start_column: int,
+        end_row: int,
+        end_column: int,
+        html: str,
+    ) -> None: ...
+
+    # Rich text
+    def get_cell_rich_text(
+        self, sheet: int, row: int, column: int
+    ) -> list[RichTextRunDict] | None: ...
+    def set_cell_rich_text(
+        self, sheet: int, row: int, column: int, runs: list[RichTextRunDict]
+    ) -> None: ...
+
+    # Merged cells
+    def get_merged_cells(self, sheet: int) -> list[AreaDict]: ...
+    def get_merged_area(self, sheet: int, row: int, column: int) -> AreaDict | None: ...
+    def merge_cells(
+        self, sheet: int, start_row: int, start_column: int, end_row: int, end_column: int
+    ) -> None: ...
+    def unmerge_cells(
+        self, sheet: int, start_row: int, start_column: int, end_row: int, end_column: int
+    ) -> None: ...
+
+    # Tables
+    def get_tables(self, sheet: int) -> list[dict[str, Any]]: ...
+    def get_table_name_at(self, sheet: int, row: int, column: int) -> str | None: ...
+    def is_valid_table_name(self, name: str) -> None: ...
+    def create_table(
+        self,
+        sheet: int,
+        start_row: int,
+        start_column: int,
+        end_row: int,
+        end_column: int,
+        has_headers: bool,
+    ) -> str: ...
+    def resize_table(
+        self,
+        name: str,
+        sheet: int,
+        start_row: int,
+        start_column: int,
+        end_row: int,
+        end_column: int,
+    ) -> None: ...
+    def rename_table(self, name: str, new_name: str) -> None: ...
+    def delete_table(self, name: str) -> None: ...
+    def convert_table_to_range(self, name: str) -> None: ...
+    def insert_table_column(self, name: str, column: int) -> None: ...
+    def delete_table_column(self, name: str, column: int) -> None: ...
+    def rename_table_column(self, name: str, column: int, new_name: str) -> None: ...
+    def set_table_header_row(self, name: str, show: bool) -> None: ...
+    def set_table_totals_row(self, name: str, show: bool) -> None: ...
+
+    # Comments
+    def get_comments(self, sheet: int) -> list[dict[str, Any]]: ...
+    def get_comment(self, sheet: int, row: int, column: int) -> dict[str, Any] | None: ...
+    def set_comment(
+        self, sheet: int, row: int, column: int, text: str, author_name: str
+    ) -> None: ...
+    def add_threaded_comment(
+        self, sheet: int, row: int, column: int, text: str, author_name: str
+    ) -> None: ...
+    def add_comment_reply(
+        self, sheet: int, row: int, column: int, text: str, author_name: str
+    ) -> None: ...
+    def set_comment_thread_done(
+        self, sheet: int, row: int, column: int, done: bool
+    ) -> None: ...
+    def delete_comment(self, sheet: int, row: int, column: int) -> None: ...
+
+    # Find and replace
+    def find(
+        self, text: str, options: FindOptionsDict | None = None
+    ) -> list[CellReferenceDict]: ...
+    def replace_all(
+        self, text: str, replacement: str, options: FindOptionsDict | None = None
+    ) -> int: ...
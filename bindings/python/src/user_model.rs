@@ -1,8 +1,10 @@
 use pyo3::prelude::*;
 
 use xlsx::base::cf_types::CfRuleInput;
+use xlsx::base::find_types::FindOptions;
+use xlsx::base::rich_text_types::RichTextRun;
 use xlsx::base::types::{Link, Style, StyleIncludes, Theme};
-use xlsx::base::{BorderArea, ClipboardData, UserModel};
+use xlsx::base::{BorderArea, ClipboardData, PasteOptions, UserModel};
 use xlsx::export::{save_to_icalc, save_to_xlsx};
 use xlsx::import;
 
@@ -1016,4 +1018,348 @@ impl PyUserModel {
             )
             .map_err(to_py_err)
     }
+
+    /// Pastes data copied with `copy_to_clipboard` into the selected area.
+    /// `options` is a dictionary, i.e. {"paste": "values", "operation": "add",
+    /// "skip_blanks": True, "transpose": False}. Missing keys take their defaults.
+    #[pyo3(signature = (source_sheet, source_range, clipboard, options=None))]
+    pub fn paste_special_from_clipboard(
+        &mut self,
+        source_sheet: u32,
+        source_range: (i32, i32, i32, i32),
+        clipboard: &Bound<'_, PyAny>,
+        options: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        let clipboard: ClipboardData = from_python(clipboard)?;
+        let options: PasteOptions = match options {
+            Some(obj) => from_python(obj)?,
+            None => PasteOptions::default(),
+        };
+        self.model
+            .paste_special_from_clipboard(source_sheet, source_range, &clipboard, &options)
+            .map_err(to_py_err)
+    }
+
+    /// Pastes an HTML table starting at the top-left corner of the given area
+    pub fn paste_html_string(
+        &mut self,
+        sheet: u32,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+        html: &str,
+    ) -> PyResult<()> {
+        self.model
+            .paste_html_string(
+                &area(sheet, start_row, start_column, end_row, end_column),
+                html,
+            )
+            .map_err(to_py_err)
+    }
+
+    // Rich text
+
+    /// Returns the runs of a rich text cell as [{"text", "font"}] or None
+    /// if the cell is not rich text
+    pub fn get_cell_rich_text<'py>(
+        &self,
+        py: Python<'py>,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let runs = self
+            .model
+            .get_cell_rich_text(sheet, row, column)
+            .map_err(to_py_err)?;
+        to_python(py, &runs)
+    }
+
+    /// Sets the cell to rich text. `runs` is a list of dictionaries,
+    /// i.e. [{"text": "Hello "}, {"text": "world", "font": {"b": True}}]
+    pub fn set_cell_rich_text(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        runs: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let runs: Vec<RichTextRun> = from_python(runs)?;
+        self.model
+            .set_cell_rich_text(sheet, row, column, runs)
+            .map_err(to_py_err)
+    }
+
+    // Merged cells
+
+    /// Returns the merged areas of the sheet
+    pub fn get_merged_cells<'py>(
+        &self,
+        py: Python<'py>,
+        sheet: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let areas = self.model.get_merged_cells(sheet).map_err(to_py_err)?;
+        to_python(py, &areas)
+    }
+
+    /// Returns the merged area the cell belongs to or None
+    pub fn get_merged_area<'py>(
+        &self,
+        py: Python<'py>,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let merged = self
+            .model
+            .get_merged_area(sheet, row, column)
+            .map_err(to_py_err)?;
+        to_python(py, &merged)
+    }
+
+    pub fn merge_cells(
+        &mut self,
+        sheet: u32,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+    ) -> PyResult<()> {
+        self.model
+            .merge_cells(&area(sheet, start_row, start_column, end_row, end_column))
+            .map_err(to_py_err)
+    }
+
+    pub fn unmerge_cells(
+        &mut self,
+        sheet: u32,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+    ) -> PyResult<()> {
+        self.model
+            .unmerge_cells(&area(sheet, start_row, start_column, end_row, end_column))
+            .map_err(to_py_err)
+    }
+
+    // Tables
+
+    /// Returns the tables of the sheet
+    pub fn get_tables<'py>(&self, py: Python<'py>, sheet: u32) -> PyResult<Bound<'py, PyAny>> {
+        let tables = self.model.get_tables(sheet).map_err(to_py_err)?;
+        to_python(py, &tables)
+    }
+
+    /// Returns the name of the table the cell belongs to or None
+    pub fn get_table_name_at(&self, sheet: u32, row: i32, column: i32) -> PyResult<Option<String>> {
+        self.model
+            .get_table_name_at(sheet, row, column)
+            .map_err(to_py_err)
+    }
+
+    pub fn is_valid_table_name(&self, name: &str) -> PyResult<()> {
+        self.model.is_valid_table_name(name).map_err(to_py_err)
+    }
+
+    /// Creates a table in the given area and returns its name
+    pub fn create_table(
+        &mut self,
+        sheet: u32,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+        has_headers: bool,
+    ) -> PyResult<String> {
+        self.model
+            .create_table(
+                &area(sheet, start_row, start_column, end_row, end_column),
+                has_headers,
+            )
+            .map_err(to_py_err)
+    }
+
+    pub fn resize_table(
+        &mut self,
+        name: &str,
+        sheet: u32,
+        start_row: i32,
+        start_column: i32,
+        end_row: i32,
+        end_column: i32,
+    ) -> PyResult<()> {
+        self.model
+            .resize_table(
+                name,
+                &area(sheet, start_row, start_column, end_row, end_column),
+            )
+            .map_err(to_py_err)
+    }
+
+    pub fn rename_table(&mut self, name: &str, new_name: &str) -> PyResult<()> {
+        self.model.rename_table(name, new_name).map_err(to_py_err)
+    }
+
+    pub fn delete_table(&mut self, name: &str) -> PyResult<()> {
+        self.model.delete_table(name).map_err(to_py_err)
+    }
+
+    /// Removes the table keeping the cells and their values
+    pub fn convert_table_to_range(&mut self, name: &str) -> PyResult<()> {
+        self.model.convert_table_to_range(name).map_err(to_py_err)
+    }
+
+    pub fn insert_table_column(&mut self, name: &str, column: i32) -> PyResult<()> {
+        self.model
+            .insert_table_column(name, column)
+            .map_err(to_py_err)
+    }
+
+    pub fn delete_table_column(&mut self, name: &str, column: i32) -> PyResult<()> {
+        self.model
+            .delete_table_column(name, column)
+            .map_err(to_py_err)
+    }
+
+    pub fn rename_table_column(&mut self, name: &str, column: i32, new_name: &str) -> PyResult<()> {
+        self.model
+            .rename_table_column(name, column, new_name)
+            .map_err(to_py_err)
+    }
+
+    pub fn set_table_header_row(&mut self, name: &str, show: bool) -> PyResult<()> {
+        self.model
+            .set_table_header_row(name, show)
+            .map_err(to_py_err)
+    }
+
+    pub fn set_table_totals_row(&mut self, name: &str, show: bool) -> PyResult<()> {
+        self.model
+            .set_table_totals_row(name, show)
+            .map_err(to_py_err)
+    }
+
+    // Comments
+
+    /// Returns the comments of the sheet
+    pub fn get_comments<'py>(&self, py: Python<'py>, sheet: u32) -> PyResult<Bound<'py, PyAny>> {
+        let comments = self.model.get_comments(sheet).map_err(to_py_err)?;
+        to_python(py, &comments)
+    }
+
+    /// Returns the comment of the cell or None
+    pub fn get_comment<'py>(
+        &self,
+        py: Python<'py>,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let comment = self
+            .model
+            .get_comment(sheet, row, column)
+            .map_err(to_py_err)?;
+        to_python(py, &comment)
+    }
+
+    /// Sets a plain comment (a note) in the cell, replacing the existing one
+    pub fn set_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> PyResult<()> {
+        self.model
+            .set_comment(sheet, row, column, text, author_name)
+            .map_err(to_py_err)
+    }
+
+    pub fn add_threaded_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> PyResult<()> {
+        self.model
+            .add_threaded_comment(sheet, row, column, text, author_name)
+            .map_err(to_py_err)
+    }
+
+    pub fn add_comment_reply(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> PyResult<()> {
+        self.model
+            .add_comment_reply(sheet, row, column, text, author_name)
+            .map_err(to_py_err)
+    }
+
+    pub fn set_comment_thread_done(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        done: bool,
+    ) -> PyResult<()> {
+        self.model
+            .set_comment_thread_done(sheet, row, column, done)
+            .map_err(to_py_err)
+    }
+
+    pub fn delete_comment(&mut self, sheet: u32, row: i32, column: i32) -> PyResult<()> {
+        self.model
+            .delete_comment(sheet, row, column)
+            .map_err(to_py_err)
+    }
+
+    // Find and replace
+
+    /// Returns the cells matching `text` as [{"sheet", "row", "column"}].
+    /// `options` is a dictionary, i.e. {"look_in": "Values", "mode": "Wildcard",
+    /// "match_case": True}. Missing keys take their defaults.
+    #[pyo3(signature = (text, options=None))]
+    pub fn find<'py>(
+        &self,
+        py: Python<'py>,
+        text: &str,
+        options: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let options: FindOptions = match options {
+            Some(obj) => from_python(obj)?,
+            None => FindOptions::default(),
+        };
+        let cells: Vec<_> = self
+            .model
+            .find(text, &options)
+            .map_err(to_py_err)?
+            .collect();
+        to_python(py, &cells)
+    }
+
+    /// Replaces `text` in all the matching cells and returns the number of cells changed
+    #[pyo3(signature = (text, replacement, options=None))]
+    pub fn replace_all(
+        &mut self,
+        text: &str,
+        replacement: &str,
+        options: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<usize> {
+        let options: FindOptions = match options {
+            Some(obj) => from_python(obj)?,
+            None => FindOptions::default(),
+        };
+        self.model
+            .replace_all(text, replacement, &options)
+            .map_err(to_py_err)
+    }
 }
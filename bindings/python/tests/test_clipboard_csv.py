@@ -39,3 +39,38 @@ def test_paste_csv(um):
     assert um.get_formatted_cell_value(0, 2, 1) == "apples"
     assert um.get_formatted_cell_value(0, 2, 2) == "3"
     assert um.get_formatted_cell_value(0, 3, 2) == "5"
+
+
+def test_paste_special_values(um):
+    um.set_user_input(0, 1, 1, "2")
+    um.set_user_input(0, 2, 1, "=A1*3")
+    um.set_user_input(0, 1, 3, "10")
+    um.set_user_input(0, 2, 3, "10")
+
+    um.set_selected_range(1, 1, 2, 1)
+    clipboard = um.copy_to_clipboard()
+
+    um.set_selected_cell(1, 3)
+    um.paste_special_from_clipboard(
+        clipboard["sheet"],
+        tuple(clipboard["range"]),
+        clipboard["data"],
+        {"paste": "values", "operation": "add"},
+    )
+    assert um.get_cell_content(0, 1, 3) == "12"
+    assert um.get_cell_content(0, 2, 3) == "16"
+
+
+def test_copy_html(um):
+    um.set_user_input(0, 1, 1, "apples")
+    um.set_selected_cell(1, 1)
+    clipboard = um.copy_to_clipboard()
+    assert "<table" in clipboard["html"]
+    assert "apples" in clipboard["html"]
+
+
+def test_paste_html(um):
+    html = "<table><tr><td>name</td><td>value</td></tr><tr><td>apples</td><td>3</td></tr></table>"
+    um.paste_html_string(0, 1, 1, 2, 2, html)
+    assert um.get_formatted_cell_value(0, 1, 1) == "name"
+    assert um.get_formatted_cell_value(0, 2, 2) == "3"
@@ -0,0 +1,23 @@
+def test_note(um):
+    assert um.get_comment(0, 1, 1) is None
+
+    um.set_comment(0, 1, 1, "Check this", "Ada")
+    comment = um.get_comment(0, 1, 1)
+    assert comment["text"] == "Check this"
+    assert comment["author_name"] == "Ada"
+    assert comment["cell_ref"] == "A1"
+    assert comment["thread"] is None
+    assert len(um.get_comments(0)) == 1
+
+    um.delete_comment(0, 1, 1)
+    assert um.get_comment(0, 1, 1) is None
+
+
+def test_threaded_comment(um):
+    um.add_threaded_comment(0, 2, 2, "Is this right?", "Ada")
+    um.add_comment_reply(0, 2, 2, "Yes", "Grace")
+    um.set_comment_thread_done(0, 2, 2, True)
+
+    thread = um.get_comment(0, 2, 2)["thread"]
+    assert thread["done"]
+    assert [reply["text"] for reply in thread["replies"]] == ["Yes"]
@@ -0,0 +1,25 @@
+def test_find(um):
+    um.set_user_input(0, 1, 1, "apples")
+    um.set_user_input(0, 3, 2, "green apples")
+    um.set_user_input(0, 2, 1, "oranges")
+
+    assert um.find("apples") == [
+        {"sheet": 0, "row": 1, "column": 1},
+        {"sheet": 0, "row": 3, "column": 2},
+    ]
+    assert um.find("apples", {"whole_cell": True}) == [
+        {"sheet": 0, "row": 1, "column": 1}
+    ]
+    assert um.find("APPLES", {"match_case": True}) == []
+    assert um.find("*ges", {"mode": "Wildcard", "whole_cell": True}) == [
+        {"sheet": 0, "row": 2, "column": 1}
+    ]
+
+
+def test_replace_all(um):
+    um.set_user_input(0, 1, 1, "apples")
+    um.set_user_input(0, 2, 1, "green apples")
+
+    assert um.replace_all("apples", "pears") == 2
+    assert um.get_cell_content(0, 1, 1) == "pears"
+    assert um.get_cell_content(0, 2, 1) == "green pears"
@@ -0,0 +1,14 @@
+def test_merge_unmerge(um):
+    assert um.get_merged_cells(0) == []
+
+    um.merge_cells(0, 1, 1, 2, 3)
+    area = {"sheet": 0, "row": 1, "column": 1, "width": 3, "height": 2}
+    assert um.get_merged_cells(0) == [area]
+    assert um.get_merged_area(0, 2, 2) == area
+    assert um.get_merged_area(0, 5, 5) is None
+
+    um.unmerge_cells(0, 1, 1, 2, 3)
+    assert um.get_merged_cells(0) == []
+
+    um.undo()
+    assert um.get_merged_cells(0) == [area]
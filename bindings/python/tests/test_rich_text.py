@@ -0,0 +1,8 @@
+def test_rich_text(um):
+    um.set_user_input(0, 1, 1, "plain")
+    assert um.get_cell_rich_text(0, 1, 1) is None
+
+    runs = [{"text": "Hello "}, {"text": "world", "font": {"b": True}}]
+    um.set_cell_rich_text(0, 1, 1, runs)
+    assert um.get_cell_rich_text(0, 1, 1) == runs
+    assert um.get_formatted_cell_value(0, 1, 1) == "Hello world"
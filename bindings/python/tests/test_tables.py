@@ -0,0 +1,39 @@
+def test_create_rename_delete(um):
+    um.set_user_input(0, 1, 1, "Fruit")
+    um.set_user_input(0, 1, 2, "Amount")
+    um.set_user_input(0, 2, 1, "apples")
+    um.set_user_input(0, 2, 2, "3")
+    um.set_user_input(0, 3, 1, "oranges")
+    um.set_user_input(0, 3, 2, "5")
+
+    name = um.create_table(0, 1, 1, 3, 2, True)
+    assert name == "Table1"
+    assert um.get_table_name_at(0, 2, 2) == "Table1"
+    assert um.get_table_name_at(0, 5, 5) is None
+
+    tables = um.get_tables(0)
+    assert len(tables) == 1
+    assert tables[0]["reference"] == "A1:B3"
+    assert [column["name"] for column in tables[0]["columns"]] == ["Fruit", "Amount"]
+
+    um.set_user_input(0, 5, 1, "=SUM(Table1[Amount])")
+    assert um.get_formatted_cell_value(0, 5, 1) == "8"
+
+    um.rename_table("Table1", "Fruits")
+    assert um.get_cell_content(0, 5, 1) == "=SUM(Fruits[Amount])"
+
+    um.delete_table("Fruits")
+    assert um.get_tables(0) == []
+
+    um.undo()
+    assert um.get_table_name_at(0, 2, 2) == "Fruits"
+
+
+def test_totals_row(um):
+    um.set_user_input(0, 1, 1, "Amount")
+    um.set_user_input(0, 2, 1, "3")
+    um.set_user_input(0, 3, 1, "5")
+    um.create_table(0, 1, 1, 3, 1, True)
+
+    um.set_table_totals_row("Table1", True)
+    assert um.get_tables(0)[0]["totals_row_count"] == 1
@@ -12,9 +12,11 @@ use ironcalc_base::{
         types::Area,
         utils::{column_to_number, number_to_column, quote_name as quote_name_ic},
     },
+    find_types::FindOptions,
+    rich_text_types::RichTextRun,
     types::{CellType, Color, Link, Style, StyleIncludes},
     worksheet::NavigationDirection,
-    BorderArea, ClipboardData, UserModel as BaseModel,
+    BorderArea, ClipboardData, PasteOptions, UserModel as BaseModel,
 };
 
 fn to_js_error(error: String) -> JsError {
@@ -961,6 +963,40 @@ impl Model {
             .map_err(|e| to_js_error(e.to_string()))
     }
 
+    #[wasm_bindgen(js_name = "pasteSpecialFromClipboard")]
+    pub fn paste_special_from_clipboard(
+        &mut self,
+        source_sheet: u32,
+        #[wasm_bindgen(unchecked_param_type = "[number, number, number, number]")]
+        source_range: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "ClipboardData")] clipboard: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "PasteOptions")] options: JsValue,
+    ) -> Result<(), JsError> {
+        let source_range: (i32, i32, i32, i32) =
+            serde_wasm_bindgen::from_value(source_range).map_err(|e| to_js_error(e.to_string()))?;
+        let clipboard: ClipboardData =
+            serde_wasm_bindgen::from_value(clipboard).map_err(|e| to_js_error(e.to_string()))?;
+        let options: PasteOptions =
+            serde_wasm_bindgen::from_value(options).map_err(|e| to_js_error(e.to_string()))?;
+        self.model
+            .paste_special_from_clipboard(source_sheet, source_range, &clipboard, &options)
+            .map_err(to_js_error)
+    }
+
+    /// Pastes an HTML table, like the one copied from a web page or another spreadsheet
+    #[wasm_bindgen(js_name = "pasteHtmlText")]
+    pub fn paste_html_string(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Area")] area: JsValue,
+        html: &str,
+    ) -> Result<(), JsError> {
+        let range: Area =
+            serde_wasm_bindgen::from_value(area).map_err(|e| to_js_error(e.to_string()))?;
+        self.model
+            .paste_html_string(&range, html)
+            .map_err(to_js_error)
+    }
+
     #[wasm_bindgen(
         js_name = "getDefinedNameList",
         unchecked_return_type = "DefinedName[]"
@@ -1301,4 +1337,290 @@ impl Model {
     pub fn move_sheet(&mut self, sheet: u32, new_index: u32) -> Result<(), JsError> {
         self.model.move_sheet(sheet, new_index).map_err(to_js_error)
     }
+
+    /// Returns the runs of the rich text of the cell or undefined if the cell is not rich text.
+    #[wasm_bindgen(
+        js_name = "getCellRichText",
+        unchecked_return_type = "RichTextRun[] | undefined"
+    )]
+    pub fn get_cell_rich_text(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<JsValue, JsError> {
+        let runs = self
+            .model
+            .get_cell_rich_text(sheet, row, column)
+            .map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&runs).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "setCellRichText")]
+    pub fn set_cell_rich_text(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        #[wasm_bindgen(unchecked_param_type = "RichTextRun[]")] runs: JsValue,
+    ) -> Result<(), JsError> {
+        let runs: Vec<RichTextRun> =
+            serde_wasm_bindgen::from_value(runs).map_err(|e| to_js_error(e.to_string()))?;
+        self.model
+            .set_cell_rich_text(sheet, row, column, runs)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "getMergedCells", unchecked_return_type = "Area[]")]
+    pub fn get_merged_cells(&self, sheet: u32) -> Result<JsValue, JsError> {
+        let areas = self.model.get_merged_cells(sheet).map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&areas).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    /// Returns the merged area the cell belongs to or undefined if it is not merged.
+    #[wasm_bindgen(js_name = "getMergedArea", unchecked_return_type = "Area | undefined")]
+    pub fn get_merged_area(&self, sheet: u32, row: i32, column: i32) -> Result<JsValue, JsError> {
+        let area = self
+            .model
+            .get_merged_area(sheet, row, column)
+            .map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&area).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "mergeCells")]
+    pub fn merge_cells(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Area")] area: JsValue,
+    ) -> Result<(), JsError> {
+        let area: Area =
+            serde_wasm_bindgen::from_value(area).map_err(|e| to_js_error(e.to_string()))?;
+        self.model.merge_cells(&area).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "unmergeCells")]
+    pub fn unmerge_cells(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Area")] area: JsValue,
+    ) -> Result<(), JsError> {
+        let area: Area =
+            serde_wasm_bindgen::from_value(area).map_err(|e| to_js_error(e.to_string()))?;
+        self.model.unmerge_cells(&area).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "getTables", unchecked_return_type = "Table[]")]
+    pub fn get_tables(&self, sheet: u32) -> Result<JsValue, JsError> {
+        let tables = self.model.get_tables(sheet).map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&tables).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    /// Returns the name of the table the cell belongs to or undefined if there isn't one.
+    #[wasm_bindgen(js_name = "getTableNameAt")]
+    pub fn get_table_name_at(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<Option<String>, JsError> {
+        self.model
+            .get_table_name_at(sheet, row, column)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "isValidTableName")]
+    pub fn is_valid_table_name(&self, name: &str) -> Result<(), JsError> {
+        self.model.is_valid_table_name(name).map_err(to_js_error)
+    }
+
+    /// Creates a table in the area and returns its name.
+    #[wasm_bindgen(js_name = "createTable")]
+    pub fn create_table(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Area")] area: JsValue,
+        has_headers: bool,
+    ) -> Result<String, JsError> {
+        let area: Area =
+            serde_wasm_bindgen::from_value(area).map_err(|e| to_js_error(e.to_string()))?;
+        self.model
+            .create_table(&area, has_headers)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "resizeTable")]
+    pub fn resize_table(
+        &mut self,
+        name: &str,
+        #[wasm_bindgen(unchecked_param_type = "Area")] area: JsValue,
+    ) -> Result<(), JsError> {
+        let area: Area =
+            serde_wasm_bindgen::from_value(area).map_err(|e| to_js_error(e.to_string()))?;
+        self.model.resize_table(name, &area).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "renameTable")]
+    pub fn rename_table(&mut self, name: &str, new_name: &str) -> Result<(), JsError> {
+        self.model.rename_table(name, new_name).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "deleteTable")]
+    pub fn delete_table(&mut self, name: &str) -> Result<(), JsError> {
+        self.model.delete_table(name).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "convertTableToRange")]
+    pub fn convert_table_to_range(&mut self, name: &str) -> Result<(), JsError> {
+        self.model.convert_table_to_range(name).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "insertTableColumn")]
+    pub fn insert_table_column(&mut self, name: &str, column: i32) -> Result<(), JsError> {
+        self.model
+            .insert_table_column(name, column)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "deleteTableColumn")]
+    pub fn delete_table_column(&mut self, name: &str, column: i32) -> Result<(), JsError> {
+        self.model
+            .delete_table_column(name, column)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "renameTableColumn")]
+    pub fn rename_table_column(
+        &mut self,
+        name: &str,
+        column: i32,
+        new_name: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .rename_table_column(name, column, new_name)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "setTableHeaderRow")]
+    pub fn set_table_header_row(&mut self, name: &str, show: bool) -> Result<(), JsError> {
+        self.model
+            .set_table_header_row(name, show)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "setTableTotalsRow")]
+    pub fn set_table_totals_row(&mut self, name: &str, show: bool) -> Result<(), JsError> {
+        self.model
+            .set_table_totals_row(name, show)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "getComments", unchecked_return_type = "Comment[]")]
+    pub fn get_comments(&self, sheet: u32) -> Result<JsValue, JsError> {
+        let comments = self.model.get_comments(sheet).map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&comments).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    /// Returns the comment of the cell or undefined if there isn't one.
+    #[wasm_bindgen(js_name = "getComment", unchecked_return_type = "Comment | undefined")]
+    pub fn get_comment(&self, sheet: u32, row: i32, column: i32) -> Result<JsValue, JsError> {
+        let comment = self
+            .model
+            .get_comment(sheet, row, column)
+            .map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&comment).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    /// Sets a plain comment (a note) in the cell, replacing the existing one.
+    #[wasm_bindgen(js_name = "setComment")]
+    pub fn set_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .set_comment(sheet, row, column, text, author_name)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "addThreadedComment")]
+    pub fn add_threaded_comment(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .add_threaded_comment(sheet, row, column, text, author_name)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "addCommentReply")]
+    pub fn add_comment_reply(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        text: &str,
+        author_name: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .add_comment_reply(sheet, row, column, text, author_name)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "setCommentThreadDone")]
+    pub fn set_comment_thread_done(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        done: bool,
+    ) -> Result<(), JsError> {
+        self.model
+            .set_comment_thread_done(sheet, row, column, done)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = "deleteComment")]
+    pub fn delete_comment(&mut self, sheet: u32, row: i32, column: i32) -> Result<(), JsError> {
+        self.model
+            .delete_comment(sheet, row, column)
+            .map_err(to_js_error)
+    }
+
+    /// Returns the cells that match the text, in the order of the search.
+    #[wasm_bindgen(js_name = "find", unchecked_return_type = "CellReference[]")]
+    pub fn find(
+        &self,
+        text: &str,
+        #[wasm_bindgen(unchecked_param_type = "FindOptions")] options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let options: FindOptions =
+            serde_wasm_bindgen::from_value(options).map_err(|e| to_js_error(e.to_string()))?;
+        let cells: Vec<_> = self
+            .model
+            .find(text, &options)
+            .map_err(to_js_error)?
+            .collect();
+        serde_wasm_bindgen::to_value(&cells).map_err(|e| to_js_error(e.to_string()))
+    }
+
+    /// Replaces the text in all the cells that match and returns the number of cells changed.
+    #[wasm_bindgen(js_name = "replaceAll")]
+    pub fn replace_all(
+        &mut self,
+        text: &str,
+        replacement: &str,
+        #[wasm_bindgen(unchecked_param_type = "FindOptions")] options: JsValue,
+    ) -> Result<u32, JsError> {
+        let options: FindOptions =
+            serde_wasm_bindgen::from_value(options).map_err(|e| to_js_error(e.to_string()))?;
+        let count = self
+            .model
+            .replace_all(text, replacement, &options)
+            .map_err(to_js_error)?;
+        Ok(count as u32)
+    }
 }
@@ -0,0 +1,136 @@
+use ironcalc_base::{
+    filter_types::{AutoFilter, DateTimeGrouping, DynamicFilter, Filter, FilterOperator},
+    types::{Color, Dxf},
+};
+
+use super::escape::escape_xml;
+
+fn get_color_filter_xml(color: &Color, cell_color: bool, dxfs: &[Dxf]) -> String {
+    let dxf_id = dxfs.iter().position(|dxf| {
+        if cell_color {
+            dxf.fill.as_ref().map(|fill| &fill.color) == Some(color)
+        } else {
+            dxf.font.as_ref().map(|font| &font.color) == Some(color)
+        }
+    });
+    match dxf_id {
+        Some(dxf_id) if cell_color => format!("<colorFilter dxfId=\"{dxf_id}\"/>"),
+        Some(dxf_id) => format!("<colorFilter dxfId=\"{dxf_id}\" cellColor=\"0\"/>"),
+        None => "".to_string(),
+    }
+}
+
+fn get_filter_xml(filter: &Filter, dxfs: &[Dxf]) -> String {
+    match filter {
+        Filter::Values {
+            values,
+            date_groups,
+            blank,
+        } => {
+            let mut items = Vec::new();
+            for value in values {
+                items.push(format!("<filter val=\"{}\"/>", escape_xml(value)));
+            }
+            for item in date_groups {
+                let (grouping, count) = match item.grouping {
+                    DateTimeGrouping::Year => ("year", 1),
+                    DateTimeGrouping::Month => ("month", 2),
+                    DateTimeGrouping::Day => ("day", 3),
+                    DateTimeGrouping::Hour => ("hour", 4),
+                    DateTimeGrouping::Minute => ("minute", 5),
+                    DateTimeGrouping::Second => ("second", 6),
+                };
+                let fields = [
+                    ("year", item.year as u32),
+                    ("month", item.month),
+                    ("day", item.day),
+                    ("hour", item.hour),
+                    ("minute", item.minute),
+                    ("second", item.second),
+                ];
+                let attributes: String = fields[..count]
+                    .iter()
+                    .map(|(name, value)| format!(" {name}=\"{value}\""))
+                    .collect();
+                items.push(format!(
+                    "<dateGroupItem{attributes} dateTimeGrouping=\"{grouping}\"/>"
+                ));
+            }
+            let blank = if *blank { " blank=\"1\"" } else { "" };
+            format!("<filters{blank}>{}</filters>", items.join(""))
+        }
+        Filter::Top10 {
+            top,
+            percent,
+            value,
+        } => {
+            let top = if *top { "" } else { " top=\"0\"" };
+            let percent = if *percent { " percent=\"1\"" } else { "" };
+            format!("<top10{top}{percent} val=\"{value}\"/>")
+        }
+        Filter::Dynamic { kind } => {
+            let kind = match kind {
+                DynamicFilter::AboveAverage => "aboveAverage",
+                DynamicFilter::BelowAverage => "belowAverage",
+            };
+            format!("<dynamicFilter type=\"{kind}\"/>")
+        }
+        Filter::Custom { and, filters } => {
+            let mut items = Vec::new();
+            for filter in filters {
+                let operator = match filter.operator {
+                    FilterOperator::Equal => "",
+                    FilterOperator::NotEqual => " operator=\"notEqual\"",
+                    FilterOperator::GreaterThan => " operator=\"greaterThan\"",
+                    FilterOperator::GreaterThanOrEqual => " operator=\"greaterThanOrEqual\"",
+                    FilterOperator::LessThan => " operator=\"lessThan\"",
+                    FilterOperator::LessThanOrEqual => " operator=\"lessThanOrEqual\"",
+                };
+                items.push(format!(
+                    "<customFilter{operator} val=\"{}\"/>",
+                    escape_xml(&filter.value)
+                ));
+            }
+            let and = if *and { " and=\"1\"" } else { "" };
+            format!("<customFilters{and}>{}</customFilters>", items.join(""))
+        }
+        Filter::CellColor { color } => get_color_filter_xml(color, true, dxfs),
+        Filter::FontColor { color } => get_color_filter_xml(color, false, dxfs),
+    }
+}
+
+/// The `<filterColumn>` with the criteria of the column `index` of an autofilter
+/// ```xml
+/// <filterColumn colId="2">
+///   <customFilters and="1">
+///     <customFilter operator="greaterThan" val="20"/>
+///     <customFilter operator="lessThan" val="50"/>
+///   </customFilters>
+/// </filterColumn>
+/// ```
+pub(crate) fn get_filter_column_xml(index: i32, filter: &Filter, dxfs: &[Dxf]) -> String {
+    let filter = get_filter_xml(filter, dxfs);
+    if filter.is_empty() {
+        return "".to_string();
+    }
+    format!("<filterColumn colId=\"{index}\">{filter}</filterColumn>")
+}
+
+/// The `<autoFilter>` of the worksheet or an empty string if the sheet has none
+pub(crate) fn get_auto_filter_xml(auto_filter: &Option<AutoFilter>, dxfs: &[Dxf]) -> String {
+    let Some(auto_filter) = auto_filter else {
+        return "".to_string();
+    };
+    let columns: String = auto_filter
+        .columns
+        .iter()
+        .map(|column| get_filter_column_xml(column.column, &column.filter, dxfs))
+        .collect();
+    if columns.is_empty() {
+        return format!("<autoFilter ref=\"{}\"/>", auto_filter.range);
+    }
+    format!(
+        "<autoFilter ref=\"{}\">{columns}</autoFilter>",
+        auto_filter.range
+    )
+}
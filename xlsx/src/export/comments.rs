@@ -0,0 +1,258 @@
+//! The comments of a worksheet are stored in three parts referenced from the sheet rels:
+//!
+//! * `xl/commentsN.xml` with the text and the author of each comment.
+//! * `xl/drawings/vmlDrawingN.vml` with the box in which Excel shows each comment.
+//! * `xl/threadedComments/threadedCommentN.xml` with the threaded comments and their replies,
+//!   only if there are any. Excel also writes those as plain comments for older readers.
+//!
+//! The authors of the threaded comments are listed in `xl/persons/person.xml`.
+//! The numbers of the parts are global to the workbook, in the order of the worksheets.
+
+use ironcalc_base::{
+    expressions::utils::parse_reference_a1,
+    types::{Comment, Workbook, Worksheet},
+};
+
+use super::escape::escape_xml;
+use super::xml_constants::XML_DECLARATION;
+
+const THREADED_COMMENT_NOTICE: &str = "[Threaded comment]\n\nYour version of Excel allows you to \
+read this threaded comment; however, any edits to it will get removed if the file is opened in a \
+newer version of Excel. Learn more: https://go.microsoft.com/fwlink/?linkid=870924";
+
+/// The number of the comment parts of each worksheet, or None if it has no comments
+pub(crate) fn get_comment_ids(workbook: &Workbook) -> Vec<Option<usize>> {
+    let mut count = 0;
+    workbook
+        .worksheets
+        .iter()
+        .map(|worksheet| {
+            if worksheet.comments.is_empty() {
+                None
+            } else {
+                count += 1;
+                Some(count)
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn has_threaded_comments(worksheet: &Worksheet) -> bool {
+    worksheet
+        .comments
+        .iter()
+        .any(|comment| comment.thread.is_some())
+}
+
+// The text of the comment as seen by readers that do not understand threaded comments
+fn get_plain_text(comment: &Comment) -> String {
+    match &comment.thread {
+        None => comment.text.clone(),
+        Some(thread) => {
+            let mut text = format!(
+                "{THREADED_COMMENT_NOTICE}\n\nComment:\n    {}",
+                comment.text
+            );
+            for reply in &thread.replies {
+                text.push_str(&format!("\nReply:\n    {}", reply.text));
+            }
+            text
+        }
+    }
+}
+
+// The author of the comment in the plain comments part
+fn get_plain_author(comment: &Comment) -> String {
+    match &comment.thread {
+        None => comment.author_name.clone(),
+        Some(thread) => format!("tc={}", thread.id),
+    }
+}
+
+pub(crate) fn get_comments_xml(worksheet: &Worksheet) -> String {
+    let mut authors: Vec<String> = Vec::new();
+    let mut comments_str = Vec::new();
+    for comment in &worksheet.comments {
+        let author = get_plain_author(comment);
+        let author_id = match authors.iter().position(|a| a == &author) {
+            Some(index) => index,
+            None => {
+                authors.push(author);
+                authors.len() - 1
+            }
+        };
+        comments_str.push(format!(
+            "<comment ref=\"{}\" authorId=\"{author_id}\"><text><t xml:space=\"preserve\">{}</t></text></comment>",
+            escape_xml(&comment.cell_ref),
+            escape_xml(&get_plain_text(comment))
+        ));
+    }
+    let authors_str: Vec<String> = authors
+        .iter()
+        .map(|author| format!("<author>{}</author>", escape_xml(author)))
+        .collect();
+    format!(
+        "{XML_DECLARATION}\n\
+        <comments xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+        <authors>{}</authors>\
+        <commentList>{}</commentList>\
+        </comments>",
+        authors_str.join(""),
+        comments_str.join("")
+    )
+}
+
+/// The VML drawing with the (hidden) boxes of the comments of the worksheet
+pub(crate) fn get_vml_drawing_xml(worksheet: &Worksheet, id: usize) -> String {
+    let mut shapes = Vec::new();
+    for (index, comment) in worksheet.comments.iter().enumerate() {
+        let (row, column) = match parse_reference_a1(&comment.cell_ref) {
+            Some(reference) => (reference.row - 1, reference.column - 1),
+            None => continue,
+        };
+        let shape_id = id * 1024 + index + 1;
+        // The box is anchored to the right of the cell: column, x offset, row, y offset
+        let anchor = format!(
+            "{}, 15, {}, 10, {}, 15, {}, 4",
+            column + 1,
+            (row - 1).max(0),
+            column + 3,
+            row + 3
+        );
+        shapes.push(format!(
+            "<v:shape id=\"_x0000_s{shape_id}\" type=\"#_x0000_t202\" \
+            style=\"position:absolute;margin-left:59.25pt;margin-top:1.5pt;width:108pt;height:59.25pt;z-index:{};visibility:hidden\" \
+            fillcolor=\"#ffffe1\" o:insetmode=\"auto\">\
+            <v:fill color2=\"#ffffe1\"/>\
+            <v:shadow on=\"t\" color=\"black\" obscured=\"t\"/>\
+            <v:path o:connecttype=\"none\"/>\
+            <v:textbox style=\"mso-direction-alt:auto\"><div style=\"text-align:left\"></div></v:textbox>\
+            <x:ClientData ObjectType=\"Note\">\
+            <x:MoveWithCells/><x:SizeWithCells/>\
+            <x:Anchor>{anchor}</x:Anchor>\
+            <x:AutoFill>False</x:AutoFill>\
+            <x:Row>{row}</x:Row>\
+            <x:Column>{column}</x:Column>\
+            </x:ClientData>\
+            </v:shape>",
+            index + 1
+        ));
+    }
+    format!(
+        "<xml xmlns:v=\"urn:schemas-microsoft-com:vml\" xmlns:o=\"urn:schemas-microsoft-com:office:office\" xmlns:x=\"urn:schemas-microsoft-com:office:excel\">\
+        <o:shapelayout v:ext=\"edit\"><o:idmap v:ext=\"edit\" data=\"{id}\"/></o:shapelayout>\
+        <v:shapetype id=\"_x0000_t202\" coordsize=\"21600,21600\" o:spt=\"202\" path=\"m,l,21600r21600,l21600,xe\">\
+        <v:stroke joinstyle=\"miter\"/><v:path gradientshapeok=\"t\" o:connecttype=\"rect\"/>\
+        </v:shapetype>\
+        {}\
+        </xml>",
+        shapes.join("")
+    )
+}
+
+fn get_threaded_comment_xml(
+    cell_ref: &str,
+    date: &str,
+    author_id: &Option<String>,
+    id: &str,
+    parent_id: Option<&str>,
+    done: bool,
+    text: &str,
+) -> String {
+    let mut attributes = format!(" ref=\"{}\"", escape_xml(cell_ref));
+    if !date.is_empty() {
+        attributes.push_str(&format!(" dT=\"{}\"", escape_xml(date)));
+    }
+    if let Some(author_id) = author_id {
+        attributes.push_str(&format!(" personId=\"{}\"", escape_xml(author_id)));
+    }
+    attributes.push_str(&format!(" id=\"{}\"", escape_xml(id)));
+    if let Some(parent_id) = parent_id {
+        attributes.push_str(&format!(" parentId=\"{}\"", escape_xml(parent_id)));
+    }
+    if done {
+        attributes.push_str(" done=\"1\"");
+    }
+    format!(
+        "<threadedComment{attributes}><text>{}</text></threadedComment>",
+        escape_xml(text)
+    )
+}
+
+pub(crate) fn get_threaded_comments_xml(worksheet: &Worksheet) -> String {
+    let mut comments_str = Vec::new();
+    for comment in &worksheet.comments {
+        if let Some(thread) = &comment.thread {
+            comments_str.push(get_threaded_comment_xml(
+                &comment.cell_ref,
+                &thread.date,
+                &comment.author_id,
+                &thread.id,
+                None,
+                thread.done,
+                &comment.text,
+            ));
+            for reply in &thread.replies {
+                comments_str.push(get_threaded_comment_xml(
+                    &comment.cell_ref,
+                    &reply.date,
+                    &reply.author_id,
+                    &reply.id,
+                    Some(&thread.id),
+                    false,
+                    &reply.text,
+                ));
+            }
+        }
+    }
+    format!(
+        "{XML_DECLARATION}\n\
+        <ThreadedComments xmlns=\"http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments\" \
+        xmlns:x=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">{}</ThreadedComments>",
+        comments_str.join("")
+    )
+}
+
+/// The list of authors of threaded comments or None if the workbook has no threaded comments
+pub(crate) fn get_persons_xml(workbook: &Workbook) -> Option<String> {
+    // (id, name) of the authors of the threaded comments and their replies
+    let mut authors: Vec<(&Option<String>, &String)> = Vec::new();
+    for worksheet in &workbook.worksheets {
+        for comment in &worksheet.comments {
+            if let Some(thread) = &comment.thread {
+                authors.push((&comment.author_id, &comment.author_name));
+                for reply in &thread.replies {
+                    authors.push((&reply.author_id, &reply.author_name));
+                }
+            }
+        }
+    }
+    if authors.is_empty() {
+        return None;
+    }
+    let mut persons: Vec<(&String, &String)> = Vec::new();
+    for (id, name) in authors {
+        if let Some(id) = id {
+            if !persons.iter().any(|(person_id, _)| *person_id == id) {
+                persons.push((id, name));
+            }
+        }
+    }
+    let persons_str: Vec<String> = persons
+        .iter()
+        .map(|(id, name)| {
+            format!(
+                "<person displayName=\"{}\" id=\"{}\" userId=\"{}\" providerId=\"None\"/>",
+                escape_xml(name),
+                escape_xml(id),
+                escape_xml(name)
+            )
+        })
+        .collect();
+    Some(format!(
+        "{XML_DECLARATION}\n\
+        <personList xmlns=\"http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments\" \
+        xmlns:x=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">{}</personList>",
+        persons_str.join("")
+    ))
+}
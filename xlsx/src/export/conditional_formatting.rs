@@ -62,7 +62,7 @@ fn color_to_hex(color: &Color) -> String {
     }
 }
 
-fn value_operator_to_str(op: &ValueOperator) -> &'static str {
+pub(super) fn value_operator_to_str(op: &ValueOperator) -> &'static str {
     match op {
         ValueOperator::Equal => "equal",
         ValueOperator::GreaterThan => "greaterThan",
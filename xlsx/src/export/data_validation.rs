@@ -0,0 +1,145 @@
+use ironcalc_base::{
+    cf_types::ValueOperator,
+    dv_types::{
+        DataValidation, DataValidationErrorStyle, DataValidationListSource, DataValidationRule,
+    },
+};
+
+use super::{conditional_formatting::value_operator_to_str, escape::escape_xml};
+
+fn get_bool_attribute(name: &str, value: bool) -> String {
+    if value {
+        format!(" {name}=\"1\"")
+    } else {
+        "".to_string()
+    }
+}
+
+fn get_string_attribute(name: &str, value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!(" {name}=\"{}\"", escape_xml(value)),
+        None => "".to_string(),
+    }
+}
+
+fn get_data_validation_xml(data_validation: &DataValidation) -> String {
+    // type, operator and the formulas
+    let (rule_type, operator, formula1, formula2) = match &data_validation.rule {
+        DataValidationRule::List { source, .. } => {
+            let formula = match source {
+                DataValidationListSource::Values(values) => {
+                    format!("\"{}\"", values.join(",").replace('"', "\"\""))
+                }
+                DataValidationListSource::Formula(formula) => formula.clone(),
+            };
+            ("list", None, formula, None)
+        }
+        DataValidationRule::WholeNumber {
+            operator,
+            formula1,
+            formula2,
+        } => ("whole", Some(operator), formula1.clone(), formula2.clone()),
+        DataValidationRule::Decimal {
+            operator,
+            formula1,
+            formula2,
+        } => (
+            "decimal",
+            Some(operator),
+            formula1.clone(),
+            formula2.clone(),
+        ),
+        DataValidationRule::Date {
+            operator,
+            formula1,
+            formula2,
+        } => ("date", Some(operator), formula1.clone(), formula2.clone()),
+        DataValidationRule::Time {
+            operator,
+            formula1,
+            formula2,
+        } => ("time", Some(operator), formula1.clone(), formula2.clone()),
+        DataValidationRule::TextLength {
+            operator,
+            formula1,
+            formula2,
+        } => (
+            "textLength",
+            Some(operator),
+            formula1.clone(),
+            formula2.clone(),
+        ),
+        DataValidationRule::Custom { formula } => ("custom", None, formula.clone(), None),
+    };
+    let mut attributes = format!(" type=\"{rule_type}\"");
+    match data_validation.error_style {
+        DataValidationErrorStyle::Stop => {}
+        DataValidationErrorStyle::Warning => attributes.push_str(" errorStyle=\"warning\""),
+        DataValidationErrorStyle::Information => attributes.push_str(" errorStyle=\"information\""),
+    }
+    // between is the default operator
+    if let Some(operator) = operator {
+        if operator != &ValueOperator::Between {
+            attributes.push_str(&format!(
+                " operator=\"{}\"",
+                value_operator_to_str(operator)
+            ));
+        }
+    }
+    attributes.push_str(&get_bool_attribute(
+        "allowBlank",
+        data_validation.allow_blank,
+    ));
+    // Note that showDropDown="1" hides the dropdown
+    if let DataValidationRule::List {
+        show_dropdown: false,
+        ..
+    } = data_validation.rule
+    {
+        attributes.push_str(" showDropDown=\"1\"");
+    }
+    attributes.push_str(&get_bool_attribute(
+        "showInputMessage",
+        data_validation.show_input_message,
+    ));
+    attributes.push_str(&get_bool_attribute(
+        "showErrorMessage",
+        data_validation.show_error_message,
+    ));
+    attributes.push_str(&get_string_attribute(
+        "errorTitle",
+        &data_validation.error_title,
+    ));
+    attributes.push_str(&get_string_attribute("error", &data_validation.error));
+    attributes.push_str(&get_string_attribute(
+        "promptTitle",
+        &data_validation.prompt_title,
+    ));
+    attributes.push_str(&get_string_attribute("prompt", &data_validation.prompt));
+
+    let formula2 = match formula2 {
+        Some(formula2) => format!("<formula2>{}</formula2>", escape_xml(&formula2)),
+        None => "".to_string(),
+    };
+    format!(
+        "<dataValidation{attributes} sqref=\"{}\"><formula1>{}</formula1>{formula2}</dataValidation>",
+        escape_xml(&data_validation.range),
+        escape_xml(&formula1)
+    )
+}
+
+/// The `<dataValidations>` section of the worksheet or an empty string if there are none
+pub(crate) fn get_data_validations_xml(data_validations: &[DataValidation]) -> String {
+    if data_validations.is_empty() {
+        return "".to_string();
+    }
+    let data_validations_str: Vec<String> = data_validations
+        .iter()
+        .map(get_data_validation_xml)
+        .collect();
+    format!(
+        "<dataValidations count=\"{}\">{}</dataValidations>",
+        data_validations.len(),
+        data_validations_str.join("")
+    )
+}
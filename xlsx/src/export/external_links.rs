@@ -0,0 +1,86 @@
+//! A link to another workbook is stored in its own part, `xl/externalLinks/externalLinkN.xml`,
+//! with the names of the sheets and the cached values of the referenced cells:
+//!
+//! ```xml
+//! <externalLink>
+//!   <externalBook r:id="rId1">
+//!     <sheetNames><sheetName val="Summary"/></sheetNames>
+//!     <sheetDataSet>
+//!       <sheetData sheetId="0">
+//!         <row r="4"><cell r="C4"><v>100</v></cell></row>
+//!       </sheetData>
+//!     </sheetDataSet>
+//!   </externalBook>
+//! </externalLink>
+//! ```
+//!
+//! The path of the linked workbook is the target of the relationship `rId1` of the part.
+
+use ironcalc_base::expressions::utils::number_to_column;
+use ironcalc_base::types::{ExternalLink, ExternalValue};
+
+use super::escape::escape_xml;
+use super::xml_constants::{XML_DECLARATION, XML_EXTERNAL_LINK_PATH};
+
+pub(crate) fn get_external_link_xml(link: &ExternalLink) -> String {
+    let mut sheet_names = Vec::new();
+    let mut sheet_data_set = Vec::new();
+    for (sheet_id, sheet) in link.sheets.iter().enumerate() {
+        sheet_names.push(format!("<sheetName val=\"{}\"/>", escape_xml(&sheet.name)));
+        let mut rows: Vec<&i32> = sheet.sheet_data.keys().collect();
+        rows.sort();
+        let mut rows_str = Vec::new();
+        for row in rows {
+            let data_row = &sheet.sheet_data[row];
+            let mut columns: Vec<&i32> = data_row.keys().collect();
+            columns.sort();
+            let mut cells_str = Vec::new();
+            for column in columns {
+                let cell_name = format!("{}{row}", number_to_column(*column).unwrap_or_default());
+                let cell = match &data_row[column] {
+                    ExternalValue::Number(value) => {
+                        format!("<cell r=\"{cell_name}\"><v>{value}</v></cell>")
+                    }
+                    ExternalValue::Text(value) => format!(
+                        "<cell r=\"{cell_name}\" t=\"str\"><v>{}</v></cell>",
+                        escape_xml(value)
+                    ),
+                    ExternalValue::Boolean(value) => format!(
+                        "<cell r=\"{cell_name}\" t=\"b\"><v>{}</v></cell>",
+                        if *value { 1 } else { 0 }
+                    ),
+                    ExternalValue::Error(error) => {
+                        format!("<cell r=\"{cell_name}\" t=\"e\"><v>{error}</v></cell>")
+                    }
+                };
+                cells_str.push(cell);
+            }
+            rows_str.push(format!("<row r=\"{row}\">{}</row>", cells_str.join("")));
+        }
+        sheet_data_set.push(format!(
+            "<sheetData sheetId=\"{sheet_id}\">{}</sheetData>",
+            rows_str.join("")
+        ));
+    }
+    format!(
+        "{XML_DECLARATION}\n\
+        <externalLink xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+        <externalBook r:id=\"rId1\">\
+        <sheetNames>{}</sheetNames>\
+        <sheetDataSet>{}</sheetDataSet>\
+        </externalBook>\
+        </externalLink>",
+        sheet_names.join(""),
+        sheet_data_set.join("")
+    )
+}
+
+pub(crate) fn get_external_link_xml_rels(link: &ExternalLink) -> String {
+    format!(
+        "{XML_DECLARATION}\n\
+        <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+        <Relationship Id=\"rId1\" Type=\"{XML_EXTERNAL_LINK_PATH}\" Target=\"{}\" TargetMode=\"External\"/>\
+        </Relationships>",
+        escape_xml(&link.target)
+    )
+}
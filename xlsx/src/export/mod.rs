@@ -1,13 +1,21 @@
 #![allow(clippy::unwrap_used)]
 
 mod _rels;
+mod auto_filter;
+mod comments;
 mod conditional_formatting;
+mod data_validation;
 mod doc_props;
 mod dxfs_styles;
 mod escape;
+mod external_links;
+mod page_setup;
+mod protection;
 mod shared_strings;
+mod sort;
 mod styles;
 mod styles_util;
+mod tables;
 mod theme;
 mod workbook;
 mod workbook_xml_rels;
@@ -37,6 +45,7 @@ fn get_content_types_xml(workbook: &Workbook) -> String {
         r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#.to_string(),
         r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#.to_string(),
         r#"<Default Extension="xml" ContentType="application/xml"/>"#.to_string(),
+        r#"<Default Extension="vml" ContentType="application/vnd.openxmlformats-officedocument.vmlDrawing"/>"#.to_string(),
         r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#.to_string(),
     ];
     for worksheet in 0..workbook.worksheets.len() {
@@ -46,6 +55,39 @@ fn get_content_types_xml(workbook: &Workbook) -> String {
         );
         content.push(sheet);
     }
+    for index in 0..workbook.external_links.len() {
+        content.push(format!(
+            r#"<Override PartName="/xl/externalLinks/externalLink{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.externalLink+xml"/>"#,
+            index + 1
+        ));
+    }
+    for index in 0..workbook.tables.len() {
+        content.push(format!(
+            r#"<Override PartName="/xl/tables/table{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.table+xml"/>"#,
+            index + 1
+        ));
+    }
+    for (worksheet, comment_id) in workbook
+        .worksheets
+        .iter()
+        .zip(comments::get_comment_ids(workbook))
+    {
+        if let Some(id) = comment_id {
+            content.push(format!(
+                r#"<Override PartName="/xl/comments{id}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml"/>"#
+            ));
+            if comments::has_threaded_comments(worksheet) {
+                content.push(format!(
+                    r#"<Override PartName="/xl/threadedComments/threadedComment{id}.xml" ContentType="application/vnd.ms-excel.threadedcomments+xml"/>"#
+                ));
+            }
+        }
+    }
+    if comments::get_persons_xml(workbook).is_some() {
+        content.push(
+            r#"<Override PartName="/xl/persons/person.xml" ContentType="application/vnd.ms-excel.person+xml"/>"#.to_string(),
+        );
+    }
     content.push(
         r#"<Override PartName="/xl/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#.to_string(),
     );
@@ -118,11 +160,27 @@ pub fn save_xlsx_to_writer<W: Write + Seek>(model: &Model, writer: W) -> Result<
     zip.start_file("xl/_rels/workbook.xml.rels", options)?;
     zip.write_all(workbook_xml_rels::get_workbook_xml_rels(workbook).as_bytes())?;
 
+    // The numbers of the table parts of each sheet
+    let tables_by_sheet = tables::get_tables_by_sheet(workbook);
+    let mut table_ids = Vec::new();
+    let mut table_count = 0;
+    for sheet_tables in &tables_by_sheet {
+        table_ids.push((table_count + 1..=table_count + sheet_tables.len()).collect::<Vec<_>>());
+        table_count += sheet_tables.len();
+    }
+
+    // The numbers of the comment parts of each sheet
+    let comment_ids = comments::get_comment_ids(workbook);
+
     zip.add_directory("xl/worksheets", options)?;
-    // sheet rels parts (one per sheet with external hyperlinks)
+    // sheet rels parts (one per sheet with external hyperlinks, tables or comments)
     let mut has_worksheet_rels = false;
     for (sheet_index, worksheet) in workbook.worksheets.iter().enumerate() {
-        if let Some(rels_xml) = worksheets::get_worksheet_xml_rels(worksheet) {
+        if let Some(rels_xml) = worksheets::get_worksheet_xml_rels(
+            worksheet,
+            &table_ids[sheet_index],
+            comment_ids[sheet_index],
+        ) {
             if !has_worksheet_rels {
                 zip.add_directory("xl/worksheets/_rels", options)?;
                 has_worksheet_rels = true;
@@ -154,11 +212,70 @@ pub fn save_xlsx_to_writer<W: Write + Seek>(model: &Model, writer: W) -> Result<
                 &model.parsed_formulas[sheet_index],
                 sheet_dimension_str,
                 is_sheet_selected,
+                &table_ids[sheet_index],
+                &model.workbook.styles.dxfs,
             )
             .as_bytes(),
         )?;
     }
 
+    if table_count > 0 {
+        zip.add_directory("xl/tables", options)?;
+        for (sheet_tables, ids) in tables_by_sheet.iter().zip(&table_ids) {
+            for (table, id) in sheet_tables.iter().zip(ids) {
+                zip.start_file(format!("xl/tables/table{id}.xml"), options)?;
+                zip.write_all(
+                    tables::get_table_xml(table, *id, &model.workbook.styles.dxfs).as_bytes(),
+                )?;
+            }
+        }
+    }
+
+    if comment_ids.iter().any(|id| id.is_some()) {
+        zip.add_directory("xl/drawings", options)?;
+        let mut has_threaded_comments = false;
+        for (worksheet, comment_id) in workbook.worksheets.iter().zip(&comment_ids) {
+            let Some(id) = comment_id else {
+                continue;
+            };
+            zip.start_file(format!("xl/comments{id}.xml"), options)?;
+            zip.write_all(comments::get_comments_xml(worksheet).as_bytes())?;
+            zip.start_file(format!("xl/drawings/vmlDrawing{id}.vml"), options)?;
+            zip.write_all(comments::get_vml_drawing_xml(worksheet, *id).as_bytes())?;
+            if comments::has_threaded_comments(worksheet) {
+                if !has_threaded_comments {
+                    zip.add_directory("xl/threadedComments", options)?;
+                    has_threaded_comments = true;
+                }
+                zip.start_file(
+                    format!("xl/threadedComments/threadedComment{id}.xml"),
+                    options,
+                )?;
+                zip.write_all(comments::get_threaded_comments_xml(worksheet).as_bytes())?;
+            }
+        }
+        if let Some(persons_xml) = comments::get_persons_xml(workbook) {
+            zip.add_directory("xl/persons", options)?;
+            zip.start_file("xl/persons/person.xml", options)?;
+            zip.write_all(persons_xml.as_bytes())?;
+        }
+    }
+
+    if !workbook.external_links.is_empty() {
+        zip.add_directory("xl/externalLinks", options)?;
+        zip.add_directory("xl/externalLinks/_rels", options)?;
+        for (index, link) in workbook.external_links.iter().enumerate() {
+            let id = index + 1;
+            zip.start_file(format!("xl/externalLinks/externalLink{id}.xml"), options)?;
+            zip.write_all(external_links::get_external_link_xml(link).as_bytes())?;
+            zip.start_file(
+                format!("xl/externalLinks/_rels/externalLink{id}.xml.rels"),
+                options,
+            )?;
+            zip.write_all(external_links::get_external_link_xml_rels(link).as_bytes())?;
+        }
+    }
+
     let writer = zip.finish()?;
     Ok(writer)
 }
@@ -0,0 +1,161 @@
+use ironcalc_base::{
+    expressions::utils::{number_to_column, quote_name},
+    page_setup_types::{PageOrientation, PageSetup},
+};
+
+use super::escape::escape_xml;
+
+fn get_breaks_xml(tag_name: &str, breaks: &[i32], max: i32) -> String {
+    if breaks.is_empty() {
+        return "".to_string();
+    }
+    let count = breaks.len();
+    let breaks: String = breaks
+        .iter()
+        .map(|id| format!("<brk id=\"{id}\" max=\"{max}\" man=\"1\"/>"))
+        .collect();
+    format!("<{tag_name} count=\"{count}\" manualBreakCount=\"{count}\">{breaks}</{tag_name}>")
+}
+
+/// The print settings of the worksheet, from `<printOptions>` to `<colBreaks>`
+/// ```xml
+/// <printOptions horizontalCentered="1" gridLines="1"/>
+/// <pageMargins left="0.25" right="0.25" top="0.75" bottom="0.75" header="0.3" footer="0.3"/>
+/// <pageSetup paperSize="9" scale="80" orientation="landscape"/>
+/// <headerFooter><oddFooter>&amp;CPage &amp;P of &amp;N</oddFooter></headerFooter>
+/// <rowBreaks count="1" manualBreakCount="1"><brk id="20" max="16383" man="1"/></rowBreaks>
+/// ```
+pub(crate) fn get_page_setup_xml(page_setup: &PageSetup) -> String {
+    let mut xml = String::new();
+
+    let mut print_options = String::new();
+    for (value, name) in [
+        (page_setup.horizontal_centered, "horizontalCentered"),
+        (page_setup.vertical_centered, "verticalCentered"),
+        (page_setup.print_headings, "headings"),
+        (page_setup.print_grid_lines, "gridLines"),
+    ] {
+        if value {
+            print_options.push_str(&format!(" {name}=\"1\""));
+        }
+    }
+    if !print_options.is_empty() {
+        xml.push_str(&format!("<printOptions{print_options}/>"));
+    }
+
+    let margins = &page_setup.margins;
+    xml.push_str(&format!(
+        "<pageMargins left=\"{}\" right=\"{}\" top=\"{}\" bottom=\"{}\" header=\"{}\" footer=\"{}\"/>",
+        margins.left, margins.right, margins.top, margins.bottom, margins.header, margins.footer
+    ));
+
+    let default = PageSetup::default();
+    let mut attributes = String::new();
+    if page_setup.paper_size != default.paper_size {
+        attributes.push_str(&format!(" paperSize=\"{}\"", page_setup.paper_size));
+    }
+    if page_setup.scale != default.scale {
+        attributes.push_str(&format!(" scale=\"{}\"", page_setup.scale));
+    }
+    if let Some(first_page_number) = page_setup.first_page_number {
+        attributes.push_str(&format!(" firstPageNumber=\"{first_page_number}\""));
+    }
+    if page_setup.fit_to_width != default.fit_to_width {
+        attributes.push_str(&format!(" fitToWidth=\"{}\"", page_setup.fit_to_width));
+    }
+    if page_setup.fit_to_height != default.fit_to_height {
+        attributes.push_str(&format!(" fitToHeight=\"{}\"", page_setup.fit_to_height));
+    }
+    match page_setup.orientation {
+        PageOrientation::Default => {}
+        PageOrientation::Portrait => attributes.push_str(" orientation=\"portrait\""),
+        PageOrientation::Landscape => attributes.push_str(" orientation=\"landscape\""),
+    }
+    if page_setup.first_page_number.is_some() {
+        attributes.push_str(" useFirstPageNumber=\"1\"");
+    }
+    if !attributes.is_empty() {
+        xml.push_str(&format!("<pageSetup{attributes}/>"));
+    }
+
+    let header_footer = &page_setup.header_footer;
+    if *header_footer != Default::default() {
+        let mut attributes = String::new();
+        if header_footer.different_odd_even {
+            attributes.push_str(" differentOddEven=\"1\"");
+        }
+        if header_footer.different_first {
+            attributes.push_str(" differentFirst=\"1\"");
+        }
+        let mut texts = String::new();
+        for (text, tag_name) in [
+            (&header_footer.odd_header, "oddHeader"),
+            (&header_footer.odd_footer, "oddFooter"),
+            (&header_footer.even_header, "evenHeader"),
+            (&header_footer.even_footer, "evenFooter"),
+            (&header_footer.first_header, "firstHeader"),
+            (&header_footer.first_footer, "firstFooter"),
+        ] {
+            if !text.is_empty() {
+                texts.push_str(&format!("<{tag_name}>{}</{tag_name}>", escape_xml(text)));
+            }
+        }
+        xml.push_str(&format!("<headerFooter{attributes}>{texts}</headerFooter>"));
+    }
+
+    // The maximum of a break is the last column (or row) it spans, zero based
+    xml.push_str(&get_breaks_xml("rowBreaks", &page_setup.row_breaks, 16383));
+    xml.push_str(&get_breaks_xml(
+        "colBreaks",
+        &page_setup.column_breaks,
+        1048575,
+    ));
+    xml
+}
+
+/// The formulas of the defined names `_xlnm.Print_Area` and `_xlnm.Print_Titles` of the sheet,
+/// if it has a print area or print titles
+pub(crate) fn get_print_names(
+    sheet_name: &str,
+    page_setup: &PageSetup,
+) -> (Option<String>, Option<String>) {
+    let sheet_name = quote_name(sheet_name);
+    let print_area = if page_setup.print_area.is_empty() {
+        None
+    } else {
+        let ranges: Vec<String> = page_setup
+            .print_area
+            .iter()
+            .map(|range| {
+                let absolute = range
+                    .split(':')
+                    .map(|reference| {
+                        let index = reference
+                            .find(|c: char| c.is_ascii_digit())
+                            .unwrap_or(reference.len());
+                        let (column, row) = reference.split_at(index);
+                        format!("${column}${row}")
+                    })
+                    .collect::<Vec<String>>()
+                    .join(":");
+                format!("{sheet_name}!{absolute}")
+            })
+            .collect();
+        Some(ranges.join(","))
+    };
+    let mut titles = Vec::new();
+    if let Some((start, end)) = page_setup.print_title_columns {
+        if let (Some(start), Some(end)) = (number_to_column(start), number_to_column(end)) {
+            titles.push(format!("{sheet_name}!${start}:${end}"));
+        }
+    }
+    if let Some((start, end)) = page_setup.print_title_rows {
+        titles.push(format!("{sheet_name}!${start}:${end}"));
+    }
+    let print_titles = if titles.is_empty() {
+        None
+    } else {
+        Some(titles.join(","))
+    };
+    (print_area, print_titles)
+}
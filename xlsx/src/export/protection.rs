@@ -0,0 +1,102 @@
+use ironcalc_base::types::{PasswordHash, SheetProtection, WorkbookProtection};
+
+use super::escape::escape_xml;
+
+// The names of the password attributes: legacy password, algorithm, hash, salt and spin count
+type PasswordAttributes = [&'static str; 5];
+
+const SHEET_PASSWORD: PasswordAttributes = [
+    "password",
+    "algorithmName",
+    "hashValue",
+    "saltValue",
+    "spinCount",
+];
+
+const WORKBOOK_PASSWORD: PasswordAttributes = [
+    "workbookPassword",
+    "workbookAlgorithmName",
+    "workbookHashValue",
+    "workbookSaltValue",
+    "workbookSpinCount",
+];
+
+fn get_password_attributes(
+    password: &Option<PasswordHash>,
+    attributes: PasswordAttributes,
+) -> String {
+    let [password_name, algorithm_name_name, hash_value_name, salt_value_name, spin_count_name] =
+        attributes;
+    match password {
+        None => "".to_string(),
+        Some(PasswordHash::Legacy { hash }) => format!(" {password_name}=\"{}\"", escape_xml(hash)),
+        Some(PasswordHash::Salted {
+            algorithm_name,
+            hash_value,
+            salt_value,
+            spin_count,
+        }) => format!(
+            " {algorithm_name_name}=\"{}\" {hash_value_name}=\"{}\" {salt_value_name}=\"{}\" {spin_count_name}=\"{spin_count}\"",
+            escape_xml(algorithm_name),
+            escape_xml(hash_value),
+            escape_xml(salt_value)
+        ),
+    }
+}
+
+// The operation attributes are true when the operation is _not_ allowed.
+// We only write the ones that differ from the default.
+fn get_operation_attribute(name: &str, allowed: bool, default: bool) -> String {
+    if allowed == default {
+        "".to_string()
+    } else if allowed {
+        format!(" {name}=\"0\"")
+    } else {
+        format!(" {name}=\"1\"")
+    }
+}
+
+/// The `<sheetProtection>` element of a worksheet or an empty string if the sheet is not protected
+pub(crate) fn get_sheet_protection_xml(protection: &Option<SheetProtection>) -> String {
+    let Some(protection) = protection else {
+        return "".to_string();
+    };
+    let mut attributes = get_password_attributes(&protection.password, SHEET_PASSWORD);
+    attributes.push_str(" sheet=\"1\" objects=\"1\" scenarios=\"1\"");
+    for (name, allowed, default) in [
+        ("selectLockedCells", protection.select_locked_cells, true),
+        (
+            "selectUnlockedCells",
+            protection.select_unlocked_cells,
+            true,
+        ),
+        ("formatCells", protection.format_cells, false),
+        ("formatColumns", protection.format_columns, false),
+        ("formatRows", protection.format_rows, false),
+        ("insertColumns", protection.insert_columns, false),
+        ("insertRows", protection.insert_rows, false),
+        ("insertHyperlinks", protection.insert_hyperlinks, false),
+        ("deleteColumns", protection.delete_columns, false),
+        ("deleteRows", protection.delete_rows, false),
+        ("sort", protection.sort, false),
+        ("autoFilter", protection.auto_filter, false),
+    ] {
+        attributes.push_str(&get_operation_attribute(name, allowed, default));
+    }
+    format!("<sheetProtection{attributes}/>")
+}
+
+/// The `<workbookProtection>` element or an empty string if the workbook is not protected
+pub(crate) fn get_workbook_protection_xml(protection: &Option<WorkbookProtection>) -> String {
+    let Some(protection) = protection else {
+        return "".to_string();
+    };
+    let mut attributes = get_password_attributes(&protection.password, WORKBOOK_PASSWORD);
+    if protection.lock_structure {
+        attributes.push_str(" lockStructure=\"1\"");
+    }
+    if protection.lock_windows {
+        attributes.push_str(" lockWindows=\"1\"");
+    }
+    format!("<workbookProtection{attributes}/>")
+}
@@ -1,13 +1,78 @@
-use ironcalc_base::types::Workbook;
+use ironcalc_base::{
+    rich_text_types::{RichTextFont, RichTextRun},
+    types::{Color, Workbook},
+};
 
 use super::{escape::escape_xml, xml_constants::XML_DECLARATION};
 
+fn get_bool_xml(tag_name: &str, value: Option<bool>) -> String {
+    match value {
+        Some(true) => format!("<{tag_name}/>"),
+        Some(false) => format!("<{tag_name} val=\"0\"/>"),
+        None => "".to_string(),
+    }
+}
+
+// The elements of <rPr> must follow the order of the schema (CT_RPrElt)
+fn get_run_font_xml(font: &RichTextFont) -> String {
+    let name = match &font.name {
+        Some(name) => format!("<rFont val=\"{}\"/>", escape_xml(name)),
+        None => "".to_string(),
+    };
+    let bold = get_bool_xml("b", font.b);
+    let italic = get_bool_xml("i", font.i);
+    let strike = get_bool_xml("strike", font.strike);
+    let color = match &font.color {
+        Color::Rgb(s) => format!("<color rgb=\"FF{}\"/>", s.trim_start_matches('#')),
+        Color::Theme(idx, tint) => {
+            if *tint == 0.0 {
+                format!("<color theme=\"{idx}\"/>")
+            } else {
+                format!("<color theme=\"{idx}\" tint=\"{:.16}\"/>", tint)
+            }
+        }
+        Color::None => "".to_string(),
+    };
+    let size = match font.sz {
+        Some(sz) => format!("<sz val=\"{sz}\"/>"),
+        None => "".to_string(),
+    };
+    let underline = match font.u {
+        Some(true) => "<u/>",
+        Some(false) => "<u val=\"none\"/>",
+        None => "",
+    };
+    format!("<rPr>{name}{bold}{italic}{strike}{color}{size}{underline}</rPr>")
+}
+
+// <si><r><t>Hello</t></r><r><rPr><b/></rPr><t xml:space="preserve"> World</t></r></si>
+fn get_rich_text_xml(runs: &[RichTextRun]) -> String {
+    let runs: String = runs
+        .iter()
+        .map(|run| {
+            let font = if run.font.is_empty() {
+                "".to_string()
+            } else {
+                get_run_font_xml(&run.font)
+            };
+            format!(
+                "<r>{font}<t xml:space=\"preserve\">{}</t></r>",
+                escape_xml(&run.text)
+            )
+        })
+        .collect();
+    format!("<si>{runs}</si>")
+}
+
 pub(crate) fn get_shared_strings_xml(model: &Workbook) -> String {
     let mut shared_strings: Vec<String> = vec![];
     let count = &model.shared_strings.len();
     let unique_count = &model.shared_strings.len();
-    for shared_string in &model.shared_strings {
-        shared_strings.push(format!("<si><t>{}</t></si>", escape_xml(shared_string)));
+    for (index, shared_string) in model.shared_strings.iter().enumerate() {
+        match model.rich_text.get(&(index as i32)) {
+            Some(runs) => shared_strings.push(get_rich_text_xml(runs)),
+            None => shared_strings.push(format!("<si><t>{}</t></si>", escape_xml(shared_string))),
+        }
     }
     format!("{}\n\
       <sst xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" count=\"{count}\" uniqueCount=\"{unique_count}\">\
@@ -0,0 +1,79 @@
+use ironcalc_base::{
+    expressions::utils::{number_to_column, parse_reference_a1},
+    sort_types::{SortKey, SortOn, SortState},
+    types::Dxf,
+};
+
+use super::escape::escape_xml;
+
+// The differential format with the color of the key
+fn get_dxf_id(key: &SortKey, dxfs: &[Dxf]) -> Option<usize> {
+    match &key.sort_on {
+        SortOn::CellColor(color) => dxfs
+            .iter()
+            .position(|dxf| dxf.fill.as_ref().map(|fill| &fill.color) == Some(color)),
+        SortOn::FontColor(color) => dxfs
+            .iter()
+            .position(|dxf| dxf.font.as_ref().map(|font| &font.color) == Some(color)),
+        SortOn::Value | SortOn::CustomList(_) => None,
+    }
+}
+
+fn get_sort_condition_xml(key: &SortKey, reference: &str, dxfs: &[Dxf]) -> String {
+    let descending = if key.descending {
+        " descending=\"1\""
+    } else {
+        ""
+    };
+    let sort_by = match (&key.sort_on, get_dxf_id(key, dxfs)) {
+        (SortOn::CellColor(_), Some(dxf_id)) => format!(" sortBy=\"cellColor\" dxfId=\"{dxf_id}\""),
+        (SortOn::FontColor(_), Some(dxf_id)) => format!(" sortBy=\"fontColor\" dxfId=\"{dxf_id}\""),
+        (SortOn::CustomList(list), _) => format!(" customList=\"{}\"", escape_xml(&list.join(","))),
+        _ => "".to_string(),
+    };
+    format!("<sortCondition{descending}{sort_by} ref=\"{reference}\"/>")
+}
+
+/// The `<sortState>` of the worksheet or an empty string if the sheet has not been sorted
+/// ```xml
+/// <sortState ref="A2:C6">
+///   <sortCondition descending="1" ref="B2:B6"/>
+///   <sortCondition sortBy="cellColor" dxfId="0" ref="A2:A6"/>
+/// </sortState>
+/// ```
+pub(crate) fn get_sort_state_xml(sort_state: &Option<SortState>, dxfs: &[Dxf]) -> String {
+    let Some(sort_state) = sort_state else {
+        return "".to_string();
+    };
+    let Some((start, end)) = sort_state.range.split_once(':') else {
+        return "".to_string();
+    };
+    let (Some(start), Some(end)) = (parse_reference_a1(start), parse_reference_a1(end)) else {
+        return "".to_string();
+    };
+    let mut conditions = Vec::new();
+    for key in &sort_state.keys {
+        let reference = if sort_state.left_to_right {
+            match (number_to_column(start.column), number_to_column(end.column)) {
+                (Some(first), Some(last)) => format!("{first}{}:{last}{}", key.index, key.index),
+                _ => continue,
+            }
+        } else {
+            match number_to_column(key.index) {
+                Some(column) => format!("{column}{}:{column}{}", start.row, end.row),
+                None => continue,
+            }
+        };
+        conditions.push(get_sort_condition_xml(key, &reference, dxfs));
+    }
+    let column_sort = if sort_state.left_to_right {
+        " columnSort=\"1\""
+    } else {
+        ""
+    };
+    format!(
+        "<sortState{column_sort} ref=\"{}\">{}</sortState>",
+        sort_state.range,
+        conditions.join("")
+    )
+}
@@ -2,7 +2,7 @@ use ironcalc_base::types::{Color, Styles, Workbook};
 
 use crate::export::{
     dxfs_styles::get_dxfs_xml,
-    styles_util::{get_alignment, get_border_xml, get_fill_xml},
+    styles_util::{get_alignment, get_border_xml, get_fill_xml, get_protection},
 };
 
 use super::{escape::escape_xml, xml_constants::XML_DECLARATION};
@@ -184,6 +184,11 @@ fn get_cell_xfs_xml(styles: &Styles) -> String {
         } else {
             ""
         };
+        let apply_protection_str = if cell_xf.apply_protection {
+            r#" applyProtection="1""#
+        } else {
+            ""
+        };
         let apply_font_str = if cell_xf.apply_font {
             r#" applyFont="1""#
         } else {
@@ -212,16 +217,26 @@ fn get_cell_xfs_xml(styles: &Styles) -> String {
                 numFmtId=\"{num_fmt_id}\"\
                 {quote_prefix_str}\
                 {apply_alignment_str}\
+                {apply_protection_str}\
                 {apply_font_str}\
                 {apply_fill_str}\
                 {apply_number_format_str}\
                 {apply_border_str}"
         );
-        if let Some(alignment) = &cell_xf.alignment {
-            let alignment = get_alignment(alignment);
-            cell_xfs_str.push(format!("<xf {properties}>{alignment}</xf>"));
-        } else {
+        let alignment = cell_xf
+            .alignment
+            .as_ref()
+            .map(get_alignment)
+            .unwrap_or_default();
+        let protection = cell_xf
+            .protection
+            .as_ref()
+            .map(get_protection)
+            .unwrap_or_default();
+        if alignment.is_empty() && protection.is_empty() {
             cell_xfs_str.push(format!("<xf {properties}/>"));
+        } else {
+            cell_xfs_str.push(format!("<xf {properties}>{alignment}{protection}</xf>"));
         }
     }
     let style_count = cell_xfs.len();
@@ -1,5 +1,6 @@
 use ironcalc_base::types::{
-    Alignment, Border, BorderItem, Color, Fill, HorizontalAlignment, VerticalAlignment,
+    Alignment, Border, BorderItem, Color, Fill, GradientFill, GradientType, HorizontalAlignment,
+    PatternType, Protection, ReadingOrder, VerticalAlignment,
 };
 
 pub(crate) fn get_color_xml(color: &Color, name: &str) -> String {
@@ -35,7 +36,39 @@ pub(crate) fn get_alignment(alignment: &Alignment) -> String {
     } else {
         "".to_string()
     };
-    format!("<alignment{wrap_text}{horizontal}{vertical}/>")
+    let mut extended = String::new();
+    if alignment.text_rotation != 0 {
+        extended.push_str(&format!(" textRotation=\"{}\"", alignment.text_rotation));
+    }
+    if alignment.indent != 0 {
+        extended.push_str(&format!(" indent=\"{}\"", alignment.indent));
+    }
+    if alignment.shrink_to_fit {
+        extended.push_str(" shrinkToFit=\"1\"");
+    }
+    match alignment.reading_order {
+        ReadingOrder::ContextDependent => {}
+        ReadingOrder::LeftToRight => extended.push_str(" readingOrder=\"1\""),
+        ReadingOrder::RightToLeft => extended.push_str(" readingOrder=\"2\""),
+    }
+    if alignment.justify_last_line {
+        extended.push_str(" justifyLastLine=\"1\"");
+    }
+    format!("<alignment{wrap_text}{horizontal}{vertical}{extended}/>")
+}
+
+pub(crate) fn get_protection(protection: &Protection) -> String {
+    let locked = if protection.locked {
+        ""
+    } else {
+        " locked=\"0\""
+    };
+    let hidden = if protection.hidden {
+        " hidden=\"1\""
+    } else {
+        ""
+    };
+    format!("<protection{locked}{hidden}/>")
 }
 
 fn get_border_xml_inner(border: &Option<BorderItem>, name: &str) -> String {
@@ -55,8 +88,58 @@ pub(crate) fn get_border_xml(border: &Border) -> String {
     format!("<border>{left}{right}{top}{bottom}{diagonal}</border>")
 }
 
+// <gradientFill degree="90"><stop position="0"><color theme="0"/></stop>...</gradientFill>
+fn get_gradient_fill_xml(gradient: &GradientFill) -> String {
+    let mut attributes = String::new();
+    match gradient.r#type {
+        GradientType::Linear => {
+            if gradient.degree != 0.0 {
+                attributes.push_str(&format!(" degree=\"{}\"", gradient.degree));
+            }
+        }
+        GradientType::Path => {
+            attributes.push_str(" type=\"path\"");
+            for (value, name) in [
+                (gradient.left, "left"),
+                (gradient.right, "right"),
+                (gradient.top, "top"),
+                (gradient.bottom, "bottom"),
+            ] {
+                if value != 0.0 {
+                    attributes.push_str(&format!(" {name}=\"{value}\""));
+                }
+            }
+        }
+    }
+    let stops: String = gradient
+        .stops
+        .iter()
+        .map(|stop| {
+            format!(
+                "<stop position=\"{}\">{}</stop>",
+                stop.position,
+                get_color_xml(&stop.color, "color")
+            )
+        })
+        .collect();
+    format!("<fill><gradientFill{attributes}>{stops}</gradientFill></fill>")
+}
+
+fn get_pattern_fill_xml(fill: &Fill) -> String {
+    let fg_color = get_color_xml(&fill.color, "fgColor");
+    let bg_color = get_color_xml(&fill.bg_color, "bgColor");
+    format!(
+        "<fill><patternFill patternType=\"{}\">{fg_color}{bg_color}</patternFill></fill>",
+        fill.pattern
+    )
+}
+
 pub(crate) fn get_fill_xml(fill: &Fill) -> String {
-    if fill.color.is_some() {
+    if let Some(gradient) = &fill.gradient {
+        get_gradient_fill_xml(gradient)
+    } else if fill.pattern != PatternType::Solid {
+        get_pattern_fill_xml(fill)
+    } else if fill.color.is_some() {
         let fg_color = get_color_xml(&fill.color, "fgColor");
         let bg_color = "<bgColor indexed=\"64\"/>".to_string();
         format!(
@@ -67,7 +150,14 @@ pub(crate) fn get_fill_xml(fill: &Fill) -> String {
     }
 }
 
+// In differential formats the color of a solid fill is the background color
 pub(crate) fn get_dxf_fill_xml(fill: &Fill) -> String {
+    if let Some(gradient) = &fill.gradient {
+        return get_gradient_fill_xml(gradient);
+    }
+    if fill.pattern != PatternType::Solid {
+        return get_pattern_fill_xml(fill);
+    }
     let bg_color = get_color_xml(&fill.color, "bgColor");
     format!("<fill><patternFill>{bg_color}</patternFill></fill>")
 }
@@ -0,0 +1,191 @@
+//! Each table is stored in its own part, `xl/tables/tableN.xml`, referenced from the
+//! `<tableParts>` section of its worksheet through the sheet rels:
+//!
+//! ```xml
+//! <table id="1" name="Sales" displayName="Sales" ref="A1:D5" totalsRowCount="1">
+//!   <autoFilter ref="A1:D4">
+//!     <filterColumn colId="1"><top10 val="2"/></filterColumn>
+//!   </autoFilter>
+//!   <tableColumns count="4">
+//!     <tableColumn id="1" name="Region" totalsRowLabel="Total"/>
+//!     <tableColumn id="2" name="Amount" totalsRowFunction="sum"/>
+//!     <tableColumn id="3" name="Notes"/>
+//!     <tableColumn id="4" name="Tax" totalsRowFunction="custom">
+//!       <calculatedColumnFormula>Sales[[#This Row],[Amount]]*0.2</calculatedColumnFormula>
+//!       <totalsRowFormula>ROUND(SUM(Sales[Tax]),0)</totalsRowFormula>
+//!     </tableColumn>
+//!   </tableColumns>
+//!   <tableStyleInfo name="TableStyleMedium2" showFirstColumn="0" showLastColumn="0" showRowStripes="1" showColumnStripes="0"/>
+//! </table>
+//! ```
+//!
+//! The numbers of the parts are global to the workbook, in the order of the worksheets.
+
+use ironcalc_base::expressions::utils::{number_to_column, parse_reference_a1};
+use ironcalc_base::types::{Dxf, Table, Workbook};
+
+use super::auto_filter::get_filter_column_xml;
+use super::escape::escape_xml;
+use super::xml_constants::XML_DECLARATION;
+
+/// The tables of each worksheet, sorted by name.
+/// The number of the part of a table is its position in the flattened list plus one.
+pub(crate) fn get_tables_by_sheet(workbook: &Workbook) -> Vec<Vec<&Table>> {
+    workbook
+        .worksheets
+        .iter()
+        .map(|worksheet| {
+            let mut tables: Vec<&Table> = workbook
+                .tables
+                .values()
+                .filter(|table| table.sheet_name == worksheet.name)
+                .collect();
+            tables.sort_by(|a, b| a.name.cmp(&b.name));
+            tables
+        })
+        .collect()
+}
+
+fn get_dxf_id_attribute(name: &str, dxf_id: Option<u32>) -> String {
+    match dxf_id {
+        Some(id) => format!(" {name}=\"{id}\""),
+        None => "".to_string(),
+    }
+}
+
+fn get_bool_attribute(name: &str, value: bool) -> String {
+    format!(" {name}=\"{}\"", if value { 1 } else { 0 })
+}
+
+// The range of the table without the totals row
+fn get_auto_filter_reference(table: &Table) -> String {
+    if table.totals_row_count == 0 {
+        return table.reference.clone();
+    }
+    let (start, end) = match table.reference.split_once(':') {
+        Some(parts) => parts,
+        None => return table.reference.clone(),
+    };
+    match parse_reference_a1(end) {
+        Some(end) => format!(
+            "{start}:{}{}",
+            number_to_column(end.column).unwrap_or_default(),
+            end.row - table.totals_row_count as i32
+        ),
+        None => table.reference.clone(),
+    }
+}
+
+pub(crate) fn get_table_xml(table: &Table, id: usize, dxfs: &[Dxf]) -> String {
+    let name = escape_xml(&table.name);
+    let display_name = escape_xml(&table.display_name);
+    let reference = &table.reference;
+    let mut attributes = String::new();
+    if table.header_row_count != 1 {
+        attributes.push_str(&format!(" headerRowCount=\"{}\"", table.header_row_count));
+    }
+    if table.totals_row_count > 0 {
+        attributes.push_str(&format!(" totalsRowCount=\"{}\"", table.totals_row_count));
+    } else {
+        attributes.push_str(" totalsRowShown=\"0\"");
+    }
+    attributes.push_str(&get_dxf_id_attribute(
+        "headerRowDxfId",
+        table.header_row_dxf_id,
+    ));
+    attributes.push_str(&get_dxf_id_attribute("dataDxfId", table.data_dxf_id));
+    attributes.push_str(&get_dxf_id_attribute(
+        "totalsRowDxfId",
+        table.totals_row_dxf_id,
+    ));
+
+    // Tables with headers show the filter buttons
+    let filter_columns: String = table
+        .columns
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let filter = column.filter.as_ref()?;
+            Some(get_filter_column_xml(index as i32, filter, dxfs))
+        })
+        .collect();
+    let auto_filter = if table.header_row_count == 0 {
+        "".to_string()
+    } else if filter_columns.is_empty() {
+        format!("<autoFilter ref=\"{}\"/>", get_auto_filter_reference(table))
+    } else {
+        format!(
+            "<autoFilter ref=\"{}\">{filter_columns}</autoFilter>",
+            get_auto_filter_reference(table)
+        )
+    };
+
+    let mut columns_str = Vec::new();
+    for column in &table.columns {
+        let mut column_attributes = String::new();
+        if let Some(label) = &column.totals_row_label {
+            column_attributes.push_str(&format!(" totalsRowLabel=\"{}\"", escape_xml(label)));
+        }
+        if let Some(function) = &column.totals_row_function {
+            column_attributes.push_str(&format!(" totalsRowFunction=\"{}\"", escape_xml(function)));
+        }
+        column_attributes.push_str(&get_dxf_id_attribute(
+            "headerRowDxfId",
+            column.header_row_dxf_id,
+        ));
+        column_attributes.push_str(&get_dxf_id_attribute("dataDxfId", column.data_dxf_id));
+        column_attributes.push_str(&get_dxf_id_attribute(
+            "totalsRowDxfId",
+            column.totals_row_dxf_id,
+        ));
+        let mut formulas = String::new();
+        if let Some(formula) = &column.calculated_column_formula {
+            formulas.push_str(&format!(
+                "<calculatedColumnFormula>{}</calculatedColumnFormula>",
+                escape_xml(formula)
+            ));
+        }
+        if let Some(formula) = &column.totals_row_formula {
+            formulas.push_str(&format!(
+                "<totalsRowFormula>{}</totalsRowFormula>",
+                escape_xml(formula)
+            ));
+        }
+        let id = column.id;
+        let name = escape_xml(&column.name);
+        if formulas.is_empty() {
+            columns_str.push(format!(
+                "<tableColumn id=\"{id}\" name=\"{name}\"{column_attributes}/>"
+            ));
+        } else {
+            columns_str.push(format!(
+                "<tableColumn id=\"{id}\" name=\"{name}\"{column_attributes}>{formulas}</tableColumn>"
+            ));
+        }
+    }
+
+    let style_info = &table.style_info;
+    let style_name = match &style_info.name {
+        Some(name) => format!(" name=\"{}\"", escape_xml(name)),
+        None => "".to_string(),
+    };
+    let style_info_str = format!(
+        "<tableStyleInfo{style_name}{}{}{}{}/>",
+        get_bool_attribute("showFirstColumn", style_info.show_first_column),
+        get_bool_attribute("showLastColumn", style_info.show_last_column),
+        get_bool_attribute("showRowStripes", style_info.show_row_stripes),
+        get_bool_attribute("showColumnStripes", style_info.show_column_stripes),
+    );
+
+    format!(
+        "{XML_DECLARATION}\n\
+        <table xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+        id=\"{id}\" name=\"{name}\" displayName=\"{display_name}\" ref=\"{reference}\"{attributes}>\
+        {auto_filter}\
+        <tableColumns count=\"{}\">{}</tableColumns>\
+        {style_info_str}\
+        </table>",
+        table.columns.len(),
+        columns_str.join("")
+    )
+}
@@ -9,7 +9,7 @@ use ironcalc_base::{
         icon_set_icons, CfRule, CfRuleInput, Cfvo, ColorScaleThreshold, Icon, IconThreshold,
         PeriodType, TextOperator, ValueOperator,
     },
-    types::{Color, Dxf, DxfFont, Fill},
+    types::{Color, Dxf, DxfFont, Fill, PatternType},
     Model,
 };
 
@@ -103,6 +103,7 @@ fn fill_dxf(color: &str) -> Dxf {
     Dxf {
         fill: Some(Fill {
             color: Color::Rgb(color.to_string()),
+            ..Default::default()
         }),
         ..Default::default()
     }
@@ -1104,3 +1105,51 @@ fn test_cf_custom_icon_set_round_trip() {
         "thumbs up/down icon set found in imported model (and not expected)"
     );
 }
+
+#[test]
+fn test_cf_pattern_fill_round_trip() {
+    let mut model = new_empty_model();
+    for i in 1i32..=10 {
+        model.set_user_input(0, i, 1, i.to_string()).unwrap();
+    }
+    model.evaluate();
+
+    // Inputs are marked with a hatch pattern
+    let hatch = Fill {
+        color: Color::Rgb("#FF0000".to_string()),
+        pattern: PatternType::LightTrellis,
+        bg_color: Color::Rgb("#FFFF00".to_string()),
+        gradient: None,
+    };
+    model
+        .add_conditional_formatting(
+            0,
+            "A1:A10",
+            CfRuleInput::CellIs {
+                operator: ValueOperator::GreaterThan,
+                formula: "5".to_string(),
+                formula2: None,
+                format: Dxf {
+                    fill: Some(hatch.clone()),
+                    ..Default::default()
+                },
+                stop_if_true: false,
+            },
+        )
+        .unwrap();
+    model.evaluate();
+    let style = model.get_extended_style_for_cell(0, 7, 1).unwrap().style;
+    assert_eq!(style.fill, hatch);
+
+    let temp_file = "temp_cf_pattern_fill.xlsx";
+    save_to_xlsx(&model, temp_file).unwrap();
+    let imported = load_from_xlsx(temp_file, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file).unwrap();
+
+    let cf = &imported.workbook.worksheets[0].conditional_formatting[0];
+    let CfRule::CellIs { dxf_id, .. } = &cf.cf_rule else {
+        panic!("CellIs rule not found");
+    };
+    let dxf = &imported.workbook.styles.dxfs[*dxf_id as usize];
+    assert_eq!(dxf.fill, Some(hatch));
+}
@@ -1,6 +1,25 @@
 use std::fs;
 
-use ironcalc_base::types::StyleIncludes;
+use std::collections::HashMap;
+
+use ironcalc_base::cf_types::ValueOperator;
+use ironcalc_base::dv_types::{
+    DataValidation, DataValidationErrorStyle, DataValidationListSource, DataValidationRule,
+};
+use ironcalc_base::expressions::token::Error;
+use ironcalc_base::expressions::types::Area;
+use ironcalc_base::filter_types::{
+    CustomFilter, DateGroupItem, DateTimeGrouping, Filter, FilterOperator,
+};
+use ironcalc_base::page_setup_types::{PageOrientation, PageSetup};
+use ironcalc_base::rich_text_types::{RichTextFont, RichTextRun};
+use ironcalc_base::sort_types::{SortKey, SortOn};
+use ironcalc_base::types::{
+    Alignment, Color, Comment, CommentReply, CommentThread, DateSystem, Dxf, DxfFont, ExternalLink,
+    ExternalSheet, ExternalValue, Fill, GradientFill, GradientStop, GradientType,
+    HorizontalAlignment, IterativeCalculation, Link, PasswordHash, PatternType, Protection,
+    ReadingOrder, SheetProtection, StyleIncludes, VerticalAlignment, WorkbookProtection,
+};
 use ironcalc_base::Model;
 
 use crate::error::XlsxError;
@@ -354,3 +373,987 @@ fn test_defined_name_lambda_exports_excel_prefixes() {
     );
     fs::remove_file(temp_file_name).unwrap();
 }
+
+#[test]
+fn test_iterative_calculation_roundtrip() {
+    let mut model = new_empty_model();
+    model
+        .set_user_input(0, 1, 1, "=A1/2+1".to_string())
+        .unwrap();
+    model
+        .set_iterative_calculation(IterativeCalculation {
+            enabled: true,
+            max_iterations: 50,
+            max_change: 0.0001,
+        })
+        .unwrap();
+    let temp_file_name = "temp_file_test_iterative_calculation.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+
+    let model = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    assert_eq!(
+        model.get_iterative_calculation(),
+        IterativeCalculation {
+            enabled: true,
+            max_iterations: 50,
+            max_change: 0.0001,
+        }
+    );
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1).unwrap(),
+        "1.999938965"
+    );
+    fs::remove_file(temp_file_name).unwrap();
+}
+
+#[test]
+fn test_date_system_roundtrip() {
+    let mut model = new_empty_model();
+    model.set_date_system(DateSystem::Date1904);
+    model
+        .set_user_input(0, 1, 1, "2024-01-15".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 1, 2, "=TEXT(A1,\"0\")".to_string())
+        .unwrap();
+    model.evaluate();
+    let temp_file_name = "temp_file_test_date_system.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+
+    let model = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    assert_eq!(model.get_date_system(), DateSystem::Date1904);
+    assert_eq!(
+        model.get_formatted_cell_value(0, 1, 1).unwrap(),
+        "2024-01-15"
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 2).unwrap(), "43844");
+    fs::remove_file(temp_file_name).unwrap();
+}
+
+#[test]
+fn test_3d_references_roundtrip() {
+    let mut model = new_empty_model();
+    model.add_sheet("Jan 2024").unwrap();
+    model.add_sheet("Feb").unwrap();
+    model.set_user_input(1, 5, 2, "1".to_string()).unwrap();
+    model.set_user_input(2, 5, 2, "2".to_string()).unwrap();
+    model
+        .set_user_input(0, 1, 1, "=SUM('Jan 2024:Feb'!B5)".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 2, 1, "=COUNT('Jan 2024:Feb'!$A$1:B5)".to_string())
+        .unwrap();
+    model.evaluate();
+    let temp_file_name = "temp_file_test_3d_references.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+
+    let model = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    assert_eq!(
+        model.get_localized_cell_content(0, 1, 1).unwrap(),
+        "=SUM('Jan 2024:Feb'!B5)"
+    );
+    assert_eq!(
+        model.get_localized_cell_content(0, 2, 1).unwrap(),
+        "=COUNT('Jan 2024:Feb'!$A$1:B5)"
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1).unwrap(), "3");
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "2");
+    fs::remove_file(temp_file_name).unwrap();
+}
+
+#[test]
+fn test_external_links_roundtrip() {
+    let mut model = new_empty_model();
+    let sheet_data = HashMap::from([
+        (4, HashMap::from([(3, ExternalValue::Number(100.0))])),
+        (
+            5,
+            HashMap::from([
+                (3, ExternalValue::Text("Q1 & Q2".to_string())),
+                (4, ExternalValue::Boolean(true)),
+                (5, ExternalValue::Error(Error::NA)),
+            ]),
+        ),
+    ]);
+    model.workbook.external_links = vec![ExternalLink {
+        target: "Budget.xlsx".to_string(),
+        sheets: vec![
+            ExternalSheet {
+                name: "Notes".to_string(),
+                sheet_data: HashMap::new(),
+            },
+            ExternalSheet {
+                name: "Summary".to_string(),
+                sheet_data,
+            },
+        ],
+    }];
+    model
+        .set_user_input(0, 1, 1, "=[1]Summary!C4*2".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 2, 1, "=[1]Summary!C5".to_string())
+        .unwrap();
+    model.evaluate();
+    let temp_file_name = "temp_file_test_external_links.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+
+    let model = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+    assert_eq!(model.workbook.external_links.len(), 1);
+    let link = &model.workbook.external_links[0];
+    assert_eq!(link.target, "Budget.xlsx");
+    assert_eq!(link.sheets[1].name, "Summary");
+    assert_eq!(
+        link.sheets[1].sheet_data[&5][&5],
+        ExternalValue::Error(Error::NA)
+    );
+    assert_eq!(
+        link.sheets[1].sheet_data[&5][&4],
+        ExternalValue::Boolean(true)
+    );
+    assert_eq!(
+        model.get_localized_cell_content(0, 1, 1).unwrap(),
+        "=[1]Summary!C4*2"
+    );
+    assert_eq!(model.get_formatted_cell_value(0, 1, 1).unwrap(), "200");
+    assert_eq!(model.get_formatted_cell_value(0, 2, 1).unwrap(), "Q1 & Q2");
+}
+
+#[test]
+fn test_tables_roundtrip() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    let inputs = [
+        (1, 1, "Region"),
+        (1, 2, "Amount"),
+        (2, 1, "North"),
+        (2, 2, "10"),
+        (3, 1, "South"),
+        (3, 2, "20"),
+    ];
+    for (row, column, value) in inputs {
+        model
+            .set_user_input(0, row, column, value.to_string())
+            .unwrap();
+    }
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 3,
+    };
+    let name = model.create_table(&area, true).unwrap();
+    model.set_table_totals_row(&name, true).unwrap();
+    model.rename_table(&name, "Sales").unwrap();
+    model.workbook.styles.dxfs.push(Dxf {
+        font: Some(DxfFont {
+            b: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let mut table = model.get_table("Sales").unwrap();
+    table.header_row_dxf_id = Some(0);
+    table.columns[1].data_dxf_id = Some(0);
+    table.style_info.show_first_column = true;
+    model.workbook.tables.insert("Sales".to_string(), table);
+    // A hyperlink in the same sheet shares the relationships with the table
+    model
+        .set_cell_link(
+            0,
+            8,
+            1,
+            Link::External {
+                target: "https://www.ironcalc.com".to_string(),
+                tooltip: None,
+            },
+        )
+        .unwrap();
+
+    // A table without headers in the second sheet
+    model.set_user_input(1, 5, 3, "1".to_string()).unwrap();
+    model.set_user_input(1, 6, 3, "2".to_string()).unwrap();
+    let area = Area {
+        sheet: 1,
+        row: 5,
+        column: 3,
+        width: 1,
+        height: 2,
+    };
+    model.create_table(&area, false).unwrap();
+
+    model
+        .set_user_input(1, 1, 1, "=SUM(Sales)".to_string())
+        .unwrap();
+    model
+        .set_user_input(1, 2, 1, "=SUM(Sales[Amount])".to_string())
+        .unwrap();
+    model
+        .set_user_input(1, 3, 1, "=SUM(Table1)".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 3, 4, "=Sales[@Amount]*2".to_string())
+        .unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_tables.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(imported.workbook.tables, model.workbook.tables);
+    let sales = imported.get_table("Sales").unwrap();
+    assert_eq!(sales.reference, "A1:B4");
+    assert_eq!(sales.columns[0].totals_row_label, Some("Total".to_string()));
+    assert_eq!(
+        sales.columns[1].totals_row_function,
+        Some("sum".to_string())
+    );
+    assert_eq!(
+        imported.get_localized_cell_content(1, 1, 1).unwrap(),
+        "=SUM(Sales)"
+    );
+    // Structured references are kept in the formulas
+    assert_eq!(
+        imported.get_localized_cell_content(1, 2, 1).unwrap(),
+        "=SUM(Sales[Amount])"
+    );
+    assert_eq!(
+        imported.get_localized_cell_content(0, 4, 2).unwrap(),
+        "=SUBTOTAL(109,Sales[Amount])"
+    );
+    assert_eq!(
+        imported.get_localized_cell_content(0, 3, 4).unwrap(),
+        "=Sales[@Amount]*2"
+    );
+    assert_eq!(imported.get_formatted_cell_value(0, 3, 4).unwrap(), "40");
+    assert_eq!(imported.get_formatted_cell_value(1, 1, 1).unwrap(), "30");
+    assert_eq!(imported.get_formatted_cell_value(1, 2, 1).unwrap(), "30");
+    assert_eq!(imported.get_formatted_cell_value(1, 3, 1).unwrap(), "3");
+    assert_eq!(imported.get_formatted_cell_value(0, 4, 2).unwrap(), "30");
+    assert_eq!(
+        imported.get_cell_link(0, 8, 1).unwrap(),
+        model.get_cell_link(0, 8, 1).unwrap()
+    );
+}
+
+#[test]
+fn test_table_column_formulas_roundtrip() {
+    let mut model = new_empty_model();
+    let inputs = [
+        (1, 1, "Amount"),
+        (1, 2, "Tax"),
+        (2, 1, "10"),
+        (2, 2, "=A2*0.2"),
+        (3, 1, "20"),
+        (3, 2, "=A3*0.2"),
+    ];
+    for (row, column, value) in inputs {
+        model
+            .set_user_input(0, row, column, value.to_string())
+            .unwrap();
+    }
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 3,
+    };
+    let name = model.create_table(&area, true).unwrap();
+    model.set_table_totals_row(&name, true).unwrap();
+    model.rename_table(&name, "Sales").unwrap();
+    let mut table = model.get_table("Sales").unwrap();
+    let tax = &mut table.columns[1];
+    tax.calculated_column_formula = Some("Sales[[#This Row],[Amount]]*0.2".to_string());
+    tax.totals_row_function = Some("custom".to_string());
+    tax.totals_row_formula = Some("ROUND(SUM(Sales[Tax]),0)".to_string());
+    model.workbook.tables.insert("Sales".to_string(), table);
+    model
+        .set_user_input(0, 4, 2, "=ROUND(SUM(B2:B3),0)".to_string())
+        .unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_table_column_formulas.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(imported.workbook.tables, model.workbook.tables);
+    let tax = &imported.get_table("Sales").unwrap().columns[1];
+    assert_eq!(
+        tax.calculated_column_formula,
+        Some("Sales[[#This Row],[Amount]]*0.2".to_string())
+    );
+    assert_eq!(tax.totals_row_function, Some("custom".to_string()));
+    assert_eq!(
+        tax.totals_row_formula,
+        Some("ROUND(SUM(Sales[Tax]),0)".to_string())
+    );
+    assert_eq!(imported.get_formatted_cell_value(0, 4, 2).unwrap(), "6");
+}
+
+#[test]
+fn test_comments_roundtrip() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    let note = Comment {
+        text: "First line\nSecond <line> & more".to_string(),
+        author_name: "Ann".to_string(),
+        author_id: None,
+        cell_ref: "B2".to_string(),
+        thread: None,
+    };
+    model.set_comment(0, 2, 2, Some(note)).unwrap();
+    let threaded_comment = Comment {
+        text: "Is this right?".to_string(),
+        author_name: "Ann".to_string(),
+        author_id: Some("{00000000-0000-4000-8000-000000000001}".to_string()),
+        cell_ref: "D4".to_string(),
+        thread: Some(CommentThread {
+            id: "{00000000-0000-4000-8000-000000000002}".to_string(),
+            date: "2024-03-05T10:20:30.000".to_string(),
+            done: true,
+            replies: vec![CommentReply {
+                id: "{00000000-0000-4000-8000-000000000003}".to_string(),
+                text: "Yes".to_string(),
+                author_name: "Bob".to_string(),
+                author_id: Some("{00000000-0000-4000-8000-000000000004}".to_string()),
+                date: "2024-03-05T11:00:00.000".to_string(),
+            }],
+        }),
+    };
+    model.set_comment(0, 4, 4, Some(threaded_comment)).unwrap();
+    // The comments share the sheet relationships with hyperlinks and tables
+    model
+        .set_cell_link(
+            0,
+            8,
+            1,
+            Link::External {
+                target: "https://www.ironcalc.com".to_string(),
+                tooltip: None,
+            },
+        )
+        .unwrap();
+    model.set_user_input(0, 10, 1, "Name".to_string()).unwrap();
+    model.set_user_input(0, 11, 1, "Ann".to_string()).unwrap();
+    let area = Area {
+        sheet: 0,
+        row: 10,
+        column: 1,
+        width: 1,
+        height: 2,
+    };
+    model.create_table(&area, true).unwrap();
+    let note = Comment {
+        text: "On the second sheet".to_string(),
+        author_name: "Bob".to_string(),
+        author_id: None,
+        cell_ref: "A1".to_string(),
+        thread: None,
+    };
+    model.set_comment(1, 1, 1, Some(note)).unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_comments.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(imported.get_comments(0), model.get_comments(0));
+    assert_eq!(imported.get_comments(1), model.get_comments(1));
+    assert_eq!(imported.workbook.tables, model.workbook.tables);
+    assert_eq!(
+        imported.get_cell_link(0, 8, 1).unwrap(),
+        model.get_cell_link(0, 8, 1).unwrap()
+    );
+}
+
+#[test]
+fn test_import_comment_authors() {
+    let model = load_from_xlsx("tests/example.xlsx", "en", "UTC", "en").unwrap();
+    let comments = model.get_comments(0).unwrap();
+    assert!(!comments.is_empty());
+    assert!(comments
+        .iter()
+        .all(|comment| comment.author_name == "nicol"));
+}
+
+#[test]
+fn test_data_validations_roundtrip() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    let list = DataValidation {
+        range: "B2:B10 D2".to_string(),
+        rule: DataValidationRule::List {
+            source: DataValidationListSource::Values(vec![
+                "Yes".to_string(),
+                "No \"maybe\"".to_string(),
+            ]),
+            show_dropdown: false,
+        },
+        allow_blank: true,
+        error_style: DataValidationErrorStyle::Stop,
+        show_input_message: true,
+        prompt_title: Some("Answer".to_string()),
+        prompt: Some("Pick <one>".to_string()),
+        show_error_message: true,
+        error_title: None,
+        error: Some("Not & allowed".to_string()),
+    };
+    model.add_data_validation(0, list).unwrap();
+    let whole = DataValidation {
+        range: "C2:C10".to_string(),
+        rule: DataValidationRule::WholeNumber {
+            operator: ValueOperator::Between,
+            formula1: "1".to_string(),
+            formula2: Some("Sheet2!$A$1".to_string()),
+        },
+        allow_blank: false,
+        error_style: DataValidationErrorStyle::Warning,
+        show_input_message: false,
+        prompt_title: None,
+        prompt: None,
+        show_error_message: true,
+        error_title: Some("Careful".to_string()),
+        error: None,
+    };
+    model.add_data_validation(0, whole).unwrap();
+    let length = DataValidation {
+        range: "A1:A3".to_string(),
+        rule: DataValidationRule::TextLength {
+            operator: ValueOperator::LessThan,
+            formula1: "LEN(B1)".to_string(),
+            formula2: None,
+        },
+        allow_blank: true,
+        error_style: DataValidationErrorStyle::Information,
+        show_input_message: false,
+        prompt_title: None,
+        prompt: None,
+        show_error_message: false,
+        error_title: None,
+        error: None,
+    };
+    model.add_data_validation(1, length).unwrap();
+    let custom = DataValidation {
+        range: "E1".to_string(),
+        rule: DataValidationRule::Custom {
+            formula: "ISNUMBER(E1)".to_string(),
+        },
+        allow_blank: false,
+        error_style: DataValidationErrorStyle::Stop,
+        show_input_message: false,
+        prompt_title: None,
+        prompt: None,
+        show_error_message: true,
+        error_title: None,
+        error: None,
+    };
+    model.add_data_validation(1, custom).unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_data_validations.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(
+        imported.get_data_validations(0),
+        model.get_data_validations(0)
+    );
+    assert_eq!(
+        imported.get_data_validations(1),
+        model.get_data_validations(1)
+    );
+    assert_eq!(imported.get_data_validations(1).unwrap().len(), 2);
+}
+
+#[test]
+fn test_protection_roundtrip() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    model.set_user_input(0, 1, 1, "=1+1".to_string()).unwrap();
+    let mut style = model.get_style_for_cell(0, 1, 1).unwrap();
+    style.protection = Some(Protection {
+        locked: false,
+        hidden: true,
+    });
+    model.set_cell_style(0, 1, 1, &style).unwrap();
+    let sheet_protection = SheetProtection {
+        password: Some(PasswordHash::new("secret")),
+        select_locked_cells: false,
+        format_cells: true,
+        insert_rows: true,
+        auto_filter: true,
+        ..Default::default()
+    };
+    model.protect_sheet(0, sheet_protection.clone()).unwrap();
+    let salted = SheetProtection {
+        password: Some(PasswordHash::Salted {
+            algorithm_name: "SHA-512".to_string(),
+            hash_value: "k9BhlCsJFfxaNSu4u4rHdyjPGlFYi4NxkOK+BNrt7tzuGhaVF8Kv1V7GO1cMuKy8OBGPPwOxZSByBRqIgRIrnQ==".to_string(),
+            salt_value: "AAECAwQFBgcICQoLDA0ODw==".to_string(),
+            spin_count: 100,
+        }),
+        ..Default::default()
+    };
+    model.protect_sheet(1, salted.clone()).unwrap();
+    let workbook_protection = WorkbookProtection {
+        password: Some(PasswordHash::new("pass")),
+        lock_structure: true,
+        lock_windows: false,
+    };
+    model.protect_workbook(workbook_protection.clone());
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_protection.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let mut imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(
+        imported.get_style_for_cell(0, 1, 1).unwrap().protection,
+        style.protection
+    );
+    assert_eq!(
+        imported.get_style_for_cell(0, 2, 1).unwrap().protection,
+        None
+    );
+    assert!(imported.is_cell_formula_hidden(0, 1, 1).unwrap());
+    assert!(imported.is_cell_locked(0, 2, 1).unwrap());
+    assert_eq!(imported.get_sheet_protection(0), Ok(Some(sheet_protection)));
+    assert_eq!(imported.get_sheet_protection(1), Ok(Some(salted)));
+    assert_eq!(
+        imported.get_workbook_protection(),
+        Some(workbook_protection)
+    );
+    imported.unprotect_sheet(1, "secret").unwrap();
+    imported.unprotect_workbook("pass").unwrap();
+}
+
+#[test]
+fn test_sort_state_roundtrip() {
+    let mut model = new_empty_model();
+    model.new_sheet();
+    for (row, value) in ["Name", "pear", "apple", "fig"].iter().enumerate() {
+        model
+            .set_user_input(0, row as i32 + 1, 1, value.to_string())
+            .unwrap();
+        model
+            .set_user_input(0, row as i32 + 1, 2, (row * 2).to_string())
+            .unwrap();
+    }
+    let keys = vec![
+        SortKey {
+            index: 2,
+            descending: true,
+            sort_on: SortOn::Value,
+        },
+        SortKey {
+            index: 1,
+            descending: false,
+            sort_on: SortOn::CellColor(Color::Rgb("#FFFF00".to_string())),
+        },
+        SortKey {
+            index: 1,
+            descending: false,
+            sort_on: SortOn::CustomList(vec!["fig".to_string(), "pear & apple".to_string()]),
+        },
+    ];
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 4,
+    };
+    model.sort_range(&area, true, false, &keys).unwrap();
+    model.set_user_input(1, 1, 1, "b".to_string()).unwrap();
+    model.set_user_input(1, 1, 2, "a".to_string()).unwrap();
+    let left_to_right = vec![SortKey {
+        index: 1,
+        descending: false,
+        sort_on: SortOn::FontColor(Color::Rgb("#0000FF".to_string())),
+    }];
+    let area = Area {
+        sheet: 1,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 1,
+    };
+    model
+        .sort_range(&area, false, true, &left_to_right)
+        .unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_sort_state.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    let sort_state = imported.get_sort_state(0).unwrap().unwrap();
+    assert_eq!(sort_state.range, "A2:B4");
+    assert!(!sort_state.left_to_right);
+    assert_eq!(sort_state.keys, keys);
+    let sort_state = imported.get_sort_state(1).unwrap().unwrap();
+    assert_eq!(sort_state.range, "A1:B1");
+    assert!(sort_state.left_to_right);
+    assert_eq!(sort_state.keys, left_to_right);
+}
+
+#[test]
+fn test_auto_filter_roundtrip() {
+    let mut model = new_empty_model();
+    for (row, value) in ["Name", "pear", "apple", "fig"].iter().enumerate() {
+        model
+            .set_user_input(0, row as i32 + 1, 1, value.to_string())
+            .unwrap();
+        model
+            .set_user_input(0, row as i32 + 1, 2, (row * 2).to_string())
+            .unwrap();
+        model
+            .set_user_input(0, row as i32 + 1, 4, value.to_string())
+            .unwrap();
+        model
+            .set_user_input(0, row as i32 + 1, 5, (row * 3).to_string())
+            .unwrap();
+    }
+    let area = Area {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        width: 2,
+        height: 4,
+    };
+    model.set_auto_filter(&area).unwrap();
+    let values = Filter::Values {
+        values: vec!["pear & apple".to_string(), "fig".to_string()],
+        date_groups: vec![DateGroupItem {
+            grouping: DateTimeGrouping::Day,
+            year: 2024,
+            month: 3,
+            day: 15,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }],
+        blank: true,
+    };
+    let custom = Filter::Custom {
+        and: true,
+        filters: vec![
+            CustomFilter {
+                operator: FilterOperator::GreaterThan,
+                value: "1".to_string(),
+            },
+            CustomFilter {
+                operator: FilterOperator::Equal,
+                value: "<4".to_string(),
+            },
+        ],
+    };
+    model.set_filter(0, 1, 1, Some(values.clone())).unwrap();
+    model.set_filter(0, 1, 2, Some(custom.clone())).unwrap();
+
+    let table_area = Area {
+        sheet: 0,
+        row: 1,
+        column: 4,
+        width: 2,
+        height: 4,
+    };
+    let name = model.create_table(&table_area, true).unwrap();
+    let color = Filter::FontColor {
+        color: Color::Rgb("#0000FF".to_string()),
+    };
+    let top10 = Filter::Top10 {
+        top: false,
+        percent: true,
+        value: 50.0,
+    };
+    model.set_filter(0, 1, 4, Some(color.clone())).unwrap();
+    model.set_filter(0, 1, 5, Some(top10.clone())).unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_auto_filter.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    let auto_filter = imported.get_auto_filter(0).unwrap().unwrap();
+    assert_eq!(auto_filter.range, "A1:B4");
+    assert_eq!(auto_filter.columns.len(), 2);
+    assert_eq!(imported.get_filter(0, 1, 1).unwrap(), Some(values));
+    assert_eq!(imported.get_filter(0, 1, 2).unwrap(), Some(custom));
+
+    let table = imported.get_table(&name).unwrap();
+    assert!(table.has_filters);
+    assert_eq!(table.columns[0].filter, Some(color));
+    assert_eq!(table.columns[1].filter, Some(top10));
+    assert_eq!(
+        imported.is_row_hidden(0, 2).unwrap(),
+        model.is_row_hidden(0, 2).unwrap()
+    );
+}
+
+#[test]
+fn test_outline_roundtrip() {
+    let mut model = new_empty_model();
+    model.set_user_input(0, 1, 1, "Total".to_string()).unwrap();
+    model.set_user_input(0, 2, 1, "2".to_string()).unwrap();
+    model.set_user_input(0, 3, 1, "3".to_string()).unwrap();
+    model.workbook.worksheets[0]
+        .outline_properties
+        .summary_below = false;
+    // Rows 2 to 5 in a group, with rows 4 and 5 (without cells) in a nested group
+    model.group_rows(0, 2, 5).unwrap();
+    model.group_rows(0, 4, 5).unwrap();
+    model.set_row_group_collapsed(0, 4, 2, true).unwrap();
+    model.group_columns(0, 3, 4).unwrap();
+    model.set_column_group_collapsed(0, 3, 1, true).unwrap();
+
+    let temp_file_name = "temp_file_test_outline.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert!(
+        !imported.workbook.worksheets[0]
+            .outline_properties
+            .summary_below
+    );
+    assert_eq!(
+        imported.get_row_outline(0).unwrap(),
+        model.get_row_outline(0).unwrap()
+    );
+    assert_eq!(
+        imported.get_column_outline(0).unwrap(),
+        model.get_column_outline(0).unwrap()
+    );
+    let row_outline = imported.get_row_outline(0).unwrap();
+    assert_eq!(row_outline.max_level, 2);
+    assert!(row_outline.groups[1].collapsed);
+    assert!(imported.is_row_hidden(0, 5).unwrap());
+    assert!(!imported.is_row_hidden(0, 2).unwrap());
+    assert!(imported.is_column_hidden(0, 4).unwrap());
+}
+
+#[test]
+fn test_page_setup_roundtrip() {
+    let mut model = new_empty_model();
+    model.add_sheet("Sales Q1").unwrap();
+    model.set_user_input(1, 1, 1, "Region".to_string()).unwrap();
+    let mut page_setup = model.get_page_setup(1).unwrap();
+    page_setup.orientation = PageOrientation::Landscape;
+    page_setup.paper_size = 9;
+    page_setup.fit_to_page = true;
+    page_setup.fit_to_height = 0;
+    page_setup.first_page_number = Some(3);
+    page_setup.horizontal_centered = true;
+    page_setup.print_grid_lines = true;
+    page_setup.margins.left = 0.25;
+    page_setup.margins.bottom = 1.5;
+    page_setup.header_footer.odd_header = "&L<Sales> & more".to_string();
+    page_setup.header_footer.odd_footer = "&CPage &P of &N".to_string();
+    page_setup.header_footer.different_first = true;
+    page_setup.print_area = vec!["A1:F40".to_string(), "H1:H10".to_string()];
+    page_setup.print_title_rows = Some((1, 2));
+    page_setup.print_title_columns = Some((1, 1));
+    page_setup.row_breaks = vec![20, 30];
+    page_setup.column_breaks = vec![3];
+    model.set_page_setup(1, page_setup.clone()).unwrap();
+    model
+        .new_defined_name("Total", None, "'Sales Q1'!$A$1")
+        .unwrap();
+
+    let temp_file_name = "temp_file_test_page_setup.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(imported.get_page_setup(1).unwrap(), page_setup);
+    assert_eq!(imported.get_page_setup(0).unwrap(), PageSetup::default());
+    // The print area and titles are not listed with the defined names
+    let defined_names = imported.workbook.get_defined_names_with_scope();
+    assert_eq!(defined_names.len(), 1);
+    assert_eq!(defined_names[0].0, "Total");
+}
+
+#[test]
+fn test_rich_text_roundtrip() {
+    let mut model = new_empty_model();
+    let runs = vec![
+        RichTextRun {
+            text: " Total: ".to_string(),
+            font: RichTextFont::default(),
+        },
+        RichTextRun {
+            text: "<100> & more".to_string(),
+            font: RichTextFont {
+                b: Some(true),
+                i: Some(false),
+                u: Some(true),
+                sz: Some(14),
+                color: Color::Theme(4, -0.25),
+                name: Some("Courier New".to_string()),
+                ..Default::default()
+            },
+        },
+        RichTextRun {
+            text: " units".to_string(),
+            font: RichTextFont {
+                strike: Some(true),
+                color: Color::Rgb("#FF0000".to_string()),
+                ..Default::default()
+            },
+        },
+    ];
+    model.set_cell_rich_text(0, 1, 1, runs.clone()).unwrap();
+    model
+        .set_user_input(0, 2, 1, " Total: <100> & more units".to_string())
+        .unwrap();
+    model
+        .set_user_input(0, 3, 1, "=LEN(A1)".to_string())
+        .unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_test_rich_text.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let mut imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(imported.get_cell_rich_text(0, 1, 1).unwrap(), Some(runs));
+    assert_eq!(imported.get_cell_rich_text(0, 2, 1).unwrap(), None);
+    imported.evaluate();
+    assert_eq!(imported.get_formatted_cell_value(0, 3, 1).unwrap(), "26");
+}
+
+#[test]
+fn test_extended_alignment_roundtrip() {
+    let mut model = new_empty_model();
+    model
+        .set_user_input(0, 1, 1, "Revenue".to_string())
+        .unwrap();
+    model.set_user_input(0, 2, 1, "Cost".to_string()).unwrap();
+    let mut style = model.get_style_for_cell(0, 1, 1).unwrap();
+    style.alignment = Some(Alignment {
+        horizontal: HorizontalAlignment::Left,
+        vertical: VerticalAlignment::Distributed,
+        text_rotation: 90,
+        indent: 3,
+        shrink_to_fit: true,
+        reading_order: ReadingOrder::LeftToRight,
+        justify_last_line: true,
+        ..Default::default()
+    });
+    model.set_cell_style(0, 1, 1, &style).unwrap();
+    // A named style with a rotated text
+    style.alignment = Some(Alignment {
+        text_rotation: 255,
+        ..Default::default()
+    });
+    model
+        .workbook
+        .styles
+        .create_named_style("Vertical header", &style, StyleIncludes::default())
+        .unwrap();
+    model
+        .set_cell_style_by_name(0, 2, 1, "Vertical header")
+        .unwrap();
+
+    let temp_file_name = "temp_file_test_extended_alignment.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(
+        imported.get_style_for_cell(0, 1, 1).unwrap().alignment,
+        model.get_style_for_cell(0, 1, 1).unwrap().alignment
+    );
+    let alignment = imported.get_style_for_cell(0, 2, 1).unwrap().alignment;
+    assert_eq!(alignment.unwrap().text_rotation, 255);
+    let named_style = imported.get_named_style("Vertical header").unwrap();
+    assert_eq!(named_style.alignment.unwrap().text_rotation, 255);
+}
+
+#[test]
+fn test_fill_patterns_roundtrip() {
+    let mut model = new_empty_model();
+    let mut style = model.get_style_for_cell(0, 1, 1).unwrap();
+    style.fill = Fill {
+        color: Color::Rgb("#FF0000".to_string()),
+        pattern: PatternType::DarkHorizontal,
+        bg_color: Color::Theme(4, 0.0),
+        gradient: None,
+    };
+    model.set_cell_style(0, 1, 1, &style).unwrap();
+    // gray125 without colors
+    style.fill = Fill {
+        pattern: PatternType::Gray125,
+        ..Default::default()
+    };
+    model.set_cell_style(0, 2, 1, &style).unwrap();
+    style.fill = Fill {
+        gradient: Some(GradientFill {
+            r#type: GradientType::Linear,
+            degree: 90.0,
+            stops: vec![
+                GradientStop {
+                    position: 0.0,
+                    color: Color::Rgb("#FFFFFF".to_string()),
+                },
+                GradientStop {
+                    position: 1.0,
+                    color: Color::Theme(4, 0.0),
+                },
+            ],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    model.set_cell_style(0, 3, 1, &style).unwrap();
+    style.fill = Fill {
+        gradient: Some(GradientFill {
+            r#type: GradientType::Path,
+            left: 0.5,
+            right: 0.5,
+            top: 0.5,
+            bottom: 0.5,
+            stops: vec![
+                GradientStop {
+                    position: 0.0,
+                    color: Color::Rgb("#FFFFFF".to_string()),
+                },
+                GradientStop {
+                    position: 0.5,
+                    color: Color::Rgb("#70AD47".to_string()),
+                },
+                GradientStop {
+                    position: 1.0,
+                    color: Color::Rgb("#4472C4".to_string()),
+                },
+            ],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    model.set_cell_style(0, 4, 1, &style).unwrap();
+
+    let temp_file_name = "temp_file_test_fill_patterns.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let imported = load_from_xlsx(temp_file_name, "en", "UTC", "en").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    for row in 1..=4 {
+        assert_eq!(
+            imported.get_style_for_cell(0, row, 1).unwrap().fill,
+            model.get_style_for_cell(0, row, 1).unwrap().fill,
+            "row {row}"
+        );
+    }
+}
@@ -33,9 +33,12 @@ use std::collections::HashMap;
 
 use ironcalc_base::expressions::parser::{new_parser_english, stringify::to_excel_string};
 use ironcalc_base::expressions::types::CellReferenceRC;
-use ironcalc_base::types::{SheetState, Workbook};
+use ironcalc_base::types::{DateSystem, IterativeCalculation, SheetState, Workbook};
 
 use super::escape::escape_xml;
+use super::page_setup::get_print_names;
+use super::protection::get_workbook_protection_xml;
+use super::workbook_xml_rels::get_external_link_rel_id;
 use super::xml_constants::XML_DECLARATION;
 
 pub(crate) fn get_workbook_xml(workbook: &Workbook, selected_sheet: u32) -> String {
@@ -104,20 +107,85 @@ pub(crate) fn get_workbook_xml(workbook: &Workbook, selected_sheet: u32) -> Stri
             "<definedName name=\"{name}\"{local_sheet_id}>{formula}</definedName>"
         ))
     }
+    // The print areas and print titles are defined names local to their sheets
+    for (sheet_index, worksheet) in workbook.worksheets.iter().enumerate() {
+        let (print_area, print_titles) = get_print_names(&worksheet.name, &worksheet.page_setup);
+        for (name, formula) in [
+            ("_xlnm.Print_Area", print_area),
+            ("_xlnm.Print_Titles", print_titles),
+        ] {
+            if let Some(formula) = formula {
+                let formula = escape_xml(&formula);
+                defined_names_str.push(format!(
+                    "<definedName name=\"{name}\" localSheetId=\"{sheet_index}\">{formula}</definedName>"
+                ));
+            }
+        }
+    }
+
+    // <calcPr iterate="1" iterateCount="100" iterateDelta="0.001"/>
+    // Only values that differ from Excel's defaults are written
+    let iterative_calculation = &workbook.settings.iterative_calculation;
+    let default_iterative_calculation = IterativeCalculation::default();
+    let mut calc_pr = "<calcPr".to_string();
+    if iterative_calculation.enabled {
+        calc_pr.push_str(" iterate=\"1\"");
+    }
+    if iterative_calculation.max_iterations != default_iterative_calculation.max_iterations {
+        calc_pr.push_str(&format!(
+            " iterateCount=\"{}\"",
+            iterative_calculation.max_iterations
+        ));
+    }
+    if iterative_calculation.max_change != default_iterative_calculation.max_change {
+        calc_pr.push_str(&format!(
+            " iterateDelta=\"{}\"",
+            iterative_calculation.max_change
+        ));
+    }
+    calc_pr.push_str("/>");
+
+    let workbook_pr = match workbook.settings.date_system {
+        DateSystem::Date1900 => "",
+        DateSystem::Date1904 => "<workbookPr date1904=\"1\"/>",
+    };
+
+    // <externalReferences><externalReference r:id="rId8"/></externalReferences>
+    let external_references = if workbook.external_links.is_empty() {
+        "".to_string()
+    } else {
+        let references: Vec<String> = (0..workbook.external_links.len())
+            .map(|index| {
+                format!(
+                    "<externalReference r:id=\"{}\"/>",
+                    get_external_link_rel_id(workbook, index)
+                )
+            })
+            .collect();
+        format!(
+            "<externalReferences>{}</externalReferences>",
+            references.join("")
+        )
+    };
+
+    let workbook_protection = get_workbook_protection_xml(&workbook.protection);
 
     let sheets = sheets_str.join("");
     let defined_names = defined_names_str.join("");
     format!("{XML_DECLARATION}\n\
     <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+    {workbook_pr}\
+    {workbook_protection}\
     <bookViews>
     <workbookView activeTab=\"{selected_sheet}\"/>\
     </bookViews>
       <sheets>\
         {sheets}\
       </sheets>\
+      {external_references}\
       <definedNames>\
         {defined_names}\
       </definedNames>\
-      <calcPr/>\
+      {calc_pr}\
     </workbook>")
 }
@@ -1,6 +1,12 @@
 use ironcalc_base::types::Workbook;
 
-use super::xml_constants::{XML_DECLARATION, XML_WORKSHEET};
+use super::comments::get_persons_xml;
+use super::xml_constants::{XML_DECLARATION, XML_EXTERNAL_LINK, XML_WORKSHEET};
+
+/// Relationship id of the n-th (0-based) external link, they come after the worksheets and the four fixed parts
+pub(crate) fn get_external_link_rel_id(workbook: &Workbook, index: usize) -> String {
+    format!("rId{}", workbook.worksheets.len() + 5 + index)
+}
 
 pub(crate) fn get_workbook_xml_rels(workbook: &Workbook) -> String {
     let mut relationships_str: Vec<String> = vec![];
@@ -26,6 +32,19 @@ pub(crate) fn get_workbook_xml_rels(workbook: &Workbook) -> String {
     relationships_str.push(
         format!("<Relationship Id=\"rId{id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme\" Target=\"theme/theme1.xml\"/>")
     );
+    for index in 0..workbook.external_links.len() {
+        let id = get_external_link_rel_id(workbook, index);
+        relationships_str.push(format!(
+            "<Relationship Id=\"{id}\" Type=\"{XML_EXTERNAL_LINK}\" Target=\"externalLinks/externalLink{}.xml\"/>",
+            index + 1
+        ));
+    }
+    if get_persons_xml(workbook).is_some() {
+        let id = get_external_link_rel_id(workbook, workbook.external_links.len());
+        relationships_str.push(format!(
+            "<Relationship Id=\"{id}\" Type=\"http://schemas.microsoft.com/office/2017/10/relationships/person\" Target=\"persons/person.xml\"/>"
+        ));
+    }
     format!(
         "{XML_DECLARATION}\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{}</Relationships>",
         relationships_str.join("")
@@ -30,12 +30,18 @@ use ironcalc_base::{
         types::CellReferenceRC,
         utils::number_to_column,
     },
-    types::{ArrayKind, Cell, FormulaValue, Link, SpillValue, Worksheet},
+    outline_types::OutlineProperties,
+    types::{ArrayKind, Cell, Dxf, FormulaValue, Link, SpillValue, Worksheet},
 };
 
+use crate::export::auto_filter::get_auto_filter_xml;
 use crate::export::conditional_formatting::get_conditional_formatting_xml;
+use crate::export::data_validation::get_data_validations_xml;
+use crate::export::page_setup::get_page_setup_xml;
+use crate::export::protection::get_sheet_protection_xml;
+use crate::export::sort::get_sort_state_xml;
 
-use super::{escape::escape_xml, xml_constants::XML_DECLARATION};
+use super::{comments::has_threaded_comments, escape::escape_xml, xml_constants::XML_DECLARATION};
 
 fn get_range_str(row: i32, column: i32, width: i32, height: i32) -> Option<String> {
     let column1 = number_to_column(column)?;
@@ -121,9 +127,51 @@ fn get_tooltip_attribute(tooltip: &Option<String>) -> String {
     }
 }
 
+/// The number of relationships of the worksheet used by external links
+fn get_external_links_count(worksheet: &Worksheet) -> usize {
+    worksheet
+        .links
+        .values()
+        .filter(|link| matches!(link, Link::External { .. }))
+        .count()
+}
+
+/// The `<tableParts>` section of the worksheet or an empty string if there are none.
+/// The relationships of the tables come after those of the external links.
+fn get_table_parts_section(worksheet: &Worksheet, table_ids: &[usize]) -> String {
+    if table_ids.is_empty() {
+        return "".to_string();
+    }
+    let first_rel_id = get_external_links_count(worksheet) + 1;
+    let table_parts: Vec<String> = (0..table_ids.len())
+        .map(|index| format!("<tablePart r:id=\"rId{}\"/>", first_rel_id + index))
+        .collect();
+    format!(
+        "<tableParts count=\"{}\">{}</tableParts>",
+        table_ids.len(),
+        table_parts.join("")
+    )
+}
+
+/// The `<legacyDrawing>` section pointing to the drawing of the comments of the worksheet, or
+/// an empty string if there are none. Its relationship comes after those of the tables.
+fn get_legacy_drawing_section(worksheet: &Worksheet, table_ids: &[usize]) -> String {
+    if worksheet.comments.is_empty() {
+        return "".to_string();
+    }
+    let rel_id = get_external_links_count(worksheet) + table_ids.len() + 1;
+    format!("<legacyDrawing r:id=\"rId{rel_id}\"/>")
+}
+
 /// The rels part of the worksheet (`xl/worksheets/_rels/sheetN.xml.rels`) holding one
-/// relationship per external link, or `None` if the worksheet has no external links.
-pub(crate) fn get_worksheet_xml_rels(worksheet: &Worksheet) -> Option<String> {
+/// relationship per external link, one per table (`table_ids` are the numbers of the
+/// table parts) and those of the comments (`comment_id` is the number of the comment parts),
+/// or `None` if the worksheet has none of them.
+pub(crate) fn get_worksheet_xml_rels(
+    worksheet: &Worksheet,
+    table_ids: &[usize],
+    comment_id: Option<usize>,
+) -> Option<String> {
     let mut relationships_str: Vec<String> = vec![];
     let mut rel_id = 0;
     for (_, link) in get_sorted_links(worksheet) {
@@ -135,6 +183,29 @@ pub(crate) fn get_worksheet_xml_rels(worksheet: &Worksheet) -> Option<String> {
             ));
         }
     }
+    for table_id in table_ids {
+        rel_id += 1;
+        relationships_str.push(format!(
+            "<Relationship Id=\"rId{rel_id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/table\" Target=\"../tables/table{table_id}.xml\"/>"
+        ));
+    }
+    if let Some(comment_id) = comment_id {
+        // The drawing must be the first one, see get_legacy_drawing_section
+        relationships_str.push(format!(
+            "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/vmlDrawing\" Target=\"../drawings/vmlDrawing{comment_id}.vml\"/>",
+            rel_id + 1
+        ));
+        relationships_str.push(format!(
+            "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments\" Target=\"../comments{comment_id}.xml\"/>",
+            rel_id + 2
+        ));
+        if has_threaded_comments(worksheet) {
+            relationships_str.push(format!(
+                "<Relationship Id=\"rId{}\" Type=\"http://schemas.microsoft.com/office/2017/10/relationships/threadedComment\" Target=\"../threadedComments/threadedComment{comment_id}.xml\"/>",
+                rel_id + 3
+            ));
+        }
+    }
     if relationships_str.is_empty() {
         return None;
     }
@@ -159,11 +230,73 @@ fn get_formula_attribute(
     escape_xml(formula).to_string()
 }
 
+// The outlineLevel and collapsed attributes of a <row> or a <col>
+fn get_outline_attributes(outline_level: u8, collapsed: bool) -> String {
+    let mut attributes = String::new();
+    if outline_level > 0 {
+        attributes.push_str(&format!(" outlineLevel=\"{outline_level}\""));
+    }
+    if collapsed {
+        attributes.push_str(" collapsed=\"1\"");
+    }
+    attributes
+}
+
+// <sheetPr><outlinePr summaryBelow="0" summaryRight="1"/><pageSetUpPr fitToPage="1"/></sheetPr>
+fn get_sheet_pr_xml(worksheet: &Worksheet) -> String {
+    let mut sheet_pr = String::new();
+    let outline_properties = &worksheet.outline_properties;
+    if *outline_properties != OutlineProperties::default() {
+        sheet_pr.push_str(&format!(
+            "<outlinePr summaryBelow=\"{}\" summaryRight=\"{}\"/>",
+            i32::from(outline_properties.summary_below),
+            i32::from(outline_properties.summary_right)
+        ));
+    }
+    if worksheet.page_setup.fit_to_page {
+        sheet_pr.push_str("<pageSetUpPr fitToPage=\"1\"/>");
+    }
+    if sheet_pr.is_empty() {
+        return "".to_string();
+    }
+    format!("<sheetPr>{sheet_pr}</sheetPr>")
+}
+
+// Excel uses the maximum outline levels to make room for the outline symbols
+// <sheetFormatPr defaultRowHeight="14.5" outlineLevelRow="2" outlineLevelCol="1"/>
+fn get_sheet_format_pr_xml(worksheet: &Worksheet) -> String {
+    let outline_level_row = worksheet
+        .rows
+        .iter()
+        .map(|row| row.outline_level)
+        .max()
+        .unwrap_or(0);
+    let outline_level_col = worksheet
+        .cols
+        .iter()
+        .map(|col| col.outline_level)
+        .max()
+        .unwrap_or(0);
+    if outline_level_row == 0 && outline_level_col == 0 {
+        return "".to_string();
+    }
+    let mut attributes = String::new();
+    if outline_level_row > 0 {
+        attributes.push_str(&format!(" outlineLevelRow=\"{outline_level_row}\""));
+    }
+    if outline_level_col > 0 {
+        attributes.push_str(&format!(" outlineLevelCol=\"{outline_level_col}\""));
+    }
+    format!("<sheetFormatPr defaultRowHeight=\"14.5\"{attributes}/>")
+}
+
 pub(crate) fn get_worksheet_xml(
     worksheet: &Worksheet,
     parsed_formulas: &[(Node, StaticResult)],
     dimension: &str,
     is_sheet_selected: bool,
+    table_ids: &[usize],
+    dxfs: &[Dxf],
 ) -> String {
     let mut sheet_data_str: Vec<String> = vec![];
     let mut cols_str: Vec<String> = vec![];
@@ -180,8 +313,9 @@ pub(crate) fn get_worksheet_xml(
             None => "".to_string(),
         };
         let hidden = if col.hidden { " hidden=\"1\"" } else { "" };
+        let outline = get_outline_attributes(col.outline_level, col.collapsed);
         cols_str.push(format!(
-            "<col min=\"{min}\" max=\"{max}\" width=\"{width}\" customWidth=\"{custom_width}\"{column_style}{hidden}/>"
+            "<col min=\"{min}\" max=\"{max}\" width=\"{width}\" customWidth=\"{custom_width}\"{column_style}{hidden}{outline}/>"
         ));
     }
 
@@ -199,7 +333,16 @@ pub(crate) fn get_worksheet_xml(
         row_style_dict.insert(row.r, row.clone());
     }
 
-    for (row_index, row_data) in worksheet.sheet_data.iter().sorted_by_key(|x| x.0) {
+    // Rows without cells are still written if they have properties, like an outline level
+    let empty_row = HashMap::new();
+    let row_indices = worksheet
+        .sheet_data
+        .keys()
+        .chain(row_style_dict.keys())
+        .sorted()
+        .dedup();
+    for row_index in row_indices {
+        let row_data = worksheet.sheet_data.get(row_index).unwrap_or(&empty_row);
         let mut row_data_str: Vec<String> = vec![];
         for (column_index, cell) in row_data.iter().sorted_by_key(|x| x.0) {
             let column_name = number_to_column(*column_index).unwrap();
@@ -539,12 +682,13 @@ pub(crate) fn get_worksheet_xml(
                     ""
                 };
                 format!(
-                    r#" s="{}" ht="{}" customHeight="{}" customFormat="{}"{}"#,
+                    r#" s="{}" ht="{}" customHeight="{}" customFormat="{}"{}{}"#,
                     row_style.s,
                     row_style.height,
                     i32::from(row_style.custom_height),
                     i32::from(row_style.custom_format),
                     hidden_str,
+                    get_outline_attributes(row_style.outline_level, row_style.collapsed),
                 )
             }
             None => "".to_string(),
@@ -650,27 +794,46 @@ pub(crate) fn get_worksheet_xml(
         format!(r#"<selection activeCell="{active_cell}" sqref="{sqref}"/>"#)
     };
 
+    let sheet_pr = get_sheet_pr_xml(worksheet);
+    let sheet_format_pr = get_sheet_format_pr_xml(worksheet);
+
     let (cf_sections, cf_ext_lst) =
         get_conditional_formatting_xml(&worksheet.conditional_formatting);
+    let data_validations_section = get_data_validations_xml(&worksheet.data_validations);
+    let sheet_protection_section = get_sheet_protection_xml(&worksheet.protection);
+    let auto_filter_section = get_auto_filter_xml(&worksheet.auto_filter, dxfs);
+    let sort_state_section = get_sort_state_xml(&worksheet.sort_state, dxfs);
 
     let hyperlinks_section = get_hyperlinks_section(worksheet);
+    let page_setup_section = get_page_setup_xml(&worksheet.page_setup);
+    let legacy_drawing_section = get_legacy_drawing_section(worksheet, table_ids);
+    let table_parts_section = get_table_parts_section(worksheet, table_ids);
 
     format!(
         "{XML_DECLARATION}\
 <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+  {sheet_pr}\
   <dimension ref=\"{dimension}\"/>\
   <sheetViews>\
     <sheetView workbookViewId=\"0\"{show_grid_lines}{tab_selected}>\
         {pane}\
     </sheetView>\
   </sheetViews>\
+  {sheet_format_pr}\
   {cols}\
   <sheetData>\
   {sheet_data}\
   </sheetData>\
+  {sheet_protection_section}\
+  {auto_filter_section}\
+  {sort_state_section}\
   {merge_cells_section}\
   {cf_sections}\
+  {data_validations_section}\
   {hyperlinks_section}\
+  {page_setup_section}\
+  {legacy_drawing_section}\
+  {table_parts_section}\
   {cf_ext_lst}\
 </worksheet>"
     )
@@ -3,3 +3,9 @@ pub(crate) const XML_DECLARATION: &str =
 
 pub(crate) const XML_WORKSHEET: &str =
     r#"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet"#;
+
+pub(crate) const XML_EXTERNAL_LINK: &str =
+    r#"http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLink"#;
+
+pub(crate) const XML_EXTERNAL_LINK_PATH: &str =
+    r#"http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLinkPath"#;
@@ -0,0 +1,144 @@
+use ironcalc_base::{
+    filter_types::{
+        AutoFilter, CustomFilter, DateGroupItem, DateTimeGrouping, DynamicFilter, Filter,
+        FilterColumn, FilterOperator,
+    },
+    types::Dxf,
+};
+use roxmltree::Node;
+
+use super::util::{get_bool, get_bool_false};
+
+fn get_u32(node: Node, name: &str) -> u32 {
+    node.attribute(name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+fn load_date_group_item(node: Node) -> Option<DateGroupItem> {
+    let grouping = match node.attribute("dateTimeGrouping")? {
+        "year" => DateTimeGrouping::Year,
+        "month" => DateTimeGrouping::Month,
+        "day" => DateTimeGrouping::Day,
+        "hour" => DateTimeGrouping::Hour,
+        "minute" => DateTimeGrouping::Minute,
+        "second" => DateTimeGrouping::Second,
+        _ => return None,
+    };
+    Some(DateGroupItem {
+        grouping,
+        year: node.attribute("year")?.parse().ok()?,
+        month: get_u32(node, "month"),
+        day: get_u32(node, "day"),
+        hour: get_u32(node, "hour"),
+        minute: get_u32(node, "minute"),
+        second: get_u32(node, "second"),
+    })
+}
+
+fn load_custom_filter(node: Node) -> Option<CustomFilter> {
+    let operator = match node.attribute("operator").unwrap_or("equal") {
+        "equal" => FilterOperator::Equal,
+        "notEqual" => FilterOperator::NotEqual,
+        "greaterThan" => FilterOperator::GreaterThan,
+        "greaterThanOrEqual" => FilterOperator::GreaterThanOrEqual,
+        "lessThan" => FilterOperator::LessThan,
+        "lessThanOrEqual" => FilterOperator::LessThanOrEqual,
+        _ => return None,
+    };
+    Some(CustomFilter {
+        operator,
+        value: node.attribute("val").unwrap_or("").to_string(),
+    })
+}
+
+fn load_filter(node: Node, dxfs: &[Dxf]) -> Option<Filter> {
+    match node.tag_name().name() {
+        "filters" => Some(Filter::Values {
+            values: node
+                .children()
+                .filter(|n| n.has_tag_name("filter"))
+                .filter_map(|n| n.attribute("val").map(|s| s.to_string()))
+                .collect(),
+            date_groups: node
+                .children()
+                .filter(|n| n.has_tag_name("dateGroupItem"))
+                .filter_map(load_date_group_item)
+                .collect(),
+            blank: get_bool_false(node, "blank"),
+        }),
+        "top10" => Some(Filter::Top10 {
+            top: get_bool(node, "top"),
+            percent: get_bool_false(node, "percent"),
+            value: node.attribute("val")?.parse().ok()?,
+        }),
+        "dynamicFilter" => {
+            let kind = match node.attribute("type")? {
+                "aboveAverage" => DynamicFilter::AboveAverage,
+                "belowAverage" => DynamicFilter::BelowAverage,
+                // Other dynamic filters, like dates relative to today, are not supported
+                _ => return None,
+            };
+            Some(Filter::Dynamic { kind })
+        }
+        "customFilters" => Some(Filter::Custom {
+            and: get_bool_false(node, "and"),
+            filters: node
+                .children()
+                .filter(|n| n.has_tag_name("customFilter"))
+                .filter_map(load_custom_filter)
+                .collect(),
+        }),
+        "colorFilter" => {
+            let dxf = dxfs.get(node.attribute("dxfId")?.parse::<usize>().ok()?)?;
+            if get_bool(node, "cellColor") {
+                Some(Filter::CellColor {
+                    color: dxf.fill.as_ref()?.color.clone(),
+                })
+            } else {
+                Some(Filter::FontColor {
+                    color: dxf.font.as_ref()?.color.clone(),
+                })
+            }
+        }
+        // Icon filters are not supported
+        _ => None,
+    }
+}
+
+/// Reads the criteria of the columns of an autofilter, of a sheet or of a table:
+/// ```xml
+/// <autoFilter ref="A1:C6">
+///   <filterColumn colId="0">
+///     <filters blank="1"><filter val="Apple"/></filters>
+///   </filterColumn>
+///   <filterColumn colId="2">
+///     <customFilters and="1">
+///       <customFilter operator="greaterThan" val="20"/>
+///       <customFilter operator="lessThan" val="50"/>
+///     </customFilters>
+///   </filterColumn>
+/// </autoFilter>
+/// ```
+pub(super) fn load_filter_columns(node: Node, dxfs: &[Dxf]) -> Vec<FilterColumn> {
+    node.children()
+        .filter(|n| n.has_tag_name("filterColumn"))
+        .filter_map(|n| {
+            let column = n.attribute("colId")?.parse().ok()?;
+            let filter = n
+                .children()
+                .filter(|child| child.is_element())
+                .find_map(|child| load_filter(child, dxfs))?;
+            Some(FilterColumn { column, filter })
+        })
+        .collect()
+}
+
+/// Reads the autofilter of the sheet, if any
+pub(super) fn load_auto_filter(ws: Node, dxfs: &[Dxf]) -> Option<AutoFilter> {
+    let node = ws.children().find(|n| n.has_tag_name("autoFilter"))?;
+    Some(AutoFilter {
+        range: node.attribute("ref")?.to_string(),
+        columns: load_filter_columns(node, dxfs),
+    })
+}
@@ -0,0 +1,170 @@
+use std::{collections::HashMap, io::Read};
+
+use ironcalc_base::types::{Comment, CommentReply, CommentThread};
+use roxmltree::Node;
+
+use crate::error::XlsxError;
+
+use super::util::{get_attribute, get_bool_false};
+
+/// Reads the comments (notes) of a sheet:
+/// ```xml
+/// <comments>
+///   <authors><author>Ann</author></authors>
+///   <commentList>
+///     <comment ref="B3" authorId="0"><text><r><t>Ann:</t></r><r><t>Hello</t></r></text></comment>
+///   </commentList>
+/// </comments>
+/// ```
+pub(super) fn load_comments<R: Read + std::io::Seek>(
+    archive: &mut zip::read::ZipArchive<R>,
+    path: &str,
+) -> Result<Vec<Comment>, XlsxError> {
+    let mut comments = Vec::new();
+    let mut file = archive.by_name(path)?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    let doc = roxmltree::Document::parse(&text)?;
+    let ws = doc
+        .root()
+        .first_child()
+        .ok_or_else(|| XlsxError::Xml("Corrupt XML structure".to_string()))?;
+    let authors: Vec<String> = ws
+        .children()
+        .filter(|n| n.has_tag_name("authors"))
+        .flat_map(|n| n.children().filter(|n| n.has_tag_name("author")))
+        .map(|n| n.text().unwrap_or_default().to_string())
+        .collect();
+    let comment_list = ws
+        .children()
+        .filter(|n| n.has_tag_name("commentList"))
+        .collect::<Vec<Node>>();
+    if comment_list.len() == 1 {
+        for comment in comment_list[0]
+            .children()
+            .filter(|n| n.has_tag_name("comment"))
+        {
+            let text = comment
+                .descendants()
+                .filter(|n| n.has_tag_name("t"))
+                .map(|n| n.text().unwrap_or_default().to_string())
+                .collect::<Vec<String>>()
+                .join("");
+            let cell_ref = get_attribute(&comment, "ref")?.to_string();
+            let author_name = comment
+                .attribute("authorId")
+                .and_then(|id| id.parse::<usize>().ok())
+                .and_then(|id| authors.get(id))
+                .cloned()
+                .unwrap_or_default();
+            comments.push(Comment {
+                text,
+                author_name,
+                author_id: None,
+                cell_ref,
+                thread: None,
+            });
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Reads the list of persons that wrote threaded comments (`xl/persons/person.xml`).
+/// Returns a map from the id of the person to their name.
+pub(super) fn load_persons<R: Read + std::io::Seek>(
+    archive: &mut zip::read::ZipArchive<R>,
+    path: &str,
+) -> Result<HashMap<String, String>, XlsxError> {
+    let mut persons = HashMap::new();
+    let mut file = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(persons),
+    };
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    let doc = roxmltree::Document::parse(&text)?;
+    for person in doc.descendants().filter(|n| n.has_tag_name("person")) {
+        let id = get_attribute(&person, "id")?.to_string();
+        let display_name = person.attribute("displayName").unwrap_or_default();
+        persons.insert(id, display_name.to_string());
+    }
+    Ok(persons)
+}
+
+/// Reads the threaded comments of a sheet. Replies point to the first comment of the thread:
+/// ```xml
+/// <ThreadedComments>
+///   <threadedComment ref="B3" dT="2024-03-05T10:20:30.00" personId="{...}" id="{A...}">
+///     <text>Is this right?</text>
+///   </threadedComment>
+///   <threadedComment ref="B3" dT="2024-03-05T11:00:00.00" personId="{...}" id="{B...}" parentId="{A...}">
+///     <text>Yes</text>
+///   </threadedComment>
+/// </ThreadedComments>
+/// ```
+pub(super) fn load_threaded_comments<R: Read + std::io::Seek>(
+    archive: &mut zip::read::ZipArchive<R>,
+    path: &str,
+    persons: &HashMap<String, String>,
+) -> Result<Vec<Comment>, XlsxError> {
+    let mut file = archive.by_name(path)?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    let doc = roxmltree::Document::parse(&text)?;
+    let mut comments: Vec<Comment> = Vec::new();
+    let mut replies = Vec::new();
+    for node in doc
+        .descendants()
+        .filter(|n| n.has_tag_name("threadedComment"))
+    {
+        let id = get_attribute(&node, "id")?.to_string();
+        let text = node
+            .children()
+            .filter(|n| n.has_tag_name("text"))
+            .map(|n| n.text().unwrap_or_default().to_string())
+            .collect::<Vec<String>>()
+            .join("");
+        let author_id = node.attribute("personId").map(|id| id.to_string());
+        let author_name = author_id
+            .as_ref()
+            .and_then(|id| persons.get(id))
+            .cloned()
+            .unwrap_or_default();
+        let date = node.attribute("dT").unwrap_or_default().to_string();
+        match node.attribute("parentId") {
+            Some(parent_id) => replies.push((
+                parent_id.to_string(),
+                CommentReply {
+                    id,
+                    text,
+                    author_name,
+                    author_id,
+                    date,
+                },
+            )),
+            None => comments.push(Comment {
+                text,
+                author_name,
+                author_id,
+                cell_ref: get_attribute(&node, "ref")?.to_string(),
+                thread: Some(CommentThread {
+                    id,
+                    date,
+                    done: get_bool_false(node, "done"),
+                    replies: vec![],
+                }),
+            }),
+        }
+    }
+    for (parent_id, reply) in replies {
+        if let Some(thread) = comments
+            .iter_mut()
+            .filter_map(|comment| comment.thread.as_mut())
+            .find(|thread| thread.id == parent_id)
+        {
+            thread.replies.push(reply);
+        }
+    }
+    Ok(comments)
+}
@@ -363,7 +363,7 @@ fn parse_x14_expression_rule(
     })
 }
 
-fn parse_operator(s: &str) -> Result<ValueOperator, XlsxError> {
+pub(super) fn parse_operator(s: &str) -> Result<ValueOperator, XlsxError> {
     match s {
         "equal" => Ok(ValueOperator::Equal),
         "greaterThan" => Ok(ValueOperator::GreaterThan),
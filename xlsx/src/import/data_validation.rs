@@ -0,0 +1,152 @@
+use ironcalc_base::{
+    cf_types::ValueOperator,
+    dv_types::{
+        DataValidation, DataValidationErrorStyle, DataValidationListSource, DataValidationRule,
+    },
+};
+use roxmltree::Node;
+
+use crate::error::XlsxError;
+
+use super::{conditional_formatting::parse_operator, util::get_bool_false};
+
+// The text of a <formula1> or <formula2> child. In the x14 extension the formula is in an <xm:f> node.
+fn get_formula(node: Node, tag_name: &str) -> Option<String> {
+    let formula = node.children().find(|n| n.has_tag_name(tag_name))?;
+    let text = match formula.children().find(|n| n.has_tag_name("f")) {
+        Some(f) => f.text(),
+        None => formula.text(),
+    };
+    text.map(|t| t.trim().to_string())
+}
+
+// A list of values in a formula is a string like "Yes,No"
+fn parse_list_source(formula: String) -> DataValidationListSource {
+    match formula
+        .strip_prefix('"')
+        .and_then(|formula| formula.strip_suffix('"'))
+    {
+        Some(values) => DataValidationListSource::Values(
+            values
+                .replace("\"\"", "\"")
+                .split(',')
+                .map(|value| value.to_string())
+                .collect(),
+        ),
+        None => DataValidationListSource::Formula(formula),
+    }
+}
+
+fn parse_data_validation(node: Node, range: String) -> Result<Option<DataValidation>, XlsxError> {
+    let formula1 = get_formula(node, "formula1");
+    let formula2 = get_formula(node, "formula2");
+    let operator = match node.attribute("operator") {
+        Some(operator) => parse_operator(operator)?,
+        None => ValueOperator::Between,
+    };
+    let formula2 = match operator {
+        ValueOperator::Between | ValueOperator::NotBetween => formula2,
+        _ => None,
+    };
+    let rule = match (node.attribute("type").unwrap_or("none"), formula1) {
+        ("list", Some(formula1)) => DataValidationRule::List {
+            source: parse_list_source(formula1),
+            // Note that showDropDown="1" hides the dropdown
+            show_dropdown: !get_bool_false(node, "showDropDown"),
+        },
+        ("whole", Some(formula1)) => DataValidationRule::WholeNumber {
+            operator,
+            formula1,
+            formula2,
+        },
+        ("decimal", Some(formula1)) => DataValidationRule::Decimal {
+            operator,
+            formula1,
+            formula2,
+        },
+        ("date", Some(formula1)) => DataValidationRule::Date {
+            operator,
+            formula1,
+            formula2,
+        },
+        ("time", Some(formula1)) => DataValidationRule::Time {
+            operator,
+            formula1,
+            formula2,
+        },
+        ("textLength", Some(formula1)) => DataValidationRule::TextLength {
+            operator,
+            formula1,
+            formula2,
+        },
+        ("custom", Some(formula)) => DataValidationRule::Custom { formula },
+        // "none" (any value) and rules without formulas
+        _ => return Ok(None),
+    };
+    let error_style = match node.attribute("errorStyle") {
+        Some("warning") => DataValidationErrorStyle::Warning,
+        Some("information") => DataValidationErrorStyle::Information,
+        _ => DataValidationErrorStyle::Stop,
+    };
+    Ok(Some(DataValidation {
+        range,
+        rule,
+        allow_blank: get_bool_false(node, "allowBlank"),
+        error_style,
+        show_input_message: get_bool_false(node, "showInputMessage"),
+        prompt_title: node.attribute("promptTitle").map(|s| s.to_string()),
+        prompt: node.attribute("prompt").map(|s| s.to_string()),
+        show_error_message: get_bool_false(node, "showErrorMessage"),
+        error_title: node.attribute("errorTitle").map(|s| s.to_string()),
+        error: node.attribute("error").map(|s| s.to_string()),
+    }))
+}
+
+/// Reads the data validation rules of a sheet:
+/// ```xml
+/// <dataValidations count="2">
+///   <dataValidation type="list" allowBlank="1" showErrorMessage="1" sqref="B2:B10">
+///     <formula1>"Yes,No"</formula1>
+///   </dataValidation>
+///   <dataValidation type="whole" operator="between" errorStyle="warning" sqref="C2:C10">
+///     <formula1>1</formula1>
+///     <formula2>10</formula2>
+///   </dataValidation>
+/// </dataValidations>
+/// ```
+/// Rules with formulas that reference other sheets may be in the x14 extension of the `<extLst>`,
+/// with the formulas in `<xm:f>` nodes and the range in an `<xm:sqref>` node.
+pub(super) fn load_data_validations(ws: Node) -> Result<Vec<DataValidation>, XlsxError> {
+    let mut data_validations = Vec::new();
+    for node in ws
+        .children()
+        .filter(|n| n.has_tag_name("dataValidations"))
+        .flat_map(|n| n.children().filter(|n| n.has_tag_name("dataValidation")))
+    {
+        let Some(range) = node.attribute("sqref") else {
+            continue;
+        };
+        if let Some(data_validation) = parse_data_validation(node, range.to_string())? {
+            data_validations.push(data_validation);
+        }
+    }
+    for node in ws
+        .children()
+        .filter(|n| n.has_tag_name("extLst"))
+        .flat_map(|n| n.children().filter(|n| n.has_tag_name("ext")))
+        .flat_map(|n| n.children().filter(|n| n.has_tag_name("dataValidations")))
+        .flat_map(|n| n.children().filter(|n| n.has_tag_name("dataValidation")))
+    {
+        let Some(range) = node
+            .children()
+            .find(|n| n.has_tag_name("sqref"))
+            .and_then(|n| n.text())
+        else {
+            continue;
+        };
+        if let Some(data_validation) = parse_data_validation(node, range.trim().to_string())? {
+            data_validations.push(data_validation);
+        }
+    }
+    Ok(data_validations)
+}
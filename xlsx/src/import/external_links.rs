@@ -0,0 +1,114 @@
+use std::{collections::HashMap, io::Read};
+
+use ironcalc_base::{
+    expressions::{token::get_error_by_english_name, utils::parse_reference_a1},
+    types::{ExternalLink, ExternalSheet, ExternalValue},
+};
+use roxmltree::Node;
+
+use crate::error::XlsxError;
+
+use super::{util::get_attribute, worksheets::Relationship};
+
+// <externalLink>
+//   <externalBook r:id="rId1">
+//     <sheetNames><sheetName val="Summary"/></sheetNames>
+//     <sheetDataSet>
+//       <sheetData sheetId="0">
+//         <row r="4"><cell r="C4"><v>100</v></cell></row>
+//       </sheetData>
+//     </sheetDataSet>
+//   </externalBook>
+// </externalLink>
+
+/// Reads the links to other workbooks, in the order of the `externalReference` elements in the workbook
+pub(super) fn load_external_links<R: Read + std::io::Seek>(
+    archive: &mut zip::read::ZipArchive<R>,
+    rels: &HashMap<String, Relationship>,
+    external_references: &[String],
+) -> Result<Vec<ExternalLink>, XlsxError> {
+    let mut external_links = Vec::new();
+    for id in external_references {
+        let rel = rels
+            .get(id)
+            .ok_or_else(|| XlsxError::Xml(format!("Missing relationship: {id}")))?;
+        // Target="externalLinks/externalLink1.xml"
+        let path = match rel.target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("xl/{}", rel.target),
+        };
+        external_links.push(load_external_link(archive, &path)?);
+    }
+    Ok(external_links)
+}
+
+fn load_external_link<R: Read + std::io::Seek>(
+    archive: &mut zip::read::ZipArchive<R>,
+    path: &str,
+) -> Result<ExternalLink, XlsxError> {
+    let mut text = String::new();
+    archive.by_name(path)?.read_to_string(&mut text)?;
+    let doc = roxmltree::Document::parse(&text)?;
+
+    let mut sheets: Vec<ExternalSheet> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("sheetName"))
+        .map(|node| {
+            Ok(ExternalSheet {
+                name: get_attribute(&node, "val")?.to_string(),
+                sheet_data: HashMap::new(),
+            })
+        })
+        .collect::<Result<Vec<ExternalSheet>, XlsxError>>()?;
+
+    let sheet_data_nodes: Vec<Node> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("sheetData"))
+        .collect();
+    for sheet_data in sheet_data_nodes {
+        let sheet_id = get_attribute(&sheet_data, "sheetId")?.parse::<usize>()?;
+        let sheet = sheets.get_mut(sheet_id).ok_or_else(|| {
+            XlsxError::Xml(format!("Invalid sheetId in external link: {sheet_id}"))
+        })?;
+        for cell in sheet_data.descendants().filter(|n| n.has_tag_name("cell")) {
+            let cell_ref = get_attribute(&cell, "r")?;
+            let reference = parse_reference_a1(cell_ref)
+                .ok_or_else(|| XlsxError::Xml(format!("Invalid cell reference: {cell_ref}")))?;
+            let value = match cell.children().find(|n| n.has_tag_name("v")) {
+                Some(v) => v.text().unwrap_or("").to_string(),
+                None => continue,
+            };
+            let value = match cell.attribute("t").unwrap_or("n") {
+                "b" => ExternalValue::Boolean(value == "1" || value == "true"),
+                "e" => match get_error_by_english_name(&value) {
+                    Some(error) => ExternalValue::Error(error),
+                    None => continue,
+                },
+                "str" | "s" | "inlineStr" => ExternalValue::Text(value),
+                _ => ExternalValue::Number(value.parse::<f64>()?),
+            };
+            sheet
+                .sheet_data
+                .entry(reference.row)
+                .or_default()
+                .insert(reference.column, value);
+        }
+    }
+
+    // The target of the link is in the rels file of the part:
+    // <Relationship Id="rId1" Type="...externalLinkPath" Target="Budget.xlsx" TargetMode="External"/>
+    let rels_path = match path.rsplit_once('/') {
+        Some((folder, file_name)) => format!("{folder}/_rels/{file_name}.rels"),
+        None => format!("_rels/{path}.rels"),
+    };
+    let mut target = String::new();
+    if let Ok(mut file) = archive.by_name(&rels_path) {
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        let doc = roxmltree::Document::parse(&text)?;
+        if let Some(rel) = doc.descendants().find(|n| n.has_tag_name("Relationship")) {
+            target = get_attribute(&rel, "Target")?.to_string();
+        }
+    }
+    Ok(ExternalLink { target, sheets })
+}
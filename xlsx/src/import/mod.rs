@@ -1,6 +1,13 @@
+mod auto_filter;
+mod comments;
 mod conditional_formatting;
+mod data_validation;
+mod external_links;
 mod metadata;
+mod page_setup;
+mod protection;
 pub(crate) mod shared_strings;
+mod sort;
 mod styles;
 mod tables;
 mod theme;
@@ -29,6 +36,7 @@ use crate::error::XlsxError;
 
 use shared_strings::read_shared_strings;
 
+use external_links::load_external_links;
 use metadata::load_metadata;
 use styles::load_styles;
 use util::get_attribute;
@@ -96,17 +104,17 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
 ) -> Result<Workbook, XlsxError> {
     let mut archive = zip::ZipArchive::new(reader)?;
 
-    let mut shared_strings = read_shared_strings(&mut archive)?;
     let mut workbook = load_workbook(&mut archive)?;
     let rels = load_relationships(&mut archive)?;
     let theme_path = resolve_theme_path(&rels);
     let theme = theme::load(&mut archive, theme_path.as_deref());
+    let mut shared_strings = read_shared_strings(&mut archive, &theme)?;
     let mut tables = HashMap::new();
     // Styles must be loaded before the worksheets: conditional-formatting rules
     // stored in x14 `extLst` extensions carry inline `<x14:dxf>` formats that we
     // append to `styles.dxfs`, referencing them back by index from the rule.
     let mut styles = load_styles(&mut archive, &theme)?;
-    let (worksheets, selected_sheet) = load_sheets(
+    let (mut worksheets, selected_sheet) = load_sheets(
         &mut archive,
         &rels,
         &workbook,
@@ -115,6 +123,7 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
         &theme,
         &mut styles.dxfs,
     )?;
+    page_setup::load_print_names(&mut workbook.defined_names, &mut worksheets);
     // reparse formulas in defined names, since they may refer to sheets and tables that have been loaded
     let worksheet_names = worksheets
         .iter()
@@ -123,6 +132,7 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
     for dn in &mut workbook.defined_names {
         dn.formula = reparse_formula_hack(&dn.formula, &worksheet_names)?;
     }
+    let external_links = load_external_links(&mut archive, &rels, &workbook.external_references)?;
     let metadata = match load_metadata(&mut archive) {
         Ok(metadata) => metadata,
         Err(_) => {
@@ -147,7 +157,8 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
         },
     );
     Ok(Workbook {
-        shared_strings,
+        shared_strings: shared_strings.strings,
+        rich_text: shared_strings.rich_text,
         defined_names: workbook.defined_names,
         worksheets,
         styles,
@@ -155,11 +166,15 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
         settings: WorkbookSettings {
             tz: tz.to_string(),
             locale: locale.to_string(),
+            iterative_calculation: workbook.iterative_calculation,
+            date_system: workbook.date_system,
         },
         metadata,
         tables,
         views,
         theme,
+        external_links,
+        protection: workbook.protection,
     })
 }
 
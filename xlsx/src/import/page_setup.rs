@@ -0,0 +1,201 @@
+use ironcalc_base::{
+    expressions::utils::{column_to_number, number_to_column, parse_reference_a1},
+    page_setup_types::{HeaderFooter, PageMargins, PageOrientation, PageSetup},
+    types::{DefinedName, Worksheet},
+};
+use roxmltree::Node;
+
+use super::util::get_bool_false;
+
+fn get_text(node: Node, tag_name: &str) -> String {
+    node.children()
+        .find(|n| n.has_tag_name(tag_name))
+        .and_then(|n| n.text())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn get_u32(node: Node, name: &str) -> Option<u32> {
+    node.attribute(name).and_then(|s| s.parse::<u32>().ok())
+}
+
+// <rowBreaks count="2" manualBreakCount="2">
+//   <brk id="20" max="16383" man="1"/>
+//   <brk id="40" max="16383" man="1"/>
+// </rowBreaks>
+fn load_breaks(ws: Node, tag_name: &str) -> Vec<i32> {
+    let Some(node) = ws.children().find(|n| n.has_tag_name(tag_name)) else {
+        return vec![];
+    };
+    node.children()
+        .filter(|n| n.has_tag_name("brk"))
+        .filter_map(|n| n.attribute("id")?.parse::<i32>().ok())
+        .filter(|&id| id > 0)
+        .collect()
+}
+
+/// Reads how the sheet is printed. The print area and titles are defined names, see
+/// [load_print_names].
+/// ```xml
+/// <printOptions horizontalCentered="1" gridLines="1"/>
+/// <pageMargins left="0.25" right="0.25" top="0.75" bottom="0.75" header="0.3" footer="0.3"/>
+/// <pageSetup paperSize="9" scale="80" orientation="landscape" r:id="rId1"/>
+/// <headerFooter><oddFooter>&amp;CPage &amp;P of &amp;N</oddFooter></headerFooter>
+/// ```
+pub(super) fn load_page_setup(ws: Node) -> PageSetup {
+    let mut page_setup = PageSetup::default();
+    let children = || ws.children().filter(|n| n.is_element());
+    if let Some(node) = children().find(|n| n.has_tag_name("sheetPr")) {
+        if let Some(page_setup_pr) = node.children().find(|n| n.has_tag_name("pageSetUpPr")) {
+            page_setup.fit_to_page = get_bool_false(page_setup_pr, "fitToPage");
+        }
+    }
+    if let Some(node) = children().find(|n| n.has_tag_name("printOptions")) {
+        page_setup.horizontal_centered = get_bool_false(node, "horizontalCentered");
+        page_setup.vertical_centered = get_bool_false(node, "verticalCentered");
+        page_setup.print_grid_lines = get_bool_false(node, "gridLines");
+        page_setup.print_headings = get_bool_false(node, "headings");
+    }
+    if let Some(node) = children().find(|n| n.has_tag_name("pageMargins")) {
+        let default = PageMargins::default();
+        let margin = |name: &str, default: f64| {
+            node.attribute(name)
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+        page_setup.margins = PageMargins {
+            left: margin("left", default.left),
+            right: margin("right", default.right),
+            top: margin("top", default.top),
+            bottom: margin("bottom", default.bottom),
+            header: margin("header", default.header),
+            footer: margin("footer", default.footer),
+        };
+    }
+    if let Some(node) = children().find(|n| n.has_tag_name("pageSetup")) {
+        page_setup.orientation = match node.attribute("orientation") {
+            Some("portrait") => PageOrientation::Portrait,
+            Some("landscape") => PageOrientation::Landscape,
+            _ => PageOrientation::Default,
+        };
+        if let Some(paper_size) = get_u32(node, "paperSize").filter(|&size| size > 0) {
+            page_setup.paper_size = paper_size;
+        }
+        if let Some(scale) = get_u32(node, "scale") {
+            page_setup.scale = scale.clamp(10, 400);
+        }
+        if let Some(fit_to_width) = get_u32(node, "fitToWidth") {
+            page_setup.fit_to_width = fit_to_width;
+        }
+        if let Some(fit_to_height) = get_u32(node, "fitToHeight") {
+            page_setup.fit_to_height = fit_to_height;
+        }
+        if get_bool_false(node, "useFirstPageNumber") {
+            page_setup.first_page_number = get_u32(node, "firstPageNumber");
+        }
+    }
+    if let Some(node) = children().find(|n| n.has_tag_name("headerFooter")) {
+        page_setup.header_footer = HeaderFooter {
+            odd_header: get_text(node, "oddHeader"),
+            odd_footer: get_text(node, "oddFooter"),
+            even_header: get_text(node, "evenHeader"),
+            even_footer: get_text(node, "evenFooter"),
+            first_header: get_text(node, "firstHeader"),
+            first_footer: get_text(node, "firstFooter"),
+            different_odd_even: get_bool_false(node, "differentOddEven"),
+            different_first: get_bool_false(node, "differentFirst"),
+        };
+    }
+    page_setup.row_breaks = load_breaks(ws, "rowBreaks");
+    page_setup.column_breaks = load_breaks(ws, "colBreaks");
+    page_setup.row_breaks.sort_unstable();
+    page_setup.column_breaks.sort_unstable();
+    page_setup
+}
+
+// Splits 'Sheet, 1'!$A$1:$B$2,Sheet2!$C$3 at the commas that are not in a quoted sheet name
+fn split_union(formula: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in formula.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            ',' if !quoted => {
+                parts.push(&formula[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&formula[start..]);
+    parts
+}
+
+// The reference of a part of a union without the sheet name and the dollar signs
+fn get_local_reference(part: &str) -> String {
+    let reference = match part.rfind('!') {
+        Some(index) => &part[index + 1..],
+        None => part,
+    };
+    reference.trim().replace('$', "")
+}
+
+// A1:F40 or A1
+fn parse_print_area_range(reference: &str) -> Option<String> {
+    let (start, end) = reference.split_once(':').unwrap_or((reference, reference));
+    let start = parse_reference_a1(start)?;
+    let end = parse_reference_a1(end)?;
+    Some(format!(
+        "{}{}:{}{}",
+        number_to_column(start.column)?,
+        start.row,
+        number_to_column(end.column)?,
+        end.row
+    ))
+}
+
+// 1:2 for rows or A:B for columns
+fn parse_title_range(reference: &str) -> Option<(bool, (i32, i32))> {
+    let (start, end) = reference.split_once(':')?;
+    if let (Ok(start), Ok(end)) = (start.parse::<i32>(), end.parse::<i32>()) {
+        return Some((true, (start, end)));
+    }
+    let start = column_to_number(start).ok()?;
+    let end = column_to_number(end).ok()?;
+    Some((false, (start, end)))
+}
+
+/// Moves the print areas and print titles, the defined names `_xlnm.Print_Area` and
+/// `_xlnm.Print_Titles`, to the page setup of their sheets
+pub(super) fn load_print_names(defined_names: &mut Vec<DefinedName>, worksheets: &mut [Worksheet]) {
+    defined_names.retain(|defined_name| {
+        let name = defined_name.name.to_lowercase();
+        if name != "_xlnm.print_area" && name != "_xlnm.print_titles" {
+            return true;
+        }
+        let Some(worksheet) = worksheets
+            .iter_mut()
+            .find(|ws| Some(ws.sheet_id) == defined_name.sheet_id)
+        else {
+            return true;
+        };
+        let page_setup = &mut worksheet.page_setup;
+        let formula = defined_name.formula.trim_start_matches('=');
+        for part in split_union(formula) {
+            let reference = get_local_reference(part);
+            if name == "_xlnm.print_area" {
+                if let Some(range) = parse_print_area_range(&reference) {
+                    page_setup.print_area.push(range);
+                }
+            } else {
+                match parse_title_range(&reference) {
+                    Some((true, rows)) => page_setup.print_title_rows = Some(rows),
+                    Some((false, columns)) => page_setup.print_title_columns = Some(columns),
+                    None => {}
+                }
+            }
+        }
+        false
+    });
+}
@@ -0,0 +1,89 @@
+use ironcalc_base::types::{PasswordHash, SheetProtection, WorkbookProtection};
+use roxmltree::Node;
+
+use super::util::{get_bool, get_bool_false};
+
+// The names of the password attributes: legacy password, algorithm, hash, salt and spin count
+type PasswordAttributes = [&'static str; 5];
+
+const SHEET_PASSWORD: PasswordAttributes = [
+    "password",
+    "algorithmName",
+    "hashValue",
+    "saltValue",
+    "spinCount",
+];
+
+const WORKBOOK_PASSWORD: PasswordAttributes = [
+    "workbookPassword",
+    "workbookAlgorithmName",
+    "workbookHashValue",
+    "workbookSaltValue",
+    "workbookSpinCount",
+];
+
+fn load_password(node: Node, attributes: PasswordAttributes) -> Option<PasswordHash> {
+    let [password, algorithm_name, hash_value, salt_value, spin_count] = attributes;
+    if let (Some(algorithm_name), Some(hash_value)) =
+        (node.attribute(algorithm_name), node.attribute(hash_value))
+    {
+        return Some(PasswordHash::Salted {
+            algorithm_name: algorithm_name.to_string(),
+            hash_value: hash_value.to_string(),
+            salt_value: node.attribute(salt_value).unwrap_or("").to_string(),
+            spin_count: node
+                .attribute(spin_count)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+        });
+    }
+    node.attribute(password).map(|hash| PasswordHash::Legacy {
+        hash: hash.to_string(),
+    })
+}
+
+/// Reads the protection of a sheet:
+/// ```xml
+/// <sheetProtection password="CBEB" sheet="1" objects="1" scenarios="1" formatCells="0"/>
+/// ```
+/// Note that the operation attributes are true when the operation is _not_ allowed.
+pub(super) fn load_sheet_protection(ws: Node) -> Option<SheetProtection> {
+    let node = ws.children().find(|n| n.has_tag_name("sheetProtection"))?;
+    if !get_bool_false(node, "sheet") {
+        return None;
+    }
+    Some(SheetProtection {
+        password: load_password(node, SHEET_PASSWORD),
+        select_locked_cells: !get_bool_false(node, "selectLockedCells"),
+        select_unlocked_cells: !get_bool_false(node, "selectUnlockedCells"),
+        format_cells: !get_bool(node, "formatCells"),
+        format_columns: !get_bool(node, "formatColumns"),
+        format_rows: !get_bool(node, "formatRows"),
+        insert_columns: !get_bool(node, "insertColumns"),
+        insert_rows: !get_bool(node, "insertRows"),
+        insert_hyperlinks: !get_bool(node, "insertHyperlinks"),
+        delete_columns: !get_bool(node, "deleteColumns"),
+        delete_rows: !get_bool(node, "deleteRows"),
+        sort: !get_bool(node, "sort"),
+        auto_filter: !get_bool(node, "autoFilter"),
+    })
+}
+
+/// Reads the protection of the workbook:
+/// ```xml
+/// <workbookProtection workbookPassword="CBEB" lockStructure="1"/>
+/// ```
+pub(super) fn load_workbook_protection(workbook: Node) -> Option<WorkbookProtection> {
+    let node = workbook
+        .children()
+        .find(|n| n.has_tag_name("workbookProtection"))?;
+    let protection = WorkbookProtection {
+        password: load_password(node, WORKBOOK_PASSWORD),
+        lock_structure: get_bool_false(node, "lockStructure"),
+        lock_windows: get_bool_false(node, "lockWindows"),
+    };
+    if !protection.lock_structure && !protection.lock_windows {
+        return None;
+    }
+    Some(protection)
+}
@@ -1,41 +1,146 @@
-use std::io::Read;
+use std::{collections::HashMap, io::Read};
 
+use ironcalc_base::{
+    rich_text_types::{get_rich_text_plain_text, RichTextFont, RichTextRun},
+    types::Theme,
+};
 use roxmltree::Node;
 
 use crate::error::XlsxError;
 
+use super::util::{get_bool, get_color};
+
+/// The shared strings of the workbook and the runs of those that are rich texts
+#[derive(Default)]
+pub(crate) struct SharedStrings {
+    pub(crate) strings: Vec<String>,
+    pub(crate) rich_text: HashMap<i32, Vec<RichTextRun>>,
+}
+
+impl SharedStrings {
+    /// Returns the index of the text, adding it if it is not in the list.
+    /// Rich texts with the same plain text are different strings.
+    pub(crate) fn get_or_insert(&mut self, text: &str) -> i32 {
+        let position = self
+            .strings
+            .iter()
+            .enumerate()
+            .position(|(index, s)| s == text && !self.rich_text.contains_key(&(index as i32)));
+        match position {
+            Some(index) => index as i32,
+            None => {
+                self.strings.push(text.to_string());
+                self.strings.len() as i32 - 1
+            }
+        }
+    }
+
+    /// Returns the index of the text of the runs, adding a new string if it is a rich text
+    pub(crate) fn get_or_insert_runs(&mut self, runs: Vec<RichTextRun>) -> i32 {
+        let text = get_rich_text_plain_text(&runs);
+        if runs.iter().all(|run| run.font.is_empty()) {
+            return self.get_or_insert(&text);
+        }
+        self.strings.push(text);
+        let index = self.strings.len() as i32 - 1;
+        self.rich_text.insert(index, runs);
+        index
+    }
+}
+
 /// Reads the list of shared strings in an Excel workbook
-/// Note than in IronCalc we lose _internal_ styling of a string
 /// See Section 18.4
 pub(crate) fn read_shared_strings<R: Read + std::io::Seek>(
     archive: &mut zip::read::ZipArchive<R>,
-) -> Result<Vec<String>, XlsxError> {
+    theme: &Theme,
+) -> Result<SharedStrings, XlsxError> {
     match archive.by_name("xl/sharedStrings.xml") {
         Ok(mut file) => {
             let mut text = String::new();
             file.read_to_string(&mut text)?;
-            read_shared_strings_from_string(&text)
+            read_shared_strings_from_string(&text, theme)
         }
-        Err(_e) => Ok(Vec::new()),
+        Err(_e) => Ok(SharedStrings::default()),
     }
 }
 
-fn read_shared_strings_from_string(text: &str) -> Result<Vec<String>, XlsxError> {
+fn read_shared_strings_from_string(text: &str, theme: &Theme) -> Result<SharedStrings, XlsxError> {
     let doc = roxmltree::Document::parse(text)?;
-    let mut shared_strings = Vec::new();
+    let mut shared_strings = SharedStrings::default();
     let nodes: Vec<Node> = doc.descendants().filter(|n| n.has_tag_name("si")).collect();
     for node in nodes {
-        let text = node
-            .descendants()
-            .filter(|n| n.has_tag_name("t"))
-            .map(|n| decode_xlsx_escapes(n.text().unwrap_or("")))
-            .collect::<Vec<String>>()
-            .join("");
-        shared_strings.push(text);
+        // The cells refer to the strings by position, rich texts are never merged
+        let runs = load_rich_text(node, theme)?;
+        let index = shared_strings.strings.len() as i32;
+        shared_strings.strings.push(get_rich_text_plain_text(&runs));
+        if runs.iter().any(|run| !run.font.is_empty()) {
+            shared_strings.rich_text.insert(index, runs);
+        }
     }
     Ok(shared_strings)
 }
 
+// <rPr><b/><sz val="11"/><color rgb="FFFF0000"/><rFont val="Inter"/></rPr>
+fn load_run_font(node: Node, theme: &Theme) -> Result<RichTextFont, XlsxError> {
+    let mut font = RichTextFont::default();
+    for feature in node.children() {
+        match feature.tag_name().name() {
+            "b" => font.b = Some(get_bool(feature, "val")),
+            "i" => font.i = Some(get_bool(feature, "val")),
+            "strike" => font.strike = Some(get_bool(feature, "val")),
+            // single, double, ... are all underlined in IronCalc
+            "u" => font.u = Some(feature.attribute("val") != Some("none")),
+            "sz" => {
+                font.sz = feature
+                    .attribute("val")
+                    .and_then(|val| val.parse::<f64>().ok())
+                    .map(|sz| sz.round() as i32)
+            }
+            "color" => font.color = get_color(feature, theme)?,
+            "rFont" => font.name = feature.attribute("val").map(|name| name.to_string()),
+            _ => {}
+        }
+    }
+    Ok(font)
+}
+
+/// Reads the runs of a shared string (`<si>`) or of an inline string (`<is>`).
+/// A plain text is a single run without font. Phonetic runs (`<rPh>`) are ignored.
+/// ```xml
+/// <si>
+///   <r><t>Hello</t></r>
+///   <r><rPr><b/><color rgb="FFFF0000"/></rPr><t xml:space="preserve"> World</t></r>
+/// </si>
+/// ```
+pub(crate) fn load_rich_text(node: Node, theme: &Theme) -> Result<Vec<RichTextRun>, XlsxError> {
+    let get_text = |n: Node| decode_xlsx_escapes(n.text().unwrap_or(""));
+    let mut runs = Vec::new();
+    for child in node.children() {
+        match child.tag_name().name() {
+            "t" => runs.push(RichTextRun {
+                text: get_text(child),
+                font: RichTextFont::default(),
+            }),
+            "r" => {
+                let text = child
+                    .children()
+                    .filter(|n| n.has_tag_name("t"))
+                    .map(get_text)
+                    .collect::<String>();
+                let font = match child.children().find(|n| n.has_tag_name("rPr")) {
+                    Some(properties) => load_run_font(properties, theme)?,
+                    None => RichTextFont::default(),
+                };
+                if !text.is_empty() {
+                    runs.push(RichTextRun { text, font });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(runs)
+}
+
 /// Decodes Excel's `_xXXXX_` escape sequences for characters that are invalid in XML 1.0.
 /// For example, `_x0001_` → U+0001 (SOH).
 pub(crate) fn decode_xlsx_escapes(s: &str) -> String {
@@ -79,6 +184,7 @@ pub(crate) fn decode_xlsx_escapes(s: &str) -> String {
 mod tests {
     #![allow(clippy::unwrap_used)]
     use super::*;
+    use ironcalc_base::types::Color;
 
     #[test]
     fn test_shared_strings() {
@@ -107,15 +213,42 @@ mod tests {
         </r>
     </si>
 </sst>"#;
-        let shared_strings = read_shared_strings_from_string(xml_string.trim()).unwrap();
+        let shared_strings =
+            read_shared_strings_from_string(xml_string.trim(), &Theme::default()).unwrap();
         assert_eq!(
-            shared_strings,
+            shared_strings.strings,
             [
                 "A string".to_string(),
                 "A second String".to_string(),
                 "Hello World".to_string()
             ]
         );
+        assert_eq!(shared_strings.rich_text.len(), 1);
+        let runs = &shared_strings.rich_text[&2];
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].text, "Hello");
+        assert!(runs[0].font.is_empty());
+        assert_eq!(runs[1].text, " World");
+        assert_eq!(runs[1].font.b, Some(true));
+        assert_eq!(runs[1].font.sz, Some(11));
+        assert_eq!(runs[1].font.color, Color::Rgb("#FF0000".to_string()));
+        assert_eq!(runs[1].font.name, Some("Inter".to_string()));
+    }
+
+    #[test]
+    fn test_shared_strings_phonetic_runs() {
+        let xml_string = r#"
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="1" uniqueCount="1">
+    <si>
+        <t>東京</t>
+        <rPh sb="0" eb="2"><t>トウキョウ</t></rPh>
+        <phoneticPr fontId="1"/>
+    </si>
+</sst>"#;
+        let shared_strings =
+            read_shared_strings_from_string(xml_string.trim(), &Theme::default()).unwrap();
+        assert_eq!(shared_strings.strings, ["東京".to_string()]);
+        assert!(shared_strings.rich_text.is_empty());
     }
 
     #[test]
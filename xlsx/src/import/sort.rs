@@ -0,0 +1,53 @@
+use ironcalc_base::{
+    sort_types::{SortKey, SortOn, SortState},
+    types::Dxf,
+};
+use roxmltree::Node;
+
+use super::{util::get_bool_false, worksheets::parse_range};
+
+fn load_sort_condition(node: Node, left_to_right: bool, dxfs: &[Dxf]) -> Option<SortKey> {
+    let (row, column, _, _) = parse_range(node.attribute("ref")?).ok()?;
+    let dxf = node
+        .attribute("dxfId")
+        .and_then(|id| id.parse::<usize>().ok())
+        .and_then(|id| dxfs.get(id));
+    let sort_on = match node.attribute("sortBy") {
+        Some("cellColor") => SortOn::CellColor(dxf?.fill.as_ref()?.color.clone()),
+        Some("fontColor") => SortOn::FontColor(dxf?.font.as_ref()?.color.clone()),
+        // Sorting by icon is not supported
+        Some("icon") => return None,
+        _ => match node.attribute("customList") {
+            Some(list) => SortOn::CustomList(list.split(',').map(|s| s.to_string()).collect()),
+            None => SortOn::Value,
+        },
+    };
+    Some(SortKey {
+        index: if left_to_right { row } else { column },
+        descending: get_bool_false(node, "descending"),
+        sort_on,
+    })
+}
+
+/// Reads the last sort of the sheet:
+/// ```xml
+/// <sortState ref="A2:C6">
+///   <sortCondition descending="1" ref="B2:B6"/>
+///   <sortCondition sortBy="cellColor" dxfId="0" ref="A2:A6"/>
+/// </sortState>
+/// ```
+pub(super) fn load_sort_state(ws: Node, dxfs: &[Dxf]) -> Option<SortState> {
+    let node = ws.children().find(|n| n.has_tag_name("sortState"))?;
+    let range = node.attribute("ref")?.to_string();
+    let left_to_right = get_bool_false(node, "columnSort");
+    let keys = node
+        .children()
+        .filter(|n| n.has_tag_name("sortCondition"))
+        .filter_map(|n| load_sort_condition(n, left_to_right, dxfs))
+        .collect();
+    Some(SortState {
+        range,
+        left_to_right,
+        keys,
+    })
+}
@@ -2,7 +2,8 @@ use std::{collections::HashMap, io::Read};
 
 use ironcalc_base::types::{
     Alignment, Border, BorderItem, BorderStyle, CellStyleXfs, CellStyles, CellXfs, Color, Dxf,
-    DxfFont, Fill, Font, FontScheme, HorizontalAlignment, NumFmt, Styles, Theme, VerticalAlignment,
+    DxfFont, Fill, Font, FontScheme, GradientFill, GradientStop, GradientType, HorizontalAlignment,
+    NumFmt, PatternType, Protection, ReadingOrder, Styles, Theme, VerticalAlignment,
 };
 use roxmltree::Node;
 
@@ -85,6 +86,159 @@ fn get_border(
     Ok(Some(BorderItem { style, color }))
 }
 
+fn get_f64(node: Node, name: &str) -> f64 {
+    node.attribute(name)
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(0.0)
+}
+
+// <gradientFill type="path" left="0.5" right="0.5" top="0.5" bottom="0.5">
+//   <stop position="0"><color theme="0"/></stop>
+//   <stop position="1"><color theme="4"/></stop>
+// </gradientFill>
+fn load_gradient_fill(
+    node: Node,
+    theme: &Theme,
+    indexed: Option<&[String]>,
+) -> Result<GradientFill, XlsxError> {
+    let mut stops = Vec::new();
+    for stop in node.children().filter(|n| n.has_tag_name("stop")) {
+        let color = match stop.children().find(|n| n.has_tag_name("color")) {
+            Some(color) => get_color_indexed(color, theme, indexed)?,
+            None => Color::None,
+        };
+        stops.push(GradientStop {
+            position: get_f64(stop, "position"),
+            color,
+        });
+    }
+    Ok(GradientFill {
+        r#type: match node.attribute("type") {
+            Some("path") => GradientType::Path,
+            _ => GradientType::Linear,
+        },
+        degree: get_f64(node, "degree"),
+        left: get_f64(node, "left"),
+        right: get_f64(node, "right"),
+        top: get_f64(node, "top"),
+        bottom: get_f64(node, "bottom"),
+        stops,
+    })
+}
+
+// <fill>
+//   <patternFill patternType="lightUp"><fgColor rgb="FF000000"/><bgColor rgb="FFFFFF00"/></patternFill>
+// </fill>
+// In differential formats a pattern fill without type is a solid fill
+fn load_fill(
+    node: Node,
+    theme: &Theme,
+    indexed: Option<&[String]>,
+    is_dxf: bool,
+) -> Result<Fill, XlsxError> {
+    if let Some(gradient_fill) = node.children().find(|n| n.has_tag_name("gradientFill")) {
+        return Ok(Fill {
+            gradient: Some(load_gradient_fill(gradient_fill, theme, indexed)?),
+            ..Default::default()
+        });
+    }
+    let Some(pattern_fill) = node.children().find(|n| n.has_tag_name("patternFill")) else {
+        return Ok(Fill::default());
+    };
+    let pattern_type = match pattern_fill.attribute("patternType") {
+        Some(name) => name,
+        None if is_dxf => "solid",
+        None => "none",
+    };
+    let Some(pattern) = PatternType::from_name(pattern_type) else {
+        return Ok(Fill::default());
+    };
+    let mut fg_color = Color::None;
+    let mut bg_color = Color::None;
+    for feature in pattern_fill.children().filter(|n| n.is_element()) {
+        match feature.tag_name().name() {
+            "fgColor" => {
+                fg_color = get_color_indexed(feature, theme, indexed)?;
+            }
+            "bgColor" => {
+                bg_color = get_color_indexed(feature, theme, indexed)?;
+            }
+            _ => {
+                println!("Unexpected pattern");
+                dbg!(feature);
+            }
+        }
+    }
+    if pattern == PatternType::Solid {
+        // Prefer fgColor (solid fill convention); fall back to bgColor
+        return Ok(Fill {
+            color: if fg_color.is_some() {
+                fg_color
+            } else {
+                bg_color
+            },
+            ..Default::default()
+        });
+    }
+    Ok(Fill {
+        color: fg_color,
+        pattern,
+        bg_color,
+        gradient: None,
+    })
+}
+
+// <alignment horizontal="left" vertical="center" wrapText="1" textRotation="90" indent="2"/>
+fn load_alignment(node: Node) -> Alignment {
+    let horizontal = match node.attribute("horizontal") {
+        Some("center") => HorizontalAlignment::Center,
+        Some("centerContinuous") => HorizontalAlignment::CenterContinuous,
+        Some("distributed") => HorizontalAlignment::Distributed,
+        Some("fill") => HorizontalAlignment::Fill,
+        Some("general") => HorizontalAlignment::General,
+        Some("justify") => HorizontalAlignment::Justify,
+        Some("left") => HorizontalAlignment::Left,
+        Some("right") => HorizontalAlignment::Right,
+        // TODO: Should we fail in this case or set the alignment to default?
+        Some(_) => HorizontalAlignment::default(),
+        None => HorizontalAlignment::default(),
+    };
+
+    let vertical = match node.attribute("vertical") {
+        Some("bottom") => VerticalAlignment::Bottom,
+        Some("center") => VerticalAlignment::Center,
+        Some("distributed") => VerticalAlignment::Distributed,
+        Some("justify") => VerticalAlignment::Justify,
+        Some("top") => VerticalAlignment::Top,
+        // TODO: Should we fail in this case or set the alignment to default?
+        Some(_) => VerticalAlignment::default(),
+        None => VerticalAlignment::default(),
+    };
+
+    let text_rotation = get_number(node, "textRotation");
+    let indent = get_number(node, "indent");
+    let reading_order = match get_number(node, "readingOrder") {
+        1 => ReadingOrder::LeftToRight,
+        2 => ReadingOrder::RightToLeft,
+        _ => ReadingOrder::ContextDependent,
+    };
+
+    Alignment {
+        horizontal,
+        vertical,
+        wrap_text: get_bool_false(node, "wrapText"),
+        text_rotation: if (0..=180).contains(&text_rotation) || text_rotation == 255 {
+            text_rotation
+        } else {
+            0
+        },
+        indent: indent.clamp(0, 250),
+        shrink_to_fit: get_bool_false(node, "shrinkToFit"),
+        reading_order,
+        justify_last_line: get_bool_false(node, "justifyLastLine"),
+    }
+}
+
 pub(super) fn load_styles<R: Read + std::io::Seek>(
     archive: &mut zip::read::ZipArchive<R>,
     theme: &Theme,
@@ -211,42 +365,7 @@ pub(super) fn load_styles<R: Read + std::io::Seek>(
         .filter(|n| n.has_tag_name("fills"))
         .collect::<Vec<Node>>()[0];
     for fill in fill_nodes.children() {
-        let pattern_fill = fill
-            .children()
-            .filter(|n| n.has_tag_name("patternFill"))
-            .collect::<Vec<Node>>();
-        if pattern_fill.len() != 1 {
-            // safety belt
-            // Some fills do not have a patternFill, but they have gradientFill
-            fills.push(Fill::default());
-            continue;
-        }
-        let pattern_fill = pattern_fill[0];
-
-        let mut fg_color = Color::None;
-        let mut bg_color = Color::None;
-        for feature in pattern_fill.children() {
-            match feature.tag_name().name() {
-                "fgColor" => {
-                    fg_color = get_color_indexed(feature, theme, indexed)?;
-                }
-                "bgColor" => {
-                    bg_color = get_color_indexed(feature, theme, indexed)?;
-                }
-                _ => {
-                    println!("Unexpected pattern");
-                    dbg!(feature);
-                }
-            }
-        }
-        // Prefer fgColor (solid fill convention); fall back to bgColor
-        fills.push(Fill {
-            color: if fg_color.is_some() {
-                fg_color
-            } else {
-                bg_color
-            },
-        })
+        fills.push(load_fill(fill, theme, indexed, false)?);
     }
 
     let mut borders = Vec::new();
@@ -361,43 +480,20 @@ pub(super) fn load_styles<R: Read + std::io::Seek>(
             .filter(|n| n.has_tag_name("alignment"))
             .collect::<Vec<Node>>();
         let alignment = if alignment_nodes.len() == 1 {
-            let alignment_node = alignment_nodes[0];
-            let wrap_text = get_bool_false(alignment_node, "wrapText");
-
-            let horizontal = match alignment_node.attribute("horizontal") {
-                Some("center") => HorizontalAlignment::Center,
-                Some("centerContinuous") => HorizontalAlignment::CenterContinuous,
-                Some("distributed") => HorizontalAlignment::Distributed,
-                Some("fill") => HorizontalAlignment::Fill,
-                Some("general") => HorizontalAlignment::General,
-                Some("justify") => HorizontalAlignment::Justify,
-                Some("left") => HorizontalAlignment::Left,
-                Some("right") => HorizontalAlignment::Right,
-                // TODO: Should we fail in this case or set the alignment to default?
-                Some(_) => HorizontalAlignment::default(),
-                None => HorizontalAlignment::default(),
-            };
-
-            let vertical = match alignment_node.attribute("vertical") {
-                Some("bottom") => VerticalAlignment::Bottom,
-                Some("center") => VerticalAlignment::Center,
-                Some("distributed") => VerticalAlignment::Distributed,
-                Some("justify") => VerticalAlignment::Justify,
-                Some("top") => VerticalAlignment::Top,
-                // TODO: Should we fail in this case or set the alignment to default?
-                Some(_) => VerticalAlignment::default(),
-                None => VerticalAlignment::default(),
-            };
-
-            Some(Alignment {
-                horizontal,
-                vertical,
-                wrap_text,
-            })
+            Some(load_alignment(alignment_nodes[0]))
         } else {
             None
         };
 
+        // <protection locked="0" hidden="1"/>
+        let protection =
+            xfs.children()
+                .find(|n| n.has_tag_name("protection"))
+                .map(|protection_node| Protection {
+                    locked: get_bool(protection_node, "locked"),
+                    hidden: get_bool_false(protection_node, "hidden"),
+                });
+
         cell_xfs.push(CellXfs {
             xf_id,
             num_fmt_id,
@@ -412,6 +508,7 @@ pub(super) fn load_styles<R: Read + std::io::Seek>(
             apply_fill,
             quote_prefix,
             alignment,
+            protection,
         });
     }
 
@@ -500,30 +597,7 @@ pub(super) fn parse_dxf(
                 font = Some(f);
             }
             "fill" => {
-                let pattern_fill_nodes = child
-                    .children()
-                    .filter(|n| n.has_tag_name("patternFill"))
-                    .collect::<Vec<Node>>();
-                if pattern_fill_nodes.len() == 1 {
-                    let pf = pattern_fill_nodes[0];
-                    let mut fg_color = Color::None;
-                    let mut bg_color = Color::None;
-                    for feat in pf.children() {
-                        match feat.tag_name().name() {
-                            "fgColor" => fg_color = get_color_indexed(feat, theme, indexed)?,
-                            "bgColor" => bg_color = get_color_indexed(feat, theme, indexed)?,
-                            _ => {}
-                        }
-                    }
-                    // Prefer fgColor (solid fill convention); fall back to bgColor
-                    fill = Some(Fill {
-                        color: if fg_color.is_some() {
-                            fg_color
-                        } else {
-                            bg_color
-                        },
-                    });
-                }
+                fill = Some(load_fill(child, theme, indexed, true)?);
             }
             "border" => {
                 let left = get_border(child, "left", theme, indexed)?;
@@ -550,25 +624,7 @@ pub(super) fn parse_dxf(
                 });
             }
             "alignment" => {
-                let wrap_text = matches!(child.attribute("wrapText"), Some("1"));
-                let horizontal = match child.attribute("horizontal") {
-                    Some("center") => HorizontalAlignment::Center,
-                    Some("left") => HorizontalAlignment::Left,
-                    Some("right") => HorizontalAlignment::Right,
-                    Some("justify") => HorizontalAlignment::Justify,
-                    _ => HorizontalAlignment::default(),
-                };
-                let vertical = match child.attribute("vertical") {
-                    Some("bottom") => VerticalAlignment::Bottom,
-                    Some("center") => VerticalAlignment::Center,
-                    Some("top") => VerticalAlignment::Top,
-                    _ => VerticalAlignment::default(),
-                };
-                alignment = Some(Alignment {
-                    horizontal,
-                    vertical,
-                    wrap_text,
-                });
+                alignment = Some(load_alignment(child));
             }
             _ => {}
         }
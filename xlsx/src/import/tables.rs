@@ -1,10 +1,11 @@
 use std::io::Read;
 
-use ironcalc_base::types::{Table, TableColumn, TableStyleInfo};
+use ironcalc_base::types::{Dxf, Table, TableColumn, TableStyleInfo};
 use roxmltree::Node;
 
 use crate::error::XlsxError;
 
+use super::auto_filter::load_filter_columns;
 use super::util::{get_bool, get_bool_false};
 
 // <table name="Table" displayName="Table" totalsRowCount ref="A1:D6">
@@ -15,6 +16,10 @@ use super::util::{get_bool, get_bool_false};
 //   </autoFilter>
 //   <tableColumns count="5">
 //      <tableColumn name="Monday" totalsRowFunction="sum" />
+//      <tableColumn name="Tuesday" totalsRowFunction="custom">
+//          <calculatedColumnFormula>Table[[#This Row],[Monday]]*2</calculatedColumnFormula>
+//          <totalsRowFormula>MAX(Table[Tuesday])</totalsRowFormula>
+//      </tableColumn>
 //      ...
 //   </tableColumns>
 //   <tableStyleInfo name="TableStyle5"/>
@@ -25,6 +30,7 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
     archive: &mut zip::read::ZipArchive<R>,
     path: &str,
     sheet_name: &str,
+    dxfs: &[Dxf],
 ) -> Result<Table, XlsxError> {
     let mut file = archive.by_name(path)?;
     let mut text = String::new();
@@ -45,7 +51,7 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
         .to_string();
 
     let display_name = table
-        .attribute("name")
+        .attribute("displayName")
         .ok_or_else(|| {
             XlsxError::Xml("Corrupt XML structure: missing table display name".to_string())
         })?
@@ -80,8 +86,8 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
         None
     };
 
-    // style index of the header row of the table
-    let data_dxf_id = if let Some(index_str) = table.attribute("headerRowDxfId") {
+    // style index of the data of the table
+    let data_dxf_id = if let Some(index_str) = table.attribute("dataDxfId") {
         index_str.parse::<u32>().ok()
     } else {
         None
@@ -103,16 +109,12 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
     // TODO: Conformant implementations should panic if header_row_dxf_id or data_dxf_id are out of bounds.
 
     // Note that filters are non dynamic
-    // The only thing important for us is whether or not it has filters
-    let auto_filter = table
-        .descendants()
-        .filter(|n| n.has_tag_name("autoFilter"))
-        .collect::<Vec<Node>>();
-
-    let has_filters = if let Some(filter) = auto_filter.first() {
-        filter.children().count() > 0
-    } else {
-        false
+    // The criteria of the filters, by column
+    let auto_filter = table.children().find(|n| n.has_tag_name("autoFilter"));
+    let has_filters = auto_filter.is_some_and(|filter| filter.children().count() > 0);
+    let filter_columns = match auto_filter {
+        Some(auto_filter) => load_filter_columns(auto_filter, dxfs),
+        None => Vec::new(),
     };
 
     // tableColumn
@@ -139,8 +141,8 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
             None
         };
 
-        // style index of the header row of the table column
-        let data_dxf_id = if let Some(index_str) = table_column.attribute("headerRowDxfId") {
+        // style index of the data of the table column
+        let data_dxf_id = if let Some(index_str) = table_column.attribute("dataDxfId") {
             index_str.parse::<u32>().ok()
         } else {
             None
@@ -157,21 +159,47 @@ pub(crate) fn load_table<R: Read + std::io::Seek>(
         // Missing in Calc: styles can also be defined via a name:
         // headerRowCellStyle, dataCellStyle, totalsRowCellStyle
 
+        // Either a text (totalsRowLabel) or a function (totalsRowFunction) in the totals row
+        let totals_row_label = table_column
+            .attribute("totalsRowLabel")
+            .map(|s| s.to_string());
+        let totals_row_function = table_column
+            .attribute("totalsRowFunction")
+            .map(|s| s.to_string());
+
+        // The formula of a "custom" totals row function and the one of a calculated column
+        let child_text = |tag_name: &str| {
+            table_column
+                .children()
+                .find(|n| n.has_tag_name(tag_name))
+                .and_then(|n| n.text())
+                .map(|s| s.to_string())
+        };
+        let totals_row_formula = child_text("totalsRowFormula");
+        let calculated_column_formula = child_text("calculatedColumnFormula");
+
+        let filter = filter_columns
+            .iter()
+            .find(|filter_column| filter_column.column as usize == columns.len())
+            .map(|filter_column| filter_column.filter.clone());
         columns.push(TableColumn {
+            filter,
             id,
             name: column_name.to_string(),
-            totals_row_label: None,
+            totals_row_label,
             header_row_dxf_id,
             data_dxf_id,
-            totals_row_function: None,
+            totals_row_function,
+            totals_row_formula,
+            calculated_column_formula,
             totals_row_dxf_id,
         });
     }
 
-    // tableInfo
+    // tableStyleInfo
     let table_info = table
         .descendants()
-        .filter(|n| n.has_tag_name("tableInfo"))
+        .filter(|n| n.has_tag_name("tableStyleInfo"))
         .collect::<Vec<Node>>();
     let style_info = match table_info.first() {
         Some(node) => {
@@ -1,11 +1,12 @@
 use std::io::Read;
 
-use ironcalc_base::types::{DefinedName, SheetState};
+use ironcalc_base::types::{DateSystem, DefinedName, IterativeCalculation, SheetState};
 use roxmltree::Node;
 
 use crate::error::XlsxError;
 
 use super::{
+    protection::load_workbook_protection,
     util::get_attribute,
     worksheets::{Sheet, WorkbookXML},
 };
@@ -71,9 +72,51 @@ pub(super) fn load_workbook<R: Read + std::io::Seek>(
             sheet_id,
         })
     }
+    // Calculation properties
+    // <calcPr calcId="191029" iterate="1" iterateCount="50" iterateDelta="0.0001"/>
+    let mut iterative_calculation = IterativeCalculation::default();
+    if let Some(calc_pr) = doc.descendants().find(|n| n.has_tag_name("calcPr")) {
+        iterative_calculation.enabled =
+            matches!(calc_pr.attribute("iterate"), Some("1") | Some("true"));
+        if let Some(count) = calc_pr.attribute("iterateCount") {
+            iterative_calculation.max_iterations = count.parse::<i32>()?;
+        }
+        if let Some(delta) = calc_pr.attribute("iterateDelta") {
+            iterative_calculation.max_change = delta.parse::<f64>()?;
+        }
+    }
+    // <workbookPr date1904="1"/>
+    let date_system = match doc.descendants().find(|n| n.has_tag_name("workbookPr")) {
+        Some(workbook_pr)
+            if matches!(workbook_pr.attribute("date1904"), Some("1") | Some("true")) =>
+        {
+            DateSystem::Date1904
+        }
+        _ => DateSystem::Date1900,
+    };
+    // <externalReferences><externalReference r:id="rId4"/></externalReferences>
+    // The n-th external reference is the workbook [n] in formulas
+    let external_references = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("externalReference"))
+        .map(|node| {
+            get_attribute(
+                &node,
+                (
+                    "http://schemas.openxmlformats.org/officeDocument/2006/relationships",
+                    "id",
+                ),
+            )
+            .map(|id| id.to_string())
+        })
+        .collect::<Result<Vec<String>, XlsxError>>()?;
     // read the relationships file
     Ok(WorkbookXML {
         worksheets: sheets,
         defined_names,
+        iterative_calculation,
+        date_system,
+        external_references,
+        protection: load_workbook_protection(doc.root_element()),
     })
 }
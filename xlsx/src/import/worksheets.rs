@@ -12,9 +12,12 @@ use ironcalc_base::{
         types::CellReferenceRC,
         utils::{column_to_number, parse_reference_a1},
     },
+    outline_types::{OutlineProperties, MAX_OUTLINE_LEVEL},
+    rich_text_types::{get_rich_text_plain_text, RichTextRun},
     types::{
-        ArrayKind, Cell, Col, Color, Comment, DefinedName, Dxf, FormulaValue, Link, Row, SheetData,
-        SheetState, SpillValue, Table, Theme, Worksheet, WorksheetView,
+        ArrayKind, Cell, Col, Color, Comment, DateSystem, DefinedName, Dxf, FormulaValue,
+        IterativeCalculation, Link, Row, SheetData, SheetState, SpillValue, Table, Theme,
+        WorkbookProtection, Worksheet, WorksheetView,
     },
 };
 use roxmltree::Node;
@@ -23,10 +26,16 @@ use thiserror::Error;
 use crate::error::XlsxError;
 
 use super::{
+    auto_filter::load_auto_filter,
+    comments::{load_comments, load_persons, load_threaded_comments},
     conditional_formatting::load_conditional_formatting,
-    shared_strings::decode_xlsx_escapes,
+    data_validation::load_data_validations,
+    page_setup::load_page_setup,
+    protection::load_sheet_protection,
+    shared_strings::{decode_xlsx_escapes, load_rich_text, SharedStrings},
+    sort::load_sort_state,
     tables::load_table,
-    util::{get_attribute, get_bool_false, get_color, get_number},
+    util::{get_attribute, get_bool, get_bool_false, get_color, get_number},
 };
 
 pub(crate) struct Sheet {
@@ -39,6 +48,11 @@ pub(crate) struct Sheet {
 pub(crate) struct WorkbookXML {
     pub(crate) worksheets: Vec<Sheet>,
     pub(crate) defined_names: Vec<DefinedName>,
+    pub(crate) iterative_calculation: IterativeCalculation,
+    pub(crate) date_system: DateSystem,
+    /// Relationship ids of the external links, in order
+    pub(crate) external_references: Vec<String>,
+    pub(crate) protection: Option<WorkbookProtection>,
 }
 
 pub(crate) struct Relationship {
@@ -77,7 +91,7 @@ fn parse_cell_reference(cell: &str) -> Result<(i32, i32), String> {
     }
 }
 
-fn parse_range(range: &str) -> Result<(i32, i32, i32, i32), String> {
+pub(super) fn parse_range(range: &str) -> Result<(i32, i32, i32, i32), String> {
     let parts: Vec<&str> = range.split(':').collect();
     if parts.len() == 1 {
         if let Some(r) = parse_reference_a1(parts[0]) {
@@ -152,6 +166,8 @@ fn load_columns(ws: Node) -> Result<Vec<Col>, XlsxError> {
             let style = col
                 .attribute("style")
                 .map(|s| s.parse::<i32>().unwrap_or(0));
+            let outline_level = load_outline_level(col);
+            let collapsed = get_bool_false(col, "collapsed");
             cols.push(Col {
                 min,
                 max,
@@ -159,6 +175,8 @@ fn load_columns(ws: Node) -> Result<Vec<Col>, XlsxError> {
                 custom_width,
                 style,
                 hidden,
+                outline_level,
+                collapsed,
             })
         }
     }
@@ -205,44 +223,29 @@ fn load_sheet_color(ws: Node, theme: &Theme) -> Result<Color, XlsxError> {
     Ok(color)
 }
 
-fn load_comments<R: Read + std::io::Seek>(
-    archive: &mut zip::read::ZipArchive<R>,
-    path: &str,
-) -> Result<Vec<Comment>, XlsxError> {
-    let mut comments = Vec::new();
-    let mut file = archive.by_name(path)?;
-    let mut text = String::new();
-    file.read_to_string(&mut text)?;
-    let doc = roxmltree::Document::parse(&text)?;
-    let ws = doc
-        .root()
-        .first_child()
-        .ok_or_else(|| XlsxError::Xml("Corrupt XML structure".to_string()))?;
-    let comment_list = ws
+fn load_outline_properties(ws: Node) -> OutlineProperties {
+    // <sheetPr>
+    //     <outlinePr summaryBelow="0" summaryRight="0"/>
+    // </sheetPr>
+    let outline_pr = ws
         .children()
-        .filter(|n| n.has_tag_name("commentList"))
-        .collect::<Vec<Node>>();
-    if comment_list.len() == 1 {
-        for comment in comment_list[0].children() {
-            let text = comment
-                .descendants()
-                .filter(|n| n.has_tag_name("t"))
-                .map(|n| n.text().unwrap().to_string())
-                .collect::<Vec<String>>()
-                .join("");
-            let cell_ref = get_attribute(&comment, "ref")?.to_string();
-            // TODO: Read author_name from the list of authors
-            let author_name = "".to_string();
-            comments.push(Comment {
-                text,
-                author_name,
-                author_id: None,
-                cell_ref,
-            });
-        }
+        .find(|n| n.has_tag_name("sheetPr"))
+        .and_then(|sheet_pr| sheet_pr.children().find(|n| n.has_tag_name("outlinePr")));
+    match outline_pr {
+        Some(node) => OutlineProperties {
+            summary_below: get_bool(node, "summaryBelow"),
+            summary_right: get_bool(node, "summaryRight"),
+        },
+        None => OutlineProperties::default(),
     }
+}
 
-    Ok(comments)
+// The outlineLevel attribute of a row or a column, Excel allows up to seven levels
+fn load_outline_level(node: Node) -> u8 {
+    node.attribute("outlineLevel")
+        .and_then(|s| s.parse::<u8>().ok())
+        .unwrap_or(0)
+        .min(MAX_OUTLINE_LEVEL)
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -335,8 +338,8 @@ fn get_cell_from_excel(
     formula_index: i32,
     sheet_name: &str,
     cell_ref: &str,
-    shared_strings: &mut Vec<String>,
-    rich_text_inline: Option<String>,
+    shared_strings: &mut SharedStrings,
+    rich_text_inline: Option<Vec<RichTextRun>>,
     anchor_cell: Option<(i32, i32)>,
     array_kind: CellArrayKind,
 ) -> Cell {
@@ -414,12 +417,7 @@ fn get_cell_from_excel(
             },
             "str" => {
                 let s = decode_xlsx_escapes(cell_value.unwrap_or(""));
-                let si = if let Some(i) = shared_strings.iter().position(|r| r == &s) {
-                    i
-                } else {
-                    shared_strings.push(s.clone());
-                    shared_strings.len() - 1
-                } as i32;
+                let si = shared_strings.get_or_insert(&s);
 
                 if let Some(anchor) = anchor_cell {
                     Cell::SpillCell {
@@ -440,13 +438,7 @@ fn get_cell_from_excel(
                 }
             }
             "inlineStr" => {
-                let s = rich_text_inline.unwrap_or_default();
-                let si = if let Some(i) = shared_strings.iter().position(|r| r == &s) {
-                    i
-                } else {
-                    shared_strings.push(s.to_string());
-                    shared_strings.len() - 1
-                } as i32;
+                let si = shared_strings.get_or_insert_runs(rich_text_inline.unwrap_or_default());
 
                 Cell::SharedString { si, s: cell_style }
             }
@@ -530,9 +522,9 @@ fn get_cell_from_excel(
             }
             "inlineStr" => {
                 // NB: This is untested, I don't know of any engine that uses inline strings in formulas
-                make_cell(FormulaValue::Text(
-                    rich_text_inline.unwrap_or("".to_string()),
-                ))
+                make_cell(FormulaValue::Text(get_rich_text_plain_text(
+                    &rich_text_inline.unwrap_or_default(),
+                )))
             }
             _ => {
                 // error
@@ -552,6 +544,7 @@ fn load_sheet_rels<R: Read + std::io::Seek>(
     path: &str,
     tables: &mut HashMap<String, Table>,
     sheet_name: &str,
+    dxfs: &[Dxf],
 ) -> Result<(Vec<Comment>, HashMap<String, String>), XlsxError> {
     // ...xl/worksheets/sheet6.xml -> xl/worksheets/_rels/sheet6.xml.rels
     let mut comments = Vec::new();
@@ -577,6 +570,7 @@ fn load_sheet_rels<R: Read + std::io::Seek>(
         .children()
         .filter(|n| n.has_tag_name("Relationship"))
         .collect::<Vec<Node>>();
+    let mut threaded_comments = Vec::new();
     for rel in rels {
         let t = get_attribute(&rel, "Type")?.to_string();
         if t.ends_with("comments") {
@@ -584,6 +578,12 @@ fn load_sheet_rels<R: Read + std::io::Seek>(
             // Target="../comments1.xlsx"
             target.replace_range(..2, v[0]);
             comments = load_comments(archive, &target)?;
+        } else if t.ends_with("threadedComment") {
+            let mut target = get_attribute(&rel, "Target")?.to_string();
+            // Target="../threadedComments/threadedComment1.xml"
+            target.replace_range(..2, v[0]);
+            let persons = load_persons(archive, &format!("{}/persons/person.xml", v[0]))?;
+            threaded_comments = load_threaded_comments(archive, &target, &persons)?;
         } else if t.ends_with("hyperlink") {
             let id = get_attribute(&rel, "Id")?.to_string();
             let target = get_attribute(&rel, "Target")?.to_string();
@@ -599,10 +599,20 @@ fn load_sheet_rels<R: Read + std::io::Seek>(
                 target
             };
 
-            let table = load_table(archive, &path, sheet_name)?;
+            let table = load_table(archive, &path, sheet_name, dxfs)?;
             tables.insert(table.name.clone(), table);
         }
     }
+    // Excel also writes each threaded comment as a plain comment for older readers
+    for threaded_comment in threaded_comments {
+        match comments
+            .iter_mut()
+            .find(|comment| comment.cell_ref == threaded_comment.cell_ref)
+        {
+            Some(comment) => *comment = threaded_comment,
+            None => comments.push(threaded_comment),
+        }
+    }
     Ok((comments, hyperlinks))
 }
 
@@ -831,7 +841,7 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
     settings: SheetSettings,
     worksheets: &[String],
     tables: &HashMap<String, Table>,
-    shared_strings: &mut Vec<String>,
+    shared_strings: &mut SharedStrings,
     defined_names: Vec<DefinedNameS>,
     theme: &Theme,
     dxfs: &mut Vec<Dxf>,
@@ -856,6 +866,8 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
 
     let cols = load_columns(ws)?;
     let color = load_sheet_color(ws, theme)?;
+    let outline_properties = load_outline_properties(ws);
+    let page_setup = load_page_setup(ws);
 
     // sheetData
     // <row r="1" spans="1:15" x14ac:dyDescent="0.35">
@@ -914,9 +926,18 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
         };
         let custom_format = get_bool_false(row, "customFormat");
         let hidden = get_bool_false(row, "hidden");
+        let outline_level = load_outline_level(row);
+        let collapsed = get_bool_false(row, "collapsed");
 
         if let Some(row_index) = row_index {
-            if custom_height || custom_format || row_style != 0 || has_height_attribute || hidden {
+            if custom_height
+                || custom_format
+                || row_style != 0
+                || has_height_attribute
+                || hidden
+                || outline_level > 0
+                || collapsed
+            {
                 rows.push(Row {
                     r: row_index,
                     height,
@@ -924,12 +945,14 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
                     custom_height,
                     custom_format,
                     hidden,
+                    outline_level,
+                    collapsed,
                 });
             }
         }
 
         // Unused attributes:
-        // * thickBot, thickTop, ph, collapsed, outlineLevel
+        // * thickBot, thickTop, ph
 
         let mut data_row = HashMap::new();
 
@@ -968,19 +991,9 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
             //     <t>Hello, World!</t>
             //   </is>
             // </c>
-            let cell_rich_text_nodes: Vec<Node> =
-                cell.children().filter(|n| n.has_tag_name("is")).collect();
-            let cell_rich_text = if cell_rich_text_nodes.is_empty() {
-                None
-            } else {
-                let texts: Vec<String> = cell_rich_text_nodes[0]
-                    .descendants()
-                    .filter(|n| n.has_tag_name("t"))
-                    .filter_map(|n| n.text())
-                    .map(|s| s.to_string())
-                    .collect();
-
-                Some(texts.join(""))
+            let cell_rich_text = match cell.children().find(|n| n.has_tag_name("is")) {
+                Some(node) => Some(load_rich_text(node, theme)?),
+                None => None,
             };
 
             let cell_metadata = cell.attribute("cm");
@@ -1246,8 +1259,13 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
     let links = load_hyperlinks(ws, &settings.hyperlink_rels)?;
 
     let conditional_formatting = load_conditional_formatting(ws, theme, dxfs)?;
-    // pageSetup
-    // <pageSetup orientation="portrait" r:id="rId1"/>
+
+    let data_validations = load_data_validations(ws)?;
+
+    let protection = load_sheet_protection(ws);
+
+    let sort_state = load_sort_state(ws, dxfs);
+    let auto_filter = load_auto_filter(ws, dxfs);
 
     let mut views = HashMap::new();
     views.insert(
@@ -1279,7 +1297,13 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
             show_grid_lines: sheet_view.show_grid_lines,
             views,
             conditional_formatting,
+            data_validations,
             links,
+            protection,
+            sort_state,
+            auto_filter,
+            outline_properties,
+            page_setup,
         },
         sheet_view.is_selected,
     ))
@@ -1290,7 +1314,7 @@ pub(super) fn load_sheets<R: Read + std::io::Seek>(
     rels: &HashMap<String, Relationship>,
     workbook: &WorkbookXML,
     tables: &mut HashMap<String, Table>,
-    shared_strings: &mut Vec<String>,
+    shared_strings: &mut SharedStrings,
     theme: &Theme,
     dxfs: &mut Vec<Dxf>,
 ) -> Result<(Vec<Worksheet>, u32), XlsxError> {
@@ -1307,7 +1331,7 @@ pub(super) fn load_sheets<R: Read + std::io::Seek>(
             };
             sheet_rels.insert(
                 &sheet.id,
-                load_sheet_rels(archive, &path, tables, &sheet.name)?,
+                load_sheet_rels(archive, &path, tables, &sheet.name, dxfs)?,
             );
         }
     }